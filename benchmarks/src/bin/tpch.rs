@@ -423,6 +423,8 @@ fn get_table(
         target_partitions,
         collect_stat: true,
         table_partition_cols: vec![],
+        file_sort_order: None,
+        bucketing: None,
     };
 
     let config = ListingTableConfig::new(Arc::new(LocalFileSystem {}), path)
@@ -90,7 +90,7 @@ impl Command {
             Self::Quit => Err(DataFusionError::Execution(
                 "Unexpected quit, this should be handled outside".into(),
             )),
-            Self::ListFunctions => display_all_functions(),
+            Self::ListFunctions => display_all_functions(ctx),
             Self::SearchFunctions(function) => {
                 if let Ok(func) = function.parse::<Function>() {
                     let details = func.function_details()?;
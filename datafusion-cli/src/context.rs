@@ -18,6 +18,7 @@
 //! Context (remote or local)
 
 use datafusion::dataframe::DataFrame;
+use datafusion::datafusion_data_access::object_store::ObjectStore;
 use datafusion::error::Result;
 use datafusion::execution::context::{SessionConfig, SessionContext};
 use std::sync::Arc;
@@ -40,4 +41,44 @@ impl Context {
             Context::Local(datafusion) => datafusion.sql(sql).await,
         }
     }
+
+    /// register an object store for the given URL scheme, e.g. `s3`
+    pub fn register_object_store(&self, scheme: &str, store: Arc<dyn ObjectStore>) {
+        match self {
+            Context::Local(datafusion) => {
+                datafusion
+                    .runtime_env()
+                    .register_object_store(scheme, store);
+            }
+        }
+    }
+
+    /// names of the user defined scalar, aggregate and window functions
+    /// registered in the context
+    pub fn user_defined_functions(&self) -> Vec<String> {
+        match self {
+            Context::Local(datafusion) => {
+                let state = datafusion.state.read();
+                let mut names: Vec<String> = state
+                    .scalar_functions
+                    .keys()
+                    .chain(state.aggregate_functions.keys())
+                    .chain(state.window_functions.keys())
+                    .cloned()
+                    .collect();
+                names.sort();
+                names
+            }
+        }
+    }
+
+    /// the time zone of the session, in which timestamps with a time zone are
+    /// printed
+    pub fn time_zone(&self) -> String {
+        match self {
+            Context::Local(datafusion) => {
+                datafusion.state.read().config.time_zone.clone()
+            }
+        }
+    }
 }
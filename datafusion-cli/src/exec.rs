@@ -23,6 +23,7 @@ use crate::{
     helper::CliHelper,
     print_options::PrintOptions,
 };
+use datafusion::dataframe::format_time_zones;
 use datafusion::error::Result;
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
@@ -163,7 +164,7 @@ async fn exec_and_print(
 ) -> Result<()> {
     let now = Instant::now();
     let df = ctx.sql(&sql).await?;
-    let results = df.collect().await?;
+    let results = format_time_zones(&df.collect().await?, &ctx.time_zone())?;
     print_options.print_batches(&results, now)?;
 
     Ok(())
@@ -16,6 +16,7 @@
 // under the License.
 
 //! Functions that are query-able and searchable via the `\h` command
+use crate::context::Context;
 use arrow::array::StringArray;
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
@@ -183,12 +184,13 @@ impl fmt::Display for Function {
     }
 }
 
-pub fn display_all_functions() -> Result<()> {
+pub fn display_all_functions(ctx: &Context) -> Result<()> {
     println!("Available help:");
     let array = StringArray::from(
         ALL_FUNCTIONS
             .iter()
             .map(|f| format!("{}", f))
+            .chain(ctx.user_defined_functions())
             .collect::<Vec<String>>(),
     );
     let schema = Schema::new(vec![Field::new("Function", DataType::Utf8, false)]);
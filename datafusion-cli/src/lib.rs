@@ -23,5 +23,6 @@ pub mod context;
 pub mod exec;
 pub mod functions;
 pub mod helper;
+pub mod object_storage;
 pub mod print_format;
 pub mod print_options;
@@ -19,12 +19,16 @@ use clap::Parser;
 use datafusion::error::Result;
 use datafusion::execution::context::SessionConfig;
 use datafusion_cli::{
-    context::Context, exec, print_format::PrintFormat, print_options::PrintOptions,
+    context::Context,
+    exec,
+    object_storage::{load_config, register_object_stores, OBJECT_STORE_CONFIG_FILE},
+    print_format::PrintFormat,
+    print_options::PrintOptions,
     DATAFUSION_CLI_VERSION,
 };
 use mimalloc::MiMalloc;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
@@ -67,6 +71,13 @@ struct Args {
     )]
     rc: Option<Vec<String>>,
 
+    #[clap(
+        long,
+        help = "Object store configuration file, default to ~/.datafusion_object_stores",
+        validator(is_valid_file)
+    )]
+    object_store_config: Option<String>,
+
     #[clap(long, arg_enum, default_value_t = PrintFormat::Table)]
     format: PrintFormat,
 
@@ -100,6 +111,13 @@ pub async fn main() -> Result<()> {
 
     let mut ctx: Context = Context::new_local(&session_config);
 
+    let object_store_config = match args.object_store_config {
+        Some(file) => Some(PathBuf::from(file)),
+        None => dirs::home_dir().map(|p| p.join(OBJECT_STORE_CONFIG_FILE)),
+    };
+    let object_store_configs = load_config(object_store_config.as_deref())?;
+    register_object_stores(&ctx, &object_store_configs)?;
+
     let mut print_options = PrintOptions {
         format: args.format,
         quiet: args.quiet,
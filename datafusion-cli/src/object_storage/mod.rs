@@ -0,0 +1,333 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store configuration, used to register object stores for
+//! URL schemes other than the local filesystem at startup
+//!
+//! The configuration file contains a section per URL scheme, e.g.
+//!
+//! ```text
+//! [s3]
+//! endpoint = http://localhost:9000
+//! region = us-east-1
+//! access_key_id = minio
+//! secret_access_key = minio123
+//! ```
+//!
+//! The following keys are supported in each section:
+//!
+//! * `type`: the kind of store, defaults to the scheme. Only `s3` is supported
+//! * `endpoint`: the URL of the service, defaults to AWS S3 in `region`
+//! * `region`: the region of the service, defaults to `us-east-1`
+//! * `access_key_id`, `secret_access_key` and `session_token`: the credentials
+//!   used to sign requests. Requests are not signed if these are not set
+//!
+//! The standard `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, `AWS_SESSION_TOKEN`,
+//! `AWS_REGION` (or `AWS_DEFAULT_REGION`) and `AWS_ENDPOINT_URL` environment variables
+//! override the settings of the `s3` scheme, which is registered if any of them is set.
+
+pub mod s3;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use datafusion::datafusion_data_access::object_store::ObjectStore;
+use datafusion::error::{DataFusionError, Result};
+
+use crate::context::Context;
+use crate::object_storage::s3::S3FileSystem;
+
+/// The name of the object store configuration file in the home directory
+pub const OBJECT_STORE_CONFIG_FILE: &str = ".datafusion_object_stores";
+
+/// The region used when none is configured
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+/// Configuration of the object store registered for a URL scheme
+#[derive(Clone, Default, PartialEq)]
+pub struct ObjectStoreConfig {
+    /// The URL scheme the store is registered for, e.g. `s3`
+    pub scheme: String,
+    /// The kind of store, defaults to `scheme`
+    pub store_type: Option<String>,
+    /// The URL of the service
+    pub endpoint: Option<String>,
+    /// The region of the service
+    pub region: Option<String>,
+    /// The access key id used to sign requests
+    pub access_key_id: Option<String>,
+    /// The secret access key used to sign requests
+    pub secret_access_key: Option<String>,
+    /// The session token of temporary credentials
+    pub session_token: Option<String>,
+}
+
+impl std::fmt::Debug for ObjectStoreConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the secrets
+        f.debug_struct("ObjectStoreConfig")
+            .field("scheme", &self.scheme)
+            .field("store_type", &self.store_type)
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("access_key_id", &self.access_key_id)
+            .finish()
+    }
+}
+
+impl ObjectStoreConfig {
+    /// Create an empty configuration for `scheme`
+    pub fn new(scheme: impl Into<String>) -> Self {
+        Self {
+            scheme: scheme.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The kind of store to register
+    pub fn store_type(&self) -> &str {
+        self.store_type.as_deref().unwrap_or(&self.scheme)
+    }
+
+    /// The region of the service
+    pub fn region(&self) -> &str {
+        self.region.as_deref().unwrap_or(DEFAULT_REGION)
+    }
+
+    /// Set the configuration value `key`
+    fn set(&mut self, key: &str, value: String) -> Result<()> {
+        let field = match key {
+            "type" => &mut self.store_type,
+            "endpoint" => &mut self.endpoint,
+            "region" => &mut self.region,
+            "access_key_id" => &mut self.access_key_id,
+            "secret_access_key" => &mut self.secret_access_key,
+            "session_token" => &mut self.session_token,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "Unknown object store configuration key '{}' for scheme '{}'",
+                    key, self.scheme
+                )))
+            }
+        };
+        *field = Some(value);
+        Ok(())
+    }
+
+    /// Create the object store described by this configuration
+    pub fn build(&self) -> Result<Arc<dyn ObjectStore>> {
+        match self.store_type() {
+            "s3" => Ok(Arc::new(S3FileSystem::try_new(self)?)),
+            other => Err(DataFusionError::NotImplemented(format!(
+                "Unsupported object store type '{}' for scheme '{}'",
+                other, self.scheme
+            ))),
+        }
+    }
+}
+
+/// Parse the sections of an object store configuration file
+pub fn parse_config(contents: &str) -> Result<Vec<ObjectStoreConfig>> {
+    let mut configs: Vec<ObjectStoreConfig> = vec![];
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(scheme) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let scheme = scheme.trim().to_lowercase();
+            if configs.iter().any(|c| c.scheme == scheme) {
+                return Err(DataFusionError::Plan(format!(
+                    "Duplicate object store configuration for scheme '{}'",
+                    scheme
+                )));
+            }
+            configs.push(ObjectStoreConfig::new(scheme));
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "Invalid object store configuration on line {}: '{}'",
+                i + 1,
+                line
+            ))
+        })?;
+        let config = configs.last_mut().ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "Object store configuration on line {} is not in a [scheme] section",
+                i + 1
+            ))
+        })?;
+        config.set(key.trim(), value.trim().to_string())?;
+    }
+
+    Ok(configs)
+}
+
+/// Override the configuration of the `s3` scheme with the standard AWS
+/// environment variables returned by `var`
+pub fn apply_env(
+    configs: &mut Vec<ObjectStoreConfig>,
+    var: impl Fn(&str) -> Option<String>,
+) {
+    let vars = [
+        ("AWS_ACCESS_KEY_ID", "access_key_id"),
+        ("AWS_SECRET_ACCESS_KEY", "secret_access_key"),
+        ("AWS_SESSION_TOKEN", "session_token"),
+        ("AWS_DEFAULT_REGION", "region"),
+        ("AWS_REGION", "region"),
+        ("AWS_ENDPOINT_URL", "endpoint"),
+    ];
+    let values = vars
+        .iter()
+        .filter_map(|(name, key)| var(name).map(|value| (*key, value)))
+        .collect::<HashMap<_, _>>();
+    if values.is_empty() {
+        return;
+    }
+
+    let index = match configs.iter().position(|c| c.scheme == "s3") {
+        Some(index) => index,
+        None => {
+            configs.push(ObjectStoreConfig::new("s3"));
+            configs.len() - 1
+        }
+    };
+    for (key, value) in values {
+        // all keys are known
+        configs[index].set(key, value).unwrap();
+    }
+}
+
+/// Load the object store configuration from the file at `path`, if it exists,
+/// and the environment
+pub fn load_config(path: Option<&Path>) -> Result<Vec<ObjectStoreConfig>> {
+    let mut configs = match path {
+        Some(path) if path.exists() => parse_config(&std::fs::read_to_string(path)?)?,
+        _ => vec![],
+    };
+    apply_env(&mut configs, |name| std::env::var(name).ok());
+    Ok(configs)
+}
+
+/// Create and register the object stores described by `configs` with `ctx`
+pub fn register_object_stores(
+    ctx: &Context,
+    configs: &[ObjectStoreConfig],
+) -> Result<()> {
+    for config in configs {
+        ctx.register_object_store(&config.scheme, config.build()?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sections() -> Result<()> {
+        let configs = parse_config(
+            "# comment\n\
+             [s3]\n\
+             endpoint = http://localhost:9000\n\
+             access_key_id = key\n\
+             secret_access_key = secret\n\
+             \n\
+             [MINIO]\n\
+             type = s3\n\
+             region = eu-west-1\n",
+        )?;
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].scheme, "s3");
+        assert_eq!(
+            configs[0].endpoint.as_deref(),
+            Some("http://localhost:9000")
+        );
+        assert_eq!(configs[0].access_key_id.as_deref(), Some("key"));
+        assert_eq!(configs[0].secret_access_key.as_deref(), Some("secret"));
+        assert_eq!(configs[0].region(), DEFAULT_REGION);
+        assert_eq!(configs[1].scheme, "minio");
+        assert_eq!(configs[1].store_type(), "s3");
+        assert_eq!(configs[1].region(), "eu-west-1");
+
+        // secrets are not printed
+        assert!(!format!("{:?}", configs[0]).contains("secret\""));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = parse_config("endpoint = foo").unwrap_err();
+        assert!(err.to_string().contains("not in a [scheme] section"));
+
+        let err = parse_config("[s3]\nfoo = bar").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown object store configuration key"));
+
+        let err = parse_config("[s3]\nendpoint").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid object store configuration"));
+
+        let err = parse_config("[s3]\n[s3]").unwrap_err();
+        assert!(err.to_string().contains("Duplicate"));
+    }
+
+    #[test]
+    fn env_overrides_config() -> Result<()> {
+        let mut configs = parse_config("[s3]\nregion = eu-west-1\nendpoint = foo")?;
+        apply_env(&mut configs, |name| match name {
+            "AWS_REGION" => Some("us-west-2".to_string()),
+            "AWS_ACCESS_KEY_ID" => Some("key".to_string()),
+            _ => None,
+        });
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].region(), "us-west-2");
+        assert_eq!(configs[0].endpoint.as_deref(), Some("foo"));
+        assert_eq!(configs[0].access_key_id.as_deref(), Some("key"));
+
+        // the s3 scheme is registered when only configured by environment
+        let mut configs = vec![];
+        apply_env(&mut configs, |name| match name {
+            "AWS_ENDPOINT_URL" => Some("http://localhost:9000".to_string()),
+            _ => None,
+        });
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].scheme, "s3");
+
+        // nothing is registered without configuration
+        let mut configs = vec![];
+        apply_env(&mut configs, |_| None);
+        assert!(configs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_type() -> Result<()> {
+        let configs = parse_config("[hdfs]")?;
+        let err = configs[0].build().unwrap_err();
+        assert!(err.to_string().contains("Unsupported object store type"));
+        Ok(())
+    }
+}
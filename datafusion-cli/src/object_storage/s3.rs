@@ -0,0 +1,373 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store for Amazon S3 and services with an S3 compatible API
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::{self, Cursor, ErrorKind, Read};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use datafusion::datafusion_data_access::object_store::{
+    FileMetaStream, ListEntryStream, ObjectReader, ObjectStore,
+};
+use datafusion::datafusion_data_access::{FileMeta, ListEntry, SizedFile};
+use datafusion::error::{DataFusionError, Result};
+use futures::{stream, AsyncRead, TryStreamExt};
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::{ListResult, ObjectMeta, ObjectStore as _};
+use tokio::runtime::Runtime;
+use url::Url;
+
+use super::ObjectStoreConfig;
+
+/// The client making the requests of a store and of its readers.
+///
+/// The connections of an HTTP client are bound to the runtime they are made on,
+/// so all requests are made on a runtime owned by the client, whichever runtime,
+/// if any, the store and its readers are called from.
+struct S3Client {
+    endpoint: Option<String>,
+    region: String,
+    /// The settings of the stores of all buckets
+    builder: AmazonS3Builder,
+    /// The store of each bucket, built when the bucket is first accessed
+    buckets: Mutex<HashMap<String, Arc<AmazonS3>>>,
+    /// Only `None` once dropped
+    runtime: Option<Runtime>,
+}
+
+impl S3Client {
+    /// Returns the store of `bucket`
+    fn bucket(&self, bucket: &str) -> io::Result<Arc<AmazonS3>> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if let Some(store) = buckets.get(bucket) {
+            return Ok(Arc::clone(store));
+        }
+        let store = self
+            .builder
+            .clone()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(to_io_error)?;
+        let store = Arc::new(store);
+        buckets.insert(bucket.to_string(), Arc::clone(&store));
+        Ok(store)
+    }
+
+    /// Runs `request` on the runtime of the client
+    async fn run<T, F>(&self, request: F) -> io::Result<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = object_store::Result<T>> + Send + 'static,
+    {
+        let runtime = self
+            .runtime
+            .as_ref()
+            .expect("runtime is only taken on drop");
+        runtime
+            .spawn(request)
+            .await
+            .map_err(|e| io::Error::new(ErrorKind::Other, e))?
+            .map_err(to_io_error)
+    }
+}
+
+impl Drop for S3Client {
+    fn drop(&mut self) {
+        // a runtime can not be shut down by blocking within an async context
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Object store for S3 compatible services. Paths are of the form `bucket/key`,
+/// and objects are addressed with path-style URLs, `{endpoint}/{bucket}/{key}`,
+/// which are supported by AWS S3 as well as most S3 compatible services
+pub struct S3FileSystem {
+    client: Arc<S3Client>,
+}
+
+impl std::fmt::Debug for S3FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3FileSystem")
+            .field("endpoint", &self.client.endpoint)
+            .field("region", &self.client.region)
+            .finish()
+    }
+}
+
+impl S3FileSystem {
+    /// Create a new store from `config`
+    pub fn try_new(config: &ObjectStoreConfig) -> Result<Self> {
+        let mut builder = AmazonS3Builder::new()
+            .with_region(config.region())
+            .with_virtual_hosted_style_request(false);
+
+        if let Some(endpoint) = &config.endpoint {
+            let url = Url::parse(endpoint.trim_end_matches('/')).map_err(|e| {
+                DataFusionError::Plan(format!(
+                    "Invalid endpoint '{}' for scheme '{}': {}",
+                    endpoint, config.scheme, e
+                ))
+            })?;
+            builder = builder
+                .with_allow_http(url.scheme() == "http")
+                .with_endpoint(endpoint.trim_end_matches('/'));
+        }
+
+        builder = match (&config.access_key_id, &config.secret_access_key) {
+            (Some(access_key_id), Some(secret_access_key)) => {
+                let builder = builder
+                    .with_access_key_id(access_key_id)
+                    .with_secret_access_key(secret_access_key);
+                match &config.session_token {
+                    Some(token) => builder.with_token(token),
+                    None => builder,
+                }
+            }
+            // requests are not signed without credentials
+            (None, None) => builder.with_skip_signature(true),
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                "Both access_key_id and secret_access_key must be set for scheme '{}'",
+                config.scheme
+            )))
+            }
+        };
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name(format!("{}-object-store", config.scheme))
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            client: Arc::new(S3Client {
+                endpoint: config.endpoint.clone(),
+                region: config.region().to_string(),
+                builder,
+                buckets: Mutex::new(HashMap::new()),
+                runtime: Some(runtime),
+            }),
+        })
+    }
+
+    /// Lists the objects under the `prefix` directory, and the common prefixes
+    /// up to the next `/` if `delimited`
+    async fn list(&self, prefix: &str, delimited: bool) -> io::Result<Vec<ListEntry>> {
+        let (bucket, key_prefix) = split_path(prefix)?;
+        let store = self.client.bucket(bucket)?;
+        let prefix = parse_key(key_prefix)?;
+
+        let result = self
+            .client
+            .run(async move {
+                if delimited {
+                    store.list_with_delimiter(Some(&prefix)).await
+                } else {
+                    let objects = store.list(Some(&prefix)).try_collect().await?;
+                    Ok(ListResult {
+                        common_prefixes: vec![],
+                        objects,
+                    })
+                }
+            })
+            .await?;
+
+        let prefixes = result
+            .common_prefixes
+            .into_iter()
+            .map(|p| ListEntry::Prefix(format!("{}/{}", bucket, p)));
+        let files = result
+            .objects
+            .into_iter()
+            .map(|meta| ListEntry::FileMeta(file_meta(bucket, meta)));
+        Ok(files.chain(prefixes).collect())
+    }
+
+    /// Returns the metadata of the object at `path`, if there is one
+    async fn head(&self, path: &str) -> io::Result<Option<FileMeta>> {
+        let (bucket, key) = split_path(path)?;
+        if key.is_empty() {
+            return Ok(None);
+        }
+        let store = self.client.bucket(bucket)?;
+        let location = parse_key(key)?;
+        match self
+            .client
+            .run(async move { store.head(&location).await })
+            .await
+        {
+            Ok(meta) => Ok(Some(file_meta(bucket, meta))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3FileSystem {
+    async fn list_file(&self, prefix: &str) -> io::Result<FileMetaStream> {
+        let mut files = self
+            .list(prefix, false)
+            .await?
+            .into_iter()
+            .filter_map(|entry| match entry {
+                ListEntry::FileMeta(meta) => Some(meta),
+                ListEntry::Prefix(_) => None,
+            })
+            .collect::<Vec<_>>();
+        // the prefix may also be the path of a single object
+        if files.is_empty() {
+            files.extend(self.head(prefix).await?);
+        }
+        Ok(Box::pin(stream::iter(files.into_iter().map(Ok))))
+    }
+
+    async fn list_dir(
+        &self,
+        prefix: &str,
+        delimiter: Option<String>,
+    ) -> io::Result<ListEntryStream> {
+        let delimited = match delimiter.as_deref() {
+            None => false,
+            Some("/") => true,
+            Some(d) => {
+                return Err(io::Error::new(
+                    ErrorKind::Unsupported,
+                    format!("S3 paths can only be delimited by '/', not '{}'", d),
+                ))
+            }
+        };
+        let entries = self.list(prefix, delimited).await?;
+        Ok(Box::pin(stream::iter(entries.into_iter().map(Ok))))
+    }
+
+    fn file_reader(&self, file: SizedFile) -> io::Result<Arc<dyn ObjectReader>> {
+        Ok(Arc::new(S3FileReader {
+            client: Arc::clone(&self.client),
+            file,
+        }))
+    }
+}
+
+struct S3FileReader {
+    client: Arc<S3Client>,
+    file: SizedFile,
+}
+
+impl S3FileReader {
+    async fn get(&self, start: u64, length: usize) -> io::Result<Vec<u8>> {
+        if length == 0 {
+            return Ok(vec![]);
+        }
+        let (bucket, key) = split_path(&self.file.path)?;
+        let store = self.client.bucket(bucket)?;
+        let location = parse_key(key)?;
+        let range = start as usize..start as usize + length;
+        let bytes = self
+            .client
+            .run(async move { store.get_range(&location, range).await })
+            .await?;
+        Ok(bytes.to_vec())
+    }
+}
+
+#[async_trait]
+impl ObjectReader for S3FileReader {
+    async fn chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> io::Result<Box<dyn AsyncRead>> {
+        let bytes = self.get(start, length).await?;
+        Ok(Box::new(futures::io::Cursor::new(bytes)))
+    }
+
+    fn sync_chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> io::Result<Box<dyn Read + Send + Sync>> {
+        // The request is made on the runtime of the client, so it is only
+        // waited for here, which is possible from within an async context too
+        let bytes = futures::executor::block_on(self.get(start, length))?;
+        Ok(Box::new(Cursor::new(bytes)))
+    }
+
+    fn length(&self) -> u64 {
+        self.file.size
+    }
+}
+
+fn to_io_error(e: object_store::Error) -> io::Error {
+    match e {
+        object_store::Error::NotFound { .. } => io::Error::new(ErrorKind::NotFound, e),
+        _ => io::Error::new(ErrorKind::Other, e),
+    }
+}
+
+fn file_meta(bucket: &str, meta: ObjectMeta) -> FileMeta {
+    FileMeta {
+        sized_file: SizedFile {
+            path: format!("{}/{}", bucket, meta.location),
+            size: meta.size as u64,
+        },
+        last_modified: Some(meta.last_modified),
+    }
+}
+
+/// Splits `path` into its bucket and key
+fn split_path(path: &str) -> io::Result<(&str, &str)> {
+    let path = path.trim_start_matches('/');
+    let (bucket, key) = path.split_once('/').unwrap_or((path, ""));
+    if bucket.is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("No bucket in path '{}'", path),
+        ));
+    }
+    Ok((bucket, key))
+}
+
+/// Parses `key` as the location of an object, or of the objects under it
+fn parse_key(key: &str) -> io::Result<Path> {
+    Path::parse(key).map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_paths() {
+        assert_eq!(split_path("bucket/a/b.csv").unwrap(), ("bucket", "a/b.csv"));
+        assert_eq!(split_path("bucket").unwrap(), ("bucket", ""));
+        assert_eq!(split_path("/bucket/").unwrap(), ("bucket", ""));
+        assert!(split_path("").is_err());
+    }
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(parse_key("a&b/c d.csv").unwrap().as_ref(), "a&b/c d.csv");
+        assert_eq!(parse_key("data/").unwrap().as_ref(), "data");
+        assert_eq!(parse_key("").unwrap().as_ref(), "");
+        assert!(parse_key("a//b").is_err());
+    }
+}
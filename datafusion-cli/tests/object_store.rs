@@ -0,0 +1,271 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests of the object stores registered from configuration, run against
+//! a mock S3 server serving the files of a local directory
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Int32Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::assert_batches_sorted_eq;
+use datafusion::error::Result;
+use datafusion::execution::context::SessionConfig;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion_cli::context::Context;
+use datafusion_cli::object_storage::{parse_config, register_object_stores};
+
+/// The credentials the mock S3 server accepts
+const ACCESS_KEY_ID: &str = "test_key";
+const SECRET_ACCESS_KEY: &str = "test_secret";
+
+/// A mock S3 server, the `mock-s3` binary of this crate, serving the
+/// directories in a root directory as buckets from a child process that
+/// is killed when dropped
+struct MockS3 {
+    process: Child,
+    addr: SocketAddr,
+}
+
+impl MockS3 {
+    fn start(root: &Path) -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_mock-s3"))
+            .arg(root)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        // the server prints its address once it listens
+        let mut line = String::new();
+        BufReader::new(process.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line.trim().parse().unwrap();
+        Self { process, addr }
+    }
+}
+
+impl Drop for MockS3 {
+    fn drop(&mut self) {
+        self.process.kill().ok();
+        self.process.wait().ok();
+    }
+}
+
+/// Creates a bucket with CSV and parquet files in a temporary directory
+fn create_bucket() -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    let data = root.path().join("bucket").join("data");
+    fs::create_dir_all(data.join("csv")).unwrap();
+    fs::create_dir_all(data.join("parquet")).unwrap();
+
+    for i in 0..3 {
+        fs::write(
+            data.join("csv").join(format!("part-{}.csv", i)),
+            format!("a,b\n{},x{}\n{},y{}\n", i * 2, i, i * 2 + 1, i),
+        )
+        .unwrap();
+    }
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Utf8, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["x", "y", "z"])),
+        ],
+    )
+    .unwrap();
+    let file = fs::File::create(data.join("parquet").join("part-0.parquet")).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    root
+}
+
+fn s3_context(
+    addr: SocketAddr,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> Result<Context> {
+    let configs = parse_config(&format!(
+        "[s3]\n\
+         endpoint = http://{}\n\
+         access_key_id = {}\n\
+         secret_access_key = {}\n",
+        addr, access_key_id, secret_access_key
+    ))?;
+    let ctx = Context::new_local(&SessionConfig::new().with_target_partitions(2));
+    register_object_stores(&ctx, &configs)?;
+    Ok(ctx)
+}
+
+#[tokio::test]
+async fn create_external_table_on_s3() -> Result<()> {
+    let root = create_bucket();
+    let server = MockS3::start(root.path());
+    let mut ctx = s3_context(server.addr, ACCESS_KEY_ID, SECRET_ACCESS_KEY)?;
+
+    ctx.sql(
+        "CREATE EXTERNAL TABLE csv_table STORED AS CSV WITH HEADER ROW \
+         LOCATION 's3://bucket/data/csv/'",
+    )
+    .await?;
+    let batches = ctx
+        .sql("SELECT a, b FROM csv_table WHERE a > 1")
+        .await?
+        .collect()
+        .await?;
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 2 | x1 |",
+        "| 3 | y1 |",
+        "| 4 | x2 |",
+        "| 5 | y2 |",
+        "+---+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &batches);
+
+    ctx.sql(
+        "CREATE EXTERNAL TABLE parquet_table STORED AS PARQUET \
+         LOCATION 's3://bucket/data/parquet'",
+    )
+    .await?;
+    let batches = ctx
+        .sql("SELECT b, a FROM parquet_table")
+        .await?
+        .collect()
+        .await?;
+    let expected = vec![
+        "+---+---+",
+        "| b | a |",
+        "+---+---+",
+        "| x | 1 |",
+        "| y | 2 |",
+        "| z | 3 |",
+        "+---+---+",
+    ];
+    assert_batches_sorted_eq!(expected, &batches);
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_dir_on_s3() -> Result<()> {
+    let root = create_bucket();
+    let server = MockS3::start(root.path());
+    let configs = parse_config(&format!(
+        "[s3]\nendpoint = http://{}\naccess_key_id = {}\nsecret_access_key = {}",
+        server.addr, ACCESS_KEY_ID, SECRET_ACCESS_KEY
+    ))?;
+    let store = configs[0].build()?;
+
+    let mut entries = vec![];
+    let mut stream = store.list_dir("bucket/data", Some("/".to_string())).await?;
+    while let Some(entry) = futures::StreamExt::next(&mut stream).await {
+        entries.push(format!("{:?}", entry?));
+    }
+    assert_eq!(
+        entries,
+        vec![
+            "Prefix(\"bucket/data/csv\")".to_string(),
+            "Prefix(\"bucket/data/parquet\")".to_string(),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn keys_with_special_characters() -> Result<()> {
+    let root = create_bucket();
+    let dir = root.path().join("bucket").join("a&b c");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("x&y.csv"), "a,b\n1,x&y\n").unwrap();
+    let server = MockS3::start(root.path());
+    let mut ctx = s3_context(server.addr, ACCESS_KEY_ID, SECRET_ACCESS_KEY)?;
+
+    // the keys are listed in a directory, and read as a single file
+    for (table, location) in [("dir_table", "a&b c/"), ("file_table", "a&b c/x&y.csv")] {
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE {} STORED AS CSV WITH HEADER ROW \
+             LOCATION 's3://bucket/{}'",
+            table, location
+        ))
+        .await?;
+        let batches = ctx
+            .sql(&format!("SELECT a, b FROM {}", table))
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+---+-----+",
+            "| a | b   |",
+            "+---+-----+",
+            "| 1 | x&y |",
+            "+---+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_credentials() -> Result<()> {
+    let root = create_bucket();
+    let server = MockS3::start(root.path());
+    for (access_key_id, secret_access_key) in [
+        ("wrong_key", SECRET_ACCESS_KEY),
+        (ACCESS_KEY_ID, "wrong_secret"),
+    ] {
+        let mut ctx = s3_context(server.addr, access_key_id, secret_access_key)?;
+        let err = ctx
+            .sql(
+                "CREATE EXTERNAL TABLE csv_table STORED AS CSV WITH HEADER ROW \
+                 LOCATION 's3://bucket/data/csv/'",
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("403 Forbidden"), "{}", err);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn unregistered_scheme() -> Result<()> {
+    let mut ctx = Context::new_local(&SessionConfig::new());
+    let err = ctx
+        .sql("CREATE EXTERNAL TABLE t STORED AS CSV LOCATION 's3://bucket/data.csv'")
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("No suitable object store found for s3"),
+        "{}",
+        err
+    );
+    Ok(())
+}
@@ -18,10 +18,10 @@
 //! DataFrame API for building and executing query plans.
 
 use crate::arrow::record_batch::RecordBatch;
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
 use crate::logical_plan::{
-    col, DFSchema, Expr, FunctionRegistry, JoinType, LogicalPlan, LogicalPlanBuilder,
-    Partitioning,
+    col, Column, DFSchema, Expr, FunctionRegistry, JoinType, LogicalPlan,
+    LogicalPlanBuilder, Partitioning, UnnestOptions,
 };
 use parquet::file::properties::WriterProperties;
 use std::sync::Arc;
@@ -29,13 +29,18 @@ use std::sync::Arc;
 use crate::physical_plan::SendableRecordBatchStream;
 use async_trait::async_trait;
 
-use crate::arrow::datatypes::Schema;
 use crate::arrow::datatypes::SchemaRef;
+use crate::arrow::datatypes::{DataType, Field, Schema};
 use crate::arrow::util::pretty;
+use crate::datasource::listing::BucketSpec;
 use crate::datasource::TableProvider;
 use crate::execution::context::{SessionState, TaskContext};
 use crate::logical_expr::{utils::find_window_exprs, TableType};
-use crate::physical_plan::file_format::{plan_to_csv, plan_to_json, plan_to_parquet};
+use crate::physical_plan::bucket_hash::BUCKET_HASH_VERSION;
+use crate::physical_plan::expressions::{cast_array, DEFAULT_DATAFUSION_CAST_OPTIONS};
+use crate::physical_plan::file_format::{
+    plan_to_arrow, plan_to_csv, plan_to_json, plan_to_parquet,
+};
 use crate::physical_plan::{collect, collect_partitioned};
 use crate::physical_plan::{execute_stream, execute_stream_partitioned, ExecutionPlan};
 use crate::scalar::ScalarValue;
@@ -208,6 +213,52 @@ impl DataFrame {
         Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
     }
 
+    /// Expand the list column `column` of this DataFrame, producing a row for
+    /// each element of the list, in which the other columns are repeated.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.sql("SELECT 1 AS id, array(1, 2, 3) AS a").await?;
+    /// let df = df.unnest_column("a")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unnest_column(&self, column: &str) -> Result<Arc<DataFrame>> {
+        self.unnest_column_with_options(column, UnnestOptions::default())
+    }
+
+    /// Expand the list column `column` of this DataFrame with the given
+    /// [`UnnestOptions`], which may keep the rows of null lists and add a
+    /// column with the position of each element
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::logical_plan::UnnestOptions;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.sql("SELECT 1 AS id, array(1, 2, 3) AS a").await?;
+    /// let options = UnnestOptions::default().with_ordinality("position");
+    /// let df = df.unnest_column_with_options("a", options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unnest_column_with_options(
+        &self,
+        column: &str,
+        options: UnnestOptions,
+    ) -> Result<Arc<DataFrame>> {
+        let plan = LogicalPlanBuilder::from(self.plan.clone())
+            .unnest_column_with_options(column, options)?
+            .build()?;
+        Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
+    }
+
     /// Calculate the union of two [`DataFrame`]s, preserving duplicate rows.The
     /// two [`DataFrame`]s must have exactly the same schema
     ///
@@ -356,6 +407,41 @@ impl DataFrame {
         Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
     }
 
+    /// Repartition a DataFrame into the buckets of `bucketing`, so that writing
+    /// it produces the `part-{bucket}` files of a bucketed [`ListingTable`].
+    /// The rows are assigned to their bucket by [`create_bucket_hashes`], so
+    /// `bucketing` must use its current version.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::datasource::listing::BucketSpec;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new()).await?;
+    /// let df1 = df.bucket_by(&BucketSpec::new(vec!["a".to_string()], 4))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ListingTable`]: crate::datasource::listing::ListingTable
+    /// [`create_bucket_hashes`]: crate::physical_plan::bucket_hash::create_bucket_hashes
+    pub fn bucket_by(&self, bucketing: &BucketSpec) -> Result<Arc<DataFrame>> {
+        if bucketing.hash_version != BUCKET_HASH_VERSION {
+            return Err(DataFusionError::Plan(format!(
+                "Rows can only be bucketed with version {} of the bucket hash, not {}",
+                BUCKET_HASH_VERSION, bucketing.hash_version
+            )));
+        }
+        let bucket_exprs = bucketing
+            .columns
+            .iter()
+            .map(|name| Expr::Column(Column::from_name(name)))
+            .collect();
+        self.repartition(Partitioning::Hash(bucket_exprs, bucketing.num_buckets))
+    }
+
     /// Convert the logical plan represented by this DataFrame into a physical plan and
     /// execute it, collecting all resulting batches into memory
     /// Executes this DataFrame and collects all results into a vector of RecordBatch.
@@ -391,7 +477,10 @@ impl DataFrame {
     /// ```
     pub async fn show(&self) -> Result<()> {
         let results = self.collect().await?;
-        Ok(pretty::print_batches(&results)?)
+        let time_zone = self.session_state.read().config.time_zone.clone();
+        Ok(pretty::print_batches(&format_time_zones(
+            &results, &time_zone,
+        )?)?)
     }
 
     /// Print results and limit rows.
@@ -409,7 +498,10 @@ impl DataFrame {
     /// ```
     pub async fn show_limit(&self, num: usize) -> Result<()> {
         let results = self.limit(num)?.collect().await?;
-        Ok(pretty::print_batches(&results)?)
+        let time_zone = self.session_state.read().config.time_zone.clone();
+        Ok(pretty::print_batches(&format_time_zones(
+            &results, &time_zone,
+        )?)?)
     }
 
     /// Executes this DataFrame and returns a stream over a single partition
@@ -607,6 +699,13 @@ impl DataFrame {
         let state = self.session_state.read().clone();
         plan_to_json(&state, plan, path).await
     }
+
+    /// Executes a query and writes the results to a partitioned Arrow IPC file.
+    pub async fn write_arrow(&self, path: impl AsRef<str>) -> Result<()> {
+        let plan = self.create_physical_plan().await?;
+        let state = self.session_state.read().clone();
+        plan_to_arrow(&state, plan, path).await
+    }
 }
 
 #[async_trait]
@@ -666,16 +765,76 @@ impl TableProvider for DataFrame {
     }
 }
 
+/// Formats the timestamps with a time zone of `batches` as the local time of
+/// `time_zone`, usually the [`SessionConfig::time_zone`], which arrow would print in
+/// UTC
+///
+/// [`SessionConfig::time_zone`]: crate::execution::context::SessionConfig::time_zone
+pub fn format_time_zones(
+    batches: &[RecordBatch],
+    time_zone: &str,
+) -> Result<Vec<RecordBatch>> {
+    batches
+        .iter()
+        .map(|batch| {
+            let schema = batch.schema();
+            if !schema
+                .fields()
+                .iter()
+                .any(|f| matches!(f.data_type(), DataType::Timestamp(_, Some(_))))
+            {
+                return Ok(batch.clone());
+            }
+            let (fields, columns): (Vec<_>, Vec<_>) = schema
+                .fields()
+                .iter()
+                .zip(batch.columns())
+                .map(|(field, column)| match field.data_type() {
+                    DataType::Timestamp(unit, Some(_)) => {
+                        // the same instants, in the time zone of the session
+                        let zoned = cast_array(
+                            column,
+                            &DataType::Timestamp(
+                                unit.clone(),
+                                Some(time_zone.to_owned()),
+                            ),
+                            &DEFAULT_DATAFUSION_CAST_OPTIONS,
+                        )?;
+                        Ok((
+                            Field::new(field.name(), DataType::Utf8, field.is_nullable()),
+                            cast_array(
+                                &zoned,
+                                &DataType::Utf8,
+                                &DEFAULT_DATAFUSION_CAST_OPTIONS,
+                            )?,
+                        ))
+                    }
+                    _ => Ok((field.clone(), column.clone())),
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .unzip();
+            Ok(RecordBatch::try_new(
+                Arc::new(Schema::new(fields)),
+                columns,
+            )?)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
 
     use super::*;
+    use crate::datasource::MemTable;
+    use crate::execution::context::{SessionConfig, SessionContext};
     use crate::execution::options::CsvReadOptions;
     use crate::physical_plan::ColumnarValue;
-    use crate::{assert_batches_sorted_eq, execution::context::SessionContext};
+    use crate::{assert_batches_eq, assert_batches_sorted_eq};
     use crate::{logical_plan::*, test_util};
-    use arrow::datatypes::DataType;
+    use arrow::array::{Int64Array, ListArray, TimestampSecondArray};
+    use arrow::datatypes::{DataType, Field, Int32Type, TimeUnit};
     use datafusion_expr::Volatility;
     use datafusion_expr::{
         BuiltInWindowFunction, ScalarFunctionImplementation, WindowFunction,
@@ -804,6 +963,67 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn unnest_column() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new(
+                "a",
+                DataType::List(Box::new(Field::new("item", DataType::Int32, true))),
+                true,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+                    Some(vec![Some(10), Some(20)]),
+                    None,
+                ])),
+            ],
+        )?;
+        let table = MemTable::try_new(schema, vec![vec![batch]])?;
+        let ctx = SessionContext::new();
+        let df = ctx.read_table(Arc::new(table))?;
+
+        let results = df.unnest_column("a")?.collect().await?;
+        let expected = vec![
+            "+----+----+",
+            "| id | a  |",
+            "+----+----+",
+            "| 1  | 10 |",
+            "| 1  | 20 |",
+            "+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let options = UnnestOptions::default()
+            .with_preserve_nulls(true)
+            .with_ordinality("n");
+        let results = df
+            .unnest_column_with_options("a", options)?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+----+----+---+",
+            "| id | a  | n |",
+            "+----+----+---+",
+            "| 1  | 10 | 1 |",
+            "| 1  | 20 | 2 |",
+            "| 2  |    |   |",
+            "+----+----+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let err = df.unnest_column("id").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Unnest of column ?table?.id requires a list, got Int64"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn explain() -> Result<()> {
         // build query using Table API
@@ -954,6 +1174,52 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn format_time_zones_in_session_time_zone() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "zoned",
+                DataType::Timestamp(TimeUnit::Second, Some("America/New_York".into())),
+                true,
+            ),
+            Field::new("local", DataType::Timestamp(TimeUnit::Second, None), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampSecondArray::from_opt_vec(
+                    vec![Some(1656676800), None],
+                    Some("America/New_York".into()),
+                )),
+                Arc::new(TimestampSecondArray::from_opt_vec(
+                    vec![Some(1656676800), None],
+                    None,
+                )),
+            ],
+        )?;
+        let ctx = SessionContext::with_config(
+            SessionConfig::new().with_time_zone("Europe/Paris"),
+        );
+        let df = ctx.read_table(Arc::new(MemTable::try_new(
+            batch.schema(),
+            vec![vec![batch]],
+        )?))?;
+
+        let time_zone = ctx.state.read().config.time_zone.clone();
+        let results = format_time_zones(&df.collect().await?, &time_zone)?;
+        let expected = vec![
+            "+---------------------------+---------------------+",
+            "| zoned                     | local               |",
+            "+---------------------------+---------------------+",
+            "| 2022-07-01 14:00:00+02:00 | 2022-07-01 12:00:00 |",
+            "|                           |                     |",
+            "+---------------------------+---------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        Ok(())
+    }
+
     /// Compare the formatted string representation of two plans for equality
     fn assert_same_plan(plan1: &LogicalPlan, plan2: &LogicalPlan) {
         assert_eq!(format!("{:?}", plan1), format!("{:?}", plan2));
@@ -0,0 +1,82 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! The table scanned as the name of a recursive common table expression
+//! within its recursive term
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+
+use crate::datasource::{TableProvider, TableType};
+use crate::error::Result;
+use crate::logical_plan::Expr;
+use crate::physical_plan::work_table::WorkTableExec;
+use crate::physical_plan::{project_schema, ExecutionPlan};
+
+/// The work table of the recursive query `name`, holding the rows produced
+/// by the previous iteration of its recursive term. Scanning it produces a
+/// [`WorkTableExec`], which reads the rows of the enclosing
+/// [`RecursiveQueryExec`](crate::physical_plan::recursive_query::RecursiveQueryExec).
+pub struct CteWorkTable {
+    name: String,
+    schema: SchemaRef,
+}
+
+impl CteWorkTable {
+    /// Create the work table of the recursive query `name`
+    pub fn new(name: impl Into<String>, schema: SchemaRef) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+        }
+    }
+
+    /// The name of the recursive query
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[async_trait]
+impl TableProvider for CteWorkTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(WorkTableExec::new(
+            self.name.clone(),
+            project_schema(&self.schema, projection.as_ref())?,
+            projection.clone(),
+        )))
+    }
+}
@@ -26,7 +26,7 @@ pub use datafusion_expr::{TableProviderFilterPushDown, TableType};
 use crate::arrow::datatypes::SchemaRef;
 use crate::error::Result;
 use crate::logical_plan::Expr;
-use crate::physical_plan::ExecutionPlan;
+use crate::physical_plan::{ExecutionPlan, Statistics};
 
 /// Source table
 #[async_trait]
@@ -64,4 +64,10 @@ pub trait TableProvider: Sync + Send {
     ) -> Result<TableProviderFilterPushDown> {
         Ok(TableProviderFilterPushDown::Unsupported)
     }
+
+    /// Get statistics for this table, if available, without scanning it.
+    /// These are used by the logical optimizer to estimate the cost of plans
+    fn statistics(&self) -> Option<Statistics> {
+        None
+    }
 }
@@ -0,0 +1,118 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Apache Arrow IPC file format abstractions
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::datatypes::Schema;
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::reader::FileReader;
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use super::FileFormat;
+use super::FileScanConfig;
+use crate::error::Result;
+use crate::logical_plan::Expr;
+use crate::physical_plan::file_format::{ArrowExec, SeekableObjectReader};
+use crate::physical_plan::ExecutionPlan;
+use crate::physical_plan::Statistics;
+use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
+
+/// The default file extension of arrow files
+pub const DEFAULT_ARROW_EXTENSION: &str = ".arrow";
+/// Arrow IPC file `FileFormat` implementation.
+#[derive(Default, Debug)]
+pub struct ArrowFormat;
+
+#[async_trait]
+impl FileFormat for ArrowFormat {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn infer_schema(&self, mut readers: ObjectReaderStream) -> Result<SchemaRef> {
+        let mut schemas = vec![];
+        while let Some(obj_reader) = readers.next().await {
+            let reader =
+                FileReader::try_new(SeekableObjectReader::new(obj_reader?), None)?;
+            schemas.push(reader.schema().as_ref().clone());
+        }
+        let merged_schema = Schema::try_merge(schemas)?;
+        Ok(Arc::new(merged_schema))
+    }
+
+    async fn infer_stats(
+        &self,
+        _reader: Arc<dyn ObjectReader>,
+        _table_schema: SchemaRef,
+    ) -> Result<Statistics> {
+        Ok(Statistics::default())
+    }
+
+    async fn create_physical_plan(
+        &self,
+        conf: FileScanConfig,
+        _filters: &[Expr],
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let exec = ArrowExec::new(conf);
+        Ok(Arc::new(exec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datafusion_data_access::object_store::local::local_object_reader_stream;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field};
+    use arrow::ipc::writer::FileWriter;
+    use arrow::record_batch::RecordBatch;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn infer_schema() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut filenames = vec![];
+        for (i, name) in ["a", "b"].iter().enumerate() {
+            let schema =
+                Arc::new(Schema::new(vec![Field::new(name, DataType::Int32, false)]));
+            let path = tmp_dir.path().join(format!("{}.arrow", i));
+            let mut writer = FileWriter::try_new(std::fs::File::create(&path)?, &schema)?;
+            writer.write(&RecordBatch::try_new(
+                schema,
+                vec![Arc::new(Int32Array::from(vec![1, 2]))],
+            )?)?;
+            writer.finish()?;
+            filenames.push(path.to_str().unwrap().to_owned());
+        }
+
+        let schema = ArrowFormat
+            .infer_schema(local_object_reader_stream(filenames))
+            .await?;
+        let fields: Vec<String> = schema
+            .fields()
+            .iter()
+            .map(|f| format!("{}: {:?}", f.name(), f.data_type()))
+            .collect();
+        assert_eq!(vec!["a: Int32", "b: Int32"], fields);
+
+        Ok(())
+    }
+}
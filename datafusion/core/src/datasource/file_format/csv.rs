@@ -280,9 +280,12 @@ mod tests {
                     file_schema,
                     file_groups,
                     statistics,
+                    pruned_files: 0,
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: vec![],
+                    output_ordering: None,
+                    bucket_columns: None,
                 },
                 &[],
             )
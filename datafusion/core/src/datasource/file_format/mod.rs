@@ -20,6 +20,7 @@
 /// default max records to scan to infer the schema
 pub const DEFAULT_SCHEMA_INFER_MAX_RECORD: usize = 1000;
 
+pub mod arrow;
 pub mod avro;
 pub mod csv;
 pub mod json;
@@ -30,14 +31,21 @@ use std::fmt;
 use std::sync::Arc;
 
 use crate::arrow::datatypes::SchemaRef;
+use crate::datasource::object_store_registry::ObjectStoreUrl;
 use crate::error::Result;
+use crate::execution::cache_manager::{
+    FileMetadata, FileMetadataCache, FileMetadataKey, FileMetadataKind,
+};
 use crate::logical_plan::Expr;
 use crate::physical_plan::file_format::FileScanConfig;
 use crate::physical_plan::{ExecutionPlan, Statistics};
 
 use async_trait::async_trait;
 
-use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
+use datafusion_data_access::object_store::{
+    ObjectReader, ObjectReaderStream, ObjectStore,
+};
+use datafusion_data_access::FileMeta;
 
 /// This trait abstracts all the file format specific implementations
 /// from the `TableProvider`. This helps code re-utilization across
@@ -67,6 +75,62 @@ pub trait FileFormat: Send + Sync + fmt::Debug {
         table_schema: SchemaRef,
     ) -> Result<Statistics>;
 
+    /// Infer the common schema of `files` of the object store at
+    /// `object_store_url` like [`FileFormat::infer_schema`], using `cache` to
+    /// avoid reading again the metadata of files that did not change. The
+    /// default implementation does not use the cache, as the schema of formats
+    /// such as CSV is inferred across files.
+    async fn infer_schema_with_cache(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        _object_store_url: &ObjectStoreUrl,
+        files: Vec<FileMeta>,
+        _cache: Arc<dyn FileMetadataCache>,
+    ) -> Result<SchemaRef> {
+        let readers = files
+            .into_iter()
+            .map(move |file| object_store.file_reader(file.sized_file));
+        self.infer_schema(Box::pin(futures::stream::iter(readers)))
+            .await
+    }
+
+    /// Infer the statistics of `file` of the object store at
+    /// `object_store_url` like [`FileFormat::infer_stats`], looking them up in
+    /// `cache` first and caching them once inferred.
+    async fn infer_stats_with_cache(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        object_store_url: &ObjectStoreUrl,
+        file: &FileMeta,
+        table_schema: SchemaRef,
+        cache: Arc<dyn FileMetadataCache>,
+    ) -> Result<Statistics> {
+        let key = FileMetadataKey::try_new(
+            object_store_url,
+            file,
+            FileMetadataKind::Statistics,
+        );
+        if let Some(statistics) = key
+            .as_ref()
+            .and_then(|key| cached_statistics(cache.as_ref(), key, &table_schema))
+        {
+            return Ok(statistics);
+        }
+
+        let reader = object_store.file_reader(file.sized_file.clone())?;
+        let statistics = self.infer_stats(reader, table_schema.clone()).await?;
+        if let Some(key) = key {
+            cache.put(
+                key,
+                FileMetadata::Statistics {
+                    table_schema,
+                    statistics: statistics.clone(),
+                },
+            );
+        }
+        Ok(statistics)
+    }
+
     /// Take a list of files and convert it to the appropriate executor
     /// according to this file format.
     async fn create_physical_plan(
@@ -75,3 +139,19 @@ pub trait FileFormat: Send + Sync + fmt::Debug {
         filters: &[Expr],
     ) -> Result<Arc<dyn ExecutionPlan>>;
 }
+
+/// Get the statistics cached under `key`, if they were computed for
+/// `table_schema`
+pub(crate) fn cached_statistics(
+    cache: &dyn FileMetadataCache,
+    key: &FileMetadataKey,
+    table_schema: &SchemaRef,
+) -> Option<Statistics> {
+    match cache.get(key) {
+        Some(FileMetadata::Statistics {
+            table_schema: cached_schema,
+            statistics,
+        }) if cached_schema == *table_schema => Some(statistics),
+        _ => None,
+    }
+}
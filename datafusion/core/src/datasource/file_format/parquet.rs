@@ -26,31 +26,38 @@ use arrow::datatypes::SchemaRef;
 use async_trait::async_trait;
 use futures::TryStreamExt;
 use hashbrown::HashMap;
-use parquet::arrow::ArrowReader;
-use parquet::arrow::ParquetFileArrowReader;
+use parquet::arrow::parquet_to_arrow_schema;
 use parquet::errors::ParquetError;
 use parquet::errors::Result as ParquetResult;
+use parquet::file::footer::parse_metadata;
+use parquet::file::metadata::ParquetMetaData;
 use parquet::file::reader::ChunkReader;
 use parquet::file::reader::Length;
-use parquet::file::serialized_reader::SerializedFileReader;
 use parquet::file::statistics::Statistics as ParquetStatistics;
 
-use super::FileFormat;
 use super::FileScanConfig;
+use super::{cached_statistics, FileFormat};
 use crate::arrow::array::{
     BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array,
 };
 use crate::arrow::datatypes::{DataType, Field};
+use crate::datasource::object_store_registry::ObjectStoreUrl;
 use crate::datasource::{create_max_min_accs, get_col_stats};
 use crate::error::DataFusionError;
 use crate::error::Result;
+use crate::execution::cache_manager::{
+    FileMetadata, FileMetadataCache, FileMetadataKey, FileMetadataKind,
+};
 use crate::logical_plan::combine_filters;
 use crate::logical_plan::Expr;
 use crate::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
 use crate::physical_plan::file_format::{ParquetExec, SchemaAdapter};
 use crate::physical_plan::{metrics, ExecutionPlan};
 use crate::physical_plan::{Accumulator, Statistics};
-use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
+use datafusion_data_access::object_store::{
+    ObjectReader, ObjectReaderStream, ObjectStore,
+};
+use datafusion_data_access::FileMeta;
 
 /// The default file exetension of parquet files
 pub const DEFAULT_PARQUET_EXTENSION: &str = ".parquet";
@@ -109,6 +116,67 @@ impl FileFormat for ParquetFormat {
         Ok(stats)
     }
 
+    async fn infer_schema_with_cache(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        object_store_url: &ObjectStoreUrl,
+        files: Vec<FileMeta>,
+        cache: Arc<dyn FileMetadataCache>,
+    ) -> Result<SchemaRef> {
+        let schemas = files
+            .iter()
+            .map(|file| {
+                let schema = fetch_cached_schema(
+                    object_store.as_ref(),
+                    object_store_url,
+                    file,
+                    cache.as_ref(),
+                )?;
+                Ok(schema.as_ref().clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(Schema::try_merge(schemas)?))
+    }
+
+    async fn infer_stats_with_cache(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        object_store_url: &ObjectStoreUrl,
+        file: &FileMeta,
+        table_schema: SchemaRef,
+        cache: Arc<dyn FileMetadataCache>,
+    ) -> Result<Statistics> {
+        let key = FileMetadataKey::try_new(
+            object_store_url,
+            file,
+            FileMetadataKind::Statistics,
+        );
+        if let Some(statistics) = key
+            .as_ref()
+            .and_then(|key| cached_statistics(cache.as_ref(), key, &table_schema))
+        {
+            return Ok(statistics);
+        }
+
+        let metadata = fetch_cached_metadata(
+            object_store.as_ref(),
+            object_store_url,
+            file,
+            cache.as_ref(),
+        )?;
+        let statistics = statistics_from_metadata(&metadata, table_schema.clone())?;
+        if let Some(key) = key {
+            cache.put(
+                key,
+                FileMetadata::Statistics {
+                    table_schema,
+                    statistics: statistics.clone(),
+                },
+            );
+        }
+        Ok(statistics)
+    }
+
     async fn create_physical_plan(
         &self,
         conf: FileScanConfig,
@@ -273,34 +341,92 @@ fn summarize_min_max(
     }
 }
 
-/// Read and parse the schema of the Parquet file at location `path`
-fn fetch_schema(object_reader: Arc<dyn ObjectReader>) -> Result<Schema> {
+/// Read and parse the footer of the Parquet file at location `path`
+fn fetch_metadata(object_reader: Arc<dyn ObjectReader>) -> Result<ParquetMetaData> {
     let obj_reader = ChunkObjectReader {
         object_reader,
         bytes_scanned: None,
     };
-    let file_reader = Arc::new(SerializedFileReader::new(obj_reader)?);
-    let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
-    let schema = arrow_reader.get_schema()?;
+    Ok(parse_metadata(&obj_reader)?)
+}
+
+/// Get the footer of `file` of the object store at `object_store_url` from
+/// `cache`, reading and caching it if missing
+fn fetch_cached_metadata(
+    object_store: &dyn ObjectStore,
+    object_store_url: &ObjectStoreUrl,
+    file: &FileMeta,
+    cache: &dyn FileMetadataCache,
+) -> Result<Arc<ParquetMetaData>> {
+    let key = FileMetadataKey::try_new(object_store_url, file, FileMetadataKind::Parquet);
+    if let Some(FileMetadata::Parquet(metadata)) =
+        key.as_ref().and_then(|key| cache.get(key))
+    {
+        return Ok(metadata);
+    }
+
+    let object_reader = object_store.file_reader(file.sized_file.clone())?;
+    let metadata = Arc::new(fetch_metadata(object_reader)?);
+    if let Some(key) = key {
+        cache.put(key, FileMetadata::Parquet(Arc::clone(&metadata)));
+    }
+    Ok(metadata)
+}
+
+/// Get the schema of `file` of the object store at `object_store_url` from
+/// `cache`, using its cached footer and caching the schema if missing
+fn fetch_cached_schema(
+    object_store: &dyn ObjectStore,
+    object_store_url: &ObjectStoreUrl,
+    file: &FileMeta,
+    cache: &dyn FileMetadataCache,
+) -> Result<SchemaRef> {
+    let key = FileMetadataKey::try_new(object_store_url, file, FileMetadataKind::Schema);
+    if let Some(FileMetadata::Schema(schema)) =
+        key.as_ref().and_then(|key| cache.get(key))
+    {
+        return Ok(schema);
+    }
 
+    let metadata = fetch_cached_metadata(object_store, object_store_url, file, cache)?;
+    let schema = Arc::new(schema_from_metadata(&metadata)?);
+    if let Some(key) = key {
+        cache.put(key, FileMetadata::Schema(Arc::clone(&schema)));
+    }
     Ok(schema)
 }
 
+/// Read and parse the schema of the Parquet file at location `path`
+fn fetch_schema(object_reader: Arc<dyn ObjectReader>) -> Result<Schema> {
+    schema_from_metadata(&fetch_metadata(object_reader)?)
+}
+
+/// Convert the schema stored in the footer of a Parquet file
+fn schema_from_metadata(metadata: &ParquetMetaData) -> Result<Schema> {
+    let file_metadata = metadata.file_metadata();
+    Ok(parquet_to_arrow_schema(
+        file_metadata.schema_descr(),
+        file_metadata.key_value_metadata(),
+    )?)
+}
+
 /// Read and parse the statistics of the Parquet file at location `path`
 fn fetch_statistics(
     object_reader: Arc<dyn ObjectReader>,
     table_schema: SchemaRef,
 ) -> Result<Statistics> {
-    let obj_reader = ChunkObjectReader {
-        object_reader,
-        bytes_scanned: None,
-    };
-    let file_reader = Arc::new(SerializedFileReader::new(obj_reader)?);
-    let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
-    let file_schema = arrow_reader.get_schema()?;
+    statistics_from_metadata(&fetch_metadata(object_reader)?, table_schema)
+}
+
+/// Compute the statistics of the columns of `table_schema` from the footer
+/// of a Parquet file
+fn statistics_from_metadata(
+    meta_data: &ParquetMetaData,
+    table_schema: SchemaRef,
+) -> Result<Statistics> {
+    let file_schema = schema_from_metadata(meta_data)?;
     let num_fields = table_schema.fields().len();
     let fields = table_schema.fields().to_vec();
-    let meta_data = arrow_reader.get_metadata();
 
     let mut num_rows = 0;
     let mut total_byte_size = 0;
@@ -822,9 +948,12 @@ mod tests {
                     file_schema,
                     file_groups,
                     statistics,
+                    pruned_files: 0,
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: vec![],
+                    output_ordering: None,
+                    bucket_columns: None,
                 },
                 &[],
             )
@@ -0,0 +1,441 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Table functions, which produce a table from literal arguments when
+//! called in the `FROM` clause of a query, e.g.
+//! `SELECT * FROM generate_series(1, 10)`
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::compute::kernels::cast_utils::string_to_timestamp_nanos;
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+
+use crate::datasource::listing::{ListingOptions, ListingTable, ListingTableConfig};
+use crate::datasource::object_store_registry::ObjectStoreUrl;
+use crate::datasource::{TableProvider, TableType};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::SessionState;
+use crate::execution::options::{CsvReadOptions, ParquetReadOptions};
+use crate::logical_plan::Expr;
+use crate::physical_plan::generate_series::{
+    GenerateSeriesExec, Series, SeriesStep, EPOCH_DAYS_FROM_CE, NANOS_PER_DAY,
+};
+use crate::physical_plan::{project_schema, ExecutionPlan};
+use crate::scalar::ScalarValue;
+
+/// A function producing a table, registered with
+/// [`SessionContext::register_udtf`](crate::execution::context::SessionContext::register_udtf)
+///
+/// Note in SQL queries, table function names are looked up using
+/// lowercase unless the query uses quotes.
+#[async_trait]
+pub trait TableFunction: Send + Sync {
+    /// Create the table produced by calling the function with `args`, the
+    /// values of the constant arguments of the call. It is called before the
+    /// query calling the function is planned, so it may do I/O.
+    async fn call(
+        &self,
+        state: &SessionState,
+        args: &[ScalarValue],
+    ) -> Result<Arc<dyn TableProvider>>;
+
+    /// Create the table produced by calling the function with `args` like
+    /// [`TableFunction::call`], if it can be done without I/O. This lets the
+    /// function be called in the statements planned with a [`SessionState`]
+    /// as their `ContextProvider`, such as by
+    /// [`SessionContext::create_logical_plan`](crate::execution::context::SessionContext::create_logical_plan).
+    /// Returns `None` by default, as the table may only be created by `call`.
+    fn call_without_io(
+        &self,
+        _state: &SessionState,
+        _args: &[ScalarValue],
+    ) -> Option<Result<Arc<dyn TableProvider>>> {
+        None
+    }
+}
+
+/// Returns the built-in table functions by name
+pub(crate) fn builtin_table_functions() -> HashMap<String, Arc<dyn TableFunction>> {
+    let functions: Vec<(&str, Arc<dyn TableFunction>)> = vec![
+        (
+            "generate_series",
+            Arc::new(GenerateSeries::new("generate_series", true)),
+        ),
+        ("range", Arc::new(GenerateSeries::new("range", false))),
+        ("read_parquet", Arc::new(ReadParquet {})),
+        ("read_csv", Arc::new(ReadCsv {})),
+    ];
+    functions
+        .into_iter()
+        .map(|(name, fun)| (name.to_string(), fun))
+        .collect()
+}
+
+/// `generate_series([start,] stop [, step])` and `range([start,] stop [, step])`
+///
+/// Produces a single column, named after the function, with the integers from
+/// `start` (default 0) to `stop` in increments of `step` (default 1), or the
+/// timestamps from `start` to `stop` if `step` is an interval.
+/// `generate_series` includes `stop` while `range` excludes it.
+pub struct GenerateSeries {
+    name: &'static str,
+    include_stop: bool,
+}
+
+impl GenerateSeries {
+    /// Create a series function called `name`
+    pub fn new(name: &'static str, include_stop: bool) -> Self {
+        Self { name, include_stop }
+    }
+
+    fn series(&self, args: &[ScalarValue]) -> Result<Series> {
+        match args {
+            [stop] => Series::try_new(
+                0,
+                self.int_arg(stop)?,
+                SeriesStep::Int64(1),
+                self.include_stop,
+            ),
+            [start, stop] => Series::try_new(
+                self.int_arg(start)?,
+                self.int_arg(stop)?,
+                SeriesStep::Int64(1),
+                self.include_stop,
+            ),
+            [start, stop, step] => match self.interval_arg(step)? {
+                Some(step) => Series::try_new(
+                    self.timestamp_arg(start)?,
+                    self.timestamp_arg(stop)?,
+                    step,
+                    self.include_stop,
+                ),
+                None => Series::try_new(
+                    self.int_arg(start)?,
+                    self.int_arg(stop)?,
+                    SeriesStep::Int64(self.int_arg(step)?),
+                    self.include_stop,
+                ),
+            },
+            _ => Err(DataFusionError::Plan(format!(
+                "{} expects 1 to 3 arguments, got {}",
+                self.name,
+                args.len()
+            ))),
+        }
+    }
+
+    fn int_arg(&self, arg: &ScalarValue) -> Result<i64> {
+        let value = match arg {
+            ScalarValue::Int8(Some(v)) => Some(*v as i64),
+            ScalarValue::Int16(Some(v)) => Some(*v as i64),
+            ScalarValue::Int32(Some(v)) => Some(*v as i64),
+            ScalarValue::Int64(Some(v)) => Some(*v),
+            ScalarValue::UInt8(Some(v)) => Some(*v as i64),
+            ScalarValue::UInt16(Some(v)) => Some(*v as i64),
+            ScalarValue::UInt32(Some(v)) => Some(*v as i64),
+            ScalarValue::UInt64(Some(v)) => i64::try_from(*v).ok(),
+            _ => None,
+        };
+        value.ok_or_else(|| self.invalid_arg("an integer", arg))
+    }
+
+    /// Returns the timestamp in nanoseconds of a timestamp, date, or string
+    /// holding either
+    fn timestamp_arg(&self, arg: &ScalarValue) -> Result<i64> {
+        let value = match arg {
+            ScalarValue::TimestampSecond(Some(v), _) => v.checked_mul(1_000_000_000),
+            ScalarValue::TimestampMillisecond(Some(v), _) => v.checked_mul(1_000_000),
+            ScalarValue::TimestampMicrosecond(Some(v), _) => v.checked_mul(1_000),
+            ScalarValue::TimestampNanosecond(Some(v), _) => Some(*v),
+            ScalarValue::Date32(Some(v)) => (*v as i64).checked_mul(NANOS_PER_DAY),
+            ScalarValue::Date64(Some(v)) => v.checked_mul(1_000_000),
+            ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => {
+                string_to_timestamp_nanos(v).ok().or_else(|| {
+                    let days = v.parse::<NaiveDate>().ok()?.num_days_from_ce() as i64
+                        - EPOCH_DAYS_FROM_CE;
+                    days.checked_mul(NANOS_PER_DAY)
+                })
+            }
+            _ => None,
+        };
+        value.ok_or_else(|| self.invalid_arg("a timestamp", arg))
+    }
+
+    /// Returns the step of a timestamp series, or `None` if `arg` is not an interval
+    fn interval_arg(&self, arg: &ScalarValue) -> Result<Option<SeriesStep>> {
+        let (months, days, nanos) = match arg {
+            ScalarValue::IntervalYearMonth(Some(v)) => (*v, 0, 0),
+            ScalarValue::IntervalDayTime(Some(v)) => {
+                (0, (*v >> 32) as i32, (*v as i32) as i64 * 1_000_000)
+            }
+            ScalarValue::IntervalMonthDayNano(Some(v)) => {
+                ((*v >> 96) as i32, (*v >> 64) as i32, *v as i64)
+            }
+            ScalarValue::IntervalYearMonth(None)
+            | ScalarValue::IntervalDayTime(None)
+            | ScalarValue::IntervalMonthDayNano(None) => {
+                return Err(self.invalid_arg("an interval", arg))
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(SeriesStep::Interval {
+            months,
+            days,
+            nanos,
+        }))
+    }
+
+    fn invalid_arg(&self, expected: &str, arg: &ScalarValue) -> DataFusionError {
+        DataFusionError::Plan(format!(
+            "{} expects {} argument, got {:?}",
+            self.name, expected, arg
+        ))
+    }
+}
+
+#[async_trait]
+impl TableFunction for GenerateSeries {
+    async fn call(
+        &self,
+        _state: &SessionState,
+        args: &[ScalarValue],
+    ) -> Result<Arc<dyn TableProvider>> {
+        Ok(Arc::new(SeriesTable::new(self.name, self.series(args)?)))
+    }
+
+    fn call_without_io(
+        &self,
+        _state: &SessionState,
+        args: &[ScalarValue],
+    ) -> Option<Result<Arc<dyn TableProvider>>> {
+        Some(
+            self.series(args)
+                .map(|series| Arc::new(SeriesTable::new(self.name, series)) as _),
+        )
+    }
+}
+
+/// A table with a single column holding the values of a [`Series`]
+pub struct SeriesTable {
+    schema: SchemaRef,
+    series: Series,
+}
+
+impl SeriesTable {
+    /// Create a table with the values of `series` in column `name`
+    pub fn new(name: &str, series: Series) -> Self {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            name,
+            series.data_type(),
+            false,
+        )]));
+        Self { schema, series }
+    }
+}
+
+#[async_trait]
+impl TableProvider for SeriesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = project_schema(&self.schema, projection.as_ref())?;
+        Ok(Arc::new(GenerateSeriesExec::new(
+            self.series,
+            schema,
+            limit,
+        )))
+    }
+}
+
+/// `read_parquet(path)`: reads the parquet files at `path`, which may be a
+/// glob, as a table
+pub struct ReadParquet {}
+
+#[async_trait]
+impl TableFunction for ReadParquet {
+    async fn call(
+        &self,
+        state: &SessionState,
+        args: &[ScalarValue],
+    ) -> Result<Arc<dyn TableProvider>> {
+        let path = match args {
+            [path] => string_arg("read_parquet", path)?,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "read_parquet expects 1 argument, got {}",
+                    args.len()
+                )))
+            }
+        };
+        let options = ParquetReadOptions::default()
+            .to_listing_options(state.config.target_partitions);
+        listing_table(state, path, options).await
+    }
+}
+
+/// `read_csv(path [, has_header [, delimiter]])`: reads the CSV files at
+/// `path`, which may be a glob, as a table. The files have a header row
+/// and are delimited by `,` by default.
+pub struct ReadCsv {}
+
+#[async_trait]
+impl TableFunction for ReadCsv {
+    async fn call(
+        &self,
+        state: &SessionState,
+        args: &[ScalarValue],
+    ) -> Result<Arc<dyn TableProvider>> {
+        if args.is_empty() || args.len() > 3 {
+            return Err(DataFusionError::Plan(format!(
+                "read_csv expects 1 to 3 arguments, got {}",
+                args.len()
+            )));
+        }
+        let path = string_arg("read_csv", &args[0])?;
+        let mut options = CsvReadOptions::new();
+        if let Some(has_header) = args.get(1) {
+            options = match has_header {
+                ScalarValue::Boolean(Some(has_header)) => options.has_header(*has_header),
+                other => {
+                    return Err(DataFusionError::Plan(format!(
+                        "read_csv expects a boolean has_header argument, got {:?}",
+                        other
+                    )))
+                }
+            };
+        }
+        if let Some(delimiter) = args.get(2) {
+            options = match string_arg("read_csv", delimiter)?.as_bytes() {
+                [delimiter] => options.delimiter(*delimiter),
+                _ => {
+                    return Err(DataFusionError::Plan(format!(
+                        "read_csv expects a single character delimiter, got {:?}",
+                        delimiter
+                    )))
+                }
+            };
+        }
+        let options = options.to_listing_options(state.config.target_partitions);
+        listing_table(state, path, options).await
+    }
+}
+
+fn string_arg<'a>(name: &str, arg: &'a ScalarValue) -> Result<&'a str> {
+    match arg {
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => Ok(v),
+        other => Err(DataFusionError::Plan(format!(
+            "{} expects a string argument, got {:?}",
+            name, other
+        ))),
+    }
+}
+
+/// Create a [`ListingTable`] reading the files at `uri`
+async fn listing_table(
+    state: &SessionState,
+    uri: &str,
+    options: ListingOptions,
+) -> Result<Arc<dyn TableProvider>> {
+    let (object_store, path) = state.runtime_env.object_store(uri)?;
+    let object_store_url = ObjectStoreUrl::from_uri(uri);
+    let cache_manager = Arc::clone(&state.runtime_env.cache_manager);
+    let schema = options
+        .infer_schema_with_cache(
+            Arc::clone(&object_store),
+            path,
+            Some((&object_store_url, cache_manager.as_ref())),
+        )
+        .await?;
+    if schema.fields().is_empty() {
+        return Err(DataFusionError::Plan(format!(
+            "No files found at '{}'",
+            uri
+        )));
+    }
+    let config = ListingTableConfig::new(object_store, path)
+        .with_cache_manager(object_store_url, cache_manager)
+        .with_listing_options(options)
+        .with_schema(schema);
+    Ok(Arc::new(ListingTable::try_new(config)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::common;
+    use crate::prelude::SessionContext;
+    use arrow::datatypes::{DataType, TimeUnit};
+
+    #[tokio::test]
+    async fn generate_series_args() -> Result<()> {
+        let ctx = SessionContext::new();
+        let state = ctx.state.read().clone();
+        let fun = GenerateSeries::new("generate_series", true);
+
+        let table = fun.call(&state, &[ScalarValue::Int64(Some(3))]).await?;
+        assert_eq!(table.schema().field(0).name(), "generate_series");
+        assert_eq!(table.schema().field(0).data_type(), &DataType::Int64);
+        let exec = table.scan(&None, &[], None).await?;
+        let batches = common::collect(exec.execute(0, ctx.task_ctx())?).await?;
+        assert_eq!(batches[0].num_rows(), 4);
+
+        let table = fun
+            .call(
+                &state,
+                &[
+                    ScalarValue::Date32(Some(0)),
+                    ScalarValue::Utf8(Some("1970-01-03T00:00:00".to_string())),
+                    ScalarValue::IntervalDayTime(Some(1 << 32)),
+                ],
+            )
+            .await?;
+        assert_eq!(
+            table.schema().field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None)
+        );
+
+        let err = fun
+            .call(&state, &[ScalarValue::Utf8(Some("a".to_string()))])
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("expects an integer argument"));
+
+        let err = fun.call(&state, &[]).await.err().unwrap();
+        assert!(err.to_string().contains("expects 1 to 3 arguments, got 0"));
+        Ok(())
+    }
+}
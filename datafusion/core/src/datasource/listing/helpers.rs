@@ -37,15 +37,20 @@ use futures::{
 use log::debug;
 
 use crate::{
-    datasource::MemTable,
+    datasource::{object_store_registry::ObjectStoreUrl, MemTable},
     error::Result,
-    execution::context::SessionContext,
+    execution::{cache_manager::ListFilesCache, context::SessionContext},
     logical_plan::{self, Expr, ExprVisitable, ExpressionVisitor, Recursion},
+    physical_optimizer::pruning::PruningStatistics,
+    physical_plan::{ColumnStatistics, Statistics},
     scalar::ScalarValue,
 };
 
 use super::{PartitionedFile, PartitionedFileStream};
-use datafusion_data_access::{object_store::ObjectStore, FileMeta, SizedFile};
+use datafusion_data_access::{
+    object_store::{FileMetaStream, ObjectStore},
+    FileMeta, SizedFile,
+};
 use datafusion_expr::Volatility;
 
 const FILE_SIZE_COLUMN_NAME: &str = "_df_part_file_size_";
@@ -137,6 +142,48 @@ pub fn expr_applicable_for_cols(col_names: &[String], expr: &Expr) -> bool {
     is_applicable
 }
 
+/// The statistics of a single file, against which a
+/// [`PruningPredicate`](crate::physical_optimizer::pruning::PruningPredicate)
+/// determines whether the file can contain rows matching the filters
+pub(crate) struct FileStatistics<'a> {
+    /// The schema of the file, that the column statistics refer to
+    pub schema: &'a Schema,
+    /// The statistics of the file
+    pub statistics: &'a Statistics,
+}
+
+impl FileStatistics<'_> {
+    fn column(&self, column: &logical_plan::Column) -> Option<&ColumnStatistics> {
+        let index = self.schema.index_of(&column.name).ok()?;
+        self.statistics.column_statistics.as_ref()?.get(index)
+    }
+}
+
+impl PruningStatistics for FileStatistics<'_> {
+    fn min_values(&self, column: &logical_plan::Column) -> Option<ArrayRef> {
+        self.column(column)?
+            .min_value
+            .as_ref()
+            .map(|v| v.to_array())
+    }
+
+    fn max_values(&self, column: &logical_plan::Column) -> Option<ArrayRef> {
+        self.column(column)?
+            .max_value
+            .as_ref()
+            .map(|v| v.to_array())
+    }
+
+    fn num_containers(&self) -> usize {
+        1
+    }
+
+    fn null_counts(&self, column: &logical_plan::Column) -> Option<ArrayRef> {
+        let null_count = self.column(column)?.null_count?;
+        Some(Arc::new(UInt64Array::from(vec![null_count as u64])))
+    }
+}
+
 /// Partition the list of files into `n` groups
 pub fn split_files(
     partitioned_files: Vec<PartitionedFile>,
@@ -153,6 +200,47 @@ pub fn split_files(
         .collect()
 }
 
+/// List the files with `file_extension` under `table_path`, using the
+/// listing cached in `list_files_cache` if it has one, along with the URL
+/// identifying `store` in the cache
+pub async fn list_all_files(
+    store: &dyn ObjectStore,
+    table_path: &str,
+    file_extension: &str,
+    list_files_cache: Option<(&ObjectStoreUrl, &Arc<dyn ListFilesCache>)>,
+) -> Result<FileMetaStream> {
+    let (object_store_url, list_files_cache) = match list_files_cache {
+        Some(cache) => cache,
+        None => {
+            return Ok(store
+                .glob_file_with_suffix(table_path, file_extension)
+                .await?)
+        }
+    };
+
+    let files = match list_files_cache.get(object_store_url, table_path, file_extension) {
+        Some(files) => files,
+        None => {
+            let files: Vec<FileMeta> = store
+                .glob_file_with_suffix(table_path, file_extension)
+                .await?
+                .try_collect()
+                .await?;
+            let files = Arc::new(files);
+            list_files_cache.put(
+                object_store_url,
+                table_path,
+                file_extension,
+                Arc::clone(&files),
+            );
+            files
+        }
+    };
+    Ok(Box::pin(stream::iter(
+        files.as_ref().clone().into_iter().map(Ok),
+    )))
+}
+
 /// Discover the partitions on the given path and prune out files
 /// that belong to irrelevant partitions using `filters` expressions.
 /// `filters` might contain expressions that can be resolved only at the
@@ -167,12 +255,12 @@ pub async fn pruned_partition_list(
     filters: &[Expr],
     file_extension: &str,
     table_partition_cols: &[String],
+    list_files_cache: Option<(&ObjectStoreUrl, &Arc<dyn ListFilesCache>)>,
 ) -> Result<PartitionedFileStream> {
     // if no partition col => simply list all the files
     if table_partition_cols.is_empty() {
         return Ok(Box::pin(
-            store
-                .glob_file_with_suffix(table_path, file_extension)
+            list_all_files(store, table_path, file_extension, list_files_cache)
                 .await?
                 .map(|f| {
                     Ok(PartitionedFile {
@@ -196,8 +284,7 @@ pub async fn pruned_partition_list(
         // the object store.
         let table_partition_cols_stream = table_partition_cols.to_vec();
         Ok(Box::pin(
-            store
-                .glob_file_with_suffix(table_path, file_extension)
+            list_all_files(store, table_path, file_extension, list_files_cache)
                 .await?
                 .filter_map(move |f| {
                     let stream_path = stream_path.clone();
@@ -231,22 +318,24 @@ pub async fn pruned_partition_list(
     } else {
         // parse the partition values and serde them as a RecordBatch to filter them
         // TODO avoid collecting but have a streaming memory table instead
-        let batches: Vec<RecordBatch> = store
-            .glob_file_with_suffix(table_path, file_extension)
-            .await?
-            // TODO we set an arbitrary high batch size here, it does not matter as we list
-            // all the files anyway. This number will need to be adjusted according to the object
-            // store if we switch to a streaming-stlye pruning of the files. For instance S3 lists
-            // 1000 items at a time so batches of 1000 would be ideal with S3 as store.
-            .chunks(1024)
-            .map(|v| {
-                v.into_iter()
-                    .collect::<datafusion_data_access::Result<Vec<_>>>()
-            })
-            .map_err(DataFusionError::IoError)
-            .map(move |metas| paths_to_batch(table_partition_cols, &stream_path, &metas?))
-            .try_collect()
-            .await?;
+        let batches: Vec<RecordBatch> =
+            list_all_files(store, table_path, file_extension, list_files_cache)
+                .await?
+                // TODO we set an arbitrary high batch size here, it does not matter as we list
+                // all the files anyway. This number will need to be adjusted according to the object
+                // store if we switch to a streaming-stlye pruning of the files. For instance S3 lists
+                // 1000 items at a time so batches of 1000 would be ideal with S3 as store.
+                .chunks(1024)
+                .map(|v| {
+                    v.into_iter()
+                        .collect::<datafusion_data_access::Result<Vec<_>>>()
+                })
+                .map_err(DataFusionError::IoError)
+                .map(move |metas| {
+                    paths_to_batch(table_partition_cols, &stream_path, &metas?)
+                })
+                .try_collect()
+                .await?;
 
         let mem_table = MemTable::try_new(batches[0].schema(), vec![batches])?;
 
@@ -399,6 +488,7 @@ fn parse_partitions_for_path<'a>(
 mod tests {
     use crate::{
         logical_plan::{case, col, lit},
+        physical_optimizer::pruning::PruningPredicate,
         test::object_store::TestObjectStore,
     };
 
@@ -457,6 +547,7 @@ mod tests {
             &[filter],
             ".parquet",
             &[String::from("mypartition")],
+            None,
         )
         .await
         .expect("partition pruning failed")
@@ -480,6 +571,7 @@ mod tests {
             &[filter],
             ".parquet",
             &[String::from("mypartition")],
+            None,
         )
         .await
         .expect("partition pruning failed")
@@ -526,6 +618,7 @@ mod tests {
             &[filter1, filter2, filter3],
             ".parquet",
             &[String::from("part1"), String::from("part2")],
+            None,
         )
         .await
         .expect("partition pruning failed")
@@ -754,4 +847,42 @@ mod tests {
         // this helper function
         assert!(expr_applicable_for_cols(&[], &lit(true)));
     }
+
+    #[test]
+    fn test_file_statistics_pruning() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let statistics = Statistics {
+            num_rows: Some(10),
+            total_byte_size: None,
+            column_statistics: Some(vec![
+                ColumnStatistics {
+                    null_count: Some(0),
+                    max_value: Some(ScalarValue::Int32(Some(20))),
+                    min_value: Some(ScalarValue::Int32(Some(10))),
+                    distinct_count: None,
+                },
+                ColumnStatistics::default(),
+            ]),
+            is_exact: true,
+        };
+        let file_statistics = FileStatistics {
+            schema: &schema,
+            statistics: &statistics,
+        };
+        let prune = |expr: Expr| {
+            PruningPredicate::try_new(expr, Arc::new(schema.clone()))?
+                .prune(&file_statistics)
+        };
+
+        assert_eq!(prune(col("a").gt(lit(20)))?, vec![false]);
+        assert_eq!(prune(col("a").lt_eq(lit(10)))?, vec![true]);
+        assert_eq!(prune(col("a").eq(lit(15)))?, vec![true]);
+        assert_eq!(prune(col("a").is_null())?, vec![false]);
+        // without statistics, the file may match
+        assert_eq!(prune(col("b").gt(lit(20)))?, vec![true]);
+        Ok(())
+    }
 }
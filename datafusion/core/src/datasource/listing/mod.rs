@@ -26,7 +26,7 @@ use datafusion_data_access::{object_store::local, FileMeta, Result, SizedFile};
 use futures::Stream;
 use std::pin::Pin;
 
-pub use table::{ListingOptions, ListingTable, ListingTableConfig};
+pub use table::{BucketSpec, ListingOptions, ListingTable, ListingTableConfig};
 
 /// Stream of files get listed from object store
 pub type PartitionedFileStream =
@@ -17,34 +17,47 @@
 
 //! The table implementation.
 
-use std::{any::Any, sync::Arc};
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
+use arrow::compute::SortOptions;
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
+use log::debug;
 
 use crate::datasource::{
     file_format::{
-        avro::AvroFormat, csv::CsvFormat, json::JsonFormat, parquet::ParquetFormat,
-        FileFormat,
+        arrow::ArrowFormat, avro::AvroFormat, csv::CsvFormat, json::JsonFormat,
+        parquet::ParquetFormat, FileFormat,
     },
-    get_statistics_with_limit, TableProvider, TableType,
+    get_statistics_with_limit,
+    object_store_registry::ObjectStoreUrl,
+    TableProvider, TableType,
 };
+use crate::execution::cache_manager::CacheManager;
 use crate::logical_expr::TableProviderFilterPushDown;
+use crate::physical_optimizer::pruning::PruningPredicate;
 use crate::{
     error::{DataFusionError, Result},
-    logical_plan::Expr,
+    logical_plan::{combine_filters, Expr},
     physical_plan::{
+        bucket_hash::BUCKET_HASH_VERSION,
         empty::EmptyExec,
+        expressions::{Column, PhysicalSortExpr},
         file_format::{FileScanConfig, DEFAULT_PARTITION_COLUMN_DATATYPE},
-        project_schema, ExecutionPlan, Statistics,
+        project_schema, ColumnStatistics, ExecutionPlan, Statistics,
     },
 };
 
 use super::PartitionedFile;
 use datafusion_data_access::object_store::ObjectStore;
 
-use super::helpers::{expr_applicable_for_cols, pruned_partition_list, split_files};
+use super::helpers::{
+    expr_applicable_for_cols, list_all_files, pruned_partition_list, split_files,
+    FileStatistics,
+};
 
 /// Configuration for creating a 'ListingTable'  
 pub struct ListingTableConfig {
@@ -56,6 +69,10 @@ pub struct ListingTableConfig {
     pub file_schema: Option<SchemaRef>,
     /// Optional `ListingOptions` for the to be created `ListingTable`.
     pub options: Option<ListingOptions>,
+    /// Optional `CacheManager` caching the listing and file metadata of the
+    /// to be created `ListingTable`, along with the URL identifying
+    /// `object_store` in the caches, such as `s3://`.
+    pub cache_manager: Option<(ObjectStoreUrl, Arc<CacheManager>)>,
 }
 
 impl ListingTableConfig {
@@ -69,6 +86,7 @@ impl ListingTableConfig {
             table_path: table_path.into(),
             file_schema: None,
             options: None,
+            cache_manager: None,
         }
     }
     /// Add `schema` to `ListingTableConfig`
@@ -78,6 +96,7 @@ impl ListingTableConfig {
             table_path: self.table_path,
             file_schema: Some(schema),
             options: self.options,
+            cache_manager: self.cache_manager,
         }
     }
 
@@ -88,11 +107,29 @@ impl ListingTableConfig {
             table_path: self.table_path,
             file_schema: self.file_schema,
             options: Some(listing_options),
+            cache_manager: self.cache_manager,
+        }
+    }
+
+    /// Add `cache_manager` to `ListingTableConfig`, caching the files of
+    /// `object_store` under `object_store_url`
+    pub fn with_cache_manager(
+        self,
+        object_store_url: ObjectStoreUrl,
+        cache_manager: Arc<CacheManager>,
+    ) -> Self {
+        Self {
+            object_store: self.object_store,
+            table_path: self.table_path,
+            file_schema: self.file_schema,
+            options: self.options,
+            cache_manager: Some((object_store_url, cache_manager)),
         }
     }
 
     fn infer_format(suffix: &str) -> Result<Arc<dyn FileFormat>> {
         match suffix {
+            "arrow" => Ok(Arc::new(ArrowFormat)),
             "avro" => Ok(Arc::new(AvroFormat::default())),
             "csv" => Ok(Arc::new(CsvFormat::default())),
             "json" => Ok(Arc::new(JsonFormat::default())),
@@ -125,6 +162,8 @@ impl ListingTableConfig {
             file_extension: file_type.to_string(),
             target_partitions: num_cpus::get(),
             table_partition_cols: vec![],
+            file_sort_order: None,
+            bucketing: None,
         };
 
         Ok(Self {
@@ -132,6 +171,7 @@ impl ListingTableConfig {
             table_path: self.table_path,
             file_schema: self.file_schema,
             options: Some(listing_options),
+            cache_manager: self.cache_manager,
         })
     }
 
@@ -140,7 +180,13 @@ impl ListingTableConfig {
         match self.options {
             Some(options) => {
                 let schema = options
-                    .infer_schema(self.object_store.clone(), self.table_path.as_str())
+                    .infer_schema_with_cache(
+                        self.object_store.clone(),
+                        self.table_path.as_str(),
+                        self.cache_manager
+                            .as_ref()
+                            .map(|(url, cache_manager)| (url, cache_manager.as_ref())),
+                    )
                     .await?;
 
                 Ok(Self {
@@ -148,6 +194,7 @@ impl ListingTableConfig {
                     table_path: self.table_path,
                     file_schema: Some(schema),
                     options: Some(options),
+                    cache_manager: self.cache_manager,
                 })
             }
             None => Err(DataFusionError::Internal(
@@ -185,6 +232,49 @@ pub struct ListingOptions {
     /// Group files to avoid that the number of partitions exceeds
     /// this limit
     pub target_partitions: usize,
+    /// The order in which the rows of each file are sorted, as sort
+    /// expressions on columns of the table (e.g. `col("ts").sort(true, false)`).
+    /// When set, each file is scanned as its own partition so that the scan
+    /// can report this ordering and sorts that it satisfies can be removed.
+    pub file_sort_order: Option<Vec<Expr>>,
+    /// The bucketing of the files of the table. When set, the file named
+    /// `part-{i}...` holds the rows of bucket `i` and, if they were assigned
+    /// with the current version of the bucket hash, the scan reports a hash
+    /// partitioning on the bucket columns, so that joins and aggregations on
+    /// these columns do not need to repartition it.
+    pub bucketing: Option<BucketSpec>,
+}
+
+/// Describes how the rows of a bucketed table are distributed among its files
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketSpec {
+    /// The columns of the file schema whose hash assigns a row to its bucket
+    pub columns: Vec<String>,
+    /// The number of buckets
+    pub num_buckets: usize,
+    /// The version of the hash that assigned the rows to their bucket, see
+    /// [`create_bucket_hashes`]
+    ///
+    /// [`create_bucket_hashes`]: crate::physical_plan::bucket_hash::create_bucket_hashes
+    pub hash_version: u32,
+}
+
+impl BucketSpec {
+    /// Creates a bucketing of the rows on `columns` into `num_buckets` buckets,
+    /// with the current version of the bucket hash
+    pub fn new(columns: Vec<String>, num_buckets: usize) -> Self {
+        Self {
+            columns,
+            num_buckets,
+            hash_version: BUCKET_HASH_VERSION,
+        }
+    }
+
+    /// Sets the version of the hash that assigned the rows to their bucket
+    pub fn with_hash_version(mut self, hash_version: u32) -> Self {
+        self.hash_version = hash_version;
+        self
+    }
 }
 
 impl ListingOptions {
@@ -194,6 +284,8 @@ impl ListingOptions {
     /// - no input partition to discover
     /// - one target partition
     /// - no stat collection
+    /// - no file sort order
+    /// - no bucketing
     pub fn new(format: Arc<dyn FileFormat>) -> Self {
         Self {
             file_extension: String::new(),
@@ -201,6 +293,8 @@ impl ListingOptions {
             table_partition_cols: vec![],
             collect_stat: true,
             target_partitions: 1,
+            file_sort_order: None,
+            bucketing: None,
         }
     }
 
@@ -215,12 +309,45 @@ impl ListingOptions {
         object_store: Arc<dyn ObjectStore>,
         path: &'a str,
     ) -> Result<SchemaRef> {
-        let file_stream = object_store
-            .glob_file_with_suffix(path, &self.file_extension)
-            .await?
-            .map(move |file_meta| object_store.file_reader(file_meta?.sized_file));
-        let file_schema = self.format.infer_schema(Box::pin(file_stream)).await?;
-        Ok(file_schema)
+        self.infer_schema_with_cache(object_store, path, None).await
+    }
+
+    /// Infer the schema of the files at the given path like
+    /// [`ListingOptions::infer_schema`], reusing the listing and the file
+    /// metadata cached by `cache_manager` for the object store at the URL it
+    /// is paired with.
+    pub async fn infer_schema_with_cache<'a>(
+        &'a self,
+        object_store: Arc<dyn ObjectStore>,
+        path: &'a str,
+        cache_manager: Option<(&'a ObjectStoreUrl, &'a CacheManager)>,
+    ) -> Result<SchemaRef> {
+        let files = list_all_files(
+            object_store.as_ref(),
+            path,
+            &self.file_extension,
+            cache_manager.and_then(|(url, cache_manager)| {
+                Some((url, cache_manager.list_files_cache()?))
+            }),
+        )
+        .await?;
+
+        match cache_manager.and_then(|(url, cache_manager)| {
+            Some((url, cache_manager.file_metadata_cache()?))
+        }) {
+            Some((url, cache)) => {
+                let files = files.try_collect().await?;
+                self.format
+                    .infer_schema_with_cache(object_store, url, files, Arc::clone(cache))
+                    .await
+            }
+            None => {
+                let file_stream = files.map(move |file_meta| {
+                    object_store.file_reader(file_meta?.sized_file)
+                });
+                self.format.infer_schema(Box::pin(file_stream)).await
+            }
+        }
     }
 }
 
@@ -234,6 +361,15 @@ pub struct ListingTable {
     /// File fields + partition columns
     table_schema: SchemaRef,
     options: ListingOptions,
+    /// The physical sort order of the rows of each file, if declared
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
+    /// The indices in the file schema of the bucket columns, if bucketed
+    bucket_columns: Option<Vec<usize>>,
+    /// The caches of the listing and file metadata, with the URL identifying
+    /// the object store in them
+    cache_manager: Option<(ObjectStoreUrl, Arc<CacheManager>)>,
+    /// The statistics of the files of the table, once collected
+    statistics: Option<Statistics>,
 }
 
 impl ListingTable {
@@ -263,17 +399,59 @@ impl ListingTable {
             ));
         }
 
+        let table_schema = Arc::new(Schema::new(table_fields));
+        let output_ordering = options
+            .file_sort_order
+            .as_ref()
+            .map(|sort_order| file_output_ordering(sort_order, &table_schema))
+            .transpose()?;
+        // buckets assigned by another version of the hash are still scanned
+        // one at a time, but not as a hash partitioning
+        let bucket_columns = options
+            .bucketing
+            .as_ref()
+            .map(|bucketing| bucket_column_indices(bucketing, &file_schema))
+            .transpose()?
+            .filter(|_| {
+                options.bucketing.as_ref().map(|b| b.hash_version)
+                    == Some(BUCKET_HASH_VERSION)
+            });
+
         let table = Self {
             object_store: config.object_store.clone(),
             table_path: config.table_path.clone(),
             file_schema,
-            table_schema: Arc::new(Schema::new(table_fields)),
+            table_schema,
             options,
+            output_ordering,
+            bucket_columns,
+            cache_manager: config.cache_manager,
+            statistics: None,
         };
 
         Ok(table)
     }
 
+    /// Collects the statistics of the files of the table, if the options
+    /// require it, so that they are available to the optimizer when planning
+    /// queries. This lists all the files of the table and reads their
+    /// metadata, so it is not done unless requested, and the statistics are
+    /// those of the files listed at the time of the call.
+    pub async fn with_collected_statistics(mut self) -> Result<Self> {
+        if self.options.collect_stat {
+            let (_, mut statistics, _) = self.list_files_for_scan(&[], None).await?;
+            // the partition columns have no statistics
+            if let Some(column_statistics) = &mut statistics.column_statistics {
+                column_statistics.resize(
+                    self.table_schema.fields().len(),
+                    ColumnStatistics::default(),
+                );
+            }
+            self.statistics = Some(statistics);
+        }
+        Ok(self)
+    }
+
     /// Get object store ref
     pub fn object_store(&self) -> &Arc<dyn ObjectStore> {
         &self.object_store
@@ -308,16 +486,27 @@ impl TableProvider for ListingTable {
         filters: &[Expr],
         limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let (partitioned_file_lists, statistics) =
+        let (mut partitioned_file_lists, statistics, pruned_files) =
             self.list_files_for_scan(filters, limit).await?;
 
-        // if no files need to be read, return an `EmptyExec`
-        if partitioned_file_lists.is_empty() {
+        // if no files need to be read, return an `EmptyExec`, unless files
+        // were pruned, so that the pruning is still reported by the scan
+        if partitioned_file_lists.is_empty() && pruned_files > 0 {
+            partitioned_file_lists.push(vec![]);
+        } else if partitioned_file_lists.is_empty() {
             let schema = self.schema();
             let projected_schema = project_schema(&schema, projection.as_ref())?;
             return Ok(Arc::new(EmptyExec::new(false, projected_schema)));
         }
 
+        // concatenating sorted files would not keep them sorted
+        let output_ordering =
+            if partitioned_file_lists.iter().all(|group| group.len() <= 1) {
+                self.output_ordering.clone()
+            } else {
+                None
+            };
+
         // create the execution plan
         self.options
             .format
@@ -327,9 +516,12 @@ impl TableProvider for ListingTable {
                     file_schema: Arc::clone(&self.file_schema),
                     file_groups: partitioned_file_lists,
                     statistics,
+                    pruned_files,
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: self.options.table_partition_cols.clone(),
+                    output_ordering,
+                    bucket_columns: self.bucket_columns.clone(),
                 },
                 filters,
             )
@@ -349,17 +541,23 @@ impl TableProvider for ListingTable {
             Ok(TableProviderFilterPushDown::Inexact)
         }
     }
+
+    fn statistics(&self) -> Option<Statistics> {
+        self.statistics.clone()
+    }
 }
 
 impl ListingTable {
-    /// Get the list of files for a scan as well as the file level statistics.
+    /// Get the list of files for a scan as well as the file level statistics,
+    /// and the number of files skipped as their statistics show that they
+    /// cannot match `filters`.
     /// The list is grouped to let the execution plan know how the files should
     /// be distributed to different threads / executors.
     async fn list_files_for_scan<'a>(
         &'a self,
         filters: &'a [Expr],
         limit: Option<usize>,
-    ) -> Result<(Vec<Vec<PartitionedFile>>, Statistics)> {
+    ) -> Result<(Vec<Vec<PartitionedFile>>, Statistics, usize)> {
         // list files (with partitions)
         let file_list = pruned_partition_list(
             self.object_store.as_ref(),
@@ -367,37 +565,219 @@ impl ListingTable {
             filters,
             &self.options.file_extension,
             &self.options.table_partition_cols,
+            self.cache_manager
+                .as_ref()
+                .and_then(|(url, cache_manager)| {
+                    Some((url, cache_manager.list_files_cache()?))
+                }),
         )
         .await?;
 
+        // files whose statistics show that they cannot match the filters are skipped
+        let pruning_predicate = if self.options.collect_stat {
+            self.file_pruning_predicate(filters)
+        } else {
+            None
+        };
+        let pruned_files = AtomicUsize::new(0);
+
         // collect the statistics if required by the config
         let object_store = Arc::clone(&self.object_store);
-        let files = file_list.then(move |part_file| {
-            let object_store = object_store.clone();
-            async move {
-                let part_file = part_file?;
-                let statistics = if self.options.collect_stat {
-                    let object_reader = object_store
-                        .file_reader(part_file.file_meta.sized_file.clone())?;
-                    self.options
-                        .format
-                        .infer_stats(object_reader, self.file_schema.clone())
-                        .await?
-                } else {
-                    Statistics::default()
-                };
-                Ok((part_file, statistics)) as Result<(PartitionedFile, Statistics)>
-            }
-        });
+        let file_metadata_cache =
+            self.cache_manager
+                .as_ref()
+                .and_then(|(url, cache_manager)| {
+                    Some((url, cache_manager.file_metadata_cache()?))
+                });
+        let pruning_predicate = &pruning_predicate;
+        let pruned_files_ref = &pruned_files;
+        let files = file_list
+            .then(move |part_file| {
+                let object_store = object_store.clone();
+                async move {
+                    let part_file = part_file?;
+                    let statistics = if !self.options.collect_stat {
+                        Statistics::default()
+                    } else if let Some((url, cache)) = file_metadata_cache {
+                        self.options
+                            .format
+                            .infer_stats_with_cache(
+                                object_store,
+                                url,
+                                &part_file.file_meta,
+                                self.file_schema.clone(),
+                                Arc::clone(cache),
+                            )
+                            .await?
+                    } else {
+                        let object_reader = object_store
+                            .file_reader(part_file.file_meta.sized_file.clone())?;
+                        self.options
+                            .format
+                            .infer_stats(object_reader, self.file_schema.clone())
+                            .await?
+                    };
+                    if let Some(pruning_predicate) = pruning_predicate {
+                        let file_statistics = FileStatistics {
+                            schema: &self.file_schema,
+                            statistics: &statistics,
+                        };
+                        match pruning_predicate.prune(&file_statistics) {
+                            Ok(matches) if !matches[0] => {
+                                pruned_files_ref.fetch_add(1, Ordering::Relaxed);
+                                return Ok(None);
+                            }
+                            Ok(_) => {}
+                            // the file is read if its statistics cannot be evaluated
+                            Err(e) => debug!(
+                                "Could not prune file {} with its statistics: {}",
+                                part_file.file_meta.path(),
+                                e
+                            ),
+                        }
+                    }
+                    Ok(Some((part_file, statistics)))
+                        as Result<Option<(PartitionedFile, Statistics)>>
+                }
+            })
+            .filter_map(|file| async move { file.transpose() });
 
         let (files, statistics) =
             get_statistics_with_limit(files, self.schema(), limit).await?;
 
-        Ok((
-            split_files(files, self.options.target_partitions),
-            statistics,
-        ))
+        // each bucket is scanned as its own partition, and concatenating
+        // sorted files would not keep them sorted
+        let file_groups = if let Some(bucketing) = &self.options.bucketing {
+            if files.is_empty() {
+                vec![]
+            } else {
+                group_files_by_bucket(files, bucketing.num_buckets)?
+            }
+        } else if self.output_ordering.is_some() {
+            files.into_iter().map(|file| vec![file]).collect()
+        } else {
+            split_files(files, self.options.target_partitions)
+        };
+
+        Ok((file_groups, statistics, pruned_files.into_inner()))
+    }
+
+    /// Builds a predicate for pruning files with their statistics from the
+    /// `filters` that only reference columns of the files
+    fn file_pruning_predicate(&self, filters: &[Expr]) -> Option<PruningPredicate> {
+        let columns = self
+            .file_schema
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        let filters = filters
+            .iter()
+            .filter(|filter| expr_applicable_for_cols(&columns, filter))
+            .cloned()
+            .collect::<Vec<_>>();
+        let predicate = combine_filters(&filters)?;
+        match PruningPredicate::try_new(predicate, self.file_schema.clone()) {
+            Ok(pruning_predicate) => Some(pruning_predicate),
+            Err(e) => {
+                debug!("Could not create file pruning predicate: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Resolves the bucket columns of `bucketing` to their indices in `file_schema`
+fn bucket_column_indices(
+    bucketing: &BucketSpec,
+    file_schema: &Schema,
+) -> Result<Vec<usize>> {
+    if bucketing.columns.is_empty() || bucketing.num_buckets == 0 {
+        return Err(DataFusionError::Plan(format!(
+            "A bucketed table needs at least one bucket column and one bucket, found: {:?}",
+            bucketing
+        )));
+    }
+    bucketing
+        .columns
+        .iter()
+        .map(|name| {
+            file_schema.index_of(name).map_err(|_| {
+                DataFusionError::Plan(format!(
+                    "Bucket column {} is not a column of the files",
+                    name
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Groups `files` by bucket, the bucket of a file being the number that
+/// follows `part-` in its file name
+fn group_files_by_bucket(
+    files: Vec<PartitionedFile>,
+    num_buckets: usize,
+) -> Result<Vec<Vec<PartitionedFile>>> {
+    let mut groups = vec![vec![]; num_buckets];
+    for file in files {
+        let path = file.file_meta.path();
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let bucket = file_name
+            .strip_prefix("part-")
+            .map(|suffix| {
+                suffix
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+            })
+            .and_then(|digits| digits.parse::<usize>().ok())
+            .filter(|bucket| *bucket < num_buckets)
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "File {} of a table with {} buckets is not named part-{{bucket}}",
+                    path, num_buckets
+                ))
+            })?;
+        groups[bucket].push(file);
     }
+    Ok(groups)
+}
+
+/// Converts the declared sort order of the files to sort expressions on the
+/// columns of `table_schema`
+fn file_output_ordering(
+    sort_order: &[Expr],
+    table_schema: &Schema,
+) -> Result<Vec<PhysicalSortExpr>> {
+    sort_order
+        .iter()
+        .map(|expr| match expr {
+            Expr::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => match expr.as_ref() {
+                Expr::Column(column) => Ok(PhysicalSortExpr {
+                    expr: Arc::new(Column::new(
+                        &column.name,
+                        table_schema.index_of(&column.name)?,
+                    )),
+                    options: SortOptions {
+                        descending: !asc,
+                        nulls_first: *nulls_first,
+                    },
+                }),
+                other => Err(DataFusionError::Plan(format!(
+                    "Only columns are supported in the file sort order, found: {:?}",
+                    other
+                ))),
+            },
+            other => Err(DataFusionError::Plan(format!(
+                "Expected a sort expression in the file sort order, found: {:?}",
+                other
+            ))),
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -407,6 +787,7 @@ mod tests {
         datafusion_data_access::object_store::local::LocalFileSystem,
         datasource::file_format::{avro::AvroFormat, parquet::ParquetFormat},
         logical_plan::{col, lit},
+        physical_plan::Partitioning,
         test::{columns, object_store::TestObjectStore},
     };
     use arrow::datatypes::DataType;
@@ -462,6 +843,8 @@ mod tests {
             table_partition_cols: vec![String::from("p1")],
             target_partitions: 4,
             collect_stat: true,
+            file_sort_order: None,
+            bucketing: None,
         };
 
         let file_schema =
@@ -557,6 +940,129 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn scan_with_file_sort_order() -> Result<()> {
+        let files = ["table/file0", "table/file1", "table/file2"];
+        let store =
+            TestObjectStore::new_arc(&files.iter().map(|f| (*f, 10)).collect::<Vec<_>>());
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]));
+
+        let table_with_sort_order = |file_sort_order| {
+            let opt = ListingOptions {
+                file_extension: "".to_owned(),
+                format: Arc::new(AvroFormat {}),
+                table_partition_cols: vec![],
+                target_partitions: 2,
+                collect_stat: false,
+                file_sort_order: Some(file_sort_order),
+                bucketing: None,
+            };
+            let config = ListingTableConfig::new(Arc::clone(&store), "table/")
+                .with_listing_options(opt)
+                .with_schema(Arc::clone(&file_schema));
+            ListingTable::try_new(config)
+        };
+
+        // each sorted file is scanned as its own partition
+        let table = table_with_sort_order(vec![col("b").sort(false, true)])?;
+        let scan = table.scan(&None, &[], None).await?;
+        assert_eq!(scan.output_partitioning().partition_count(), 3);
+        let ordering = scan.output_ordering().expect("scan ordering");
+        assert_eq!(ordering.len(), 1);
+        assert_eq!(ordering[0].to_string(), "b@1 DESC");
+
+        // the ordering is projected, up to the first column that is not
+        let scan = table.scan(&Some(vec![1]), &[], None).await?;
+        assert_eq!(scan.output_ordering().unwrap()[0].to_string(), "b@0 DESC");
+        let scan = table.scan(&Some(vec![0]), &[], None).await?;
+        assert!(scan.output_ordering().is_none());
+
+        let err = table_with_sort_order(vec![(col("a") + lit(1)).sort(true, false)])
+            .err()
+            .expect("expression in sort order");
+        assert!(err
+            .to_string()
+            .contains("Only columns are supported in the file sort order"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_bucketed_table() -> Result<()> {
+        let store = TestObjectStore::new_arc(&[
+            ("table/part-0.avro", 10),
+            ("table/part-2.avro", 10),
+            ("table/part-2-copy.avro", 10),
+        ]);
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]));
+
+        let bucketed_table = |bucketing| {
+            let opt = ListingOptions {
+                file_extension: "".to_owned(),
+                format: Arc::new(AvroFormat {}),
+                table_partition_cols: vec![],
+                target_partitions: 2,
+                collect_stat: false,
+                file_sort_order: None,
+                bucketing: Some(bucketing),
+            };
+            let config = ListingTableConfig::new(Arc::clone(&store), "table/")
+                .with_listing_options(opt)
+                .with_schema(Arc::clone(&file_schema));
+            ListingTable::try_new(config)
+        };
+
+        // each bucket is its own group, even if it holds no file
+        let table = bucketed_table(BucketSpec::new(vec!["b".to_owned()], 3))?;
+        let (file_groups, _, _) = table.list_files_for_scan(&[], None).await?;
+        let group_sizes = file_groups.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(group_sizes, vec![1, 0, 2]);
+
+        // the scan is hash partitioned on the projected bucket columns
+        let scan = table.scan(&Some(vec![1]), &[], None).await?;
+        match scan.output_partitioning() {
+            Partitioning::Hash(exprs, 3) => {
+                assert_eq!(exprs.len(), 1);
+                assert_eq!(exprs[0].to_string(), "b@0");
+            }
+            other => panic!("unexpected partitioning {:?}", other),
+        }
+        let scan = table.scan(&Some(vec![0]), &[], None).await?;
+        assert!(matches!(
+            scan.output_partitioning(),
+            Partitioning::UnknownPartitioning(3)
+        ));
+
+        // files must be named after buckets that exist
+        let table = bucketed_table(BucketSpec::new(vec!["b".to_owned()], 2))?;
+        let err = table.scan(&None, &[], None).await.unwrap_err();
+        assert!(err.to_string().contains("is not named part-{bucket}"));
+
+        // nor is a table bucketed with another version of the hash
+        let table = bucketed_table(
+            BucketSpec::new(vec!["b".to_owned()], 3)
+                .with_hash_version(BUCKET_HASH_VERSION + 1),
+        )?;
+        let scan = table.scan(&Some(vec![1]), &[], None).await?;
+        assert!(matches!(
+            scan.output_partitioning(),
+            Partitioning::UnknownPartitioning(3)
+        ));
+
+        let err = bucketed_table(BucketSpec::new(vec!["c".to_owned()], 2))
+            .err()
+            .expect("unknown bucket column");
+        assert!(err
+            .to_string()
+            .contains("Bucket column c is not a column of the files"));
+        Ok(())
+    }
+
     async fn load_table(name: &str) -> Result<Arc<dyn TableProvider>> {
         let testdata = crate::test_util::parquet_test_data();
         let filename = format!("{}/{}", testdata, name);
@@ -586,6 +1092,8 @@ mod tests {
             table_partition_cols: vec![],
             target_partitions,
             collect_stat: true,
+            file_sort_order: None,
+            bucketing: None,
         };
 
         let schema = Schema::new(vec![Field::new("a", DataType::Boolean, false)]);
@@ -596,7 +1104,7 @@ mod tests {
 
         let table = ListingTable::try_new(config)?;
 
-        let (file_list, _) = table.list_files_for_scan(&[], None).await?;
+        let (file_list, _, _) = table.list_files_for_scan(&[], None).await?;
 
         assert_eq!(file_list.len(), output_partitioning);
 
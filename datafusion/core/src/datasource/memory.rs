@@ -20,9 +20,12 @@
 //! repeatedly queried without incurring additional file I/O overhead.
 
 use futures::StreamExt;
+use hashbrown::HashSet;
+use parking_lot::Mutex;
 use std::any::Any;
 use std::sync::Arc;
 
+use arrow::array::Array;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
@@ -32,14 +35,21 @@ use crate::error::{DataFusionError, Result};
 use crate::execution::context::TaskContext;
 use crate::logical_plan::Expr;
 use crate::physical_plan::common;
+use crate::physical_plan::hash_utils::create_hashes;
 use crate::physical_plan::memory::MemoryExec;
-use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::{repartition::RepartitionExec, Partitioning};
+use crate::physical_plan::{ExecutionPlan, Statistics};
+
+/// The maximum number of rows of a [`MemTable`] whose distinct values are
+/// counted for its statistics, to bound the cost of computing them
+const MAX_DISTINCT_COUNT_ROWS: usize = 1 << 20;
 
 /// In-memory table
 pub struct MemTable {
     schema: SchemaRef,
     batches: Vec<Vec<RecordBatch>>,
+    /// The statistics of the batches, computed on first use
+    statistics: Mutex<Option<Statistics>>,
 }
 
 impl MemTable {
@@ -53,6 +63,7 @@ impl MemTable {
             Ok(Self {
                 schema,
                 batches: partitions,
+                statistics: Mutex::new(None),
             })
         } else {
             Err(DataFusionError::Plan(
@@ -143,6 +154,53 @@ impl TableProvider for MemTable {
             projection.clone(),
         )?))
     }
+
+    fn statistics(&self) -> Option<Statistics> {
+        let mut statistics = self.statistics.lock();
+        let statistics = statistics.get_or_insert_with(|| {
+            let mut statistics = common::compute_record_batch_statistics(
+                &self.batches,
+                &self.schema,
+                None,
+            );
+            // the distinct values of too many rows are not counted
+            let num_rows = statistics.num_rows.unwrap_or(usize::MAX);
+            if num_rows <= MAX_DISTINCT_COUNT_ROWS {
+                if let Some(column_statistics) = &mut statistics.column_statistics {
+                    for (index, column_statistics) in
+                        column_statistics.iter_mut().enumerate()
+                    {
+                        column_statistics.distinct_count =
+                            count_distinct_values(&self.batches, index);
+                    }
+                }
+            }
+            statistics
+        });
+        Some(statistics.clone())
+    }
+}
+
+/// Counts the distinct non null values of the column at `index` of `batches`,
+/// by their hash, or returns `None` if its values can't be hashed
+fn count_distinct_values(batches: &[Vec<RecordBatch>], index: usize) -> Option<usize> {
+    let random_state = ahash::RandomState::with_seeds(0, 0, 0, 0);
+    let mut distinct_hashes = HashSet::new();
+    let mut hashes_buffer = vec![];
+    for batch in batches.iter().flatten() {
+        let array = batch.column(index);
+        hashes_buffer.clear();
+        hashes_buffer.resize(array.len(), 0);
+        create_hashes(&[array.clone()], &random_state, &mut hashes_buffer).ok()?;
+        distinct_hashes.extend(
+            hashes_buffer
+                .iter()
+                .enumerate()
+                .filter(|(row, _)| array.is_valid(*row))
+                .map(|(_, hash)| *hash),
+        );
+    }
+    Some(distinct_hashes.len())
 }
 
 #[cfg(test)]
@@ -191,6 +249,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_distinct_counts() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let batch = |a: Vec<i32>, b: Vec<Option<i32>>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+            )
+        };
+        let partitions = vec![
+            vec![batch(vec![1, 2, 1], vec![None, Some(1), None])?],
+            vec![batch(vec![2, 3, 3], vec![Some(1), Some(1), None])?],
+        ];
+
+        let provider = MemTable::try_new(schema.clone(), partitions)?;
+        let statistics = provider.statistics().unwrap();
+        let column_statistics = statistics.column_statistics.unwrap();
+        // nulls are not distinct values
+        assert_eq!(column_statistics[0].distinct_count, Some(3));
+        assert_eq!(column_statistics[1].distinct_count, Some(1));
+        assert_eq!(column_statistics[1].null_count, Some(3));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_without_projection() -> Result<()> {
         let session_ctx = SessionContext::new();
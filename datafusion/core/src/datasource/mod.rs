@@ -18,9 +18,11 @@
 //! DataFusion data sources
 
 #![allow(clippy::module_inception)]
+pub mod cte_worktable;
 pub mod datasource;
 pub mod empty;
 pub mod file_format;
+pub mod function;
 pub mod listing;
 pub mod memory;
 pub mod object_store_registry;
@@ -29,6 +31,7 @@ pub mod view;
 use futures::Stream;
 
 pub use self::datasource::TableProvider;
+pub use self::function::TableFunction;
 use self::listing::PartitionedFile;
 pub use self::memory::MemTable;
 pub use self::view::ViewTable;
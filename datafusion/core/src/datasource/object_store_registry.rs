@@ -105,9 +105,56 @@ impl ObjectStoreRegistry {
     }
 }
 
+/// The URL of an object store of an [`ObjectStoreRegistry`], such as `s3://`,
+/// identifying the store rather than a file in it, as the same path can be
+/// found in several stores
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectStoreUrl {
+    url: String,
+}
+
+impl ObjectStoreUrl {
+    /// The URL of the object store that [`ObjectStoreRegistry::get_by_uri`]
+    /// returns for `uri`, such as `s3://` for `s3://bucket/key`, which is
+    /// `file://` for the URIs without scheme
+    pub fn from_uri(uri: &str) -> Self {
+        let scheme = match uri.split_once("://") {
+            Some((scheme, _)) => scheme.to_lowercase(),
+            None => LOCAL_SCHEME.to_owned(),
+        };
+        Self {
+            url: format!("{}://", scheme),
+        }
+    }
+
+    /// The URL of the local file system, `file://`
+    pub fn local_filesystem() -> Self {
+        Self {
+            url: format!("{}://", LOCAL_SCHEME),
+        }
+    }
+
+    /// The URL as a string
+    pub fn as_str(&self) -> &str {
+        &self.url
+    }
+}
+
+impl AsRef<str> for ObjectStoreUrl {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for ObjectStoreUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.url)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ObjectStoreRegistry;
+    use super::{ObjectStoreRegistry, ObjectStoreUrl};
     use datafusion_data_access::object_store::local::LocalFileSystem;
     use std::sync::Arc;
 
@@ -135,4 +182,16 @@ mod tests {
         let (_, path) = sut.get_by_uri(uri).unwrap();
         assert_eq!(path, "/bucket/key");
     }
+
+    #[test]
+    fn test_object_store_url() {
+        let url = |uri| ObjectStoreUrl::from_uri(uri).to_string();
+        assert_eq!(url("S3://bucket/key"), "s3://");
+        assert_eq!(url("file:///bucket/key"), "file://");
+        assert_eq!(url("/bucket/key"), "file://");
+        assert_eq!(
+            ObjectStoreUrl::from_uri("/bucket/key"),
+            ObjectStoreUrl::local_filesystem()
+        );
+    }
 }
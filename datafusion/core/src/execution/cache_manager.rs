@@ -0,0 +1,494 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Manages the caches of file metadata and directory listings shared by the
+//! queries of a [`RuntimeEnv`](crate::execution::runtime_env::RuntimeEnv)
+
+use crate::datasource::object_store_registry::ObjectStoreUrl;
+use crate::physical_plan::Statistics;
+use arrow::datatypes::SchemaRef;
+use chrono::{DateTime, Utc};
+use datafusion_data_access::FileMeta;
+use parking_lot::Mutex;
+use parquet::file::metadata::ParquetMetaData;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default number of entries kept by the [`LruFileMetadataCache`] created
+/// by [`CacheManagerConfig::default`]
+pub const DEFAULT_FILE_METADATA_CACHE_LIMIT: usize = 1024;
+
+/// The kind of metadata cached for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileMetadataKind {
+    /// The footer of a Parquet file
+    Parquet,
+    /// The schema inferred from the file
+    Schema,
+    /// The statistics of the file
+    Statistics,
+}
+
+/// Metadata read from a file
+#[derive(Debug, Clone)]
+pub enum FileMetadata {
+    /// The footer of a Parquet file
+    Parquet(Arc<ParquetMetaData>),
+    /// The schema inferred from the file
+    Schema(SchemaRef),
+    /// The statistics of the file, for the columns of `table_schema`
+    Statistics {
+        /// The schema of the table the statistics were computed for
+        table_schema: SchemaRef,
+        /// The statistics of the file
+        statistics: Statistics,
+    },
+}
+
+impl FileMetadata {
+    /// The kind of this metadata
+    pub fn kind(&self) -> FileMetadataKind {
+        match self {
+            FileMetadata::Parquet(_) => FileMetadataKind::Parquet,
+            FileMetadata::Schema(_) => FileMetadataKind::Schema,
+            FileMetadata::Statistics { .. } => FileMetadataKind::Statistics,
+        }
+    }
+}
+
+/// Identifies the metadata of a version of a file: a file that is rewritten
+/// gets a new size or modification time, and so a new key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileMetadataKey {
+    /// URL of the object store of the file, such as `s3://`, as the same
+    /// path can be found in several stores
+    pub object_store_url: ObjectStoreUrl,
+    /// Path of the file in its object store
+    pub path: String,
+    /// Size of the file
+    pub size: u64,
+    /// Last modification time of the file
+    pub last_modified: DateTime<Utc>,
+    /// The kind of metadata
+    pub kind: FileMetadataKind,
+}
+
+impl FileMetadataKey {
+    /// Create the key of the metadata of `kind` for `file` of the object
+    /// store at `object_store_url`, or `None` if the object store does not
+    /// report when the file was last modified, as its metadata can then not
+    /// be safely cached
+    pub fn try_new(
+        object_store_url: &ObjectStoreUrl,
+        file: &FileMeta,
+        kind: FileMetadataKind,
+    ) -> Option<Self> {
+        file.last_modified.map(|last_modified| Self {
+            object_store_url: object_store_url.clone(),
+            path: file.path().to_owned(),
+            size: file.size(),
+            last_modified,
+            kind,
+        })
+    }
+}
+
+/// Cache of the metadata read from files, such as Parquet footers, inferred
+/// schemas and statistics
+pub trait FileMetadataCache: Send + Sync + Debug {
+    /// Get the metadata cached for `key`
+    fn get(&self, key: &FileMetadataKey) -> Option<FileMetadata>;
+
+    /// Cache `metadata` under `key`
+    fn put(&self, key: FileMetadataKey, metadata: FileMetadata);
+
+    /// Remove all the entries of the cache
+    fn clear(&self);
+
+    /// Number of entries in the cache
+    fn len(&self) -> usize;
+
+    /// Returns true if the cache has no entries
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A [`FileMetadataCache`] holding up to a number of entries, evicting the
+/// least recently used ones first
+#[derive(Debug)]
+pub struct LruFileMetadataCache {
+    limit: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    /// The cached metadata with the tick of its last use
+    entries: HashMap<FileMetadataKey, (FileMetadata, u64)>,
+    /// The keys of `entries` by the tick of their last use
+    order: BTreeMap<u64, FileMetadataKey>,
+    tick: u64,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &FileMetadataKey) -> Option<FileMetadata> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (metadata, last_used) = self.entries.get_mut(key)?;
+        self.order.remove(last_used);
+        self.order.insert(tick, key.clone());
+        *last_used = tick;
+        Some(metadata.clone())
+    }
+}
+
+impl LruFileMetadataCache {
+    /// Create a cache holding up to `limit` entries
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// The maximum number of entries of the cache
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl FileMetadataCache for LruFileMetadataCache {
+    fn get(&self, key: &FileMetadataKey) -> Option<FileMetadata> {
+        self.state.lock().touch(key)
+    }
+
+    fn put(&self, key: FileMetadataKey, metadata: FileMetadata) {
+        if self.limit == 0 {
+            return;
+        }
+        let mut state = self.state.lock();
+        state.tick += 1;
+        let tick = state.tick;
+        if let Some((_, last_used)) = state.entries.insert(key.clone(), (metadata, tick))
+        {
+            state.order.remove(&last_used);
+        }
+        state.order.insert(tick, key);
+
+        while state.entries.len() > self.limit {
+            let oldest = *state
+                .order
+                .keys()
+                .next()
+                .expect("the order has an entry per cached key");
+            if let Some(evicted) = state.order.remove(&oldest) {
+                state.entries.remove(&evicted);
+            }
+        }
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock();
+        state.entries.clear();
+        state.order.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+}
+
+/// Cache of the files listed under a path of an object store, identified by
+/// its URL such as `s3://`
+pub trait ListFilesCache: Send + Sync + Debug {
+    /// Get the files with `suffix` listed under `path` of the object store at
+    /// `object_store_url`
+    fn get(
+        &self,
+        object_store_url: &ObjectStoreUrl,
+        path: &str,
+        suffix: &str,
+    ) -> Option<Arc<Vec<FileMeta>>>;
+
+    /// Cache the `files` with `suffix` listed under `path` of the object
+    /// store at `object_store_url`
+    fn put(
+        &self,
+        object_store_url: &ObjectStoreUrl,
+        path: &str,
+        suffix: &str,
+        files: Arc<Vec<FileMeta>>,
+    );
+
+    /// Remove all the entries of the cache
+    fn clear(&self);
+}
+
+/// The files listed under a path of an object store with a suffix, with the
+/// time they were listed
+type ListedFiles =
+    HashMap<(ObjectStoreUrl, String, String), (Instant, Arc<Vec<FileMeta>>)>;
+
+/// A [`ListFilesCache`] whose entries expire after a fixed duration, after
+/// which the files are listed again from the object store
+#[derive(Debug)]
+pub struct TtlListFilesCache {
+    ttl: Duration,
+    entries: Mutex<ListedFiles>,
+}
+
+impl TtlListFilesCache {
+    /// Create a cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The duration after which entries expire
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+impl ListFilesCache for TtlListFilesCache {
+    fn get(
+        &self,
+        object_store_url: &ObjectStoreUrl,
+        path: &str,
+        suffix: &str,
+    ) -> Option<Arc<Vec<FileMeta>>> {
+        let mut entries = self.entries.lock();
+        let key = (object_store_url.clone(), path.to_owned(), suffix.to_owned());
+        match entries.get(&key) {
+            Some((listed_at, files)) if listed_at.elapsed() < self.ttl => {
+                Some(Arc::clone(files))
+            }
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put(
+        &self,
+        object_store_url: &ObjectStoreUrl,
+        path: &str,
+        suffix: &str,
+        files: Arc<Vec<FileMeta>>,
+    ) {
+        self.entries.lock().insert(
+            (object_store_url.clone(), path.to_owned(), suffix.to_owned()),
+            (Instant::now(), files),
+        );
+    }
+
+    fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+/// Configuration of the [CacheManager]
+#[derive(Debug, Clone)]
+pub struct CacheManagerConfig {
+    /// Cache of file metadata, `None` to disable it. Defaults to a
+    /// [`LruFileMetadataCache`] of [`DEFAULT_FILE_METADATA_CACHE_LIMIT`]
+    /// entries.
+    pub file_metadata_cache: Option<Arc<dyn FileMetadataCache>>,
+    /// Cache of directory listings, `None` to disable it. Disabled by
+    /// default, as files added to a table would otherwise only be seen once
+    /// the listing expires.
+    pub list_files_cache: Option<Arc<dyn ListFilesCache>>,
+}
+
+impl Default for CacheManagerConfig {
+    fn default() -> Self {
+        Self {
+            file_metadata_cache: Some(Arc::new(LruFileMetadataCache::new(
+                DEFAULT_FILE_METADATA_CACHE_LIMIT,
+            ))),
+            list_files_cache: None,
+        }
+    }
+}
+
+impl CacheManagerConfig {
+    /// Create the default configuration
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Use the provided cache of file metadata, or none
+    pub fn with_file_metadata_cache(
+        mut self,
+        cache: Option<Arc<dyn FileMetadataCache>>,
+    ) -> Self {
+        self.file_metadata_cache = cache;
+        self
+    }
+
+    /// Cache up to `limit` entries of file metadata in a
+    /// [`LruFileMetadataCache`]
+    pub fn with_file_metadata_cache_limit(self, limit: usize) -> Self {
+        self.with_file_metadata_cache(Some(Arc::new(LruFileMetadataCache::new(limit))))
+    }
+
+    /// Use the provided cache of directory listings, or none
+    pub fn with_list_files_cache(
+        mut self,
+        cache: Option<Arc<dyn ListFilesCache>>,
+    ) -> Self {
+        self.list_files_cache = cache;
+        self
+    }
+
+    /// Cache directory listings for `ttl` in a [`TtlListFilesCache`]
+    pub fn with_list_files_cache_ttl(self, ttl: Duration) -> Self {
+        self.with_list_files_cache(Some(Arc::new(TtlListFilesCache::new(ttl))))
+    }
+}
+
+/// Holds the caches shared by the queries run in a
+/// [`RuntimeEnv`](crate::execution::runtime_env::RuntimeEnv)
+#[derive(Debug)]
+pub struct CacheManager {
+    file_metadata_cache: Option<Arc<dyn FileMetadataCache>>,
+    list_files_cache: Option<Arc<dyn ListFilesCache>>,
+}
+
+impl CacheManager {
+    /// Create a CacheManager given the configuration
+    pub fn new(config: CacheManagerConfig) -> Arc<Self> {
+        Arc::new(Self {
+            file_metadata_cache: config.file_metadata_cache,
+            list_files_cache: config.list_files_cache,
+        })
+    }
+
+    /// The cache of file metadata, if enabled
+    pub fn file_metadata_cache(&self) -> Option<&Arc<dyn FileMetadataCache>> {
+        self.file_metadata_cache.as_ref()
+    }
+
+    /// The cache of directory listings, if enabled
+    pub fn list_files_cache(&self) -> Option<&Arc<dyn ListFilesCache>> {
+        self.list_files_cache.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Schema;
+    use chrono::TimeZone;
+    use datafusion_data_access::SizedFile;
+
+    fn file(path: &str, size: u64, last_modified: Option<i64>) -> FileMeta {
+        FileMeta {
+            sized_file: SizedFile {
+                path: path.to_owned(),
+                size,
+            },
+            last_modified: last_modified
+                .map(|millis| Utc.timestamp_millis_opt(millis).unwrap()),
+        }
+    }
+
+    fn local() -> ObjectStoreUrl {
+        ObjectStoreUrl::local_filesystem()
+    }
+
+    fn s3() -> ObjectStoreUrl {
+        ObjectStoreUrl::from_uri("s3://")
+    }
+
+    fn schema_key(file: &FileMeta) -> FileMetadataKey {
+        FileMetadataKey::try_new(&local(), file, FileMetadataKind::Schema).unwrap()
+    }
+
+    fn schema_metadata() -> FileMetadata {
+        FileMetadata::Schema(Arc::new(Schema::empty()))
+    }
+
+    #[test]
+    fn file_metadata_key() {
+        let key_of = |object_store_url, file, kind| {
+            FileMetadataKey::try_new(&object_store_url, &file, kind)
+        };
+        assert!(key_of(local(), file("a", 10, None), FileMetadataKind::Schema).is_none());
+        let key = schema_key(&file("a", 10, Some(1)));
+        assert_eq!(key, schema_key(&file("a", 10, Some(1))));
+        assert_ne!(key, schema_key(&file("a", 11, Some(1))));
+        assert_ne!(key, schema_key(&file("a", 10, Some(2))));
+        assert_ne!(
+            Some(key.clone()),
+            key_of(local(), file("a", 10, Some(1)), FileMetadataKind::Parquet)
+        );
+        // the same file in another object store
+        assert_ne!(
+            Some(key),
+            key_of(s3(), file("a", 10, Some(1)), FileMetadataKind::Schema)
+        );
+    }
+
+    #[test]
+    fn lru_file_metadata_cache() {
+        let cache = LruFileMetadataCache::new(2);
+        let (a, b, c) = (
+            schema_key(&file("a", 1, Some(1))),
+            schema_key(&file("b", 1, Some(1))),
+            schema_key(&file("c", 1, Some(1))),
+        );
+        assert!(cache.is_empty());
+        cache.put(a.clone(), schema_metadata());
+        cache.put(b.clone(), schema_metadata());
+        // a is now more recently used than b
+        assert!(cache.get(&a).is_some());
+        cache.put(c.clone(), schema_metadata());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert!(cache.get(&a).is_none());
+    }
+
+    #[test]
+    fn ttl_list_files_cache() {
+        let files = Arc::new(vec![file("a", 1, None)]);
+        let cache = TtlListFilesCache::new(Duration::from_secs(3600));
+        cache.put(&local(), "table", ".parquet", Arc::clone(&files));
+        assert_eq!(
+            cache.get(&local(), "table", ".parquet"),
+            Some(files.clone())
+        );
+        assert_eq!(cache.get(&local(), "table", ".csv"), None);
+        assert_eq!(cache.get(&s3(), "table", ".parquet"), None);
+
+        let cache = TtlListFilesCache::new(Duration::ZERO);
+        cache.put(&local(), "table", ".parquet", files);
+        assert_eq!(cache.get(&local(), "table", ".parquet"), None);
+    }
+}
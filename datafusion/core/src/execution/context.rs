@@ -21,9 +21,10 @@ use crate::{
         catalog::{CatalogList, MemoryCatalogList},
         information_schema::CatalogWithInformationSchema,
     },
-    datasource::listing::{ListingOptions, ListingTable},
+    datasource::listing::{BucketSpec, ListingOptions, ListingTable},
     datasource::{
         file_format::{
+            arrow::{ArrowFormat, DEFAULT_ARROW_EXTENSION},
             avro::{AvroFormat, DEFAULT_AVRO_EXTENSION},
             csv::{CsvFormat, DEFAULT_CSV_EXTENSION},
             json::{JsonFormat, DEFAULT_JSON_EXTENSION},
@@ -37,7 +38,8 @@ use crate::{
     optimizer::eliminate_limit::EliminateLimit,
     physical_optimizer::{
         aggregate_statistics::AggregateStatistics,
-        hash_build_probe_order::HashBuildProbeOrder, optimizer::PhysicalOptimizerRule,
+        hash_build_probe_order::HashBuildProbeOrder,
+        join_runtime_filter::JoinRuntimeFilter, optimizer::PhysicalOptimizerRule,
     },
 };
 use log::{debug, trace};
@@ -57,37 +59,46 @@ use crate::catalog::{
     ResolvedTableReference, TableReference,
 };
 use crate::dataframe::DataFrame;
+use crate::datasource::function::builtin_table_functions;
 use crate::datasource::listing::ListingTableConfig;
-use crate::datasource::TableProvider;
+use crate::datasource::object_store_registry::ObjectStoreUrl;
+use crate::datasource::{TableFunction, TableProvider};
 use crate::error::{DataFusionError, Result};
 use crate::logical_plan::{
     provider_as_source, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
-    CreateMemoryTable, CreateView, DropTable, FileType, FunctionRegistry, LogicalPlan,
-    LogicalPlanBuilder, UNNAMED_TABLE,
+    CreateMemoryTable, CreateView, DropTable, Expr, ExprRewritable, FileType,
+    FunctionRegistry, LogicalPlan, LogicalPlanBuilder, UNNAMED_TABLE,
 };
+use crate::optimizer::aggregate_push_down::AggregatePushDown;
 use crate::optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
 use crate::optimizer::filter_push_down::FilterPushDown;
 use crate::optimizer::limit_push_down::LimitPushDown;
+use crate::optimizer::multiple_distinct_to_groupby::MultipleDistinctToGroupBy;
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::projection_push_down::ProjectionPushDown;
-use crate::optimizer::simplify_expressions::SimplifyExpressions;
+use crate::optimizer::simplify_expressions::{ConstEvaluator, SimplifyExpressions};
 use crate::optimizer::single_distinct_to_groupby::SingleDistinctToGroupBy;
 use crate::optimizer::subquery_filter_to_join::SubqueryFilterToJoin;
 
 use crate::physical_optimizer::coalesce_batches::CoalesceBatches;
+use crate::physical_optimizer::eliminate_sorts::EliminateSorts;
 use crate::physical_optimizer::merge_exec::AddCoalescePartitionsExec;
 use crate::physical_optimizer::repartition::Repartition;
 
 use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use crate::logical_plan::plan::Explain;
-use crate::physical_plan::file_format::{plan_to_csv, plan_to_json, plan_to_parquet};
+use crate::physical_plan::file_format::{
+    plan_to_arrow, plan_to_csv, plan_to_json, plan_to_parquet,
+};
 use crate::physical_plan::planner::DefaultPhysicalPlanner;
 use crate::physical_plan::udaf::AggregateUDF;
 use crate::physical_plan::udf::ScalarUDF;
+use crate::physical_plan::udwf::WindowUDF;
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::PhysicalPlanner;
+use crate::scalar::ScalarValue;
 use crate::sql::{
-    parser::DFParser,
+    parser::{DFParser, Statement as DFStatement},
     planner::{ContextProvider, SqlToRel},
 };
 use crate::variable::{VarProvider, VarType};
@@ -97,7 +108,8 @@ use parquet::file::properties::WriterProperties;
 use uuid::Uuid;
 
 use super::options::{
-    AvroReadOptions, CsvReadOptions, NdJsonReadOptions, ParquetReadOptions,
+    ArrowReadOptions, AvroReadOptions, CsvReadOptions, NdJsonReadOptions,
+    ParquetReadOptions,
 };
 
 /// The default catalog name - this impacts what SQL queries use if not specified
@@ -211,9 +223,17 @@ impl SessionContext {
     /// Creates a dataframe that will execute a SQL query.
     ///
     /// This method is `async` because queries of type `CREATE EXTERNAL TABLE`
-    /// might require the schema to be inferred.
+    /// might require the schema to be inferred, as might the tables produced
+    /// by the table functions the query calls, such as `read_parquet`.
     pub async fn sql(&self, sql: &str) -> Result<Arc<DataFrame>> {
-        let plan = self.create_logical_plan(sql)?;
+        let statement = Self::parse_statement(sql)?;
+        let state = self.state.read().clone();
+        let table_function_sources = state.table_function_sources(&statement).await?;
+        let provider = SessionContextProvider {
+            state: &state,
+            table_function_sources,
+        };
+        let plan = SqlToRel::new(&provider).statement_to_plan(statement)?;
         match plan {
             LogicalPlan::CreateExternalTable(CreateExternalTable {
                 ref schema,
@@ -223,6 +243,8 @@ impl SessionContext {
                 ref has_header,
                 ref delimiter,
                 ref table_partition_cols,
+                ref order_exprs,
+                ref bucketing,
                 ref if_not_exists,
             }) => {
                 let (file_format, file_extension) = match file_type {
@@ -246,6 +268,10 @@ impl SessionContext {
                         Arc::new(JsonFormat::default()) as Arc<dyn FileFormat>,
                         DEFAULT_JSON_EXTENSION,
                     ),
+                    FileType::Arrow => (
+                        Arc::new(ArrowFormat) as Arc<dyn FileFormat>,
+                        DEFAULT_ARROW_EXTENSION,
+                    ),
                 };
                 let table = self.table(name.as_str());
                 match (if_not_exists, table) {
@@ -266,6 +292,13 @@ impl SessionContext {
                             file_extension: file_extension.to_owned(),
                             target_partitions: self.copied_config().target_partitions,
                             table_partition_cols: table_partition_cols.clone(),
+                            file_sort_order: (!order_exprs.is_empty())
+                                .then(|| order_exprs.clone()),
+                            bucketing: bucketing.as_ref().map(
+                                |(columns, num_buckets)| {
+                                    BucketSpec::new(columns.clone(), *num_buckets)
+                                },
+                            ),
                         };
                         self.register_listing_table(
                             name,
@@ -440,7 +473,18 @@ impl SessionContext {
     /// Creates a logical plan.
     ///
     /// This function is intended for internal use and should not be called directly.
+    /// It only supports the table functions that create their tables without I/O,
+    /// such as `generate_series`, the others being supported by [`Self::sql`].
     pub fn create_logical_plan(&self, sql: &str) -> Result<LogicalPlan> {
+        let statement = Self::parse_statement(sql)?;
+
+        // create a query planner
+        let state = self.state.read().clone();
+        let query_planner = SqlToRel::new(&state);
+        query_planner.statement_to_plan(statement)
+    }
+
+    fn parse_statement(sql: &str) -> Result<DFStatement> {
         let mut statements = DFParser::parse_sql(sql)?;
 
         if statements.len() != 1 {
@@ -448,11 +492,7 @@ impl SessionContext {
                 "The context currently only supports a single SQL statement".to_string(),
             ));
         }
-
-        // create a query planner
-        let state = self.state.read().clone();
-        let query_planner = SqlToRel::new(&state);
-        query_planner.statement_to_plan(statements.pop_front().unwrap())
+        Ok(statements.pop_front().unwrap())
     }
 
     /// Registers a variable provider within this context.
@@ -495,6 +535,35 @@ impl SessionContext {
             .insert(f.name.clone(), Arc::new(f));
     }
 
+    /// Registers a window UDF within this context.
+    ///
+    /// Note in SQL queries, window function names are looked up using
+    /// lowercase unless the query uses quotes. For example,
+    ///
+    /// `SELECT MY_UDWF(x) OVER ()...` will look for a window function named `"my_udwf"`
+    /// `SELECT "my_UDWF"(x) OVER ()` will look for a window function named `"my_UDWF"`
+    pub fn register_udwf(&mut self, f: WindowUDF) {
+        self.state
+            .write()
+            .window_functions
+            .insert(f.name.clone(), Arc::new(f));
+    }
+
+    /// Registers a table function within this context, replacing any
+    /// existing function with the same name, such as a built-in one.
+    ///
+    /// Note in SQL queries, table function names are looked up using
+    /// lowercase unless the query uses quotes. For example,
+    ///
+    /// `SELECT * FROM MY_UDTF(1)` will look for a function named `"my_udtf"`
+    /// `SELECT * FROM "my_UDTF"(1)` will look for a function named `"my_UDTF"`
+    pub fn register_udtf(&mut self, name: &str, f: Arc<dyn TableFunction>) {
+        self.state
+            .write()
+            .table_functions
+            .insert(name.to_string(), f);
+    }
+
     /// Creates a DataFrame for reading an Avro data source.
     pub async fn read_avro(
         &self,
@@ -503,6 +572,8 @@ impl SessionContext {
     ) -> Result<Arc<DataFrame>> {
         let uri: String = uri.into();
         let (object_store, path) = self.runtime_env().object_store(&uri)?;
+        let object_store_url = ObjectStoreUrl::from_uri(&uri);
+        let cache_manager = Arc::clone(&self.runtime_env().cache_manager);
         let target_partitions = self.copied_config().target_partitions;
 
         let listing_options = options.to_listing_options(target_partitions);
@@ -513,11 +584,16 @@ impl SessionContext {
             Some(s) => s,
             None => {
                 listing_options
-                    .infer_schema(Arc::clone(&object_store), &path)
+                    .infer_schema_with_cache(
+                        Arc::clone(&object_store),
+                        &path,
+                        Some((&object_store_url, cache_manager.as_ref())),
+                    )
                     .await?
             }
         };
         let config = ListingTableConfig::new(object_store, path.clone())
+            .with_cache_manager(object_store_url, cache_manager)
             .with_listing_options(listing_options)
             .with_schema(resolved_schema);
         let provider = ListingTable::try_new(config)?;
@@ -532,6 +608,45 @@ impl SessionContext {
     ) -> Result<Arc<DataFrame>> {
         let uri: String = uri.into();
         let (object_store, path) = self.runtime_env().object_store(&uri)?;
+        let object_store_url = ObjectStoreUrl::from_uri(&uri);
+        let cache_manager = Arc::clone(&self.runtime_env().cache_manager);
+        let target_partitions = self.copied_config().target_partitions;
+
+        let listing_options = options.to_listing_options(target_partitions);
+
+        let path: String = path.into();
+
+        let resolved_schema = match options.schema {
+            Some(s) => s,
+            None => {
+                listing_options
+                    .infer_schema_with_cache(
+                        Arc::clone(&object_store),
+                        &path,
+                        Some((&object_store_url, cache_manager.as_ref())),
+                    )
+                    .await?
+            }
+        };
+        let config = ListingTableConfig::new(object_store, path)
+            .with_cache_manager(object_store_url, cache_manager)
+            .with_listing_options(listing_options)
+            .with_schema(resolved_schema);
+        let provider = ListingTable::try_new(config)?;
+
+        self.read_table(Arc::new(provider))
+    }
+
+    /// Creates a DataFrame for reading an Arrow IPC data source.
+    pub async fn read_arrow(
+        &self,
+        uri: impl Into<String>,
+        options: ArrowReadOptions<'_>,
+    ) -> Result<Arc<DataFrame>> {
+        let uri: String = uri.into();
+        let (object_store, path) = self.runtime_env().object_store(&uri)?;
+        let object_store_url = ObjectStoreUrl::from_uri(&uri);
+        let cache_manager = Arc::clone(&self.runtime_env().cache_manager);
         let target_partitions = self.copied_config().target_partitions;
 
         let listing_options = options.to_listing_options(target_partitions);
@@ -542,11 +657,16 @@ impl SessionContext {
             Some(s) => s,
             None => {
                 listing_options
-                    .infer_schema(Arc::clone(&object_store), &path)
+                    .infer_schema_with_cache(
+                        Arc::clone(&object_store),
+                        &path,
+                        Some((&object_store_url, cache_manager.as_ref())),
+                    )
                     .await?
             }
         };
         let config = ListingTableConfig::new(object_store, path)
+            .with_cache_manager(object_store_url, cache_manager)
             .with_listing_options(listing_options)
             .with_schema(resolved_schema);
         let provider = ListingTable::try_new(config)?;
@@ -570,6 +690,8 @@ impl SessionContext {
     ) -> Result<Arc<DataFrame>> {
         let uri: String = uri.into();
         let (object_store, path) = self.runtime_env().object_store(&uri)?;
+        let object_store_url = ObjectStoreUrl::from_uri(&uri);
+        let cache_manager = Arc::clone(&self.runtime_env().cache_manager);
         let target_partitions = self.copied_config().target_partitions;
         let path = path.to_string();
         let listing_options = options.to_listing_options(target_partitions);
@@ -577,11 +699,16 @@ impl SessionContext {
             Some(s) => Arc::new(s.to_owned()),
             None => {
                 listing_options
-                    .infer_schema(Arc::clone(&object_store), &path)
+                    .infer_schema_with_cache(
+                        Arc::clone(&object_store),
+                        &path,
+                        Some((&object_store_url, cache_manager.as_ref())),
+                    )
                     .await?
             }
         };
         let config = ListingTableConfig::new(object_store, path.clone())
+            .with_cache_manager(object_store_url, cache_manager)
             .with_listing_options(listing_options)
             .with_schema(resolved_schema);
         let provider = ListingTable::try_new(config)?;
@@ -600,6 +727,8 @@ impl SessionContext {
     ) -> Result<Arc<DataFrame>> {
         let uri: String = uri.into();
         let (object_store, path) = self.runtime_env().object_store(&uri)?;
+        let object_store_url = ObjectStoreUrl::from_uri(&uri);
+        let cache_manager = Arc::clone(&self.runtime_env().cache_manager);
         let target_partitions = self.copied_config().target_partitions;
 
         let listing_options = options.to_listing_options(target_partitions);
@@ -607,10 +736,15 @@ impl SessionContext {
 
         // with parquet we resolve the schema in all cases
         let resolved_schema = listing_options
-            .infer_schema(Arc::clone(&object_store), &path)
+            .infer_schema_with_cache(
+                Arc::clone(&object_store),
+                &path,
+                Some((&object_store_url, cache_manager.as_ref())),
+            )
             .await?;
 
         let config = ListingTableConfig::new(object_store, path)
+            .with_cache_manager(object_store_url, cache_manager)
             .with_listing_options(listing_options)
             .with_schema(resolved_schema);
 
@@ -629,7 +763,9 @@ impl SessionContext {
 
     /// Registers a table that uses the listing feature of the object store to
     /// find the files to be processed
-    /// This is async because it might need to resolve the schema.
+    /// This is async because it might need to resolve the schema, and to collect
+    /// the statistics of the files if `options.collect_stat` is set, which lets
+    /// the optimizer use them when planning queries on the table.
     pub async fn register_listing_table<'a>(
         &'a self,
         name: &'a str,
@@ -638,18 +774,27 @@ impl SessionContext {
         provided_schema: Option<SchemaRef>,
     ) -> Result<()> {
         let (object_store, path) = self.runtime_env().object_store(uri)?;
+        let object_store_url = ObjectStoreUrl::from_uri(uri);
+        let cache_manager = Arc::clone(&self.runtime_env().cache_manager);
         let resolved_schema = match provided_schema {
             None => {
                 options
-                    .infer_schema(Arc::clone(&object_store), path)
+                    .infer_schema_with_cache(
+                        Arc::clone(&object_store),
+                        path,
+                        Some((&object_store_url, cache_manager.as_ref())),
+                    )
                     .await?
             }
             Some(s) => s,
         };
         let config = ListingTableConfig::new(object_store, path)
+            .with_cache_manager(object_store_url, cache_manager)
             .with_listing_options(options)
             .with_schema(resolved_schema);
-        let table = ListingTable::try_new(config)?;
+        let table = ListingTable::try_new(config)?
+            .with_collected_statistics()
+            .await?;
         self.register_table(name, Arc::new(table))?;
         Ok(())
     }
@@ -729,6 +874,22 @@ impl SessionContext {
         Ok(())
     }
 
+    /// Registers an Arrow IPC data source so that it can be referenced from SQL
+    /// statements executed against this context.
+    pub async fn register_arrow(
+        &self,
+        name: &str,
+        uri: &str,
+        options: ArrowReadOptions<'_>,
+    ) -> Result<()> {
+        let listing_options =
+            options.to_listing_options(self.copied_config().target_partitions);
+
+        self.register_listing_table(name, uri, listing_options, options.schema)
+            .await?;
+        Ok(())
+    }
+
     /// Registers a named catalog using a custom `CatalogProvider` so that
     /// it can be referenced from SQL statements executed against this
     /// context.
@@ -902,6 +1063,16 @@ impl SessionContext {
         plan_to_json(&state, plan, path).await
     }
 
+    /// Executes a query and writes the results to a partitioned Arrow IPC file.
+    pub async fn write_arrow(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        path: impl AsRef<str>,
+    ) -> Result<()> {
+        let state = self.state.read().clone();
+        plan_to_arrow(&state, plan, path).await
+    }
+
     /// Executes a query and writes the results to a partitioned Parquet file.
     pub async fn write_parquet(
         &self,
@@ -931,6 +1102,10 @@ impl FunctionRegistry for SessionContext {
     fn udaf(&self, name: &str) -> Result<Arc<AggregateUDF>> {
         self.state.read().udaf(name)
     }
+
+    fn udwf(&self, name: &str) -> Result<Arc<WindowUDF>> {
+        self.state.read().udwf(name)
+    }
 }
 
 /// A planner used to add extensions to DataFusion logical and physical plans.
@@ -972,8 +1147,16 @@ pub const REPARTITION_JOINS: &str = "repartition_joins";
 pub const REPARTITION_AGGREGATIONS: &str = "repartition_aggregations";
 /// Session Configuration entry name for 'REPARTITION_WINDOWS'
 pub const REPARTITION_WINDOWS: &str = "repartition_windows";
+/// Session Configuration entry name for 'REPARTITION_SORTS'
+pub const REPARTITION_SORTS: &str = "repartition_sorts";
 /// Session Configuration entry name for 'PARQUET_PRUNING'
 pub const PARQUET_PRUNING: &str = "parquet_pruning";
+/// Session Configuration entry name for 'RUNTIME_JOIN_FILTERS'
+pub const RUNTIME_JOIN_FILTERS: &str = "runtime_join_filters";
+/// Session Configuration entry name for 'MAX_RECURSION_ITERATIONS'
+pub const MAX_RECURSION_ITERATIONS: &str = "max_recursion_iterations";
+/// Session Configuration entry name for 'TIME_ZONE'
+pub const TIME_ZONE: &str = "time_zone";
 
 /// Configuration options for session context
 #[derive(Clone)]
@@ -1002,8 +1185,22 @@ pub struct SessionConfig {
     /// Should DataFusion repartition data using the partition keys to execute window functions in
     /// parallel using the provided `target_partitions` level
     pub repartition_windows: bool,
+    /// Should DataFusion repartition data by ranges of the sort keys to execute global sorts in
+    /// parallel using the provided `target_partitions` level. The ranges are computed once all
+    /// of the input is buffered in memory, so this is disabled by default
+    pub repartition_sorts: bool,
     /// Should DataFusion parquet reader using the predicate to prune data
     pub parquet_pruning: bool,
+    /// Should DataFusion hash joins publish the keys of their build side to
+    /// filter the probe side at runtime
+    pub runtime_join_filters: bool,
+    /// Maximum number of iterations of the recursive term of a recursive
+    /// query, after which the query fails rather than running forever
+    pub max_recursion_iterations: usize,
+    /// Time zone of the session, of the `TIMESTAMP WITH TIME ZONE` values of SQL
+    /// statements, which their literals without an offset from UTC are parsed in
+    /// and which they are formatted in
+    pub time_zone: String,
 }
 
 impl Default for SessionConfig {
@@ -1018,7 +1215,11 @@ impl Default for SessionConfig {
             repartition_joins: true,
             repartition_aggregations: true,
             repartition_windows: true,
+            repartition_sorts: false,
             parquet_pruning: true,
+            runtime_join_filters: true,
+            max_recursion_iterations: 1000,
+            time_zone: "UTC".to_owned(),
         }
     }
 }
@@ -1086,12 +1287,39 @@ impl SessionConfig {
         self
     }
 
+    /// Enables or disables the use of repartitioning for global sorts to improve parallelism.
+    /// All of the input of the sort is buffered in memory to compute the ranges
+    pub fn with_repartition_sorts(mut self, enabled: bool) -> Self {
+        self.repartition_sorts = enabled;
+        self
+    }
+
     /// Enables or disables the use of pruning predicate for parquet readers to skip row groups
     pub fn with_parquet_pruning(mut self, enabled: bool) -> Self {
         self.parquet_pruning = enabled;
         self
     }
 
+    /// Enables or disables the use of the build side keys of hash joins to
+    /// filter their probe side at runtime
+    pub fn with_runtime_join_filters(mut self, enabled: bool) -> Self {
+        self.runtime_join_filters = enabled;
+        self
+    }
+
+    /// Customize the maximum number of iterations of recursive queries
+    pub fn with_max_recursion_iterations(mut self, n: usize) -> Self {
+        self.max_recursion_iterations = n;
+        self
+    }
+
+    /// Customize the time zone of the session, `UTC`, an offset such as `+05:30` or
+    /// the name of a zone of the time zone database such as `America/New_York`
+    pub fn with_time_zone(mut self, time_zone: impl Into<String>) -> Self {
+        self.time_zone = time_zone.into();
+        self
+    }
+
     /// Convert configuration to name-value pairs
     pub fn to_props(&self) -> HashMap<String, String> {
         let mut map = HashMap::new();
@@ -1112,10 +1340,23 @@ impl SessionConfig {
             REPARTITION_WINDOWS.to_owned(),
             format!("{}", self.repartition_windows),
         );
+        map.insert(
+            REPARTITION_SORTS.to_owned(),
+            format!("{}", self.repartition_sorts),
+        );
         map.insert(
             PARQUET_PRUNING.to_owned(),
             format!("{}", self.parquet_pruning),
         );
+        map.insert(
+            RUNTIME_JOIN_FILTERS.to_owned(),
+            format!("{}", self.runtime_join_filters),
+        );
+        map.insert(
+            MAX_RECURSION_ITERATIONS.to_owned(),
+            format!("{}", self.max_recursion_iterations),
+        );
+        map.insert(TIME_ZONE.to_owned(), self.time_zone.clone());
         map
     }
 }
@@ -1199,6 +1440,10 @@ pub struct SessionState {
     pub scalar_functions: HashMap<String, Arc<ScalarUDF>>,
     /// Aggregate functions registered in the context
     pub aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
+    /// Window functions registered in the context
+    pub window_functions: HashMap<String, Arc<WindowUDF>>,
+    /// Table functions registered in the context, including the built-in ones
+    pub table_functions: HashMap<String, Arc<dyn TableFunction>>,
     /// Session configuration
     pub config: SessionConfig,
     /// Execution properties
@@ -1264,20 +1509,26 @@ impl SessionState {
                 Arc::new(EliminateLimit::new()),
                 Arc::new(ProjectionPushDown::new()),
                 Arc::new(FilterPushDown::new()),
+                Arc::new(AggregatePushDown::new()),
                 Arc::new(LimitPushDown::new()),
                 Arc::new(SingleDistinctToGroupBy::new()),
+                Arc::new(MultipleDistinctToGroupBy::new()),
             ],
             physical_optimizers: vec![
                 Arc::new(AggregateStatistics::new()),
                 Arc::new(HashBuildProbeOrder::new()),
+                Arc::new(EliminateSorts::new()),
                 Arc::new(CoalesceBatches::new()),
                 Arc::new(Repartition::new()),
                 Arc::new(AddCoalescePartitionsExec::new()),
+                Arc::new(JoinRuntimeFilter::new()),
             ],
             query_planner: Arc::new(DefaultQueryPlanner {}),
             catalog_list,
             scalar_functions: HashMap::new(),
             aggregate_functions: HashMap::new(),
+            window_functions: HashMap::new(),
+            table_functions: builtin_table_functions(),
             config,
             execution_props: ExecutionProps::new(),
             runtime_env: runtime,
@@ -1422,6 +1673,111 @@ impl SessionState {
     }
 }
 
+/// The tables produced by the table function calls of a statement, by the
+/// name of the function and the values of its arguments
+type TableFunctionSources = HashMap<(String, Vec<ScalarValue>), Arc<dyn TableProvider>>;
+
+impl SessionState {
+    /// Creates the tables produced by the table function calls of `statement`,
+    /// which may require I/O, such as inferring the schema of files
+    async fn table_function_sources(
+        &self,
+        statement: &DFStatement,
+    ) -> Result<TableFunctionSources> {
+        let calls = match statement {
+            DFStatement::Statement(statement) => {
+                SqlToRel::new(self).table_function_calls(statement)?
+            }
+            DFStatement::CreateExternalTable(_) => vec![],
+        };
+        let mut sources = HashMap::new();
+        for (name, args) in calls {
+            let args = self.table_function_args(&name, args)?;
+            if sources.contains_key(&(name.clone(), args.clone())) {
+                continue;
+            }
+            let fun = self.table_functions.get(&name).ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "There is no table function named '{}'",
+                    name
+                ))
+            })?;
+            let source = fun.call(self, &args).await?;
+            sources.insert((name, args), source);
+        }
+        Ok(sources)
+    }
+
+    /// Evaluates the arguments of a call of table function `name`, which must
+    /// be constants
+    fn table_function_args(
+        &self,
+        name: &str,
+        args: Vec<Expr>,
+    ) -> Result<Vec<ScalarValue>> {
+        let mut const_evaluator = ConstEvaluator::new(&self.execution_props);
+        args.into_iter()
+            .map(|arg| match arg.rewrite(&mut const_evaluator)? {
+                Expr::Literal(value) => Ok(value),
+                other => Err(DataFusionError::Plan(format!(
+                    "Arguments of table function '{}' must be constants, got {:?}",
+                    name, other
+                ))),
+            })
+            .collect()
+    }
+}
+
+/// The [`ContextProvider`] planning a statement with [`SessionContext::sql`],
+/// which provides the tables of its table function calls created beforehand
+struct SessionContextProvider<'a> {
+    state: &'a SessionState,
+    table_function_sources: TableFunctionSources,
+}
+
+impl<'a> ContextProvider for SessionContextProvider<'a> {
+    fn get_table_provider(&self, name: TableReference) -> Result<Arc<dyn TableProvider>> {
+        self.state.get_table_provider(name)
+    }
+
+    fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        self.state.get_function_meta(name)
+    }
+
+    fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        self.state.get_aggregate_meta(name)
+    }
+
+    fn get_window_meta(&self, name: &str) -> Option<Arc<WindowUDF>> {
+        self.state.get_window_meta(name)
+    }
+
+    fn get_time_zone(&self) -> String {
+        self.state.get_time_zone()
+    }
+
+    fn get_table_function_source(
+        &self,
+        name: &str,
+        args: Vec<Expr>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let args = self.state.table_function_args(name, args)?;
+        self.table_function_sources
+            .get(&(name.to_owned(), args))
+            .cloned()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "The table of the call of table function '{}' was not created",
+                    name
+                ))
+            })
+    }
+
+    fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
+        self.state.get_variable_type(variable_names)
+    }
+}
+
 impl ContextProvider for SessionState {
     fn get_table_provider(&self, name: TableReference) -> Result<Arc<dyn TableProvider>> {
         let resolved_ref = self.resolve_table_ref(name);
@@ -1444,6 +1800,32 @@ impl ContextProvider for SessionState {
         self.aggregate_functions.get(name).cloned()
     }
 
+    fn get_window_meta(&self, name: &str) -> Option<Arc<WindowUDF>> {
+        self.window_functions.get(name).cloned()
+    }
+
+    fn get_time_zone(&self) -> String {
+        self.config.time_zone.clone()
+    }
+
+    fn get_table_function_source(
+        &self,
+        name: &str,
+        args: Vec<Expr>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let fun = self.table_functions.get(name).ok_or_else(|| {
+            DataFusionError::Plan(format!("There is no table function named '{}'", name))
+        })?;
+        let args = self.table_function_args(name, args)?;
+        fun.call_without_io(self, &args).unwrap_or_else(|| {
+            Err(DataFusionError::NotImplemented(format!(
+                "Table function '{}' does I/O to create its table, so it can only be \
+                 called in queries run with SessionContext::sql",
+                name
+            )))
+        })
+    }
+
     fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
         if variable_names.is_empty() {
             return None;
@@ -1488,6 +1870,17 @@ impl FunctionRegistry for SessionState {
             ))
         })
     }
+
+    fn udwf(&self, name: &str) -> Result<Arc<WindowUDF>> {
+        let result = self.window_functions.get(name);
+
+        result.cloned().ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "There is no UDWF named \"{}\" in the registry",
+                name
+            ))
+        })
+    }
 }
 
 /// Task Context Properties
@@ -1510,6 +1903,8 @@ pub struct TaskContext {
     scalar_functions: HashMap<String, Arc<ScalarUDF>>,
     /// Aggregate functions associated with this task context
     aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
+    /// Window functions associated with this task context
+    window_functions: HashMap<String, Arc<WindowUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
 }
@@ -1522,6 +1917,7 @@ impl TaskContext {
         task_props: HashMap<String, String>,
         scalar_functions: HashMap<String, Arc<ScalarUDF>>,
         aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
+        window_functions: HashMap<String, Arc<WindowUDF>>,
         runtime: Arc<RuntimeEnv>,
     ) -> Self {
         Self {
@@ -1530,6 +1926,7 @@ impl TaskContext {
             properties: TaskProperties::KVPairs(task_props),
             scalar_functions,
             aggregate_functions,
+            window_functions,
             runtime,
         }
     }
@@ -1543,6 +1940,9 @@ impl TaskContext {
                 if props.is_empty() {
                     session_config
                 } else {
+                    // the properties of tasks created before a setting existed
+                    // fall back to its default
+                    let defaults = SessionConfig::new();
                     session_config
                         .with_batch_size(props.get(BATCH_SIZE).unwrap().parse().unwrap())
                         .with_target_partitions(
@@ -1561,9 +1961,30 @@ impl TaskContext {
                         .with_repartition_windows(
                             props.get(REPARTITION_WINDOWS).unwrap().parse().unwrap(),
                         )
+                        .with_repartition_sorts(
+                            props
+                                .get(REPARTITION_SORTS)
+                                .map(|v| v.parse().unwrap())
+                                .unwrap_or(defaults.repartition_sorts),
+                        )
                         .with_parquet_pruning(
                             props.get(PARQUET_PRUNING).unwrap().parse().unwrap(),
                         )
+                        .with_runtime_join_filters(
+                            props
+                                .get(RUNTIME_JOIN_FILTERS)
+                                .map(|v| v.parse().unwrap())
+                                .unwrap_or(defaults.runtime_join_filters),
+                        )
+                        .with_max_recursion_iterations(
+                            props
+                                .get(MAX_RECURSION_ITERATIONS)
+                                .map(|v| v.parse().unwrap())
+                                .unwrap_or(defaults.max_recursion_iterations),
+                        )
+                        .with_time_zone(
+                            props.get(TIME_ZONE).cloned().unwrap_or(defaults.time_zone),
+                        )
                 }
             }
             TaskProperties::SessionConfig(session_config) => session_config.clone(),
@@ -1590,12 +2011,13 @@ impl TaskContext {
 impl From<&SessionContext> for TaskContext {
     fn from(session: &SessionContext) -> Self {
         let session_id = session.session_id.clone();
-        let (config, scalar_functions, aggregate_functions) = {
+        let (config, scalar_functions, aggregate_functions, window_functions) = {
             let session_state = session.state.read();
             (
                 session_state.config.clone(),
                 session_state.scalar_functions.clone(),
                 session_state.aggregate_functions.clone(),
+                session_state.window_functions.clone(),
             )
         };
         let runtime = session.runtime_env();
@@ -1605,6 +2027,7 @@ impl From<&SessionContext> for TaskContext {
             properties: TaskProperties::SessionConfig(config),
             scalar_functions,
             aggregate_functions,
+            window_functions,
             runtime,
         }
     }
@@ -1617,6 +2040,7 @@ impl From<&SessionState> for TaskContext {
         let config = state.config.clone();
         let scalar_functions = state.scalar_functions.clone();
         let aggregate_functions = state.aggregate_functions.clone();
+        let window_functions = state.window_functions.clone();
         let runtime = state.runtime_env.clone();
         Self {
             task_id: None,
@@ -1624,6 +2048,7 @@ impl From<&SessionState> for TaskContext {
             properties: TaskProperties::SessionConfig(config),
             scalar_functions,
             aggregate_functions,
+            window_functions,
             runtime,
         }
     }
@@ -1655,6 +2080,17 @@ impl FunctionRegistry for TaskContext {
             ))
         })
     }
+
+    fn udwf(&self, name: &str) -> Result<Arc<WindowUDF>> {
+        let result = self.window_functions.get(name);
+
+        result.cloned().ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "There is no UDWF named \"{}\" in the TaskContext",
+                name
+            ))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -2158,6 +2594,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn task_context_properties_without_newer_settings() {
+        let config = SessionConfig::new()
+            .with_batch_size(100)
+            .with_runtime_join_filters(false)
+            .with_time_zone("+08:00");
+        let mut props = config.to_props();
+        for key in [
+            REPARTITION_SORTS,
+            RUNTIME_JOIN_FILTERS,
+            MAX_RECURSION_ITERATIONS,
+            TIME_ZONE,
+        ] {
+            props.remove(key);
+        }
+        let task_context = TaskContext::new(
+            "task".to_owned(),
+            "session".to_owned(),
+            props,
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::new(),
+            Arc::new(RuntimeEnv::default()),
+        );
+
+        let config = task_context.session_config();
+        let defaults = SessionConfig::new();
+        assert_eq!(config.batch_size, 100);
+        assert_eq!(config.repartition_sorts, defaults.repartition_sorts);
+        assert_eq!(config.runtime_join_filters, defaults.runtime_join_filters);
+        assert_eq!(
+            config.max_recursion_iterations,
+            defaults.max_recursion_iterations
+        );
+        assert_eq!(config.time_zone, "UTC");
+    }
+
     struct MyPhysicalPlanner {}
 
     #[async_trait]
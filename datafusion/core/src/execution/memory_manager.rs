@@ -226,6 +226,89 @@ impl Display for dyn MemoryConsumer {
     }
 }
 
+/// A [`MemoryConsumer`] requesting the memory of data an operator buffers and
+/// can not spill, so that growing it fails with
+/// [`DataFusionError::ResourcesExhausted`] when the [`MemoryManager`] can not
+/// grant the memory. Its memory is returned when it is dropped.
+#[derive(Debug)]
+pub struct MemoryReservation {
+    name: String,
+    id: MemoryConsumerId,
+    memory_manager: Arc<MemoryManager>,
+    used: AtomicUsize,
+}
+
+impl MemoryReservation {
+    /// Create and register a reservation named `name` for `partition`
+    pub fn new(
+        name: impl Into<String>,
+        partition: usize,
+        memory_manager: Arc<MemoryManager>,
+    ) -> Self {
+        let id = MemoryConsumerId::new(partition);
+        memory_manager.register_requester(&id);
+        Self {
+            name: name.into(),
+            id,
+            memory_manager,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Grow the reservation by `size`, failing if the memory manager does
+    /// not grant it
+    pub async fn grow(&self, size: usize) -> Result<()> {
+        self.try_grow(size).await?;
+        self.used.fetch_add(size, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Shrink the reservation by `size`, returning it to the memory manager
+    pub fn shrink(&self, size: usize) {
+        let used = self.used.fetch_sub(size, Ordering::SeqCst);
+        assert!(used >= size, "cannot shrink {} by {}", self.name, size);
+        self.memory_manager.record_free(size);
+    }
+}
+
+#[async_trait]
+impl MemoryConsumer for MemoryReservation {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> &MemoryConsumerId {
+        &self.id
+    }
+
+    fn memory_manager(&self) -> Arc<MemoryManager> {
+        self.memory_manager.clone()
+    }
+
+    fn type_(&self) -> &ConsumerType {
+        &ConsumerType::Requesting
+    }
+
+    async fn spill(&self) -> Result<usize> {
+        Err(DataFusionError::ResourcesExhausted(format!(
+            "{}[{}] can not buffer more than {} in memory",
+            self.name,
+            self.id,
+            human_readable_size(self.mem_used())
+        )))
+    }
+
+    fn mem_used(&self) -> usize {
+        self.used.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.memory_manager.drop_consumer(&self.id, self.mem_used());
+    }
+}
+
 /*
 The memory management architecture is the following:
 
@@ -612,6 +695,31 @@ mod tests {
         assert_eq!(*runtime.memory_manager.requesters_total.lock(), 40);
     }
 
+    #[tokio::test]
+    async fn reservation() {
+        let config = RuntimeConfig::new()
+            .with_memory_manager(MemoryManagerConfig::try_new_limit(100, 1.0).unwrap());
+        let runtime = Arc::new(RuntimeEnv::new(config).unwrap());
+
+        let reservation =
+            MemoryReservation::new("Reservation", 0, runtime.memory_manager.clone());
+        reservation.grow(40).await.unwrap();
+        reservation.grow(30).await.unwrap();
+        assert_eq!(reservation.mem_used(), 70);
+        assert_eq!(*runtime.memory_manager.requesters_total.lock(), 70);
+
+        let err = reservation.grow(40).await.unwrap_err();
+        assert!(
+            matches!(err, DataFusionError::ResourcesExhausted(_)),
+            "unexpected error: {:?}",
+            err
+        );
+        assert_eq!(reservation.mem_used(), 70);
+
+        drop(reservation);
+        assert_eq!(*runtime.memory_manager.requesters_total.lock(), 0);
+    }
+
     #[tokio::test]
     #[should_panic(expected = "invalid max_memory. Expected greater than 0, got 0")]
     async fn test_try_new_with_limit_0() {
@@ -17,6 +17,7 @@
 
 //! DataFusion query execution
 
+pub mod cache_manager;
 pub mod context;
 pub mod disk_manager;
 pub mod memory_manager;
@@ -26,4 +27,5 @@ pub mod runtime_env;
 pub use disk_manager::DiskManager;
 pub use memory_manager::{
     human_readable_size, MemoryConsumer, MemoryConsumerId, MemoryManager,
+    MemoryReservation,
 };
@@ -24,6 +24,7 @@ use arrow::datatypes::{Schema, SchemaRef};
 use crate::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
 use crate::datasource::{
     file_format::{
+        arrow::{ArrowFormat, DEFAULT_ARROW_EXTENSION},
         avro::{AvroFormat, DEFAULT_AVRO_EXTENSION},
         csv::{CsvFormat, DEFAULT_CSV_EXTENSION},
         json::{JsonFormat, DEFAULT_JSON_EXTENSION},
@@ -130,6 +131,8 @@ impl<'a> CsvReadOptions<'a> {
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: None,
+            bucketing: None,
         }
     }
 }
@@ -181,6 +184,8 @@ impl<'a> ParquetReadOptions<'a> {
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: None,
+            bucketing: None,
         }
     }
 }
@@ -225,6 +230,8 @@ impl<'a> AvroReadOptions<'a> {
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: None,
+            bucketing: None,
         }
     }
 }
@@ -272,6 +279,52 @@ impl<'a> NdJsonReadOptions<'a> {
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: None,
+            bucketing: None,
+        }
+    }
+}
+
+/// Arrow IPC file read options
+#[derive(Clone)]
+pub struct ArrowReadOptions<'a> {
+    /// The data source schema.
+    pub schema: Option<SchemaRef>,
+
+    /// File extension; only files with this extension are selected for data input.
+    /// Defaults to DEFAULT_ARROW_EXTENSION.
+    pub file_extension: &'a str,
+    /// Partition Columns
+    pub table_partition_cols: Vec<String>,
+}
+
+impl<'a> Default for ArrowReadOptions<'a> {
+    fn default() -> Self {
+        Self {
+            schema: None,
+            file_extension: DEFAULT_ARROW_EXTENSION,
+            table_partition_cols: vec![],
+        }
+    }
+}
+
+impl<'a> ArrowReadOptions<'a> {
+    /// Specify table_partition_cols for partition pruning
+    pub fn table_partition_cols(mut self, table_partition_cols: Vec<String>) -> Self {
+        self.table_partition_cols = table_partition_cols;
+        self
+    }
+
+    /// Helper to convert these user facing options to `ListingTable` options
+    pub fn to_listing_options(&self, target_partitions: usize) -> ListingOptions {
+        ListingOptions {
+            format: Arc::new(ArrowFormat),
+            collect_stat: false,
+            file_extension: self.file_extension.to_owned(),
+            target_partitions,
+            table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: None,
+            bucketing: None,
         }
     }
 }
@@ -21,6 +21,7 @@
 use crate::{
     error::Result,
     execution::{
+        cache_manager::{CacheManager, CacheManagerConfig},
         disk_manager::{DiskManager, DiskManagerConfig},
         memory_manager::{MemoryConsumerId, MemoryManager, MemoryManagerConfig},
     },
@@ -42,6 +43,8 @@ pub struct RuntimeEnv {
     pub disk_manager: Arc<DiskManager>,
     /// Object Store Registry
     pub object_store_registry: Arc<ObjectStoreRegistry>,
+    /// Caches of file metadata and directory listings
+    pub cache_manager: Arc<CacheManager>,
 }
 
 impl Debug for RuntimeEnv {
@@ -56,12 +59,14 @@ impl RuntimeEnv {
         let RuntimeConfig {
             memory_manager,
             disk_manager,
+            cache_manager,
         } = config;
 
         Ok(Self {
             memory_manager: MemoryManager::new(memory_manager),
             disk_manager: DiskManager::try_new(disk_manager)?,
             object_store_registry: Arc::new(ObjectStoreRegistry::new()),
+            cache_manager: CacheManager::new(cache_manager),
         })
     }
 
@@ -123,6 +128,8 @@ pub struct RuntimeConfig {
     pub disk_manager: DiskManagerConfig,
     /// MemoryManager to limit access to memory
     pub memory_manager: MemoryManagerConfig,
+    /// CacheManager to cache file metadata and directory listings
+    pub cache_manager: CacheManagerConfig,
 }
 
 impl RuntimeConfig {
@@ -143,6 +150,12 @@ impl RuntimeConfig {
         self
     }
 
+    /// Customize cache manager
+    pub fn with_cache_manager(mut self, cache_manager: CacheManagerConfig) -> Self {
+        self.cache_manager = cache_manager;
+        self
+    }
+
     /// Specify the total memory to use while running the DataFusion
     /// plan to `max_memory * memory_fraction` in bytes.
     ///
@@ -28,9 +28,10 @@ use datafusion_expr::BuiltinScalarFunction;
 pub use datafusion_expr::Expr;
 use datafusion_expr::StateTypeFunction;
 pub use datafusion_expr::{lit, lit_timestamp_nano, Literal};
-use datafusion_expr::{AggregateUDF, ScalarUDF};
+use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
 use datafusion_expr::{
-    ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility,
+    PartitionEvaluatorFunctionImplementation, ReturnTypeFunction,
+    ScalarFunctionImplementation, Signature, Volatility,
 };
 use std::sync::Arc;
 
@@ -80,6 +81,8 @@ pub fn create_udf(
 
 /// Creates a new UDAF with a specific signature, state type and return type.
 /// The signature and state type must match the `Accumulator's implementation`.
+/// When used as a window function over a sliding frame, an `Accumulator` that
+/// implements `retract_batch` and `supports_retract_batch` is updated incrementally.
 #[allow(clippy::rc_buffer)]
 pub fn create_udaf(
     name: &str,
@@ -100,6 +103,25 @@ pub fn create_udaf(
     )
 }
 
+/// Creates a new UDWF with a specific signature and return type.
+/// This is a helper function to create a new UDWF, whose partitions are evaluated
+/// by the `PartitionEvaluator`s that `partition_evaluator` creates.
+pub fn create_udwf(
+    name: &str,
+    input_types: Vec<DataType>,
+    return_type: Arc<DataType>,
+    volatility: Volatility,
+    partition_evaluator: PartitionEvaluatorFunctionImplementation,
+) -> WindowUDF {
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(return_type.clone()));
+    WindowUDF::new(
+        name,
+        &Signature::exact(input_types, volatility),
+        &return_type,
+        &partition_evaluator,
+    )
+}
+
 /// Calls a named built in function
 /// ```
 /// use datafusion::logical_plan::*;
@@ -40,14 +40,15 @@ pub use expr::{
     abs, acos, and, approx_distinct, approx_percentile_cont, array, ascii, asin, atan,
     avg, bit_length, btrim, call_fn, case, ceil, character_length, chr, coalesce, col,
     combine_filters, concat, concat_expr, concat_ws, concat_ws_expr, cos, count,
-    count_distinct, create_udaf, create_udf, date_part, date_trunc, digest, exists, exp,
-    floor, in_list, in_subquery, initcap, left, length, lit, lit_timestamp_nano, ln,
-    log10, log2, lower, lpad, ltrim, max, md5, min, not_exists, not_in_subquery, now,
-    now_expr, nullif, octet_length, or, power, random, regexp_match, regexp_replace,
-    repeat, replace, reverse, right, round, rpad, rtrim, scalar_subquery, sha224, sha256,
-    sha384, sha512, signum, sin, split_part, sqrt, starts_with, strpos, substr, sum, tan,
-    to_hex, to_timestamp_micros, to_timestamp_millis, to_timestamp_seconds, translate,
-    trim, trunc, unalias, upper, when, Column, Expr, ExprSchema, Literal,
+    count_distinct, create_udaf, create_udf, create_udwf, date_part, date_trunc, digest,
+    exists, exp, floor, in_list, in_subquery, initcap, left, length, lit,
+    lit_timestamp_nano, ln, log10, log2, lower, lpad, ltrim, max, md5, min, not_exists,
+    not_in_subquery, now, now_expr, nullif, octet_length, or, power, random,
+    regexp_match, regexp_replace, repeat, replace, reverse, right, round, rpad, rtrim,
+    scalar_subquery, sha224, sha256, sha384, sha512, signum, sin, split_part, sqrt,
+    starts_with, strpos, substr, sum, tan, to_hex, to_timestamp_micros,
+    to_timestamp_millis, to_timestamp_seconds, translate, trim, trunc, unalias, upper,
+    when, Column, Expr, ExprSchema, Literal,
 };
 pub use expr_rewriter::{
     normalize_col, normalize_col_with_schemas, normalize_cols, replace_col,
@@ -59,8 +60,8 @@ pub use plan::{provider_as_source, source_as_provider};
 pub use plan::{
     CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateMemoryTable,
     CreateView, CrossJoin, DropTable, EmptyRelation, FileType, JoinConstraint, JoinType,
-    Limit, LogicalPlan, Offset, Partitioning, PlanType, PlanVisitor, Repartition,
-    StringifiedPlan, Subquery, TableScan, ToStringifiedPlan, Union,
-    UserDefinedLogicalNode, Values,
+    Limit, LogicalPlan, Offset, Partitioning, PlanType, PlanVisitor, RecursiveQuery,
+    Repartition, StringifiedPlan, Subquery, TableScan, ToStringifiedPlan, Union, Unnest,
+    UnnestOptions, UserDefinedLogicalNode, Values,
 };
 pub use registry::FunctionRegistry;
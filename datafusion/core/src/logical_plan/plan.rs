@@ -27,9 +27,10 @@ pub use crate::logical_expr::{
         Aggregate, Analyze, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
         CreateMemoryTable, CreateView, CrossJoin, DropTable, EmptyRelation, Explain,
         Extension, FileType, Filter, Join, JoinConstraint, JoinType, Limit, LogicalPlan,
-        Offset, Partitioning, PlanType, PlanVisitor, Projection, Repartition, Sort,
-        StringifiedPlan, Subquery, SubqueryAlias, TableScan, ToStringifiedPlan, Union,
-        UserDefinedLogicalNode, Values, Window,
+        Offset, Partitioning, PlanType, PlanVisitor, Projection, RecursiveQuery,
+        Repartition, Sort, StringifiedPlan, Subquery, SubqueryAlias, TableScan,
+        ToStringifiedPlan, Union, Unnest, UnnestOptions, UserDefinedLogicalNode, Values,
+        Window,
     },
     TableProviderFilterPushDown, TableSource,
 };
@@ -15,8 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::error::Result;
-use datafusion_expr::{AggregateUDF, ScalarUDF};
+use crate::error::{DataFusionError, Result};
+use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
 use std::{collections::HashSet, sync::Arc};
 
 /// A registry knows how to build logical expressions out of user-defined function' names
@@ -29,4 +29,12 @@ pub trait FunctionRegistry {
 
     /// Returns a reference to the udaf named `name`.
     fn udaf(&self, name: &str) -> Result<Arc<AggregateUDF>>;
+
+    /// Returns a reference to the udwf named `name`.
+    fn udwf(&self, name: &str) -> Result<Arc<WindowUDF>> {
+        Err(DataFusionError::Plan(format!(
+            "There is no UDWF named \"{}\" in the registry",
+            name
+        )))
+    }
 }
@@ -0,0 +1,644 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Aggregate push down optimizer rule that pre-aggregates the input of joins
+
+use crate::error::Result;
+use crate::execution::context::ExecutionProps;
+use crate::logical_plan::plan::{
+    source_as_provider, Aggregate, Filter, Join, Projection, SubqueryAlias, TableScan,
+};
+use crate::logical_plan::{
+    build_join_schema, Column, DFSchema, Expr, ExprSchemable, JoinType, LogicalPlan,
+};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use crate::physical_plan::aggregates::AggregateFunction;
+use crate::physical_plan::ColumnStatistics;
+use crate::scalar::ScalarValue;
+use datafusion_expr::utils::{expr_to_columns, exprlist_to_fields};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// The minimum factor by which the partial aggregate must be estimated to reduce the
+/// number of rows of its input for the aggregate to be pushed below a join
+const MIN_REDUCTION_FACTOR: usize = 2;
+
+/// Aggregate push down optimizer rule (also known as eager aggregation) that
+/// partially aggregates one side of an inner join before the join, so that fewer
+/// rows are joined
+///  ```text
+///    SELECT d.name, SUM(f.amount)
+///    FROM fact f JOIN dim d ON f.dk = d.k
+///    GROUP BY d.name
+///
+///    Into
+///
+///    SELECT d.name, SUM(amount)
+///    FROM (
+///      SELECT f.dk, SUM(f.amount) AS amount FROM fact f GROUP BY f.dk
+///    ) f JOIN dim d ON f.dk = d.k
+///    GROUP BY d.name
+///  ```
+///
+/// The partial aggregate is grouped by the join keys of its side of the join, and
+/// any of the grouping columns of the original aggregate from that side, so that all
+/// rows of a group join with the same rows of the other side.
+///
+/// This is only valid if every aggregate is decomposable, that is `SUM`, `COUNT`,
+/// `MIN` or `MAX` without `DISTINCT`, and only references columns from one side of
+/// the join. As the partial aggregate is only beneficial if it significantly reduces
+/// the number of rows, it is only applied if the [`Statistics`] of the input show that
+/// it reduces the number of rows by at least a factor of 2. The number of distinct
+/// values of a column is taken from its statistics, such as the exact counts of a
+/// `MemTable`, or else bounded by the range between the minimum and the maximum of
+/// integer columns, such as those of the footers of Parquet files.
+///
+/// [`Statistics`]: crate::physical_plan::Statistics
+#[derive(Default)]
+pub struct AggregatePushDown {}
+
+impl AggregatePushDown {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for AggregatePushDown {
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        let plan = utils::optimize_children(self, plan, execution_props)?;
+        match &plan {
+            LogicalPlan::Aggregate(aggregate) => {
+                Ok(push_down_aggregate(aggregate)?.unwrap_or(plan))
+            }
+            _ => Ok(plan),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "aggregate_push_down"
+    }
+}
+
+/// Returns the rewritten plan if `aggregate` can be pushed below its input join
+fn push_down_aggregate(aggregate: &Aggregate) -> Result<Option<LogicalPlan>> {
+    let join = match aggregate.input.as_ref() {
+        LogicalPlan::Join(join)
+            if join.join_type == JoinType::Inner && join.filter.is_none() =>
+        {
+            join
+        }
+        _ => return Ok(None),
+    };
+
+    // Without grouping the final aggregate of an empty join would return
+    // null instead of 0 for COUNT
+    if aggregate.group_expr.is_empty() || aggregate.aggr_expr.is_empty() {
+        return Ok(None);
+    }
+
+    let mut group_columns = vec![];
+    for expr in &aggregate.group_expr {
+        match expr {
+            Expr::Column(c) => group_columns.push(c.clone()),
+            _ => return Ok(None),
+        }
+    }
+
+    // Find the side of the join that the aggregates reference
+    let mut left_side = None;
+    for expr in &aggregate.aggr_expr {
+        match expr {
+            Expr::AggregateFunction {
+                fun:
+                    AggregateFunction::Sum
+                    | AggregateFunction::Count
+                    | AggregateFunction::Min
+                    | AggregateFunction::Max,
+                args,
+                distinct: false,
+                filter: None,
+                ..
+            } => {
+                let mut columns = HashSet::new();
+                for arg in args {
+                    expr_to_columns(arg, &mut columns)?;
+                }
+                if columns.is_empty() {
+                    continue;
+                }
+
+                let side = if contains_columns(join.left.schema(), &columns) {
+                    true
+                } else if contains_columns(join.right.schema(), &columns) {
+                    false
+                } else {
+                    return Ok(None);
+                };
+                if *left_side.get_or_insert(side) != side {
+                    return Ok(None);
+                }
+            }
+            _ => return Ok(None),
+        }
+    }
+
+    let left_side = match left_side {
+        Some(left_side) => left_side,
+        None => return Ok(None),
+    };
+    let (input, keys) = match left_side {
+        true => (
+            &join.left,
+            join.on.iter().map(|(l, _)| l).collect::<Vec<_>>(),
+        ),
+        false => (&join.right, join.on.iter().map(|(_, r)| r).collect()),
+    };
+
+    // Group the partial aggregate by the join keys and the grouping columns of its side
+    let mut partial_group_columns: Vec<Column> = vec![];
+    for column in keys.into_iter().chain(group_columns.iter()) {
+        let column = match input.schema().field_from_column(column) {
+            Ok(field) => field.qualified_column(),
+            Err(_) => continue,
+        };
+        if !partial_group_columns.contains(&column) {
+            partial_group_columns.push(column);
+        }
+    }
+
+    if !is_worthwhile(input, &partial_group_columns) {
+        return Ok(None);
+    }
+
+    let partial_group_expr = partial_group_columns
+        .into_iter()
+        .map(Expr::Column)
+        .collect::<Vec<_>>();
+    let partial_aggr_expr = aggregate.aggr_expr.clone();
+    let partial_schema = DFSchema::new_with_metadata(
+        exprlist_to_fields(
+            partial_group_expr.iter().chain(partial_aggr_expr.iter()),
+            input,
+        )?,
+        input.schema().metadata().clone(),
+    )?;
+    let partial = LogicalPlan::Aggregate(Aggregate {
+        input: input.clone(),
+        group_expr: partial_group_expr,
+        aggr_expr: partial_aggr_expr,
+        schema: Arc::new(partial_schema),
+    });
+
+    let (left, right) = match left_side {
+        true => (Arc::new(partial), join.right.clone()),
+        false => (join.left.clone(), Arc::new(partial)),
+    };
+    let join_schema = build_join_schema(left.schema(), right.schema(), &JoinType::Inner)?;
+    let join = LogicalPlan::Join(Join {
+        left,
+        right,
+        on: join.on.clone(),
+        filter: None,
+        join_type: JoinType::Inner,
+        join_constraint: join.join_constraint,
+        schema: Arc::new(join_schema),
+        null_equals_null: join.null_equals_null,
+        null_aware: false,
+    });
+
+    // Combine the partial aggregates for each group
+    let final_aggr_expr = aggregate
+        .aggr_expr
+        .iter()
+        .map(|expr| {
+            let fun = match expr {
+                Expr::AggregateFunction { fun, .. } => match fun {
+                    AggregateFunction::Count => AggregateFunction::Sum,
+                    fun => fun.clone(),
+                },
+                _ => unreachable!(),
+            };
+            let column = Column::from_name(expr.name(aggregate.input.schema())?);
+            Ok(Expr::AggregateFunction {
+                fun,
+                args: vec![Expr::Column(column)],
+                distinct: false,
+                filter: None,
+                order_by: vec![],
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let final_schema = DFSchema::new_with_metadata(
+        exprlist_to_fields(
+            aggregate.group_expr.iter().chain(final_aggr_expr.iter()),
+            &join,
+        )?,
+        join.schema().metadata().clone(),
+    )?;
+    let final_schema = Arc::new(final_schema);
+    let final_aggregate = LogicalPlan::Aggregate(Aggregate {
+        input: Arc::new(join),
+        group_expr: aggregate.group_expr.clone(),
+        aggr_expr: final_aggr_expr,
+        schema: final_schema.clone(),
+    });
+
+    // so the aggregates are displayed in the same way even after the rewrite, and
+    // re-aggregating does not widen their types (e.g. SUM(SUM(decimal)))
+    let expr = final_schema
+        .fields()
+        .iter()
+        .zip(aggregate.schema.fields())
+        .map(|(field, original)| {
+            let expr = Expr::Column(field.qualified_column());
+            Ok(match field.data_type() == original.data_type() {
+                true if field.name() == original.name() => expr,
+                true => expr.alias(original.name()),
+                false => expr
+                    .cast_to(original.data_type(), &final_schema)?
+                    .alias(original.name()),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(LogicalPlan::Projection(Projection {
+        expr,
+        input: Arc::new(final_aggregate),
+        schema: aggregate.schema.clone(),
+        alias: None,
+    })))
+}
+
+/// Returns true if all of `columns` are contained in `schema`
+fn contains_columns(schema: &DFSchema, columns: &HashSet<Column>) -> bool {
+    columns.iter().all(|c| schema.field_from_column(c).is_ok())
+}
+
+/// Returns true if grouping `plan` by `columns` is estimated to reduce its
+/// number of rows by at least [`MIN_REDUCTION_FACTOR`]
+fn is_worthwhile(plan: &LogicalPlan, columns: &[Column]) -> bool {
+    match estimate_distinct_counts(plan, columns) {
+        Some((num_rows, distinct_counts)) => {
+            let num_groups = distinct_counts
+                .into_iter()
+                .fold(1_usize, |acc, count| acc.saturating_mul(count))
+                .min(num_rows);
+            num_groups.saturating_mul(MIN_REDUCTION_FACTOR) <= num_rows
+        }
+        None => false,
+    }
+}
+
+/// Estimates an upper bound for the number of rows of `plan`, and the
+/// number of distinct values of each of `columns`, from the statistics
+/// of the scanned tables
+fn estimate_distinct_counts(
+    plan: &LogicalPlan,
+    columns: &[Column],
+) -> Option<(usize, Vec<usize>)> {
+    match plan {
+        LogicalPlan::TableScan(TableScan { source, .. }) => {
+            let provider = source_as_provider(source).ok()?;
+            let statistics = provider.statistics()?;
+            let column_statistics = statistics.column_statistics?;
+            let schema = provider.schema();
+
+            let distinct_counts = columns
+                .iter()
+                .map(|c| {
+                    let index = schema.index_of(&c.name).ok()?;
+                    estimate_distinct_count(column_statistics.get(index)?)
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((statistics.num_rows?, distinct_counts))
+        }
+        // a filter can only reduce the number of rows and distinct values
+        LogicalPlan::Filter(Filter { input, .. }) => {
+            estimate_distinct_counts(input, columns)
+        }
+        // the columns are looked up by name in the aliased input
+        LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => {
+            estimate_distinct_counts(input, columns)
+        }
+        LogicalPlan::Projection(Projection {
+            expr,
+            input,
+            schema,
+            ..
+        }) => {
+            let columns = columns
+                .iter()
+                .map(|c| {
+                    let index = schema.index_of_column(c).ok()?;
+                    match &expr[index] {
+                        Expr::Column(c) => Some(c.clone()),
+                        Expr::Alias(expr, _) => match expr.as_ref() {
+                            Expr::Column(c) => Some(c.clone()),
+                            _ => None,
+                        },
+                        _ => None,
+                    }
+                })
+                .collect::<Option<Vec<_>>>()?;
+            estimate_distinct_counts(input, &columns)
+        }
+        _ => None,
+    }
+}
+
+/// The number of distinct values of a column, or else an upper bound for it
+/// if the column is an integer
+fn estimate_distinct_count(statistics: &ColumnStatistics) -> Option<usize> {
+    let integer = |value: &Option<ScalarValue>| match value.as_ref()? {
+        ScalarValue::Int8(v) => v.map(i128::from),
+        ScalarValue::Int16(v) => v.map(i128::from),
+        ScalarValue::Int32(v) => v.map(i128::from),
+        ScalarValue::Int64(v) => v.map(i128::from),
+        ScalarValue::UInt8(v) => v.map(i128::from),
+        ScalarValue::UInt16(v) => v.map(i128::from),
+        ScalarValue::UInt32(v) => v.map(i128::from),
+        ScalarValue::UInt64(v) => v.map(i128::from),
+        ScalarValue::Date32(v) => v.map(i128::from),
+        _ => None,
+    };
+    statistics.distinct_count.or_else(|| {
+        let min = integer(&statistics.min_value)?;
+        let max = integer(&statistics.max_value)?;
+        usize::try_from(max - min + 1).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::{TableProvider, TableType};
+    use crate::logical_plan::plan::provider_as_source;
+    use crate::logical_plan::{avg, col, count, count_distinct, sum, LogicalPlanBuilder};
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::{ColumnStatistics, ExecutionPlan, Statistics};
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use async_trait::async_trait;
+
+    struct StatisticsProvider {
+        schema: SchemaRef,
+        statistics: Statistics,
+    }
+
+    #[async_trait]
+    impl TableProvider for StatisticsProvider {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn table_type(&self) -> TableType {
+            TableType::Base
+        }
+
+        async fn scan(
+            &self,
+            projection: &Option<Vec<usize>>,
+            _: &[Expr],
+            _: Option<usize>,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            Ok(Arc::new(MemoryExec::try_new(
+                &[],
+                self.schema(),
+                projection.clone(),
+            )?))
+        }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(self.statistics.clone())
+        }
+    }
+
+    /// Creates a scan of a table with `num_rows` rows, and columns with
+    /// the given names and distinct counts
+    fn table_scan(
+        name: &str,
+        num_rows: usize,
+        columns: &[(&str, usize)],
+    ) -> Result<LogicalPlan> {
+        let columns = columns
+            .iter()
+            .map(|(name, distinct_count)| {
+                let statistics = ColumnStatistics {
+                    distinct_count: Some(*distinct_count),
+                    ..Default::default()
+                };
+                (*name, DataType::Int64, statistics)
+            })
+            .collect::<Vec<_>>();
+        table_scan_with_statistics(name, num_rows, columns)
+    }
+
+    /// Creates a scan of a table with `num_rows` rows, and columns with
+    /// the given names, types and statistics
+    fn table_scan_with_statistics(
+        name: &str,
+        num_rows: usize,
+        columns: Vec<(&str, DataType, ColumnStatistics)>,
+    ) -> Result<LogicalPlan> {
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|(name, data_type, _)| Field::new(name, data_type.clone(), false))
+                .collect(),
+        ));
+        let statistics = Statistics {
+            num_rows: Some(num_rows),
+            column_statistics: Some(
+                columns
+                    .into_iter()
+                    .map(|(_, _, statistics)| statistics)
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let provider = StatisticsProvider { schema, statistics };
+        LogicalPlanBuilder::scan(name, provider_as_source(Arc::new(provider)), None)?
+            .build()
+    }
+
+    fn fact_dim_join(fact_rows: usize) -> Result<LogicalPlanBuilder> {
+        let fact = table_scan("f", fact_rows, &[("dk", 10), ("amount", fact_rows)])?;
+        let dim = table_scan("d", 10, &[("k", 10), ("name", 5)])?;
+        LogicalPlanBuilder::from(fact).join(
+            &dim,
+            JoinType::Inner,
+            (vec![Column::from_name("dk")], vec![Column::from_name("k")]),
+        )
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = AggregatePushDown::new();
+        let optimized_plan = rule
+            .optimize(plan, &ExecutionProps::new())
+            .expect("failed to optimize plan");
+        let formatted_plan = format!("{}", optimized_plan.display_indent_schema());
+        assert_eq!(formatted_plan, expected);
+    }
+
+    #[test]
+    fn push_down_sum_and_count() -> Result<()> {
+        let plan = fact_dim_join(1000)?
+            .aggregate(
+                vec![col("d.name")],
+                vec![sum(col("f.amount")), count(col("f.amount"))],
+            )?
+            .build()?;
+
+        let expected = "Projection: #d.name, #SUM(SUM(f.amount)) AS SUM(f.amount), #SUM(COUNT(f.amount)) AS COUNT(f.amount) [name:Int64, SUM(f.amount):Int64;N, COUNT(f.amount):UInt64;N]\
+        \n  Aggregate: groupBy=[[#d.name]], aggr=[[SUM(#SUM(f.amount)), SUM(#COUNT(f.amount))]] [name:Int64, SUM(SUM(f.amount)):Int64;N, SUM(COUNT(f.amount)):UInt64;N]\
+        \n    Inner Join: #f.dk = #d.k [dk:Int64, SUM(f.amount):Int64;N, COUNT(f.amount):UInt64;N, k:Int64, name:Int64]\
+        \n      Aggregate: groupBy=[[#f.dk]], aggr=[[SUM(#f.amount), COUNT(#f.amount)]] [dk:Int64, SUM(f.amount):Int64;N, COUNT(f.amount):UInt64;N]\
+        \n        TableScan: f projection=None [dk:Int64, amount:Int64]\
+        \n      TableScan: d projection=None [k:Int64, name:Int64]";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn no_push_down_without_reduction() -> Result<()> {
+        // each fact row has a distinct join key
+        let plan = fact_dim_join(10)?
+            .aggregate(vec![col("d.name")], vec![sum(col("f.amount"))])?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[#d.name]], aggr=[[SUM(#f.amount)]] [name:Int64, SUM(f.amount):Int64;N]\
+        \n  Inner Join: #f.dk = #d.k [dk:Int64, amount:Int64, k:Int64, name:Int64]\
+        \n    TableScan: f projection=None [dk:Int64, amount:Int64]\
+        \n    TableScan: d projection=None [k:Int64, name:Int64]";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn push_down_with_range_of_integer_keys() -> Result<()> {
+        // the join keys range from 1 to 10, without a count of distinct values
+        let key_range = |min: i64, max: i64| ColumnStatistics {
+            min_value: Some(ScalarValue::Int64(Some(min))),
+            max_value: Some(ScalarValue::Int64(Some(max))),
+            ..Default::default()
+        };
+        let fact = table_scan_with_statistics(
+            "f",
+            1000,
+            vec![
+                ("dk", DataType::Int64, key_range(1, 10)),
+                ("amount", DataType::Int64, ColumnStatistics::default()),
+            ],
+        )?;
+        let dim = table_scan("d", 10, &[("k", 10), ("name", 5)])?;
+        let plan = LogicalPlanBuilder::from(fact)
+            .join(
+                &dim,
+                JoinType::Inner,
+                (vec![Column::from_name("dk")], vec![Column::from_name("k")]),
+            )?
+            .aggregate(vec![col("d.name")], vec![sum(col("f.amount"))])?
+            .build()?;
+
+        let expected = "Projection: #d.name, #SUM(SUM(f.amount)) AS SUM(f.amount) [name:Int64, SUM(f.amount):Int64;N]\
+        \n  Aggregate: groupBy=[[#d.name]], aggr=[[SUM(#SUM(f.amount))]] [name:Int64, SUM(SUM(f.amount)):Int64;N]\
+        \n    Inner Join: #f.dk = #d.k [dk:Int64, SUM(f.amount):Int64;N, k:Int64, name:Int64]\
+        \n      Aggregate: groupBy=[[#f.dk]], aggr=[[SUM(#f.amount)]] [dk:Int64, SUM(f.amount):Int64;N]\
+        \n        TableScan: f projection=None [dk:Int64, amount:Int64]\
+        \n      TableScan: d projection=None [k:Int64, name:Int64]";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn push_down_sum_of_decimal() -> Result<()> {
+        let distinct = |distinct_count| ColumnStatistics {
+            distinct_count: Some(distinct_count),
+            ..Default::default()
+        };
+        let fact = table_scan_with_statistics(
+            "f",
+            1000,
+            vec![
+                ("dk", DataType::Int64, distinct(10)),
+                ("amount", DataType::Decimal(10, 2), distinct(1000)),
+            ],
+        )?;
+        let dim = table_scan("d", 10, &[("k", 10), ("name", 5)])?;
+        let plan = LogicalPlanBuilder::from(fact)
+            .join(
+                &dim,
+                JoinType::Inner,
+                (vec![Column::from_name("dk")], vec![Column::from_name("k")]),
+            )?
+            .aggregate(vec![col("d.name")], vec![sum(col("f.amount"))])?
+            .build()?;
+
+        // the sum of the partial sums is cast back to the type of the original sum
+        let expected = "Projection: #d.name, CAST(#SUM(SUM(f.amount)) AS Decimal(20, 2)) AS SUM(f.amount) [name:Int64, SUM(f.amount):Decimal(20, 2);N]\
+        \n  Aggregate: groupBy=[[#d.name]], aggr=[[SUM(#SUM(f.amount))]] [name:Int64, SUM(SUM(f.amount)):Decimal(30, 2);N]\
+        \n    Inner Join: #f.dk = #d.k [dk:Int64, SUM(f.amount):Decimal(20, 2);N, k:Int64, name:Int64]\
+        \n      Aggregate: groupBy=[[#f.dk]], aggr=[[SUM(#f.amount)]] [dk:Int64, SUM(f.amount):Decimal(20, 2);N]\
+        \n        TableScan: f projection=None [dk:Int64, amount:Decimal(10, 2)]\
+        \n      TableScan: d projection=None [k:Int64, name:Int64]";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn no_push_down_non_decomposable() -> Result<()> {
+        let plan = fact_dim_join(1000)?
+            .aggregate(
+                vec![col("d.name")],
+                vec![avg(col("f.amount")), count_distinct(col("f.amount"))],
+            )?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[#d.name]], aggr=[[AVG(#f.amount), COUNT(DISTINCT #f.amount)]] [name:Int64, AVG(f.amount):Float64;N, COUNT(DISTINCT f.amount):UInt64;N]\
+        \n  Inner Join: #f.dk = #d.k [dk:Int64, amount:Int64, k:Int64, name:Int64]\
+        \n    TableScan: f projection=None [dk:Int64, amount:Int64]\
+        \n    TableScan: d projection=None [k:Int64, name:Int64]";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn no_push_down_both_sides() -> Result<()> {
+        let plan = fact_dim_join(1000)?
+            .aggregate(
+                vec![col("d.name")],
+                vec![sum(col("f.amount")), sum(col("d.k"))],
+            )?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[#d.name]], aggr=[[SUM(#f.amount), SUM(#d.k)]] [name:Int64, SUM(f.amount):Int64;N, SUM(d.k):Int64;N]\
+        \n  Inner Join: #f.dk = #d.k [dk:Int64, amount:Int64, k:Int64, name:Int64]\
+        \n    TableScan: f projection=None [dk:Int64, amount:Int64]\
+        \n    TableScan: d projection=None [k:Int64, name:Int64]";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+}
@@ -17,11 +17,11 @@
 use crate::error::Result;
 use crate::execution::context::ExecutionProps;
 use crate::logical_expr::TableProviderFilterPushDown;
-use crate::logical_plan::plan::{Aggregate, Filter, Join, Projection, Union};
+use crate::logical_plan::plan::{Aggregate, Filter, Join, Projection, Union, Unnest};
 use crate::logical_plan::{
     col, replace_col, Column, CrossJoin, JoinType, Limit, LogicalPlan, TableScan,
 };
-use crate::logical_plan::{DFSchema, Expr};
+use crate::logical_plan::{DFSchema, Expr, Operator};
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::utils;
 use datafusion_expr::utils::{expr_to_columns, exprlist_to_columns, from_plan};
@@ -52,6 +52,13 @@ use std::collections::{HashMap, HashSet};
 /// and when it reaches a node that does not commute with it, it adds the filter to that place.
 /// When it passes through a projection, it re-writes the filter's expression taking into account that projection.
 /// When multiple filters would have been written, it `AND` their expressions into a single expression.
+///
+/// When it passes through a join, it also infers new predicates for the join inputs:
+/// * predicates on one side of an equality, e.g. `a.k = 5` with `a.k = b.k`, are inferred
+///   for the other side, e.g. `b.k = 5`
+/// * `IS NOT NULL` predicates are added for the nullable join keys of inner joins
+/// * outer joins whose null-padded rows are rejected by a predicate above them are
+///   converted to inner (or, for full joins, left or right) joins
 #[derive(Default)]
 pub struct FilterPushDown {}
 
@@ -162,7 +169,7 @@ fn lr_is_preserved(plan: &LogicalPlan) -> (bool, bool) {
     match plan {
         LogicalPlan::Join(Join { join_type, .. }) => match join_type {
             JoinType::Inner => (true, true),
-            JoinType::Left => (true, false),
+            JoinType::Left | JoinType::AsOf => (true, false),
             JoinType::Right => (false, true),
             JoinType::Full => (false, false),
             // No columns from the right side of the join can be referenced in output
@@ -188,17 +195,7 @@ fn get_pushable_join_predicates<'a>(
         return (vec![], vec![]);
     }
 
-    let schema_columns = schema
-        .fields()
-        .iter()
-        .flat_map(|f| {
-            [
-                f.qualified_column(),
-                // we need to push down filter using unqualified column as well
-                f.unqualified_column(),
-            ]
-        })
-        .collect::<HashSet<_>>();
+    let schema_columns = schema_columns(schema);
 
     state
         .filters
@@ -215,6 +212,234 @@ fn get_pushable_join_predicates<'a>(
         .unzip()
 }
 
+/// Returns the columns of `schema`, both qualified and unqualified
+fn schema_columns(schema: &DFSchema) -> HashSet<Column> {
+    schema
+        .fields()
+        .iter()
+        .flat_map(|f| {
+            [
+                f.qualified_column(),
+                // we need to push down filter using unqualified column as well
+                f.unqualified_column(),
+            ]
+        })
+        .collect()
+}
+
+/// Returns the `column = column` predicates in `state`
+fn column_equalities(state: &State) -> Vec<(Column, Column)> {
+    state
+        .filters
+        .iter()
+        .filter_map(|(predicate, _)| column_equality(predicate))
+        .collect()
+}
+
+fn column_equality(expr: &Expr) -> Option<(Column, Column)> {
+    match expr {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        } => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(l), Expr::Column(r)) => Some((l.clone(), r.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Infers new predicates from `filters` by substituting the columns of each predicate
+/// with the columns they are equal to according to `equalities`.
+///
+/// Only inferred predicates whose columns are all contained in one of `schemas`, and that
+/// are not already part of `filters`, are returned
+fn infer_predicates(
+    filters: &[(Expr, HashSet<Column>)],
+    equalities: &[(Column, Column)],
+    schemas: &[&DFSchema],
+) -> Result<Vec<(Expr, HashSet<Column>)>> {
+    let schemas = schemas
+        .iter()
+        .map(|s| schema_columns(s))
+        .collect::<Vec<_>>();
+
+    let mut inferred = vec![];
+    for (predicate, columns) in filters {
+        if column_equality(predicate).is_some() {
+            continue;
+        }
+
+        let mut cols_to_replace = HashMap::new();
+        for col in columns.iter() {
+            for (l, r) in equalities {
+                if col == l {
+                    cols_to_replace.insert(col, r);
+                    break;
+                } else if col == r {
+                    cols_to_replace.insert(col, l);
+                    break;
+                }
+            }
+        }
+
+        if cols_to_replace.is_empty() {
+            continue;
+        }
+
+        let inferred_columns = columns
+            .iter()
+            // replace keys in cols_to_replace with values in resulting column set
+            .filter(|c| !cols_to_replace.contains_key(c))
+            .chain(cols_to_replace.values().copied())
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        if !schemas.iter().any(|s| inferred_columns.is_subset(s)) {
+            continue;
+        }
+
+        let inferred_predicate = replace_col(predicate.clone(), &cols_to_replace)?;
+        let exists = filters
+            .iter()
+            .chain(inferred.iter())
+            .any(|(p, _)| p == &inferred_predicate);
+        if !exists {
+            inferred.push((inferred_predicate, inferred_columns));
+        }
+    }
+    Ok(inferred)
+}
+
+/// Adds the predicates in `predicates` that are not already present to `state`
+fn add_predicates(state: &mut State, predicates: Vec<(Expr, HashSet<Column>)>) {
+    for (predicate, columns) in predicates {
+        if !state.filters.iter().any(|(p, _)| p == &predicate) {
+            state.filters.push((predicate, columns));
+        }
+    }
+}
+
+/// Returns `IS NOT NULL` predicates for the nullable join keys of `join`
+fn join_keys_not_null(join: &Join) -> Result<Vec<(Expr, HashSet<Column>)>> {
+    let mut predicates = vec![];
+    for (l, r) in &join.on {
+        for (column, schema) in [(l, join.left.schema()), (r, join.right.schema())] {
+            if schema.field_from_column(column)?.is_nullable() {
+                let predicate = Expr::Column(column.clone()).is_not_null();
+                predicates.push((predicate, HashSet::from([column.clone()])));
+            }
+        }
+    }
+    Ok(predicates)
+}
+
+/// Wraps `input`, the non-preserved side of a join, in a filter containing the
+/// predicates that can be inferred for it from the predicates in `state` that only
+/// reference the preserved side `preserved`.
+///
+/// `on` contains the join keys as pairs of (preserved, non-preserved) columns
+fn add_inferred_filter(
+    state: &State,
+    on: &[(Column, Column)],
+    preserved: &LogicalPlan,
+    input: LogicalPlan,
+) -> Result<LogicalPlan> {
+    let (predicates, columns) =
+        get_pushable_join_predicates(state, preserved.schema(), true);
+    let filters = predicates
+        .into_iter()
+        .cloned()
+        .zip(columns.into_iter().cloned())
+        .collect::<Vec<_>>();
+
+    let inferred = infer_predicates(&filters, on, &[input.schema()])?;
+    if inferred.is_empty() {
+        return Ok(input);
+    }
+
+    let predicates = inferred.iter().map(|(p, _)| p).collect::<Vec<_>>();
+    Ok(utils::add_filter(input, &predicates))
+}
+
+/// Returns the join type `join` can be converted to, given the predicates in `state`
+/// that will be evaluated against its output.
+///
+/// The rows an outer join produces for unmatched rows of one side contain nulls for
+/// the columns of the other side. If a predicate above the join rejects these nulls,
+/// these rows are discarded, and so the join does not need to produce them
+fn simplify_join_type(state: &State, join: &Join) -> JoinType {
+    let rejects_nulls = |schema: &DFSchema| {
+        let schema_columns = schema_columns(schema);
+        state.filters.iter().any(|(predicate, columns)| {
+            let columns = columns
+                .intersection(&schema_columns)
+                .cloned()
+                .collect::<HashSet<_>>();
+            !columns.is_empty() && is_null_rejecting(predicate, &columns)
+        })
+    };
+
+    match join.join_type {
+        JoinType::Left if rejects_nulls(join.right.schema()) => JoinType::Inner,
+        JoinType::Right if rejects_nulls(join.left.schema()) => JoinType::Inner,
+        JoinType::Full => match (
+            rejects_nulls(join.left.schema()),
+            rejects_nulls(join.right.schema()),
+        ) {
+            (true, true) => JoinType::Inner,
+            (true, false) => JoinType::Left,
+            (false, true) => JoinType::Right,
+            (false, false) => JoinType::Full,
+        },
+        join_type => join_type,
+    }
+}
+
+/// Returns true if `predicate` evaluates to false or null whenever all of `columns` are null
+fn is_null_rejecting(predicate: &Expr, columns: &HashSet<Column>) -> bool {
+    match predicate {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        } => is_null_rejecting(left, columns) || is_null_rejecting(right, columns),
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        } => is_null_rejecting(left, columns) && is_null_rejecting(right, columns),
+        Expr::IsNotNull(expr) => is_null_propagating(expr, columns),
+        expr => is_null_propagating(expr, columns),
+    }
+}
+
+/// Returns true if `expr` evaluates to null whenever all of `columns` are null
+fn is_null_propagating(expr: &Expr, columns: &HashSet<Column>) -> bool {
+    match expr {
+        Expr::Column(c) => columns.contains(c),
+        Expr::Alias(expr, _)
+        | Expr::Not(expr)
+        | Expr::Negative(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::TryCast { expr, .. }
+        | Expr::Between { expr, .. }
+        | Expr::InList { expr, .. } => is_null_propagating(expr, columns),
+        Expr::BinaryExpr { left, op, right } => match op {
+            Operator::And
+            | Operator::Or
+            | Operator::IsDistinctFrom
+            | Operator::IsNotDistinctFrom
+            | Operator::StringConcat => false,
+            _ => {
+                is_null_propagating(left, columns) || is_null_propagating(right, columns)
+            }
+        },
+        _ => false,
+    }
+}
+
 fn optimize_join(
     mut state: State,
     plan: &LogicalPlan,
@@ -280,7 +505,7 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
                     expr_to_columns(predicate, &mut columns)?;
                     if columns.is_empty() {
                         no_col_predicates.push(predicate)
-                    } else {
+                    } else if !state.filters.iter().any(|(p, _)| p == predicate) {
                         // collect the predicate
                         state.filters.push((predicate.clone(), columns));
                     }
@@ -384,6 +609,20 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
 
             push_down(&state, plan)
         }
+        LogicalPlan::Unnest(Unnest {
+            column, options, ..
+        }) => {
+            // unnest is filter-commutable, except for the unnested column and
+            // its ordinality
+            let mut used_columns = HashSet::from([column.clone()]);
+            if let Some(ordinality) = &options.ordinality {
+                used_columns.insert(Column {
+                    relation: column.relation.clone(),
+                    name: ordinality.clone(),
+                });
+            }
+            issue_filters(state, used_columns, plan)
+        }
         LogicalPlan::Limit(Limit { input, .. }) => {
             // limit is _not_ filter-commutable => collect all columns from its input
             let used_columns = input
@@ -395,72 +634,76 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
             issue_filters(state, used_columns, plan)
         }
         LogicalPlan::CrossJoin(CrossJoin { left, right, .. }) => {
+            // Equalities between the two sides of a cross join let predicates on one
+            // side be applied to the other, e.g. `a.k = 5 AND a.k = b.k` implies `b.k = 5`
+            let equalities = column_equalities(&state);
+            let inferred = infer_predicates(
+                &state.filters,
+                &equalities,
+                &[left.schema(), right.schema()],
+            )?;
+            add_predicates(&mut state, inferred);
             optimize_join(state, plan, left, right)
         }
-        LogicalPlan::Join(Join {
-            left,
-            right,
-            on,
-            join_type,
-            ..
-        }) => {
-            if *join_type == JoinType::Inner {
-                // For inner joins, duplicate filters for joined columns so filters can be pushed down
-                // to both sides. Take the following query as an example:
-                //
-                // ```sql
-                // SELECT * FROM t1 JOIN t2 on t1.id = t2.uid WHERE t1.id > 1
-                // ```
-                //
-                // `t1.id > 1` predicate needs to be pushed down to t1 table scan, while
-                // `t2.uid > 1` predicate needs to be pushed down to t2 table scan.
-                //
-                // Join clauses with `Using` constraints also take advantage of this logic to make sure
-                // predicates reference the shared join columns are pushed to both sides.
-                let join_side_filters = state
-                    .filters
-                    .iter()
-                    .filter_map(|(predicate, columns)| {
-                        let mut join_cols_to_replace = HashMap::new();
-                        for col in columns.iter() {
-                            for (l, r) in on {
-                                if col == l {
-                                    join_cols_to_replace.insert(col, r);
-                                    break;
-                                } else if col == r {
-                                    join_cols_to_replace.insert(col, l);
-                                    break;
-                                }
-                            }
-                        }
-
-                        if join_cols_to_replace.is_empty() {
-                            return None;
-                        }
-
-                        let join_side_predicate =
-                            match replace_col(predicate.clone(), &join_cols_to_replace) {
-                                Ok(p) => p,
-                                Err(e) => {
-                                    return Some(Err(e));
-                                }
-                            };
-
-                        let join_side_columns = columns
-                            .clone()
-                            .into_iter()
-                            // replace keys in join_cols_to_replace with values in resulting column
-                            // set
-                            .filter(|c| !join_cols_to_replace.contains_key(c))
-                            .chain(join_cols_to_replace.iter().map(|(_, v)| (*v).clone()))
-                            .collect();
-
-                        Some(Ok((join_side_predicate, join_side_columns)))
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                state.filters.extend(join_side_filters);
+        LogicalPlan::Join(join) => {
+            let join = Join {
+                join_type: simplify_join_type(&state, join),
+                ..join.clone()
+            };
+            let (mut left, mut right) =
+                (join.left.as_ref().clone(), join.right.as_ref().clone());
+
+            match join.join_type {
+                JoinType::Inner => {
+                    // For inner joins, duplicate filters for joined columns so filters can be pushed down
+                    // to both sides. Take the following query as an example:
+                    //
+                    // ```sql
+                    // SELECT * FROM t1 JOIN t2 on t1.id = t2.uid WHERE t1.id > 1
+                    // ```
+                    //
+                    // `t1.id > 1` predicate needs to be pushed down to t1 table scan, while
+                    // `t2.uid > 1` predicate needs to be pushed down to t2 table scan.
+                    //
+                    // Join clauses with `Using` constraints also take advantage of this logic to make sure
+                    // predicates reference the shared join columns are pushed to both sides.
+                    let mut equalities = join.on.clone();
+                    equalities.extend(column_equalities(&state));
+                    let inferred = infer_predicates(
+                        &state.filters,
+                        &equalities,
+                        &[left.schema(), right.schema()],
+                    )?;
+                    add_predicates(&mut state, inferred);
+
+                    // Rows with null join keys never match, and so can be discarded
+                    // by the inputs, where the scans may be able to prune them
+                    if !join.null_equals_null {
+                        let not_null = join_keys_not_null(&join)?;
+                        add_predicates(&mut state, not_null);
+                    }
+                }
+                JoinType::Left | JoinType::Semi | JoinType::Anti | JoinType::AsOf => {
+                    // Only right rows that match a left row that passes the predicates
+                    // on the left join keys can affect the output. A null-aware join
+                    // also depends on whether the right side has null keys at all,
+                    // which the inferred predicates would filter out
+                    if !join.null_aware {
+                        right = add_inferred_filter(&state, &join.on, &left, right)?;
+                    }
+                }
+                JoinType::Right => {
+                    let on = join
+                        .on
+                        .iter()
+                        .map(|(l, r)| (r.clone(), l.clone()))
+                        .collect::<Vec<_>>();
+                    left = add_inferred_filter(&state, &on, &right, left)?;
+                }
+                JoinType::Full => {}
             }
-            optimize_join(state, plan, left, right)
+
+            optimize_join(state, &LogicalPlan::Join(join), &left, &right)
         }
         LogicalPlan::TableScan(TableScan {
             source,
@@ -570,8 +813,9 @@ mod tests {
     use crate::physical_plan::ExecutionPlan;
     use crate::prelude::JoinType;
     use crate::test::*;
+    use crate::test_util::scan_empty;
 
-    use arrow::datatypes::SchemaRef;
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
     use async_trait::async_trait;
 
     fn optimize_plan(plan: &LogicalPlan) -> LogicalPlan {
@@ -620,6 +864,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn filter_around_unnest() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new(
+                "l",
+                DataType::List(Box::new(Field::new("item", DataType::UInt32, true))),
+                true,
+            ),
+        ]);
+        let plan = scan_empty(Some("test"), &schema, None)?
+            .unnest_column("l")?
+            .filter(and(col("a").eq(lit(1u32)), col("l").eq(lit(2u32))))?
+            .build()?;
+        // only the filter on the unnested column stays above the unnest
+        let expected = "\
+            Filter: #test.l = UInt32(2)\
+            \n  Unnest: #test.l\
+            \n    Filter: #test.a = UInt32(1)\
+            \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
     #[test]
     fn filter_no_columns() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -1200,19 +1468,20 @@ mod tests {
             \n      TableScan: test2 projection=None"
         );
 
-        // filter not duplicated nor pushed down - i.e. noop
+        // the filter rejects the nulls of unmatched rows, so the join is converted
+        // to an inner join and the filter pushed to both sides
         let expected = "\
-        Filter: #test2.a <= Int64(1)\
-        \n  Left Join: Using #test.a = #test2.a\
+        Inner Join: Using #test.a = #test2.a\
+        \n  Filter: #test.a <= Int64(1)\
         \n    TableScan: test projection=None\
-        \n    Projection: #test2.a\
+        \n  Projection: #test2.a\
+        \n    Filter: #test2.a <= Int64(1)\
         \n      TableScan: test2 projection=None";
         assert_optimized_plan_eq(&plan, expected);
         Ok(())
     }
 
-    /// post-join predicates on the left side of a right join are not duplicated
-    /// TODO: In this case we can sometimes convert the join to an INNER join
+    /// post-join predicates on the left side of a right join convert it to an inner join
     #[test]
     fn filter_using_right_join() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -1241,19 +1510,21 @@ mod tests {
             \n      TableScan: test2 projection=None"
         );
 
-        // filter not duplicated nor pushed down - i.e. noop
+        // the filter rejects the nulls of unmatched rows, so the join is converted
+        // to an inner join and the filter pushed to both sides
         let expected = "\
-        Filter: #test.a <= Int64(1)\
-        \n  Right Join: Using #test.a = #test2.a\
+        Inner Join: Using #test.a = #test2.a\
+        \n  Filter: #test.a <= Int64(1)\
         \n    TableScan: test projection=None\
-        \n    Projection: #test2.a\
+        \n  Projection: #test2.a\
+        \n    Filter: #test2.a <= Int64(1)\
         \n      TableScan: test2 projection=None";
         assert_optimized_plan_eq(&plan, expected);
         Ok(())
     }
 
-    /// post-left-join predicate on a column common to both sides is only pushed to the left side
-    /// i.e. - not duplicated to the right side
+    /// post-left-join predicate on a column common to both sides is pushed to the left side,
+    /// and inferred for the right side of the join
     #[test]
     fn filter_using_left_join_on_common() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -1282,19 +1553,20 @@ mod tests {
             \n      TableScan: test2 projection=None"
         );
 
-        // filter sent to left side of the join, not the right
+        // filter sent to left side of the join, and inferred for the right
         let expected = "\
         Left Join: Using #test.a = #test2.a\
         \n  Filter: #test.a <= Int64(1)\
         \n    TableScan: test projection=None\
         \n  Projection: #test2.a\
-        \n    TableScan: test2 projection=None";
+        \n    Filter: #test2.a <= Int64(1)\
+        \n      TableScan: test2 projection=None";
         assert_optimized_plan_eq(&plan, expected);
         Ok(())
     }
 
-    /// post-right-join predicate on a column common to both sides is only pushed to the right side
-    /// i.e. - not duplicated to the left side.
+    /// post-right-join predicate on a column common to both sides is pushed to the right side,
+    /// and inferred for the left side of the join
     #[test]
     fn filter_using_right_join_on_common() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -1323,10 +1595,11 @@ mod tests {
             \n      TableScan: test2 projection=None"
         );
 
-        // filter sent to right side of join, not duplicated to the left
+        // filter sent to right side of join, and inferred for the left
         let expected = "\
         Right Join: Using #test.a = #test2.a\
-        \n  TableScan: test projection=None\
+        \n  Filter: #test.a <= Int64(1)\
+        \n    TableScan: test projection=None\
         \n  Projection: #test2.a\
         \n    Filter: #test2.a <= Int64(1)\
         \n      TableScan: test2 projection=None";
@@ -1334,6 +1607,112 @@ mod tests {
         Ok(())
     }
 
+    /// predicates on one side of a cross join are inferred for the other side
+    /// through the equality predicates between them
+    #[test]
+    fn filter_cross_join_infer_predicates() -> Result<()> {
+        let left = test_table_scan()?;
+        let right = test_table_scan_with_name("test2")?;
+        let plan = LogicalPlanBuilder::from(left)
+            .cross_join(&right)?
+            .filter(and(
+                col("test.a").eq(col("test2.a")),
+                col("test.a").eq(lit(5u32)),
+            ))?
+            .build()?;
+
+        let expected = "\
+        Filter: #test.a = #test2.a\
+        \n  CrossJoin:\
+        \n    Filter: #test.a = UInt32(5)\
+        \n      TableScan: test projection=None\
+        \n    Filter: #test2.a = UInt32(5)\
+        \n      TableScan: test2 projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    fn nullable_table_scan(name: &str) -> Result<LogicalPlan> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, true),
+            Field::new("b", DataType::UInt32, true),
+        ]);
+        scan_empty(Some(name), &schema, None)?.build()
+    }
+
+    /// nullable join keys of inner joins are filtered with IS NOT NULL on both sides
+    #[test]
+    fn filter_inner_join_keys_not_null() -> Result<()> {
+        let left = nullable_table_scan("t1")?;
+        let right = nullable_table_scan("t2")?;
+        let plan = LogicalPlanBuilder::from(left)
+            .join(
+                &right,
+                JoinType::Inner,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+            )?
+            .build()?;
+
+        let expected = "\
+        Inner Join: #t1.a = #t2.a\
+        \n  Filter: #t1.a IS NOT NULL\
+        \n    TableScan: t1 projection=None\
+        \n  Filter: #t2.a IS NOT NULL\
+        \n    TableScan: t2 projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+
+        // optimizing the plan again does not duplicate the predicates
+        let optimized = optimize_plan(&plan);
+        assert_optimized_plan_eq(&optimized, expected);
+        Ok(())
+    }
+
+    /// a predicate that does not reject nulls keeps the outer join
+    #[test]
+    fn filter_left_join_not_null_rejecting() -> Result<()> {
+        let left = nullable_table_scan("t1")?;
+        let right = nullable_table_scan("t2")?;
+        let plan = LogicalPlanBuilder::from(left)
+            .join(
+                &right,
+                JoinType::Left,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+            )?
+            .filter(col("t2.b").is_null())?
+            .build()?;
+
+        let expected = "\
+        Filter: #t2.b IS NULL\
+        \n  Left Join: #t1.a = #t2.a\
+        \n    TableScan: t1 projection=None\
+        \n    TableScan: t2 projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    /// a full join is converted to a left join by a predicate rejecting nulls of the left side
+    #[test]
+    fn filter_full_join_to_left_join() -> Result<()> {
+        let left = nullable_table_scan("t1")?;
+        let right = nullable_table_scan("t2")?;
+        let plan = LogicalPlanBuilder::from(left)
+            .join(
+                &right,
+                JoinType::Full,
+                (vec![Column::from_name("a")], vec![Column::from_name("a")]),
+            )?
+            .filter(col("t1.b").gt(lit(1u32)).or(col("t1.b").lt(lit(0u32))))?
+            .build()?;
+
+        let expected = "\
+        Left Join: #t1.a = #t2.a\
+        \n  Filter: #t1.b > UInt32(1) OR #t1.b < UInt32(0)\
+        \n    TableScan: t1 projection=None\
+        \n  TableScan: t2 projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
     struct PushDownProvider {
         pub filter_support: TableProviderFilterPushDown,
     }
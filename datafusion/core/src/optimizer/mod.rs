@@ -19,11 +19,13 @@
 //! some simple rules to a logical plan, such as "Projection Push Down" and "Type Coercion".
 
 #![allow(clippy::module_inception)]
+pub mod aggregate_push_down;
 pub mod common_subexpr_eliminate;
 pub mod eliminate_filter;
 pub mod eliminate_limit;
 pub mod filter_push_down;
 pub mod limit_push_down;
+pub mod multiple_distinct_to_groupby;
 pub mod optimizer;
 pub mod projection_push_down;
 pub mod simplify_expressions;
@@ -0,0 +1,462 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Multiple distinct to group by optimizer rule that evaluates distinct aggregates of
+//! different arguments with regular hash aggregates
+
+use crate::error::Result;
+use crate::execution::context::ExecutionProps;
+use crate::logical_plan::plan::{Aggregate, Projection, Values};
+use crate::logical_plan::{
+    coalesce, col, lit, when, DFField, DFSchema, Expr, ExprSchemable, LogicalPlan,
+    LogicalPlanBuilder,
+};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use crate::physical_plan::aggregates::AggregateFunction;
+use arrow::datatypes::DataType;
+use datafusion_expr::utils::columnize_expr;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Multiple distinct to group by optimizer rule, which rewrites an aggregate with
+/// distinct aggregates of different arguments, possibly next to regular aggregates,
+/// so that it no longer keeps a set of distinct values per group.
+///
+/// Each input row is expanded to one row per distinct argument, tagged with a
+/// grouping id, in which only that argument is not null, unless the row does not
+/// pass the filter of the aggregate. A row with grouping id 0 holds the arguments
+/// of the regular aggregates. A first aggregate removes the
+/// duplicate arguments, and partially evaluates the regular aggregates, after which
+/// a second aggregate evaluates the distinct aggregates, which ignore the nulls of
+/// the other grouping ids, and merges the regular aggregates.
+///
+///  ```text
+///    SELECT F1(DISTINCT a), F2(DISTINCT b), F3(c)
+///    ...
+///    GROUP BY k
+///
+///    Into
+///
+///    SELECT F1(alias1), F2(alias2), MERGE_F3(F3(alias3))
+///    FROM (
+///      SELECT k, grouping_id, alias1, alias2, F3(alias3)
+///      FROM (
+///        SELECT k, grouping_id,
+///          CASE WHEN grouping_id = 1 THEN a END AS alias1,
+///          CASE WHEN grouping_id = 2 THEN b END AS alias2,
+///          CASE WHEN grouping_id = 0 THEN c END AS alias3
+///        FROM (VALUES (0), (1), (2)) AS ids (grouping_id) CROSS JOIN ...
+///      )
+///      GROUP BY k, grouping_id, alias1, alias2
+///    )
+///    GROUP BY k
+///  ```
+#[derive(Default)]
+pub struct MultipleDistinctToGroupBy {}
+
+/// Name of the column that tells which aggregates the arguments of an expanded row
+/// are for
+const GROUPING_ID: &str = "grouping_id";
+
+impl MultipleDistinctToGroupBy {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for MultipleDistinctToGroupBy {
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        let plan = utils::optimize_children(self, plan, execution_props)?;
+        match &plan {
+            LogicalPlan::Aggregate(aggregate) if is_rewritable(aggregate) => {
+                rewrite_aggregate(aggregate)
+            }
+            _ => Ok(plan),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "multiple_distinct_to_groupby"
+    }
+}
+
+/// Returns the function that merges the results of `fun` over parts of a group
+fn merge_function(fun: &AggregateFunction) -> Option<AggregateFunction> {
+    match fun {
+        AggregateFunction::Count | AggregateFunction::Sum => Some(AggregateFunction::Sum),
+        AggregateFunction::Min => Some(AggregateFunction::Min),
+        AggregateFunction::Max => Some(AggregateFunction::Max),
+        _ => None,
+    }
+}
+
+/// Returns whether `aggregate` has distinct aggregates that the single distinct
+/// rewrite does not apply to, and all of its aggregates ignore nulls and the order
+/// of their input
+fn is_rewritable(aggregate: &Aggregate) -> bool {
+    let mut distinct_args = vec![];
+    let mut has_regular = false;
+    for expr in &aggregate.aggr_expr {
+        match expr {
+            Expr::AggregateFunction {
+                fun,
+                args,
+                distinct: true,
+                filter,
+                order_by,
+            } if args.len() == 1
+                && order_by.is_empty()
+                && matches!(
+                    fun,
+                    AggregateFunction::Count
+                        | AggregateFunction::Sum
+                        | AggregateFunction::Avg
+                        | AggregateFunction::Min
+                        | AggregateFunction::Max
+                ) =>
+            {
+                let arg = (&args[0], filter);
+                if !distinct_args.contains(&arg) {
+                    distinct_args.push(arg);
+                }
+            }
+            Expr::AggregateFunction {
+                fun,
+                args,
+                distinct: false,
+                order_by,
+                ..
+            } if args.len() == 1
+                && order_by.is_empty()
+                && merge_function(fun).is_some() =>
+            {
+                has_regular = true
+            }
+            _ => return false,
+        }
+    }
+    let is_single_distinct =
+        distinct_args.len() == 1 && distinct_args[0].1.is_none() && !has_regular;
+    !distinct_args.is_empty()
+        && !is_single_distinct
+        && !aggregate
+            .group_expr
+            .iter()
+            .any(|e| matches!(e, Expr::GroupingSet(_)))
+}
+
+fn rewrite_aggregate(aggregate: &Aggregate) -> Result<LogicalPlan> {
+    let Aggregate {
+        input,
+        group_expr,
+        aggr_expr,
+        schema,
+    } = aggregate;
+
+    // the distinct arguments, with the filters of their aggregates
+    let mut distinct_args = vec![];
+    let mut regular_exprs = vec![];
+    for expr in aggr_expr {
+        if let Expr::AggregateFunction {
+            args,
+            distinct,
+            filter,
+            ..
+        } = expr
+        {
+            let arg = (&args[0], filter);
+            if !*distinct {
+                regular_exprs.push(expr);
+            } else if !distinct_args.contains(&arg) {
+                distinct_args.push(arg);
+            }
+        }
+    }
+
+    // the grouping ids of the rows each input row is expanded to
+    let first_id = if regular_exprs.is_empty() { 1 } else { 0 };
+    let ids = Values {
+        schema: Arc::new(DFSchema::new_with_metadata(
+            vec![DFField::new(None, GROUPING_ID, DataType::UInt32, false)],
+            HashMap::new(),
+        )?),
+        values: (first_id..=distinct_args.len() as u32)
+            .map(|id| vec![lit(id)])
+            .collect(),
+    };
+
+    // whether an expanded row holds the argument of aggregates with the filter
+    let is_for = |id: usize, filter: &Option<Box<Expr>>| {
+        let is_id = col(GROUPING_ID).eq(lit(id as u32));
+        match filter {
+            Some(filter) => is_id.and(filter.as_ref().clone()),
+            None => is_id,
+        }
+    };
+    let mut expand_expr = group_expr.clone();
+    expand_expr.push(col(GROUPING_ID));
+    for (i, (arg, filter)) in distinct_args.iter().enumerate() {
+        let alias = format!("alias{}", i + 1);
+        let arg = when(is_for(i + 1, filter), (*arg).clone()).end()?;
+        expand_expr.push(arg.alias(&alias));
+    }
+    let mut partial_expr = vec![];
+    for (i, expr) in regular_exprs.iter().enumerate() {
+        if let Expr::AggregateFunction {
+            fun, args, filter, ..
+        } = expr
+        {
+            let alias = format!("alias{}", distinct_args.len() + i + 1);
+            let arg = when(is_for(0, filter), args[0].clone()).end()?;
+            expand_expr.push(arg.alias(&alias));
+            partial_expr.push(Expr::AggregateFunction {
+                fun: fun.clone(),
+                args: vec![col(&alias)],
+                distinct: false,
+                filter: None,
+                order_by: vec![],
+            });
+        }
+    }
+    let expanded = LogicalPlanBuilder::from(LogicalPlan::Values(ids))
+        .cross_join(input)?
+        .project(expand_expr)?
+        .build()?;
+
+    // remove the duplicate arguments of the distinct aggregates
+    let columnize = |exprs: &[Expr], plan: &LogicalPlan| {
+        exprs
+            .iter()
+            .map(|e| columnize_expr(e.clone(), plan.schema()))
+            .collect::<Vec<_>>()
+    };
+    let mut inner_group_expr = columnize(group_expr, &expanded);
+    inner_group_expr.push(col(GROUPING_ID));
+    inner_group_expr
+        .extend((1..=distinct_args.len()).map(|i| col(&format!("alias{}", i))));
+    let inner = LogicalPlanBuilder::from(expanded)
+        .aggregate(inner_group_expr, partial_expr.clone())?
+        .build()?;
+
+    let partial_columns = columnize(&partial_expr, &inner);
+    let mut partial_columns = partial_columns.into_iter();
+    let outer_aggr_expr = aggr_expr
+        .iter()
+        .map(|expr| match expr {
+            Expr::AggregateFunction {
+                fun,
+                args,
+                distinct: true,
+                filter,
+                ..
+            } => {
+                let i = distinct_args
+                    .iter()
+                    .position(|arg| arg == &(&args[0], filter))
+                    .unwrap();
+                Expr::AggregateFunction {
+                    fun: fun.clone(),
+                    args: vec![col(&format!("alias{}", i + 1))],
+                    distinct: false,
+                    filter: None,
+                    order_by: vec![],
+                }
+            }
+            Expr::AggregateFunction { fun, .. } => Expr::AggregateFunction {
+                fun: merge_function(fun).unwrap(),
+                args: vec![partial_columns.next().unwrap()],
+                distinct: false,
+                filter: None,
+                order_by: vec![],
+            },
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    let outer_group_expr = columnize(group_expr, &inner);
+    let outer = LogicalPlanBuilder::from(inner)
+        .aggregate(outer_group_expr.clone(), outer_aggr_expr.clone())?
+        .build()?;
+
+    // so the aggregates are displayed in the same way even after the rewrite
+    let outer_schema = outer.schema().clone();
+    let expr = outer_group_expr
+        .iter()
+        .chain(outer_aggr_expr.iter())
+        .zip(group_expr.iter().chain(aggr_expr.iter()))
+        .zip(schema.fields())
+        .map(|((outer_expr, expr), field)| {
+            let mut outer_expr = columnize_expr(outer_expr.clone(), &outer_schema);
+            // a count is 0, rather than null, for the empty input of a global
+            // aggregate
+            if matches!(
+                expr,
+                Expr::AggregateFunction {
+                    fun: AggregateFunction::Count,
+                    distinct: false,
+                    ..
+                }
+            ) {
+                outer_expr = coalesce(vec![outer_expr, lit(0u64)]);
+            }
+            if &outer_expr.get_type(&outer_schema)? != field.data_type() {
+                outer_expr = outer_expr.cast_to(field.data_type(), &outer_schema)?;
+            }
+            Ok(outer_expr.alias(field.name()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(LogicalPlan::Projection(Projection {
+        expr,
+        input: Arc::new(outer),
+        schema: schema.clone(),
+        alias: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{avg, count, count_distinct, max, sum};
+    use crate::test::*;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = MultipleDistinctToGroupBy::new();
+        let optimized_plan = rule
+            .optimize(plan, &ExecutionProps::new())
+            .expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    fn sum_distinct(expr: Expr) -> Expr {
+        Expr::AggregateFunction {
+            fun: AggregateFunction::Sum,
+            distinct: true,
+            args: vec![expr],
+            filter: None,
+            order_by: vec![],
+        }
+    }
+
+    #[test]
+    fn two_distinct_and_groupby() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![
+                    count_distinct(col("b")),
+                    count_distinct(col("c")),
+                    sum_distinct(col("c")),
+                ],
+            )?
+            .build()?;
+
+        let expected = "Projection: #test.a AS a, #COUNT(alias1) AS COUNT(DISTINCT test.b), #COUNT(alias2) AS COUNT(DISTINCT test.c), #SUM(alias2) AS SUM(DISTINCT test.c)\
+        \n  Aggregate: groupBy=[[#test.a]], aggr=[[COUNT(#alias1), COUNT(#alias2), SUM(#alias2)]]\
+        \n    Aggregate: groupBy=[[#test.a, #grouping_id, #alias1, #alias2]], aggr=[[]]\
+        \n      Projection: #test.a, #grouping_id, CASE WHEN #grouping_id = UInt32(1) THEN #test.b END AS alias1, CASE WHEN #grouping_id = UInt32(2) THEN #test.c END AS alias2\
+        \n        CrossJoin:\
+        \n          Values: (UInt32(1)), (UInt32(2))\
+        \n          TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_and_regular() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                Vec::<Expr>::new(),
+                vec![count_distinct(col("b")), count(col("c")), max(col("a"))],
+            )?
+            .build()?;
+
+        let expected = "Projection: #COUNT(alias1) AS COUNT(DISTINCT test.b), coalesce(#SUM(COUNT(alias2)), UInt64(0)) AS COUNT(test.c), #MAX(MAX(alias3)) AS MAX(test.a)\
+        \n  Aggregate: groupBy=[[]], aggr=[[COUNT(#alias1), SUM(#COUNT(alias2)), MAX(#MAX(alias3))]]\
+        \n    Aggregate: groupBy=[[#grouping_id, #alias1]], aggr=[[COUNT(#alias2), MAX(#alias3)]]\
+        \n      Projection: #grouping_id, CASE WHEN #grouping_id = UInt32(1) THEN #test.b END AS alias1, CASE WHEN #grouping_id = UInt32(0) THEN #test.c END AS alias2, CASE WHEN #grouping_id = UInt32(0) THEN #test.a END AS alias3\
+        \n        CrossJoin:\
+        \n          Values: (UInt32(0)), (UInt32(1))\
+        \n          TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_with_filter() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let filtered = Expr::AggregateFunction {
+            fun: AggregateFunction::Count,
+            distinct: true,
+            args: vec![col("b")],
+            filter: Some(Box::new(col("c").gt(lit(1u32)))),
+            order_by: vec![],
+        };
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(vec![col("a")], vec![count_distinct(col("b")), filtered])?
+            .build()?;
+
+        let expected = "Projection: #test.a AS a, #COUNT(alias1) AS COUNT(DISTINCT test.b), #COUNT(alias2) AS COUNT(DISTINCT test.b) FILTER (WHERE test.c > UInt32(1))\
+        \n  Aggregate: groupBy=[[#test.a]], aggr=[[COUNT(#alias1), COUNT(#alias2)]]\
+        \n    Aggregate: groupBy=[[#test.a, #grouping_id, #alias1, #alias2]], aggr=[[]]\
+        \n      Projection: #test.a, #grouping_id, CASE WHEN #grouping_id = UInt32(1) THEN #test.b END AS alias1, CASE WHEN #grouping_id = UInt32(2) AND #test.c > UInt32(1) THEN #test.b END AS alias2\
+        \n        CrossJoin:\
+        \n          Values: (UInt32(1)), (UInt32(2))\
+        \n          TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn single_distinct_or_unsupported_aggregate_unchanged() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        // left to the single distinct rewrite
+        let plan = LogicalPlanBuilder::from(table_scan.clone())
+            .aggregate(vec![col("a")], vec![count_distinct(col("b"))])?
+            .build()?;
+        let expected = "Aggregate: groupBy=[[#test.a]], aggr=[[COUNT(DISTINCT #test.b)]]\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+
+        // an average cannot be merged from the averages of parts of a group
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![count_distinct(col("b")), avg(col("c")), sum(col("c"))],
+            )?
+            .build()?;
+        let expected = "Aggregate: groupBy=[[#test.a]], aggr=[[COUNT(DISTINCT #test.b), AVG(#test.c), SUM(#test.c)]]\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+}
@@ -208,13 +208,18 @@ fn optimize_plan(
             on,
             join_type,
             join_constraint,
+            filter,
             null_equals_null,
+            null_aware,
             ..
         }) => {
             for (l, r) in on {
                 new_required_columns.insert(l.clone());
                 new_required_columns.insert(r.clone());
             }
+            if let Some(filter) = filter {
+                expr_to_columns(filter, &mut new_required_columns)?;
+            }
 
             let optimized_left = Arc::new(optimize_plan(
                 _optimizer,
@@ -244,8 +249,10 @@ fn optimize_plan(
                 join_type: *join_type,
                 join_constraint: *join_constraint,
                 on: on.clone(),
+                filter: filter.clone(),
                 schema: DFSchemaRef::new(schema),
                 null_equals_null: *null_equals_null,
+                null_aware: *null_aware,
             }))
         }
         LogicalPlan::Window(Window {
@@ -438,6 +445,30 @@ fn optimize_plan(
                 alias: alias.clone(),
             }))
         }
+        LogicalPlan::RecursiveQuery(_) => {
+            // every column of an iteration feeds the next one, so all the
+            // columns of both terms are required
+            let new_inputs = plan
+                .inputs()
+                .iter()
+                .map(|input_plan| {
+                    let required_columns = input_plan
+                        .schema()
+                        .fields()
+                        .iter()
+                        .map(|f| f.qualified_column())
+                        .collect();
+                    optimize_plan(
+                        _optimizer,
+                        input_plan,
+                        &required_columns,
+                        false,
+                        _execution_props,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            from_plan(plan, &[], &new_inputs)
+        }
         LogicalPlan::SubqueryAlias(SubqueryAlias { input, alias, .. }) => {
             match input.as_ref() {
                 LogicalPlan::TableScan(TableScan { table_name, .. }) => {
@@ -470,6 +501,7 @@ fn optimize_plan(
         // expressions in this node to the list of required columns
         LogicalPlan::Limit(_)
         | LogicalPlan::Offset(_)
+        | LogicalPlan::Unnest(_)
         | LogicalPlan::Filter { .. }
         | LogicalPlan::Repartition(_)
         | LogicalPlan::EmptyRelation(_)
@@ -122,6 +122,12 @@ impl OptimizerRule for SubqueryFilterToJoin {
                                 JoinType::Semi
                             };
 
+                            // `x NOT IN (...)` is unknown rather than true if `x`
+                            // or any of the subquery values is null
+                            let null_aware = *negated
+                                && (input.schema().field_from_column(&left_key)?.is_nullable()
+                                    || right_schema.field(0).is_nullable());
+
                             let schema = build_join_schema(
                                 optimized_input.schema(),
                                 right_schema,
@@ -132,10 +138,12 @@ impl OptimizerRule for SubqueryFilterToJoin {
                                 left: Arc::new(input),
                                 right: Arc::new(right_input),
                                 on: vec![(left_key, right_key)],
+                                filter: None,
                                 join_type,
                                 join_constraint: JoinConstraint::On,
                                 schema: Arc::new(schema),
                                 null_equals_null: false,
+                                null_aware,
                             }))
                         }
                         _ => Err(DataFusionError::Plan(
@@ -196,6 +204,8 @@ mod tests {
         Operator,
     };
     use crate::test::*;
+    use crate::test_util::scan_empty;
+    use arrow::datatypes::{DataType, Field, Schema};
 
     fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
         let rule = SubqueryFilterToJoin::new();
@@ -253,6 +263,27 @@ mod tests {
         Ok(())
     }
 
+    /// Test for NOT IN subquery filter with a nullable subquery column
+    #[test]
+    fn not_in_subquery_nullable() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("c", DataType::UInt32, true)]);
+        let subquery = scan_empty(Some("sq"), &schema, None)?.build()?;
+
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(not_in_subquery(col("c"), Arc::new(subquery)))?
+            .project(vec![col("test.b")])?
+            .build()?;
+
+        let expected = "Projection: #test.b [b:UInt32]\
+        \n  Anti Join: #test.c = #sq.c, null_aware=true [a:UInt32, b:UInt32, c:UInt32]\
+        \n    TableScan: test projection=None [a:UInt32, b:UInt32, c:UInt32]\
+        \n    TableScan: sq projection=None [c:UInt32;N]";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
     /// Test for several IN subquery expressions
     #[test]
     fn in_subquery_multiple() -> Result<()> {
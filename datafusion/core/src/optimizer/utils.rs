@@ -33,6 +33,8 @@ const CASE_EXPR_MARKER: &str = "__DATAFUSION_CASE_EXPR__";
 const CASE_ELSE_MARKER: &str = "__DATAFUSION_CASE_ELSE__";
 const WINDOW_PARTITION_MARKER: &str = "__DATAFUSION_WINDOW_PARTITION__";
 const WINDOW_SORT_MARKER: &str = "__DATAFUSION_WINDOW_SORT__";
+const AGGREGATE_FILTER_MARKER: &str = "__DATAFUSION_AGGREGATE_FILTER__";
+const AGGREGATE_SORT_MARKER: &str = "__DATAFUSION_AGGREGATE_SORT__";
 
 /// Convenience rule for writing optimizers: recursively invoke
 /// optimize on plan's children and then return a node of the same
@@ -72,8 +74,25 @@ pub fn expr_sub_expressions(expr: &Expr) -> Result<Vec<Expr>> {
         | Expr::GetIndexedField { expr, .. } => Ok(vec![expr.as_ref().to_owned()]),
         Expr::ScalarFunction { args, .. }
         | Expr::ScalarUDF { args, .. }
-        | Expr::AggregateFunction { args, .. }
         | Expr::AggregateUDF { args, .. } => Ok(args.clone()),
+        Expr::AggregateFunction {
+            args,
+            filter,
+            order_by,
+            ..
+        } => {
+            let mut expr_list: Vec<Expr> = vec![];
+            expr_list.extend(args.clone());
+            if let Some(filter) = filter {
+                expr_list.push(lit(AGGREGATE_FILTER_MARKER));
+                expr_list.push(filter.as_ref().to_owned());
+            }
+            if !order_by.is_empty() {
+                expr_list.push(lit(AGGREGATE_SORT_MARKER));
+                expr_list.extend(order_by.clone());
+            }
+            Ok(expr_list)
+        }
         Expr::GroupingSet(grouping_set) => match grouping_set {
             GroupingSet::Rollup(exprs) => Ok(exprs.clone()),
             GroupingSet::Cube(exprs) => Ok(exprs.clone()),
@@ -203,15 +222,29 @@ pub fn rewrite_expression(expr: &Expr, expressions: &[Expr]) -> Result<Expr> {
                     args: expressions[..partition_index].to_vec(),
                     partition_by: expressions[partition_index + 1..sort_index].to_vec(),
                     order_by: expressions[sort_index + 1..].to_vec(),
-                    window_frame: *window_frame,
+                    window_frame: window_frame.clone(),
                 })
             }
         }
-        Expr::AggregateFunction { fun, distinct, .. } => Ok(Expr::AggregateFunction {
-            fun: fun.clone(),
-            args: expressions.to_vec(),
-            distinct: *distinct,
-        }),
+        Expr::AggregateFunction { fun, distinct, .. } => {
+            let is_marker = |expr: &Expr, marker: &str| matches!(expr, Expr::Literal(ScalarValue::Utf8(Some(str))) if str == marker);
+            let filter_index = expressions
+                .iter()
+                .position(|expr| is_marker(expr, AGGREGATE_FILTER_MARKER));
+            let sort_index = expressions
+                .iter()
+                .position(|expr| is_marker(expr, AGGREGATE_SORT_MARKER));
+            let args_end = filter_index.or(sort_index).unwrap_or(expressions.len());
+            Ok(Expr::AggregateFunction {
+                fun: fun.clone(),
+                args: expressions[..args_end].to_vec(),
+                distinct: *distinct,
+                filter: filter_index.map(|i| Box::new(expressions[i + 1].clone())),
+                order_by: sort_index
+                    .map(|i| expressions[i + 1..].to_vec())
+                    .unwrap_or_default(),
+            })
+        }
         Expr::AggregateUDF { fun, .. } => Ok(Expr::AggregateUDF {
             fun: fun.clone(),
             args: expressions.to_vec(),
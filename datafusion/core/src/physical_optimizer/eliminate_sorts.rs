@@ -0,0 +1,264 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! EliminateSorts optimizer that removes the sorts whose input is already
+//! sorted as required, e.g. scans of files with a declared sort order
+
+use super::optimizer::PhysicalOptimizerRule;
+use super::utils::optimize_children;
+use crate::execution::context::SessionConfig;
+use crate::physical_plan::expressions::{Column, PhysicalSortExpr};
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::{error::Result, physical_plan::ExecutionPlan};
+use std::sync::Arc;
+
+/// Optimizer that removes a `SortExec` when the ordering of its input
+/// satisfies the sort. If the input has several partitions that each need to
+/// be sorted into a single one, they are merged with a
+/// `SortPreservingMergeExec` instead.
+#[derive(Default)]
+pub struct EliminateSorts {}
+
+impl EliminateSorts {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for EliminateSorts {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &SessionConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let plan = optimize_children(self, plan, config)?;
+
+        let sort = match plan.as_any().downcast_ref::<SortExec>() {
+            Some(sort) => sort,
+            None => return Ok(plan),
+        };
+        let input = sort.input();
+        if !ordering_satisfies(input.output_ordering(), sort.expr()) {
+            return Ok(plan);
+        }
+
+        if sort.preserve_partitioning()
+            || input.output_partitioning().partition_count() == 1
+        {
+            Ok(Arc::clone(input))
+        } else {
+            Ok(Arc::new(SortPreservingMergeExec::new(
+                sort.expr().to_vec(),
+                Arc::clone(input),
+            )))
+        }
+    }
+
+    fn name(&self) -> &str {
+        "eliminate_sorts"
+    }
+}
+
+/// Whether data sorted by `ordering` is also sorted by `required`, which is
+/// the case when `required` is a prefix of `ordering`.
+/// Only sort expressions on columns are compared.
+pub(crate) fn ordering_satisfies(
+    ordering: Option<&[PhysicalSortExpr]>,
+    required: &[PhysicalSortExpr],
+) -> bool {
+    let ordering = match ordering {
+        Some(ordering) if ordering.len() >= required.len() => ordering,
+        _ => return false,
+    };
+    ordering.iter().zip(required).all(|(provided, required)| {
+        let provided_column = provided.expr.as_any().downcast_ref::<Column>();
+        let required_column = required.expr.as_any().downcast_ref::<Column>();
+        match (provided_column, required_column) {
+            (Some(provided_column), Some(required_column)) => {
+                provided_column == required_column && provided.options == required.options
+            }
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+    use super::*;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::file_format::{FileScanConfig, ParquetExec};
+    use crate::physical_plan::filter::FilterExec;
+    use crate::physical_plan::projection::ProjectionExec;
+    use crate::physical_plan::{displayable, Statistics};
+    use crate::test::object_store::TestObjectStore;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Boolean, true),
+        ]))
+    }
+
+    fn sort_expr(name: &str, descending: bool) -> PhysicalSortExpr {
+        PhysicalSortExpr {
+            expr: col(name, &schema()).unwrap(),
+            options: SortOptions {
+                descending,
+                nulls_first: false,
+            },
+        }
+    }
+
+    /// A scan of `files` files, each being sorted by `a ASC NULLS LAST`
+    fn sorted_parquet_exec(files: usize) -> Arc<dyn ExecutionPlan> {
+        let names = (0..files).map(|i| format!("x{}", i)).collect::<Vec<_>>();
+        let files = names
+            .iter()
+            .map(|name| (name.as_str(), 100))
+            .collect::<Vec<_>>();
+        Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store: TestObjectStore::new_arc(&files),
+                file_schema: schema(),
+                file_groups: names
+                    .iter()
+                    .map(|name| vec![PartitionedFile::new(name.clone(), 100)])
+                    .collect(),
+                statistics: Statistics::default(),
+                pruned_files: 0,
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: Some(vec![sort_expr("a", false)]),
+                bucket_columns: None,
+            },
+            None,
+        ))
+    }
+
+    fn sort_exec(
+        sort_exprs: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Arc<dyn ExecutionPlan> {
+        Arc::new(SortExec::try_new(sort_exprs, input).unwrap())
+    }
+
+    fn trim_plan_display(plan: &str) -> Vec<&str> {
+        plan.split('\n')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Runs the sort elimination optimizer and asserts the plan against the expected
+    macro_rules! assert_optimized {
+        ($EXPECTED_LINES: expr, $PLAN: expr) => {
+            let expected_lines: Vec<&str> = $EXPECTED_LINES.iter().map(|s| *s).collect();
+
+            let optimized =
+                EliminateSorts::new().optimize($PLAN, &SessionConfig::new())?;
+
+            let plan = displayable(optimized.as_ref()).indent().to_string();
+            let actual_lines = trim_plan_display(&plan);
+
+            assert_eq!(
+                &expected_lines, &actual_lines,
+                "\n\nexpected:\n\n{:#?}\nactual:\n\n{:#?}\n\n",
+                expected_lines, actual_lines
+            );
+        };
+    }
+
+    #[test]
+    fn remove_satisfied_sort() -> Result<()> {
+        let filter = FilterExec::try_new(col("b", &schema())?, sorted_parquet_exec(1))?;
+        let plan = sort_exec(vec![sort_expr("a", false)], Arc::new(filter));
+
+        let expected = &[
+            "FilterExec: b@1",
+            "ParquetExec: limit=None, partitions=[x0], projection=[a, b]",
+        ];
+        assert_optimized!(expected, plan);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_sorted_partitions() -> Result<()> {
+        let plan = sort_exec(vec![sort_expr("a", false)], sorted_parquet_exec(2));
+
+        let expected = &[
+            "SortPreservingMergeExec: [a@0 ASC NULLS LAST]",
+            "ParquetExec: limit=None, partitions=[x0, x1], projection=[a, b]",
+        ];
+        assert_optimized!(expected, plan);
+        Ok(())
+    }
+
+    #[test]
+    fn keep_unsatisfied_sorts() -> Result<()> {
+        let descending = sort_exec(vec![sort_expr("a", true)], sorted_parquet_exec(1));
+        let expected = &[
+            "SortExec: [a@0 DESC NULLS LAST]",
+            "ParquetExec: limit=None, partitions=[x0], projection=[a, b]",
+        ];
+        assert_optimized!(expected, descending);
+
+        let longer = sort_exec(
+            vec![sort_expr("a", false), sort_expr("b", false)],
+            sorted_parquet_exec(1),
+        );
+        let expected = &[
+            "SortExec: [a@0 ASC NULLS LAST,b@1 ASC NULLS LAST]",
+            "ParquetExec: limit=None, partitions=[x0], projection=[a, b]",
+        ];
+        assert_optimized!(expected, longer);
+        Ok(())
+    }
+
+    #[test]
+    fn ordering_through_projection() -> Result<()> {
+        // the column `a` moves from index 0 to 1
+        let projection = ProjectionExec::try_new(
+            vec![
+                (col("b", &schema())?, "b".to_string()),
+                (col("a", &schema())?, "a".to_string()),
+            ],
+            sorted_parquet_exec(1),
+        )?;
+        let projected_sort = PhysicalSortExpr {
+            expr: Arc::new(Column::new("a", 1)),
+            options: SortOptions {
+                descending: false,
+                nulls_first: false,
+            },
+        };
+        let plan = sort_exec(vec![projected_sort], Arc::new(projection));
+
+        let expected = &[
+            "ProjectionExec: expr=[b@1 as b, a@0 as a]",
+            "ParquetExec: limit=None, partitions=[x0], projection=[a, b]",
+        ];
+        assert_optimized!(expected, plan);
+        Ok(())
+    }
+}
@@ -69,7 +69,7 @@ fn should_swap_join_order(left: &dyn ExecutionPlan, right: &dyn ExecutionPlan) -
 fn supports_swap(join_type: JoinType) -> bool {
     match join_type {
         JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => true,
-        JoinType::Semi | JoinType::Anti => false,
+        JoinType::Semi | JoinType::Anti | JoinType::AsOf => false,
     }
 }
 
@@ -133,7 +133,8 @@ impl PhysicalOptimizerRule for HashBuildProbeOrder {
                     &swap_join_type(*hash_join.join_type()),
                     *hash_join.partition_mode(),
                     hash_join.null_equals_null(),
-                )?;
+                )?
+                .with_filter(hash_join.filter().map(|filter| filter.swap()))?;
                 let proj = ProjectionExec::try_new(
                     swap_reverting_projection(&*left.schema(), &*right.schema()),
                     Arc::new(new_join),
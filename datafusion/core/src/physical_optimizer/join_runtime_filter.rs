@@ -0,0 +1,347 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! JoinRuntimeFilter optimizer that lets hash joins publish the keys of their
+//! build side to the scans and filters of their probe side
+use std::sync::Arc;
+
+use super::optimizer::PhysicalOptimizerRule;
+use super::utils::optimize_children;
+use crate::execution::context::SessionConfig;
+use crate::logical_plan::{JoinType, Operator};
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::expressions::{BinaryExpr, Column};
+use crate::physical_plan::file_format::ParquetExec;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::hash_join::{HashJoinExec, PartitionMode};
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::recursive_query::RecursiveQueryExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::runtime_filter::{RuntimeFilter, RuntimeFilterExpr};
+use crate::physical_plan::{with_new_children_if_necessary, ExecutionPlan};
+use crate::{error::Result, physical_plan::PhysicalExpr};
+
+/// Optimizer rule that creates a [`RuntimeFilter`] for each [`HashJoinExec`] whose
+/// unmatched probe side rows are discarded, and pushes it down the probe side to any
+/// [`FilterExec`] and [`ParquetExec`] that it can be applied to
+#[derive(Default)]
+pub struct JoinRuntimeFilter {}
+
+impl JoinRuntimeFilter {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for JoinRuntimeFilter {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &SessionConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.runtime_join_filters {
+            return Ok(plan);
+        }
+
+        if let Some(query) = plan.as_any().downcast_ref::<RecursiveQueryExec>() {
+            // A runtime filter is only published once, but the recursive term
+            // is executed again for each iteration
+            let static_term = self.optimize(query.static_term().clone(), config)?;
+            let recursive_term = query.recursive_term().clone();
+            return with_new_children_if_necessary(
+                plan,
+                vec![static_term, recursive_term],
+            );
+        }
+
+        let plan = optimize_children(self, plan, config)?;
+        let join = match plan.as_any().downcast_ref::<HashJoinExec>() {
+            Some(join) if join.runtime_filter().is_none() => join,
+            _ => return Ok(plan),
+        };
+
+        // Unmatched probe side rows must not be part of the output
+        let filterable = matches!(
+            join.join_type(),
+            JoinType::Inner | JoinType::Left | JoinType::Semi | JoinType::Anti
+        );
+        // A null-aware anti join depends on the null keys of the probe side
+        if !filterable || *join.null_equals_null() || join.null_aware() {
+            return Ok(plan);
+        }
+
+        let partitions = match join.partition_mode() {
+            PartitionMode::CollectLeft => 1,
+            PartitionMode::Partitioned => {
+                join.left().output_partitioning().partition_count()
+            }
+        };
+        let filter = Arc::new(RuntimeFilter::new(join.on().len(), partitions));
+        let columns = join.on().iter().map(|(_, right)| right.clone()).collect();
+        let expr = Arc::new(RuntimeFilterExpr::new(filter.clone(), columns));
+
+        match push_down(join.right().clone(), &expr)? {
+            Some(right) => {
+                let join = HashJoinExec::try_new(
+                    join.left().clone(),
+                    right,
+                    join.on().to_vec(),
+                    join.join_type(),
+                    *join.partition_mode(),
+                    join.null_equals_null(),
+                )?
+                .with_filter(join.filter().cloned())?
+                .with_runtime_filter(filter);
+                Ok(Arc::new(join))
+            }
+            None => Ok(plan),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "join_runtime_filter"
+    }
+}
+
+/// Pushes `expr` down `plan`, returning the rewritten plan if it was
+/// applied to at least one operator
+fn push_down(
+    plan: Arc<dyn ExecutionPlan>,
+    expr: &Arc<RuntimeFilterExpr>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let any = plan.as_any();
+
+    if let Some(parquet) = any.downcast_ref::<ParquetExec>() {
+        let parquet = parquet.clone().with_runtime_filter(expr.clone());
+        return Ok(Some(Arc::new(parquet)));
+    }
+
+    if let Some(filter) = any.downcast_ref::<FilterExec>() {
+        let input = push_down(filter.input().clone(), expr)?
+            .unwrap_or_else(|| filter.input().clone());
+        let predicate = Arc::new(BinaryExpr::new(
+            filter.predicate().clone(),
+            Operator::And,
+            expr.clone() as Arc<dyn PhysicalExpr>,
+        ));
+        return Ok(Some(Arc::new(FilterExec::try_new(predicate, input)?)));
+    }
+
+    if let Some(projection) = any.downcast_ref::<ProjectionExec>() {
+        // Only push through projections of the key columns
+        let columns = expr
+            .columns()
+            .iter()
+            .map(|c| {
+                projection.expr()[c.index()]
+                    .0
+                    .as_any()
+                    .downcast_ref::<Column>()
+                    .cloned()
+            })
+            .collect::<Option<Vec<_>>>();
+
+        return match columns {
+            Some(columns) => {
+                let expr = Arc::new(expr.with_columns(columns));
+                push_down_child(plan.clone(), projection.input().clone(), &expr)
+            }
+            None => Ok(None),
+        };
+    }
+
+    // Operators that neither change their schema nor drop unmatched rows
+    let passthrough = any.downcast_ref::<CoalesceBatchesExec>().is_some()
+        || any.downcast_ref::<CoalescePartitionsExec>().is_some()
+        || any.downcast_ref::<RepartitionExec>().is_some();
+
+    if passthrough {
+        let input = plan.children()[0].clone();
+        return push_down_child(plan, input, expr);
+    }
+
+    Ok(None)
+}
+
+/// Pushes `expr` down `child` the single child of `plan`
+fn push_down_child(
+    plan: Arc<dyn ExecutionPlan>,
+    child: Arc<dyn ExecutionPlan>,
+    expr: &Arc<RuntimeFilterExpr>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    match push_down(child, expr)? {
+        Some(child) => Ok(Some(with_new_children_if_necessary(plan, vec![child])?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_sorted_eq;
+    use crate::datafusion_data_access::object_store::local::LocalFileSystem;
+    use crate::physical_plan::expressions::{col, lit};
+    use crate::physical_plan::file_format::FileScanConfig;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::{collect, displayable, Statistics};
+    use crate::prelude::SessionContext;
+    use crate::scalar::ScalarValue;
+    use arrow::array::{ArrayRef, Int32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+
+    fn memory_exec(name: &str, values: Vec<i32>) -> Arc<dyn ExecutionPlan> {
+        let array = Arc::new(Int32Array::from(values)) as ArrayRef;
+        let batch = RecordBatch::try_from_iter(vec![(name, array)]).unwrap();
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    fn parquet_exec(name: &str) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new(name, DataType::Int32, true)]));
+        Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_schema: schema,
+                file_groups: vec![vec![]],
+                statistics: Statistics::default(),
+                pruned_files: 0,
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: None,
+                bucket_columns: None,
+            },
+            None,
+        ))
+    }
+
+    fn hash_join(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        join_type: JoinType,
+    ) -> Arc<dyn ExecutionPlan> {
+        let on = vec![(
+            Column::new_with_schema("a", &left.schema()).unwrap(),
+            Column::new_with_schema("b", &right.schema()).unwrap(),
+        )];
+        Arc::new(
+            HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                &join_type,
+                PartitionMode::CollectLeft,
+                &false,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let optimized = JoinRuntimeFilter::new()
+            .optimize(plan, &SessionConfig::new())
+            .unwrap();
+        let formatted = displayable(optimized.as_ref()).indent().to_string();
+        formatted.trim().lines().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn push_to_parquet_through_filter() {
+        let right = parquet_exec("b");
+        let predicate = col("b", &right.schema()).unwrap();
+        let predicate = Arc::new(BinaryExpr::new(
+            predicate,
+            Operator::Gt,
+            lit(ScalarValue::Int32(Some(1))),
+        ));
+        let right = Arc::new(FilterExec::try_new(predicate, right).unwrap());
+        let plan = hash_join(memory_exec("a", vec![1]), right, JoinType::Inner);
+
+        let expected = vec![
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(Column { name: \"a\", index: 0 }, Column { name: \"b\", index: 0 })], runtime_filter=true",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  FilterExec: b@0 > 1 AND runtime_filter(b@0)",
+            "    ParquetExec: limit=None, partitions=[], projection=[b], runtime_filters=[runtime_filter(b@0)]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn no_push_for_right_join() {
+        let plan = hash_join(
+            memory_exec("a", vec![1]),
+            parquet_exec("b"),
+            JoinType::Right,
+        );
+        let expected = vec![
+            "HashJoinExec: mode=CollectLeft, join_type=Right, on=[(Column { name: \"a\", index: 0 }, Column { name: \"b\", index: 0 })]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  ParquetExec: limit=None, partitions=[], projection=[b]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn no_push_without_target() {
+        let plan = hash_join(
+            memory_exec("a", vec![1]),
+            memory_exec("b", vec![1]),
+            JoinType::Inner,
+        );
+        let expected = vec![
+            "HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(Column { name: \"a\", index: 0 }, Column { name: \"b\", index: 0 })]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+            "  MemoryExec: partitions=1, partition_sizes=[1]",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[tokio::test]
+    async fn filter_probe_rows() -> Result<()> {
+        let right = memory_exec("b", vec![1, 2, 3, 4, 5, 6]);
+        let predicate = col("b", &right.schema()).unwrap();
+        let predicate = Arc::new(BinaryExpr::new(
+            predicate,
+            Operator::Gt,
+            lit(ScalarValue::Int32(Some(1))),
+        ));
+        let right = Arc::new(FilterExec::try_new(predicate, right).unwrap());
+        let plan = hash_join(memory_exec("a", vec![2, 4]), right, JoinType::Inner);
+
+        let plan = JoinRuntimeFilter::new().optimize(plan, &SessionConfig::new())?;
+        let task_ctx = SessionContext::new().task_ctx();
+        let batches = collect(plan.clone(), task_ctx).await?;
+
+        // The filter below the join should only have output the matching rows
+        let filter_metrics = plan.children()[1].metrics().unwrap();
+        assert_eq!(filter_metrics.output_rows(), Some(2));
+
+        let expected = vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 2 | 2 |",
+            "| 4 | 4 |",
+            "+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -20,7 +20,9 @@
 
 pub mod aggregate_statistics;
 pub mod coalesce_batches;
+pub mod eliminate_sorts;
 pub mod hash_build_probe_order;
+pub mod join_runtime_filter;
 pub mod merge_exec;
 pub mod optimizer;
 pub mod pruning;
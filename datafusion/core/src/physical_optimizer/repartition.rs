@@ -19,6 +19,7 @@
 use std::sync::Arc;
 
 use super::optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::file_format::ArrowExec;
 use crate::physical_plan::Partitioning::*;
 use crate::physical_plan::{
     repartition::RepartitionExec, with_new_children_if_necessary, ExecutionPlan,
@@ -196,14 +197,26 @@ fn optimize_partitions(
         with_new_children_if_necessary(plan, children)?
     };
 
+    // split the files of Arrow scans into byte ranges rather than
+    // redistributing their output
+    let new_plan = match new_plan.as_any().downcast_ref::<ArrowExec>() {
+        Some(exec) if would_benefit && can_reorder => {
+            match exec.repartitioned(target_partitions) {
+                Some(exec) => Arc::new(exec),
+                None => new_plan,
+            }
+        }
+        _ => new_plan,
+    };
+
     // decide if we should bother trying to repartition the output of this plan
     let could_repartition = match new_plan.output_partitioning() {
         // Apply when underlying node has less than `self.target_partitions` amount of concurrency
         RoundRobinBatch(x) => x < target_partitions,
         UnknownPartitioning(x) => x < target_partitions,
-        // we don't want to introduce partitioning after hash partitioning
-        // as the plan will likely depend on this
-        Hash(_, _) => false,
+        // we don't want to introduce partitioning after hash or range
+        // partitioning as the plan will likely depend on this
+        Hash(_, _) | Range(_, _) => false,
     };
 
     if would_benefit && could_repartition && can_reorder {
@@ -264,9 +277,12 @@ mod tests {
                 file_schema: schema(),
                 file_groups: vec![vec![PartitionedFile::new("x".to_string(), 100)]],
                 statistics: Statistics::default(),
+                pruned_files: 0,
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
+                bucket_columns: None,
             },
             None,
         ))
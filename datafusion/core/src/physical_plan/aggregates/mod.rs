@@ -46,7 +46,9 @@ mod row_hash;
 use crate::physical_plan::aggregates::row_hash::GroupedHashAggregateStreamV2;
 pub use datafusion_expr::AggregateFunction;
 use datafusion_physical_expr::aggregate::row_accumulator::RowAccumulator;
-pub use datafusion_physical_expr::expressions::create_aggregate_expr;
+pub use datafusion_physical_expr::expressions::{
+    create_aggregate_expr, create_aggregate_expr_with_clauses,
+};
 use datafusion_row::{row_supported, RowType};
 
 /// Hash aggregate modes
@@ -167,7 +169,29 @@ impl ExecutionPlan for AggregateExec {
 
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        self.input.output_partitioning()
+        // the input hash partitioning holds if all its columns are grouped on
+        match self.input.output_partitioning() {
+            Partitioning::Hash(hash_exprs, n) => hash_exprs
+                .iter()
+                .map(|hash_expr| {
+                    let column = hash_expr.as_any().downcast_ref::<Column>()?;
+                    self.group_expr
+                        .iter()
+                        .enumerate()
+                        .find_map(|(index, (e, name))| {
+                            let e = e.as_any().downcast_ref::<Column>()?;
+                            (e.index() == column.index()).then(|| {
+                                Arc::new(Column::new(name, index))
+                                    as Arc<dyn PhysicalExpr>
+                            })
+                        })
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|hash_exprs| Partitioning::Hash(hash_exprs, n))
+                .unwrap_or(Partitioning::UnknownPartitioning(n)),
+            Partitioning::Range(_, n) => Partitioning::UnknownPartitioning(n),
+            partitioning => partitioning,
+        }
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
@@ -0,0 +1,375 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The hash that assigns rows to partitions in a [`Partitioning::Hash`], and
+//! the rows of bucketed tables to their bucket. Unlike the hashes of the
+//! hash tables of joins and aggregations, its values are part of the layout
+//! of the files of bucketed tables, so they must not change across platforms
+//! and releases.
+//!
+//! [`Partitioning::Hash`]: crate::physical_plan::Partitioning::Hash
+
+use crate::error::{DataFusionError, Result};
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Date32Array, Date64Array, DecimalArray,
+    DictionaryArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+    Int8Array, LargeStringArray, StringArray, TimestampMicrosecondArray,
+    TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
+    UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow::compute::take;
+use arrow::datatypes::{
+    ArrowDictionaryKeyType, ArrowNativeType, DataType, Int16Type, Int32Type, Int64Type,
+    Int8Type, TimeUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use std::convert::TryInto;
+
+/// The version of the hash computed by [`create_bucket_hashes`]. It changes
+/// whenever the hash of any value changes, and the files of a bucketed table
+/// written with another version are not trusted to be hash partitioned.
+pub const BUCKET_HASH_VERSION: u32 = 1;
+
+/// The seed of the hash of the first column of a row
+const BUCKET_HASH_SEED: u32 = 42;
+
+/// Computes the hash of the rows of `arrays`, as defined by version 1 of the
+/// bucket hash:
+///
+/// - The hash of a row is the 32-bit x86 MurmurHash3 of the bytes of the value
+///   of its last column, seeded with the hash of its previous columns. The
+///   seed of the first column is 42.
+/// - A null value leaves the hash of the row unchanged.
+/// - Integers, dates and timestamps are hashed as the 8 little-endian bytes of
+///   their value as an `i64`, or as an `u64` if they are unsigned.
+/// - Floating point numbers are hashed as the 8 little-endian bytes of their
+///   value as an `f64`, with -0.0 hashed as 0.0 and every NaN as the same NaN.
+/// - Decimals are hashed as the 16 little-endian bytes of their unscaled value.
+/// - Booleans are hashed as a single byte, 1 for true and 0 for false.
+/// - Strings are hashed as their UTF-8 bytes.
+/// - Dictionaries are hashed as their values.
+///
+/// The number of rows to hash is determined by `hashes_buffer.len()`.
+/// `hashes_buffer` should be pre-sized appropriately
+pub fn create_bucket_hashes<'a>(
+    arrays: &[ArrayRef],
+    hashes_buffer: &'a mut Vec<u32>,
+) -> Result<&'a mut Vec<u32>> {
+    for hash in hashes_buffer.iter_mut() {
+        *hash = BUCKET_HASH_SEED;
+    }
+    for array in arrays {
+        hash_array(array, hashes_buffer)?;
+    }
+    Ok(hashes_buffer)
+}
+
+/// The bucket of a row with the hash `hash` among `num_buckets` buckets
+pub fn bucket_of(hash: u32, num_buckets: usize) -> usize {
+    (hash as u64 % num_buckets as u64) as usize
+}
+
+macro_rules! hash_integers {
+    ($array_type:ident, $native:ty, $array:ident, $hashes:ident) => {{
+        let array = $array.as_any().downcast_ref::<$array_type>().unwrap();
+        hash_values(
+            array
+                .iter()
+                .map(|v| v.map(|v| (v as $native).to_le_bytes())),
+            $hashes,
+        )
+    }};
+}
+
+macro_rules! hash_floats {
+    ($array_type:ident, $array:ident, $hashes:ident) => {{
+        let array = $array.as_any().downcast_ref::<$array_type>().unwrap();
+        hash_values(
+            array.iter().map(|v| v.map(|v| float_bytes(v as f64))),
+            $hashes,
+        )
+    }};
+}
+
+macro_rules! hash_strings {
+    ($array_type:ident, $array:ident, $hashes:ident) => {{
+        let array = $array.as_any().downcast_ref::<$array_type>().unwrap();
+        hash_values(array.iter(), $hashes)
+    }};
+}
+
+/// Updates the hashes of the rows with the values of `array`
+fn hash_array(array: &ArrayRef, hashes: &mut [u32]) -> Result<()> {
+    match array.data_type() {
+        DataType::Null => {}
+        DataType::Boolean => {
+            let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+            hash_values(array.iter().map(|v| v.map(|v| [v as u8])), hashes)
+        }
+        DataType::Int8 => hash_integers!(Int8Array, i64, array, hashes),
+        DataType::Int16 => hash_integers!(Int16Array, i64, array, hashes),
+        DataType::Int32 => hash_integers!(Int32Array, i64, array, hashes),
+        DataType::Int64 => hash_integers!(Int64Array, i64, array, hashes),
+        DataType::UInt8 => hash_integers!(UInt8Array, u64, array, hashes),
+        DataType::UInt16 => hash_integers!(UInt16Array, u64, array, hashes),
+        DataType::UInt32 => hash_integers!(UInt32Array, u64, array, hashes),
+        DataType::UInt64 => hash_integers!(UInt64Array, u64, array, hashes),
+        DataType::Date32 => hash_integers!(Date32Array, i64, array, hashes),
+        DataType::Date64 => hash_integers!(Date64Array, i64, array, hashes),
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            hash_integers!(TimestampSecondArray, i64, array, hashes)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            hash_integers!(TimestampMillisecondArray, i64, array, hashes)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            hash_integers!(TimestampMicrosecondArray, i64, array, hashes)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            hash_integers!(TimestampNanosecondArray, i64, array, hashes)
+        }
+        DataType::Float32 => hash_floats!(Float32Array, array, hashes),
+        DataType::Float64 => hash_floats!(Float64Array, array, hashes),
+        DataType::Decimal(_, _) => {
+            let array = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+            hash_values(
+                (0..array.len())
+                    .map(|i| (!array.is_null(i)).then(|| array.value(i).to_le_bytes())),
+                hashes,
+            )
+        }
+        DataType::Utf8 => hash_strings!(StringArray, array, hashes),
+        DataType::LargeUtf8 => hash_strings!(LargeStringArray, array, hashes),
+        DataType::Dictionary(key_type, _) => match key_type.as_ref() {
+            DataType::Int8 => hash_dictionary::<Int8Type>(array, hashes)?,
+            DataType::Int16 => hash_dictionary::<Int16Type>(array, hashes)?,
+            DataType::Int32 => hash_dictionary::<Int32Type>(array, hashes)?,
+            DataType::Int64 => hash_dictionary::<Int64Type>(array, hashes)?,
+            DataType::UInt8 => hash_dictionary::<UInt8Type>(array, hashes)?,
+            DataType::UInt16 => hash_dictionary::<UInt16Type>(array, hashes)?,
+            DataType::UInt32 => hash_dictionary::<UInt32Type>(array, hashes)?,
+            DataType::UInt64 => hash_dictionary::<UInt64Type>(array, hashes)?,
+            other => {
+                return Err(DataFusionError::Internal(format!(
+                    "Invalid dictionary key type in bucket hasher: {}",
+                    other
+                )))
+            }
+        },
+        other => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Unsupported data type in bucket hasher: {}",
+                other
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Updates the hashes of the rows with the values of the dictionary `array`
+fn hash_dictionary<K: ArrowDictionaryKeyType>(
+    array: &ArrayRef,
+    hashes: &mut [u32],
+) -> Result<()> {
+    let array = array.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
+    let values = array.values();
+    let keys = array
+        .keys()
+        .iter()
+        .map(|key| {
+            key.map(|key| {
+                key.to_usize().ok_or_else(|| {
+                    DataFusionError::Internal(format!(
+                        "Can not convert key value {:?} to usize in dictionary of type {:?}",
+                        key,
+                        array.data_type()
+                    ))
+                })
+            })
+            .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let seed = match hashes.first() {
+        Some(seed) => *seed,
+        None => return Ok(()),
+    };
+    if hashes.iter().any(|hash| *hash != seed) {
+        // the value of a row is hashed with the hash of its previous columns
+        let indices = keys
+            .iter()
+            .map(|key| key.map(|key| key as u64))
+            .collect::<UInt64Array>();
+        return hash_array(&take(values.as_ref(), &indices, None)?, hashes);
+    }
+
+    // All the rows have the same seed, as for the first column, so each
+    // dictionary value is hashed once
+    let mut value_hashes = vec![seed; values.len()];
+    hash_array(values, &mut value_hashes)?;
+    for (hash, key) in hashes.iter_mut().zip(keys) {
+        if let Some(key) = key {
+            *hash = value_hashes[key];
+        }
+    }
+    Ok(())
+}
+
+/// Updates the hashes of the rows with their values, which are skipped if null
+fn hash_values<V: AsRef<[u8]>>(
+    values: impl Iterator<Item = Option<V>>,
+    hashes: &mut [u32],
+) {
+    for (hash, value) in hashes.iter_mut().zip(values) {
+        if let Some(value) = value {
+            *hash = murmur3_32(value.as_ref(), *hash);
+        }
+    }
+}
+
+/// The bytes a floating point number is hashed as
+fn float_bytes(value: f64) -> [u8; 8] {
+    let value = if value == 0.0 {
+        0.0
+    } else if value.is_nan() {
+        f64::NAN
+    } else {
+        value
+    };
+    value.to_le_bytes()
+}
+
+/// The 32-bit x86 variant of MurmurHash3 of `data`
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+    let mut hash = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        hash ^= mix(u32::from_le_bytes(block.try_into().unwrap()));
+        hash = hash
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .enumerate()
+            .fold(0u32, |k, (i, byte)| k | (*byte as u32) << (8 * i));
+        hash ^= mix(k);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::DictionaryArray;
+    use arrow::datatypes::Int8Type;
+    use std::sync::Arc;
+
+    #[test]
+    fn murmur3_reference_values() {
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"", 1), 0x514e_28b7);
+        assert_eq!(murmur3_32(b"", 0xffff_ffff), 0x81f1_6f39);
+        assert_eq!(murmur3_32(&[0, 0, 0, 0], 0), 0x2362_f9de);
+        assert_eq!(murmur3_32(b"aaaa", 0x9747_b28c), 0x5a97_808a);
+        assert_eq!(murmur3_32(b"Hello, world!", 0x9747_b28c), 0x2488_4cba);
+        assert_eq!(
+            murmur3_32(b"The quick brown fox jumps over the lazy dog", 0x9747_b28c),
+            0x2fa8_26cd
+        );
+    }
+
+    fn bucket_hashes(arrays: &[ArrayRef]) -> Result<Vec<u32>> {
+        let mut hashes = vec![0; arrays[0].len()];
+        create_bucket_hashes(arrays, &mut hashes)?;
+        Ok(hashes)
+    }
+
+    #[test]
+    fn bucket_hashes_of_equal_values() -> Result<()> {
+        // integers hash alike whatever their width
+        let ints = bucket_hashes(&[Arc::new(Int64Array::from(vec![Some(1), None]))])?;
+        let small_ints =
+            bucket_hashes(&[Arc::new(Int8Array::from(vec![Some(1), None]))])?;
+        assert_eq!(ints, small_ints);
+        assert_eq!(ints[0], murmur3_32(&1i64.to_le_bytes(), 42));
+        // nulls leave the seed unchanged
+        assert_eq!(ints[1], 42);
+
+        let floats = bucket_hashes(&[Arc::new(Float64Array::from(vec![
+            0.0,
+            -0.0,
+            f64::NAN,
+            -f64::NAN,
+        ]))])?;
+        assert_eq!(floats[0], floats[1]);
+        assert_eq!(floats[2], floats[3]);
+
+        let strings: ArrayRef = Arc::new(StringArray::from(vec!["a", "b", "a"]));
+        let dictionary: ArrayRef = Arc::new(
+            vec!["a", "b", "a"]
+                .into_iter()
+                .collect::<DictionaryArray<Int8Type>>(),
+        );
+        assert_eq!(bucket_hashes(&[strings])?, bucket_hashes(&[dictionary])?);
+        Ok(())
+    }
+
+    #[test]
+    fn bucket_hashes_of_rows() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        let b: ArrayRef = Arc::new(StringArray::from(vec![Some("x"), None]));
+        let hashes = bucket_hashes(&[a, b])?;
+        assert_eq!(
+            hashes[0],
+            murmur3_32(b"x", murmur3_32(&1i64.to_le_bytes(), 42))
+        );
+        assert_eq!(hashes[1], murmur3_32(&2i64.to_le_bytes(), 42));
+        assert_eq!(bucket_of(7, 4), 3);
+
+        // dictionaries hash as their values in any column
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let strings: ArrayRef =
+            Arc::new(StringArray::from(vec![Some("x"), None, Some("x")]));
+        let dictionary: ArrayRef = Arc::new(
+            vec![Some("x"), None, Some("x")]
+                .into_iter()
+                .collect::<DictionaryArray<Int8Type>>(),
+        );
+        assert_eq!(
+            bucket_hashes(&[a.clone(), strings.clone()])?,
+            bucket_hashes(&[a, dictionary.clone()])?
+        );
+        assert_eq!(
+            bucket_hashes(&[strings.clone(), strings])?,
+            bucket_hashes(&[dictionary.clone(), dictionary])?
+        );
+        Ok(())
+    }
+}
@@ -119,15 +119,11 @@ impl ExecutionPlan for CoalesceBatchesExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        Ok(Box::pin(CoalesceBatchesStream {
-            input: self.input.execute(partition, context)?,
-            schema: self.input.schema(),
-            target_batch_size: self.target_batch_size,
-            buffer: Vec::new(),
-            buffered_rows: 0,
-            is_closed: false,
-            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
-        }))
+        Ok(Box::pin(CoalesceBatchesStream::new(
+            self.input.execute(partition, context)?,
+            self.target_batch_size,
+            BaselineMetrics::new(&self.metrics, partition),
+        )))
     }
 
     fn fmt_as(
@@ -155,7 +151,9 @@ impl ExecutionPlan for CoalesceBatchesExec {
     }
 }
 
-struct CoalesceBatchesStream {
+/// Stream combining the batches of its input into batches of at least
+/// `target_batch_size` rows
+pub(crate) struct CoalesceBatchesStream {
     /// The input plan
     input: SendableRecordBatchStream,
     /// The input schema
@@ -190,6 +188,22 @@ impl Stream for CoalesceBatchesStream {
 }
 
 impl CoalesceBatchesStream {
+    pub(crate) fn new(
+        input: SendableRecordBatchStream,
+        target_batch_size: usize,
+        baseline_metrics: BaselineMetrics,
+    ) -> Self {
+        Self {
+            schema: input.schema(),
+            input,
+            target_batch_size,
+            buffer: Vec::new(),
+            buffered_rows: 0,
+            is_closed: false,
+            baseline_metrics,
+        }
+    }
+
     fn poll_next_inner(
         self: &mut Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -0,0 +1,599 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan for reading Arrow IPC files
+
+use crate::datasource::listing::{FileRange, PartitionedFile};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::{SessionState, TaskContext};
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use crate::scalar::ScalarValue;
+use arrow::datatypes::SchemaRef;
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::ipc;
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use datafusion_data_access::object_store::{ObjectReader, ObjectStore};
+use futures::{Stream, StreamExt};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::iter;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::task::{self, JoinHandle};
+
+use super::file_stream::BatchIter;
+use super::{
+    partitions_to_write, FileScanConfig, PartitionColumnProjector, SchemaAdapter,
+};
+
+/// Execution plan for scanning Arrow IPC files
+#[derive(Debug, Clone)]
+pub struct ArrowExec {
+    base_config: FileScanConfig,
+    projected_statistics: Statistics,
+    projected_schema: SchemaRef,
+    projected_output_ordering: Option<Vec<PhysicalSortExpr>>,
+}
+
+impl ArrowExec {
+    /// Create a new Arrow reader execution plan provided base configurations
+    pub fn new(base_config: FileScanConfig) -> Self {
+        let (projected_schema, projected_statistics) = base_config.project();
+        let projected_output_ordering = base_config.projected_output_ordering();
+
+        Self {
+            base_config,
+            projected_schema,
+            projected_statistics,
+            projected_output_ordering,
+        }
+    }
+
+    /// Ref to the base configs
+    pub fn base_config(&self) -> &FileScanConfig {
+        &self.base_config
+    }
+
+    /// Split the files to scan into up to `target_partitions` groups of byte
+    /// ranges of similar size, so that the record batches of large files are
+    /// read in parallel. A record batch is read by the partition whose range
+    /// contains its start.
+    ///
+    /// Returns `None` if there are already enough partitions, if the files
+    /// are already split into ranges or if they are bucketed.
+    pub fn repartitioned(&self, target_partitions: usize) -> Option<Self> {
+        if self.base_config.bucket_columns.is_some() {
+            return None;
+        }
+        let files = self
+            .base_config
+            .file_groups
+            .iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        if self.base_config.file_groups.len() >= target_partitions
+            || files.iter().any(|file| file.range.is_some())
+        {
+            return None;
+        }
+
+        let total_size: u64 = files.iter().map(|file| file.file_meta.size()).sum();
+        if total_size == 0 {
+            return None;
+        }
+        let target_size =
+            (total_size + target_partitions as u64 - 1) / target_partitions as u64;
+
+        let mut file_groups = vec![];
+        let mut group = vec![];
+        let mut group_size = 0;
+        for file in files {
+            let size = file.file_meta.size();
+            let mut start = 0;
+            while start < size {
+                let end = size.min(start + target_size - group_size);
+                group.push(PartitionedFile {
+                    range: Some(FileRange {
+                        start: start as i64,
+                        end: end as i64,
+                    }),
+                    ..file.clone()
+                });
+                group_size += end - start;
+                start = end;
+                if group_size == target_size {
+                    file_groups.push(std::mem::take(&mut group));
+                    group_size = 0;
+                }
+            }
+        }
+        if !group.is_empty() {
+            file_groups.push(group);
+        }
+
+        // a group may read the end of a file then the start of the next one,
+        // so the files being sorted does not make the groups sorted anymore
+        Some(Self::new(FileScanConfig {
+            file_groups,
+            output_ordering: None,
+            bucket_columns: None,
+            ..self.base_config.clone()
+        }))
+    }
+}
+
+impl ExecutionPlan for ArrowExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.base_config.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.projected_output_ordering.as_deref()
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let projection = match self.base_config.file_column_projection_indices() {
+            Some(proj) => proj,
+            None => (0..self.base_config.file_schema.fields().len()).collect(),
+        };
+
+        Ok(Box::pin(ArrowStream {
+            object_store: Arc::clone(&self.base_config.object_store),
+            files: self.base_config.file_groups[partition].clone().into(),
+            batch_iter: Box::new(iter::empty()),
+            partition_values: vec![],
+            projection,
+            adapter: SchemaAdapter::new(Arc::clone(&self.base_config.file_schema)),
+            pc_projector: PartitionColumnProjector::new(
+                Arc::clone(&self.projected_schema),
+                &self.base_config.table_partition_cols,
+            ),
+            projected_schema: Arc::clone(&self.projected_schema),
+            remain: self.base_config.limit,
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "ArrowExec: limit={:?}, files={}",
+                    self.base_config.limit,
+                    super::FileGroupsDisplay(&self.base_config.file_groups),
+                )
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.projected_statistics.clone()
+    }
+}
+
+/// A stream that reads the record batches of Arrow files, file over file,
+/// only reading the record batches that start within the range of a file.
+struct ArrowStream {
+    object_store: Arc<dyn ObjectStore>,
+    /// The files left to read
+    files: VecDeque<PartitionedFile>,
+    /// The record batches left to read from the current file
+    batch_iter: BatchIter,
+    /// Partitioning column values for the current file
+    partition_values: Vec<ScalarValue>,
+    /// The indices of the columns of the file schema to read
+    projection: Vec<usize>,
+    /// Maps the columns of each file to the file schema of the table
+    adapter: SchemaAdapter,
+    /// Adds the partition columns to the batches
+    pc_projector: PartitionColumnProjector,
+    /// The schema of the batches of the stream
+    projected_schema: SchemaRef,
+    /// The remaining number of records to read, None if no limit
+    remain: Option<usize>,
+}
+
+impl ArrowStream {
+    /// Open a reader over the record batches of `file` in its range
+    fn open(&self, file: &PartitionedFile) -> Result<BatchIter> {
+        let object_reader = self
+            .object_store
+            .file_reader(file.file_meta.sized_file.clone())?;
+
+        let batches = match &file.range {
+            Some(range) => {
+                let offsets = record_batch_offsets(object_reader.as_ref())?;
+                let first = offsets.iter().position(|offset| *offset >= range.start);
+                let count = offsets
+                    .iter()
+                    .filter(|offset| (range.start..range.end).contains(*offset))
+                    .count();
+                match first {
+                    Some(first) if count > 0 => Some((first, count)),
+                    _ => return Ok(Box::new(iter::empty())),
+                }
+            }
+            None => None,
+        };
+
+        // whole record batches are read as the projection of the IPC reader only
+        // supports leading columns, they are projected when adapting them
+        let mut reader =
+            FileReader::try_new(SeekableObjectReader::new(object_reader), None)?;
+        self.adapter
+            .map_projections(&reader.schema(), &self.projection)?;
+
+        let adapter = self.adapter.clone();
+        let projection = self.projection.clone();
+        let adapt = move |batch: ArrowResult<RecordBatch>| {
+            batch.and_then(|batch| {
+                adapter
+                    .adapt_batch(batch, &projection)
+                    .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+            })
+        };
+        Ok(match batches {
+            Some((first, count)) => {
+                reader.set_index(first)?;
+                Box::new(reader.take(count).map(adapt))
+            }
+            None => Box::new(reader.map(adapt)),
+        })
+    }
+
+    /// Acts as a flat_map of record batches over files. Adds the partitioning
+    /// Columns to the returned record batches.
+    fn next_batch(&mut self) -> Option<ArrowResult<RecordBatch>> {
+        loop {
+            match self.batch_iter.next() {
+                Some(Ok(batch)) => {
+                    return Some(self.pc_projector.project(batch, &self.partition_values))
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    let file = self.files.pop_front()?;
+                    match self.open(&file) {
+                        Ok(batch_iter) => {
+                            self.batch_iter = batch_iter;
+                            self.partition_values = file.partition_values;
+                        }
+                        Err(e) => {
+                            self.files.clear();
+                            return Some(Err(ArrowError::ExternalError(Box::new(e))));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Stream for ArrowStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.remain == Some(0) {
+            return Poll::Ready(None);
+        }
+
+        Poll::Ready(match self.next_batch() {
+            Some(Ok(batch)) => match self.remain.as_mut() {
+                Some(remain) if *remain < batch.num_rows() => {
+                    let len = *remain;
+                    *remain = 0;
+                    Some(Ok(batch.slice(0, len)))
+                }
+                Some(remain) => {
+                    *remain -= batch.num_rows();
+                    Some(Ok(batch))
+                }
+                None => Some(Ok(batch)),
+            },
+            other => other,
+        })
+    }
+}
+
+impl RecordBatchStream for ArrowStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.projected_schema)
+    }
+}
+
+/// Read the offsets of the record batches of an Arrow file from its footer
+fn record_batch_offsets(object_reader: &dyn ObjectReader) -> Result<Vec<i64>> {
+    let invalid = || {
+        DataFusionError::Execution("Arrow file does not contain a valid footer".into())
+    };
+    // the footer is followed by its length and the magic bytes
+    let length = object_reader.length();
+    if length < 10 {
+        return Err(invalid());
+    }
+    let mut footer_size = [0u8; 4];
+    object_reader
+        .sync_chunk_reader(length - 10, 4)?
+        .read_exact(&mut footer_size)?;
+    let footer_len = u64::try_from(i32::from_le_bytes(footer_size))
+        .ok()
+        .filter(|footer_len| *footer_len + 10 <= length)
+        .ok_or_else(invalid)?;
+
+    let mut footer_data = vec![0; footer_len as usize];
+    object_reader
+        .sync_chunk_reader(length - 10 - footer_len, footer_len as usize)?
+        .read_exact(&mut footer_data)?;
+    let footer = ipc::root_as_footer(&footer_data).map_err(|_| invalid())?;
+
+    Ok(footer
+        .recordBatches()
+        .map(|blocks| blocks.iter().map(|block| block.offset()).collect())
+        .unwrap_or_default())
+}
+
+/// Adapts an [`ObjectReader`] to the `Read` and `Seek` traits required by the
+/// Arrow IPC file reader, reading the requested bytes on each read
+pub(crate) struct SeekableObjectReader {
+    object_reader: Arc<dyn ObjectReader>,
+    position: u64,
+}
+
+impl SeekableObjectReader {
+    pub(crate) fn new(object_reader: Arc<dyn ObjectReader>) -> Self {
+        Self {
+            object_reader,
+            position: 0,
+        }
+    }
+}
+
+impl Read for SeekableObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.object_reader.length().saturating_sub(self.position);
+        let len = remaining.min(buf.len() as u64) as usize;
+        if len == 0 {
+            return Ok(0);
+        }
+        self.object_reader
+            .sync_chunk_reader(self.position, len)?
+            .read_exact(&mut buf[..len])?;
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for SeekableObjectReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(position) => (position, 0),
+            SeekFrom::End(offset) => (self.object_reader.length(), offset),
+            SeekFrom::Current(offset) => (self.position, offset),
+        };
+        let position = (base as i64).checked_add(offset).filter(|p| *p >= 0);
+        match position {
+            Some(position) => {
+                self.position = position as u64;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+pub async fn plan_to_arrow(
+    state: &SessionState,
+    plan: Arc<dyn ExecutionPlan>,
+    path: impl AsRef<str>,
+) -> Result<()> {
+    let plan = partitions_to_write(plan);
+    let path = path.as_ref();
+    // create directory to contain the Arrow files (one per partition)
+    let fs_path = Path::new(path);
+    match fs::create_dir(fs_path) {
+        Ok(()) => {
+            let mut tasks = vec![];
+            for i in 0..plan.output_partitioning().partition_count() {
+                let plan = plan.clone();
+                let filename = format!("part-{}.arrow", i);
+                let path = fs_path.join(&filename);
+                let file = fs::File::create(path)?;
+                let mut writer = FileWriter::try_new(file, plan.schema().as_ref())?;
+                let task_ctx = Arc::new(TaskContext::from(state));
+                let mut stream = plan.execute(i, task_ctx)?;
+                let handle: JoinHandle<Result<()>> = task::spawn(async move {
+                    while let Some(batch) = stream.next().await {
+                        writer.write(&batch?)?;
+                    }
+                    writer.finish().map_err(DataFusionError::from)
+                });
+                tasks.push(handle);
+            }
+            for result in futures::future::join_all(tasks).await {
+                result.map_err(|e| DataFusionError::Execution(e.to_string()))??;
+            }
+            Ok(())
+        }
+        Err(e) => Err(DataFusionError::Execution(format!(
+            "Could not create directory {}: {:?}",
+            path, e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::file_format::{arrow::ArrowFormat, FileFormat};
+    use crate::datasource::listing::local_unpartitioned_file;
+    use crate::physical_plan::collect;
+    use crate::prelude::SessionContext;
+    use crate::{assert_batches_eq, assert_batches_sorted_eq};
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_data_access::object_store::local::{
+        local_object_reader_stream, LocalFileSystem,
+    };
+    use tempfile::TempDir;
+
+    /// Write an Arrow file with three batches of 2 rows
+    fn write_file(dir: &TempDir) -> Result<String> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let path = dir.path().join("data.arrow");
+        let mut writer = FileWriter::try_new(fs::File::create(&path)?, &schema)?;
+        for i in 0..3 {
+            writer.write(&RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(vec![2 * i, 2 * i + 1])),
+                    Arc::new(StringArray::from(vec![Some("x"), None])),
+                ],
+            )?)?;
+        }
+        writer.finish()?;
+        Ok(path.to_str().unwrap().to_owned())
+    }
+
+    async fn arrow_exec(
+        filename: String,
+        projection: Option<Vec<usize>>,
+        limit: Option<usize>,
+    ) -> Result<ArrowExec> {
+        let file_schema = ArrowFormat
+            .infer_schema(local_object_reader_stream(vec![filename.clone()]))
+            .await?;
+        Ok(ArrowExec::new(FileScanConfig {
+            object_store: Arc::new(LocalFileSystem {}),
+            file_groups: vec![vec![local_unpartitioned_file(filename)]],
+            file_schema,
+            statistics: Statistics::default(),
+            pruned_files: 0,
+            projection,
+            limit,
+            table_partition_cols: vec![],
+            output_ordering: None,
+            bucket_columns: None,
+        }))
+    }
+
+    #[tokio::test]
+    async fn arrow_exec_with_projection_and_limit() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let filename = write_file(&tmp_dir)?;
+        let task_ctx = SessionContext::new().task_ctx();
+
+        let exec = arrow_exec(filename.clone(), Some(vec![1, 0]), None).await?;
+        assert_eq!(exec.output_partitioning().partition_count(), 1);
+        let batches = collect(Arc::new(exec), task_ctx.clone()).await?;
+        let expected = vec![
+            "+---+---+",
+            "| b | a |",
+            "+---+---+",
+            "| x | 0 |",
+            "|   | 1 |",
+            "| x | 2 |",
+            "|   | 3 |",
+            "| x | 4 |",
+            "|   | 5 |",
+            "+---+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        let exec = arrow_exec(filename, Some(vec![0]), Some(3)).await?;
+        let batches = collect(Arc::new(exec), task_ctx).await?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 0 |", "| 1 |", "| 2 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn arrow_exec_repartitioned() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let filename = write_file(&tmp_dir)?;
+        let task_ctx = SessionContext::new().task_ctx();
+
+        let exec = arrow_exec(filename, Some(vec![0]), None).await?;
+        assert!(exec.repartitioned(1).is_none());
+        let exec = exec.repartitioned(3).unwrap();
+        assert!(exec.repartitioned(4).is_none());
+        assert_eq!(exec.output_partitioning().partition_count(), 3);
+
+        // each record batch is read by exactly one partition
+        let mut batches = vec![];
+        for partition in 0..3 {
+            let stream = exec.execute(partition, task_ctx.clone())?;
+            batches.extend(crate::physical_plan::common::collect(stream).await?);
+        }
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 0 |", "| 1 |", "| 2 |", "| 3 |", "| 4 |",
+            "| 5 |", "+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+}
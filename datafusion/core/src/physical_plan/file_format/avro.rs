@@ -41,17 +41,20 @@ pub struct AvroExec {
     base_config: FileScanConfig,
     projected_statistics: Statistics,
     projected_schema: SchemaRef,
+    projected_output_ordering: Option<Vec<PhysicalSortExpr>>,
 }
 
 impl AvroExec {
     /// Create a new Avro reader execution plan provided base configurations
     pub fn new(base_config: FileScanConfig) -> Self {
         let (projected_schema, projected_statistics) = base_config.project();
+        let projected_output_ordering = base_config.projected_output_ordering();
 
         Self {
             base_config,
             projected_schema,
             projected_statistics,
+            projected_output_ordering,
         }
     }
     /// Ref to the base configs
@@ -70,11 +73,11 @@ impl ExecutionPlan for AvroExec {
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+        self.base_config.output_partitioning()
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.projected_output_ordering.as_deref()
     }
 
     fn relies_on_input_order(&self) -> bool {
@@ -190,9 +193,12 @@ mod tests {
                 .infer_schema(local_object_reader_stream(vec![filename]))
                 .await?,
             statistics: Statistics::default(),
+            pruned_files: 0,
             projection: Some(vec![0, 1, 2]),
             limit: None,
             table_partition_cols: vec![],
+            output_ordering: None,
+            bucket_columns: None,
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -256,9 +262,12 @@ mod tests {
             file_groups: vec![vec![local_unpartitioned_file(filename.clone())]],
             file_schema,
             statistics: Statistics::default(),
+            pruned_files: 0,
             projection,
             limit: None,
             table_partition_cols: vec![],
+            output_ordering: None,
+            bucket_columns: None,
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -321,8 +330,11 @@ mod tests {
             file_groups: vec![vec![partitioned_file]],
             file_schema,
             statistics: Statistics::default(),
+            pruned_files: 0,
             limit: None,
             table_partition_cols: vec!["date".to_owned()],
+            output_ordering: None,
+            bucket_columns: None,
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -34,7 +34,7 @@ use std::sync::Arc;
 use tokio::task::{self, JoinHandle};
 
 use super::file_stream::{BatchIter, FileStream};
-use super::FileScanConfig;
+use super::{partitions_to_write, FileScanConfig};
 
 /// Execution plan for scanning a CSV file
 #[derive(Debug, Clone)]
@@ -42,6 +42,7 @@ pub struct CsvExec {
     base_config: FileScanConfig,
     projected_statistics: Statistics,
     projected_schema: SchemaRef,
+    projected_output_ordering: Option<Vec<PhysicalSortExpr>>,
     has_header: bool,
     delimiter: u8,
 }
@@ -50,11 +51,13 @@ impl CsvExec {
     /// Create a new CSV reader execution plan provided base and specific configurations
     pub fn new(base_config: FileScanConfig, has_header: bool, delimiter: u8) -> Self {
         let (projected_schema, projected_statistics) = base_config.project();
+        let projected_output_ordering = base_config.projected_output_ordering();
 
         Self {
             base_config,
             projected_schema,
             projected_statistics,
+            projected_output_ordering,
             has_header,
             delimiter,
         }
@@ -87,7 +90,7 @@ impl ExecutionPlan for CsvExec {
 
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+        self.base_config.output_partitioning()
     }
 
     fn relies_on_input_order(&self) -> bool {
@@ -95,7 +98,7 @@ impl ExecutionPlan for CsvExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.projected_output_ordering.as_deref()
     }
 
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
@@ -176,6 +179,7 @@ pub async fn plan_to_csv(
     plan: Arc<dyn ExecutionPlan>,
     path: impl AsRef<str>,
 ) -> Result<()> {
+    let plan = partitions_to_write(plan);
     let path = path.as_ref();
     // create directory to contain the CSV files (one per partition)
     let fs_path = Path::new(path);
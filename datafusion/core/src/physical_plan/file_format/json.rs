@@ -34,7 +34,7 @@ use std::sync::Arc;
 use tokio::task::{self, JoinHandle};
 
 use super::file_stream::{BatchIter, FileStream};
-use super::FileScanConfig;
+use super::{partitions_to_write, FileScanConfig};
 
 /// Execution plan for scanning NdJson data source
 #[derive(Debug, Clone)]
@@ -42,17 +42,20 @@ pub struct NdJsonExec {
     base_config: FileScanConfig,
     projected_statistics: Statistics,
     projected_schema: SchemaRef,
+    projected_output_ordering: Option<Vec<PhysicalSortExpr>>,
 }
 
 impl NdJsonExec {
     /// Create a new JSON reader execution plan provided base configurations
     pub fn new(base_config: FileScanConfig) -> Self {
         let (projected_schema, projected_statistics) = base_config.project();
+        let projected_output_ordering = base_config.projected_output_ordering();
 
         Self {
             base_config,
             projected_schema,
             projected_statistics,
+            projected_output_ordering,
         }
     }
 }
@@ -67,11 +70,11 @@ impl ExecutionPlan for NdJsonExec {
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+        self.base_config.output_partitioning()
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.projected_output_ordering.as_deref()
     }
 
     fn relies_on_input_order(&self) -> bool {
@@ -153,6 +156,7 @@ pub async fn plan_to_json(
     plan: Arc<dyn ExecutionPlan>,
     path: impl AsRef<str>,
 ) -> Result<()> {
+    let plan = partitions_to_write(plan);
     let path = path.as_ref();
     // create directory to contain the CSV files (one per partition)
     let fs_path = Path::new(path);
@@ -224,9 +228,12 @@ mod tests {
             file_groups: vec![vec![local_unpartitioned_file(path.clone())]],
             file_schema: infer_schema(path).await?,
             statistics: Statistics::default(),
+            pruned_files: 0,
             projection: None,
             limit: Some(3),
             table_partition_cols: vec![],
+            output_ordering: None,
+            bucket_columns: None,
         });
 
         // TODO: this is not where schema inference should be tested
@@ -289,9 +296,12 @@ mod tests {
             file_groups: vec![vec![local_unpartitioned_file(path.clone())]],
             file_schema,
             statistics: Statistics::default(),
+            pruned_files: 0,
             projection: None,
             limit: Some(3),
             table_partition_cols: vec![],
+            output_ordering: None,
+            bucket_columns: None,
         });
 
         let mut it = exec.execute(0, task_ctx)?;
@@ -321,9 +331,12 @@ mod tests {
             file_groups: vec![vec![local_unpartitioned_file(path.clone())]],
             file_schema: infer_schema(path).await?,
             statistics: Statistics::default(),
+            pruned_files: 0,
             projection: Some(vec![0, 2]),
             limit: None,
             table_partition_cols: vec![],
+            output_ordering: None,
+            bucket_columns: None,
         });
         let inferred_schema = exec.schema();
         assert_eq!(inferred_schema.fields().len(), 2);
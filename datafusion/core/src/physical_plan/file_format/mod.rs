@@ -17,6 +17,7 @@
 
 //! Execution plans that read file formats
 
+mod arrow_file;
 mod avro;
 mod csv;
 mod file_stream;
@@ -32,6 +33,8 @@ use arrow::{
     error::{ArrowError, Result as ArrowResult},
     record_batch::RecordBatch,
 };
+pub use arrow_file::ArrowExec;
+pub(crate) use arrow_file::{plan_to_arrow, SeekableObjectReader};
 pub use avro::AvroExec;
 pub(crate) use csv::plan_to_csv;
 pub use csv::CsvExec;
@@ -55,7 +58,9 @@ use std::{
     vec,
 };
 
-use super::{ColumnStatistics, Statistics};
+use super::expressions::{Column, PhysicalSortExpr};
+use super::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use super::{ColumnStatistics, ExecutionPlan, Partitioning, PhysicalExpr, Statistics};
 
 lazy_static! {
     /// The datatype used for all partitioning columns for now
@@ -75,6 +80,9 @@ pub struct FileScanConfig {
     pub file_groups: Vec<Vec<PartitionedFile>>,
     /// Estimated overall statistics of the files, taking `filters` into account.
     pub statistics: Statistics,
+    /// Number of files of the table that were skipped before the scan, as
+    /// their statistics show that they cannot match the filters
+    pub pruned_files: usize,
     /// Columns on which to project the data. Indexes that are higher than the
     /// number of columns of `file_schema` refer to `table_partition_cols`.
     pub projection: Option<Vec<usize>>,
@@ -82,6 +90,14 @@ pub struct FileScanConfig {
     pub limit: Option<usize>,
     /// The partitioning column names
     pub table_partition_cols: Vec<String>,
+    /// The sort order of the rows of each file group, if known, as columns of
+    /// the table schema (the file columns followed by the partition columns)
+    pub output_ordering: Option<Vec<PhysicalSortExpr>>,
+    /// The columns of the file schema whose hash assigns the rows of bucketed
+    /// files to the file groups: the group `i` holds the rows whose hash, as
+    /// computed by [`RepartitionExec`](crate::physical_plan::repartition::RepartitionExec),
+    /// modulo the number of groups is `i`
+    pub bucket_columns: Option<Vec<usize>>,
 }
 
 impl FileScanConfig {
@@ -133,6 +149,52 @@ impl FileScanConfig {
         (table_schema, table_stats)
     }
 
+    /// The partitioning of the output of a scan of the file groups, which is a
+    /// hash partitioning if the files are bucketed and their bucket columns are
+    /// all part of the projection
+    fn output_partitioning(&self) -> Partitioning {
+        let partition_count = self.file_groups.len();
+        let hash_exprs = self.bucket_columns.as_ref().and_then(|bucket_columns| {
+            bucket_columns
+                .iter()
+                .map(|column_idx| {
+                    let index = match &self.projection {
+                        Some(proj) => proj.iter().position(|idx| idx == column_idx)?,
+                        None => *column_idx,
+                    };
+                    let name = self.file_schema.field(*column_idx).name();
+                    Some(Arc::new(Column::new(name, index)) as Arc<dyn PhysicalExpr>)
+                })
+                .collect::<Option<Vec<_>>>()
+        });
+        match hash_exprs {
+            Some(hash_exprs) => Partitioning::Hash(hash_exprs, partition_count),
+            None => Partitioning::UnknownPartitioning(partition_count),
+        }
+    }
+
+    /// Project the output ordering on the projected columns, keeping its
+    /// longest prefix of columns that are part of the projection
+    fn projected_output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        let ordering = self
+            .output_ordering
+            .as_ref()?
+            .iter()
+            .map_while(|sort_expr| {
+                let column = sort_expr.expr.as_any().downcast_ref::<Column>()?;
+                let index = match &self.projection {
+                    Some(proj) => proj.iter().position(|idx| *idx == column.index())?,
+                    None => column.index(),
+                };
+                Some(PhysicalSortExpr {
+                    expr: Arc::new(Column::new(column.name(), index)),
+                    options: sort_expr.options,
+                })
+            })
+            .collect::<Vec<_>>();
+        (!ordering.is_empty()).then(|| ordering)
+    }
+
     fn projected_file_column_names(&self) -> Option<Vec<String>> {
         self.projection.as_ref().map(|p| {
             p.iter()
@@ -363,6 +425,18 @@ impl PartitionColumnProjector {
     }
 }
 
+/// Returns the plan whose partitions the `plan_to_*` functions write as
+/// separate files: for a global sort whose sorted ranges are concatenated,
+/// the ranges, so that the files are sorted in the order of their names
+pub(crate) fn partitions_to_write(
+    plan: Arc<dyn ExecutionPlan>,
+) -> Arc<dyn ExecutionPlan> {
+    match plan.as_any().downcast_ref::<SortPreservingMergeExec>() {
+        Some(merge) if merge.concatenates_ranges() => Arc::clone(merge.input()),
+        _ => plan,
+    }
+}
+
 fn create_dict_array(
     key_buffer_cache: &mut Option<Buffer>,
     val: &ScalarValue,
@@ -660,7 +734,10 @@ mod tests {
             object_store: TestObjectStore::new_arc(&[]),
             projection,
             statistics,
+            pruned_files: 0,
             table_partition_cols,
+            output_ordering: None,
+            bucket_columns: None,
         }
     }
 }
@@ -28,7 +28,8 @@ use std::task::{Context, Poll};
 use std::{any::Any, convert::TryInto};
 
 use arrow::{
-    array::ArrayRef,
+    array::{Array, ArrayRef, BooleanArray},
+    compute::{and_kleene, filter_record_batch},
     datatypes::{Schema, SchemaRef},
     error::{ArrowError, Result as ArrowResult},
     record_batch::RecordBatch,
@@ -60,13 +61,14 @@ use crate::{
         expressions::PhysicalSortExpr,
         file_format::{FileScanConfig, SchemaAdapter},
         metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
-        DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+        runtime_filter::RuntimeFilterExpr,
+        DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr, RecordBatchStream,
         SendableRecordBatchStream, Statistics,
     },
     scalar::ScalarValue,
 };
 
-use super::PartitionColumnProjector;
+use super::{partitions_to_write, PartitionColumnProjector};
 
 /// Execution plan for scanning one or more Parquet partitions
 #[derive(Debug, Clone)]
@@ -74,10 +76,13 @@ pub struct ParquetExec {
     base_config: FileScanConfig,
     projected_statistics: Statistics,
     projected_schema: SchemaRef,
+    projected_output_ordering: Option<Vec<PhysicalSortExpr>>,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
     /// Optional predicate for pruning row groups
     pruning_predicate: Option<PruningPredicate>,
+    /// Filters published at runtime by joins this scan is the probe side of
+    runtime_filters: Vec<Arc<RuntimeFilterExpr>>,
 }
 
 /// Stores metrics about the parquet execution for a particular parquet file
@@ -101,6 +106,9 @@ impl ParquetExec {
         let metrics = ExecutionPlanMetricsSet::new();
         let predicate_creation_errors =
             MetricBuilder::new(&metrics).global_counter("num_predicate_creation_errors");
+        MetricBuilder::new(&metrics)
+            .global_counter("files_pruned_by_statistics")
+            .add(base_config.pruned_files);
 
         let pruning_predicate = predicate.and_then(|predicate_expr| {
             match PruningPredicate::try_new(
@@ -117,16 +125,31 @@ impl ParquetExec {
         });
 
         let (projected_schema, projected_statistics) = base_config.project();
+        let projected_output_ordering = base_config.projected_output_ordering();
 
         Self {
             base_config,
             projected_schema,
             projected_statistics,
+            projected_output_ordering,
             metrics,
             pruning_predicate,
+            runtime_filters: vec![],
         }
     }
 
+    /// Apply `runtime_filter` to the files, row groups and rows read by this scan
+    /// once it becomes available. Its columns refer to the output schema of this scan.
+    pub fn with_runtime_filter(mut self, runtime_filter: Arc<RuntimeFilterExpr>) -> Self {
+        self.runtime_filters.push(runtime_filter);
+        self
+    }
+
+    /// The runtime filters applied to this scan
+    pub fn runtime_filters(&self) -> &[Arc<RuntimeFilterExpr>] {
+        &self.runtime_filters
+    }
+
     /// Ref to the base configs
     pub fn base_config(&self) -> &FileScanConfig {
         &self.base_config
@@ -182,11 +205,11 @@ impl ExecutionPlan for ParquetExec {
 
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+        self.base_config.output_partitioning()
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.projected_output_ordering.as_deref()
     }
 
     fn relies_on_input_order(&self) -> bool {
@@ -229,6 +252,12 @@ impl ExecutionPlan for ParquetExec {
             projector: partition_col_proj,
             adapter: SchemaAdapter::new(self.base_config.file_schema.clone()),
             baseline_metrics: BaselineMetrics::new(&self.metrics, partition_index),
+            runtime_filters: RuntimeFilters::new(
+                self.runtime_filters.clone(),
+                &self.base_config,
+                &self.metrics,
+                partition_index,
+            ),
         };
 
         // Use spawn_blocking only if running from a tokio context (#2201)
@@ -268,7 +297,7 @@ impl ExecutionPlan for ParquetExec {
                         super::FileGroupsDisplay(&self.base_config.file_groups),
                         pre.predicate_expr(),
                         super::ProjectSchemaDisplay(&self.projected_schema),
-                    )
+                    )?;
                 } else {
                     write!(
                         f,
@@ -276,8 +305,17 @@ impl ExecutionPlan for ParquetExec {
                         self.base_config.limit,
                         super::FileGroupsDisplay(&self.base_config.file_groups),
                         super::ProjectSchemaDisplay(&self.projected_schema),
-                    )
+                    )?;
+                }
+                if !self.runtime_filters.is_empty() {
+                    let filters = self
+                        .runtime_filters
+                        .iter()
+                        .map(|f| f.to_string())
+                        .collect::<Vec<_>>();
+                    write!(f, ", runtime_filters=[{}]", filters.join(", "))?;
                 }
+                Ok(())
             }
         }
     }
@@ -311,6 +349,91 @@ struct ParquetExecStream {
     projector: PartitionColumnProjector,
     adapter: SchemaAdapter,
     baseline_metrics: BaselineMetrics,
+    runtime_filters: RuntimeFilters,
+}
+
+/// The runtime filters applied by a [`ParquetExecStream`]
+struct RuntimeFilters {
+    filters: Vec<Arc<RuntimeFilterExpr>>,
+    /// The schema of the files, used to prune row groups
+    file_schema: SchemaRef,
+    /// The names of the table partition columns
+    table_partition_cols: Vec<String>,
+    /// Number of files skipped based on their partition values
+    files_pruned: metrics::Count,
+    /// Number of rows removed by the filters
+    rows_pruned: metrics::Count,
+}
+
+impl RuntimeFilters {
+    fn new(
+        filters: Vec<Arc<RuntimeFilterExpr>>,
+        config: &FileScanConfig,
+        metrics: &ExecutionPlanMetricsSet,
+        partition: usize,
+    ) -> Self {
+        Self {
+            filters,
+            file_schema: config.file_schema.clone(),
+            table_partition_cols: config.table_partition_cols.clone(),
+            files_pruned: MetricBuilder::new(metrics)
+                .counter("runtime_filter_files_pruned", partition),
+            rows_pruned: MetricBuilder::new(metrics)
+                .counter("runtime_filter_rows_pruned", partition),
+        }
+    }
+
+    /// Returns `false` if the partition values of `file` cannot pass the filters
+    fn may_match(&self, file: &PartitionedFile) -> bool {
+        let matches = self.filters.iter().all(|filter| {
+            self.table_partition_cols
+                .iter()
+                .zip(&file.partition_values)
+                .all(|(name, value)| filter.may_contain(name, value))
+        });
+        if !matches {
+            self.files_pruned.add(1);
+        }
+        matches
+    }
+
+    /// Returns the predicates for pruning row groups of the
+    /// filters that are available
+    fn pruning_predicates(&self) -> Vec<PruningPredicate> {
+        self.filters
+            .iter()
+            .filter_map(|filter| filter.pruning_expr(&self.file_schema))
+            .filter_map(|expr| {
+                PruningPredicate::try_new(expr, self.file_schema.clone())
+                    .map_err(|e| {
+                        debug!("Could not create runtime pruning predicate: {}", e)
+                    })
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Removes rows from `batch` that cannot pass the filters
+    fn filter_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let mut mask: Option<BooleanArray> = None;
+        for filter in &self.filters {
+            let array = filter.evaluate(&batch)?.into_array(batch.num_rows());
+            let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+            mask = Some(match mask {
+                Some(mask) => and_kleene(&mask, array)?,
+                None => BooleanArray::from(array.data().clone()),
+            });
+        }
+
+        match mask {
+            Some(mask) => {
+                let filtered = filter_record_batch(&batch, &mask)?;
+                self.rows_pruned.add(batch.num_rows() - filtered.num_rows());
+                Ok(filtered)
+            }
+            None => Ok(batch),
+        }
+    }
 }
 
 impl ParquetExecStream {
@@ -332,7 +455,13 @@ impl ParquetExecStream {
         if let Some(pruning_predicate) = &self.pruning_predicate {
             opt = opt.with_predicate(build_row_group_predicate(
                 pruning_predicate,
-                file_metrics,
+                file_metrics.clone(),
+            ));
+        }
+        for pruning_predicate in self.runtime_filters.pruning_predicates() {
+            opt = opt.with_predicate(build_row_group_predicate(
+                &pruning_predicate,
+                file_metrics.clone(),
             ));
         }
         if let Some(range) = &file.range {
@@ -383,6 +512,7 @@ impl Iterator for ParquetExecStream {
                 Some(current) => current,
                 None => match self.files.pop_front() {
                     None => return None,
+                    Some(file) if !self.runtime_filters.may_match(&file) => continue,
                     Some(file) => match self.create_reader(&file) {
                         Ok(reader) => self.reader.insert((reader, file)),
                         Err(e) => {
@@ -403,9 +533,21 @@ impl Iterator for ParquetExecStream {
                     .and_then(|batch| {
                         self.projector.project(batch, &file.partition_values)
                     })
+                    .and_then(|batch| {
+                        self.runtime_filters
+                            .filter_batch(batch)
+                            .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+                    })
             });
 
             let result = match result {
+                Some(Ok(batch))
+                    if batch.num_rows() == 0
+                        && !self.runtime_filters.filters.is_empty() =>
+                {
+                    // All rows were removed by the runtime filters
+                    continue;
+                }
                 Some(result) => result,
                 None => {
                     self.reader = None;
@@ -586,6 +728,7 @@ pub async fn plan_to_parquet(
     path: impl AsRef<str>,
     writer_properties: Option<WriterProperties>,
 ) -> Result<()> {
+    let plan = partitions_to_write(plan);
     let path = path.as_ref();
     // create directory to contain the Parquet files (one per partition)
     let fs_path = Path::new(path);
@@ -719,9 +862,12 @@ mod tests {
                 file_groups: vec![file_groups],
                 file_schema,
                 statistics: Statistics::default(),
+                pruned_files: 0,
                 projection,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
+                bucket_columns: None,
             },
             predicate,
         );
@@ -1062,9 +1208,12 @@ mod tests {
                     .infer_schema(local_object_reader_stream(vec![filename]))
                     .await?,
                 statistics: Statistics::default(),
+                pruned_files: 0,
                 projection: Some(vec![0, 1, 2]),
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
+                bucket_columns: None,
             },
             None,
         );
@@ -1115,9 +1264,12 @@ mod tests {
                     file_groups,
                     file_schema,
                     statistics: Statistics::default(),
+                    pruned_files: 0,
                     projection: None,
                     limit: None,
                     table_partition_cols: vec![],
+                    output_ordering: None,
+                    bucket_columns: None,
                 },
                 None,
             );
@@ -1188,6 +1340,7 @@ mod tests {
                     .infer_schema(local_object_reader_stream(vec![filename]))
                     .await?,
                 statistics: Statistics::default(),
+                pruned_files: 0,
                 // file has 10 cols so index 12 should be month
                 projection: Some(vec![0, 1, 2, 12]),
                 limit: None,
@@ -1196,6 +1349,8 @@ mod tests {
                     "month".to_owned(),
                     "day".to_owned(),
                 ],
+                output_ordering: None,
+                bucket_columns: None,
             },
             None,
         );
@@ -1251,9 +1406,12 @@ mod tests {
                     .infer_schema(local_object_reader_stream(vec![filename]))
                     .await?,
                 statistics: Statistics::default(),
+                pruned_files: 0,
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
+                bucket_columns: None,
             },
             None,
         );
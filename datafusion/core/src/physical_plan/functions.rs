@@ -78,7 +78,7 @@ pub fn create_physical_expr(
         // so we don't have to pay a per-array/batch cost.
         BuiltinScalarFunction::ToTimestamp => {
             Arc::new(match coerced_phy_exprs[0].data_type(input_schema) {
-                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
+                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, _)) => {
                     |col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
@@ -98,7 +98,7 @@ pub fn create_physical_expr(
         }
         BuiltinScalarFunction::ToTimestampMillis => {
             Arc::new(match coerced_phy_exprs[0].data_type(input_schema) {
-                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
+                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, _)) => {
                     |col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
@@ -118,7 +118,7 @@ pub fn create_physical_expr(
         }
         BuiltinScalarFunction::ToTimestampMicros => {
             Arc::new(match coerced_phy_exprs[0].data_type(input_schema) {
-                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
+                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, _)) => {
                     |col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
@@ -138,7 +138,7 @@ pub fn create_physical_expr(
         }
         BuiltinScalarFunction::ToTimestampSeconds => Arc::new({
             match coerced_phy_exprs[0].data_type(input_schema) {
-                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
+                Ok(DataType::Int64) | Ok(DataType::Timestamp(_, _)) => {
                     |col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
@@ -0,0 +1,418 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan generating a series of integers or timestamps, used by the
+//! `generate_series` and `range` table functions
+
+use std::any::Any;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Int64Array, TimestampNanosecondArray};
+use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use chrono::{Datelike, NaiveDate};
+use futures::stream;
+
+use super::expressions::PhysicalSortExpr;
+use super::stream::RecordBatchStreamAdapter;
+use super::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+
+/// Number of nanoseconds in a day
+pub(crate) const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+/// Number of days between 0001-01-01 and 1970-01-01
+pub(crate) const EPOCH_DAYS_FROM_CE: i64 = 719_163;
+
+/// The increment between the values of a [`Series`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeriesStep {
+    /// Integer increment
+    Int64(i64),
+    /// Calendar increment of timestamps
+    Interval {
+        /// Number of months, added first
+        months: i32,
+        /// Number of days
+        days: i32,
+        /// Number of nanoseconds
+        nanos: i64,
+    },
+}
+
+/// A series of integers, or of timestamps in nanoseconds, from `start`
+/// to `stop` in increments of `step`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Series {
+    start: i64,
+    stop: i64,
+    step: SeriesStep,
+    include_stop: bool,
+    /// Whether the values increase, which for intervals mixing positive and
+    /// negative parts is determined by the first increment
+    ascending: bool,
+}
+
+impl Series {
+    /// Create a new series, which includes `stop` if `include_stop` is true
+    pub fn try_new(
+        start: i64,
+        stop: i64,
+        step: SeriesStep,
+        include_stop: bool,
+    ) -> Result<Self> {
+        let mut series = Self {
+            start,
+            stop,
+            step,
+            include_stop,
+            ascending: true,
+        };
+        series.ascending = match series.value(1) {
+            Some(value) if value == start => {
+                return Err(DataFusionError::Plan(
+                    "The step of a series must not be zero".to_string(),
+                ))
+            }
+            Some(value) => value > start,
+            None => match step {
+                SeriesStep::Int64(step) => step > 0,
+                SeriesStep::Interval {
+                    months,
+                    days,
+                    nanos,
+                } => (months, days, nanos) > (0, 0, 0),
+            },
+        };
+        Ok(series)
+    }
+
+    /// The type of the values of the series
+    pub fn data_type(&self) -> DataType {
+        match self.step {
+            SeriesStep::Int64(_) => DataType::Int64,
+            SeriesStep::Interval { .. } => {
+                DataType::Timestamp(TimeUnit::Nanosecond, None)
+            }
+        }
+    }
+
+    /// Returns the value at `index`, if it is part of the series
+    fn get(&self, index: i64) -> Option<i64> {
+        let value = self.value(index)?;
+        let in_range = match (self.ascending, self.include_stop) {
+            (true, true) => value <= self.stop,
+            (true, false) => value < self.stop,
+            (false, true) => value >= self.stop,
+            (false, false) => value > self.stop,
+        };
+        in_range.then(|| value)
+    }
+
+    /// Computes the value at `index`, returning `None` on overflow.
+    /// Values are computed from `start` rather than the previous value, so
+    /// that month increments from the end of a month do not drift.
+    fn value(&self, index: i64) -> Option<i64> {
+        match self.step {
+            SeriesStep::Int64(step) => step.checked_mul(index)?.checked_add(self.start),
+            SeriesStep::Interval {
+                months,
+                days,
+                nanos,
+            } => {
+                let value = add_months(self.start, (months as i64).checked_mul(index)?)?;
+                let days = (days as i64).checked_mul(index)?;
+                value
+                    .checked_add(days.checked_mul(NANOS_PER_DAY)?)?
+                    .checked_add(nanos.checked_mul(index)?)
+            }
+        }
+    }
+
+    /// Returns the values from `index`, up to `len` of them
+    fn values(&self, index: i64, len: usize) -> Vec<i64> {
+        (index..).take(len).map_while(|i| self.get(i)).collect()
+    }
+}
+
+/// Adds `months` to the timestamp `ts` in nanoseconds, clamping the day to the
+/// last day of the resulting month
+fn add_months(ts: i64, months: i64) -> Option<i64> {
+    if months == 0 {
+        return Some(ts);
+    }
+    let days = ts.div_euclid(NANOS_PER_DAY);
+    let nanos_of_day = ts.rem_euclid(NANOS_PER_DAY);
+    let date = NaiveDate::from_num_days_from_ce_opt(
+        i32::try_from(days + EPOCH_DAYS_FROM_CE).ok()?,
+    )?;
+
+    let month0 = (date.year() as i64 * 12 + date.month0() as i64).checked_add(months)?;
+    let year = i32::try_from(month0.div_euclid(12)).ok()?;
+    let month = month0.rem_euclid(12) as u32 + 1;
+    let date = (1..=date.day())
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))?;
+
+    let days = date.num_days_from_ce() as i64 - EPOCH_DAYS_FROM_CE;
+    days.checked_mul(NANOS_PER_DAY)?.checked_add(nanos_of_day)
+}
+
+/// Execution plan producing the values of a [`Series`] in a single partition
+#[derive(Debug)]
+pub struct GenerateSeriesExec {
+    series: Series,
+    /// The output schema, in which every field is the series
+    schema: SchemaRef,
+    /// Maximum number of values to produce
+    limit: Option<usize>,
+}
+
+impl GenerateSeriesExec {
+    /// Create a new exec producing `series` for every field of `schema`
+    pub fn new(series: Series, schema: SchemaRef, limit: Option<usize>) -> Self {
+        Self {
+            series,
+            schema,
+            limit,
+        }
+    }
+
+    /// The generated series
+    pub fn series(&self) -> &Series {
+        &self.series
+    }
+
+    /// Maximum number of values to produce
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+}
+
+impl ExecutionPlan for GenerateSeriesExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::UnspecifiedDistribution
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "GenerateSeriesExec invalid partition {} (expected 0)",
+                partition
+            )));
+        }
+
+        let series = self.series;
+        let schema = self.schema.clone();
+        let batch_size = context.session_config().batch_size;
+        let mut remaining = self.limit.unwrap_or(usize::MAX);
+        let mut index = 0;
+        let batches = stream::iter(std::iter::from_fn(move || {
+            let values = series.values(index, batch_size.min(remaining));
+            if values.is_empty() {
+                return None;
+            }
+            index += values.len() as i64;
+            remaining -= values.len();
+            Some(create_batch(&series, &schema, values))
+        }));
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            batches,
+        )))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "GenerateSeriesExec: start={}, stop={}, step={:?}, include_stop={}",
+                    self.series.start,
+                    self.series.stop,
+                    self.series.step,
+                    self.series.include_stop
+                )?;
+                if let Some(limit) = self.limit {
+                    write!(f, ", limit={}", limit)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+fn create_batch(
+    series: &Series,
+    schema: &SchemaRef,
+    values: Vec<i64>,
+) -> ArrowResult<RecordBatch> {
+    let array: ArrayRef = match series.data_type() {
+        DataType::Int64 => Arc::new(Int64Array::from(values)),
+        _ => Arc::new(TimestampNanosecondArray::from_vec(values, None)),
+    };
+    let columns = schema.fields().iter().map(|_| array.clone()).collect();
+    RecordBatch::try_new(schema.clone(), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::common;
+    use crate::prelude::{SessionConfig, SessionContext};
+    use arrow::datatypes::{Field, Schema};
+
+    fn values(series: &Series) -> Vec<i64> {
+        series.values(0, 100)
+    }
+
+    #[test]
+    fn int_series() -> Result<()> {
+        let series = Series::try_new(1, 10, SeriesStep::Int64(3), true)?;
+        assert_eq!(values(&series), vec![1, 4, 7, 10]);
+
+        let series = Series::try_new(1, 10, SeriesStep::Int64(3), false)?;
+        assert_eq!(values(&series), vec![1, 4, 7]);
+
+        let series = Series::try_new(5, 1, SeriesStep::Int64(-2), true)?;
+        assert_eq!(values(&series), vec![5, 3, 1]);
+
+        let series = Series::try_new(5, 10, SeriesStep::Int64(-1), true)?;
+        assert!(values(&series).is_empty());
+
+        let series = Series::try_new(i64::MAX - 1, i64::MAX, SeriesStep::Int64(1), true)?;
+        assert_eq!(values(&series), vec![i64::MAX - 1, i64::MAX]);
+
+        let err = Series::try_new(1, 10, SeriesStep::Int64(0), true).unwrap_err();
+        assert!(err.to_string().contains("must not be zero"));
+        Ok(())
+    }
+
+    #[test]
+    fn timestamp_series() -> Result<()> {
+        let day = |y, m, d| {
+            (NaiveDate::from_ymd_opt(y, m, d).unwrap().num_days_from_ce() as i64
+                - EPOCH_DAYS_FROM_CE)
+                * NANOS_PER_DAY
+        };
+
+        // month increments are clamped to the end of the month without drifting
+        let step = SeriesStep::Interval {
+            months: 1,
+            days: 0,
+            nanos: 0,
+        };
+        let series = Series::try_new(day(2020, 1, 31), day(2020, 4, 30), step, true)?;
+        assert_eq!(
+            values(&series),
+            vec![
+                day(2020, 1, 31),
+                day(2020, 2, 29),
+                day(2020, 3, 31),
+                day(2020, 4, 30)
+            ]
+        );
+
+        let step = SeriesStep::Interval {
+            months: 0,
+            days: -1,
+            nanos: -NANOS_PER_DAY / 2,
+        };
+        let series = Series::try_new(day(1970, 1, 1), day(1969, 12, 29), step, false)?;
+        assert_eq!(
+            values(&series),
+            vec![day(1970, 1, 1), day(1969, 12, 31) - NANOS_PER_DAY / 2]
+        );
+
+        let step = SeriesStep::Interval {
+            months: -12,
+            days: 0,
+            nanos: 0,
+        };
+        let series = Series::try_new(day(2000, 2, 29), day(1998, 1, 1), step, true)?;
+        assert_eq!(
+            values(&series),
+            vec![day(2000, 2, 29), day(1999, 2, 28), day(1998, 2, 28)]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn generate_batches() -> Result<()> {
+        let session_ctx =
+            SessionContext::with_config(SessionConfig::new().with_batch_size(4));
+        let task_ctx = session_ctx.task_ctx();
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        let series = Series::try_new(0, 9, SeriesStep::Int64(1), true)?;
+
+        let exec = GenerateSeriesExec::new(series, schema.clone(), None);
+        let batches = common::collect(exec.execute(0, task_ctx.clone())?).await?;
+        let sizes = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![4, 4, 2]);
+
+        let exec = GenerateSeriesExec::new(series, schema, Some(5));
+        let batches = common::collect(exec.execute(0, task_ctx)?).await?;
+        let sizes = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![4, 1]);
+        Ok(())
+    }
+}
@@ -26,7 +26,6 @@ use arrow::{
         TimestampMicrosecondArray, TimestampMillisecondArray, TimestampSecondArray,
         UInt32BufferBuilder, UInt32Builder, UInt64BufferBuilder, UInt64Builder,
     },
-    compute,
     datatypes::{UInt32Type, UInt64Type},
 };
 use smallvec::{smallvec, SmallVec};
@@ -36,7 +35,7 @@ use std::{time::Instant, vec};
 
 use futures::{ready, Stream, StreamExt, TryStreamExt};
 
-use arrow::array::{new_null_array, Array};
+use arrow::array::Array;
 use arrow::datatypes::DataType;
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::error::Result as ArrowResult;
@@ -53,7 +52,11 @@ use hashbrown::raw::RawTable;
 use super::{
     coalesce_partitions::CoalescePartitionsExec,
     expressions::PhysicalSortExpr,
-    join_utils::{build_join_schema, check_join_is_valid, ColumnIndex, JoinOn, JoinSide},
+    join_utils::{
+        append_right_unmatched, apply_join_filter, build_batch_from_indices,
+        build_join_schema, check_join_is_valid, produce_from_matched, ColumnIndex,
+        JoinFilter, JoinOn,
+    },
 };
 use super::{
     expressions::Column,
@@ -64,13 +67,14 @@ use crate::error::{DataFusionError, Result};
 use crate::logical_plan::JoinType;
 
 use super::{
-    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, RecordBatchStream,
     SendableRecordBatchStream,
 };
 use crate::arrow::array::BooleanBufferBuilder;
 use crate::arrow::datatypes::TimeUnit;
 use crate::execution::context::TaskContext;
 use crate::physical_plan::coalesce_batches::concat_batches;
+use crate::physical_plan::runtime_filter::RuntimeFilter;
 use crate::physical_plan::PhysicalExpr;
 
 use crate::physical_plan::join_utils::{OnceAsync, OnceFut};
@@ -126,6 +130,12 @@ pub struct HashJoinExec {
     column_indices: Vec<ColumnIndex>,
     /// If null_equals_null is true, null == null else null != null
     null_equals_null: bool,
+    /// Filter of the pairs of rows with equal keys
+    filter: Option<JoinFilter>,
+    /// Filter to publish the build side keys to, once collected
+    runtime_filter: Option<Arc<RuntimeFilter>>,
+    /// If true, the anti join has the null semantics of `NOT IN`
+    null_aware: bool,
 }
 
 /// Metrics for HashJoinExec
@@ -196,6 +206,12 @@ impl HashJoinExec {
             ));
         }
 
+        if *join_type == JoinType::AsOf {
+            return Err(DataFusionError::Plan(
+                "ASOF joins are not supported by HashJoinExec".to_string(),
+            ));
+        }
+
         check_join_is_valid(&left_schema, &right_schema, &on)?;
 
         let (schema, column_indices) =
@@ -215,9 +231,71 @@ impl HashJoinExec {
             metrics: ExecutionPlanMetricsSet::new(),
             column_indices,
             null_equals_null: *null_equals_null,
+            filter: None,
+            runtime_filter: None,
+            null_aware: false,
         })
     }
 
+    /// Give the anti join the null semantics of `NOT IN`: no row is produced
+    /// if the right side has a null key, and rows with a null key are only
+    /// produced if the right side is empty. This requires both sides to be
+    /// in a single partition.
+    /// # Error
+    /// This function errors when the join is not an anti join on a single key,
+    /// or when it has a filter.
+    pub fn with_null_aware(mut self, null_aware: bool) -> Result<Self> {
+        if null_aware && (self.join_type != JoinType::Anti || self.on.len() != 1) {
+            return Err(DataFusionError::Plan(
+                "Only anti joins on a single key can be null-aware".to_string(),
+            ));
+        }
+        if null_aware && self.filter.is_some() {
+            return Err(DataFusionError::Plan(
+                "Null-aware anti joins can't have a filter".to_string(),
+            ));
+        }
+        self.null_aware = null_aware;
+        Ok(self)
+    }
+
+    /// Whether the anti join has the null semantics of `NOT IN`
+    pub fn null_aware(&self) -> bool {
+        self.null_aware
+    }
+
+    /// Only join the pairs of rows with equal keys that satisfy `filter`. The
+    /// rows without such a pair are the unmatched rows of outer, semi and
+    /// anti joins.
+    /// # Error
+    /// This function errors when the join is null-aware.
+    pub fn with_filter(mut self, filter: Option<JoinFilter>) -> Result<Self> {
+        if filter.is_some() && self.null_aware {
+            return Err(DataFusionError::Plan(
+                "Null-aware anti joins can't have a filter".to_string(),
+            ));
+        }
+        self.filter = filter;
+        Ok(self)
+    }
+
+    /// Filter of the pairs of rows with equal keys, if any
+    pub fn filter(&self) -> Option<&JoinFilter> {
+        self.filter.as_ref()
+    }
+
+    /// Publish the keys of the build side to `runtime_filter` once they have
+    /// been collected, so that it can be applied to the probe side
+    pub fn with_runtime_filter(mut self, runtime_filter: Arc<RuntimeFilter>) -> Self {
+        self.runtime_filter = Some(runtime_filter);
+        self
+    }
+
+    /// The filter the build side keys are published to, if any
+    pub fn runtime_filter(&self) -> Option<&Arc<RuntimeFilter>> {
+        self.runtime_filter.as_ref()
+    }
+
     /// left (build) side which gets hashed
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -266,24 +344,80 @@ impl ExecutionPlan for HashJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(HashJoinExec::try_new(
+        let mut join = HashJoinExec::try_new(
             children[0].clone(),
             children[1].clone(),
             self.on.clone(),
             &self.join_type,
             self.mode,
             &self.null_equals_null,
-        )?))
+        )?;
+        join.filter = self.filter.clone();
+        join.runtime_filter = self.runtime_filter.clone();
+        join.null_aware = self.null_aware;
+        Ok(Arc::new(join))
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        self.right.output_partitioning()
+        let left_columns = self.left.schema().fields().len();
+        let right_partitioning = self.right.output_partitioning();
+        let n = right_partitioning.partition_count();
+        // hash partitioning expressions shifted by `offset` columns, if they
+        // are all columns
+        let shifted = |partitioning: Partitioning, offset: usize| match partitioning {
+            Partitioning::Hash(hash_exprs, n) => hash_exprs
+                .iter()
+                .map(|hash_expr| {
+                    let column = hash_expr.as_any().downcast_ref::<Column>()?;
+                    Some(
+                        Arc::new(Column::new(column.name(), column.index() + offset))
+                            as Arc<dyn PhysicalExpr>,
+                    )
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|hash_exprs| Partitioning::Hash(hash_exprs, n)),
+            _ => None,
+        };
+        // in partitioned mode, each partition joins the rows of the same left
+        // and right partitions, and outputs the left rows it does not match
+        let keeps_left = self.mode == PartitionMode::Partitioned
+            && matches!(
+                self.join_type,
+                JoinType::Inner | JoinType::Left | JoinType::Semi | JoinType::Anti
+            );
+        // the rows of each right partition are output in the same partition,
+        // after the columns of the left side
+        let keeps_right = matches!(self.join_type, JoinType::Inner | JoinType::Right);
+        let partitioning = keeps_left
+            .then(|| shifted(self.left.output_partitioning(), 0))
+            .flatten()
+            .or_else(|| {
+                keeps_right
+                    .then(|| shifted(right_partitioning.clone(), left_columns))
+                    .flatten()
+            });
+        match (partitioning, right_partitioning) {
+            (Some(partitioning), _) => partitioning,
+            (None, Partitioning::Hash(_, _) | Partitioning::Range(_, _)) => {
+                Partitioning::UnknownPartitioning(n)
+            }
+            (None, right_partitioning) => right_partitioning,
+        }
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
         None
     }
 
+    fn required_child_distribution(&self) -> Distribution {
+        // whether the right side has a null key is only known once all of it is seen
+        if self.null_aware {
+            Distribution::SinglePartition
+        } else {
+            Distribution::UnspecifiedDistribution
+        }
+    }
+
     fn relies_on_input_order(&self) -> bool {
         false
     }
@@ -296,22 +430,40 @@ impl ExecutionPlan for HashJoinExec {
         let on_left = self.on.iter().map(|on| on.0.clone()).collect::<Vec<_>>();
         let on_right = self.on.iter().map(|on| on.1.clone()).collect::<Vec<_>>();
 
+        if self.null_aware && self.right.output_partitioning().partition_count() != 1 {
+            return Err(DataFusionError::Execution(
+                "A null-aware anti join requires a single right partition".to_string(),
+            ));
+        }
+
+        // start a new build of the runtime filter before the probe side executes
+        let start_build = || {
+            if let Some(runtime_filter) = &self.runtime_filter {
+                runtime_filter.start_build();
+            }
+        };
         let left_fut = match self.mode {
             PartitionMode::CollectLeft => self.left_fut.once(|| {
+                start_build();
                 collect_left_input(
                     self.random_state.clone(),
                     self.left.clone(),
                     on_left.clone(),
                     context.clone(),
+                    self.runtime_filter.clone(),
                 )
             }),
-            PartitionMode::Partitioned => OnceFut::new(partitioned_left_input(
-                partition,
-                self.random_state.clone(),
-                self.left.clone(),
-                on_left.clone(),
-                context.clone(),
-            )),
+            PartitionMode::Partitioned => {
+                start_build();
+                OnceFut::new(partitioned_left_input(
+                    partition,
+                    self.random_state.clone(),
+                    self.left.clone(),
+                    on_left.clone(),
+                    context.clone(),
+                    self.runtime_filter.clone(),
+                ))
+            }
         };
 
         // we have the batches and the hash map with their keys. We can how create a stream
@@ -330,7 +482,11 @@ impl ExecutionPlan for HashJoinExec {
             random_state: self.random_state.clone(),
             join_metrics: HashJoinMetrics::new(partition, &self.metrics),
             null_equals_null: self.null_equals_null,
+            filter: self.filter.clone(),
             is_exhausted: false,
+            null_aware: self.null_aware,
+            right_is_empty: true,
+            right_has_null: false,
         }))
     }
 
@@ -345,7 +501,17 @@ impl ExecutionPlan for HashJoinExec {
                     f,
                     "HashJoinExec: mode={:?}, join_type={:?}, on={:?}",
                     self.mode, self.join_type, self.on
-                )
+                )?;
+                if let Some(filter) = &self.filter {
+                    write!(f, ", filter={}", filter)?;
+                }
+                if self.runtime_filter.is_some() {
+                    write!(f, ", runtime_filter=true")?;
+                }
+                if self.null_aware {
+                    write!(f, ", null_aware=true")?;
+                }
+                Ok(())
             }
         }
     }
@@ -367,6 +533,7 @@ async fn collect_left_input(
     left: Arc<dyn ExecutionPlan>,
     on_left: Vec<Column>,
     context: Arc<TaskContext>,
+    runtime_filter: Option<Arc<RuntimeFilter>>,
 ) -> Result<JoinLeftData> {
     let schema = left.schema();
     let start = Instant::now();
@@ -407,6 +574,10 @@ async fn collect_left_input(
     // can directly index into the arrays
     let single_batch = concat_batches(&schema, &batches, num_rows)?;
 
+    if let Some(runtime_filter) = runtime_filter {
+        publish_runtime_filter(&runtime_filter, &on_left, &single_batch)?;
+    }
+
     debug!(
         "Built build-side of hash join containing {} rows in {} ms",
         num_rows,
@@ -422,6 +593,7 @@ async fn partitioned_left_input(
     left: Arc<dyn ExecutionPlan>,
     on_left: Vec<Column>,
     context: Arc<TaskContext>,
+    runtime_filter: Option<Arc<RuntimeFilter>>,
 ) -> Result<JoinLeftData> {
     let schema = left.schema();
 
@@ -462,6 +634,10 @@ async fn partitioned_left_input(
     // can directly index into the arrays
     let single_batch = concat_batches(&schema, &batches, num_rows)?;
 
+    if let Some(runtime_filter) = runtime_filter {
+        publish_runtime_filter(&runtime_filter, &on_left, &single_batch)?;
+    }
+
     debug!(
         "Built build-side {} of hash join containing {} rows in {} ms",
         partition,
@@ -472,6 +648,19 @@ async fn partitioned_left_input(
     Ok((hashmap, single_batch))
 }
 
+/// Publishes the values of the build side keys `on` in `batch` to `runtime_filter`
+fn publish_runtime_filter(
+    runtime_filter: &RuntimeFilter,
+    on: &[Column],
+    batch: &RecordBatch,
+) -> Result<()> {
+    let keys = on
+        .iter()
+        .map(|c| Ok(c.evaluate(batch)?.into_array(batch.num_rows())))
+        .collect::<Result<Vec<_>>>()?;
+    runtime_filter.publish(&keys)
+}
+
 /// Updates `hash` with new entries from [RecordBatch] evaluated against the expressions `on`,
 /// assuming that the [RecordBatch] corresponds to the `index`th
 fn update_hash(
@@ -535,6 +724,14 @@ struct HashJoinStream {
     column_indices: Vec<ColumnIndex>,
     /// If null_equals_null is true, null == null else null != null
     null_equals_null: bool,
+    /// Filter of the pairs of rows with equal keys
+    filter: Option<JoinFilter>,
+    /// If true, the anti join has the null semantics of `NOT IN`
+    null_aware: bool,
+    /// No row has been read from the right so far
+    right_is_empty: bool,
+    /// A row with a null key has been read from the right
+    right_has_null: bool,
 }
 
 impl RecordBatchStream for HashJoinStream {
@@ -543,53 +740,6 @@ impl RecordBatchStream for HashJoinStream {
     }
 }
 
-/// Returns a new [RecordBatch] by combining the `left` and `right` according to `indices`.
-/// The resulting batch has [Schema] `schema`.
-/// # Error
-/// This function errors when:
-/// *
-fn build_batch_from_indices(
-    schema: &Schema,
-    left: &RecordBatch,
-    right: &RecordBatch,
-    left_indices: UInt64Array,
-    right_indices: UInt32Array,
-    column_indices: &[ColumnIndex],
-) -> ArrowResult<(RecordBatch, UInt64Array)> {
-    // build the columns of the new [RecordBatch]:
-    // 1. pick whether the column is from the left or right
-    // 2. based on the pick, `take` items from the different RecordBatches
-    let mut columns: Vec<Arc<dyn Array>> = Vec::with_capacity(schema.fields().len());
-
-    for column_index in column_indices {
-        let array = match column_index.side {
-            JoinSide::Left => {
-                let array = left.column(column_index.index);
-                if array.is_empty() || left_indices.null_count() == left_indices.len() {
-                    // Outer join would generate a null index when finding no match at our side.
-                    // Therefore, it's possible we are empty but need to populate an n-length null array,
-                    // where n is the length of the index array.
-                    assert_eq!(left_indices.null_count(), left_indices.len());
-                    new_null_array(array.data_type(), left_indices.len())
-                } else {
-                    compute::take(array.as_ref(), &left_indices, None)?
-                }
-            }
-            JoinSide::Right => {
-                let array = right.column(column_index.index);
-                if array.is_empty() || right_indices.null_count() == right_indices.len() {
-                    assert_eq!(right_indices.null_count(), right_indices.len());
-                    new_null_array(array.data_type(), right_indices.len())
-                } else {
-                    compute::take(array.as_ref(), &right_indices, None)?
-                }
-            }
-        };
-        columns.push(array);
-    }
-    RecordBatch::try_new(Arc::new(schema.clone()), columns).map(|x| (x, left_indices))
-}
-
 #[allow(clippy::too_many_arguments)]
 fn build_batch(
     batch: &RecordBatch,
@@ -601,17 +751,45 @@ fn build_batch(
     column_indices: &[ColumnIndex],
     random_state: &RandomState,
     null_equals_null: &bool,
+    filter: Option<&JoinFilter>,
 ) -> ArrowResult<(RecordBatch, UInt64Array)> {
-    let (left_indices, right_indices) = build_join_indexes(
-        left_data,
-        batch,
-        join_type,
-        on_left,
-        on_right,
-        random_state,
-        null_equals_null,
-    )
-    .unwrap();
+    let (left_indices, right_indices) = match filter {
+        Some(filter) => {
+            // the pairs of rows with equal keys are filtered before the
+            // unmatched rows of the right are known
+            let (left_indices, right_indices) = build_join_indexes(
+                left_data,
+                batch,
+                JoinType::Inner,
+                on_left,
+                on_right,
+                random_state,
+                null_equals_null,
+            )?;
+            let (left_indices, right_indices) = apply_join_filter(
+                filter,
+                &left_data.1,
+                batch,
+                left_indices,
+                right_indices,
+            )?;
+            if matches!(join_type, JoinType::Right | JoinType::Full) {
+                append_right_unmatched(left_indices, right_indices, batch.num_rows())?
+            } else {
+                (left_indices, right_indices)
+            }
+        }
+        None => build_join_indexes(
+            left_data,
+            batch,
+            join_type,
+            on_left,
+            on_right,
+            random_state,
+            null_equals_null,
+        )
+        .unwrap(),
+    };
 
     if matches!(join_type, JoinType::Semi | JoinType::Anti) {
         return Ok((
@@ -788,6 +966,9 @@ fn build_join_indexes(
             }
             Ok((left_indices.finish(), right_indices.finish()))
         }
+        JoinType::AsOf => Err(DataFusionError::Internal(
+            "ASOF joins are not supported by HashJoinExec".to_string(),
+        )),
     }
 }
 
@@ -915,46 +1096,6 @@ fn equal_rows(
     err.unwrap_or(Ok(res))
 }
 
-// Produces a batch for left-side rows that have/have not been matched during the whole join
-fn produce_from_matched(
-    visited_left_side: &BooleanBufferBuilder,
-    schema: &SchemaRef,
-    column_indices: &[ColumnIndex],
-    left_data: &JoinLeftData,
-    unmatched: bool,
-) -> ArrowResult<RecordBatch> {
-    let indices = if unmatched {
-        UInt64Array::from_iter_values(
-            (0..visited_left_side.len())
-                .filter_map(|v| (!visited_left_side.get_bit(v)).then(|| v as u64)),
-        )
-    } else {
-        UInt64Array::from_iter_values(
-            (0..visited_left_side.len())
-                .filter_map(|v| (visited_left_side.get_bit(v)).then(|| v as u64)),
-        )
-    };
-
-    // generate batches by taking values from the left side and generating columns filled with null on the right side
-    let num_rows = indices.len();
-    let mut columns: Vec<Arc<dyn Array>> = Vec::with_capacity(schema.fields().len());
-    for (idx, column_index) in column_indices.iter().enumerate() {
-        let array = match column_index.side {
-            JoinSide::Left => {
-                let array = left_data.1.column(column_index.index);
-                compute::take(array.as_ref(), &indices, None).unwrap()
-            }
-            JoinSide::Right => {
-                let datatype = schema.field(idx).data_type();
-                arrow::array::new_null_array(datatype, num_rows)
-            }
-        };
-
-        columns.push(array);
-    }
-    RecordBatch::try_new(schema.clone(), columns)
-}
-
 impl HashJoinStream {
     /// Separate implementation function that unpins the [`HashJoinStream`] so
     /// that partial borrows work correctly
@@ -977,7 +1118,9 @@ impl HashJoinStream {
 
                     buffer
                 }
-                JoinType::Inner | JoinType::Right => BooleanBufferBuilder::new(0),
+                JoinType::Inner | JoinType::Right | JoinType::AsOf => {
+                    BooleanBufferBuilder::new(0)
+                }
             }
         });
 
@@ -986,6 +1129,11 @@ impl HashJoinStream {
             .map(|maybe_batch| match maybe_batch {
                 Some(Ok(batch)) => {
                     let timer = self.join_metrics.join_time.timer();
+                    if self.null_aware && batch.num_rows() > 0 {
+                        self.right_is_empty = false;
+                        let keys = batch.column(self.on_right[0].index());
+                        self.right_has_null |= keys.null_count() > 0;
+                    }
                     let result = build_batch(
                         &batch,
                         left_data,
@@ -996,6 +1144,7 @@ impl HashJoinStream {
                         &self.column_indices,
                         &self.random_state,
                         &self.null_equals_null,
+                        self.filter.as_ref(),
                     );
                     self.join_metrics.input_batches.add(1);
                     self.join_metrics.input_rows.add(batch.num_rows());
@@ -1013,7 +1162,7 @@ impl HashJoinStream {
                                     visited_left_side.set_bit(x as usize, true);
                                 });
                             }
-                            JoinType::Inner | JoinType::Right => {}
+                            JoinType::Inner | JoinType::Right | JoinType::AsOf => {}
                         }
                     }
                     Some(result.map(|x| x.0))
@@ -1028,11 +1177,21 @@ impl HashJoinStream {
                         | JoinType::Anti
                             if !self.is_exhausted =>
                         {
+                            if self.null_aware && !self.right_is_empty {
+                                // `NOT IN` is unknown for null keys, and for all
+                                // keys if the right side has a null
+                                let keys = left_data.1.column(self.on_left[0].index());
+                                for i in 0..visited_left_side.len() {
+                                    if self.right_has_null || keys.is_null(i) {
+                                        visited_left_side.set_bit(i, true);
+                                    }
+                                }
+                            }
                             let result = produce_from_matched(
                                 visited_left_side,
                                 &self.schema,
                                 &self.column_indices,
-                                left_data,
+                                &left_data.1,
                                 self.join_type != JoinType::Semi,
                             );
                             if let Ok(ref batch) = result {
@@ -1052,7 +1211,8 @@ impl HashJoinStream {
                         | JoinType::Semi
                         | JoinType::Anti
                         | JoinType::Inner
-                        | JoinType::Right => {}
+                        | JoinType::Right
+                        | JoinType::AsOf => {}
                     }
 
                     other
@@ -1083,7 +1243,11 @@ mod tests {
     };
 
     use super::*;
+    use crate::logical_plan::Operator;
+    use crate::physical_plan::expressions::{binary, col};
+    use crate::physical_plan::join_utils::JoinSide;
     use crate::prelude::SessionContext;
+    use arrow::datatypes::Field;
     use std::sync::Arc;
 
     fn build_table(
@@ -1820,6 +1984,206 @@ mod tests {
         Ok(())
     }
 
+    fn build_nullable_table(
+        name: &str,
+        values: Vec<Option<i32>>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new(name, DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+        .unwrap();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    async fn null_aware_anti_join_collect(
+        left: Vec<Option<i32>>,
+        right: Vec<Option<i32>>,
+        context: Arc<TaskContext>,
+    ) -> Result<Vec<RecordBatch>> {
+        let left = build_nullable_table("a", left);
+        let right = build_nullable_table("b", right);
+        let on = vec![(
+            Column::new_with_schema("a", &left.schema())?,
+            Column::new_with_schema("b", &right.schema())?,
+        )];
+        let join =
+            join(left, right, on, &JoinType::Anti, false)?.with_null_aware(true)?;
+        let stream = join.execute(0, context)?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn join_anti_null_aware() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = vec![Some(1), Some(2), None, Some(4)];
+
+        // rows with a null key are dropped
+        let batches = null_aware_anti_join_collect(
+            left.clone(),
+            vec![Some(1), Some(3)],
+            task_ctx.clone(),
+        )
+        .await?;
+        let expected = vec!["+---+", "| a |", "+---+", "| 2 |", "| 4 |", "+---+"];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // a null key on the right drops all rows
+        let batches = null_aware_anti_join_collect(
+            left.clone(),
+            vec![Some(1), None],
+            task_ctx.clone(),
+        )
+        .await?;
+        let expected = vec!["+---+", "| a |", "+---+", "+---+"];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // all rows are kept if the right is empty
+        let batches = null_aware_anti_join_collect(left, vec![], task_ctx).await?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "|   |", "| 1 |", "| 2 |", "| 4 |", "+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn join_null_aware_requires_anti_join() -> Result<()> {
+        let left = build_nullable_table("a", vec![Some(1)]);
+        let right = build_nullable_table("b", vec![Some(1)]);
+        let on = vec![(
+            Column::new_with_schema("a", &left.schema())?,
+            Column::new_with_schema("b", &right.schema())?,
+        )];
+        let err = join(left, right, on, &JoinType::Semi, false)?
+            .with_null_aware(true)
+            .unwrap_err();
+        assert!(err.to_string().contains("Only anti joins on a single key"));
+        Ok(())
+    }
+
+    /// Joins on `b1 = b2`, keeping the pairs of rows where `c1 < c2`
+    async fn join_filtered_collect(join_type: JoinType) -> Result<Vec<RecordBatch>> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![4, 5, 6]),
+            ("c2", &vec![70, 8, 90]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b2", &right.schema())?,
+        )];
+        let filter_schema = Schema::new(vec![
+            Field::new("c1", DataType::Int32, false),
+            Field::new("c2", DataType::Int32, false),
+        ]);
+        let filter = JoinFilter::try_new(
+            binary(
+                col("c1", &filter_schema)?,
+                Operator::Lt,
+                col("c2", &filter_schema)?,
+                &filter_schema,
+            )?,
+            vec![
+                ColumnIndex {
+                    index: 2,
+                    side: JoinSide::Left,
+                },
+                ColumnIndex {
+                    index: 2,
+                    side: JoinSide::Right,
+                },
+            ],
+            filter_schema,
+        )?;
+        let join = join(left, right, on, &join_type, false)?.with_filter(Some(filter))?;
+
+        let stream = join.execute(0, task_ctx)?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn join_with_filter() -> Result<()> {
+        let batches = join_filtered_collect(JoinType::Inner).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let batches = join_filtered_collect(JoinType::Left).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  |    |    |    |",
+            "| 3  | 5  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let batches = join_filtered_collect(JoinType::Right).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 20 | 5  | 8  |",
+            "|    |    |    | 30 | 6  | 90 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let batches = join_filtered_collect(JoinType::Full).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 20 | 5  | 8  |",
+            "|    |    |    | 30 | 6  | 90 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  |    |    |    |",
+            "| 3  | 5  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let batches = join_filtered_collect(JoinType::Semi).await?;
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 4  | 7  |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let batches = join_filtered_collect(JoinType::Anti).await?;
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 2  | 5  | 8  |",
+            "| 3  | 5  | 9  |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_right_one() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -20,8 +20,15 @@
 use crate::error::{DataFusionError, Result};
 use crate::logical_plan::JoinType;
 use crate::physical_plan::expressions::Column;
-use arrow::datatypes::{Field, Schema};
-use arrow::error::ArrowError;
+use crate::physical_plan::PhysicalExpr;
+use arrow::array::{
+    new_null_array, Array, BooleanArray, BooleanBufferBuilder, UInt32Array,
+    UInt32Builder, UInt64Array, UInt64Builder,
+};
+use arrow::compute;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::record_batch::RecordBatch;
 use futures::future::{BoxFuture, Shared};
 use futures::{ready, FutureExt};
 use parking_lot::Mutex;
@@ -96,6 +103,172 @@ pub struct ColumnIndex {
     pub side: JoinSide,
 }
 
+impl JoinSide {
+    /// The opposite side, once the sides of the join are swapped
+    pub fn negate(&self) -> Self {
+        match self {
+            JoinSide::Left => JoinSide::Right,
+            JoinSide::Right => JoinSide::Left,
+        }
+    }
+}
+
+/// The number of pairs of rows a [`JoinFilter`] is evaluated against at once
+pub(crate) const JOIN_FILTER_CHUNK_SIZE: usize = 8192;
+
+/// A filter of the pairs of rows of a join beyond the equality of the join keys,
+/// evaluated against an intermediate batch made of the columns of either side it
+/// refers to
+#[derive(Debug, Clone)]
+pub struct JoinFilter {
+    /// Filter expression, evaluated against `schema`
+    expression: Arc<dyn PhysicalExpr>,
+    /// The columns of either side that make up `schema`
+    column_indices: Vec<ColumnIndex>,
+    /// The schema the filter is evaluated against
+    schema: Schema,
+}
+
+impl JoinFilter {
+    /// Creates a new [`JoinFilter`]
+    /// # Error
+    /// This function errors when `expression` is not a boolean expression of `schema`,
+    /// or when `column_indices` don't describe the columns of `schema`
+    pub fn try_new(
+        expression: Arc<dyn PhysicalExpr>,
+        column_indices: Vec<ColumnIndex>,
+        schema: Schema,
+    ) -> Result<Self> {
+        if column_indices.len() != schema.fields().len() {
+            return Err(DataFusionError::Plan(format!(
+                "The join filter refers to {} columns, but its schema has {} fields",
+                column_indices.len(),
+                schema.fields().len()
+            )));
+        }
+        match expression.data_type(&schema)? {
+            DataType::Boolean => Ok(Self {
+                expression,
+                column_indices,
+                schema,
+            }),
+            other => Err(DataFusionError::Plan(format!(
+                "The filter of a join must return a boolean, not {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Filter expression
+    pub fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expression
+    }
+
+    /// The columns of either side the filter is evaluated against
+    pub fn column_indices(&self) -> &[ColumnIndex] {
+        &self.column_indices
+    }
+
+    /// The schema the filter is evaluated against
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// The same filter, once the sides of the join are swapped
+    pub fn swap(&self) -> Self {
+        Self {
+            expression: self.expression.clone(),
+            column_indices: self
+                .column_indices
+                .iter()
+                .map(|column_index| ColumnIndex {
+                    index: column_index.index,
+                    side: column_index.side.negate(),
+                })
+                .collect(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for JoinFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+/// Keeps the pairs of rows of `left` and `right`, given by `left_indices` and
+/// `right_indices`, which satisfy `filter`. Only the columns the filter refers to are
+/// gathered, a chunk of pairs at a time.
+pub(crate) fn apply_join_filter(
+    filter: &JoinFilter,
+    left: &RecordBatch,
+    right: &RecordBatch,
+    left_indices: UInt64Array,
+    right_indices: UInt32Array,
+) -> Result<(UInt64Array, UInt32Array)> {
+    let mut kept_left = UInt64Builder::new(left_indices.len());
+    let mut kept_right = UInt32Builder::new(right_indices.len());
+    let mut offset = 0;
+    while offset < left_indices.len() {
+        let len = JOIN_FILTER_CHUNK_SIZE.min(left_indices.len() - offset);
+        let chunk_left =
+            UInt64Array::from(left_indices.slice(offset, len).data().clone());
+        let chunk_right =
+            UInt32Array::from(right_indices.slice(offset, len).data().clone());
+        let (batch, chunk_left) = build_batch_from_indices(
+            &filter.schema,
+            left,
+            right,
+            chunk_left,
+            UInt32Array::from(chunk_right.data().clone()),
+            &filter.column_indices,
+        )?;
+        let mask = filter.expression.evaluate(&batch)?.into_array(len);
+        let mask = mask
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "The filter of a join must return a boolean".to_string(),
+                )
+            })?;
+        for (i, matched) in mask.iter().enumerate() {
+            if matched == Some(true) {
+                kept_left.append_value(chunk_left.value(i))?;
+                kept_right.append_value(chunk_right.value(i))?;
+            }
+        }
+        offset += len;
+    }
+    Ok((kept_left.finish(), kept_right.finish()))
+}
+
+/// Joins the rows of the right side that are not in `right_indices` with nulls, for
+/// the outer joins which keep the unmatched rows of the right side
+pub(crate) fn append_right_unmatched(
+    left_indices: UInt64Array,
+    right_indices: UInt32Array,
+    num_right_rows: usize,
+) -> Result<(UInt64Array, UInt32Array)> {
+    let mut matched = vec![false; num_right_rows];
+    for index in right_indices.iter().flatten() {
+        matched[index as usize] = true;
+    }
+    let num_unmatched = matched.iter().filter(|m| !**m).count();
+    let mut all_left = UInt64Builder::new(left_indices.len() + num_unmatched);
+    let mut all_right = UInt32Builder::new(right_indices.len() + num_unmatched);
+    for (left, right) in left_indices.iter().zip(right_indices.iter()) {
+        all_left.append_option(left)?;
+        all_right.append_option(right)?;
+    }
+    for (index, _) in matched.iter().enumerate().filter(|(_, m)| !**m) {
+        all_left.append_null()?;
+        all_right.append_value(index as u32)?;
+    }
+    Ok((all_left.finish(), all_right.finish()))
+}
+
 /// Creates a schema for a join operation.
 /// The fields from the left side are first
 pub fn build_join_schema(
@@ -104,7 +277,11 @@ pub fn build_join_schema(
     join_type: &JoinType,
 ) -> (Schema, Vec<ColumnIndex>) {
     let (fields, column_indices): (Vec<Field>, Vec<ColumnIndex>) = match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::Full
+        | JoinType::Right
+        | JoinType::AsOf => {
             let left_fields =
                 left.fields().iter().cloned().enumerate().map(|(index, f)| {
                     (
@@ -154,6 +331,93 @@ pub fn build_join_schema(
     (Schema::new(fields), column_indices)
 }
 
+/// Returns a new [RecordBatch] by combining the `left` and `right` according to `indices`.
+/// The resulting batch has [Schema] `schema`.
+/// # Error
+/// This function errors when:
+/// *
+pub(crate) fn build_batch_from_indices(
+    schema: &Schema,
+    left: &RecordBatch,
+    right: &RecordBatch,
+    left_indices: UInt64Array,
+    right_indices: UInt32Array,
+    column_indices: &[ColumnIndex],
+) -> ArrowResult<(RecordBatch, UInt64Array)> {
+    // build the columns of the new [RecordBatch]:
+    // 1. pick whether the column is from the left or right
+    // 2. based on the pick, `take` items from the different RecordBatches
+    let mut columns: Vec<Arc<dyn Array>> = Vec::with_capacity(schema.fields().len());
+
+    for column_index in column_indices {
+        let array = match column_index.side {
+            JoinSide::Left => {
+                let array = left.column(column_index.index);
+                if array.is_empty() || left_indices.null_count() == left_indices.len() {
+                    // Outer join would generate a null index when finding no match at our side.
+                    // Therefore, it's possible we are empty but need to populate an n-length null array,
+                    // where n is the length of the index array.
+                    assert_eq!(left_indices.null_count(), left_indices.len());
+                    new_null_array(array.data_type(), left_indices.len())
+                } else {
+                    compute::take(array.as_ref(), &left_indices, None)?
+                }
+            }
+            JoinSide::Right => {
+                let array = right.column(column_index.index);
+                if array.is_empty() || right_indices.null_count() == right_indices.len() {
+                    assert_eq!(right_indices.null_count(), right_indices.len());
+                    new_null_array(array.data_type(), right_indices.len())
+                } else {
+                    compute::take(array.as_ref(), &right_indices, None)?
+                }
+            }
+        };
+        columns.push(array);
+    }
+    RecordBatch::try_new(Arc::new(schema.clone()), columns).map(|x| (x, left_indices))
+}
+
+// Produces a batch for left-side rows that have/have not been matched during the whole join
+pub(crate) fn produce_from_matched(
+    visited_left_side: &BooleanBufferBuilder,
+    schema: &SchemaRef,
+    column_indices: &[ColumnIndex],
+    left_data: &RecordBatch,
+    unmatched: bool,
+) -> ArrowResult<RecordBatch> {
+    let indices = if unmatched {
+        UInt64Array::from_iter_values(
+            (0..visited_left_side.len())
+                .filter_map(|v| (!visited_left_side.get_bit(v)).then(|| v as u64)),
+        )
+    } else {
+        UInt64Array::from_iter_values(
+            (0..visited_left_side.len())
+                .filter_map(|v| (visited_left_side.get_bit(v)).then(|| v as u64)),
+        )
+    };
+
+    // generate batches by taking values from the left side and generating columns filled with null on the right side
+    let num_rows = indices.len();
+    let mut columns: Vec<Arc<dyn Array>> = Vec::with_capacity(schema.fields().len());
+    for (idx, column_index) in column_indices.iter().enumerate() {
+        let array = match column_index.side {
+            JoinSide::Left => {
+                let array = left_data.column(column_index.index);
+                compute::take(array.as_ref(), &indices, None).unwrap()
+            }
+            JoinSide::Right => {
+                let datatype = schema.field(idx).data_type();
+                arrow::array::new_null_array(datatype, num_rows)
+            }
+        };
+
+        columns.push(array);
+    }
+    RecordBatch::try_new(schema.clone(), columns)
+}
+
 /// A [`OnceAsync`] can be used to run an async closure once, with subsequent calls
 /// to [`OnceAsync::once`] returning a [`OnceFut`] to the same asynchronous computation
 ///
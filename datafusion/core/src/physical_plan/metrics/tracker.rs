@@ -84,6 +84,22 @@ impl MemTrackingMetrics {
         }
     }
 
+    /// grow the memory usage by `size` and register it with memory manager
+    pub fn grow_mem_used(&self, size: usize) {
+        self.metrics.mem_used().add(size);
+        if let Some(rt) = self.runtime.as_ref() {
+            rt.memory_manager.grow_tracker_usage(size);
+        }
+    }
+
+    /// shrink the memory usage by `size` and release it from memory manager
+    pub fn shrink_mem_used(&self, size: usize) {
+        self.metrics.mem_used().sub(size);
+        if let Some(rt) = self.runtime.as_ref() {
+            rt.memory_manager.shrink_tracker_usage(size);
+        }
+    }
+
     /// return the metric for the total number of output rows produced
     pub fn output_rows(&self) -> &Count {
         self.metrics.output_rows()
@@ -467,8 +467,13 @@ pub enum Partitioning {
     /// Allocate batches using a round-robin algorithm and the specified number of partitions
     RoundRobinBatch(usize),
     /// Allocate rows based on a hash of one of more expressions and the specified number of
-    /// partitions
+    /// partitions, the hash of [`bucket_hash::create_bucket_hashes`]
     Hash(Vec<Arc<dyn PhysicalExpr>>, usize),
+    /// Allocate rows to the specified number of partitions based on ranges of
+    /// their sort key, so that the rows of a partition sort before those of
+    /// the following partitions. The bounds of the ranges are computed by
+    /// sampling the rows to partition.
+    Range(Vec<PhysicalSortExpr>, usize),
     /// Unknown partitioning scheme with a known number of partitions
     UnknownPartitioning(usize),
 }
@@ -478,7 +483,7 @@ impl Partitioning {
     pub fn partition_count(&self) -> usize {
         use Partitioning::*;
         match self {
-            RoundRobinBatch(n) | Hash(_, n) | UnknownPartitioning(n) => *n,
+            RoundRobinBatch(n) | Hash(_, n) | Range(_, n) | UnknownPartitioning(n) => *n,
         }
     }
 }
@@ -540,6 +545,7 @@ pub fn project_schema(
 
 pub mod aggregates;
 pub mod analyze;
+pub mod bucket_hash;
 pub mod coalesce_batches;
 pub mod coalesce_partitions;
 pub mod common;
@@ -553,21 +559,28 @@ pub use datafusion_physical_expr::expressions;
 pub mod file_format;
 pub mod filter;
 pub mod functions;
+pub mod generate_series;
 pub mod hash_join;
 pub mod hash_utils;
 pub mod join_utils;
 pub mod limit;
 pub mod memory;
 pub mod metrics;
+pub mod nested_loop_join;
 pub mod planner;
 pub mod projection;
+pub mod recursive_query;
 pub mod repartition;
+pub mod runtime_filter;
 pub mod sort_merge_join;
 pub mod sorts;
 pub mod stream;
 pub mod type_coercion;
 pub mod udaf;
 pub mod udf;
+pub mod udwf;
 pub mod union;
+pub mod unnest;
 pub mod values;
 pub mod windows;
+pub mod work_table;
@@ -0,0 +1,724 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the nested loop join plan, which joins the rows of a buffered left
+//! side and a streamed right side on an arbitrary predicate
+
+use std::any::Any;
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Instant;
+
+use arrow::array::{BooleanBufferBuilder, UInt32Array, UInt64Array};
+use arrow::datatypes::SchemaRef;
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::record_batch::RecordBatch;
+use futures::{ready, Stream, StreamExt, TryStreamExt};
+use log::debug;
+
+use super::coalesce_batches::concat_batches;
+use super::coalesce_partitions::CoalescePartitionsExec;
+use super::common::batch_byte_size;
+use super::expressions::PhysicalSortExpr;
+use super::join_utils::{
+    append_right_unmatched, apply_join_filter, build_batch_from_indices,
+    build_join_schema, check_join_is_valid, produce_from_matched, ColumnIndex,
+    JoinFilter, OnceAsync, OnceFut, JOIN_FILTER_CHUNK_SIZE,
+};
+use super::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use super::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+use crate::execution::MemoryReservation;
+use crate::logical_plan::JoinType;
+
+/// The buffered left side, and the reservation of the memory it uses
+type JoinLeftData = (RecordBatch, MemoryReservation);
+
+/// Join execution plan that evaluates a predicate for every pair of rows of
+/// its inputs, for joins whose condition is not an equality of columns.
+///
+/// The left side is buffered in memory and shared by the partitions of the
+/// right side, which are streamed.
+#[derive(Debug)]
+pub struct NestedLoopJoinExec {
+    /// left side, which is buffered in memory
+    left: Arc<dyn ExecutionPlan>,
+    /// right side, which is streamed
+    right: Arc<dyn ExecutionPlan>,
+    /// Predicate of the join, evaluated for every pair of rows
+    filter: JoinFilter,
+    /// How the join is performed
+    join_type: JoinType,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Buffered left side
+    left_fut: OnceAsync<JoinLeftData>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl NestedLoopJoinExec {
+    /// Tries to create a new [NestedLoopJoinExec].
+    /// # Error
+    /// This function errors when the sides of the join are not valid
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        filter: JoinFilter,
+        join_type: &JoinType,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        check_join_is_valid(&left_schema, &right_schema, &[])?;
+
+        let (schema, column_indices) =
+            build_join_schema(&left_schema, &right_schema, join_type);
+
+        Ok(NestedLoopJoinExec {
+            left,
+            right,
+            filter,
+            join_type: *join_type,
+            schema: Arc::new(schema),
+            column_indices,
+            left_fut: Default::default(),
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// left side, which is buffered in memory
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// right side, which is streamed
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// Predicate of the join
+    pub fn filter(&self) -> &JoinFilter {
+        &self.filter
+    }
+
+    /// How the join is performed
+    pub fn join_type(&self) -> &JoinType {
+        &self.join_type
+    }
+
+    /// Whether the unmatched rows of the left side are tracked, which is only
+    /// possible if a single stream sees all of the right side
+    fn tracks_left_side(&self) -> bool {
+        matches!(
+            self.join_type,
+            JoinType::Left | JoinType::Full | JoinType::Semi | JoinType::Anti
+        )
+    }
+}
+
+impl ExecutionPlan for NestedLoopJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(NestedLoopJoinExec::try_new(
+            children[0].clone(),
+            children[1].clone(),
+            self.filter.clone(),
+            &self.join_type,
+        )?))
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(
+            self.right.output_partitioning().partition_count(),
+        )
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        if self.tracks_left_side() {
+            Distribution::SinglePartition
+        } else {
+            Distribution::UnspecifiedDistribution
+        }
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if self.tracks_left_side()
+            && self.right.output_partitioning().partition_count() != 1
+        {
+            return Err(DataFusionError::Execution(format!(
+                "A nested loop join of type {:?} requires a single right partition",
+                self.join_type
+            )));
+        }
+
+        let left_fut = self.left_fut.once(|| {
+            load_left_input(self.left.clone(), self.metrics.clone(), context.clone())
+        });
+        let right = self.right.execute(partition, context)?;
+
+        Ok(Box::pin(NestedLoopJoinStream {
+            schema: self.schema.clone(),
+            filter: self.filter.clone(),
+            join_type: self.join_type,
+            left_fut,
+            tracks_left_side: self.tracks_left_side(),
+            visited_left_side: None,
+            right,
+            column_indices: self.column_indices.clone(),
+            is_exhausted: false,
+            metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "NestedLoopJoinExec: join_type={:?}, filter={}",
+                    self.join_type, self.filter
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Asynchronously collect the left side into a single batch, whose memory is
+/// reserved as it is loaded and until the join is dropped, failing if the
+/// memory manager refuses to grant it
+async fn load_left_input(
+    left: Arc<dyn ExecutionPlan>,
+    metrics: ExecutionPlanMetricsSet,
+    context: Arc<TaskContext>,
+) -> Result<JoinLeftData> {
+    let start = Instant::now();
+    let reservation = MemoryReservation::new(
+        "NestedLoopJoinExec",
+        0,
+        context.runtime_env().memory_manager.clone(),
+    );
+    let mem_used = MetricBuilder::new(&metrics).mem_used(0);
+
+    // merge all left parts into a single stream
+    let merge = CoalescePartitionsExec::new(left.clone());
+    let stream = merge.execute(0, context)?;
+
+    let (batches, num_rows) = stream
+        .map_err(DataFusionError::from)
+        .try_fold((Vec::new(), 0usize), |mut acc, batch| {
+            let reservation = &reservation;
+            let mem_used = &mem_used;
+            async move {
+                let size = batch_byte_size(&batch);
+                reservation.grow(size).await?;
+                mem_used.add(size);
+                acc.1 += batch.num_rows();
+                acc.0.push(batch);
+                Ok(acc)
+            }
+        })
+        .await?;
+
+    let merged_batch = concat_batches(&left.schema(), &batches, num_rows)?;
+
+    debug!(
+        "Buffered left side of nested loop join containing {} rows in {} ms",
+        num_rows,
+        start.elapsed().as_millis()
+    );
+
+    Ok((merged_batch, reservation))
+}
+
+/// A stream that joins the [RecordBatch]es of the right side with the
+/// buffered left side as they arrive
+struct NestedLoopJoinStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// Predicate of the join
+    filter: JoinFilter,
+    /// type of the join
+    join_type: JoinType,
+    /// future for data from left side
+    left_fut: OnceFut<JoinLeftData>,
+    /// Whether the rows of the left side that are visited are tracked
+    tracks_left_side: bool,
+    /// Keeps track of the left side rows whether they are visited
+    visited_left_side: Option<BooleanBufferBuilder>,
+    /// right
+    right: SendableRecordBatchStream,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// The unmatched rows of the left side have been produced
+    is_exhausted: bool,
+    /// Metrics
+    metrics: BaselineMetrics,
+}
+
+impl RecordBatchStream for NestedLoopJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Returns the indices of the pairs of `left` and `right` rows that satisfy
+/// `filter`, ordered by the right row. The pairs are made for a few rows of the
+/// right at a time, which bounds the size of the batches the filter is
+/// evaluated against.
+fn matching_indices(
+    left: &RecordBatch,
+    right: &RecordBatch,
+    filter: &JoinFilter,
+) -> Result<(UInt64Array, UInt32Array)> {
+    let num_left_rows = left.num_rows();
+    if num_left_rows == 0 {
+        return Ok((
+            UInt64Array::from(Vec::<u64>::new()),
+            UInt32Array::from(Vec::<u32>::new()),
+        ));
+    }
+
+    let right_rows_per_chunk = (JOIN_FILTER_CHUNK_SIZE / num_left_rows).max(1);
+    let mut left_matches = vec![];
+    let mut right_matches = vec![];
+    let mut right_start = 0;
+    while right_start < right.num_rows() {
+        let right_end = (right_start + right_rows_per_chunk).min(right.num_rows());
+        // pair the rows of the right with every row of the left
+        let left_indices = UInt64Array::from_iter_values(
+            (right_start..right_end).flat_map(|_| 0..num_left_rows as u64),
+        );
+        let right_indices = UInt32Array::from_iter_values(
+            (right_start..right_end)
+                .flat_map(|row| std::iter::repeat(row as u32).take(num_left_rows)),
+        );
+        let (left_indices, right_indices) =
+            apply_join_filter(filter, left, right, left_indices, right_indices)?;
+        left_matches.extend(left_indices.values().iter().copied());
+        right_matches.extend(right_indices.values().iter().copied());
+        right_start = right_end;
+    }
+    Ok((
+        UInt64Array::from(left_matches),
+        UInt32Array::from(right_matches),
+    ))
+}
+
+impl NestedLoopJoinStream {
+    /// Joins a batch of the right side with the left side, marking the rows of
+    /// the left side that have a match
+    fn join_right_batch(
+        &mut self,
+        left_data: &RecordBatch,
+        batch: &RecordBatch,
+    ) -> ArrowResult<RecordBatch> {
+        let (left_indices, right_indices) =
+            matching_indices(left_data, batch, &self.filter)
+                .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+
+        if let Some(visited_left_side) = self.visited_left_side.as_mut() {
+            for index in left_indices.values() {
+                visited_left_side.set_bit(*index as usize, true);
+            }
+        }
+
+        if matches!(self.join_type, JoinType::Semi | JoinType::Anti) {
+            return Ok(RecordBatch::new_empty(self.schema.clone()));
+        }
+
+        // the unmatched rows of the right are joined with nulls
+        let (left_indices, right_indices) =
+            if matches!(self.join_type, JoinType::Right | JoinType::Full) {
+                append_right_unmatched(left_indices, right_indices, batch.num_rows())
+                    .map_err(|e| ArrowError::ExternalError(Box::new(e)))?
+            } else {
+                (left_indices, right_indices)
+            };
+
+        build_batch_from_indices(
+            &self.schema,
+            left_data,
+            batch,
+            left_indices,
+            right_indices,
+            &self.column_indices,
+        )
+        .map(|(batch, _)| batch)
+    }
+
+    /// Separate implementation function that unpins the [`NestedLoopJoinStream`]
+    /// so that partial borrows work correctly
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<ArrowResult<RecordBatch>>> {
+        let left_data = match ready!(self.left_fut.get(cx)) {
+            Ok(left_data) => left_data.0.clone(),
+            Err(e) => return Poll::Ready(Some(Err(e))),
+        };
+
+        if self.tracks_left_side && self.visited_left_side.is_none() {
+            let num_rows = left_data.num_rows();
+            let mut buffer = BooleanBufferBuilder::new(num_rows);
+            buffer.append_n(num_rows, false);
+            self.visited_left_side = Some(buffer);
+        }
+
+        match ready!(self.right.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => {
+                let elapsed_compute = self.metrics.elapsed_compute().clone();
+                let timer = elapsed_compute.timer();
+                let result = self.join_right_batch(&left_data, &batch);
+                timer.done();
+                Poll::Ready(Some(result))
+            }
+            Some(Err(e)) => Poll::Ready(Some(Err(e))),
+            None => {
+                if !self.tracks_left_side || self.is_exhausted {
+                    return Poll::Ready(None);
+                }
+                self.is_exhausted = true;
+
+                // produce the rows of the left side that did or did not match
+                let elapsed_compute = self.metrics.elapsed_compute().clone();
+                let timer = elapsed_compute.timer();
+                let result = produce_from_matched(
+                    self.visited_left_side.as_ref().unwrap(),
+                    &self.schema,
+                    &self.column_indices,
+                    &left_data,
+                    self.join_type != JoinType::Semi,
+                );
+                timer.done();
+                Poll::Ready(Some(result))
+            }
+        }
+    }
+}
+
+impl Stream for NestedLoopJoinStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_impl(cx);
+        self.metrics.record_poll(poll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_sorted_eq;
+    use crate::execution::memory_manager::MemoryManagerConfig;
+    use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use crate::logical_plan::Operator;
+    use crate::physical_plan::expressions::{binary, col};
+    use crate::physical_plan::metrics::MetricValue;
+    use crate::physical_plan::{common, memory::MemoryExec, PhysicalExpr};
+    use crate::prelude::{SessionConfig, SessionContext};
+    use crate::test::build_table_i32;
+    use arrow::datatypes::Schema;
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    /// Builds a filter of the columns of both sides with `expression`
+    fn join_filter(
+        left: &Arc<dyn ExecutionPlan>,
+        right: &Arc<dyn ExecutionPlan>,
+        expression: impl FnOnce(&Schema) -> Result<Arc<dyn PhysicalExpr>>,
+    ) -> Result<JoinFilter> {
+        let (schema, column_indices) =
+            build_join_schema(&left.schema(), &right.schema(), &JoinType::Inner);
+        JoinFilter::try_new(expression(&schema)?, column_indices, schema)
+    }
+
+    /// Joins `left.b1 BETWEEN right.b2 AND right.c2`
+    async fn join_between(join_type: JoinType) -> Result<Vec<RecordBatch>> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![5, 15, 25]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![0, 10, 100]),
+            ("c2", &vec![10, 20, 200]),
+        );
+        let filter = join_filter(&left, &right, |schema| {
+            binary(
+                binary(
+                    col("b1", schema)?,
+                    Operator::GtEq,
+                    col("b2", schema)?,
+                    schema,
+                )?,
+                Operator::And,
+                binary(
+                    col("b1", schema)?,
+                    Operator::LtEq,
+                    col("c2", schema)?,
+                    schema,
+                )?,
+                schema,
+            )
+        })?;
+
+        let join = NestedLoopJoinExec::try_new(left, right, filter, &join_type)?;
+        let stream = join.execute(0, task_ctx)?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn join_inner() -> Result<()> {
+        let batches = join_between(JoinType::Inner).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 5  | 7  | 10 | 0  | 10 |",
+            "| 2  | 15 | 8  | 20 | 10 | 20 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_left() -> Result<()> {
+        let batches = join_between(JoinType::Left).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 5  | 7  | 10 | 0  | 10 |",
+            "| 2  | 15 | 8  | 20 | 10 | 20 |",
+            "| 3  | 25 | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_right() -> Result<()> {
+        let batches = join_between(JoinType::Right).await?;
+        let expected = vec![
+            "+----+----+----+----+-----+-----+",
+            "| a1 | b1 | c1 | a2 | b2  | c2  |",
+            "+----+----+----+----+-----+-----+",
+            "|    |    |    | 30 | 100 | 200 |",
+            "| 1  | 5  | 7  | 10 | 0   | 10  |",
+            "| 2  | 15 | 8  | 20 | 10  | 20  |",
+            "+----+----+----+----+-----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_full() -> Result<()> {
+        let batches = join_between(JoinType::Full).await?;
+        let expected = vec![
+            "+----+----+----+----+-----+-----+",
+            "| a1 | b1 | c1 | a2 | b2  | c2  |",
+            "+----+----+----+----+-----+-----+",
+            "|    |    |    | 30 | 100 | 200 |",
+            "| 1  | 5  | 7  | 10 | 0   | 10  |",
+            "| 2  | 15 | 8  | 20 | 10  | 20  |",
+            "| 3  | 25 | 9  |    |     |     |",
+            "+----+----+----+----+-----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_semi_and_anti() -> Result<()> {
+        let batches = join_between(JoinType::Semi).await?;
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 5  | 7  |",
+            "| 2  | 15 | 8  |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let batches = join_between(JoinType::Anti).await?;
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 3  | 25 | 9  |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn buffered_side_is_tracked() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![2]), ("c1", &vec![3]));
+        let right = build_table(("a2", &vec![1]), ("b2", &vec![2]), ("c2", &vec![3]));
+        let filter = join_filter(&left, &right, |schema| {
+            binary(col("a1", schema)?, Operator::Lt, col("b2", schema)?, schema)
+        })?;
+        let join = NestedLoopJoinExec::try_new(left, right, filter, &JoinType::Inner)?;
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+        let mem_used = join
+            .metrics()
+            .unwrap()
+            .sum(|m| matches!(m.value(), MetricValue::CurrentMemoryUsage(_)))
+            .map(|v| v.as_usize());
+        assert!(mem_used.unwrap() > 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn buffered_side_exceeding_memory_limit() -> Result<()> {
+        let config = RuntimeConfig::new()
+            .with_memory_manager(MemoryManagerConfig::try_new_limit(1024, 1.0)?);
+        let runtime = Arc::new(RuntimeEnv::new(config)?);
+        let session_ctx = SessionContext::with_config_rt(SessionConfig::new(), runtime);
+        let task_ctx = session_ctx.task_ctx();
+        let values = (0..1000).collect::<Vec<_>>();
+        let left = build_table(("a1", &values), ("b1", &values), ("c1", &values));
+        let right = build_table(("a2", &vec![1]), ("b2", &vec![2]), ("c2", &vec![3]));
+        let filter = join_filter(&left, &right, |schema| {
+            binary(col("a1", schema)?, Operator::Lt, col("b2", schema)?, schema)
+        })?;
+        let join = NestedLoopJoinExec::try_new(left, right, filter, &JoinType::Inner)?;
+
+        let err = common::collect(join.execute(0, task_ctx)?)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Resources exhausted"),
+            "unexpected error: {}",
+            err
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pairs_are_filtered_in_chunks() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &vec![0, 1, 2]),
+            ("b1", &vec![0; 3]),
+            ("c1", &vec![0; 3]),
+        );
+        // more pairs of rows than are filtered at once
+        let right_values = (0..5000).collect::<Vec<_>>();
+        let right = build_table(
+            ("a2", &right_values),
+            ("b2", &right_values),
+            ("c2", &right_values),
+        );
+        let filter = join_filter(&left, &right, |schema| {
+            binary(col("a1", schema)?, Operator::Lt, col("b2", schema)?, schema)
+        })?;
+        let join = NestedLoopJoinExec::try_new(left, right, filter, &JoinType::Right)?;
+
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+        // b2 = 0 has no match, b2 = 1 and b2 = 2 match fewer than 3 rows
+        let num_rows = 1 + 1 + 2 + 3 * 4997;
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).sum::<usize>(),
+            num_rows
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn filter_must_be_boolean() -> Result<()> {
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![2]), ("c1", &vec![3]));
+        let right = build_table(("a2", &vec![1]), ("b2", &vec![2]), ("c2", &vec![3]));
+        let err = join_filter(&left, &right, |schema| col("a1", schema)).unwrap_err();
+        assert!(err.to_string().contains("must return a boolean"));
+        Ok(())
+    }
+}
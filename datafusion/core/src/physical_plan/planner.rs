@@ -25,17 +25,19 @@ use super::{
 use crate::execution::context::{ExecutionProps, SessionState};
 use crate::logical_expr::utils::generate_sort_key;
 use crate::logical_plan::plan::{
-    source_as_provider, Aggregate, EmptyRelation, Filter, Join, Projection, Sort,
-    SubqueryAlias, TableScan, Window,
+    source_as_provider, Aggregate, EmptyRelation, Filter, Join, Projection,
+    RecursiveQuery, Sort, SubqueryAlias, TableScan, Unnest, Window,
 };
 use crate::logical_plan::{
     unalias, unnormalize_cols, CrossJoin, DFSchema, Expr, LogicalPlan, Operator,
     Partitioning as LogicalPartitioning, PlanType, Repartition, ToStringifiedPlan, Union,
     UserDefinedLogicalNode,
 };
-use crate::logical_plan::{Limit, Values};
+use crate::logical_plan::{JoinType, Limit, Values};
+use crate::physical_optimizer::eliminate_sorts::ordering_satisfies;
 use crate::physical_optimizer::optimizer::PhysicalOptimizerRule;
 use crate::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use crate::physical_plan::cross_join::CrossJoinExec;
 use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::expressions;
@@ -44,11 +46,17 @@ use crate::physical_plan::expressions::{
 };
 use crate::physical_plan::filter::FilterExec;
 use crate::physical_plan::hash_join::HashJoinExec;
+use crate::physical_plan::join_utils::{ColumnIndex, JoinFilter, JoinSide};
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::nested_loop_join::NestedLoopJoinExec;
 use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::recursive_query::RecursiveQueryExec;
 use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sort_merge_join::{AsOfCondition, SortMergeJoinExec};
 use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::physical_plan::udf;
+use crate::physical_plan::unnest::UnnestExec;
 use crate::physical_plan::windows::WindowAggExec;
 use crate::physical_plan::{join_utils, Partitioning};
 use crate::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr, WindowExpr};
@@ -64,10 +72,13 @@ use arrow::datatypes::{Schema, SchemaRef};
 use arrow::{compute::can_cast_types, datatypes::DataType};
 use async_trait::async_trait;
 use datafusion_expr::expr::GroupingSet;
+use datafusion_expr::utils::expr_to_columns;
+use datafusion_expr::WindowFunction;
 use datafusion_physical_expr::expressions::DateIntervalExpr;
 use futures::future::BoxFuture;
 use futures::{FutureExt, StreamExt, TryStreamExt};
 use log::{debug, trace};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 fn create_function_physical_name(
@@ -167,8 +178,20 @@ fn create_physical_name(e: &Expr, is_first_expr: bool) -> Result<String> {
             fun,
             distinct,
             args,
-            ..
-        } => create_function_physical_name(&fun.to_string(), *distinct, args),
+            filter,
+            order_by,
+        } => {
+            let mut name =
+                create_function_physical_name(&fun.to_string(), *distinct, args)?;
+            if !order_by.is_empty() {
+                name += &format!(" ORDER BY {:?}", order_by);
+            }
+            if let Some(filter) = filter {
+                name +=
+                    &format!(" FILTER (WHERE {})", create_physical_name(filter, false)?);
+            }
+            Ok(name)
+        }
         Expr::AggregateUDF { fun, args } => {
             let mut names = Vec::with_capacity(args.len());
             for e in args {
@@ -586,10 +609,22 @@ impl DefaultPhysicalPlanner {
                         && session_state.config.repartition_aggregations
                         && !contains_dict;
 
+                    // an input that is already hash partitioned on some of the
+                    // grouping columns keeps all the rows of each group in one
+                    // partition, as does the partial aggregate
+                    let input_partitioned_on_groups = !groups.is_empty()
+                        && hash_partitioned_positions(
+                            initial_aggr.input().as_ref(),
+                            &groups.iter().map(|(expr, _)| expr.clone()).collect::<Vec<_>>(),
+                        )
+                        .is_some();
+
                     let (initial_aggr, next_partition_mode): (
                         Arc<dyn ExecutionPlan>,
                         AggregateMode,
-                    ) = if can_repartition {
+                    ) = if input_partitioned_on_groups {
+                        (initial_aggr, AggregateMode::FinalPartitioned)
+                    } else if can_repartition {
                         // Divide partial hash aggregates into multiple partitions by hash key
                         let hash_repartition = Arc::new(RepartitionExec::try_new(
                             initial_aggr,
@@ -692,6 +727,23 @@ impl DefaultPhysicalPlanner {
                         .await?;
                     Ok(Arc::new(UnionExec::new(physical_plans)) )
                 }
+                LogicalPlan::RecursiveQuery(RecursiveQuery {
+                    name,
+                    static_term,
+                    recursive_term,
+                    is_distinct,
+                    schema,
+                }) => {
+                    let static_term = self.create_initial_plan(static_term, session_state).await?;
+                    let recursive_term = self.create_initial_plan(recursive_term, session_state).await?;
+                    Ok(Arc::new(RecursiveQueryExec::try_new(
+                        name.clone(),
+                        static_term,
+                        recursive_term,
+                        *is_distinct,
+                        SchemaRef::new(schema.as_ref().to_owned().into()),
+                    )?))
+                }
                 LogicalPlan::Repartition(Repartition {
                     input,
                     partitioning_scheme,
@@ -749,14 +801,40 @@ impl DefaultPhysicalPlanner {
                             )),
                         })
                         .collect::<Result<Vec<_>>>()?;
-                    Ok(Arc::new(SortExec::try_new(sort_expr, physical_input)?) )
+
+                    // an input that is already sorted is left for the
+                    // EliminateSorts optimizer
+                    let can_repartition = session_state.config.target_partitions > 1
+                        && session_state.config.repartition_sorts
+                        && !ordering_satisfies(physical_input.output_ordering(), &sort_expr);
+
+                    if can_repartition {
+                        // Sort ranges of the sort keys in parallel, and output them in order
+                        let range_repartition = Arc::new(RepartitionExec::try_new(
+                            physical_input,
+                            Partitioning::Range(
+                                sort_expr.clone(),
+                                session_state.config.target_partitions,
+                            ),
+                        )?);
+                        let sort = Arc::new(SortExec::new_with_partitioning(
+                            sort_expr.clone(),
+                            range_repartition,
+                            true,
+                        ));
+                        Ok(Arc::new(SortPreservingMergeExec::new(sort_expr, sort)))
+                    } else {
+                        Ok(Arc::new(SortExec::try_new(sort_expr, physical_input)?))
+                    }
                 }
                 LogicalPlan::Join(Join {
                     left,
                     right,
                     on: keys,
                     join_type,
+                    filter,
                     null_equals_null,
+                    null_aware,
                     ..
                 }) => {
                     let left_df_schema = left.schema();
@@ -773,10 +851,202 @@ impl DefaultPhysicalPlanner {
                         })
                         .collect::<Result<join_utils::JoinOn>>()?;
 
-                    if session_state.config.target_partitions > 1
+                    // Conditions beyond the equality of the join keys are evaluated
+                    // against the columns of either side they refer to
+                    let join_filter = match filter {
+                        Some(filter) if *join_type != JoinType::AsOf => {
+                            let mut columns = HashSet::new();
+                            expr_to_columns(filter, &mut columns)?;
+                            let left_schema = physical_left.schema();
+                            let right_schema = physical_right.schema();
+                            let referenced = |side: JoinSide, df_schema: &DFSchema, schema: &Schema| {
+                                df_schema
+                                    .fields()
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|(_, field)| {
+                                        columns.contains(&field.qualified_column())
+                                            || columns.contains(&field.unqualified_column())
+                                    })
+                                    .map(|(index, field)| {
+                                        (
+                                            ColumnIndex {
+                                                index,
+                                                side: side.clone(),
+                                            },
+                                            field.clone(),
+                                            schema.field(index).clone(),
+                                        )
+                                    })
+                                    .collect::<Vec<_>>()
+                            };
+                            let (column_indices, (df_fields, fields)): (Vec<_>, (Vec<_>, Vec<_>)) =
+                                referenced(JoinSide::Left, left_df_schema, &left_schema)
+                                    .into_iter()
+                                    .chain(referenced(
+                                        JoinSide::Right,
+                                        right_df_schema,
+                                        &right_schema,
+                                    ))
+                                    .map(|(index, df_field, field)| (index, (df_field, field)))
+                                    .unzip();
+                            let filter_df_schema =
+                                DFSchema::new_with_metadata(df_fields, HashMap::new())?;
+                            let filter_schema = Schema::new(fields);
+                            let expression = self.create_physical_expr(
+                                filter,
+                                &filter_df_schema,
+                                &filter_schema,
+                                session_state,
+                            )?;
+                            Some(JoinFilter::try_new(expression, column_indices, filter_schema)?)
+                        }
+                        _ => None,
+                    };
+
+                    if *join_type == JoinType::AsOf {
+                        let (left_column, op, right_column) = match filter {
+                            Some(Expr::BinaryExpr { left, op, right }) => {
+                                match (left.as_ref(), right.as_ref()) {
+                                    (Expr::Column(l), Expr::Column(r)) => (l, op, r),
+                                    _ => {
+                                        return Err(DataFusionError::Internal(format!(
+                                            "Unsupported ASOF match condition: {:?}",
+                                            filter
+                                        )))
+                                    }
+                                }
+                            }
+                            _ => {
+                                return Err(DataFusionError::Internal(format!(
+                                    "Unsupported ASOF match condition: {:?}",
+                                    filter
+                                )))
+                            }
+                        };
+                        let condition = AsOfCondition {
+                            left: Column::new(
+                                &left_column.name,
+                                left_df_schema.index_of_column(left_column)?,
+                            ),
+                            op: *op,
+                            right: Column::new(
+                                &right_column.name,
+                                right_df_schema.index_of_column(right_column)?,
+                            ),
+                        };
+
+                        // Rows with equal keys must meet in the same partition, and
+                        // are then merged in the order of the ordered column
+                        let partitions = (!join_on.is_empty()
+                            && session_state.config.target_partitions > 1
+                            && session_state.config.repartition_joins)
+                            .then(|| session_state.config.target_partitions);
+                        let sort_options = SortOptions {
+                            descending: false,
+                            nulls_first: false,
+                        };
+                        let sort = |plan: Arc<dyn ExecutionPlan>,
+                                    keys: Vec<Column>,
+                                    ordered: Column|
+                         -> Result<Arc<dyn ExecutionPlan>> {
+                            let plan: Arc<dyn ExecutionPlan> = match partitions {
+                                Some(n) => {
+                                    let exprs = keys
+                                        .iter()
+                                        .map(|c| Arc::new(c.clone()) as Arc<dyn PhysicalExpr>)
+                                        .collect();
+                                    Arc::new(RepartitionExec::try_new(
+                                        plan,
+                                        Partitioning::Hash(exprs, n),
+                                    )?)
+                                }
+                                None if plan.output_partitioning().partition_count() > 1 => {
+                                    Arc::new(CoalescePartitionsExec::new(plan))
+                                }
+                                None => plan,
+                            };
+                            let sort_expr = keys
+                                .into_iter()
+                                .chain(std::iter::once(ordered))
+                                .map(|c| PhysicalSortExpr {
+                                    expr: Arc::new(c),
+                                    options: sort_options,
+                                })
+                                .collect();
+                            Ok(Arc::new(SortExec::new_with_partitioning(
+                                sort_expr, plan, true,
+                            )))
+                        };
+                        let physical_left = sort(
+                            physical_left,
+                            join_on.iter().map(|(l, _)| l.clone()).collect(),
+                            condition.left.clone(),
+                        )?;
+                        let physical_right = sort(
+                            physical_right,
+                            join_on.iter().map(|(_, r)| r.clone()).collect(),
+                            condition.right.clone(),
+                        )?;
+
+                        let join = SortMergeJoinExec::try_new(
+                            physical_left,
+                            physical_right,
+                            join_on.clone(),
+                            JoinType::AsOf,
+                            vec![sort_options; join_on.len()],
+                            *null_equals_null,
+                        )?
+                        .with_asof_condition(condition)?;
+                        Ok(Arc::new(join))
+                    } else if join_on.is_empty() && filter.is_some() {
+                        // Without join keys, the filter is evaluated for every
+                        // pair of rows
+                        let join_filter = join_filter.unwrap();
+
+                        // The unmatched rows of the left side are only known once
+                        // a single stream has seen all of the right side
+                        let tracks_left_side = matches!(
+                            join_type,
+                            JoinType::Left | JoinType::Full | JoinType::Semi | JoinType::Anti
+                        );
+                        let physical_right = if tracks_left_side
+                            && physical_right.output_partitioning().partition_count() > 1
+                        {
+                            Arc::new(CoalescePartitionsExec::new(physical_right))
+                        } else {
+                            physical_right
+                        };
+                        Ok(Arc::new(NestedLoopJoinExec::try_new(
+                            physical_left,
+                            physical_right,
+                            join_filter,
+                            join_type,
+                        )?))
+                    } else if *null_aware {
+                        // Whether the right side has null keys is only known once
+                        // all of it has been probed by a single stream
+                        let physical_right =
+                            if physical_right.output_partitioning().partition_count() == 1 {
+                                physical_right
+                            } else {
+                                Arc::new(CoalescePartitionsExec::new(physical_right))
+                            };
+                        let join = HashJoinExec::try_new(
+                            physical_left,
+                            physical_right,
+                            join_on,
+                            join_type,
+                            PartitionMode::CollectLeft,
+                            null_equals_null,
+                        )?
+                        .with_filter(join_filter)?
+                        .with_null_aware(true)?;
+                        Ok(Arc::new(join))
+                    } else if session_state.config.target_partitions > 1
                         && session_state.config.repartition_joins
                     {
-                        let (left_expr, right_expr) = join_on
+                        let (left_expr, right_expr): (Vec<_>, Vec<_>) = join_on
                             .iter()
                             .map(|(l, r)| {
                                 (
@@ -786,36 +1056,76 @@ impl DefaultPhysicalPlanner {
                             })
                             .unzip();
 
-                        // Use hash partition by default to parallelize hash joins
-                        Ok(Arc::new(HashJoinExec::try_new(
-                            Arc::new(RepartitionExec::try_new(
+                        // the rows of both sides hash alike only if the keys
+                        // they are partitioned on have the same types
+                        let left_schema = physical_left.schema();
+                        let right_schema = physical_right.schema();
+                        let same_key_types = |positions: &[usize]| {
+                            positions.iter().all(|i| {
+                                left_expr[*i].data_type(&left_schema).ok()
+                                    == right_expr[*i].data_type(&right_schema).ok()
+                            })
+                        };
+                        let hash_repartition =
+                            |plan, exprs: Vec<Arc<dyn PhysicalExpr>>, n| {
+                                RepartitionExec::try_new(plan, Partitioning::Hash(exprs, n))
+                                    .map(|exec| Arc::new(exec) as Arc<dyn ExecutionPlan>)
+                            };
+                        let pick = |exprs: &[Arc<dyn PhysicalExpr>], positions: &[usize]| {
+                            positions.iter().map(|i| exprs[*i].clone()).collect()
+                        };
+
+                        // Use hash partition by default to parallelize hash joins,
+                        // unless a side is already hash partitioned on join keys
+                        let (physical_left, physical_right) = match (
+                            hash_partitioned_positions(physical_left.as_ref(), &left_expr),
+                            hash_partitioned_positions(physical_right.as_ref(), &right_expr),
+                        ) {
+                            (Some(left_hash), Some(right_hash))
+                                if left_hash == right_hash && same_key_types(&left_hash.0) =>
+                            {
+                                (physical_left, physical_right)
+                            }
+                            (Some((positions, n)), _) if same_key_types(&positions) => {
+                                let right_expr = pick(&right_expr, &positions);
+                                (physical_left, hash_repartition(physical_right, right_expr, n)?)
+                            }
+                            (_, Some((positions, n))) if same_key_types(&positions) => {
+                                let left_expr = pick(&left_expr, &positions);
+                                (hash_repartition(physical_left, left_expr, n)?, physical_right)
+                            }
+                            _ => {
+                                let n = session_state.config.target_partitions;
+                                (
+                                    hash_repartition(physical_left, left_expr, n)?,
+                                    hash_repartition(physical_right, right_expr, n)?,
+                                )
+                            }
+                        };
+
+                        Ok(Arc::new(
+                            HashJoinExec::try_new(
                                 physical_left,
-                                Partitioning::Hash(
-                                    left_expr,
-                                    session_state.config.target_partitions,
-                                ),
-                            )?),
-                            Arc::new(RepartitionExec::try_new(
                                 physical_right,
-                                Partitioning::Hash(
-                                    right_expr,
-                                    session_state.config.target_partitions,
-                                ),
-                            )?),
-                            join_on,
-                            join_type,
-                            PartitionMode::Partitioned,
-                            null_equals_null,
-                        )?))
+                                join_on,
+                                join_type,
+                                PartitionMode::Partitioned,
+                                null_equals_null,
+                            )?
+                            .with_filter(join_filter)?,
+                        ))
                     } else {
-                        Ok(Arc::new(HashJoinExec::try_new(
-                            physical_left,
-                            physical_right,
-                            join_on,
-                            join_type,
-                            PartitionMode::CollectLeft,
-                            null_equals_null,
-                        )?))
+                        Ok(Arc::new(
+                            HashJoinExec::try_new(
+                                physical_left,
+                                physical_right,
+                                join_on,
+                                join_type,
+                                PartitionMode::CollectLeft,
+                                null_equals_null,
+                            )?
+                            .with_filter(join_filter)?,
+                        ))
                     }
                 }
                 LogicalPlan::CrossJoin(CrossJoin { left, right, .. }) => {
@@ -854,6 +1164,24 @@ impl DefaultPhysicalPlanner {
 
                     Ok(Arc::new(GlobalLimitExec::new(input, limit)))
                 }
+                LogicalPlan::Unnest(Unnest {
+                    input,
+                    column,
+                    options,
+                    schema,
+                }) => {
+                    let input_exec = self.create_initial_plan(input, session_state).await?;
+                    let column_exec = expressions::Column::new(
+                        &column.name,
+                        input.schema().index_of_column(column)?,
+                    );
+                    Ok(Arc::new(UnnestExec::new(
+                        input_exec,
+                        column_exec,
+                        options.clone(),
+                        SchemaRef::new(schema.as_ref().to_owned().into()),
+                    )))
+                }
                 LogicalPlan::Offset(_) => {
                     Err(DataFusionError::Internal(
                         "Unsupported logical plan: OFFSET".to_string(),
@@ -1310,9 +1638,11 @@ pub fn create_window_expr_with_name(
                     )),
                 })
                 .collect::<Result<Vec<_>>>()?;
-            if window_frame.is_some() {
+            if window_frame.is_some()
+                && matches!(fun, WindowFunction::BuiltInWindowFunction(_))
+            {
                 return Err(DataFusionError::NotImplemented(
-                    "window expression with window frame definition is not yet supported"
+                    "built-in window function with window frame definition is not yet supported"
                         .to_owned(),
                 ));
             }
@@ -1322,7 +1652,7 @@ pub fn create_window_expr_with_name(
                 &args,
                 &partition_by,
                 &order_by,
-                *window_frame,
+                window_frame.clone(),
                 physical_input_schema,
             )
         }
@@ -1367,7 +1697,8 @@ pub fn create_aggregate_expr_with_name(
             fun,
             distinct,
             args,
-            ..
+            filter,
+            order_by,
         } => {
             let args = args
                 .iter()
@@ -1380,10 +1711,45 @@ pub fn create_aggregate_expr_with_name(
                     )
                 })
                 .collect::<Result<Vec<_>>>()?;
-            aggregates::create_aggregate_expr(
+            let filter = filter
+                .as_ref()
+                .map(|e| {
+                    create_physical_expr(
+                        e,
+                        logical_input_schema,
+                        physical_input_schema,
+                        execution_props,
+                    )
+                })
+                .transpose()?;
+            let order_by = order_by
+                .iter()
+                .map(|e| match e {
+                    Expr::Sort {
+                        expr,
+                        asc,
+                        nulls_first,
+                    } => create_physical_sort_expr(
+                        expr,
+                        logical_input_schema,
+                        physical_input_schema,
+                        SortOptions {
+                            descending: !*asc,
+                            nulls_first: *nulls_first,
+                        },
+                        execution_props,
+                    ),
+                    _ => Err(DataFusionError::Plan(
+                        "Sort only accepts sort expressions".to_string(),
+                    )),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            aggregates::create_aggregate_expr_with_clauses(
                 fun,
                 *distinct,
                 &args,
+                filter,
+                &order_by,
                 physical_input_schema,
                 name,
             )
@@ -1522,6 +1888,32 @@ impl DefaultPhysicalPlanner {
     }
 }
 
+/// Returns the positions in `keys` of the columns that `plan` is hash
+/// partitioned on, in the order of the partitioning, and the number of
+/// partitions, if all of them are part of `keys`
+fn hash_partitioned_positions(
+    plan: &dyn ExecutionPlan,
+    keys: &[Arc<dyn PhysicalExpr>],
+) -> Option<(Vec<usize>, usize)> {
+    match plan.output_partitioning() {
+        Partitioning::Hash(hash_exprs, n) if !hash_exprs.is_empty() => {
+            let positions = hash_exprs
+                .iter()
+                .map(|hash_expr| {
+                    let column = hash_expr.as_any().downcast_ref::<Column>()?;
+                    keys.iter().position(|key| {
+                        key.as_any()
+                            .downcast_ref::<Column>()
+                            .map_or(false, |key| key.index() == column.index())
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((positions, n))
+        }
+        _ => None,
+    }
+}
+
 fn tuple_err<T, R>(value: (Result<T>, Result<R>)) -> Result<(T, R)> {
     match value {
         (Ok(e), Ok(e1)) => Ok((e, e1)),
@@ -51,6 +51,10 @@ pub struct ProjectionExec {
     schema: SchemaRef,
     /// The input plan
     input: Arc<dyn ExecutionPlan>,
+    /// The ordering of the input on the columns of the output
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
+    /// The partitioning of the input on the columns of the output
+    output_partitioning: Partitioning,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
 }
@@ -82,10 +86,66 @@ impl ProjectionExec {
             input_schema.metadata().clone(),
         ));
 
+        // the input ordering holds for the prefix of its columns that are
+        // part of the output
+        let output_ordering = input.output_ordering().and_then(|ordering| {
+            let ordering = ordering
+                .iter()
+                .map_while(|sort_expr| {
+                    let column = sort_expr.expr.as_any().downcast_ref::<Column>()?;
+                    expr.iter().enumerate().find_map(|(index, (e, name))| {
+                        let e = e.as_any().downcast_ref::<Column>()?;
+                        (e.index() == column.index()).then(|| PhysicalSortExpr {
+                            expr: Arc::new(Column::new(name, index)),
+                            options: sort_expr.options,
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+            (!ordering.is_empty()).then(|| ordering)
+        });
+
+        // the input hash or range partitioning holds if all its columns are
+        // part of the output
+        let output_partitioning = match input.output_partitioning() {
+            Partitioning::Hash(hash_exprs, n) => hash_exprs
+                .iter()
+                .map(|hash_expr| {
+                    let column = hash_expr.as_any().downcast_ref::<Column>()?;
+                    expr.iter().enumerate().find_map(|(index, (e, name))| {
+                        let e = e.as_any().downcast_ref::<Column>()?;
+                        (e.index() == column.index()).then(|| {
+                            Arc::new(Column::new(name, index)) as Arc<dyn PhysicalExpr>
+                        })
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|hash_exprs| Partitioning::Hash(hash_exprs, n))
+                .unwrap_or(Partitioning::UnknownPartitioning(n)),
+            Partitioning::Range(sort_exprs, n) => sort_exprs
+                .iter()
+                .map(|sort_expr| {
+                    let column = sort_expr.expr.as_any().downcast_ref::<Column>()?;
+                    expr.iter().enumerate().find_map(|(index, (e, name))| {
+                        let e = e.as_any().downcast_ref::<Column>()?;
+                        (e.index() == column.index()).then(|| PhysicalSortExpr {
+                            expr: Arc::new(Column::new(name, index)),
+                            options: sort_expr.options,
+                        })
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|sort_exprs| Partitioning::Range(sort_exprs, n))
+                .unwrap_or(Partitioning::UnknownPartitioning(n)),
+            partitioning => partitioning,
+        };
+
         Ok(Self {
             expr,
             schema,
             input: input.clone(),
+            output_ordering,
+            output_partitioning,
             metrics: ExecutionPlanMetricsSet::new(),
         })
     }
@@ -118,11 +178,11 @@ impl ExecutionPlan for ProjectionExec {
 
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        self.input.output_partitioning()
+        self.output_partitioning.clone()
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        self.input.output_ordering()
+        self.output_ordering.as_deref()
     }
 
     fn maintains_input_order(&self) -> bool {
@@ -0,0 +1,486 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! RecursiveQueryExec evaluates a recursive common table expression, by
+//! repeatedly evaluating its recursive term over the rows produced by the
+//! previous iteration until no new rows are produced.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::array::BooleanArray;
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use futures::stream::{self, StreamExt};
+use log::debug;
+
+use super::expressions::PhysicalSortExpr;
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::stream::RecordBatchStreamAdapter;
+use super::work_table::{WorkTable, WorkTableExec};
+use super::{
+    with_new_children_if_necessary, DisplayFormatType, Distribution, ExecutionPlan,
+    Partitioning, SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+use crate::scalar::ScalarValue;
+
+/// Evaluates the recursive query `name`: produces the rows of its static
+/// term, then evaluates its recursive term, in which each [`WorkTableExec`]
+/// reads the rows produced by the previous iteration, until an iteration
+/// produces no rows.
+///
+/// If `is_distinct` is true (`UNION`), rows already produced, by the static
+/// term or any iteration, are discarded before being fed to the next
+/// iteration. Otherwise (`UNION ALL`) all rows are kept.
+#[derive(Debug)]
+pub struct RecursiveQueryExec {
+    /// The name of the query
+    name: String,
+    /// The term producing the initial rows
+    static_term: Arc<dyn ExecutionPlan>,
+    /// The term evaluated over the rows of the previous iteration
+    recursive_term: Arc<dyn ExecutionPlan>,
+    /// Whether duplicate rows are removed
+    is_distinct: bool,
+    /// The output schema
+    schema: SchemaRef,
+    /// The rows of the previous iteration, read by the recursive term
+    work_table: Arc<WorkTable>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl RecursiveQueryExec {
+    /// Create a new RecursiveQueryExec, assigning its work table to the
+    /// [`WorkTableExec`]s of `recursive_term`
+    pub fn try_new(
+        name: String,
+        static_term: Arc<dyn ExecutionPlan>,
+        recursive_term: Arc<dyn ExecutionPlan>,
+        is_distinct: bool,
+        schema: SchemaRef,
+    ) -> Result<Self> {
+        Self::try_new_with_work_table(
+            name,
+            static_term,
+            recursive_term,
+            is_distinct,
+            schema,
+            Arc::new(WorkTable::new()),
+        )
+    }
+
+    fn try_new_with_work_table(
+        name: String,
+        static_term: Arc<dyn ExecutionPlan>,
+        recursive_term: Arc<dyn ExecutionPlan>,
+        is_distinct: bool,
+        schema: SchemaRef,
+        work_table: Arc<WorkTable>,
+    ) -> Result<Self> {
+        let static_columns = static_term.schema().fields().len();
+        let recursive_columns = recursive_term.schema().fields().len();
+        if static_columns != schema.fields().len()
+            || recursive_columns != schema.fields().len()
+        {
+            return Err(DataFusionError::Plan(format!(
+                "Recursive query {} has {} columns but its static term has {} and its recursive term {}",
+                name,
+                schema.fields().len(),
+                static_columns,
+                recursive_columns
+            )));
+        }
+        let recursive_term = assign_work_table(recursive_term, &name, &work_table)?;
+        Ok(Self {
+            name,
+            static_term,
+            recursive_term,
+            is_distinct,
+            schema,
+            work_table,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// The name of the query
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The term producing the initial rows
+    pub fn static_term(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.static_term
+    }
+
+    /// The term evaluated over the rows of the previous iteration
+    pub fn recursive_term(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.recursive_term
+    }
+
+    /// Whether duplicate rows are removed
+    pub fn is_distinct(&self) -> bool {
+        self.is_distinct
+    }
+}
+
+impl ExecutionPlan for RecursiveQueryExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.static_term.clone(), self.recursive_term.clone()]
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(RecursiveQueryExec::try_new_with_work_table(
+            self.name.clone(),
+            children[0].clone(),
+            children[1].clone(),
+            self.is_distinct,
+            self.schema.clone(),
+            self.work_table.clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!("Start RecursiveQueryExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "RecursiveQueryExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let state = RecursiveQueryState {
+            name: self.name.clone(),
+            input: self.static_term.execute(0, context.clone())?,
+            recursive_term: self.recursive_term.clone(),
+            work_table: self.work_table.clone(),
+            schema: self.schema.clone(),
+            seen: self.is_distinct.then(HashSet::new),
+            buffer: vec![],
+            iteration: 0,
+            max_iterations: context.session_config().max_recursion_iterations,
+            context,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        };
+        let stream = stream::try_unfold(state, |mut state| async move {
+            match state.next().await {
+                Ok(Some(batch)) => Ok(Some((batch, state))),
+                Ok(None) => {
+                    state.baseline_metrics.done();
+                    Ok(None)
+                }
+                Err(e) => Err(ArrowError::ExternalError(Box::new(e))),
+            }
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "RecursiveQueryExec: name={}, is_distinct={}",
+                    self.name, self.is_distinct
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// The state of the evaluation of a recursive query
+struct RecursiveQueryState {
+    name: String,
+    /// The term currently evaluated
+    input: SendableRecordBatchStream,
+    recursive_term: Arc<dyn ExecutionPlan>,
+    work_table: Arc<WorkTable>,
+    schema: SchemaRef,
+    /// The rows produced so far, if duplicates are removed
+    seen: Option<HashSet<Vec<ScalarValue>>>,
+    /// The rows produced by the current iteration
+    buffer: Vec<RecordBatch>,
+    /// The number of evaluations of the recursive term
+    iteration: usize,
+    max_iterations: usize,
+    context: Arc<TaskContext>,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl RecursiveQueryState {
+    /// Returns the next batch of new rows, evaluating the recursive term
+    /// again whenever the current iteration is exhausted
+    async fn next(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            match self.input.next().await {
+                Some(batch) => {
+                    let batch = self.new_rows(batch?)?;
+                    if batch.num_rows() > 0 {
+                        self.baseline_metrics.record_output(batch.num_rows());
+                        self.buffer.push(batch.clone());
+                        return Ok(Some(batch));
+                    }
+                }
+                None if self.buffer.is_empty() => return Ok(None),
+                None => {
+                    self.iteration += 1;
+                    if self.iteration > self.max_iterations {
+                        return Err(DataFusionError::Execution(format!(
+                            "Recursive query {} exceeded the maximum of {} iterations",
+                            self.name, self.max_iterations
+                        )));
+                    }
+                    self.work_table.update(std::mem::take(&mut self.buffer));
+                    // operators such as joins only compute their build side
+                    // once, so the recursive term is rebuilt for each iteration
+                    let recursive_term = reset_plan(self.recursive_term.clone())?;
+                    self.input = recursive_term.execute(0, self.context.clone())?;
+                }
+            }
+        }
+    }
+
+    /// Returns the rows of `batch` with the output schema, discarding those
+    /// already produced if duplicates are removed
+    fn new_rows(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let _timer = self.baseline_metrics.elapsed_compute().timer();
+        let batch = RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec())?;
+        let seen = match &mut self.seen {
+            Some(seen) => seen,
+            None => return Ok(batch),
+        };
+        let keep = (0..batch.num_rows())
+            .map(|row| {
+                let key = batch
+                    .columns()
+                    .iter()
+                    .map(|array| ScalarValue::try_from_array(array, row))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Some(seen.insert(key)))
+            })
+            .collect::<Result<BooleanArray>>()?;
+        Ok(filter_record_batch(&batch, &keep)?)
+    }
+}
+
+/// Assigns `work_table` to the unassigned [`WorkTableExec`]s of `name` in `plan`
+fn assign_work_table(
+    plan: Arc<dyn ExecutionPlan>,
+    name: &str,
+    work_table: &Arc<WorkTable>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if let Some(scan) = plan.as_any().downcast_ref::<WorkTableExec>() {
+        return Ok(if scan.name() == name && !scan.has_work_table() {
+            Arc::new(scan.with_work_table(work_table.clone()))
+        } else {
+            plan
+        });
+    }
+    let children = plan.children();
+    if children.is_empty() {
+        return Ok(plan);
+    }
+    let children = children
+        .into_iter()
+        .map(|child| assign_work_table(child, name, work_table))
+        .collect::<Result<Vec<_>>>()?;
+    with_new_children_if_necessary(plan, children)
+}
+
+/// Rebuilds every operator of `plan`, discarding any state kept from a
+/// previous execution
+fn reset_plan(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    let children = plan.children();
+    if children.is_empty() {
+        return Ok(plan);
+    }
+    let children = children
+        .into_iter()
+        .map(reset_plan)
+        .collect::<Result<Vec<_>>>()?;
+    plan.with_new_children(children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::logical_plan::Operator;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::{binary, col, lit};
+    use crate::physical_plan::filter::FilterExec;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::projection::ProjectionExec;
+    use crate::prelude::{SessionConfig, SessionContext};
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]))
+    }
+
+    fn values(values: Vec<i64>) -> Result<Arc<dyn ExecutionPlan>> {
+        let batch =
+            RecordBatch::try_new(schema(), vec![Arc::new(Int64Array::from(values))])?;
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![batch]],
+            schema(),
+            None,
+        )?))
+    }
+
+    /// `SELECT n + step FROM t WHERE n < limit`
+    fn increment(step: i64, limit: i64) -> Result<Arc<dyn ExecutionPlan>> {
+        let scan = Arc::new(WorkTableExec::new("t".to_string(), schema(), None));
+        let predicate = binary(
+            col("n", &schema())?,
+            Operator::Lt,
+            lit(ScalarValue::Int64(Some(limit))),
+            &schema(),
+        )?;
+        let filter = Arc::new(FilterExec::try_new(predicate, scan)?);
+        let expr = binary(
+            col("n", &schema())?,
+            Operator::Plus,
+            lit(ScalarValue::Int64(Some(step))),
+            &schema(),
+        )?;
+        Ok(Arc::new(ProjectionExec::try_new(
+            vec![(expr, "n".to_string())],
+            filter,
+        )?))
+    }
+
+    #[tokio::test]
+    async fn recursive_query() -> Result<()> {
+        let query = Arc::new(RecursiveQueryExec::try_new(
+            "t".to_string(),
+            values(vec![1, 2])?,
+            increment(1, 4)?,
+            false,
+            schema(),
+        )?);
+        let task_ctx = SessionContext::new().task_ctx();
+        let batches = collect(query, task_ctx).await?;
+        let expected = vec![
+            "+---+", "| n |", "+---+", "| 1 |", "| 2 |", "| 2 |", "| 3 |", "| 3 |",
+            "| 4 |", "| 4 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn recursive_query_distinct() -> Result<()> {
+        let query = Arc::new(RecursiveQueryExec::try_new(
+            "t".to_string(),
+            values(vec![1, 2, 1])?,
+            increment(1, 4)?,
+            true,
+            schema(),
+        )?);
+        let task_ctx = SessionContext::new().task_ctx();
+        let batches = collect(query, task_ctx).await?;
+        let expected = vec![
+            "+---+", "| n |", "+---+", "| 1 |", "| 2 |", "| 3 |", "| 4 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn recursive_query_max_iterations() -> Result<()> {
+        let query = Arc::new(RecursiveQueryExec::try_new(
+            "t".to_string(),
+            values(vec![1])?,
+            increment(1, 100)?,
+            false,
+            schema(),
+        )?);
+        let config = SessionConfig::new().with_max_recursion_iterations(5);
+        let task_ctx = SessionContext::with_config(config).task_ctx();
+        let err = collect(query, task_ctx).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Recursive query t exceeded the maximum of 5 iterations"),
+            "{}",
+            err
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn work_table_outside_recursive_query() -> Result<()> {
+        let task_ctx = SessionContext::new().task_ctx();
+        let err = collect(increment(1, 4)?, task_ctx).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Work table t can only be read from the recursive term of its query"
+        );
+        Ok(())
+    }
+}
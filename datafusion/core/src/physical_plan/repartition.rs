@@ -18,30 +18,33 @@
 //! The repartition operator maps N input partitions to M output partitions based on a
 //! partitioning scheme.
 
+use std::cmp::Ordering;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::{any::Any, vec};
 
 use crate::error::{DataFusionError, Result};
-use crate::physical_plan::hash_utils::create_hashes;
+use crate::physical_plan::bucket_hash::{bucket_of, create_bucket_hashes};
 use crate::physical_plan::{DisplayFormatType, ExecutionPlan, Partitioning, Statistics};
-use arrow::array::{ArrayRef, UInt64Builder};
+use arrow::array::{build_compare, ArrayRef, UInt32Array, UInt64Array, UInt64Builder};
+use arrow::compute::{lexsort_to_indices, SortColumn};
 use arrow::datatypes::SchemaRef;
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
 use log::debug;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use super::common::{AbortOnDropMany, AbortOnDropSingle};
+use super::common::{batch_byte_size, AbortOnDropMany, AbortOnDropSingle};
 use super::expressions::PhysicalSortExpr;
 use super::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
 use super::{RecordBatchStream, SendableRecordBatchStream};
 
 use crate::execution::context::TaskContext;
+use crate::execution::MemoryReservation;
 use datafusion_physical_expr::PhysicalExpr;
 use futures::stream::Stream;
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
 use hashbrown::HashMap;
 use parking_lot::Mutex;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
@@ -69,15 +72,18 @@ pub struct BatchPartitioner {
 
 enum BatchPartitionerState {
     Hash {
-        random_state: ahash::RandomState,
         exprs: Vec<Arc<dyn PhysicalExpr>>,
         num_partitions: usize,
-        hash_buffer: Vec<u64>,
+        hash_buffer: Vec<u32>,
     },
     RoundRobin {
         num_partitions: usize,
         next_idx: usize,
     },
+    Range {
+        sort_exprs: Vec<PhysicalSortExpr>,
+        bounds: Vec<ArrayRef>,
+    },
 }
 
 impl BatchPartitioner {
@@ -95,10 +101,13 @@ impl BatchPartitioner {
             Partitioning::Hash(exprs, num_partitions) => BatchPartitionerState::Hash {
                 exprs,
                 num_partitions,
-                // Use fixed random hash
-                random_state: ahash::RandomState::with_seeds(0, 0, 0, 0),
                 hash_buffer: vec![],
             },
+            Partitioning::Range(_, _) => {
+                return Err(DataFusionError::Internal(
+                    "Range partitioning needs the bounds of the ranges, see BatchPartitioner::try_new_range".to_owned(),
+                ))
+            }
             other => {
                 return Err(DataFusionError::NotImplemented(format!(
                     "Unsupported repartitioning scheme {:?}",
@@ -110,6 +119,29 @@ impl BatchPartitioner {
         Ok(Self { state, timer })
     }
 
+    /// Create a new [`BatchPartitioner`] that allocates rows to
+    /// `bounds.len() + 1` partitions by ranges of their sort key, as
+    /// computed by [`range_bounds`]
+    ///
+    /// The time spent repartitioning will be recorded to `timer`
+    pub fn try_new_range(
+        sort_exprs: Vec<PhysicalSortExpr>,
+        bounds: Vec<ArrayRef>,
+        timer: metrics::Time,
+    ) -> Result<Self> {
+        if !bounds.is_empty() && bounds.len() != sort_exprs.len() {
+            return Err(DataFusionError::Internal(format!(
+                "Expected bounds for {} sort expressions, got {}",
+                sort_exprs.len(),
+                bounds.len()
+            )));
+        }
+        Ok(Self {
+            state: BatchPartitionerState::Range { sort_exprs, bounds },
+            timer,
+        })
+    }
+
     /// Partition the provided [`RecordBatch`] into one or more partitioned [`RecordBatch`]
     /// based on the [`Partitioning`] specified on construction
     ///
@@ -133,7 +165,6 @@ impl BatchPartitioner {
                 f(idx, batch)?;
             }
             BatchPartitionerState::Hash {
-                random_state,
                 exprs,
                 num_partitions: partitions,
                 hash_buffer,
@@ -148,14 +179,15 @@ impl BatchPartitioner {
                 hash_buffer.clear();
                 hash_buffer.resize(batch.num_rows(), 0);
 
-                create_hashes(&arrays, random_state, hash_buffer)?;
+                // the partitions are the buckets of bucketed tables
+                create_bucket_hashes(&arrays, hash_buffer)?;
 
                 let mut indices: Vec<_> = (0..*partitions)
                     .map(|_| UInt64Builder::new(batch.num_rows()))
                     .collect();
 
                 for (index, hash) in hash_buffer.iter().enumerate() {
-                    indices[(*hash % *partitions as u64) as usize]
+                    indices[bucket_of(*hash, *partitions)]
                         .append_value(index as u64)
                         .unwrap();
                 }
@@ -178,6 +210,86 @@ impl BatchPartitioner {
 
                     let batch = RecordBatch::try_new(batch.schema(), columns).unwrap();
 
+                    timer.stop();
+                    f(partition, batch)?;
+                    timer.restart();
+                }
+            }
+            BatchPartitionerState::Range { sort_exprs, bounds } => {
+                let mut timer = self.timer.timer();
+
+                let num_bounds = bounds.first().map_or(0, |bound| bound.len());
+                if num_bounds == 0 {
+                    timer.stop();
+                    return f(0, batch);
+                }
+
+                let keys = sort_exprs
+                    .iter()
+                    .map(|e| Ok(e.expr.evaluate(&batch)?.into_array(batch.num_rows())))
+                    .collect::<Result<Vec<_>>>()?;
+                let comparators = bounds
+                    .iter()
+                    .zip(keys.iter())
+                    .map(|(bound, key)| build_compare(bound.as_ref(), key.as_ref()))
+                    .collect::<ArrowResult<Vec<_>>>()?;
+                let compare_bound = |bound: usize, row: usize| {
+                    for (i, sort_expr) in sort_exprs.iter().enumerate() {
+                        let options = sort_expr.options;
+                        let ordering =
+                            match (bounds[i].is_valid(bound), keys[i].is_valid(row)) {
+                                (false, false) => Ordering::Equal,
+                                (false, true) if options.nulls_first => Ordering::Less,
+                                (false, true) => Ordering::Greater,
+                                (true, false) if options.nulls_first => Ordering::Greater,
+                                (true, false) => Ordering::Less,
+                                (true, true) if options.descending => {
+                                    comparators[i](bound, row).reverse()
+                                }
+                                (true, true) => comparators[i](bound, row),
+                            };
+                        if ordering != Ordering::Equal {
+                            return ordering;
+                        }
+                    }
+                    Ordering::Equal
+                };
+
+                let mut indices: Vec<_> = (0..=num_bounds)
+                    .map(|_| UInt64Builder::new(batch.num_rows()))
+                    .collect();
+                for row in 0..batch.num_rows() {
+                    // the partition of a row is the number of bounds it sorts
+                    // after, as rows equal to a bound belong to its range
+                    let (mut low, mut high) = (0, num_bounds);
+                    while low < high {
+                        let mid = (low + high) / 2;
+                        if compare_bound(mid, row) == Ordering::Less {
+                            low = mid + 1;
+                        } else {
+                            high = mid;
+                        }
+                    }
+                    indices[low].append_value(row as u64)?;
+                }
+
+                for (partition, mut indices) in indices.into_iter().enumerate() {
+                    let indices = indices.finish();
+                    if indices.is_empty() {
+                        continue;
+                    }
+
+                    let columns = batch
+                        .columns()
+                        .iter()
+                        .map(|c| {
+                            arrow::compute::take(c.as_ref(), &indices, None)
+                                .map_err(DataFusionError::ArrowError)
+                        })
+                        .collect::<Result<Vec<ArrayRef>>>()?;
+
+                    let batch = RecordBatch::try_new(batch.schema(), columns)?;
+
                     timer.stop();
                     f(partition, batch)?;
                     timer.restart();
@@ -188,6 +300,81 @@ impl BatchPartitioner {
     }
 }
 
+/// The number of rows sampled per output partition to compute the bounds of
+/// a range partitioning
+const RANGE_SAMPLE_ROWS_PER_PARTITION: usize = 100;
+
+/// Computes the bounds of `num_partitions` ranges of the sort key of the
+/// rows of `batches` that hold about as many rows each, from a sample of
+/// these rows
+///
+/// The bounds are returned as one array per sort expression, the `i`th rows
+/// of which form the largest key of partition `i`. There are fewer than
+/// `num_partitions - 1` bounds if fewer rows are sampled, and none without
+/// rows.
+pub fn range_bounds(
+    batches: &[RecordBatch],
+    sort_exprs: &[PhysicalSortExpr],
+    num_partitions: usize,
+) -> Result<Vec<ArrayRef>> {
+    let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    let sample_size = (RANGE_SAMPLE_ROWS_PER_PARTITION * num_partitions).min(num_rows);
+    if sample_size == 0 || num_partitions < 2 {
+        return Ok(vec![]);
+    }
+
+    // sample every `step`th row
+    let step = num_rows / sample_size;
+    let mut offset = 0;
+    let mut samples = vec![vec![]; sort_exprs.len()];
+    for batch in batches {
+        let first = (step - offset % step) % step;
+        let indices = UInt64Array::from_iter_values(
+            (first..batch.num_rows())
+                .step_by(step)
+                .map(|row| row as u64),
+        );
+        offset += batch.num_rows();
+        if indices.is_empty() {
+            continue;
+        }
+        for (sort_expr, samples) in sort_exprs.iter().zip(samples.iter_mut()) {
+            let key = sort_expr.expr.evaluate(batch)?.into_array(batch.num_rows());
+            samples.push(arrow::compute::take(key.as_ref(), &indices, None)?);
+        }
+    }
+    let samples = samples
+        .iter()
+        .map(|samples| {
+            let samples = samples.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
+            arrow::compute::concat(&samples)
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    // the bounds are evenly spaced in the sorted sample
+    let sort_columns = samples
+        .iter()
+        .zip(sort_exprs)
+        .map(|(values, sort_expr)| SortColumn {
+            values: values.clone(),
+            options: Some(sort_expr.options),
+        })
+        .collect::<Vec<_>>();
+    let sorted = lexsort_to_indices(&sort_columns, None)?;
+    let num_samples = sorted.len();
+    let mut bound_indices = (1..num_partitions)
+        .map(|i| sorted.value((i * num_samples / num_partitions).max(1) - 1))
+        .collect::<Vec<_>>();
+    bound_indices.dedup();
+    let bound_indices = UInt32Array::from(bound_indices);
+
+    let bounds = samples
+        .iter()
+        .map(|values| arrow::compute::take(values.as_ref(), &bound_indices, None))
+        .collect::<ArrowResult<Vec<_>>>()?;
+    Ok(bounds)
+}
+
 /// The repartition operator maps N input partitions to M output partitions based on a
 /// partitioning scheme. No guarantees are made about the order of the resulting partitions.
 #[derive(Debug)]
@@ -325,9 +512,40 @@ impl ExecutionPlan for RepartitionExec {
                 state.channels.insert(partition, (sender, receiver));
             }
 
-            // launch one async task per *input* partition
+            // launch one async task per *input* partition, or a single one
+            // that samples all of them to compute the bounds of the ranges
             let mut join_handles = Vec::with_capacity(num_input_partitions);
-            for i in 0..num_input_partitions {
+            if let Partitioning::Range(sort_exprs, num_partitions) = &self.partitioning {
+                let txs: HashMap<_, _> = state
+                    .channels
+                    .iter()
+                    .map(|(partition, (tx, _rx))| (*partition, tx.clone()))
+                    .collect();
+
+                let r_metrics = RepartitionMetrics::new(0, partition, &self.metrics);
+
+                let input_task: JoinHandle<Result<()>> =
+                    tokio::spawn(Self::pull_from_inputs_by_range(
+                        self.input.clone(),
+                        txs.clone(),
+                        sort_exprs.clone(),
+                        *num_partitions,
+                        r_metrics,
+                        context.clone(),
+                    ));
+
+                let join_handle = tokio::spawn(Self::wait_for_task(
+                    AbortOnDropSingle::new(input_task),
+                    txs,
+                ));
+                join_handles.push(join_handle);
+            }
+            let num_input_tasks = if join_handles.is_empty() {
+                num_input_partitions
+            } else {
+                0
+            };
+            for i in 0..num_input_tasks {
                 let txs: HashMap<_, _> = state
                     .channels
                     .iter()
@@ -365,6 +583,10 @@ impl ExecutionPlan for RepartitionExec {
 
         // now return stream for the specified *output* partition which will
         // read from the channel
+        let num_input_partitions = match self.partitioning {
+            Partitioning::Range(_, _) => 1,
+            _ => num_input_partitions,
+        };
         Ok(Box::pin(RepartitionStream {
             num_input_partitions,
             num_input_partitions_processed: 0,
@@ -467,6 +689,89 @@ impl RepartitionExec {
         Ok(())
     }
 
+    /// Pulls all the data from the input plan to compute the bounds of
+    /// `num_partitions` ranges of `sort_exprs` from a sample of it, then
+    /// feeds it to the output partitions by range, releasing the memory of
+    /// each buffered batch as it is sent
+    ///
+    /// txs hold the output sending channels for each output partition
+    async fn pull_from_inputs_by_range(
+        input: Arc<dyn ExecutionPlan>,
+        mut txs: HashMap<usize, UnboundedSender<Option<ArrowResult<RecordBatch>>>>,
+        sort_exprs: Vec<PhysicalSortExpr>,
+        num_partitions: usize,
+        r_metrics: RepartitionMetrics,
+        context: Arc<TaskContext>,
+    ) -> Result<()> {
+        // buffer the input partitions, in parallel, failing once the memory
+        // manager refuses to grant the memory of the buffered batches
+        let reservation = Arc::new(MemoryReservation::new(
+            "RepartitionExec by range",
+            0,
+            context.runtime_env().memory_manager.clone(),
+        ));
+        let timer = r_metrics.fetch_time.timer();
+        let input_tasks = (0..input.output_partitioning().partition_count())
+            .map(|i| {
+                let stream = input.execute(i, context.clone())?;
+                let reservation = reservation.clone();
+                Ok(AbortOnDropSingle::new(tokio::spawn(async move {
+                    stream
+                        .map_err(DataFusionError::from)
+                        .try_fold(vec![], |mut batches, batch| {
+                            let reservation = reservation.clone();
+                            async move {
+                                reservation.grow(batch_byte_size(&batch)).await?;
+                                batches.push(batch);
+                                Ok(batches)
+                            }
+                        })
+                        .await
+                })))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut batches = vec![];
+        for input_task in input_tasks {
+            let input_batches = input_task.await.map_err(|e| {
+                DataFusionError::Execution(format!("Join Error: {}", e))
+            })??;
+            batches.extend(input_batches);
+        }
+        timer.done();
+
+        let timer = r_metrics.repart_time.timer();
+        let bounds = range_bounds(&batches, &sort_exprs, num_partitions)?;
+        timer.done();
+
+        let mut partitioner = BatchPartitioner::try_new_range(
+            sort_exprs,
+            bounds,
+            r_metrics.repart_time.clone(),
+        )?;
+        for batch in batches {
+            if txs.is_empty() {
+                break;
+            }
+            // the receivers account for the batch once it is handed off
+            let size = batch_byte_size(&batch);
+            partitioner.partition(batch, |partition, partitioned| {
+                let timer = r_metrics.send_time.timer();
+                // if there is still a receiver, send to it
+                if let Some(tx) = txs.get_mut(&partition) {
+                    if tx.send(Some(Ok(partitioned))).is_err() {
+                        // If the other end has hung up, it was an early shutdown (e.g. LIMIT)
+                        txs.remove(&partition);
+                    }
+                }
+                timer.done();
+                Ok(())
+            })?;
+            reservation.shrink(size);
+        }
+
+        Ok(())
+    }
+
     /// Waits for `input_task` which is consuming one of the inputs to
     /// complete. Upon each successful completion, sends a `None` to
     /// each of the output tx channels to signal one of the inputs is
@@ -560,12 +865,18 @@ impl RecordBatchStream for RepartitionStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::execution::memory_manager::MemoryManagerConfig;
+    use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
     use crate::from_slice::FromSlice;
-    use crate::prelude::SessionContext;
+    use crate::physical_plan::common;
+    use crate::prelude::{SessionConfig, SessionContext};
     use crate::test::create_vec_batches;
     use crate::{
         assert_batches_sorted_eq,
-        physical_plan::{collect, expressions::col, memory::MemoryExec},
+        physical_plan::{
+            collect, collect_partitioned, expressions::col, memory::MemoryExec,
+            sorts::sort::SortExec,
+        },
         test::{
             assert_is_pending,
             exec::{
@@ -574,10 +885,11 @@ mod tests {
             },
         },
     };
+    use arrow::compute::SortOptions;
     use arrow::datatypes::{DataType, Field, Schema};
     use arrow::record_batch::RecordBatch;
     use arrow::{
-        array::{ArrayRef, StringArray},
+        array::{ArrayRef, Int32Array, StringArray},
         error::ArrowError,
     };
     use futures::FutureExt;
@@ -666,6 +978,153 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn many_to_many_range_partition() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("c0", DataType::Int32, true)]));
+        let partitions = (0..3)
+            .map(|p| {
+                (0..5)
+                    .map(|b| {
+                        let values = (0..100).map(|i| {
+                            let i = p * 500 + b * 100 + i;
+                            (i % 17 != 0).then(|| (i * 37) % 1000)
+                        });
+                        let array = Arc::new(Int32Array::from_iter(values)) as ArrayRef;
+                        RecordBatch::try_new(schema.clone(), vec![array]).unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let output_partitions =
+            repartition(&schema, partitions, Partitioning::Range(sort_exprs, 4)).await?;
+        assert_eq!(4, output_partitions.len());
+
+        // the sort key of the values, nulls first then descending
+        let keys = output_partitions
+            .iter()
+            .map(|batches| {
+                batches
+                    .iter()
+                    .flat_map(|batch| {
+                        let array = batch.column(0);
+                        let array = array.as_any().downcast_ref::<Int32Array>().unwrap();
+                        array.iter().map(|v| v.map(|v| -v)).collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(keys.iter().map(Vec::len).sum::<usize>(), 1500);
+        for (partition, next) in keys.iter().zip(keys.iter().skip(1)) {
+            assert!(!partition.is_empty());
+            let max = partition.iter().max().unwrap();
+            assert!(next.iter().all(|key| key > max));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn range_partition_exceeding_memory_limit() -> Result<()> {
+        let schema = test_schema();
+        let partitions = vec![create_vec_batches(&schema, 50); 3];
+        let config = RuntimeConfig::new()
+            .with_memory_manager(MemoryManagerConfig::try_new_limit(1024, 1.0)?);
+        let runtime = Arc::new(RuntimeEnv::new(config)?);
+        let session_ctx = SessionContext::with_config_rt(SessionConfig::new(), runtime);
+        let task_ctx = session_ctx.task_ctx();
+
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: SortOptions::default(),
+        }];
+        let exec = MemoryExec::try_new(&partitions, schema, None)?;
+        let exec =
+            RepartitionExec::try_new(Arc::new(exec), Partitioning::Range(sort_exprs, 4))?;
+
+        let err = common::collect(exec.execute(0, task_ctx)?)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Resources exhausted"),
+            "unexpected error: {}",
+            err
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sort_range_partitions_within_memory_limit() -> Result<()> {
+        let schema = test_schema();
+        let partitions = vec![create_vec_batches(&schema, 50); 3];
+        let input_size: usize = partitions.iter().flatten().map(batch_byte_size).sum();
+
+        // the sorts share the memory with the repartition, which has to give
+        // the memory of its buffered batches back as the sorts take them over
+        let config = RuntimeConfig::new().with_memory_manager(
+            MemoryManagerConfig::try_new_limit(6 * input_size, 1.0)?,
+        );
+        let runtime = Arc::new(RuntimeEnv::new(config)?);
+        let session_ctx =
+            SessionContext::with_config_rt(SessionConfig::new(), runtime.clone());
+        let task_ctx = session_ctx.task_ctx();
+
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: SortOptions::default(),
+        }];
+        let exec = MemoryExec::try_new(&partitions, schema, None)?;
+        let exec = RepartitionExec::try_new(
+            Arc::new(exec),
+            Partitioning::Range(sort_exprs.clone(), 4),
+        )?;
+        let sort = Arc::new(SortExec::new_with_partitioning(
+            sort_exprs,
+            Arc::new(exec),
+            true,
+        ));
+
+        let output_partitions = collect_partitioned(sort.clone(), task_ctx).await?;
+        let num_rows: usize = output_partitions
+            .iter()
+            .flatten()
+            .map(RecordBatch::num_rows)
+            .sum();
+        assert_eq!(num_rows, 1200);
+        assert_eq!(sort.metrics().unwrap().spill_count(), Some(0));
+        assert_eq!(runtime.memory_manager.get_requester_total(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn range_bounds_of_few_rows() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("c0", DataType::Int32, true)]));
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: SortOptions::default(),
+        }];
+        assert!(range_bounds(&[], &sort_exprs, 4)?.is_empty());
+
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int32Array::from(vec![Some(3), None]))],
+        )?;
+        // both rows are sampled, and the first one sorted bounds the first
+        // partition
+        let bounds = range_bounds(&[batch], &sort_exprs, 4)?;
+        assert_eq!(bounds.len(), 1);
+        assert_eq!(bounds[0].len(), 1);
+        assert!(bounds[0].is_null(0));
+        Ok(())
+    }
+
     fn test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]))
     }
@@ -0,0 +1,622 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Runtime filters published by the build side of a join, and applied
+//! to the probe side to skip rows, row groups and files that cannot match
+//!
+//! When [`HashJoinExec`](super::hash_join::HashJoinExec) has collected its build
+//! side, it knows the exact set of join keys. It publishes the minimum and maximum
+//! of each key column, and if it is small enough, the set of hashes of the keys, to
+//! a [`RuntimeFilter`]. Operators on the probe side reference the [`RuntimeFilter`]
+//! through a [`RuntimeFilterExpr`], which evaluates to `true` for every row until the
+//! filter has been published.
+//!
+//! The join starts a new build of the filter each time it executes its build side,
+//! so that the probe side of a plan executed again never uses the keys of an
+//! earlier execution.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use ahash::RandomState;
+use arrow::array::{ArrayRef, BooleanArray};
+use arrow::compute::and_kleene;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion_expr::Accumulator;
+use parking_lot::Mutex;
+
+use crate::error::Result;
+use crate::logical_plan::{self, Operator};
+use crate::physical_plan::expressions::{
+    binary, lit, Column, MaxAccumulator, MinAccumulator,
+};
+use crate::physical_plan::hash_utils::create_hashes;
+use crate::physical_plan::{ColumnarValue, PhysicalExpr};
+use crate::scalar::ScalarValue;
+
+/// The maximum number of distinct key hashes retained by a [`RuntimeFilter`],
+/// if the build side contains more distinct keys only the key ranges are used
+pub const MAX_RUNTIME_FILTER_HASHES: usize = 65536;
+
+/// The values published by the build side of a join
+#[derive(Debug)]
+struct RuntimeFilterValues {
+    /// The minimum and maximum value of each key column, or `None`
+    /// if no non-null value was seen
+    ranges: Vec<Option<(ScalarValue, ScalarValue)>>,
+    /// The types of the key columns the hashes were computed from
+    data_types: Vec<DataType>,
+    /// The hashes of all non-null keys, if there are at most
+    /// [`MAX_RUNTIME_FILTER_HASHES`] of them
+    hashes: Option<HashSet<u64>>,
+}
+
+impl RuntimeFilterValues {
+    /// Merges values published by another build side partition into `self`
+    fn merge(&mut self, other: RuntimeFilterValues) -> Result<()> {
+        for (range, other) in self.ranges.iter_mut().zip(other.ranges) {
+            *range = match (range.take(), other) {
+                (Some((min, max)), Some((other_min, other_max))) => {
+                    let min = min_max_of(&min, &other_min, false)?;
+                    let max = min_max_of(&max, &other_max, true)?;
+                    Some((min, max))
+                }
+                (range, None) => range,
+                (None, other) => other,
+            }
+        }
+
+        self.hashes = match (self.hashes.take(), other.hashes) {
+            (Some(mut hashes), Some(other)) => {
+                hashes.extend(other);
+                (hashes.len() <= MAX_RUNTIME_FILTER_HASHES).then(|| hashes)
+            }
+            _ => None,
+        };
+        Ok(())
+    }
+}
+
+fn min_max_of(a: &ScalarValue, b: &ScalarValue, max: bool) -> Result<ScalarValue> {
+    if max {
+        crate::physical_plan::expressions::helpers::max(a, b)
+    } else {
+        crate::physical_plan::expressions::helpers::min(a, b)
+    }
+}
+
+#[derive(Debug, Default)]
+struct RuntimeFilterState {
+    /// The number of build side partitions that have started the current build
+    started: usize,
+    /// The number of build side partitions that have published their values
+    published: usize,
+    /// The values merged from the partitions published so far
+    partial: Option<RuntimeFilterValues>,
+    /// The values merged from all partitions, once available
+    complete: Option<Arc<RuntimeFilterValues>>,
+}
+
+/// A filter on the join keys of the probe side of a join, whose
+/// values are only known once the build side has been collected
+#[derive(Debug)]
+pub struct RuntimeFilter {
+    /// The number of key columns
+    num_keys: usize,
+    /// The number of build side partitions that must publish
+    /// their values before the filter is available
+    partitions: usize,
+    /// The hasher used for the key hashes
+    random_state: RandomState,
+    state: Mutex<RuntimeFilterState>,
+}
+
+impl RuntimeFilter {
+    /// Creates a new [`RuntimeFilter`] on `num_keys` key columns that becomes
+    /// available once each of `partitions` build side partitions has published
+    pub fn new(num_keys: usize, partitions: usize) -> Self {
+        Self {
+            num_keys,
+            partitions,
+            random_state: RandomState::with_seeds(0, 0, 0, 0),
+            state: Default::default(),
+        }
+    }
+
+    /// The number of key columns of this filter
+    pub fn num_keys(&self) -> usize {
+        self.num_keys
+    }
+
+    /// Returns `true` if all build side partitions have published their values
+    pub fn is_available(&self) -> bool {
+        self.state.lock().complete.is_some()
+    }
+
+    /// Starts collecting the build side of one partition. Once all partitions
+    /// of the previous build have started, this discards the published values
+    /// so that the probe side of a new execution does not use them.
+    pub fn start_build(&self) {
+        let mut state = self.state.lock();
+        if state.started == self.partitions {
+            *state = RuntimeFilterState::default();
+        }
+        state.started += 1;
+    }
+
+    /// Publishes the key values of one partition of the build side
+    pub fn publish(&self, keys: &[ArrayRef]) -> Result<()> {
+        assert_eq!(
+            keys.len(),
+            self.num_keys,
+            "runtime filter key count mismatch"
+        );
+
+        let ranges = keys
+            .iter()
+            .map(|key| {
+                if key.null_count() == key.len() {
+                    return Ok(None);
+                }
+                let mut min = MinAccumulator::try_new(key.data_type())?;
+                let mut max = MaxAccumulator::try_new(key.data_type())?;
+                min.update_batch(std::slice::from_ref(key))?;
+                max.update_batch(std::slice::from_ref(key))?;
+                Ok(Some((min.evaluate()?, max.evaluate()?)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_rows = keys.first().map(|k| k.len()).unwrap_or_default();
+        let mut hashes_buffer = vec![0; num_rows];
+        create_hashes(keys, &self.random_state, &mut hashes_buffer)?;
+
+        let mut hashes = HashSet::new();
+        for (row, hash) in hashes_buffer.into_iter().enumerate() {
+            // Null keys never match
+            if keys.iter().any(|k| k.is_null(row)) {
+                continue;
+            }
+            hashes.insert(hash);
+            if hashes.len() > MAX_RUNTIME_FILTER_HASHES {
+                break;
+            }
+        }
+        let hashes = (hashes.len() <= MAX_RUNTIME_FILTER_HASHES).then(|| hashes);
+
+        let data_types = keys.iter().map(|k| k.data_type().clone()).collect();
+        let values = RuntimeFilterValues {
+            ranges,
+            data_types,
+            hashes,
+        };
+
+        let mut state = self.state.lock();
+        state.published += 1;
+        match state.partial.as_mut() {
+            Some(existing) => existing.merge(values)?,
+            None => state.partial = Some(values),
+        }
+
+        if state.published == self.partitions {
+            state.complete = state.partial.take().map(Arc::new);
+        }
+        Ok(())
+    }
+
+    /// Returns the published values if all partitions have published
+    fn values(&self) -> Option<Arc<RuntimeFilterValues>> {
+        self.state.lock().complete.clone()
+    }
+}
+
+/// The predicate derived from the values of a [`RuntimeFilter`]
+#[derive(Debug)]
+struct ResolvedRuntimeFilter {
+    /// Range predicate on the key columns, `None` if the build side was empty
+    ranges: Option<Arc<dyn PhysicalExpr>>,
+    /// Whether the probe side keys have the types of the build side keys,
+    /// so that their hashes can be looked up in the published hashes
+    check_hashes: bool,
+    /// The published values
+    values: Arc<RuntimeFilterValues>,
+}
+
+/// A [`PhysicalExpr`] that evaluates a [`RuntimeFilter`] against the probe side
+/// key columns `columns`, returning `true` for all rows until the filter is available
+#[derive(Debug)]
+pub struct RuntimeFilterExpr {
+    filter: Arc<RuntimeFilter>,
+    columns: Vec<Column>,
+    resolved: Mutex<Option<Arc<ResolvedRuntimeFilter>>>,
+}
+
+impl RuntimeFilterExpr {
+    /// Create a new [`RuntimeFilterExpr`] applying `filter` to `columns`
+    pub fn new(filter: Arc<RuntimeFilter>, columns: Vec<Column>) -> Self {
+        assert_eq!(filter.num_keys(), columns.len());
+        Self {
+            filter,
+            columns,
+            resolved: Mutex::new(None),
+        }
+    }
+
+    /// The [`RuntimeFilter`] evaluated by this expression
+    pub fn filter(&self) -> &Arc<RuntimeFilter> {
+        &self.filter
+    }
+
+    /// The probe side columns the filter is applied to
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Returns a copy of this expression applied to `columns`
+    pub fn with_columns(&self, columns: Vec<Column>) -> Self {
+        Self::new(self.filter.clone(), columns)
+    }
+
+    /// Resolves the published filter against `schema`
+    fn resolve(&self, schema: &Schema) -> Result<Option<Arc<ResolvedRuntimeFilter>>> {
+        let values = match self.filter.values() {
+            Some(values) => values,
+            None => return Ok(None),
+        };
+
+        // The filter is resolved again once a new build has been published
+        let mut resolved = self.resolved.lock();
+        if let Some(resolved) = resolved.as_ref() {
+            if Arc::ptr_eq(&resolved.values, &values) {
+                return Ok(Some(resolved.clone()));
+            }
+        }
+
+        let mut ranges: Option<Arc<dyn PhysicalExpr>> = None;
+        let mut empty = false;
+        for (column, range) in self.columns.iter().zip(&values.ranges) {
+            let (min, max) = match range {
+                Some(range) => range,
+                None => {
+                    empty = true;
+                    continue;
+                }
+            };
+            // Key types should match, but skip the key rather than error if not
+            let data_type = column.data_type(schema)?;
+            if min.get_datatype() != data_type {
+                continue;
+            }
+
+            let col = Arc::new(column.clone()) as Arc<dyn PhysicalExpr>;
+            let gt_eq = binary(col.clone(), Operator::GtEq, lit(min.clone()), schema)?;
+            let lt_eq = binary(col, Operator::LtEq, lit(max.clone()), schema)?;
+            let range = binary(gt_eq, Operator::And, lt_eq, schema)?;
+            ranges = Some(match ranges {
+                Some(ranges) => binary(ranges, Operator::And, range, schema)?,
+                None => range,
+            });
+        }
+
+        if empty {
+            // The build side contained no non-null keys, so nothing can match
+            ranges = Some(lit(ScalarValue::Boolean(Some(false))));
+        }
+
+        // Hashes of keys of different types do not match even if the values do
+        let mut check_hashes = true;
+        for (column, data_type) in self.columns.iter().zip(&values.data_types) {
+            check_hashes &= &column.data_type(schema)? == data_type;
+        }
+
+        let result = Arc::new(ResolvedRuntimeFilter {
+            ranges,
+            check_hashes,
+            values,
+        });
+        *resolved = Some(result.clone());
+        Ok(Some(result))
+    }
+
+    /// Returns a logical predicate on the key ranges of the filter suitable for
+    /// constructing a [`PruningPredicate`], or `None` if the filter is not yet
+    /// available. Only key columns contained in `schema` are included.
+    ///
+    /// [`PruningPredicate`]: crate::physical_optimizer::pruning::PruningPredicate
+    pub fn pruning_expr(&self, schema: &Schema) -> Option<logical_plan::Expr> {
+        let values = self.filter.values()?;
+        self.columns
+            .iter()
+            .zip(&values.ranges)
+            .filter_map(|(column, range)| {
+                let field = schema.field_with_name(column.name()).ok()?;
+                match range {
+                    Some((min, max)) if &min.get_datatype() == field.data_type() => {
+                        let col = logical_plan::col(column.name());
+                        Some(
+                            col.clone()
+                                .gt_eq(logical_plan::lit(min.clone()))
+                                .and(col.lt_eq(logical_plan::lit(max.clone()))),
+                        )
+                    }
+                    Some(_) => None,
+                    None => Some(logical_plan::lit(false)),
+                }
+            })
+            .reduce(logical_plan::and)
+    }
+
+    /// Returns `false` if no row whose key column `name` has the value `value` can
+    /// pass the filter, for example when `name` is a partition column of a file
+    pub fn may_contain(&self, name: &str, value: &ScalarValue) -> bool {
+        let values = match self.filter.values() {
+            Some(values) => values,
+            None => return true,
+        };
+
+        self.columns
+            .iter()
+            .zip(&values.ranges)
+            .filter(|(column, _)| column.name() == name)
+            .all(|(_, range)| match range {
+                Some((min, max)) => {
+                    let (min, max) = (min.partial_cmp(value), max.partial_cmp(value));
+                    !matches!(min, Some(std::cmp::Ordering::Greater))
+                        && !matches!(max, Some(std::cmp::Ordering::Less))
+                }
+                None => false,
+            })
+    }
+}
+
+impl fmt::Display for RuntimeFilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "runtime_filter({})", columns)
+    }
+}
+
+impl PhysicalExpr for RuntimeFilterExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, _input_schema: &Schema) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let resolved = match self.resolve(batch.schema().as_ref())? {
+            Some(resolved) => resolved,
+            None => return Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(true)))),
+        };
+
+        let mut mask = match &resolved.ranges {
+            Some(ranges) => Some(ranges.evaluate(batch)?.into_array(batch.num_rows())),
+            None => None,
+        };
+
+        let hashes = resolved
+            .values
+            .hashes
+            .as_ref()
+            .filter(|_| resolved.check_hashes);
+        if let Some(hashes) = hashes {
+            let keys = self
+                .columns
+                .iter()
+                .map(|c| Ok(c.evaluate(batch)?.into_array(batch.num_rows())))
+                .collect::<Result<Vec<_>>>()?;
+
+            let mut hashes_buffer = vec![0; batch.num_rows()];
+            create_hashes(&keys, &self.filter.random_state, &mut hashes_buffer)?;
+
+            let contained = hashes_buffer
+                .iter()
+                .enumerate()
+                .map(|(row, hash)| {
+                    let null = keys.iter().any(|k| k.is_null(row));
+                    (!null).then(|| hashes.contains(hash))
+                })
+                .collect::<BooleanArray>();
+
+            mask = Some(match mask {
+                Some(mask) => {
+                    let mask = mask.as_any().downcast_ref::<BooleanArray>().unwrap();
+                    Arc::new(and_kleene(mask, &contained)?)
+                }
+                None => Arc::new(contained),
+            });
+        }
+
+        Ok(match mask {
+            Some(mask) => ColumnarValue::Array(mask),
+            None => ColumnarValue::Scalar(ScalarValue::Boolean(Some(true))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int32Array, Int64Array, StringArray};
+    use arrow::datatypes::Field;
+
+    fn probe_batch() -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            (
+                "a",
+                Arc::new(Int32Array::from(vec![Some(1), Some(5), None, Some(9)]))
+                    as ArrayRef,
+            ),
+            (
+                "b",
+                Arc::new(StringArray::from(vec!["x", "y", "z", "x"])) as ArrayRef,
+            ),
+        ])
+        .unwrap()
+    }
+
+    fn evaluate(expr: &RuntimeFilterExpr, batch: &RecordBatch) -> Vec<Option<bool>> {
+        let array = expr.evaluate(batch).unwrap().into_array(batch.num_rows());
+        let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+        (0..array.len())
+            .map(|i| array.is_valid(i).then(|| array.value(i)))
+            .collect()
+    }
+
+    #[test]
+    fn runtime_filter_unpublished() {
+        let filter = Arc::new(RuntimeFilter::new(1, 1));
+        let expr = RuntimeFilterExpr::new(filter.clone(), vec![Column::new("a", 0)]);
+
+        let batch = probe_batch();
+        let result = expr.evaluate(&batch).unwrap();
+        assert!(matches!(
+            result,
+            ColumnarValue::Scalar(ScalarValue::Boolean(Some(true)))
+        ));
+        assert!(!filter.is_available());
+        assert!(expr.may_contain("a", &ScalarValue::Int32(Some(100))));
+        assert!(expr.pruning_expr(&batch.schema()).is_none());
+    }
+
+    #[test]
+    fn runtime_filter_single_key() {
+        let filter = Arc::new(RuntimeFilter::new(1, 1));
+        let expr = RuntimeFilterExpr::new(filter.clone(), vec![Column::new("a", 0)]);
+
+        let build: ArrayRef = Arc::new(Int32Array::from(vec![Some(5), None, Some(9)]));
+        filter.publish(&[build]).unwrap();
+        assert!(filter.is_available());
+
+        let batch = probe_batch();
+        assert_eq!(
+            evaluate(&expr, &batch),
+            vec![Some(false), Some(true), None, Some(true)]
+        );
+
+        assert!(!expr.may_contain("a", &ScalarValue::Int32(Some(1))));
+        assert!(expr.may_contain("a", &ScalarValue::Int32(Some(7))));
+        assert!(expr.may_contain("b", &ScalarValue::Int32(Some(1))));
+
+        let pruning = expr.pruning_expr(&batch.schema()).unwrap();
+        assert_eq!(
+            format!("{:?}", pruning),
+            "#a >= Int32(5) AND #a <= Int32(9)"
+        );
+    }
+
+    #[test]
+    fn runtime_filter_multiple_partitions() {
+        let filter = Arc::new(RuntimeFilter::new(2, 2));
+        let expr = RuntimeFilterExpr::new(
+            filter.clone(),
+            vec![Column::new("a", 0), Column::new("b", 1)],
+        );
+
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1]));
+        let b: ArrayRef = Arc::new(StringArray::from(vec!["x"]));
+        filter.publish(&[a, b]).unwrap();
+        assert!(!filter.is_available());
+
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![9]));
+        let b: ArrayRef = Arc::new(StringArray::from(vec!["y"]));
+        filter.publish(&[a, b]).unwrap();
+        assert!(filter.is_available());
+
+        // (5, "y") is within the key ranges but was not a build side key
+        let batch = probe_batch();
+        assert_eq!(
+            evaluate(&expr, &batch),
+            vec![Some(true), Some(false), Some(false), Some(false)]
+        );
+    }
+
+    #[test]
+    fn runtime_filter_empty_build_side() {
+        let filter = Arc::new(RuntimeFilter::new(1, 1));
+        let expr = RuntimeFilterExpr::new(filter.clone(), vec![Column::new("a", 0)]);
+
+        let build: ArrayRef = Arc::new(Int32Array::from(vec![None, None]));
+        filter.publish(&[build]).unwrap();
+
+        let batch = probe_batch();
+        assert!(evaluate(&expr, &batch).iter().all(|x| x != &Some(true)));
+        assert!(!expr.may_contain("a", &ScalarValue::Int32(Some(5))));
+
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let pruning = expr.pruning_expr(&schema).unwrap();
+        assert_eq!(format!("{:?}", pruning), "Boolean(false)");
+    }
+
+    #[test]
+    fn runtime_filter_rebuilt() {
+        let filter = Arc::new(RuntimeFilter::new(1, 2));
+        let expr = RuntimeFilterExpr::new(filter.clone(), vec![Column::new("a", 0)]);
+        let batch = probe_batch();
+
+        for keys in [vec![1, 9], vec![5, 9]] {
+            filter.start_build();
+            filter.start_build();
+            assert!(!filter.is_available());
+            assert!(evaluate(&expr, &batch).iter().all(|x| x == &Some(true)));
+
+            for key in &keys {
+                let build: ArrayRef = Arc::new(Int32Array::from(vec![*key]));
+                filter.publish(&[build]).unwrap();
+            }
+            assert!(filter.is_available());
+
+            let expected = [1, 5, 9]
+                .iter()
+                .map(|k| Some(keys.contains(k)))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                evaluate(&expr, &batch),
+                vec![expected[0], expected[1], None, expected[2]]
+            );
+        }
+    }
+
+    #[test]
+    fn runtime_filter_key_type_mismatch() {
+        let filter = Arc::new(RuntimeFilter::new(1, 1));
+        let expr = RuntimeFilterExpr::new(filter.clone(), vec![Column::new("a", 0)]);
+
+        // Int64 keys hash differently from the Int32 probe side keys
+        let build: ArrayRef = Arc::new(Int64Array::from(vec![5, 9]));
+        filter.publish(&[build]).unwrap();
+
+        let batch = probe_batch();
+        assert_eq!(
+            evaluate(&expr, &batch),
+            vec![Some(true), Some(true), Some(true), Some(true)]
+        );
+    }
+}
@@ -38,7 +38,7 @@ use futures::{Stream, StreamExt};
 use crate::error::DataFusionError;
 use crate::error::Result;
 use crate::execution::context::TaskContext;
-use crate::logical_plan::JoinType;
+use crate::logical_plan::{JoinType, Operator};
 use crate::physical_plan::common::combine_batches;
 use crate::physical_plan::expressions::Column;
 use crate::physical_plan::expressions::PhysicalSortExpr;
@@ -69,6 +69,23 @@ pub struct SortMergeJoinExec {
     sort_options: Vec<SortOptions>,
     /// If null_equals_null is true, null == null else null != null
     null_equals_null: bool,
+    /// Match condition of an ASOF join
+    asof_condition: Option<AsOfCondition>,
+}
+
+/// The inequality `left op right` between an ordered column of each side that an
+/// ASOF join uses to pick, for each left row, the nearest right row with equal keys.
+///
+/// Both inputs must be sorted on the join keys followed by the ordered column,
+/// ascending with nulls last.
+#[derive(Debug, Clone)]
+pub struct AsOfCondition {
+    /// Ordered column of the left side
+    pub left: Column,
+    /// One of `>=`, `>`, `<=` or `<`
+    pub op: Operator,
+    /// Ordered column of the right side
+    pub right: Column,
 }
 
 impl SortMergeJoinExec {
@@ -108,8 +125,55 @@ impl SortMergeJoinExec {
             metrics: ExecutionPlanMetricsSet::new(),
             sort_options,
             null_equals_null,
+            asof_condition: None,
         })
     }
+
+    /// Sets the match condition of an ASOF join.
+    /// # Error
+    /// This function errors if the join is not an ASOF join, or if the condition does
+    /// not compare columns of the same type with one of `>=`, `>`, `<=` or `<`.
+    pub fn with_asof_condition(mut self, condition: AsOfCondition) -> Result<Self> {
+        if self.join_type != JoinType::AsOf {
+            return Err(DataFusionError::Plan(
+                "Only ASOF joins can have a match condition".to_string(),
+            ));
+        }
+        if !matches!(
+            condition.op,
+            Operator::GtEq | Operator::Gt | Operator::LtEq | Operator::Lt
+        ) {
+            return Err(DataFusionError::Plan(format!(
+                "Unsupported operator in ASOF match condition: {}",
+                condition.op
+            )));
+        }
+        let left_type = self
+            .left
+            .schema()
+            .field(condition.left.index())
+            .data_type()
+            .clone();
+        let right_type = self
+            .right
+            .schema()
+            .field(condition.right.index())
+            .data_type()
+            .clone();
+        if left_type != right_type {
+            return Err(DataFusionError::Plan(format!(
+                "ASOF match condition compares {:?} with {:?}",
+                left_type, right_type
+            )));
+        }
+        self.asof_condition = Some(condition);
+        Ok(self)
+    }
+
+    /// Match condition of an ASOF join
+    pub fn asof_condition(&self) -> Option<&AsOfCondition> {
+        self.asof_condition.as_ref()
+    }
 }
 
 impl ExecutionPlan for SortMergeJoinExec {
@@ -142,14 +206,20 @@ impl ExecutionPlan for SortMergeJoinExec {
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         match &children[..] {
-            [left, right] => Ok(Arc::new(SortMergeJoinExec::try_new(
-                left.clone(),
-                right.clone(),
-                self.on.clone(),
-                self.join_type,
-                self.sort_options.clone(),
-                self.null_equals_null,
-            )?)),
+            [left, right] => {
+                let join = SortMergeJoinExec::try_new(
+                    left.clone(),
+                    right.clone(),
+                    self.on.clone(),
+                    self.join_type,
+                    self.sort_options.clone(),
+                    self.null_equals_null,
+                )?;
+                Ok(Arc::new(match &self.asof_condition {
+                    Some(condition) => join.with_asof_condition(condition.clone())?,
+                    None => join,
+                }))
+            }
             _ => Err(DataFusionError::Internal(
                 "SortMergeJoin wrong number of children".to_string(),
             )),
@@ -166,7 +236,8 @@ impl ExecutionPlan for SortMergeJoinExec {
             | JoinType::Left
             | JoinType::Full
             | JoinType::Anti
-            | JoinType::Semi => (
+            | JoinType::Semi
+            | JoinType::AsOf => (
                 self.left.clone(),
                 self.right.clone(),
                 self.on.iter().map(|on| on.0.clone()).collect(),
@@ -180,6 +251,12 @@ impl ExecutionPlan for SortMergeJoinExec {
             ),
         };
 
+        if self.join_type == JoinType::AsOf && self.asof_condition.is_none() {
+            return Err(DataFusionError::Plan(
+                "ASOF join requires a match condition".to_string(),
+            ));
+        }
+
         // execute children plans
         let streamed = streamed.execute(partition, context.clone())?;
         let buffered = buffered.execute(partition, context.clone())?;
@@ -197,6 +274,7 @@ impl ExecutionPlan for SortMergeJoinExec {
             on_streamed,
             on_buffered,
             self.join_type,
+            self.asof_condition.clone(),
             batch_size,
             SortMergeJoinMetrics::new(partition, &self.metrics),
         )?))
@@ -211,9 +289,17 @@ impl ExecutionPlan for SortMergeJoinExec {
             DisplayFormatType::Default => {
                 write!(
                     f,
-                    "SortMergeJoin: join_type={:?}, on={:?}, schema={:?}",
-                    self.join_type, self.on, &self.schema
-                )
+                    "SortMergeJoin: join_type={:?}, on={:?}",
+                    self.join_type, self.on
+                )?;
+                if let Some(condition) = &self.asof_condition {
+                    write!(
+                        f,
+                        ", match_condition={} {} {}",
+                        condition.left, condition.op, condition.right
+                    )?;
+                }
+                write!(f, ", schema={:?}", &self.schema)
             }
         }
     }
@@ -399,6 +485,11 @@ struct SMJStream {
     pub batch_size: usize,
     /// How the join is performed
     pub join_type: JoinType,
+    /// Match condition of an ASOF join
+    pub asof_condition: Option<AsOfCondition>,
+    /// (used in ASOF join) Number of rows of the buffered batches that precede the
+    /// nearest match of the current streamed row
+    pub asof_offset: usize,
     /// Metrics
     pub join_metrics: SortMergeJoinMetrics,
 }
@@ -440,6 +531,7 @@ impl Stream for SMJStream {
                                 if !buffered_exhausted {
                                     self.buffered_joined = false;
                                     self.buffered_state = BufferedState::Init;
+                                    self.asof_offset = 0;
                                 }
                             }
                         }
@@ -516,6 +608,7 @@ impl SMJStream {
         on_streamed: Vec<Column>,
         on_buffered: Vec<Column>,
         join_type: JoinType,
+        asof_condition: Option<AsOfCondition>,
         batch_size: usize,
         join_metrics: SortMergeJoinMetrics,
     ) -> Result<Self> {
@@ -543,6 +636,8 @@ impl SMJStream {
             output_size: 0,
             batch_size,
             join_type,
+            asof_condition,
+            asof_offset: 0,
             join_metrics,
         })
     }
@@ -721,12 +816,19 @@ impl SMJStream {
             Ordering::Less => {
                 if matches!(
                     self.join_type,
-                    JoinType::Left | JoinType::Right | JoinType::Full | JoinType::Anti
+                    JoinType::Left
+                        | JoinType::Right
+                        | JoinType::Full
+                        | JoinType::Anti
+                        | JoinType::AsOf
                 ) {
                     join_streamed = !self.streamed_joined;
                 }
             }
             Ordering::Equal => {
+                if matches!(self.join_type, JoinType::AsOf) {
+                    return self.join_asof();
+                }
                 if matches!(self.join_type, JoinType::Semi) {
                     join_streamed = !self.streamed_joined;
                 }
@@ -793,6 +895,96 @@ impl SMJStream {
         Ok(())
     }
 
+    /// Join the current streamed row with its nearest buffered row that satisfies
+    /// the ASOF match condition, or with nulls if there is none.
+    ///
+    /// Since both sides are sorted on the ordered column, the nearest match of each
+    /// streamed row is never before that of the previous one, and so the buffered
+    /// rows are scanned only once
+    fn join_asof(&mut self) -> ArrowResult<()> {
+        if self.streamed_joined {
+            self.buffered_data.scanning_finish();
+            return Ok(());
+        }
+        let condition = self.asof_condition.clone().unwrap();
+        let streamed_array = self
+            .streamed_batch
+            .batch
+            .column(condition.left.index())
+            .clone();
+        let streamed_idx = self.streamed_batch.idx;
+
+        let mut matched = None;
+        if !streamed_array.is_null(streamed_idx) {
+            while let Some((batch_idx, buffered_idx)) =
+                self.buffered_data.row_at(self.asof_offset)
+            {
+                let buffered_array = self.buffered_data.batches[batch_idx]
+                    .batch
+                    .column(condition.right.index());
+                // nulls are sorted last and never match
+                if buffered_array.is_null(buffered_idx) {
+                    break;
+                }
+                let ordering = compare_join_arrays(
+                    std::slice::from_ref(&streamed_array),
+                    streamed_idx,
+                    std::slice::from_ref(buffered_array),
+                    buffered_idx,
+                    &[SortOptions::default()],
+                    false,
+                )?;
+                match condition.op {
+                    // the matches are a prefix of the buffered rows: the nearest
+                    // is the last one
+                    Operator::GtEq | Operator::Gt => {
+                        let is_match = if condition.op == Operator::Gt {
+                            ordering == Ordering::Greater
+                        } else {
+                            ordering != Ordering::Less
+                        };
+                        if !is_match {
+                            break;
+                        }
+                        self.asof_offset += 1;
+                    }
+                    // the matches follow the buffered rows that are too small: the
+                    // nearest is the first one
+                    _ => {
+                        let is_match = if condition.op == Operator::Lt {
+                            ordering == Ordering::Less
+                        } else {
+                            ordering != Ordering::Greater
+                        };
+                        if is_match {
+                            matched = Some((batch_idx, buffered_idx));
+                            break;
+                        }
+                        self.asof_offset += 1;
+                    }
+                }
+            }
+            if matches!(condition.op, Operator::GtEq | Operator::Gt)
+                && self.asof_offset > 0
+            {
+                matched = self.buffered_data.row_at(self.asof_offset - 1);
+            }
+        }
+
+        match matched {
+            Some((batch_idx, buffered_idx)) => {
+                let pair_joined = &mut self.buffered_data.batches[batch_idx].pair_joined;
+                pair_joined.0.push(streamed_idx);
+                pair_joined.1.push(buffered_idx);
+            }
+            None => self.streamed_batch.null_joined.push(streamed_idx),
+        }
+        self.output_size += 1;
+        self.buffered_data.scanning_finish();
+        self.streamed_joined = true;
+        Ok(())
+    }
+
     fn freeze_all(&mut self) -> ArrowResult<()> {
         self.freeze_streamed_join_null()?;
         self.freeze_buffered_join_null(self.buffered_data.batches.len())?;
@@ -814,7 +1006,7 @@ impl SMJStream {
         Ok(())
     }
 
-    // join_type must be one of: `Left`/`Right`/`Full`/`Semi`/`Anti`
+    // join_type must be one of: `Left`/`Right`/`Full`/`Semi`/`Anti`/`AsOf`
     fn freeze_streamed_join_null(&mut self) -> ArrowResult<()> {
         if !matches!(
             self.join_type,
@@ -823,6 +1015,7 @@ impl SMJStream {
                 | JoinType::Full
                 | JoinType::Semi
                 | JoinType::Anti
+                | JoinType::AsOf
         ) {
             return Ok(());
         }
@@ -905,11 +1098,15 @@ impl SMJStream {
         Ok(())
     }
 
-    // join_type must be `Inner`/`Left`/`Right`/`Full`
+    // join_type must be `Inner`/`Left`/`Right`/`Full`/`AsOf`
     fn freeze_buffered_join_streamed(&mut self, batch_count: usize) -> ArrowResult<()> {
         if !matches!(
             self.join_type,
-            JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full
+            JoinType::Inner
+                | JoinType::Left
+                | JoinType::Right
+                | JoinType::Full
+                | JoinType::AsOf
         ) {
             return Ok(());
         }
@@ -1038,6 +1235,18 @@ impl BufferedData {
         self.scanning_batch_idx = self.batches.len();
         self.scanning_offset = 0;
     }
+
+    /// Batch index and row index of the `offset`-th buffered row with the join key
+    pub fn row_at(&self, offset: usize) -> Option<(usize, usize)> {
+        let mut offset = offset;
+        for (batch_idx, batch) in self.batches.iter().enumerate() {
+            if offset < batch.range.len() {
+                return Some((batch_idx, batch.range.start + offset));
+            }
+            offset -= batch.range.len();
+        }
+        None
+    }
 }
 
 /// Get join array refs of given batch and join columns
@@ -1213,11 +1422,11 @@ mod tests {
 
     use crate::assert_batches_sorted_eq;
     use crate::error::Result;
-    use crate::logical_plan::JoinType;
+    use crate::logical_plan::{JoinType, Operator};
     use crate::physical_plan::expressions::Column;
     use crate::physical_plan::join_utils::JoinOn;
     use crate::physical_plan::memory::MemoryExec;
-    use crate::physical_plan::sort_merge_join::SortMergeJoinExec;
+    use crate::physical_plan::sort_merge_join::{AsOfCondition, SortMergeJoinExec};
     use crate::physical_plan::{common, ExecutionPlan};
     use crate::prelude::{SessionConfig, SessionContext};
     use crate::test::{build_table_i32, columns};
@@ -1874,4 +2083,149 @@ mod tests {
         assert_batches_sorted_eq!(expected, &batches);
         Ok(())
     }
+
+    async fn join_asof_collect(op: Operator) -> Result<Vec<RecordBatch>> {
+        let left = build_table(
+            ("a1", &vec![1, 1, 1, 2, 3]),
+            ("b1", &vec![5, 10, 15, 5, 5]),
+            ("c1", &vec![1, 2, 3, 4, 5]),
+        );
+        // the rows with key 1 span both batches
+        let right_batches = vec![
+            build_table_i32(
+                ("a2", &vec![1, 1]),
+                ("b2", &vec![4, 10]),
+                ("c2", &vec![10, 20]),
+            ),
+            build_table_i32(
+                ("a2", &vec![1, 2, 2]),
+                ("b2", &vec![12, 6, 7]),
+                ("c2", &vec![30, 40, 50]),
+            ),
+        ];
+        let schema = right_batches[0].schema();
+        let right: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[right_batches], schema, None)?);
+
+        let on = vec![(
+            Column::new_with_schema("a1", &left.schema())?,
+            Column::new_with_schema("a2", &right.schema())?,
+        )];
+        let condition = AsOfCondition {
+            left: Column::new_with_schema("b1", &left.schema())?,
+            op,
+            right: Column::new_with_schema("b2", &right.schema())?,
+        };
+        let join =
+            join(left, right, on, JoinType::AsOf)?.with_asof_condition(condition)?;
+
+        let session_ctx =
+            SessionContext::with_config(SessionConfig::new().with_batch_size(2));
+        let stream = join.execute(0, session_ctx.task_ctx())?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn join_asof_greater_or_equal() -> Result<()> {
+        let batches = join_asof_collect(Operator::GtEq).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 10 | 2  | 1  | 10 | 20 |",
+            "| 1  | 15 | 3  | 1  | 12 | 30 |",
+            "| 1  | 5  | 1  | 1  | 4  | 10 |",
+            "| 2  | 5  | 4  |    |    |    |",
+            "| 3  | 5  | 5  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_asof_less() -> Result<()> {
+        let batches = join_asof_collect(Operator::Lt).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 10 | 2  | 1  | 12 | 30 |",
+            "| 1  | 15 | 3  |    |    |    |",
+            "| 1  | 5  | 1  | 1  | 10 | 20 |",
+            "| 2  | 5  | 4  | 2  | 6  | 40 |",
+            "| 3  | 5  | 5  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_asof_null_ordered_column() -> Result<()> {
+        let left = build_table_i32_nullable(
+            ("a1", &vec![Some(1), Some(1)]),
+            ("b1", &vec![Some(5), None]),
+            ("c1", &vec![Some(1), Some(2)]),
+        );
+        let right = build_table_i32_nullable(
+            ("a2", &vec![Some(1), Some(1)]),
+            ("b2", &vec![Some(3), None]),
+            ("c2", &vec![Some(10), Some(20)]),
+        );
+        let on = vec![(
+            Column::new_with_schema("a1", &left.schema())?,
+            Column::new_with_schema("a2", &right.schema())?,
+        )];
+        let condition = AsOfCondition {
+            left: Column::new_with_schema("b1", &left.schema())?,
+            op: Operator::GtEq,
+            right: Column::new_with_schema("b2", &right.schema())?,
+        };
+        let join =
+            join(left, right, on, JoinType::AsOf)?.with_asof_condition(condition)?;
+
+        let session_ctx = SessionContext::new();
+        let stream = join.execute(0, session_ctx.task_ctx())?;
+        let batches = common::collect(stream).await?;
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  |    | 2  |    |    |    |",
+            "| 1  | 5  | 1  | 1  | 3  | 10 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn join_asof_condition_requires_asof_join() -> Result<()> {
+        let left = build_table(("a1", &vec![1]), ("b1", &vec![1]), ("c1", &vec![1]));
+        let right = build_table(("a2", &vec![1]), ("b2", &vec![1]), ("c2", &vec![1]));
+        let on = vec![(
+            Column::new_with_schema("a1", &left.schema())?,
+            Column::new_with_schema("a2", &right.schema())?,
+        )];
+        let condition = AsOfCondition {
+            left: Column::new_with_schema("b1", &left.schema())?,
+            op: Operator::GtEq,
+            right: Column::new_with_schema("b2", &right.schema())?,
+        };
+
+        let err = join(left.clone(), right.clone(), on.clone(), JoinType::Left)?
+            .with_asof_condition(condition.clone())
+            .unwrap_err();
+        assert!(err.to_string().contains("Only ASOF joins"), "{}", err);
+
+        let err = join(left, right, on, JoinType::AsOf)?
+            .with_asof_condition(AsOfCondition {
+                op: Operator::Eq,
+                ..condition
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported operator"), "{}", err);
+        Ok(())
+    }
 }
@@ -18,7 +18,7 @@
 //! Defines the sort preserving merge plan
 
 use crate::physical_plan::metrics::{
-    ExecutionPlanMetricsSet, MemTrackingMetrics, MetricsSet,
+    BaselineMetrics, ExecutionPlanMetricsSet, MemTrackingMetrics, MetricsSet,
 };
 use log::debug;
 use parking_lot::Mutex;
@@ -41,8 +41,10 @@ use tokio::sync::mpsc;
 
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::TaskContext;
+use crate::physical_optimizer::eliminate_sorts::ordering_satisfies;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesStream;
 use crate::physical_plan::sorts::{RowIndex, SortKeyCursor, SortedStream};
-use crate::physical_plan::stream::RecordBatchReceiverStream;
+use crate::physical_plan::stream::{RecordBatchReceiverStream, RecordBatchStreamAdapter};
 use crate::physical_plan::{
     common::spawn_execution, expressions::PhysicalSortExpr, DisplayFormatType,
     Distribution, ExecutionPlan, Partitioning, PhysicalExpr, RecordBatchStream,
@@ -105,6 +107,18 @@ impl SortPreservingMergeExec {
     pub fn expr(&self) -> &[PhysicalSortExpr] {
         &self.expr
     }
+
+    /// Returns true if the input is range partitioned on a prefix of the sort
+    /// expressions, so that its sorted partitions are concatenated in order
+    /// rather than merged
+    pub fn concatenates_ranges(&self) -> bool {
+        match self.input.output_partitioning() {
+            Partitioning::Range(range_exprs, _) => {
+                ordering_satisfies(Some(&self.expr), &range_exprs)
+            }
+            _ => false,
+        }
+    }
 }
 
 impl ExecutionPlan for SortPreservingMergeExec {
@@ -164,8 +178,6 @@ impl ExecutionPlan for SortPreservingMergeExec {
             )));
         }
 
-        let tracking_metrics = MemTrackingMetrics::new(&self.metrics, partition);
-
         let input_partitions = self.input.output_partitioning().partition_count();
         debug!(
             "Number of input partitions of  SortPreservingMergeExec::execute: {}",
@@ -184,6 +196,41 @@ impl ExecutionPlan for SortPreservingMergeExec {
                 debug!("Done getting stream for SortPreservingMergeExec::execute with 1 input");
                 result
             }
+            _ if self.concatenates_ranges() => {
+                // sort all the partitions in parallel, if running from a tokio
+                // context, and output them one after the other in batches of
+                // `batch_size` rows
+                let streams = match tokio::runtime::Handle::try_current() {
+                    Ok(_) => (0..input_partitions)
+                        .map(|part_i| {
+                            let (sender, receiver) = mpsc::channel(1);
+                            let join_handle = spawn_execution(
+                                self.input.clone(),
+                                sender,
+                                part_i,
+                                context.clone(),
+                            );
+                            RecordBatchReceiverStream::create(
+                                &schema,
+                                receiver,
+                                join_handle,
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                    Err(_) => (0..input_partitions)
+                        .map(|partition| self.input.execute(partition, context.clone()))
+                        .collect::<Result<_>>()?,
+                };
+                let stream = Box::pin(RecordBatchStreamAdapter::new(
+                    schema,
+                    futures::stream::iter(streams).flatten(),
+                ));
+                Ok(Box::pin(CoalesceBatchesStream::new(
+                    stream,
+                    context.session_config().batch_size,
+                    BaselineMetrics::new(&self.metrics, partition),
+                )))
+            }
             _ => {
                 // Use tokio only if running from a tokio context (#2201)
                 let receivers = match tokio::runtime::Handle::try_current() {
@@ -219,6 +266,7 @@ impl ExecutionPlan for SortPreservingMergeExec {
 
                 debug!("Done setting up sender-receiver for SortPreservingMergeExec::execute");
 
+                let tracking_metrics = MemTrackingMetrics::new(&self.metrics, partition);
                 let result = Box::pin(SortPreservingMergeStream::new_from_streams(
                     receivers,
                     schema,
@@ -0,0 +1,59 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! This module contains functions and structs supporting user-defined window functions.
+
+use arrow::datatypes::Schema;
+
+use super::{type_coercion::coerce, PhysicalSortExpr, WindowExpr};
+use crate::error::Result;
+use crate::physical_plan::PhysicalExpr;
+use datafusion_expr::WindowFrame;
+pub use datafusion_expr::{PartitionEvaluator, WindowUDF};
+pub use datafusion_physical_expr::window::WindowUDFExpr;
+
+use std::sync::Arc;
+
+/// Creates a physical expression of the UDWF, that includes all necessary type coercion.
+/// This function errors when `args`' can't be coerced to a valid argument type of the UDWF.
+pub fn create_window_expr(
+    fun: &Arc<WindowUDF>,
+    input_phy_exprs: &[Arc<dyn PhysicalExpr>],
+    input_schema: &Schema,
+    name: impl Into<String>,
+    partition_by: &[Arc<dyn PhysicalExpr>],
+    order_by: &[PhysicalSortExpr],
+    window_frame: Option<WindowFrame>,
+) -> Result<Arc<dyn WindowExpr>> {
+    // coerce
+    let coerced_phy_exprs = coerce(input_phy_exprs, input_schema, &fun.signature)?;
+
+    let coerced_exprs_types = coerced_phy_exprs
+        .iter()
+        .map(|arg| arg.data_type(input_schema))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Arc::new(WindowUDFExpr::new(
+        fun.clone(),
+        coerced_phy_exprs,
+        name,
+        (fun.return_type)(&coerced_exprs_types)?.as_ref().clone(),
+        partition_by,
+        order_by,
+        window_frame,
+    )))
+}
@@ -0,0 +1,437 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! UnnestExec produces a row for each element of a list column of its input,
+//! repeating the values of the other columns.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{
+    Array, ArrayRef, FixedSizeListArray, Int64Builder, LargeListArray, ListArray,
+    UInt32Array, UInt32Builder, UInt64Array, UInt64Builder,
+};
+use arrow::compute::take;
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::record_batch::RecordBatch;
+use futures::stream::{Stream, StreamExt};
+use log::debug;
+
+use super::expressions::{Column, PhysicalSortExpr};
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::{
+    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use crate::error::Result;
+use crate::execution::context::TaskContext;
+use crate::logical_plan::UnnestOptions;
+
+/// Unnests a `List`, `LargeList` or `FixedSizeList` column of its input: each
+/// input row produces a row for each element of its list, in which the list
+/// is replaced by the element, optionally followed by the 1-based position of
+/// the element within the list.
+#[derive(Debug)]
+pub struct UnnestExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The list column to unnest
+    column: Column,
+    /// Options of the unnest
+    options: UnnestOptions,
+    /// The output schema
+    schema: SchemaRef,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl UnnestExec {
+    /// Create a new UnnestExec
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        column: Column,
+        options: UnnestOptions,
+        schema: SchemaRef,
+    ) -> Self {
+        Self {
+            input,
+            column,
+            options,
+            schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// The list column to unnest
+    pub fn column(&self) -> &Column {
+        &self.column
+    }
+
+    /// Options of the unnest
+    pub fn options(&self) -> &UnnestOptions {
+        &self.options
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+impl ExecutionPlan for UnnestExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(UnnestExec::new(
+            children[0].clone(),
+            self.column.clone(),
+            self.options.clone(),
+            self.schema.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!("Start UnnestExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        Ok(Box::pin(UnnestStream {
+            input: self.input.execute(partition, context)?,
+            schema: self.schema.clone(),
+            column: self.column.index(),
+            options: self.options.clone(),
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(f, "UnnestExec: {}", self.column)?;
+                if self.options.preserve_nulls {
+                    write!(f, ", preserve_nulls")?;
+                }
+                if let Some(ordinality) = &self.options.ordinality {
+                    write!(f, ", ordinality={}", ordinality)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+struct UnnestStream {
+    input: SendableRecordBatchStream,
+    schema: SchemaRef,
+    /// The index of the list column
+    column: usize,
+    options: UnnestOptions,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl Stream for UnnestStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let timer = self.baseline_metrics.elapsed_compute().timer();
+                let result =
+                    unnest_batch(&batch, self.column, &self.options, &self.schema);
+                timer.done();
+                Some(result)
+            }
+            other => other,
+        });
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for UnnestStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Unnests the list column `column` of `batch`
+fn unnest_batch(
+    batch: &RecordBatch,
+    column: usize,
+    options: &UnnestOptions,
+    schema: &SchemaRef,
+) -> ArrowResult<RecordBatch> {
+    let list = batch.column(column);
+    let (values, indices) = match list.data_type() {
+        DataType::List(_) => {
+            let list = list.as_any().downcast_ref::<ListArray>().unwrap();
+            let indices = UnnestIndices::try_new(list, options, |i| {
+                (
+                    list.value_offsets()[i] as usize,
+                    list.value_length(i) as usize,
+                )
+            })?;
+            (list.values(), indices)
+        }
+        DataType::LargeList(_) => {
+            let list = list.as_any().downcast_ref::<LargeListArray>().unwrap();
+            let indices = UnnestIndices::try_new(list, options, |i| {
+                (
+                    list.value_offsets()[i] as usize,
+                    list.value_length(i) as usize,
+                )
+            })?;
+            (list.values(), indices)
+        }
+        DataType::FixedSizeList(_, _) => {
+            let list = list.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+            let indices = UnnestIndices::try_new(list, options, |i| {
+                (list.value_offset(i) as usize, list.value_length() as usize)
+            })?;
+            (list.values(), indices)
+        }
+        other => {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Unnest expects a list, got {:?}",
+                other
+            )))
+        }
+    };
+
+    let mut columns = batch
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, array)| {
+            if i == column {
+                take(values.as_ref(), &indices.elements, None)
+            } else {
+                take(array.as_ref(), &indices.rows, None)
+            }
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+    if options.ordinality.is_some() {
+        columns.push(indices.ordinality);
+    }
+    RecordBatch::try_new(schema.clone(), columns)
+}
+
+/// The indices of the rows and the list elements making up the unnested
+/// output, along with the positions of the elements within their lists
+struct UnnestIndices {
+    rows: UInt32Array,
+    elements: UInt64Array,
+    ordinality: ArrayRef,
+}
+
+impl UnnestIndices {
+    /// Computes the indices of `list`, where `range` returns the offset and
+    /// length of the `i`th list within the list values
+    fn try_new(
+        list: &dyn Array,
+        options: &UnnestOptions,
+        range: impl Fn(usize) -> (usize, usize),
+    ) -> ArrowResult<Self> {
+        let capacity = list.len();
+        let mut rows = UInt32Builder::new(capacity);
+        let mut elements = UInt64Builder::new(capacity);
+        let mut ordinality = Int64Builder::new(capacity);
+        for i in 0..list.len() {
+            if list.is_null(i) {
+                if options.preserve_nulls {
+                    rows.append_value(i as u32)?;
+                    elements.append_null()?;
+                    ordinality.append_null()?;
+                }
+                continue;
+            }
+            let (offset, len) = range(i);
+            for j in 0..len {
+                rows.append_value(i as u32)?;
+                elements.append_value((offset + j) as u64)?;
+                ordinality.append_value(j as i64 + 1)?;
+            }
+        }
+        Ok(Self {
+            rows: rows.finish(),
+            elements: elements.finish(),
+            ordinality: Arc::new(ordinality.finish()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::prelude::SessionContext;
+    use arrow::array::{FixedSizeListBuilder, Int32Builder, StringArray};
+    use arrow::datatypes::{Field, Int32Type, Schema};
+
+    async fn unnest(list: ArrayRef, options: UnnestOptions) -> Result<Vec<RecordBatch>> {
+        let element = match list.data_type() {
+            DataType::List(field)
+            | DataType::LargeList(field)
+            | DataType::FixedSizeList(field, _) => field.data_type().clone(),
+            _ => unreachable!(),
+        };
+        let input_schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("list", list.data_type().clone(), true),
+        ]));
+        let mut fields = vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("list", element, true),
+        ];
+        if let Some(ordinality) = &options.ordinality {
+            fields.push(Field::new(ordinality, DataType::Int64, true));
+        }
+        let ids = StringArray::from(vec!["a", "b", "c", "d"]);
+        let batch =
+            RecordBatch::try_new(input_schema.clone(), vec![Arc::new(ids), list])?;
+        let input = MemoryExec::try_new(&[vec![batch]], input_schema, None)?;
+        let unnest = UnnestExec::new(
+            Arc::new(input),
+            Column::new("list", 1),
+            options,
+            Arc::new(Schema::new(fields)),
+        );
+        let session_ctx = SessionContext::new();
+        collect(Arc::new(unnest), session_ctx.task_ctx()).await
+    }
+
+    fn lists() -> Vec<Option<Vec<Option<i32>>>> {
+        vec![
+            Some(vec![Some(1), Some(2)]),
+            None,
+            Some(vec![]),
+            Some(vec![None, Some(3)]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn unnest_list() -> Result<()> {
+        let list = ListArray::from_iter_primitive::<Int32Type, _, _>(lists());
+        let batches = unnest(Arc::new(list), UnnestOptions::default()).await?;
+        let expected = vec![
+            "+----+------+",
+            "| id | list |",
+            "+----+------+",
+            "| a  | 1    |",
+            "| a  | 2    |",
+            "| d  |      |",
+            "| d  | 3    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unnest_large_list_preserving_nulls() -> Result<()> {
+        let list = LargeListArray::from_iter_primitive::<Int32Type, _, _>(lists());
+        let options = UnnestOptions::default()
+            .with_preserve_nulls(true)
+            .with_ordinality("n");
+        let batches = unnest(Arc::new(list), options).await?;
+        let expected = vec![
+            "+----+------+---+",
+            "| id | list | n |",
+            "+----+------+---+",
+            "| a  | 1    | 1 |",
+            "| a  | 2    | 2 |",
+            "| b  |      |   |",
+            "| d  |      | 1 |",
+            "| d  | 3    | 2 |",
+            "+----+------+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unnest_fixed_size_list() -> Result<()> {
+        let mut builder = FixedSizeListBuilder::new(Int32Builder::new(10), 2);
+        for (i, valid) in [true, true, false, true, true].into_iter().enumerate() {
+            builder.values().append_value(i as i32 * 2)?;
+            builder.values().append_value(i as i32 * 2 + 1)?;
+            builder.append(valid)?;
+        }
+        // unnest a slice to check the offset of the list is taken into account
+        let list = builder.finish().slice(1, 4);
+        let batches = unnest(list, UnnestOptions::default().with_ordinality("n")).await?;
+        let expected = vec![
+            "+----+------+---+",
+            "| id | list | n |",
+            "+----+------+---+",
+            "| a  | 2    | 1 |",
+            "| a  | 3    | 2 |",
+            "| c  | 6    | 1 |",
+            "| c  | 7    | 2 |",
+            "| d  | 8    | 1 |",
+            "| d  | 9    | 2 |",
+            "+----+------+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -25,7 +25,7 @@ use crate::physical_plan::{
         PhysicalSortExpr, RowNumber,
     },
     type_coercion::coerce,
-    PhysicalExpr,
+    udaf, udwf, PhysicalExpr,
 };
 use crate::scalar::ScalarValue;
 use arrow::datatypes::Schema;
@@ -66,6 +66,21 @@ pub fn create_window_expr(
             partition_by,
             order_by,
         )),
+        WindowFunction::AggregateUDF(fun) => Arc::new(AggregateWindowExpr::new(
+            udaf::create_aggregate_expr(fun.as_ref(), args, input_schema, name)?,
+            partition_by,
+            order_by,
+            window_frame,
+        )),
+        WindowFunction::WindowUDF(fun) => udwf::create_window_expr(
+            fun,
+            args,
+            input_schema,
+            name,
+            partition_by,
+            order_by,
+            window_frame,
+        )?,
     })
 }
 
@@ -154,9 +169,12 @@ fn create_built_in_window_expr(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::logical_plan::window_frames::{WindowFrameBound, WindowFrameUnits};
     use crate::physical_plan::aggregates::AggregateFunction;
     use crate::physical_plan::expressions::col;
     use crate::physical_plan::file_format::CsvExec;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::metrics::MetricValue;
     use crate::physical_plan::{collect, ExecutionPlan};
     use crate::prelude::SessionContext;
     use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
@@ -164,7 +182,7 @@ mod tests {
     use arrow::array::*;
     use arrow::datatypes::{DataType, Field, SchemaRef};
     use arrow::record_batch::RecordBatch;
-    use futures::FutureExt;
+    use futures::{FutureExt, StreamExt};
 
     fn create_test_schema(partitions: usize) -> Result<(Arc<CsvExec>, SchemaRef)> {
         let csv = test::scan_partitioned_csv(partitions)?;
@@ -234,6 +252,182 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn window_function_streams_partitions() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("p", DataType::Int32, false),
+            Field::new("v", DataType::Int32, false),
+        ]));
+        let batch = |p: &[i32], v: &[i32]| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(p.to_vec())),
+                    Arc::new(Int32Array::from(v.to_vec())),
+                ],
+            )
+        };
+        // the input is sorted by `p`, and its partitions span several batches
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![
+                batch(&[1, 1, 2], &[1, 2, 3])?,
+                batch(&[2, 2], &[4, 5])?,
+                batch(&[3], &[6])?,
+                batch(&[3, 4], &[7, 8])?,
+            ]],
+            schema.clone(),
+            None,
+        )?);
+
+        let window_exec = Arc::new(WindowAggExec::try_new(
+            vec![create_window_expr(
+                &WindowFunction::AggregateFunction(AggregateFunction::Sum),
+                "sum".to_owned(),
+                &[col("v", &schema)?],
+                &[col("p", &schema)?],
+                &[],
+                None,
+                schema.as_ref(),
+            )?],
+            input,
+            schema.clone(),
+        )?);
+
+        let result: Vec<RecordBatch> = collect(window_exec, task_ctx).await?;
+
+        // every partition is emitted as soon as the next one starts
+        let num_rows: Vec<usize> = result.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(num_rows, vec![2, 3, 2, 1]);
+
+        let sums: Vec<i64> = result
+            .iter()
+            .flat_map(|b| {
+                let sum: &Int64Array = as_primitive_array(b.column(0));
+                sum.values().to_vec()
+            })
+            .collect();
+        assert_eq!(sums, vec![3, 3, 12, 12, 12, 13, 13, 8]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn window_function_streams_within_partition() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Int64, false),
+            Field::new("v", DataType::Int64, false),
+        ]));
+        // the input is sorted by `ts`, whose peer groups of 4 rows span several batches
+        let sizes: [usize; 5] = [5, 7, 1, 12, 5];
+        let mut batches = vec![];
+        let mut offset: usize = 0;
+        for size in sizes {
+            let rows = offset as i64..(offset + size) as i64;
+            batches.push(RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_iter_values(rows.clone().map(|i| i / 4))),
+                    Arc::new(Int64Array::from_iter_values(rows)),
+                ],
+            )?);
+            offset += size;
+        }
+        let window_exec = |window_frame| -> Result<Arc<WindowAggExec>> {
+            let input = Arc::new(MemoryExec::try_new(
+                &[batches.clone()],
+                schema.clone(),
+                None,
+            )?);
+            Ok(Arc::new(WindowAggExec::try_new(
+                vec![create_window_expr(
+                    &WindowFunction::AggregateFunction(AggregateFunction::Sum),
+                    "sum".to_owned(),
+                    &[col("v", &schema)?],
+                    &[],
+                    &[PhysicalSortExpr {
+                        expr: col("ts", &schema)?,
+                        options: Default::default(),
+                    }],
+                    window_frame,
+                    schema.as_ref(),
+                )?],
+                input,
+                schema.clone(),
+            )?))
+        };
+        let num_rows = |result: &[RecordBatch]| -> Vec<usize> {
+            result.iter().map(|b| b.num_rows()).collect()
+        };
+        let sums = |result: &[RecordBatch]| -> Vec<i64> {
+            result
+                .iter()
+                .flat_map(|b| {
+                    let sum: &Int64Array = as_primitive_array(b.column(0));
+                    sum.values().to_vec()
+                })
+                .collect()
+        };
+
+        // SUM(v) OVER (ORDER BY ts ROWS 10 PRECEDING): every row is final once received
+        let rows_frame = || {
+            WindowFrame::try_new(
+                WindowFrameUnits::Rows,
+                WindowFrameBound::Preceding(Some(ScalarValue::UInt64(Some(10)))),
+                WindowFrameBound::CurrentRow,
+            )
+        };
+        let result = collect(window_exec(Some(rows_frame()?))?, task_ctx.clone()).await?;
+        assert_eq!(num_rows(&result), sizes);
+        let expected: Vec<i64> = (0..30).map(|i| (i.max(10) - 10..=i).sum()).collect();
+        assert_eq!(sums(&result), expected);
+
+        // SUM(v) OVER (ORDER BY ts): a row is final once its peer group ends, and the
+        // rows of the previous peer groups are summarized by the state of the sum
+        let result = collect(window_exec(None)?, task_ctx.clone()).await?;
+        assert_eq!(num_rows(&result), vec![4, 4, 4, 12, 4, 2]);
+        let expected: Vec<i64> = (0..30)
+            .map(|i: i64| (0..((i / 4 + 1) * 4).min(30)).sum())
+            .collect();
+        assert_eq!(sums(&result), expected);
+
+        // the memory of the buffered batches is released as their rows leave the
+        // frames, while the stream is consumed
+        let window_exec = window_exec(Some(rows_frame()?))?;
+        let mem_used = || {
+            window_exec
+                .metrics()
+                .unwrap()
+                .sum(|m| matches!(m.value(), MetricValue::CurrentMemoryUsage(_)))
+                .map(|v| v.as_usize())
+                .unwrap_or_default()
+        };
+        let mut stream = window_exec.execute(0, task_ctx)?;
+        let mut mem_used_per_batch = vec![];
+        while let Some(batch) = stream.next().await {
+            batch?;
+            mem_used_per_batch.push(mem_used());
+        }
+        let max_mem_used = mem_used_per_batch.iter().copied().max().unwrap();
+        assert!(max_mem_used > 0);
+        let max_index = mem_used_per_batch
+            .iter()
+            .position(|m| *m == max_mem_used)
+            .unwrap();
+        assert!(
+            mem_used_per_batch[max_index..]
+                .iter()
+                .any(|m| *m < max_mem_used),
+            "memory was not released while streaming: {:?}",
+            mem_used_per_batch
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_drop_cancel() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -19,14 +19,16 @@
 
 use crate::error::Result;
 use crate::execution::context::TaskContext;
+use crate::physical_plan::common::batch_byte_size;
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{
-    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
+    ExecutionPlanMetricsSet, MemTrackingMetrics, MetricsSet,
 };
 use crate::physical_plan::{
     common, ColumnStatistics, DisplayFormatType, Distribution, ExecutionPlan,
     Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics, WindowExpr,
 };
+use crate::scalar::ScalarValue;
 use arrow::{
     array::ArrayRef,
     datatypes::{Schema, SchemaRef},
@@ -36,6 +38,7 @@ use arrow::{
 use futures::stream::Stream;
 use futures::{ready, StreamExt};
 use std::any::Any;
+use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -151,12 +154,17 @@ impl ExecutionPlan for WindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
+        let tracking_metrics = MemTrackingMetrics::new_with_rt(
+            &self.metrics,
+            partition,
+            context.runtime_env(),
+        );
         let input = self.input.execute(partition, context)?;
         let stream = Box::pin(WindowAggStream::new(
             self.schema.clone(),
             self.window_expr.clone(),
             input,
-            BaselineMetrics::new(&self.metrics, partition),
+            tracking_metrics,
         ));
         Ok(stream)
     }
@@ -217,25 +225,63 @@ fn create_schema(
     Ok(Schema::new(fields))
 }
 
-/// Compute the window aggregate columns
+/// Compute the window aggregate columns, each continuing the rows summarized by its state
 fn compute_window_aggregates(
     window_expr: &[Arc<dyn WindowExpr>],
+    states: &[Vec<ScalarValue>],
     batch: &RecordBatch,
 ) -> Result<Vec<ArrayRef>> {
     window_expr
         .iter()
-        .map(|window_expr| window_expr.evaluate(batch))
+        .zip(states)
+        .map(|(window_expr, state)| window_expr.evaluate_with_state(batch, state))
+        .collect()
+}
+
+/// The partition of `partitions` that contains `row`, or the last one if `row` follows
+/// them
+fn partition_of(partitions: &[Range<usize>], row: usize) -> &Range<usize> {
+    let index = partitions.partition_point(|partition| partition.end <= row);
+    &partitions[index.min(partitions.len() - 1)]
+}
+
+/// The values of the partition keys of `window_expr` for `row` of `batch`
+fn partition_key(
+    window_expr: &Arc<dyn WindowExpr>,
+    batch: &RecordBatch,
+    row: usize,
+) -> Result<Vec<ScalarValue>> {
+    window_expr
+        .partition_columns(&batch.slice(row, 1))?
+        .iter()
+        .map(|column| ScalarValue::try_from_array(&column.values, 0))
         .collect()
 }
 
 /// stream for window aggregation plan
+///
+/// As the input is sorted by the partition and order by keys, the rows are evaluated as
+/// soon as their results are final, which is once their partition ends, or for the window
+/// expressions that stream within partitions once the rows their results depend on are
+/// known. Only the rows which are still needed to evaluate the following ones are kept.
 pub struct WindowAggStream {
     schema: SchemaRef,
     input: SendableRecordBatchStream,
+    /// the buffered input rows: the rows which have not been emitted yet, preceded by the
+    /// emitted rows which are still needed to evaluate them
     batches: Vec<RecordBatch>,
+    /// the memory used by `batches`
+    batches_mem_used: usize,
+    /// the number of leading buffered rows which have already been emitted
+    emitted_rows: usize,
+    /// for each window expression, the state summarizing the rows of the first buffered
+    /// partition which are no longer buffered
+    states: Vec<Vec<ScalarValue>>,
+    /// whether any output batch has been produced
+    emitted: bool,
     finished: bool,
     window_expr: Vec<Arc<dyn WindowExpr>>,
-    baseline_metrics: BaselineMetrics,
+    tracking_metrics: MemTrackingMetrics,
 }
 
 impl WindowAggStream {
@@ -244,36 +290,171 @@ impl WindowAggStream {
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
         input: SendableRecordBatchStream,
-        baseline_metrics: BaselineMetrics,
+        tracking_metrics: MemTrackingMetrics,
     ) -> Self {
         Self {
             schema,
             input,
             batches: vec![],
+            batches_mem_used: 0,
+            emitted_rows: 0,
+            states: vec![vec![]; window_expr.len()],
+            emitted: false,
             finished: false,
             window_expr,
-            baseline_metrics,
+            tracking_metrics,
         }
     }
 
-    fn compute_aggregates(&self) -> ArrowResult<RecordBatch> {
-        // record compute time on drop
-        let _timer = self.baseline_metrics.elapsed_compute().timer();
+    /// Replace the buffered rows, keeping the memory manager up to date
+    fn set_batches(&mut self, batches: Vec<RecordBatch>) {
+        let mem_used = batches.iter().map(batch_byte_size).sum();
+        if mem_used > self.batches_mem_used {
+            self.tracking_metrics
+                .grow_mem_used(mem_used - self.batches_mem_used);
+        } else {
+            self.tracking_metrics
+                .shrink_mem_used(self.batches_mem_used - mem_used);
+        }
+        self.batches = batches;
+        self.batches_mem_used = mem_used;
+    }
 
+    /// Concatenate the buffered rows into a single batch
+    fn combine_batches(&mut self) -> Result<Option<RecordBatch>> {
         let batch = common::combine_batches(&self.batches, self.input.schema())?;
-        if let Some(batch) = batch {
-            // calculate window cols
-            let mut columns = compute_window_aggregates(&self.window_expr, &batch)
-                .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
-
-            // combine with the original cols
-            // note the setup of window aggregates is that they newly calculated window
-            // expressions are always prepended to the columns
-            columns.extend_from_slice(batch.columns());
-            RecordBatch::try_new(self.schema.clone(), columns)
-        } else {
-            Ok(RecordBatch::new_empty(self.schema.clone()))
+        if self.batches.len() > 1 {
+            self.set_batches(batch.iter().cloned().collect());
         }
+        Ok(batch)
+    }
+
+    /// Buffer a new input batch, and return the evaluation of the rows whose results
+    /// became final if any
+    fn push_batch(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        let may_complete_rows = self.may_complete_rows(&batch)?;
+
+        let mem_used = batch_byte_size(&batch);
+        self.tracking_metrics.grow_mem_used(mem_used);
+        self.batches_mem_used += mem_used;
+        self.batches.push(batch);
+        if !may_complete_rows {
+            return Ok(None);
+        }
+
+        let batch = match self.combine_batches()? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+        let num_rows = batch.num_rows();
+        let partitions = self
+            .window_expr
+            .iter()
+            .map(|window_expr| {
+                window_expr.evaluate_partition_points(
+                    num_rows,
+                    &window_expr.partition_columns(&batch)?,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // the rows whose results are final for every window expression, given that
+        // the last partitions may continue after the batch
+        let mut end = num_rows;
+        for (window_expr, partitions) in self.window_expr.iter().zip(&partitions) {
+            let last = partitions.last().expect("partitions of a non empty batch");
+            let final_rows_end = if window_expr.streams_within_partition() {
+                window_expr.final_rows_end(&batch, last)?
+            } else {
+                last.start
+            };
+            end = end.min(final_rows_end);
+        }
+        if end <= self.emitted_rows {
+            return Ok(None);
+        }
+        let output = self
+            .compute_aggregates(&batch)?
+            .slice(self.emitted_rows, end - self.emitted_rows);
+
+        // drop the rows which are no longer needed, keeping at least the last one to
+        // know which partition the following rows continue
+        let mut start = end.min(num_rows - 1);
+        for (window_expr, partitions) in self.window_expr.iter().zip(&partitions) {
+            let partition = partition_of(partitions, end);
+            start = start.min(window_expr.required_rows_start(&batch, partition, end)?);
+        }
+        for ((window_expr, partitions), state) in self
+            .window_expr
+            .iter()
+            .zip(&partitions)
+            .zip(self.states.iter_mut())
+        {
+            let partition = partition_of(partitions, start);
+            *state = window_expr.dropped_rows_state(
+                &batch,
+                partition,
+                start,
+                std::mem::take(state),
+            )?;
+        }
+        self.set_batches(vec![batch.slice(start, num_rows - start)]);
+        self.emitted_rows = end - start;
+
+        Ok(Some(output))
+    }
+
+    /// Whether `batch` may make the results of buffered rows final: for the window
+    /// expressions which need their whole partitions, this is only the case if it ends
+    /// their last buffered partition. As the input is sorted by the partition keys, this
+    /// is known by comparing the keys of the last rows before and in `batch`.
+    fn may_complete_rows(&self, batch: &RecordBatch) -> Result<bool> {
+        let (previous, row) = match self.batches.last() {
+            Some(last) => (last, last.num_rows() - 1),
+            None => (batch, 0),
+        };
+        for window_expr in &self.window_expr {
+            if !window_expr.streams_within_partition()
+                && partition_key(window_expr, previous, row)?
+                    == partition_key(window_expr, batch, batch.num_rows() - 1)?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn compute_aggregates(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        // record compute time on drop
+        let _timer = self.tracking_metrics.elapsed_compute().timer();
+
+        // calculate window cols
+        let mut columns =
+            compute_window_aggregates(&self.window_expr, &self.states, batch)?;
+
+        // combine with the original cols
+        // note the setup of window aggregates is that they newly calculated window
+        // expressions are always prepended to the columns
+        columns.extend_from_slice(batch.columns());
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    /// Evaluate the remaining buffered rows once the input is exhausted
+    fn compute_remaining(&mut self) -> Result<Option<RecordBatch>> {
+        let batch = self.combine_batches()?;
+        let output = match batch {
+            Some(batch) if batch.num_rows() > self.emitted_rows => {
+                let output = self.compute_aggregates(&batch)?;
+                Some(
+                    output
+                        .slice(self.emitted_rows, output.num_rows() - self.emitted_rows),
+                )
+            }
+            _ if self.emitted => None,
+            _ => Some(RecordBatch::new_empty(self.schema.clone())),
+        };
+        self.set_batches(vec![]);
+        Ok(output)
     }
 }
 
@@ -285,7 +466,7 @@ impl Stream for WindowAggStream {
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         let poll = self.poll_next_inner(cx);
-        self.baseline_metrics.record_poll(poll)
+        self.tracking_metrics.record_poll(poll)
     }
 }
 
@@ -301,17 +482,29 @@ impl WindowAggStream {
 
         loop {
             let result = match ready!(self.input.poll_next_unpin(cx)) {
-                Some(Ok(batch)) => {
-                    self.batches.push(batch);
-                    continue;
+                Some(Ok(batch)) if batch.num_rows() == 0 => continue,
+                Some(Ok(batch)) => match self.push_batch(batch) {
+                    Ok(Some(batch)) => {
+                        self.emitted = true;
+                        return Poll::Ready(Some(Ok(batch)));
+                    }
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                },
+                Some(Err(e)) => {
+                    self.finished = true;
+                    return Poll::Ready(Some(Err(e)));
                 }
-                Some(Err(e)) => Err(e),
-                None => self.compute_aggregates(),
+                None => self.compute_remaining(),
             };
 
             self.finished = true;
 
-            return Poll::Ready(Some(result));
+            return match result {
+                Ok(Some(batch)) => Poll::Ready(Some(Ok(batch))),
+                Ok(None) => Poll::Ready(None),
+                Err(e) => Poll::Ready(Some(Err(ArrowError::ExternalError(Box::new(e))))),
+            };
         }
     }
 }
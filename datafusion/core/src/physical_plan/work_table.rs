@@ -0,0 +1,181 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! The work table of a recursive query, holding the rows produced by the
+//! previous iteration of its recursive term.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use log::debug;
+use parking_lot::Mutex;
+
+use super::expressions::PhysicalSortExpr;
+use super::memory::MemoryStream;
+use super::{
+    DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+
+/// The rows produced by an iteration of a recursive query, shared between a
+/// [`RecursiveQueryExec`](super::recursive_query::RecursiveQueryExec) and the
+/// [`WorkTableExec`]s reading them in its recursive term
+#[derive(Debug, Default)]
+pub struct WorkTable {
+    batches: Mutex<Option<Vec<RecordBatch>>>,
+}
+
+impl WorkTable {
+    /// Create a new, unpopulated, work table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the rows of the work table by `batches`
+    pub fn update(&self, batches: Vec<RecordBatch>) {
+        *self.batches.lock() = Some(batches);
+    }
+
+    /// The rows of the work table
+    pub fn batches(&self) -> Result<Vec<RecordBatch>> {
+        self.batches.lock().clone().ok_or_else(|| {
+            DataFusionError::Execution(
+                "Work table read before the static term of its recursive query completed"
+                    .to_string(),
+            )
+        })
+    }
+}
+
+/// Scans the [`WorkTable`] of a recursive query from within its recursive
+/// term, producing the rows of the previous iteration
+#[derive(Debug)]
+pub struct WorkTableExec {
+    /// The name of the recursive query
+    name: String,
+    /// The projected schema
+    schema: SchemaRef,
+    /// Optional projection of the work table columns
+    projection: Option<Vec<usize>>,
+    /// The work table, assigned by the enclosing recursive query
+    work_table: Option<Arc<WorkTable>>,
+}
+
+impl WorkTableExec {
+    /// Create a new WorkTableExec reading the work table of the recursive
+    /// query `name`, which is assigned with [`Self::with_work_table`]
+    pub fn new(name: String, schema: SchemaRef, projection: Option<Vec<usize>>) -> Self {
+        Self {
+            name,
+            schema,
+            projection,
+            work_table: None,
+        }
+    }
+
+    /// Returns this scan reading `work_table`
+    pub fn with_work_table(&self, work_table: Arc<WorkTable>) -> Self {
+        Self {
+            name: self.name.clone(),
+            schema: self.schema.clone(),
+            projection: self.projection.clone(),
+            work_table: Some(work_table),
+        }
+    }
+
+    /// The name of the recursive query
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the work table was assigned
+    pub fn has_work_table(&self) -> bool {
+        self.work_table.is_some()
+    }
+}
+
+impl ExecutionPlan for WorkTableExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!("Start WorkTableExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "WorkTableExec invalid partition {}",
+                partition
+            )));
+        }
+        let work_table = self.work_table.as_ref().ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "Work table {} can only be read from the recursive term of its query",
+                self.name
+            ))
+        })?;
+        Ok(Box::pin(MemoryStream::try_new(
+            work_table.batches()?,
+            self.schema.clone(),
+            self.projection.clone(),
+        )?))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(f, "WorkTableExec: name={}", self.name)
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
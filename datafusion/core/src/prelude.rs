@@ -28,7 +28,8 @@
 pub use crate::dataframe::DataFrame;
 pub use crate::execution::context::{SessionConfig, SessionContext};
 pub use crate::execution::options::{
-    AvroReadOptions, CsvReadOptions, NdJsonReadOptions, ParquetReadOptions,
+    ArrowReadOptions, AvroReadOptions, CsvReadOptions, NdJsonReadOptions,
+    ParquetReadOptions,
 };
 pub use crate::logical_plan::{
     approx_percentile_cont, array, ascii, avg, bit_length, btrim, character_length, chr,
@@ -43,6 +43,16 @@
 //! Queries scheduled on a [`Scheduler`] will run to completion even if the
 //! [`Scheduler`] is dropped
 //!
+//! # Cancellation and Limits
+//!
+//! A query can be cancelled by dropping its [`ExecutionResults`], or by passing a
+//! [`CancellationToken`] in the [`QueryOptions`] given to
+//! [`Scheduler::schedule_with_options`]. [`QueryOptions`] can additionally limit
+//! the number of worker threads a query may occupy, and the wall-clock time it may
+//! run for. Both cancellation and the timeout are checked between morsels, and
+//! the timeout also fires from a timer thread of the [`Scheduler`] while the
+//! query's output is awaited, whichever runtime, if any, polls it.
+//!
 //! [Morsel-Driven Parallelism]: https://db.in.tum.de/~leis/papers/morsels.pdf
 //! [rayon]: https://docs.rs/rayon/latest/rayon/
 //!
@@ -84,14 +94,18 @@ use crate::physical_plan::ExecutionPlan;
 
 use plan::{PipelinePlan, PipelinePlanner, RoutablePipeline};
 use task::{spawn_plan, Task};
+use timer::DeadlineTimer;
 
 use rayon::{ThreadPool, ThreadPoolBuilder};
 
+pub use options::{CancellationToken, QueryOptions};
 pub use task::ExecutionResults;
 
+mod options;
 mod pipeline;
 mod plan;
 mod task;
+mod timer;
 
 /// Builder for a [`Scheduler`]
 #[derive(Debug)]
@@ -125,6 +139,7 @@ impl SchedulerBuilder {
     fn build(self) -> Scheduler {
         Scheduler {
             pool: Arc::new(self.inner.build().unwrap()),
+            timer: DeadlineTimer::default(),
         }
     }
 }
@@ -132,6 +147,9 @@ impl SchedulerBuilder {
 /// A [`Scheduler`] that can be used to schedule [`ExecutionPlan`] on a dedicated thread pool
 pub struct Scheduler {
     pool: Arc<ThreadPool>,
+
+    /// Aborts queries whose timeout has elapsed
+    timer: DeadlineTimer,
 }
 
 impl Scheduler {
@@ -148,19 +166,38 @@ impl Scheduler {
         &self,
         plan: Arc<dyn ExecutionPlan>,
         context: Arc<TaskContext>,
+    ) -> Result<ExecutionResults> {
+        self.schedule_with_options(plan, context, QueryOptions::default())
+    }
+
+    /// Schedule the provided [`ExecutionPlan`] on this [`Scheduler`] subject to
+    /// the cancellation and resource limits specified in `options`.
+    ///
+    /// Returns a [`ExecutionResults`] that can be used to receive results as they are produced,
+    /// as a [`futures::Stream`] of [`RecordBatch`]
+    pub fn schedule_with_options(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        context: Arc<TaskContext>,
+        options: QueryOptions,
     ) -> Result<ExecutionResults> {
         let plan = PipelinePlanner::new(plan, context).build()?;
-        Ok(self.schedule_plan(plan))
+        Ok(self.schedule_plan(plan, options))
     }
 
-    /// Schedule the provided [`PipelinePlan`] on this [`Scheduler`].
-    pub(crate) fn schedule_plan(&self, plan: PipelinePlan) -> ExecutionResults {
-        spawn_plan(plan, self.spawner())
+    /// Schedule the provided [`PipelinePlan`] on this [`Scheduler`] subject to `options`
+    pub(crate) fn schedule_plan(
+        &self,
+        plan: PipelinePlan,
+        options: QueryOptions,
+    ) -> ExecutionResults {
+        spawn_plan(plan, self.spawner(), options)
     }
 
     fn spawner(&self) -> Spawner {
         Spawner {
             pool: self.pool.clone(),
+            timer: self.timer.clone(),
         }
     }
 }
@@ -223,6 +260,7 @@ fn spawn_local_fifo(task: Task) {
 #[derive(Debug, Clone)]
 pub struct Spawner {
     pool: Arc<ThreadPool>,
+    timer: DeadlineTimer,
 }
 
 impl Spawner {
@@ -0,0 +1,144 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+type CancelCallback = Box<dyn FnOnce() + Send>;
+
+/// A [`CancellationToken`] can be used to cancel a query scheduled on a
+/// [`Scheduler`](super::Scheduler)
+///
+/// Cloning a [`CancellationToken`] returns a handle to the same underlying token,
+/// and so cancelling any clone cancels all queries the token was passed to
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationTokenInner>,
+}
+
+#[derive(Default)]
+struct CancellationTokenInner {
+    cancelled: AtomicBool,
+    callbacks: Mutex<Vec<CancelCallback>>,
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl CancellationToken {
+    /// Create a new [`CancellationToken`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel all queries associated with this token
+    ///
+    /// Any task of these queries that has not yet started executing its next
+    /// morsel will not be run, and the query's output streams will return an error
+    pub fn cancel(&self) {
+        if self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        // Take the callbacks before invoking them so that none are run
+        // whilst holding the lock
+        let callbacks = std::mem::take(&mut *self.inner.callbacks.lock());
+        for callback in callbacks {
+            callback()
+        }
+    }
+
+    /// Returns `true` if [`Self::cancel`] has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Registers a callback to be invoked when this token is cancelled
+    ///
+    /// If the token has already been cancelled, `callback` is invoked immediately
+    pub(crate) fn on_cancel(&self, callback: impl FnOnce() + Send + 'static) {
+        let mut callbacks = self.inner.callbacks.lock();
+        if self.is_cancelled() {
+            drop(callbacks);
+            callback();
+            return;
+        }
+        callbacks.push(Box::new(callback));
+    }
+}
+
+/// Per-query options for [`Scheduler::schedule_with_options`]
+///
+/// [`Scheduler::schedule_with_options`]: super::Scheduler::schedule_with_options
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// Token that can be used to cancel the query
+    pub cancellation: Option<CancellationToken>,
+
+    /// The maximum number of worker threads that may concurrently
+    /// execute tasks belonging to the query
+    pub max_threads: Option<usize>,
+
+    /// The maximum wall-clock time the query may run for, after which
+    /// it fails with [`DataFusionError::Execution`]
+    ///
+    /// [`DataFusionError::Execution`]: crate::error::DataFusionError::Execution
+    pub timeout: Option<Duration>,
+}
+
+impl QueryOptions {
+    /// Create a new [`QueryOptions`] with no limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the query when `token` is cancelled
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Limit the number of worker threads that may concurrently execute the query
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_threads` is zero
+    pub fn with_max_threads(mut self, max_threads: usize) -> Self {
+        assert!(max_threads > 0, "max_threads must be greater than 0");
+        self.max_threads = Some(max_threads);
+        self
+    }
+
+    /// Fail the query if it has not completed within `timeout`
+    ///
+    /// The timeout is checked between morsels, and by a timer thread of the
+    /// [`Scheduler`](super::Scheduler) that fails the query's output at its
+    /// deadline, so no particular runtime is needed to poll the output. The
+    /// query's tasks may overrun by up to the time taken to process a single morsel
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
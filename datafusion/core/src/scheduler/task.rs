@@ -19,8 +19,8 @@ use crate::error::{DataFusionError, Result};
 use crate::physical_plan::stream::RecordBatchStreamAdapter;
 use crate::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
 use crate::scheduler::{
-    is_worker, plan::PipelinePlan, spawn_local, spawn_local_fifo, RoutablePipeline,
-    Spawner,
+    is_worker, plan::PipelinePlan, spawn_local, spawn_local_fifo, QueryOptions,
+    RoutablePipeline, Spawner,
 };
 use arrow::datatypes::SchemaRef;
 use arrow::error::ArrowError;
@@ -29,13 +29,21 @@ use futures::channel::mpsc;
 use futures::task::ArcWake;
 use futures::{ready, Stream, StreamExt};
 use log::{debug, trace};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
-/// Spawns a [`PipelinePlan`] using the provided [`Spawner`]
-pub fn spawn_plan(plan: PipelinePlan, spawner: Spawner) -> ExecutionResults {
+/// Spawns a [`PipelinePlan`] using the provided [`Spawner`], subject
+/// to the limits specified in the provided [`QueryOptions`]
+pub fn spawn_plan(
+    plan: PipelinePlan,
+    spawner: Spawner,
+    options: QueryOptions,
+) -> ExecutionResults {
     debug!("Spawning pipeline plan: {:#?}", plan);
 
     let (senders, receivers) = (0..plan.output_partitions)
@@ -47,8 +55,31 @@ pub fn spawn_plan(plan: PipelinePlan, spawner: Spawner) -> ExecutionResults {
         pipelines: plan.pipelines,
         schema: plan.schema,
         output: senders,
+        aborted: AtomicBool::new(false),
+        deadline: options
+            .timeout
+            .map(|timeout| (Instant::now() + timeout, timeout)),
+        worker_limit: options.max_threads.map(WorkerLimit::new),
     });
 
+    if let Some(token) = &options.cancellation {
+        let weak = Arc::downgrade(&context);
+        token.on_cancel(move || {
+            if let Some(context) = weak.upgrade() {
+                context.abort("Query cancelled".to_string())
+            }
+        });
+    }
+
+    if let Some((deadline, timeout)) = context.deadline {
+        let weak = Arc::downgrade(&context);
+        context.spawner.timer.on_deadline(deadline, move || {
+            if let Some(context) = weak.upgrade() {
+                context.abort(timeout_message(timeout))
+            }
+        });
+    }
+
     for (pipeline_idx, query_pipeline) in context.pipelines.iter().enumerate() {
         for partition in 0..query_pipeline.pipeline.output_partitions() {
             context.spawner.spawn(Task {
@@ -123,12 +154,39 @@ impl Task {
     }
 
     /// Call [`Pipeline::poll_partition`], attempting to make progress on query execution
+    ///
+    /// If the query has a worker limit, and it is currently occupying the maximum
+    /// number of worker threads, the [`Task`] is instead queued until another
+    /// [`Task`] of the same query finishes executing
     pub fn do_work(self) {
         assert!(is_worker(), "Task::do_work called outside of worker pool");
         if self.context.is_cancelled() {
+            // Drop any queued tasks to avoid a reference cycle
+            self.context.clear_queued();
+            return;
+        }
+
+        if let Some(error) = self.context.check_deadline() {
+            self.context.abort(error);
             return;
         }
 
+        let context = self.context.clone();
+        match &context.worker_limit {
+            Some(limit) => {
+                if let Some(task) = limit.acquire(self) {
+                    task.poll();
+                    if let Some(next) = limit.release() {
+                        spawn_local(next);
+                    }
+                }
+            }
+            None => self.poll(),
+        }
+    }
+
+    /// Performs a single call to [`Pipeline::poll_partition`] and routes its output
+    fn poll(self) {
         // Capture the wake count prior to calling [`Pipeline::poll_partition`]
         // this allows us to detect concurrent wake ups and handle them correctly
         let wake_count = self.waker.wake_count.load(Ordering::SeqCst);
@@ -260,6 +318,12 @@ impl Stream for ExecutionResultStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        // The scheduler's timer aborts the query at its deadline, which wakes this
+        // stream, but the timer thread may not have run yet when the stream is polled
+        if let Some(message) = self.context.check_deadline() {
+            self.context.abort(message);
+        }
+
         let opt = ready!(self.receiver.poll_next_unpin(cx)).flatten();
         Poll::Ready(opt.map(|r| r.map_err(|e| ArrowError::ExternalError(Box::new(e)))))
     }
@@ -286,6 +350,16 @@ struct ExecutionContext {
 
     /// The output streams, per partition, for this query's execution
     output: Vec<mpsc::UnboundedSender<Option<Result<RecordBatch>>>>,
+
+    /// Set once this query has been aborted by [`ExecutionContext::abort`]
+    aborted: AtomicBool,
+
+    /// The instant after which this query should fail, along with the
+    /// timeout it was computed from
+    deadline: Option<(Instant, Duration)>,
+
+    /// Limits the number of workers concurrently executing this query
+    worker_limit: Option<WorkerLimit>,
 }
 
 impl Drop for ExecutionContext {
@@ -301,6 +375,38 @@ impl ExecutionContext {
         self.output.iter().all(|x| x.is_closed())
     }
 
+    /// Returns an error message if this query has exceeded its deadline
+    fn check_deadline(&self) -> Option<String> {
+        let (deadline, timeout) = self.deadline?;
+        (Instant::now() >= deadline).then(|| timeout_message(timeout))
+    }
+
+    /// Aborts this query, returning an error containing `message` from each of the
+    /// output partitions, and closing the output streams so that no further tasks
+    /// for this query are run
+    fn abort(&self, message: String) {
+        if self.aborted.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        debug!("Aborting query: {}", message);
+        for output in &self.output {
+            let error = DataFusionError::Execution(message.clone());
+            let _ = output.unbounded_send(Some(Err(error)));
+            output.close_channel();
+        }
+        self.clear_queued();
+    }
+
+    /// Drops any tasks queued by this query's [`WorkerLimit`]
+    fn clear_queued(&self) {
+        if let Some(limit) = &self.worker_limit {
+            // Drop outside of the lock
+            let queued = std::mem::take(&mut limit.state.lock().queued);
+            drop(queued)
+        }
+    }
+
     /// Sends `output` to this query's output stream
     fn send_query_output(&self, partition: usize, output: Result<RecordBatch>) {
         let _ = self.output[partition].unbounded_send(Some(output));
@@ -312,6 +418,63 @@ impl ExecutionContext {
     }
 }
 
+/// Returns the error message of a query that exceeded its `timeout`
+fn timeout_message(timeout: Duration) -> String {
+    format!("Query exceeded timeout of {:?}", timeout)
+}
+
+/// Limits the number of [`Task`] of a given query that may execute concurrently
+///
+/// [`Task`] that would exceed this limit are queued, and are rescheduled as other
+/// [`Task`] of the same query finish executing
+#[derive(Debug)]
+struct WorkerLimit {
+    /// The maximum number of [`Task`] that may execute concurrently
+    max_workers: usize,
+
+    state: Mutex<WorkerLimitState>,
+}
+
+#[derive(Debug, Default)]
+struct WorkerLimitState {
+    /// The number of [`Task`] currently executing
+    running: usize,
+
+    /// [`Task`] waiting for a running [`Task`] to finish
+    queued: VecDeque<Task>,
+}
+
+impl WorkerLimit {
+    fn new(max_workers: usize) -> Self {
+        Self {
+            max_workers,
+            state: Default::default(),
+        }
+    }
+
+    /// Returns `task` if it may execute, otherwise queues it to be
+    /// returned by a subsequent call to [`WorkerLimit::release`]
+    fn acquire(&self, task: Task) -> Option<Task> {
+        let mut state = self.state.lock();
+        if state.running < self.max_workers {
+            state.running += 1;
+            return Some(task);
+        }
+
+        trace!("Worker limit reached, queueing {:?}", task);
+        state.queued.push_back(task);
+        None
+    }
+
+    /// Signals that a [`Task`] returned by [`WorkerLimit::acquire`] has finished
+    /// executing, returning a queued [`Task`] that should be rescheduled if any
+    fn release(&self) -> Option<Task> {
+        let mut state = self.state.lock();
+        state.running -= 1;
+        state.queued.pop_front()
+    }
+}
+
 struct TaskWaker {
     /// Store a weak reference to the [`ExecutionContext`] to avoid reference cycles if this
     /// [`Waker`] is stored within a [`Pipeline`] owned by the [`ExecutionContext`]
@@ -375,15 +538,123 @@ impl ArcWake for TaskWaker {
 mod tests {
     use super::*;
     use crate::error::Result;
-    use crate::scheduler::{pipeline::Pipeline, plan::RoutablePipeline, Scheduler};
+    use crate::scheduler::{
+        pipeline::Pipeline, plan::RoutablePipeline, CancellationToken, Scheduler,
+    };
     use arrow::array::{ArrayRef, Int32Array};
     use arrow::datatypes::{DataType, Field, Schema};
     use arrow::record_batch::RecordBatch;
-    use futures::{channel::oneshot, ready, FutureExt, StreamExt};
+    use futures::{channel::oneshot, ready, FutureExt, StreamExt, TryStreamExt};
     use parking_lot::Mutex;
     use std::fmt::Debug;
     use std::time::Duration;
 
+    fn int_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![Field::new("int", DataType::Int32, false)]))
+    }
+
+    fn int_batch() -> RecordBatch {
+        let array = Int32Array::from_iter_values([1, 2, 3]);
+        RecordBatch::try_from_iter([("int", Arc::new(array) as ArrayRef)]).unwrap()
+    }
+
+    fn single_pipeline_plan(pipeline: impl Pipeline + 'static) -> PipelinePlan {
+        PipelinePlan {
+            schema: int_schema(),
+            output_partitions: pipeline.output_partitions(),
+            pipelines: vec![RoutablePipeline {
+                pipeline: Box::new(pipeline),
+                output: None,
+            }],
+        }
+    }
+
+    /// A pipeline that yields `batches` batches per partition, or an unbounded
+    /// number if `None`, and records the peak number of concurrent polls
+    #[derive(Debug, Default)]
+    struct CountingPipeline {
+        partitions: usize,
+        batches: Option<usize>,
+        polled: Mutex<Vec<usize>>,
+        running: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl CountingPipeline {
+        fn new(partitions: usize, batches: Option<usize>) -> Self {
+            Self {
+                partitions,
+                batches,
+                polled: Mutex::new(vec![0; partitions]),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Pipeline for CountingPipeline {
+        fn push(
+            &self,
+            _input: RecordBatch,
+            _child: usize,
+            _partition: usize,
+        ) -> Result<()> {
+            unreachable!()
+        }
+
+        fn close(&self, _child: usize, _partition: usize) {}
+
+        fn output_partitions(&self) -> usize {
+            self.partitions
+        }
+
+        fn poll_partition(
+            &self,
+            _cx: &mut Context<'_>,
+            partition: usize,
+        ) -> Poll<Option<Result<RecordBatch>>> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(1));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            let mut polled = self.polled.lock();
+            if matches!(self.batches, Some(batches) if polled[partition] == batches) {
+                return Poll::Ready(None);
+            }
+            polled[partition] += 1;
+            Poll::Ready(Some(Ok(int_batch())))
+        }
+    }
+
+    /// A pipeline that never makes progress
+    #[derive(Debug)]
+    struct PendingPipeline;
+
+    impl Pipeline for PendingPipeline {
+        fn push(
+            &self,
+            _input: RecordBatch,
+            _child: usize,
+            _partition: usize,
+        ) -> Result<()> {
+            unreachable!()
+        }
+
+        fn close(&self, _child: usize, _partition: usize) {}
+
+        fn output_partitions(&self) -> usize {
+            2
+        }
+
+        fn poll_partition(
+            &self,
+            _cx: &mut Context<'_>,
+            _partition: usize,
+        ) -> Poll<Option<Result<RecordBatch>>> {
+            Poll::Pending
+        }
+    }
+
     /// Tests that waker can be sent to tokio pool
     #[derive(Debug)]
     struct TokioPipeline {
@@ -483,7 +754,7 @@ mod tests {
             }],
         };
 
-        let mut receiver = scheduler.schedule_plan(plan).stream();
+        let mut receiver = scheduler.schedule_plan(plan, Default::default()).stream();
 
         runtime.block_on(async move {
             // Should wait for output
@@ -494,4 +765,127 @@ mod tests {
             assert!(receiver.next().await.is_none());
         })
     }
+
+    #[tokio::test]
+    async fn test_cancellation_token() {
+        let scheduler = Scheduler::new(2);
+        let token = CancellationToken::new();
+        let options = QueryOptions::new().with_cancellation_token(token.clone());
+
+        let plan = single_pipeline_plan(PendingPipeline);
+        let mut stream = scheduler.schedule_plan(plan, options).stream();
+
+        token.cancel();
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("Query cancelled"), "{}", err);
+
+        // Cancelling again should be a no-op
+        token.cancel();
+        assert!(token.is_cancelled());
+
+        // Scheduling with an already cancelled token should fail immediately
+        let plan = single_pipeline_plan(CountingPipeline::new(1, None));
+        let options = QueryOptions::new().with_cancellation_token(token);
+        let mut stream = scheduler.schedule_plan(plan, options).stream();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("Query cancelled"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let scheduler = Scheduler::new(2);
+        let options = QueryOptions::new().with_timeout(Duration::from_millis(20));
+
+        let plan = single_pipeline_plan(CountingPipeline::new(2, None));
+        let mut stream = scheduler.schedule_plan(plan, options).stream();
+
+        let err = loop {
+            match stream.next().await.unwrap() {
+                Ok(_) => continue,
+                Err(e) => break e,
+            }
+        };
+        assert!(
+            err.to_string().contains("Query exceeded timeout of 20ms"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timeout_pending() {
+        let scheduler = Scheduler::new(2);
+        let options = QueryOptions::new().with_timeout(Duration::from_millis(20));
+
+        // no task makes progress, so only the timer can abort the query
+        let plan = single_pipeline_plan(PendingPipeline);
+        let mut stream = scheduler.schedule_plan(plan, options).stream();
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("Query exceeded timeout of 20ms"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_timeout_block_on() {
+        let scheduler = Scheduler::new(2);
+        let options = QueryOptions::new().with_timeout(Duration::from_millis(20));
+
+        // the output is polled outside of any tokio runtime
+        let plan = single_pipeline_plan(PendingPipeline);
+        let mut stream = scheduler.schedule_plan(plan, options).stream();
+
+        let err = futures::executor::block_on(stream.next())
+            .unwrap()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Query exceeded timeout of 20ms"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_threads() {
+        let scheduler = Scheduler::new(4);
+        let options = QueryOptions::new().with_max_threads(1);
+
+        let pipeline = Arc::new(CountingPipeline::new(4, Some(10)));
+        let plan = single_pipeline_plan(SharedPipeline(pipeline.clone()));
+        let stream = scheduler.schedule_plan(plan, options).stream();
+
+        let batches: Vec<_> = stream.try_collect().await.unwrap();
+        assert_eq!(batches.len(), 40);
+        assert_eq!(pipeline.peak.load(Ordering::SeqCst), 1);
+    }
+
+    /// Wraps a shared [`Pipeline`] so that it can be inspected after execution
+    #[derive(Debug)]
+    struct SharedPipeline(Arc<CountingPipeline>);
+
+    impl Pipeline for SharedPipeline {
+        fn push(&self, input: RecordBatch, child: usize, partition: usize) -> Result<()> {
+            self.0.push(input, child, partition)
+        }
+
+        fn close(&self, child: usize, partition: usize) {
+            self.0.close(child, partition)
+        }
+
+        fn output_partitions(&self) -> usize {
+            self.0.output_partitions()
+        }
+
+        fn poll_partition(
+            &self,
+            cx: &mut Context<'_>,
+            partition: usize,
+        ) -> Poll<Option<Result<RecordBatch>>> {
+            self.0.poll_partition(cx, partition)
+        }
+    }
 }
@@ -0,0 +1,165 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use log::debug;
+use parking_lot::{Condvar, Mutex, MutexGuard};
+
+type TimerCallback = Box<dyn FnOnce() + Send>;
+
+/// A [`DeadlineTimer`] invokes callbacks at their deadline from a dedicated thread,
+/// so that the timeouts of queries fire regardless of the runtime, if any, polling
+/// their output
+///
+/// The thread is started when a callback is registered, and exits once there are
+/// no callbacks left to invoke
+#[derive(Clone, Default)]
+pub struct DeadlineTimer {
+    inner: Arc<DeadlineTimerInner>,
+}
+
+#[derive(Default)]
+struct DeadlineTimerInner {
+    state: Mutex<DeadlineTimerState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct DeadlineTimerState {
+    /// The callbacks left to invoke, ordered by deadline
+    entries: BinaryHeap<Entry>,
+
+    /// Whether the timer thread is running
+    running: bool,
+}
+
+impl std::fmt::Debug for DeadlineTimer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeadlineTimer")
+            .field("pending", &self.inner.state.lock().entries.len())
+            .finish()
+    }
+}
+
+impl DeadlineTimer {
+    /// Registers a callback to be invoked once `deadline` has passed
+    pub fn on_deadline(
+        &self,
+        deadline: Instant,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        let mut state = self.inner.state.lock();
+        state.entries.push(Entry {
+            deadline,
+            callback: Box::new(callback),
+        });
+
+        if state.running {
+            // The deadline may be earlier than the one the thread waits for
+            self.inner.condvar.notify_one();
+            return;
+        }
+
+        debug!("Starting deadline timer thread");
+        state.running = true;
+        let inner = self.inner.clone();
+        std::thread::Builder::new()
+            .name("df-timer".to_string())
+            .spawn(move || inner.run())
+            .expect("failed to spawn deadline timer thread");
+    }
+}
+
+impl DeadlineTimerInner {
+    fn run(&self) {
+        let mut state = self.state.lock();
+        loop {
+            let deadline = match state.entries.peek() {
+                Some(entry) => entry.deadline,
+                None => {
+                    debug!("Stopping deadline timer thread");
+                    state.running = false;
+                    return;
+                }
+            };
+
+            if Instant::now() < deadline {
+                self.condvar.wait_until(&mut state, deadline);
+                continue;
+            }
+
+            // Invoke the callback without holding the lock
+            let entry = state.entries.pop().unwrap();
+            MutexGuard::unlocked(&mut state, entry.callback);
+        }
+    }
+}
+
+/// A callback and its deadline, ordered so that the [`BinaryHeap`] of
+/// [`DeadlineTimerState`] pops the earliest deadline first
+struct Entry {
+    deadline: Instant,
+    callback: TimerCallback,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_deadline_order() {
+        let timer = DeadlineTimer::default();
+        let (sender, receiver) = mpsc::channel();
+        let now = Instant::now();
+        for (idx, millis) in [30, 10, 20].into_iter().enumerate() {
+            let sender = sender.clone();
+            timer.on_deadline(now + Duration::from_millis(millis), move || {
+                sender.send(idx).unwrap()
+            });
+        }
+
+        let fired = receiver.iter().take(3).collect::<Vec<_>>();
+        assert_eq!(fired, vec![1, 2, 0]);
+        assert!(now.elapsed() >= Duration::from_millis(30));
+    }
+}
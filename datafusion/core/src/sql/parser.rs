@@ -21,7 +21,10 @@
 
 use crate::logical_plan::FileType;
 use sqlparser::{
-    ast::{ColumnDef, ColumnOptionDef, Statement as SQLStatement, TableConstraint},
+    ast::{
+        ColumnDef, ColumnOptionDef, Expr, ObjectName, OrderByExpr,
+        Statement as SQLStatement, TableConstraint,
+    },
     dialect::{keywords::Keyword, Dialect, GenericDialect},
     parser::{Parser, ParserError},
     tokenizer::{Token, Tokenizer},
@@ -41,8 +44,9 @@ fn parse_file_type(s: &str) -> Result<FileType, ParserError> {
         "NDJSON" => Ok(FileType::NdJson),
         "CSV" => Ok(FileType::CSV),
         "AVRO" => Ok(FileType::Avro),
+        "ARROW" => Ok(FileType::Arrow),
         other => Err(ParserError::ParserError(format!(
-            "expect one of PARQUET, AVRO, NDJSON, or CSV, found: {}",
+            "expect one of PARQUET, AVRO, ARROW, NDJSON, or CSV, found: {}",
             other
         ))),
     }
@@ -65,6 +69,10 @@ pub struct CreateExternalTable {
     pub location: String,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// Declared sort order of the rows of each file
+    pub order_exprs: Vec<OrderByExpr>,
+    /// Bucket columns and number of buckets of the files
+    pub bucketing: Option<(Vec<String>, usize)>,
     /// Option to not error if table already exists
     pub if_not_exists: bool,
 }
@@ -80,6 +88,49 @@ pub enum Statement {
     CreateExternalTable(CreateExternalTable),
 }
 
+/// The dialect [`DFParser`] uses by default: the generic dialect, which also
+/// parses `array(...)` as a call of the `array` function
+#[derive(Debug, Default)]
+pub struct DataFusionDialect {}
+
+impl Dialect for DataFusionDialect {
+    fn is_delimited_identifier_start(&self, ch: char) -> bool {
+        GenericDialect {}.is_delimited_identifier_start(ch)
+    }
+
+    fn is_identifier_start(&self, ch: char) -> bool {
+        GenericDialect {}.is_identifier_start(ch)
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        GenericDialect {}.is_identifier_part(ch)
+    }
+
+    fn supports_filter_during_aggregation(&self) -> bool {
+        true
+    }
+
+    fn supports_window_function_null_treatment_arg(&self) -> bool {
+        true
+    }
+
+    fn supports_window_clause_named_window_reference(&self) -> bool {
+        true
+    }
+
+    fn parse_prefix(&self, parser: &mut Parser) -> Option<Result<Expr, ParserError>> {
+        // `array(...)` calls the function building an array of its arguments, rather
+        // than being an array subquery
+        match (parser.peek_token().token, parser.peek_nth_token(1).token) {
+            (Token::Word(w), Token::LParen) if w.keyword == Keyword::ARRAY => {
+                parser.next_token();
+                Some(parser.parse_function(ObjectName(vec![w.to_ident()])))
+            }
+            _ => None,
+        }
+    }
+}
+
 /// SQL Parser
 pub struct DFParser<'a> {
     parser: Parser<'a>,
@@ -88,7 +139,7 @@ pub struct DFParser<'a> {
 impl<'a> DFParser<'a> {
     /// Parse the specified tokens
     pub fn new(sql: &str) -> Result<Self, ParserError> {
-        let dialect = &GenericDialect {};
+        let dialect = &DataFusionDialect {};
         DFParser::new_with_dialect(sql, dialect)
     }
 
@@ -101,13 +152,13 @@ impl<'a> DFParser<'a> {
         let tokens = tokenizer.tokenize()?;
 
         Ok(DFParser {
-            parser: Parser::new(tokens, dialect),
+            parser: Parser::new(dialect).with_tokens(tokens),
         })
     }
 
     /// Parse a SQL statement and produce a set of statements with dialect
     pub fn parse_sql(sql: &str) -> Result<VecDeque<Statement>, ParserError> {
-        let dialect = &GenericDialect {};
+        let dialect = &DataFusionDialect {};
         DFParser::parse_sql_with_dialect(sql, dialect)
     }
 
@@ -125,11 +176,12 @@ impl<'a> DFParser<'a> {
                 expecting_statement_delimiter = false;
             }
 
-            if parser.parser.peek_token() == Token::EOF {
+            if parser.parser.peek_token().token == Token::EOF {
                 break;
             }
             if expecting_statement_delimiter {
-                return parser.expected("end of statement", parser.parser.peek_token());
+                return parser
+                    .expected("end of statement", parser.parser.peek_token().token);
             }
 
             let statement = parser.parse_statement()?;
@@ -146,7 +198,7 @@ impl<'a> DFParser<'a> {
 
     /// Parse a new expression
     pub fn parse_statement(&mut self) -> Result<Statement, ParserError> {
-        match self.parser.peek_token() {
+        match self.parser.peek_token().token {
             Token::Word(w) => {
                 match w.keyword {
                     Keyword::CREATE => {
@@ -190,11 +242,11 @@ impl<'a> DFParser<'a> {
         }
 
         loop {
-            if let Token::Word(_) = self.parser.peek_token() {
-                let identifier = self.parser.parse_identifier()?;
+            if let Token::Word(_) = self.parser.peek_token().token {
+                let identifier = self.parser.parse_identifier(false)?;
                 partitions.push(identifier.to_string());
             } else {
-                return self.expected("partition name", self.parser.peek_token());
+                return self.expected("partition name", self.parser.peek_token().token);
             }
             let comma = self.parser.consume_token(&Token::Comma);
             if self.parser.consume_token(&Token::RParen) {
@@ -203,7 +255,7 @@ impl<'a> DFParser<'a> {
             } else if !comma {
                 return self.expected(
                     "',' or ')' after partition definition",
-                    self.parser.peek_token(),
+                    self.parser.peek_token().token,
                 );
             }
         }
@@ -225,13 +277,13 @@ impl<'a> DFParser<'a> {
         loop {
             if let Some(constraint) = self.parser.parse_optional_table_constraint()? {
                 constraints.push(constraint);
-            } else if let Token::Word(_) = self.parser.peek_token() {
+            } else if let Token::Word(_) = self.parser.peek_token().token {
                 let column_def = self.parse_column_def()?;
                 columns.push(column_def);
             } else {
                 return self.expected(
                     "column name or constraint definition",
-                    self.parser.peek_token(),
+                    self.parser.peek_token().token,
                 );
             }
             let comma = self.parser.consume_token(&Token::Comma);
@@ -241,7 +293,7 @@ impl<'a> DFParser<'a> {
             } else if !comma {
                 return self.expected(
                     "',' or ')' after column definition",
-                    self.parser.peek_token(),
+                    self.parser.peek_token().token,
                 );
             }
         }
@@ -250,23 +302,23 @@ impl<'a> DFParser<'a> {
     }
 
     fn parse_column_def(&mut self) -> Result<ColumnDef, ParserError> {
-        let name = self.parser.parse_identifier()?;
+        let name = self.parser.parse_identifier(false)?;
         let data_type = self.parser.parse_data_type()?;
         let collation = if self.parser.parse_keyword(Keyword::COLLATE) {
-            Some(self.parser.parse_object_name()?)
+            Some(self.parser.parse_object_name(false)?)
         } else {
             None
         };
         let mut options = vec![];
         loop {
             if self.parser.parse_keyword(Keyword::CONSTRAINT) {
-                let name = Some(self.parser.parse_identifier()?);
+                let name = Some(self.parser.parse_identifier(false)?);
                 if let Some(option) = self.parser.parse_optional_column_option()? {
                     options.push(ColumnOptionDef { name, option });
                 } else {
                     return self.expected(
                         "constraint details after CONSTRAINT <name>",
-                        self.parser.peek_token(),
+                        self.parser.peek_token().token,
                     );
                 }
             } else if let Some(option) = self.parser.parse_optional_column_option()? {
@@ -288,7 +340,7 @@ impl<'a> DFParser<'a> {
         let if_not_exists =
             self.parser
                 .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
-        let table_name = self.parser.parse_object_name()?;
+        let table_name = self.parser.parse_object_name(false)?;
         let (columns, _) = self.parse_columns()?;
         self.parser
             .expect_keywords(&[Keyword::STORED, Keyword::AS])?;
@@ -304,12 +356,24 @@ impl<'a> DFParser<'a> {
             false => ',',
         };
 
+        let order_exprs = if self.parse_has_order() {
+            self.parse_order_exprs()?
+        } else {
+            vec![]
+        };
+
         let table_partition_cols = if self.parse_has_partition() {
             self.parse_partitions()?
         } else {
             vec![]
         };
 
+        let bucketing = if self.parse_has_clustered() {
+            Some(self.parse_bucketing()?)
+        } else {
+            None
+        };
+
         self.parser.expect_keyword(Keyword::LOCATION)?;
         let location = self.parser.parse_literal_string()?;
 
@@ -321,6 +385,8 @@ impl<'a> DFParser<'a> {
             delimiter,
             location,
             table_partition_cols,
+            order_exprs,
+            bucketing,
             if_not_exists,
         };
         Ok(Statement::CreateExternalTable(create))
@@ -328,14 +394,14 @@ impl<'a> DFParser<'a> {
 
     /// Parses the set of valid formats
     fn parse_file_format(&mut self) -> Result<FileType, ParserError> {
-        match self.parser.next_token() {
+        match self.parser.next_token().token {
             Token::Word(w) => parse_file_type(&w.value),
             unexpected => self.expected("one of PARQUET, NDJSON, or CSV", unexpected),
         }
     }
 
     fn consume_token(&mut self, expected: &Token) -> bool {
-        let token = self.parser.peek_token().to_string().to_uppercase();
+        let token = self.parser.peek_token().token.to_string().to_uppercase();
         let token = Token::make_keyword(&token);
         if token == *expected {
             self.parser.next_token();
@@ -346,9 +412,22 @@ impl<'a> DFParser<'a> {
     }
 
     fn parse_csv_has_header(&mut self) -> bool {
-        self.consume_token(&Token::make_keyword("WITH"))
-            & self.consume_token(&Token::make_keyword("HEADER"))
-            & self.consume_token(&Token::make_keyword("ROW"))
+        self.parser
+            .parse_keywords(&[Keyword::WITH, Keyword::HEADER, Keyword::ROW])
+    }
+
+    fn parse_has_order(&mut self) -> bool {
+        self.parser.parse_keywords(&[Keyword::WITH, Keyword::ORDER])
+    }
+
+    /// Parses the parenthesized sort expressions of `WITH ORDER`
+    fn parse_order_exprs(&mut self) -> Result<Vec<OrderByExpr>, ParserError> {
+        self.parser.expect_token(&Token::LParen)?;
+        let order_exprs = self
+            .parser
+            .parse_comma_separated(Parser::parse_order_by_expr)?;
+        self.parser.expect_token(&Token::RParen)?;
+        Ok(order_exprs)
     }
 
     fn parse_has_delimiter(&mut self) -> bool {
@@ -369,12 +448,36 @@ impl<'a> DFParser<'a> {
         self.consume_token(&Token::make_keyword("PARTITIONED"))
             & self.consume_token(&Token::make_keyword("BY"))
     }
+
+    fn parse_has_clustered(&mut self) -> bool {
+        self.consume_token(&Token::make_keyword("CLUSTERED"))
+            & self.consume_token(&Token::make_keyword("BY"))
+    }
+
+    /// Parses the parenthesized columns and the number of buckets of
+    /// `CLUSTERED BY (...) INTO n BUCKETS`
+    fn parse_bucketing(&mut self) -> Result<(Vec<String>, usize), ParserError> {
+        self.parser.expect_token(&Token::LParen)?;
+        let columns = self
+            .parser
+            .parse_comma_separated(|parser| parser.parse_identifier(false))?
+            .into_iter()
+            .map(|identifier| identifier.to_string())
+            .collect();
+        self.parser.expect_token(&Token::RParen)?;
+        self.parser.expect_keyword(Keyword::INTO)?;
+        let num_buckets = self.parser.parse_literal_uint()? as usize;
+        if !self.consume_token(&Token::make_keyword("BUCKETS")) {
+            return self.expected("BUCKETS", self.parser.peek_token().token);
+        }
+        Ok((columns, num_buckets))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sqlparser::ast::{DataType, Ident};
+    use sqlparser::ast::{DataType, Expr, Ident};
 
     fn expect_parse_ok(sql: &str, expected: Statement) -> Result<(), ParserError> {
         let statements = DFParser::parse_sql(sql)?;
@@ -433,6 +536,8 @@ mod tests {
             delimiter: ',',
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
+            bucketing: None,
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -448,6 +553,8 @@ mod tests {
             delimiter: '|',
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
+            bucketing: None,
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -463,6 +570,8 @@ mod tests {
             delimiter: ',',
             location: "foo.csv".into(),
             table_partition_cols: vec!["p1".to_string(), "p2".to_string()],
+            order_exprs: vec![],
+            bucketing: None,
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -481,6 +590,8 @@ mod tests {
                 delimiter: ',',
                 location: "foo.csv".into(),
                 table_partition_cols: vec![],
+                order_exprs: vec![],
+                bucketing: None,
                 if_not_exists: false,
             });
             expect_parse_ok(sql, expected)?;
@@ -496,6 +607,8 @@ mod tests {
             delimiter: ',',
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
+            bucketing: None,
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -510,6 +623,8 @@ mod tests {
             delimiter: ',',
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
+            bucketing: None,
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -524,6 +639,24 @@ mod tests {
             delimiter: ',',
             location: "foo.avro".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
+            bucketing: None,
+            if_not_exists: false,
+        });
+        expect_parse_ok(sql, expected)?;
+
+        // positive case: it is ok for arrow files not to have columns specified
+        let sql = "CREATE EXTERNAL TABLE t STORED AS ARROW LOCATION 'foo.arrow'";
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![],
+            file_type: FileType::Arrow,
+            has_header: false,
+            delimiter: ',',
+            location: "foo.arrow".into(),
+            table_partition_cols: vec![],
+            order_exprs: vec![],
+            bucketing: None,
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -539,20 +672,76 @@ mod tests {
             delimiter: ',',
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
+            bucketing: None,
             if_not_exists: true,
         });
         expect_parse_ok(sql, expected)?;
 
+        // positive case: declared sort order
+        let sql = "CREATE EXTERNAL TABLE t(c1 int, c2 int) STORED AS CSV WITH HEADER ROW WITH ORDER (c1 DESC, c2) LOCATION 'foo.csv'";
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![
+                make_column_def("c1", DataType::Int(None)),
+                make_column_def("c2", DataType::Int(None)),
+            ],
+            file_type: FileType::CSV,
+            has_header: true,
+            delimiter: ',',
+            location: "foo.csv".into(),
+            table_partition_cols: vec![],
+            order_exprs: vec![
+                OrderByExpr {
+                    expr: Expr::Identifier(Ident::new("c1")),
+                    asc: Some(false),
+                    nulls_first: None,
+                },
+                OrderByExpr {
+                    expr: Expr::Identifier(Ident::new("c2")),
+                    asc: None,
+                    nulls_first: None,
+                },
+            ],
+            bucketing: None,
+            if_not_exists: false,
+        });
+        expect_parse_ok(sql, expected)?;
+
+        // positive case: bucketing
+        let sql = "CREATE EXTERNAL TABLE t(c1 int, c2 int) STORED AS CSV PARTITIONED BY (p1) CLUSTERED BY (c1, c2) INTO 8 BUCKETS LOCATION 'foo.csv'";
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![
+                make_column_def("c1", DataType::Int(None)),
+                make_column_def("c2", DataType::Int(None)),
+            ],
+            file_type: FileType::CSV,
+            has_header: false,
+            delimiter: ',',
+            location: "foo.csv".into(),
+            table_partition_cols: vec!["p1".to_string()],
+            order_exprs: vec![],
+            bucketing: Some((vec!["c1".to_string(), "c2".to_string()], 8)),
+            if_not_exists: false,
+        });
+        expect_parse_ok(sql, expected)?;
+
         // Error cases: Invalid type
         let sql =
             "CREATE EXTERNAL TABLE t(c1 int) STORED AS UNKNOWN_TYPE LOCATION 'foo.csv'";
-        expect_parse_error(sql, "expect one of PARQUET, AVRO, NDJSON, or CSV");
+        expect_parse_error(sql, "expect one of PARQUET, AVRO, ARROW, NDJSON, or CSV");
 
         // Error cases: partition column does not support type
         let sql =
             "CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV PARTITIONED BY (p1 int) LOCATION 'foo.csv'";
         expect_parse_error(sql, "sql parser error: Expected ',' or ')' after partition definition, found: int");
 
+        // Error cases: number of buckets without BUCKETS
+        let sql =
+            "CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV CLUSTERED BY (c1) INTO 4 LOCATION 'foo.csv'";
+        expect_parse_error(sql, "sql parser error: Expected BUCKETS, found: LOCATION");
+
         Ok(())
     }
 }
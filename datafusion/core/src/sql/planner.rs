@@ -19,19 +19,24 @@
 
 use std::collections::HashSet;
 use std::iter;
+use std::ops::ControlFlow;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::{convert::TryInto, vec};
+use std::vec;
 
 use crate::catalog::TableReference;
+use crate::datasource::cte_worktable::CteWorkTable;
 use crate::datasource::TableProvider;
-use crate::logical_plan::window_frames::{WindowFrame, WindowFrameUnits};
+use crate::logical_plan::window_frames::{
+    WindowFrame, WindowFrameBound, WindowFrameUnits,
+};
 use crate::logical_plan::Expr::Alias;
 use crate::logical_plan::{
     and, col, lit, normalize_col, normalize_col_with_schemas, provider_as_source, Column,
     CreateCatalog, CreateCatalogSchema, CreateExternalTable as PlanCreateExternalTable,
-    CreateMemoryTable, CreateView, DFSchema, DFSchemaRef, DropTable, Expr, FileType,
-    LogicalPlan, LogicalPlanBuilder, Operator, PlanType, ToDFSchema, ToStringifiedPlan,
+    CreateMemoryTable, CreateView, DFField, DFSchema, DFSchemaRef, DropTable, Expr,
+    ExprSchemable, FileType, LogicalPlan, LogicalPlanBuilder, Operator, PlanType,
+    ToDFSchema, ToStringifiedPlan,
 };
 use crate::prelude::JoinType;
 use crate::scalar::ScalarValue;
@@ -42,6 +47,7 @@ use crate::{
     physical_plan::aggregates,
     physical_plan::udaf::AggregateUDF,
     physical_plan::udf::ScalarUDF,
+    physical_plan::udwf::WindowUDF,
     sql::parser::{CreateExternalTable, Statement as DFStatement},
 };
 use arrow::datatypes::*;
@@ -49,21 +55,31 @@ use datafusion_expr::utils::{
     expr_as_column_expr, exprlist_to_columns, find_aggregate_exprs, find_column_exprs,
     find_window_exprs,
 };
-use datafusion_expr::{window_function::WindowFunction, BuiltinScalarFunction};
+use datafusion_expr::{
+    window_function::{BuiltInWindowFunction, WindowFunction},
+    BuiltinScalarFunction,
+};
+use datafusion_physical_expr::timezone::Tz;
 use hashbrown::HashMap;
 
-use datafusion_common::field_not_found;
+use datafusion_common::{field_not_found, SchemaError};
 use datafusion_expr::expr::GroupingSet;
 use datafusion_expr::logical_plan::builder::project_with_alias;
-use datafusion_expr::logical_plan::{Filter, Subquery};
+use datafusion_expr::logical_plan::{Filter, Projection, Subquery};
+use sqlparser::ast::{
+    ArrayElemTypeDef, ColumnDef as SQLColumnDef, ColumnOption, CreateTableOptions,
+    TimezoneInfo,
+};
 use sqlparser::ast::{
-    BinaryOperator, DataType as SQLDataType, DateTimeField, Expr as SQLExpr, FunctionArg,
-    FunctionArgExpr, Ident, Join, JoinConstraint, JoinOperator, ObjectName,
-    Offset as SQLOffset, Query, Select, SelectItem, SetExpr, SetOperator,
-    ShowStatementFilter, TableFactor, TableWithJoins, TrimWhereField, UnaryOperator,
-    Value, Values as SQLValues,
+    BinaryOperator, CastKind, DataType as SQLDataType, DateTimeField, Distinct,
+    DuplicateTreatment, Expr as SQLExpr, FunctionArg, FunctionArgExpr,
+    FunctionArgumentClause, FunctionArguments, GroupByExpr, Ident, Interval, Join,
+    JoinConstraint, JoinOperator, NamedWindowDefinition, NamedWindowExpr, ObjectName,
+    Offset as SQLOffset, Query, Select, SelectItem, SetExpr, SetOperator, SetQuantifier,
+    ShowStatementFilter, Subscript, TableAlias, TableFactor, TableWithJoins,
+    TrimWhereField, UnaryOperator, Value, Values as SQLValues, VisitMut, Visitor,
+    VisitorMut, WildcardAdditionalOptions, WindowSpec, WindowType,
 };
-use sqlparser::ast::{ColumnDef as SQLColumnDef, ColumnOption};
 use sqlparser::ast::{ObjectType, OrderByExpr, Statement};
 use sqlparser::parser::ParserError::ParserError;
 
@@ -76,6 +92,10 @@ use super::{
 };
 use crate::logical_plan::plan::{Analyze, Explain};
 
+/// The plan of an aggregation, and the projection, HAVING and QUALIFY expressions
+/// rewritten to reference its output
+type AggregatePlan = (LogicalPlan, Vec<Expr>, Option<Expr>, Vec<Expr>);
+
 /// The ContextProvider trait allows the query planner to obtain meta-data about tables and
 /// functions referenced in SQL statements
 pub trait ContextProvider {
@@ -85,8 +105,27 @@ pub trait ContextProvider {
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>>;
     /// Getter for a UDAF description
     fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>>;
+    /// Getter for a UDWF description
+    fn get_window_meta(&self, _name: &str) -> Option<Arc<WindowUDF>> {
+        None
+    }
     /// Getter for system/user-defined variable type
     fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType>;
+    /// Getter for the time zone of the session, of `TIMESTAMP WITH TIME ZONE` values
+    fn get_time_zone(&self) -> String {
+        "UTC".to_owned()
+    }
+    /// Getter for the table produced by calling table function `name` with `args`
+    fn get_table_function_source(
+        &self,
+        name: &str,
+        _args: Vec<Expr>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        Err(DataFusionError::NotImplemented(format!(
+            "Table function '{}' is not supported",
+            name
+        )))
+    }
 }
 
 /// SQL query planner
@@ -151,10 +190,16 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 verbose,
                 statement,
                 analyze,
-                describe_alias: _,
+                ..
             } => self.explain_statement_to_plan(verbose, analyze, *statement),
             Statement::Query(query) => self.query_to_plan(*query, &mut HashMap::new()),
             Statement::ShowVariable { variable } => self.show_variable_to_plan(&variable),
+            Statement::ShowTables {
+                extended: false,
+                full: false,
+                db_name: None,
+                filter: None,
+            } => self.show_tables_to_plan(),
             Statement::CreateTable {
                 query: Some(query),
                 name,
@@ -182,9 +227,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 name,
                 columns,
                 query,
-                with_options,
+                options: CreateTableOptions::None,
                 ..
-            } if columns.is_empty() && with_options.is_empty() => {
+            } if columns.is_empty() => {
                 let plan = self.query_to_plan(*query, &mut HashMap::new())?;
                 Ok(LogicalPlan::CreateView(CreateView {
                     name: name.to_string(),
@@ -217,8 +262,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 object_type: ObjectType::Table,
                 if_exists,
                 names,
-                cascade: _,
-                purge: _,
+                ..
             } =>
             // We don't support cascade and purge for now.
             {
@@ -269,7 +313,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         ctes: &mut HashMap<String, LogicalPlan>,
         outer_query_schema: Option<&DFSchema>,
     ) -> Result<LogicalPlan> {
-        let set_expr = query.body;
+        let set_expr = *query.body;
         if let Some(with) = query.with {
             // Process CTEs from top to bottom
             // do not allow self-references
@@ -282,19 +326,61 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         cte_name
                     ))));
                 }
-                // create logical plan & pass backreferencing CTEs
-                let logical_plan = self.query_to_plan_with_alias(
-                    cte.query,
-                    Some(cte_name.clone()),
-                    &mut ctes.clone(),
-                    outer_query_schema,
-                )?;
+                let query = *cte.query;
+                let is_plain_union = query.with.is_none()
+                    && query.order_by.is_empty()
+                    && query.limit.is_none()
+                    && query.limit_by.is_empty()
+                    && query.offset.is_none()
+                    && query.fetch.is_none()
+                    && query.locks.is_empty()
+                    && query.for_clause.is_none();
+                let logical_plan = match *query.body {
+                    SetExpr::SetOperation {
+                        op: SetOperator::Union,
+                        set_quantifier,
+                        left,
+                        right,
+                    } if with.recursive && is_plain_union => self.recursive_cte_to_plan(
+                        cte_name.clone(),
+                        cte.alias.columns,
+                        *left,
+                        *right,
+                        set_quantifier == SetQuantifier::All,
+                        ctes,
+                        outer_query_schema,
+                    )?,
+                    body => {
+                        let query = Query {
+                            body: Box::new(body),
+                            ..query
+                        };
+                        // create logical plan & pass backreferencing CTEs
+                        let logical_plan = self.query_to_plan_with_alias(
+                            query,
+                            Some(cte_name.clone()),
+                            &mut ctes.clone(),
+                            outer_query_schema,
+                        )?;
+                        rename_cte_columns(logical_plan, &cte_name, cte.alias.columns)?
+                    }
+                };
                 ctes.insert(cte_name, logical_plan);
             }
         }
-        let plan = self.set_expr_to_plan(set_expr, alias, ctes, outer_query_schema)?;
+        let mut order_by = query.order_by;
+        let plan = match set_expr {
+            // the `DISTINCT ON` clause of a select is ordered by the `ORDER BY` clause
+            // of the query, which can refer to the windows of the select
+            SetExpr::Select(s) => {
+                self.select_to_plan(*s, &mut order_by, ctes, alias, outer_query_schema)?
+            }
+            set_expr => {
+                self.set_expr_to_plan(set_expr, alias, ctes, outer_query_schema)?
+            }
+        };
 
-        let plan = self.order_by(plan, query.order_by)?;
+        let plan = self.order_by(plan, order_by)?;
 
         let plan: LogicalPlan = self.limit(plan, query.limit)?;
 
@@ -302,6 +388,64 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         self.offset(plan, query.offset)
     }
 
+    /// Generate a logical plan for the recursive CTE `name`, whose body is
+    /// `static_term UNION [ALL] recursive_term`. The recursive term scans the
+    /// rows of the previous iteration as `name`.
+    #[allow(clippy::too_many_arguments)]
+    fn recursive_cte_to_plan(
+        &self,
+        name: String,
+        columns: Vec<Ident>,
+        static_term: SetExpr,
+        recursive_term: SetExpr,
+        all: bool,
+        ctes: &mut HashMap<String, LogicalPlan>,
+        outer_query_schema: Option<&DFSchema>,
+    ) -> Result<LogicalPlan> {
+        let static_plan = self.set_expr_to_plan(
+            static_term,
+            None,
+            &mut ctes.clone(),
+            outer_query_schema,
+        )?;
+        let static_plan = rename_cte_columns(static_plan, &name, columns)?;
+
+        // within the recursive term, `name` is the work table holding the
+        // rows of the previous iteration
+        let fields = static_plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| Field::new(f.name(), f.data_type().clone(), true))
+            .collect();
+        let work_table = CteWorkTable::new(&name, Arc::new(Schema::new(fields)));
+        let work_table_scan = LogicalPlanBuilder::scan(
+            &name,
+            provider_as_source(Arc::new(work_table)),
+            None,
+        )?
+        .build()?;
+        let mut recursive_ctes = ctes.clone();
+        recursive_ctes.insert(name.clone(), work_table_scan);
+        let recursive_plan = self.set_expr_to_plan(
+            recursive_term,
+            None,
+            &mut recursive_ctes,
+            outer_query_schema,
+        )?;
+
+        let builder = LogicalPlanBuilder::from(static_plan);
+        let plan = if scans_table(&recursive_plan, &name) {
+            builder.to_recursive_query(name.clone(), recursive_plan, !all)?
+        } else if all {
+            builder.union(recursive_plan)?
+        } else {
+            builder.union_distinct(recursive_plan)?
+        }
+        .build()?;
+        project_with_alias(plan, vec![Expr::Wildcard], Some(name))
+    }
+
     fn set_expr_to_plan(
         &self,
         set_expr: SetExpr,
@@ -311,15 +455,25 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     ) -> Result<LogicalPlan> {
         match set_expr {
             SetExpr::Select(s) => {
-                self.select_to_plan(*s, ctes, alias, outer_query_schema)
+                self.select_to_plan(*s, &mut [], ctes, alias, outer_query_schema)
             }
             SetExpr::Values(v) => self.sql_values_to_plan(v),
             SetExpr::SetOperation {
                 op,
+                set_quantifier,
                 left,
                 right,
-                all,
             } => {
+                let all = match set_quantifier {
+                    SetQuantifier::All => true,
+                    SetQuantifier::Distinct | SetQuantifier::None => false,
+                    _ => {
+                        return Err(DataFusionError::NotImplemented(format!(
+                            "Set quantifier {} is not supported",
+                            set_quantifier
+                        )))
+                    }
+                };
                 let left_plan =
                     self.set_expr_to_plan(*left, None, ctes, outer_query_schema)?;
                 let right_plan =
@@ -366,6 +520,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             delimiter,
             location,
             table_partition_cols,
+            order_exprs,
+            bucketing,
             if_not_exists,
         } = statement;
 
@@ -382,10 +538,31 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             }
             FileType::NdJson => {}
             FileType::Avro => {}
+            FileType::Arrow => {}
         };
 
         let schema = self.build_schema(columns)?;
 
+        // the sort order refers to columns of the files, whose schema may only
+        // be known once they are inferred, so the columns are not resolved here
+        let order_exprs = order_exprs
+            .into_iter()
+            .map(|order_expr| match order_expr.expr {
+                SQLExpr::Identifier(id) => {
+                    let asc = order_expr.asc.unwrap_or(true);
+                    Ok(Expr::Sort {
+                        expr: Box::new(col(&normalize_ident(&id))),
+                        asc,
+                        nulls_first: order_expr.nulls_first.unwrap_or(!asc),
+                    })
+                }
+                other => Err(DataFusionError::Plan(format!(
+                    "Only column names are supported in WITH ORDER, found: {}",
+                    other
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(LogicalPlan::CreateExternalTable(PlanCreateExternalTable {
             schema: schema.to_dfschema_ref()?,
             name,
@@ -394,6 +571,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             has_header,
             delimiter,
             table_partition_cols,
+            order_exprs,
+            bucketing,
             if_not_exists,
         }))
     }
@@ -457,16 +636,24 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             SQLDataType::Char(_) | SQLDataType::Varchar(_) | SQLDataType::Text => {
                 Ok(DataType::Utf8)
             }
-            SQLDataType::Decimal(precision, scale) => {
-                make_decimal_type(*precision, *scale)
+            SQLDataType::Decimal(info) | SQLDataType::Numeric(info) => {
+                make_decimal_type(info)
             }
             SQLDataType::Float(_) => Ok(DataType::Float32),
             SQLDataType::Real => Ok(DataType::Float32),
             SQLDataType::Double => Ok(DataType::Float64),
             SQLDataType::Boolean => Ok(DataType::Boolean),
             SQLDataType::Date => Ok(DataType::Date32),
-            SQLDataType::Time => Ok(DataType::Time64(TimeUnit::Millisecond)),
-            SQLDataType::Timestamp => Ok(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+            SQLDataType::Time(None, TimezoneInfo::None) => {
+                Ok(DataType::Time64(TimeUnit::Millisecond))
+            }
+            SQLDataType::Timestamp(None, TimezoneInfo::None) => {
+                Ok(DataType::Timestamp(TimeUnit::Nanosecond, None))
+            }
+            SQLDataType::Timestamp(
+                None,
+                TimezoneInfo::WithTimeZone | TimezoneInfo::Tz,
+            ) => self.timestamp_with_time_zone(TimeUnit::Nanosecond),
             _ => Err(DataFusionError::NotImplemented(format!(
                 "The SQL data type {:?} is not implemented",
                 sql_type
@@ -474,6 +661,131 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }
     }
 
+    /// Maps the SQL type of a cast to the corresponding Arrow `DataType`
+    fn convert_data_type(&self, sql_type: &SQLDataType) -> Result<DataType> {
+        match sql_type {
+            SQLDataType::Timestamp(
+                None,
+                TimezoneInfo::WithTimeZone | TimezoneInfo::Tz,
+            ) => self.timestamp_with_time_zone(TimeUnit::Nanosecond),
+            other => convert_data_type(other),
+        }
+    }
+
+    /// Returns the type of the timestamps in `unit` with the time zone of the session
+    fn timestamp_with_time_zone(&self, unit: TimeUnit) -> Result<DataType> {
+        let time_zone = self.schema_provider.get_time_zone();
+        Tz::parse(&time_zone).map_err(|_| {
+            DataFusionError::Plan(format!(
+                "Invalid time zone '{}' of the session",
+                time_zone
+            ))
+        })?;
+        Ok(DataType::Timestamp(unit, Some(time_zone)))
+    }
+
+    /// Plans a window frame, whose offsets are constants such as numbers, or intervals
+    /// for RANGE frames ordered by dates or timestamps
+    fn window_frame_to_plan(
+        &self,
+        window_frame: sqlparser::ast::WindowFrame,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<WindowFrame> {
+        let mut offset = |offset: Option<Box<SQLExpr>>| {
+            offset
+                .map(|offset| {
+                    match self.sql_expr_to_logical_expr(
+                        *offset,
+                        &DFSchema::empty(),
+                        ctes,
+                    )? {
+                        Expr::Literal(value) if !value.is_null() => Ok(value),
+                        other => Err(DataFusionError::Plan(format!(
+                            "Window frame offset must be a constant, got {:?}",
+                            other
+                        ))),
+                    }
+                })
+                .transpose()
+        };
+        let mut bound = |bound| -> Result<WindowFrameBound> {
+            Ok(match bound {
+                sqlparser::ast::WindowFrameBound::Preceding(v) => {
+                    WindowFrameBound::Preceding(offset(v)?)
+                }
+                sqlparser::ast::WindowFrameBound::Following(v) => {
+                    WindowFrameBound::Following(offset(v)?)
+                }
+                sqlparser::ast::WindowFrameBound::CurrentRow => {
+                    WindowFrameBound::CurrentRow
+                }
+            })
+        };
+        let start_bound = bound(window_frame.start_bound)?;
+        let end_bound = match window_frame.end_bound {
+            Some(end_bound) => bound(end_bound)?,
+            None => WindowFrameBound::CurrentRow,
+        };
+        WindowFrame::try_new(window_frame.units.into(), start_bound, end_bound)
+    }
+
+    /// Plans `expr AT TIME ZONE zone`: the local time in `zone` of a timestamp with a
+    /// time zone, or the timestamp with the time zone of the session of a local time
+    /// in `zone`
+    fn at_time_zone_to_expr(
+        &self,
+        expr: SQLExpr,
+        zone: SQLExpr,
+        schema: &DFSchema,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<Expr> {
+        let expr = self.sql_expr_to_logical_expr(expr, schema, ctes)?;
+        let zone = self.sql_expr_to_logical_expr(zone, schema, ctes)?;
+        let zone = match zone {
+            Expr::Literal(ScalarValue::Utf8(Some(zone))) => zone,
+            other => {
+                return Err(DataFusionError::Plan(format!(
+                    "AT TIME ZONE only supports a constant time zone, found {:?}",
+                    other
+                )))
+            }
+        };
+        Tz::parse(&zone).map_err(|_| {
+            DataFusionError::Plan(format!("Invalid time zone '{}'", zone))
+        })?;
+
+        let (expr, unit, has_time_zone) = match expr.get_type(schema)? {
+            DataType::Timestamp(unit, time_zone) => (expr, unit, time_zone.is_some()),
+            // a string is a timestamp with the time zone of the session
+            DataType::Utf8 => {
+                let data_type = self.timestamp_with_time_zone(TimeUnit::Nanosecond)?;
+                let expr = Expr::Cast {
+                    expr: Box::new(expr),
+                    data_type,
+                };
+                (expr, TimeUnit::Nanosecond, true)
+            }
+            other => {
+                return Err(DataFusionError::Plan(format!(
+                    "AT TIME ZONE only supports timestamps, found {:?}",
+                    other
+                )))
+            }
+        };
+        let data_type = if has_time_zone {
+            DataType::Timestamp(unit.clone(), None)
+        } else {
+            self.timestamp_with_time_zone(unit.clone())?
+        };
+        Ok(Expr::Cast {
+            expr: Box::new(Expr::Cast {
+                expr: Box::new(expr),
+                data_type: DataType::Timestamp(unit, Some(zone)),
+            }),
+            data_type,
+        })
+    }
+
     fn plan_from_tables(
         &self,
         from: Vec<TableWithJoins>,
@@ -482,13 +794,62 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     ) -> Result<Vec<LogicalPlan>> {
         match from.len() {
             0 => Ok(vec![LogicalPlanBuilder::empty(true).build()?]),
-            _ => from
-                .into_iter()
-                .map(|t| self.plan_table_with_joins(t, ctes, outer_query_schema))
-                .collect::<Result<Vec<_>>>(),
+            _ => {
+                let mut plans = vec![];
+                for t in from {
+                    if is_lateral_relation(&t.relation) {
+                        self.plan_lateral_table_with_joins(
+                            &mut plans,
+                            t,
+                            ctes,
+                            outer_query_schema,
+                        )?;
+                    } else {
+                        plans.push(self.plan_table_with_joins(
+                            t,
+                            ctes,
+                            outer_query_schema,
+                        )?);
+                    }
+                }
+                Ok(plans)
+            }
         }
     }
 
+    /// Plans `t`, whose relation is LATERAL, over the last of the preceding
+    /// `plans` whose columns its relation can be planned against, replacing
+    /// that plan. `t` is planned on its own if there is no such plan.
+    fn plan_lateral_table_with_joins(
+        &self,
+        plans: &mut Vec<LogicalPlan>,
+        t: TableWithJoins,
+        ctes: &mut HashMap<String, LogicalPlan>,
+        outer_query_schema: Option<&DFSchema>,
+    ) -> Result<()> {
+        for i in (0..plans.len()).rev() {
+            let mut plan = match self.plan_lateral_relation(
+                plans[i].clone(),
+                t.relation.clone(),
+                ctes,
+            ) {
+                Ok(plan) => plan,
+                // the relation references the columns of another plan
+                Err(DataFusionError::SchemaError(SchemaError::FieldNotFound {
+                    ..
+                })) => continue,
+                Err(e) => return Err(e),
+            };
+            for join in t.joins {
+                plan = self.parse_relation_join(plan, join, ctes, outer_query_schema)?;
+            }
+            plans[i] = plan;
+            return Ok(());
+        }
+        plans.push(self.plan_table_with_joins(t, ctes, outer_query_schema)?);
+        Ok(())
+    }
+
     fn plan_table_with_joins(
         &self,
         t: TableWithJoins,
@@ -522,8 +883,23 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         ctes: &mut HashMap<String, LogicalPlan>,
         outer_query_schema: Option<&DFSchema>,
     ) -> Result<LogicalPlan> {
+        if is_lateral_relation(&join.relation) {
+            return match join.join_operator {
+                JoinOperator::CrossJoin => {
+                    self.plan_lateral_relation(left, join.relation, ctes)
+                }
+                other => Err(DataFusionError::NotImplemented(format!(
+                    "Unsupported JOIN operator {:?} of a LATERAL relation",
+                    other
+                ))),
+            };
+        }
         let right = self.create_relation(join.relation, ctes, outer_query_schema)?;
         match join.join_operator {
+            JoinOperator::AsOf {
+                match_condition,
+                constraint,
+            } => self.parse_asof_join(left, right, match_condition, constraint, ctes),
             JoinOperator::LeftOuter(constraint) => {
                 self.parse_join(left, right, constraint, JoinType::Left, ctes)
             }
@@ -552,6 +928,52 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         LogicalPlanBuilder::from(left).cross_join(right)?.build()
     }
 
+    /// Plans an ASOF join, whose `ON` clause may only hold equalities of the join keys
+    fn parse_asof_join(
+        &self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        match_condition: SQLExpr,
+        constraint: JoinConstraint,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<LogicalPlan> {
+        let join_schema = left.schema().join(right.schema())?;
+        let match_condition = self.sql_to_rex(match_condition, &join_schema, ctes)?;
+
+        let mut conjuncts = vec![];
+        match constraint {
+            JoinConstraint::On(sql_expr) => {
+                split_sql_conjunction(sql_expr, &mut conjuncts)
+            }
+            JoinConstraint::None => {}
+            other => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Unsupported join constraint of an ASOF join: {:?}",
+                    other
+                )))
+            }
+        }
+
+        let mut keys: Vec<(Column, Column)> = vec![];
+        let mut filter = vec![];
+        for conjunct in conjuncts {
+            let expr = self.sql_to_rex(conjunct, &join_schema, ctes)?;
+            extract_join_keys(expr, &mut keys, &mut filter);
+        }
+        if !filter.is_empty() {
+            return Err(DataFusionError::NotImplemented(format!(
+                "The ON clause of an ASOF join only supports equalities of columns, found {:?}",
+                filter
+            )));
+        }
+
+        let (left_keys, right_keys): (Vec<Column>, Vec<Column>) =
+            keys.into_iter().unzip();
+        LogicalPlanBuilder::from(left)
+            .join_asof(&right, (left_keys, right_keys), match_condition)?
+            .build()
+    }
+
     fn parse_join(
         &self,
         left: LogicalPlan,
@@ -581,7 +1003,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     keys.into_iter().unzip();
 
                 // return the logical plan representing the join
-                if left_keys.is_empty() {
+                if left_keys.is_empty() && join_type == JoinType::Inner {
                     // When we don't have join keys, use cross join
                     let join = LogicalPlanBuilder::from(left).cross_join(&right)?;
 
@@ -659,10 +1081,16 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         .join(&right, join_type, (left_keys, right_keys))?
                         .build()
                 } else {
-                    Err(DataFusionError::NotImplemented(format!(
-                        "Unsupported expressions in {:?} JOIN: {:?}",
-                        join_type, filter
-                    )))
+                    // The remaining expressions are evaluated for every pair of
+                    // rows that the join keys, if any, match
+                    LogicalPlanBuilder::from(left)
+                        .join_with_filter(
+                            &right,
+                            join_type,
+                            (left_keys, right_keys),
+                            filter.into_iter().reduce(Expr::and).unwrap(),
+                        )?
+                        .build()
                 }
             }
             JoinConstraint::Using(idents) => {
@@ -692,7 +1120,36 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         ctes: &mut HashMap<String, LogicalPlan>,
         outer_query_schema: Option<&DFSchema>,
     ) -> Result<LogicalPlan> {
+        if is_unnest_relation(&relation) {
+            // there are no columns for the argument to reference
+            return self.plan_lateral_relation(
+                LogicalPlanBuilder::empty(true).build()?,
+                relation,
+                ctes,
+            );
+        }
         let (plan, alias) = match relation {
+            TableFactor::Table {
+                name,
+                alias,
+                args: Some(args),
+                ..
+            } => (
+                self.table_function_to_plan(&name, args, ctes, alias.as_ref())?,
+                alias,
+            ),
+            TableFactor::TableFunction {
+                expr: SQLExpr::Function(function),
+                alias,
+            } => (
+                self.table_function_to_plan(
+                    &function.name,
+                    function_args(function.args)?.0,
+                    ctes,
+                    alias.as_ref(),
+                )?,
+                alias,
+            ),
             TableFactor::Table {
                 name: ref sql_object_name,
                 alias,
@@ -753,11 +1210,13 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     alias,
                 )
             }
-            TableFactor::NestedJoin(table_with_joins) => (
+            TableFactor::NestedJoin {
+                table_with_joins,
+                alias,
+            } => (
                 self.plan_table_with_joins(*table_with_joins, ctes, outer_query_schema)?,
-                None,
+                alias,
             ),
-            // @todo Support TableFactory::TableFunction?
             _ => {
                 return Err(DataFusionError::NotImplemented(format!(
                     "Unsupported ast node {:?} in create_relation",
@@ -793,6 +1252,295 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }
     }
 
+    /// Returns the names and arguments of the table function calls in
+    /// `statement`, so that the tables they produce, whose creation may
+    /// require I/O, can be created before the statement is planned
+    pub fn table_function_calls(
+        &self,
+        statement: &Statement,
+    ) -> Result<Vec<(String, Vec<Expr>)>> {
+        let mut visitor = TableFunctionCalls { calls: vec![] };
+        if let ControlFlow::Break(e) =
+            sqlparser::ast::Visit::visit(statement, &mut visitor)
+        {
+            return Err(e);
+        }
+        visitor
+            .calls
+            .into_iter()
+            .map(|(name, args)| {
+                let args = args
+                    .into_iter()
+                    .map(|arg| {
+                        self.sql_fn_arg_to_logical_expr(
+                            arg,
+                            &DFSchema::empty(),
+                            &mut HashMap::new(),
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok((normalize_sql_object_name(&name), args))
+            })
+            .collect()
+    }
+
+    /// Generate a logical plan scanning the table produced by a table function call
+    fn table_function_to_plan(
+        &self,
+        name: &ObjectName,
+        args: Vec<FunctionArg>,
+        ctes: &mut HashMap<String, LogicalPlan>,
+        alias: Option<&TableAlias>,
+    ) -> Result<LogicalPlan> {
+        let name = normalize_sql_object_name(name);
+        let args = args
+            .into_iter()
+            .map(|arg| self.sql_fn_arg_to_logical_expr(arg, &DFSchema::empty(), ctes))
+            .collect::<Result<Vec<_>>>()?;
+        let provider = self
+            .schema_provider
+            .get_table_function_source(&name, args)?;
+        let scan = LogicalPlanBuilder::scan(&name, provider_as_source(provider), None)?;
+        match alias {
+            Some(alias) => scan.alias(&normalize_ident(&alias.name))?.build(),
+            None => scan.build(),
+        }
+    }
+
+    /// Generate a logical plan evaluating the LATERAL `relation`, which is an
+    /// `UNNEST(...)` call or a LATERAL subquery, for each row of `left`
+    fn plan_lateral_relation(
+        &self,
+        left: LogicalPlan,
+        relation: TableFactor,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<LogicalPlan> {
+        match relation {
+            TableFactor::UNNEST {
+                alias,
+                array_exprs,
+                with_offset: false,
+                ..
+            } => {
+                let args = array_exprs
+                    .into_iter()
+                    .map(|e| FunctionArg::Unnamed(FunctionArgExpr::Expr(e)))
+                    .collect();
+                self.plan_unnest_relation(left, args, alias, ctes)
+            }
+            TableFactor::Table {
+                alias,
+                args: Some(args),
+                ..
+            } => self.plan_unnest_relation(left, args, alias, ctes),
+            TableFactor::Derived {
+                subquery, alias, ..
+            } => self.plan_lateral_subquery(left, *subquery, alias, ctes),
+            other => Err(DataFusionError::Internal(format!(
+                "Unexpected LATERAL relation {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Generate a logical plan evaluating `UNNEST(args)` for each row of `left`
+    fn plan_unnest_relation(
+        &self,
+        left: LogicalPlan,
+        args: Vec<FunctionArg>,
+        alias: Option<TableAlias>,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<LogicalPlan> {
+        let arg = self.unnest_arg(args, &left, ctes)?;
+        let (qualifier, name) = match alias {
+            Some(alias) => {
+                let name = match alias.columns.as_slice() {
+                    [] => "unnest".to_string(),
+                    [column] => normalize_ident(column),
+                    columns => {
+                        return Err(DataFusionError::Plan(format!(
+                            "unnest produces a single column but {} names given as column alias",
+                            columns.len()
+                        )))
+                    }
+                };
+                (normalize_ident(&alias.name), name)
+            }
+            None => ("unnest".to_string(), "unnest".to_string()),
+        };
+        self.plan_lateral(left, vec![(arg, true, name)], Some(&qualifier))
+    }
+
+    /// Generate a logical plan evaluating the SELECT list of the LATERAL
+    /// subquery `query` for each row of `left`. Only subqueries without a
+    /// FROM clause, such as `LATERAL (SELECT unnest(t.tags) AS tag)`, are
+    /// supported.
+    fn plan_lateral_subquery(
+        &self,
+        left: LogicalPlan,
+        query: Query,
+        alias: Option<TableAlias>,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<LogicalPlan> {
+        let select = match *query.body {
+            SetExpr::Select(select)
+                if query.with.is_none()
+                    && query.order_by.is_empty()
+                    && query.limit.is_none()
+                    && query.offset.is_none()
+                    && query.fetch.is_none()
+                    && select.from.is_empty()
+                    && select.group_by == GroupByExpr::Expressions(vec![])
+                    && select.having.is_none()
+                    && select.distinct.is_none() =>
+            {
+                select
+            }
+            _ => {
+                return Err(DataFusionError::NotImplemented(
+                    "LATERAL is only supported for subqueries of a SELECT list without FROM, GROUP BY, HAVING, DISTINCT, ORDER BY or LIMIT clauses".to_string(),
+                ))
+            }
+        };
+
+        // the WHERE clause can only reference the columns of `left`
+        let left = match select.selection {
+            Some(predicate) => {
+                let predicate = self.sql_to_rex(predicate, left.schema(), ctes)?;
+                LogicalPlanBuilder::from(left.clone())
+                    .filter(normalize_col(predicate, &left)?)?
+                    .build()?
+            }
+            None => left,
+        };
+
+        let mut items = vec![];
+        for item in select.projection {
+            let (expr, alias) = match item {
+                SelectItem::UnnamedExpr(expr) => (expr, None),
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    (expr, Some(normalize_ident(&alias)))
+                }
+                _ => {
+                    return Err(DataFusionError::NotImplemented(
+                        "Wildcards are not supported in LATERAL subqueries".to_string(),
+                    ))
+                }
+            };
+            if let Some(args) = unnest_call_args(&expr) {
+                let args = function_args(args.clone())?.0;
+                let arg = self.unnest_arg(args, &left, ctes)?;
+                items.push((arg, true, alias.unwrap_or_else(|| "unnest".to_string())));
+            } else {
+                let expr = self.sql_to_rex(expr, left.schema(), ctes)?;
+                let expr = normalize_col(expr, &left)?;
+                if !find_aggregate_exprs(&[expr.clone()]).is_empty() {
+                    return Err(DataFusionError::NotImplemented(
+                        "Aggregate functions are not supported in LATERAL subqueries"
+                            .to_string(),
+                    ));
+                }
+                let name = match alias {
+                    Some(alias) => alias,
+                    None => expr.name(left.schema())?,
+                };
+                items.push((expr, false, name));
+            }
+        }
+
+        let qualifier = alias.as_ref().map(|alias| normalize_ident(&alias.name));
+        if let Some(alias) = &alias {
+            if !alias.columns.is_empty() {
+                if alias.columns.len() != items.len() {
+                    return Err(DataFusionError::Plan(format!(
+                        "Source table contains {} columns but only {} names given as column alias",
+                        items.len(),
+                        alias.columns.len(),
+                    )));
+                }
+                for (item, column) in items.iter_mut().zip(&alias.columns) {
+                    item.2 = normalize_ident(column);
+                }
+            }
+        }
+        self.plan_lateral(left, items, qualifier.as_deref())
+    }
+
+    /// Plans the argument of an `unnest` call against `plan`
+    fn unnest_arg(
+        &self,
+        args: Vec<FunctionArg>,
+        plan: &LogicalPlan,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<Expr> {
+        let arg = match <[FunctionArg; 1]>::try_from(args) {
+            Ok([arg]) => arg,
+            Err(args) => {
+                return Err(DataFusionError::Plan(format!(
+                    "unnest expects a single argument, got {}",
+                    args.len()
+                )))
+            }
+        };
+        let arg = self.sql_fn_arg_to_logical_expr(arg, plan.schema(), ctes)?;
+        self.validate_schema_satisfies_exprs(plan.schema(), &[arg.clone()])?;
+        normalize_col(arg, plan)
+    }
+
+    /// Generate a logical plan appending `items`, computed from each row of
+    /// `input`, to its columns. Items are `(expr, unnest, name)` triples, named
+    /// `name` with the qualifier `qualifier`. The elements of the list of the
+    /// (at most one) item with `unnest` set each produce a row.
+    fn plan_lateral(
+        &self,
+        input: LogicalPlan,
+        items: Vec<(Expr, bool, String)>,
+        qualifier: Option<&str>,
+    ) -> Result<LogicalPlan> {
+        if items.iter().filter(|(_, unnest, _)| *unnest).count() > 1 {
+            return Err(DataFusionError::NotImplemented(
+                "Only a single unnest is supported per SELECT list".to_string(),
+            ));
+        }
+        let input_schema = input.schema().clone();
+        let mut exprs = input_schema
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+        let mut fields = input_schema.fields().clone();
+        let mut unnest_column = None;
+        for (expr, unnest, name) in items {
+            let field = DFField::new(
+                qualifier,
+                &name,
+                expr.get_type(&input_schema)?,
+                expr.nullable(&input_schema)?,
+            );
+            if unnest {
+                unnest_column = Some(field.qualified_column());
+            }
+            fields.push(field);
+            exprs.push(expr.alias(&name));
+        }
+        // the projection has its own schema, as its columns have different
+        // qualifiers
+        let schema =
+            DFSchema::new_with_metadata(fields, input_schema.metadata().clone())?;
+        let plan = LogicalPlan::Projection(Projection {
+            expr: exprs,
+            input: Arc::new(input),
+            schema: Arc::new(schema),
+            alias: None,
+        });
+        match unnest_column {
+            Some(column) => LogicalPlanBuilder::from(plan)
+                .unnest_column(column)?
+                .build(),
+            None => Ok(plan),
+        }
+    }
+
     /// Generate a logic plan from selection clause, the function contain optimization for cross join to inner join
     /// Related PR: <https://github.com/apache/arrow-datafusion/pull/1566>
     fn plan_selection(
@@ -956,11 +1704,18 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     /// Generate a logic plan from an SQL select
     fn select_to_plan(
         &self,
-        select: Select,
+        mut select: Select,
+        order_by: &mut [OrderByExpr],
         ctes: &mut HashMap<String, LogicalPlan>,
         alias: Option<String>,
         outer_query_schema: Option<&DFSchema>,
     ) -> Result<LogicalPlan> {
+        resolve_named_windows(&mut select, order_by)?;
+        let (distinct, distinct_on) = match select.distinct {
+            Some(Distinct::On(exprs)) => (false, Some(exprs)),
+            distinct => (distinct == Some(Distinct::Distinct), None),
+        };
+
         // process `from` clause
         let plans = self.plan_from_tables(select.from, ctes, outer_query_schema)?;
         let empty_from = matches!(plans.first(), Some(LogicalPlan::EmptyRelation(_)));
@@ -969,9 +1724,10 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let plan =
             self.plan_selection(select.selection, plans, outer_query_schema, ctes)?;
 
-        // process the SELECT expressions, with wildcards expanded.
-        let select_exprs = self.prepare_select_exprs(
-            &plan,
+        // process the SELECT expressions, with wildcards expanded and the
+        // argument of an `unnest` call unnested.
+        let (plan, select_exprs) = self.prepare_select_exprs(
+            plan,
             select.projection,
             empty_from,
             outer_query_schema,
@@ -1012,6 +1768,91 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             })
             .transpose()?;
 
+        // Optionally the QUALIFY expression, which filters on the results of the window
+        // functions and, like HAVING, may refer to aliased columns, e.g.
+        //
+        //   SELECT c1, ROW_NUMBER() OVER (PARTITION BY c1 ORDER BY c2) AS rn FROM t QUALIFY rn = 1;
+        //
+        let qualify_expr_opt = select
+            .qualify
+            .map::<Result<Expr>, _>(|qualify_expr| {
+                let qualify_expr =
+                    self.sql_expr_to_logical_expr(qualify_expr, &combined_schema, ctes)?;
+                let qualify_expr = resolve_aliases_to_exprs(&qualify_expr, &alias_map)?;
+                normalize_col(qualify_expr, &projected_plan)
+            })
+            .transpose()?;
+
+        // The `DISTINCT ON (exprs)` clause keeps the first row of each group of rows
+        // with the same `exprs`, as ordered by the `ORDER BY` clause, which is planned as
+        // a ROW_NUMBER window function, partitioned by `exprs` and ordered as the query,
+        // and a filter on its first row.
+        let distinct_on_expr_opt = distinct_on
+            .map::<Result<Expr>, _>(|exprs| {
+                let resolve = |expr: Expr| {
+                    let expr = resolve_aliases_to_exprs(&expr, &alias_map)?;
+                    normalize_col(expr, &projected_plan)
+                };
+                let partition_by = exprs
+                    .into_iter()
+                    .map(|expr| {
+                        resolve(self.sql_expr_to_logical_expr(
+                            expr,
+                            &combined_schema,
+                            ctes,
+                        )?)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let order_by = order_by
+                    .iter()
+                    .map(|order_by| {
+                        // as in the ORDER BY clause, positions refer to the items of the
+                        // select
+                        let expr = match &order_by.expr {
+                            SQLExpr::Value(Value::Number(v, _)) => {
+                                let position = v.parse::<i64>().map_err(|err| {
+                                    DataFusionError::Plan(err.to_string())
+                                })?;
+                                resolve_positions_to_exprs(&lit(position), &select_exprs)
+                                    .ok_or_else(|| {
+                                        DataFusionError::Plan(format!(
+                                            "Order by column out of bounds, specified: {}, max: {}",
+                                            position,
+                                            select_exprs.len()
+                                        ))
+                                    })?
+                            }
+                            expr => self.sql_expr_to_logical_expr(
+                                expr.clone(),
+                                &combined_schema,
+                                ctes,
+                            )?,
+                        };
+                        let asc = order_by.asc.unwrap_or(true);
+                        Ok(Expr::Sort {
+                            expr: Box::new(resolve(expr)?),
+                            asc,
+                            nulls_first: order_by.nulls_first.unwrap_or(!asc),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                check_distinct_on_order(&partition_by, &order_by)?;
+                Ok(Expr::WindowFunction {
+                    fun: WindowFunction::BuiltInWindowFunction(
+                        BuiltInWindowFunction::RowNumber,
+                    ),
+                    args: vec![],
+                    partition_by,
+                    order_by,
+                    window_frame: None,
+                })
+            })
+            .transpose()?;
+        let qualify_exprs = qualify_expr_opt
+            .into_iter()
+            .chain(distinct_on_expr_opt.clone())
+            .collect::<Vec<_>>();
+
         // The outer expressions we will search through for
         // aggregates. Aggregates may be sourced from the SELECT...
         let mut aggr_expr_haystack = select_exprs.clone();
@@ -1019,13 +1860,22 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         if let Some(having_expr) = &having_expr_opt {
             aggr_expr_haystack.push(having_expr.clone());
         }
+        // ... or from the QUALIFY and DISTINCT ON clauses.
+        aggr_expr_haystack.extend_from_slice(&qualify_exprs);
 
         // All of the aggregate expressions (deduplicated).
         let aggr_exprs = find_aggregate_exprs(&aggr_expr_haystack);
 
         // All of the group by expressions
-        let group_by_exprs = select
-            .group_by
+        let group_by = match select.group_by {
+            GroupByExpr::Expressions(exprs) => exprs,
+            GroupByExpr::All => {
+                return Err(DataFusionError::NotImplemented(
+                    "GROUP BY ALL is not supported".to_string(),
+                ))
+            }
+        };
+        let group_by_exprs = group_by
             .into_iter()
             .map(|e| {
                 let group_by_expr =
@@ -1049,33 +1899,38 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             .collect::<Result<Vec<Expr>>>()?;
 
         // process group by, aggregation or having
-        let (plan, select_exprs_post_aggr, having_expr_post_aggr) =
-            if !group_by_exprs.is_empty() || !aggr_exprs.is_empty() {
-                self.aggregate(
-                    plan,
-                    &select_exprs,
-                    &having_expr_opt,
-                    group_by_exprs,
-                    aggr_exprs,
-                )?
-            } else {
-                if let Some(having_expr) = &having_expr_opt {
-                    let available_columns = select_exprs
-                        .iter()
-                        .map(|expr| expr_as_column_expr(expr, &plan))
-                        .collect::<Result<Vec<Expr>>>()?;
-
-                    // Ensure the HAVING expression is using only columns
-                    // provided by the SELECT.
-                    check_columns_satisfy_exprs(
-                        &available_columns,
-                        &[having_expr.clone()],
-                        "HAVING clause references column(s) not provided by the select",
-                    )?;
-                }
+        let (
+            plan,
+            select_exprs_post_aggr,
+            having_expr_post_aggr,
+            qualify_exprs_post_aggr,
+        ) = if !group_by_exprs.is_empty() || !aggr_exprs.is_empty() {
+            self.aggregate(
+                plan,
+                &select_exprs,
+                &having_expr_opt,
+                &qualify_exprs,
+                group_by_exprs,
+                aggr_exprs,
+            )?
+        } else {
+            if let Some(having_expr) = &having_expr_opt {
+                let available_columns = select_exprs
+                    .iter()
+                    .map(|expr| expr_as_column_expr(expr, &plan))
+                    .collect::<Result<Vec<Expr>>>()?;
+
+                // Ensure the HAVING expression is using only columns
+                // provided by the SELECT.
+                check_columns_satisfy_exprs(
+                    &available_columns,
+                    &[having_expr.clone()],
+                    "HAVING clause references column(s) not provided by the select",
+                )?;
+            }
 
-                (plan, select_exprs, having_expr_opt)
-            };
+            (plan, select_exprs, having_expr_opt, qualify_exprs)
+        };
 
         let plan = if let Some(having_expr_post_aggr) = having_expr_post_aggr {
             LogicalPlanBuilder::from(plan)
@@ -1085,17 +1940,42 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             plan
         };
 
+        // the QUALIFY expression, if any, then the DISTINCT ON one
+        let mut qualify_exprs_post_aggr = qualify_exprs_post_aggr.into_iter();
+        let distinct_on_expr_post_aggr =
+            distinct_on_expr_opt.map(|_| qualify_exprs_post_aggr.next_back().unwrap());
+        let qualify_expr_post_aggr = qualify_exprs_post_aggr.next();
+
         // process window function
-        let window_func_exprs = find_window_exprs(&select_exprs_post_aggr);
+        let mut window_func_haystack = select_exprs_post_aggr.clone();
+        window_func_haystack.extend(qualify_expr_post_aggr.clone());
+        let window_func_exprs = find_window_exprs(&window_func_haystack);
 
-        let plan = if window_func_exprs.is_empty() {
+        let mut plan = if window_func_exprs.is_empty() {
             plan
         } else {
-            LogicalPlanBuilder::window_plan(plan, window_func_exprs)?
+            LogicalPlanBuilder::window_plan(plan.clone(), window_func_exprs.clone())?
         };
 
+        // process qualify clause, filtering on the columns of the window functions
+        if let Some(qualify_expr) = qualify_expr_post_aggr {
+            let qualify_expr = rebase_expr(&qualify_expr, &window_func_exprs, &plan)?;
+            plan = LogicalPlanBuilder::from(plan)
+                .filter(qualify_expr)?
+                .build()?;
+        }
+
+        // process distinct on clause, after the rows are filtered by the qualify clause
+        if let Some(row_number) = distinct_on_expr_post_aggr {
+            let input = plan.clone();
+            plan = LogicalPlanBuilder::window_plan(plan, vec![row_number.clone()])?;
+            let first_row = rebase_expr(&row_number, &[row_number.clone()], &input)?
+                .eq(lit(ScalarValue::UInt64(Some(1))));
+            plan = LogicalPlanBuilder::from(plan).filter(first_row)?.build()?;
+        }
+
         // process distinct clause
-        let plan = if select.distinct {
+        let plan = if distinct {
             return LogicalPlanBuilder::from(plan)
                 .aggregate(select_exprs_post_aggr, iter::empty::<Expr>())?
                 .build();
@@ -1109,25 +1989,61 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
     /// Returns the `Expr`'s corresponding to a SQL query's SELECT expressions.
     ///
-    /// Wildcards are expanded into the concrete list of columns.
+    /// Wildcards are expanded into the concrete list of columns. An `unnest`
+    /// call is planned as an [`Unnest`](crate::logical_plan::Unnest) of `plan`,
+    /// returned along with the expressions, of which it becomes a column.
     fn prepare_select_exprs(
         &self,
-        plan: &LogicalPlan,
+        plan: LogicalPlan,
         projection: Vec<SelectItem>,
         empty_from: bool,
         outer_query_schema: Option<&DFSchema>,
         ctes: &mut HashMap<String, LogicalPlan>,
-    ) -> Result<Vec<Expr>> {
-        projection
-            .into_iter()
-            .map(|expr| {
-                self.sql_select_to_rex(expr, plan, empty_from, outer_query_schema, ctes)
-            })
-            .flat_map(|result| match result {
-                Ok(vec) => vec.into_iter().map(Ok).collect(),
-                Err(err) => vec![Err(err)],
-            })
-            .collect::<Result<Vec<Expr>>>()
+    ) -> Result<(LogicalPlan, Vec<Expr>)> {
+        let mut select_exprs = vec![];
+        let mut unnest = None;
+        for item in projection {
+            let (args, alias) = match &item {
+                SelectItem::UnnamedExpr(expr) => (unnest_call_args(expr), None),
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    (unnest_call_args(expr), Some(alias))
+                }
+                _ => (None, None),
+            };
+            let args = match args {
+                Some(args) if unnest.is_none() => function_args(args.clone())?.0,
+                Some(_) => {
+                    return Err(DataFusionError::NotImplemented(
+                        "Only a single unnest is supported per SELECT list".to_string(),
+                    ))
+                }
+                None => {
+                    select_exprs.extend(self.sql_select_to_rex(
+                        item,
+                        &plan,
+                        empty_from,
+                        outer_query_schema,
+                        ctes,
+                    )?);
+                    continue;
+                }
+            };
+            let arg = self.unnest_arg(args, &plan, ctes)?;
+            let name = format!("unnest({})", arg.name(plan.schema())?);
+            let column = Expr::Column(Column::from_name(&name));
+            select_exprs.push(match alias {
+                Some(alias) => column.alias(&normalize_ident(alias)),
+                None => column,
+            });
+            unnest = Some((arg, name));
+        }
+        let plan = match unnest {
+            Some((arg, name)) => {
+                self.plan_lateral(plan, vec![(arg, true, name)], None)?
+            }
+            None => plan,
+        };
+        Ok((plan, select_exprs))
     }
 
     /// Wrap a plan in a projection
@@ -1147,27 +2063,31 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     ///                       "having" expressions must all be resolvable from this plan.
     /// * `select_exprs`    - The projection expressions from the SELECT clause.
     /// * `having_expr_opt` - Optional HAVING clause.
+    /// * `qualify_exprs`   - The expressions of the QUALIFY and DISTINCT ON clauses, if any.
     /// * `group_by_exprs`  - Grouping expressions from the GROUP BY clause. These can be column
     ///                       references or more complex expressions.
     /// * `aggr_exprs`      - Aggregate expressions, such as `SUM(a)` or `COUNT(1)`.
     ///
     /// # Return
     ///
-    /// The return value is a triplet of the following items:
+    /// The return value is a quadruplet of the following items:
     ///
     /// * `plan`                   - A [LogicalPlan::Aggregate] plan for the newly created aggregate.
     /// * `select_exprs_post_aggr` - The projection expressions rewritten to reference columns from
     ///                              the aggregate
     /// * `having_expr_post_aggr`  - The "having" expression rewritten to reference a column from
     ///                              the aggregate
+    /// * `qualify_exprs_post_aggr` - The "qualify" expressions rewritten to reference columns
+    ///                               from the aggregate
     fn aggregate(
         &self,
         input: LogicalPlan,
         select_exprs: &[Expr],
         having_expr_opt: &Option<Expr>,
+        qualify_exprs: &[Expr],
         group_by_exprs: Vec<Expr>,
         aggr_exprs: Vec<Expr>,
-    ) -> Result<(LogicalPlan, Vec<Expr>, Option<Expr>)> {
+    ) -> Result<AggregatePlan> {
         // create the aggregate plan
         let plan = LogicalPlanBuilder::from(input.clone())
             .aggregate(group_by_exprs.clone(), aggr_exprs.clone())?
@@ -1243,7 +2163,23 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             None
         };
 
-        Ok((plan, select_exprs_post_aggr, having_expr_post_aggr))
+        // Rewrite the QUALIFY and DISTINCT ON expressions as well
+        let qualify_exprs_post_aggr = qualify_exprs
+            .iter()
+            .map(|expr| rebase_expr(expr, &aggr_projection_exprs, &input))
+            .collect::<Result<Vec<Expr>>>()?;
+        check_columns_satisfy_exprs(
+            &column_exprs_post_aggr,
+            &qualify_exprs_post_aggr,
+            "QUALIFY clause references non-aggregate values",
+        )?;
+
+        Ok((
+            plan,
+            select_exprs_post_aggr,
+            having_expr_post_aggr,
+            qualify_exprs_post_aggr,
+        ))
     }
 
     /// Wrap a plan in a limit
@@ -1424,7 +2360,15 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 );
                 Ok(vec![normalize_col(expr, plan)?])
             }
-            SelectItem::Wildcard => {
+            SelectItem::Wildcard(options) | SelectItem::QualifiedWildcard(_, options)
+                if options != WildcardAdditionalOptions::default() =>
+            {
+                Err(DataFusionError::NotImplemented(format!(
+                    "Unsupported wildcard options {}",
+                    options
+                )))
+            }
+            SelectItem::Wildcard(_) => {
                 if empty_from {
                     return Err(DataFusionError::Plan(
                         "SELECT * with no tables specified is not valid".to_string(),
@@ -1433,7 +2377,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 // do not expand from outer schema
                 expand_wildcard(plan.schema().as_ref(), plan)
             }
-            SelectItem::QualifiedWildcard(ref object_name) => {
+            SelectItem::QualifiedWildcard(ref object_name, _) => {
                 let qualifier = format!("{}", object_name);
                 // do not expand from outer schema
                 expand_qualified_wildcard(&qualifier, plan.schema().as_ref(), plan)
@@ -1490,12 +2434,12 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     ) -> Result<Expr> {
         match sql {
             FunctionArg::Named {
-                name: _,
                 arg: FunctionArgExpr::Expr(arg),
+                ..
             } => self.sql_expr_to_logical_expr(arg, schema, ctes),
             FunctionArg::Named {
-                name: _,
                 arg: FunctionArgExpr::Wildcard,
+                ..
             } => Ok(Expr::Wildcard),
             FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) => {
                 self.sql_expr_to_logical_expr(arg, schema, ctes)
@@ -1530,8 +2474,6 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             BinaryOperator::Modulo => Ok(Operator::Modulo),
             BinaryOperator::And => Ok(Operator::And),
             BinaryOperator::Or => Ok(Operator::Or),
-            BinaryOperator::Like => Ok(Operator::Like),
-            BinaryOperator::NotLike => Ok(Operator::NotLike),
             BinaryOperator::PGRegexMatch => Ok(Operator::RegexMatch),
             BinaryOperator::PGRegexIMatch => Ok(Operator::RegexIMatch),
             BinaryOperator::PGRegexNotMatch => Ok(Operator::RegexNotMatch),
@@ -1593,7 +2535,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         // values should not be based on any other schema
         let schema = DFSchema::empty();
         let values = values
-            .0
+            .rows
             .into_iter()
             .map(|row| {
                 row.into_iter()
@@ -1648,19 +2590,30 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 ],
             }),
 
-            SQLExpr::Value(Value::Interval {
-                value,
-                leading_field,
-                leading_precision,
-                last_field,
-                fractional_seconds_precision,
-            }) => self.sql_interval_to_literal(
+            SQLExpr::Interval(Interval {
                 value,
                 leading_field,
                 leading_precision,
                 last_field,
                 fractional_seconds_precision,
-            ),
+            }) => {
+                let value = match *value {
+                    SQLExpr::Value(Value::SingleQuotedString(value)) => value,
+                    value => {
+                        return Err(DataFusionError::NotImplemented(format!(
+                            "Unsupported interval value {}",
+                            value
+                        )))
+                    }
+                };
+                self.sql_interval_to_literal(
+                    value,
+                    leading_field,
+                    leading_precision,
+                    last_field,
+                    fractional_seconds_precision,
+                )
+            }
 
             SQLExpr::Array(arr) => self.sql_array_literal(arr.elem, schema),
 
@@ -1692,6 +2645,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
             SQLExpr::MapAccess { ref column, keys } => {
                 if let SQLExpr::Identifier(ref id) = column.as_ref() {
+                    let keys = keys.into_iter().map(|key| key.key).collect();
                     plan_indexed(col(&normalize_ident(id)), keys)
                 } else {
                     Err(DataFusionError::NotImplemented(format!(
@@ -1701,10 +2655,16 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }
             }
 
-            SQLExpr::ArrayIndex { obj, indexs } => {
-                let expr = self.sql_expr_to_logical_expr(*obj, schema, ctes)?;
-                plan_indexed(expr, indexs)
-            }
+            SQLExpr::Subscript { expr, subscript } => match *subscript {
+                Subscript::Index { index } => {
+                    let expr = self.sql_expr_to_logical_expr(*expr, schema, ctes)?;
+                    plan_indexed(expr, vec![index])
+                }
+                subscript => Err(DataFusionError::NotImplemented(format!(
+                    "Unsupported subscript {}",
+                    subscript
+                ))),
+            },
 
             SQLExpr::CompoundIdentifier(ids) => {
                 let mut var_names: Vec<_> = ids.into_iter().map(|s| normalize_ident(&s)).collect();
@@ -1782,27 +2742,36 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             }
 
             SQLExpr::Cast {
+                kind: CastKind::Cast | CastKind::DoubleColon,
                 expr,
                 data_type,
+                format: None,
             } => Ok(Expr::Cast {
                 expr: Box::new(self.sql_expr_to_logical_expr(*expr, schema, ctes)?),
-                data_type: convert_data_type(&data_type)?,
+                data_type: self.convert_data_type(&data_type)?,
             }),
 
-            SQLExpr::TryCast {
+            SQLExpr::Cast {
+                kind: CastKind::TryCast | CastKind::SafeCast,
                 expr,
                 data_type,
+                format: None,
             } => Ok(Expr::TryCast {
                 expr: Box::new(self.sql_expr_to_logical_expr(*expr, schema, ctes)?),
-                data_type: convert_data_type(&data_type)?,
+                data_type: self.convert_data_type(&data_type)?,
             }),
 
+            SQLExpr::AtTimeZone {
+                timestamp,
+                time_zone,
+            } => self.at_time_zone_to_expr(*timestamp, *time_zone, schema, ctes),
+
             SQLExpr::TypedString {
                 ref data_type,
                 ref value,
             } => Ok(Expr::Cast {
                 expr: Box::new(lit(&**value)),
-                data_type: convert_data_type(data_type)?,
+                data_type: self.convert_data_type(data_type)?,
             }),
 
             SQLExpr::IsNull(expr) => Ok(Expr::IsNull(Box::new(
@@ -1826,13 +2795,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             }),
 
 
-            SQLExpr::UnaryOp { op, expr } => match (&op, expr.as_ref()) {
-                // The AST for Exists does not support the NOT EXISTS case so it gets
-                // wrapped in a unary NOT
-                // https://github.com/sqlparser-rs/sqlparser-rs/issues/472
-                (&UnaryOperator::Not, &SQLExpr::Exists(ref subquery)) => self.parse_exists_subquery(subquery, true, schema, ctes),
-                _ => self.parse_sql_unary_op(op, *expr, schema, ctes)
-            }
+            SQLExpr::UnaryOp { op, expr } => self.parse_sql_unary_op(op, *expr, schema, ctes),
 
             SQLExpr::Between {
                 expr,
@@ -1869,11 +2832,23 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 right,
             } => self.parse_sql_binary_op(*left, op, *right, schema, ctes),
 
+            SQLExpr::Like {
+                negated,
+                expr,
+                pattern,
+                escape_char: None,
+            } => Ok(Expr::BinaryExpr {
+                left: Box::new(self.sql_expr_to_logical_expr(*expr, schema, ctes)?),
+                op: if negated { Operator::NotLike } else { Operator::Like },
+                right: Box::new(self.sql_expr_to_logical_expr(*pattern, schema, ctes)?),
+            }),
+
             #[cfg(feature = "unicode_expressions")]
             SQLExpr::Substring {
                 expr,
                 substring_from,
                 substring_for,
+                ..
             } => {
                 let args = match (substring_from, substring_for) {
                     (Some(from_expr), Some(for_expr)) => {
@@ -1898,15 +2873,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         vec![arg, from_logic, for_logic]
                     }
                     (None, None) => {
-                        let orig_sql = SQLExpr::Substring {
-                            expr,
-                            substring_from: None,
-                            substring_for: None,
-                        };
-
                         return Err(DataFusionError::Plan(format!(
-                            "Substring without for/from is not valid {:?}",
-                            orig_sql
+                            "Substring without for/from is not valid SUBSTRING({})",
+                            expr
                         )));
                     }
                 };
@@ -1927,19 +2896,21 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 ))
             }
 
-            SQLExpr::Trim { expr, trim_where } => {
-                let (fun, where_expr) = match trim_where {
-                    Some((TrimWhereField::Leading, expr)) => {
-                        (BuiltinScalarFunction::Ltrim, Some(expr))
-                    }
-                    Some((TrimWhereField::Trailing, expr)) => {
-                        (BuiltinScalarFunction::Rtrim, Some(expr))
-                    }
-                    Some((TrimWhereField::Both, expr)) => {
-                        (BuiltinScalarFunction::Btrim, Some(expr))
+            SQLExpr::Trim {
+                expr,
+                trim_where,
+                trim_what,
+                trim_characters: None,
+            } => {
+                let fun = match (trim_where, &trim_what) {
+                    (Some(TrimWhereField::Leading), _) => BuiltinScalarFunction::Ltrim,
+                    (Some(TrimWhereField::Trailing), _) => BuiltinScalarFunction::Rtrim,
+                    (Some(TrimWhereField::Both), _) | (None, Some(_)) => {
+                        BuiltinScalarFunction::Btrim
                     }
-                    None => (BuiltinScalarFunction::Trim, None),
+                    (None, None) => BuiltinScalarFunction::Trim,
                 };
+                let where_expr = trim_what;
                 let arg = self.sql_expr_to_logical_expr(*expr, schema, ctes)?;
                 let args = match where_expr {
                     Some(to_trim) => {
@@ -1951,7 +2922,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 Ok(Expr::ScalarFunction { fun, args })
             }
 
-            SQLExpr::Function(mut function) => {
+            SQLExpr::Function(function) => {
                 let name = if function.name.0.len() > 1 {
                     // DF doesn't handle compound identifiers
                     // (e.g. "foo.bar") for function names yet
@@ -1960,23 +2931,61 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     normalize_ident(&function.name.0[0])
                 };
 
+                if let Some(null_treatment) = function.null_treatment {
+                    return Err(DataFusionError::NotImplemented(format!(
+                        "{} is not supported for function {}",
+                        null_treatment, name
+                    )));
+                }
+                let (args, distinct, mut order_by) = function_args(function.args)?;
+                order_by.extend(function.within_group);
+                let (filter, order_by) =
+                    self.aggregate_clauses(function.filter, order_by, schema, ctes)?;
+                if (filter.is_some() || !order_by.is_empty())
+                    && (function.over.is_some()
+                        || aggregates::AggregateFunction::from_str(&name).is_err())
+                {
+                    return Err(DataFusionError::Plan(format!(
+                        "FILTER and ORDER BY clauses are only supported for built-in aggregate functions, found {}",
+                        name
+                    )));
+                }
+
                 // first, check SQL reserved words
                 if name == "rollup" {
-                    let args = self.function_args_to_expr(function.args, schema)?;
+                    let args = self.function_args_to_expr(args, schema)?;
                     return Ok(Expr::GroupingSet(GroupingSet::Rollup(args)));
                 } else if name == "cube" {
-                    let args = self.function_args_to_expr(function.args, schema)?;
+                    let args = self.function_args_to_expr(args, schema)?;
                     return Ok(Expr::GroupingSet(GroupingSet::Cube(args)));
+                } else if name == "unnest" {
+                    return Err(DataFusionError::NotImplemented(
+                        "unnest is only supported as an item of a SELECT list or as a relation"
+                            .to_string(),
+                    ));
                 }
 
                 // next, scalar built-in
                 if let Ok(fun) = BuiltinScalarFunction::from_str(&name) {
-                    let args = self.function_args_to_expr(function.args, schema)?;
+                    let args = self.function_args_to_expr(args, schema)?;
                     return Ok(Expr::ScalarFunction { fun, args });
                 };
 
                 // then, window function
-                if let Some(window) = function.over.take() {
+                if let Some(window) = function.over {
+                    let window = match window {
+                        WindowType::WindowSpec(WindowSpec {
+                            window_name: Some(name),
+                            ..
+                        })
+                        | WindowType::NamedWindow(name) => {
+                            return Err(DataFusionError::Plan(format!(
+                                "Unknown window {}",
+                                name
+                            )))
+                        }
+                        WindowType::WindowSpec(window) => window,
+                    };
                     let partition_by = window
                         .partition_by
                         .into_iter()
@@ -1991,7 +3000,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         .window_frame
                         .as_ref()
                         .map(|window_frame| {
-                            let window_frame: WindowFrame = window_frame.clone().try_into()?;
+                            let window_frame =
+                                self.window_frame_to_plan(window_frame.clone(), ctes)?;
                             if WindowFrameUnits::Range == window_frame.units
                                 && order_by.len() != 1
                             {
@@ -2002,16 +3012,27 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                             }
                         })
                         .transpose()?;
-                    let fun = WindowFunction::from_str(&name)?;
+                    let fun = match WindowFunction::from_str(&name) {
+                        Ok(fun) => fun,
+                        Err(e) => {
+                            if let Some(fm) = self.schema_provider.get_window_meta(&name)
+                            {
+                                WindowFunction::WindowUDF(fm)
+                            } else if let Some(fm) =
+                                self.schema_provider.get_aggregate_meta(&name)
+                            {
+                                WindowFunction::AggregateUDF(fm)
+                            } else {
+                                return Err(e);
+                            }
+                        }
+                    };
                     match fun {
                         WindowFunction::AggregateFunction(
                             aggregate_fun,
                         ) => {
-                            let (aggregate_fun, args) = self.aggregate_fn_to_expr(
-                                aggregate_fun,
-                                function,
-                                schema,
-                            )?;
+                            let (aggregate_fun, args) =
+                                self.aggregate_fn_to_expr(aggregate_fun, args, schema)?;
 
                             return Ok(Expr::WindowFunction {
                                 fun: WindowFunction::AggregateFunction(
@@ -2023,14 +3044,12 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                                 window_frame,
                             });
                         }
-                        WindowFunction::BuiltInWindowFunction(
-                            window_fun,
-                        ) => {
+                        WindowFunction::BuiltInWindowFunction(_)
+                        | WindowFunction::AggregateUDF(_)
+                        | WindowFunction::WindowUDF(_) => {
                             return Ok(Expr::WindowFunction {
-                                fun: WindowFunction::BuiltInWindowFunction(
-                                    window_fun,
-                                ),
-                                args: self.function_args_to_expr(function.args, schema)?,
+                                fun,
+                                args: self.function_args_to_expr(args, schema)?,
                                 partition_by,
                                 order_by,
                                 window_frame,
@@ -2041,25 +3060,28 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
                 // next, aggregate built-ins
                 if let Ok(fun) = aggregates::AggregateFunction::from_str(&name) {
-                    let distinct = function.distinct;
-                    let (fun, args) = self.aggregate_fn_to_expr(fun, function, schema)?;
+                    let (fun, args) = self.aggregate_fn_to_expr(fun, args, schema)?;
+                    let (args, order_by) =
+                        Self::ordered_set_aggregate_args(&fun, args, order_by)?;
                     return Ok(Expr::AggregateFunction {
                         fun,
                         distinct,
                         args,
+                        filter,
+                        order_by,
                     });
                 };
 
                 // finally, user-defined functions (UDF) and UDAF
                 match self.schema_provider.get_function_meta(&name) {
                     Some(fm) => {
-                        let args = self.function_args_to_expr(function.args, schema)?;
+                        let args = self.function_args_to_expr(args, schema)?;
 
                         Ok(Expr::ScalarUDF { fun: fm, args })
                     }
                     None => match self.schema_provider.get_aggregate_meta(&name) {
                         Some(fm) => {
-                            let args = self.function_args_to_expr(function.args, schema)?;
+                            let args = self.function_args_to_expr(args, schema)?;
                             Ok(Expr::AggregateUDF { fun: fm, args })
                         }
                         _ => Err(DataFusionError::Plan(format!(
@@ -2070,9 +3092,35 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }
             }
 
+            SQLExpr::Floor {
+                expr,
+                field: DateTimeField::NoDateTime,
+            } => Ok(Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::Floor,
+                args: vec![self.sql_expr_to_logical_expr(*expr, schema, ctes)?],
+            }),
+
+            SQLExpr::Ceil {
+                expr,
+                field: DateTimeField::NoDateTime,
+            } => Ok(Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::Ceil,
+                args: vec![self.sql_expr_to_logical_expr(*expr, schema, ctes)?],
+            }),
+
+            SQLExpr::Struct { values, fields } if fields.is_empty() => {
+                Ok(Expr::ScalarFunction {
+                    fun: BuiltinScalarFunction::Struct,
+                    args: values
+                        .into_iter()
+                        .map(|e| self.sql_expr_to_logical_expr(e, schema, ctes))
+                        .collect::<Result<Vec<_>>>()?,
+                })
+            }
+
             SQLExpr::Nested(e) => self.sql_expr_to_logical_expr(*e, schema, ctes),
 
-            SQLExpr::Exists(subquery) => self.parse_exists_subquery(&subquery, false, schema, ctes),
+            SQLExpr::Exists { subquery, negated } => self.parse_exists_subquery(&subquery, negated, schema, ctes),
 
             SQLExpr::InSubquery {  expr, subquery, negated } => self.parse_in_subquery(&expr, &subquery, negated, schema, ctes),
 
@@ -2150,15 +3198,34 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             .collect::<Result<Vec<Expr>>>()
     }
 
+    /// Plans the `FILTER (WHERE ...)` clause of an aggregate and the `ORDER BY` clause
+    /// in its arguments
+    fn aggregate_clauses(
+        &self,
+        filter: Option<Box<SQLExpr>>,
+        order_by: Vec<OrderByExpr>,
+        schema: &DFSchema,
+        ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<(Option<Box<Expr>>, Vec<Expr>)> {
+        let filter = filter
+            .map(|filter| self.sql_expr_to_logical_expr(*filter, schema, ctes))
+            .transpose()?
+            .map(Box::new);
+        let order_by = order_by
+            .into_iter()
+            .map(|e| self.order_by_to_sort_expr(e, schema))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((filter, order_by))
+    }
+
     fn aggregate_fn_to_expr(
         &self,
         fun: aggregates::AggregateFunction,
-        function: sqlparser::ast::Function,
+        args: Vec<FunctionArg>,
         schema: &DFSchema,
     ) -> Result<(aggregates::AggregateFunction, Vec<Expr>)> {
         let args = match fun {
-            aggregates::AggregateFunction::Count => function
-                .args
+            aggregates::AggregateFunction::Count => args
                 .into_iter()
                 .map(|a| match a {
                     FunctionArg::Unnamed(FunctionArgExpr::Expr(SQLExpr::Value(
@@ -2168,13 +3235,12 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     _ => self.sql_fn_arg_to_logical_expr(a, schema, &mut HashMap::new()),
                 })
                 .collect::<Result<Vec<Expr>>>()?,
-            aggregates::AggregateFunction::ApproxMedian => function
-                .args
+            aggregates::AggregateFunction::ApproxMedian => args
                 .into_iter()
                 .map(|a| self.sql_fn_arg_to_logical_expr(a, schema, &mut HashMap::new()))
                 .chain(iter::once(Ok(lit(0.5_f64))))
                 .collect::<Result<Vec<Expr>>>()?,
-            _ => self.function_args_to_expr(function.args, schema)?,
+            _ => self.function_args_to_expr(args, schema)?,
         };
 
         let fun = match fun {
@@ -2187,6 +3253,63 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         Ok((fun, args))
     }
 
+    /// Moves the sort expression of the `WITHIN GROUP (ORDER BY ...)` clause of the
+    /// ordered-set aggregates `percentile_cont` and `mode` into their arguments, as
+    /// they are computed from the values of the sort expression.
+    ///
+    /// Returns the arguments and the remaining `ORDER BY` expressions.
+    fn ordered_set_aggregate_args(
+        fun: &aggregates::AggregateFunction,
+        mut args: Vec<Expr>,
+        order_by: Vec<Expr>,
+    ) -> Result<(Vec<Expr>, Vec<Expr>)> {
+        if order_by.is_empty()
+            || !matches!(
+                fun,
+                aggregates::AggregateFunction::PercentileCont
+                    | aggregates::AggregateFunction::Mode
+            )
+        {
+            return Ok((args, order_by));
+        }
+        let (expr, asc) = match order_by.as_slice() {
+            [Expr::Sort { expr, asc, .. }] => (*expr.clone(), *asc),
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} requires exactly one ORDER BY expression in WITHIN GROUP",
+                    fun
+                )))
+            }
+        };
+        match (fun, args.as_slice()) {
+            (aggregates::AggregateFunction::PercentileCont, [percentile]) => {
+                let percentile = match (percentile, asc) {
+                    (_, true) => percentile.clone(),
+                    // the percentile of the descending values is the complementary
+                    // percentile of the ascending values
+                    (Expr::Literal(ScalarValue::Float64(Some(p))), false) => {
+                        lit(1_f64 - p)
+                    }
+                    _ => {
+                        return Err(DataFusionError::Plan(
+                            "Percentile value for PERCENTILE_CONT ... ORDER BY DESC must be a float literal"
+                                .to_string(),
+                        ))
+                    }
+                };
+                args = vec![expr, percentile];
+            }
+            (aggregates::AggregateFunction::Mode, []) => args = vec![expr],
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "Invalid number of arguments for {} with WITHIN GROUP",
+                    fun
+                )))
+            }
+        }
+        Ok((args, vec![]))
+    }
+
     fn sql_interval_to_literal(
         &self,
         value: String,
@@ -2362,17 +3485,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         // Special case SHOW TABLES
         let variable = ObjectName(variable.to_vec()).to_string();
         if variable.as_str().eq_ignore_ascii_case("tables") {
-            if self.has_table("information_schema", "tables") {
-                let mut rewrite =
-                    DFParser::parse_sql("SELECT * FROM information_schema.tables;")?;
-                assert_eq!(rewrite.len(), 1);
-                self.statement_to_plan(rewrite.pop_front().unwrap())
-            } else {
-                Err(DataFusionError::Plan(
-                    "SHOW TABLES is not supported unless information_schema is enabled"
-                        .to_string(),
-                ))
-            }
+            self.show_tables_to_plan()
         } else {
             Err(DataFusionError::NotImplemented(format!(
                 "SHOW {} not implemented. Supported syntax: SHOW <TABLES>",
@@ -2381,6 +3494,20 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }
     }
 
+    fn show_tables_to_plan(&self) -> Result<LogicalPlan> {
+        if self.has_table("information_schema", "tables") {
+            let mut rewrite =
+                DFParser::parse_sql("SELECT * FROM information_schema.tables;")?;
+            assert_eq!(rewrite.len(), 1);
+            self.statement_to_plan(rewrite.pop_front().unwrap())
+        } else {
+            Err(DataFusionError::Plan(
+                "SHOW TABLES is not supported unless information_schema is enabled"
+                    .to_string(),
+            ))
+        }
+    }
+
     fn show_columns_to_plan(
         &self,
         extended: bool,
@@ -2493,6 +3620,46 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 }
 
 /// Normalize a SQL object name
+/// Renames the columns of the plan of the CTE `name` to `columns`, unless
+/// the CTE does not name its columns
+fn rename_cte_columns(
+    plan: LogicalPlan,
+    name: &str,
+    columns: Vec<Ident>,
+) -> Result<LogicalPlan> {
+    if columns.is_empty() {
+        return Ok(plan);
+    }
+    let fields = plan.schema().fields();
+    if columns.len() != fields.len() {
+        return Err(DataFusionError::Plan(format!(
+            "WITH query {} has {} columns available but {} columns specified",
+            name,
+            fields.len(),
+            columns.len()
+        )));
+    }
+    let expr = fields
+        .iter()
+        .zip(columns)
+        .map(|(field, column)| {
+            Expr::Column(field.qualified_column()).alias(&normalize_ident(&column))
+        })
+        .collect::<Vec<_>>();
+    project_with_alias(plan, expr, Some(name.to_owned()))
+}
+
+/// Returns whether `plan` scans the table `name`
+fn scans_table(plan: &LogicalPlan, name: &str) -> bool {
+    match plan {
+        LogicalPlan::TableScan(scan) => scan.table_name == name,
+        _ => plan
+            .inputs()
+            .into_iter()
+            .any(|input| scans_table(input, name)),
+    }
+}
+
 fn normalize_sql_object_name(sql_object_name: &ObjectName) -> String {
     sql_object_name
         .0
@@ -2502,6 +3669,54 @@ fn normalize_sql_object_name(sql_object_name: &ObjectName) -> String {
         .join(".")
 }
 
+/// Returns the arguments of `expr` if it is a call of `unnest`
+fn unnest_call_args(expr: &SQLExpr) -> Option<&FunctionArguments> {
+    match expr {
+        SQLExpr::Function(function)
+            if function.over.is_none()
+                && normalize_sql_object_name(&function.name) == "unnest" =>
+        {
+            Some(&function.args)
+        }
+        _ => None,
+    }
+}
+
+/// Splits `expr` into the expressions joined by its top level `AND`s
+fn split_sql_conjunction(expr: SQLExpr, accum: &mut Vec<SQLExpr>) {
+    match expr {
+        SQLExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_sql_conjunction(*left, accum);
+            split_sql_conjunction(*right, accum);
+        }
+        other => accum.push(other),
+    }
+}
+
+/// Returns whether `relation` is an `UNNEST(...)` call
+fn is_unnest_relation(relation: &TableFactor) -> bool {
+    match relation {
+        TableFactor::UNNEST { .. } => true,
+        TableFactor::Table {
+            name,
+            args: Some(_),
+            ..
+        } => normalize_sql_object_name(name) == "unnest",
+        _ => false,
+    }
+}
+
+/// Returns whether `relation` can reference the columns of the relations
+/// preceding it in a FROM clause
+fn is_lateral_relation(relation: &TableFactor) -> bool {
+    is_unnest_relation(relation)
+        || matches!(relation, TableFactor::Derived { lateral: true, .. })
+}
+
 /// Remove join expressions from a filter expression
 fn remove_join_expressions(
     expr: &Expr,
@@ -2614,10 +3829,14 @@ pub fn convert_simple_data_type(sql_type: &SQLDataType) -> Result<DataType> {
         SQLDataType::Char(_)
         | SQLDataType::Varchar(_)
         | SQLDataType::Text
-        | SQLDataType::String => Ok(DataType::Utf8),
-        SQLDataType::Timestamp => Ok(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        | SQLDataType::String(_) => Ok(DataType::Utf8),
+        SQLDataType::Timestamp(None, TimezoneInfo::None) => {
+            Ok(DataType::Timestamp(TimeUnit::Nanosecond, None))
+        }
         SQLDataType::Date => Ok(DataType::Date32),
-        SQLDataType::Decimal(precision, scale) => make_decimal_type(*precision, *scale),
+        SQLDataType::Decimal(info) | SQLDataType::Numeric(info) => {
+            make_decimal_type(info)
+        }
         other => Err(DataFusionError::NotImplemented(format!(
             "Unsupported SQL type {:?}",
             other
@@ -2628,7 +3847,10 @@ pub fn convert_simple_data_type(sql_type: &SQLDataType) -> Result<DataType> {
 /// Convert SQL data type to relational representation of data type
 pub fn convert_data_type(sql_type: &SQLDataType) -> Result<DataType> {
     match sql_type {
-        SQLDataType::Array(inner_sql_type) => {
+        SQLDataType::Array(
+            ArrayElemTypeDef::AngleBracket(inner_sql_type)
+            | ArrayElemTypeDef::SquareBracket(inner_sql_type, _),
+        ) => {
             let data_type = convert_simple_data_type(inner_sql_type)?;
 
             Ok(DataType::List(Box::new(Field::new(
@@ -2639,6 +3861,226 @@ pub fn convert_data_type(sql_type: &SQLDataType) -> Result<DataType> {
     }
 }
 
+/// Returns the arguments of a function call, whether they are `DISTINCT`, and the
+/// `ORDER BY` clause in the arguments
+fn function_args(
+    args: FunctionArguments,
+) -> Result<(Vec<FunctionArg>, bool, Vec<OrderByExpr>)> {
+    match args {
+        FunctionArguments::None => Ok((vec![], false, vec![])),
+        FunctionArguments::Subquery(query) => Err(DataFusionError::NotImplemented(
+            format!("Unsupported function argument {}", query),
+        )),
+        FunctionArguments::List(list) => {
+            let distinct =
+                matches!(list.duplicate_treatment, Some(DuplicateTreatment::Distinct));
+            let mut order_by = vec![];
+            for clause in list.clauses {
+                match clause {
+                    FunctionArgumentClause::OrderBy(exprs) => order_by.extend(exprs),
+                    clause => {
+                        return Err(DataFusionError::NotImplemented(format!(
+                            "Unsupported clause {} in function arguments",
+                            clause
+                        )))
+                    }
+                }
+            }
+            Ok((list.args, distinct, order_by))
+        }
+    }
+}
+
+/// Checks that the leftmost expressions of the `ORDER BY` clause of a select with a
+/// `DISTINCT ON (exprs)` clause are `exprs`, in any order, as PostgreSQL requires
+fn check_distinct_on_order(distinct_on: &[Expr], order_by: &[Expr]) -> Result<()> {
+    let mut remaining = distinct_on.to_vec();
+    for sort in order_by {
+        if remaining.is_empty() {
+            break;
+        }
+        let expr = match sort {
+            Expr::Sort { expr, .. } => expr.as_ref(),
+            expr => expr,
+        };
+        if !distinct_on.contains(expr) {
+            return Err(DataFusionError::Plan(
+                "SELECT DISTINCT ON expressions must match initial ORDER BY expressions"
+                    .to_string(),
+            ));
+        }
+        remaining.retain(|e| e != expr);
+    }
+    Ok(())
+}
+
+/// Replaces the references of the window functions of `select` and of the `ORDER BY`
+/// clause of its query to the windows of its `WINDOW` clause, `OVER w` and
+/// `OVER (w ...)`, with their definitions
+fn resolve_named_windows(
+    select: &mut Select,
+    order_by: &mut [OrderByExpr],
+) -> Result<()> {
+    let mut windows = HashMap::new();
+    for NamedWindowDefinition(name, window) in std::mem::take(&mut select.named_window) {
+        let spec = match window {
+            NamedWindowExpr::NamedWindow(base) => named_window(&windows, &base)?.clone(),
+            NamedWindowExpr::WindowSpec(spec) => resolve_window_spec(&windows, spec)?,
+        };
+        if windows.insert(normalize_ident(&name), spec).is_some() {
+            return Err(DataFusionError::Plan(format!(
+                "Window {} is already defined",
+                name
+            )));
+        }
+    }
+    if windows.is_empty() {
+        return Ok(());
+    }
+
+    let mut visitor = NamedWindows { windows, depth: 0 };
+    let flows = [
+        select.projection.visit(&mut visitor),
+        select.qualify.visit(&mut visitor),
+        order_by
+            .iter_mut()
+            .try_for_each(|order_by| order_by.visit(&mut visitor)),
+    ];
+    match flows.into_iter().find(ControlFlow::is_break) {
+        Some(ControlFlow::Break(e)) => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the definition of the window `name` among `windows`
+fn named_window<'a>(
+    windows: &'a HashMap<String, WindowSpec>,
+    name: &Ident,
+) -> Result<&'a WindowSpec> {
+    windows
+        .get(&normalize_ident(name))
+        .ok_or_else(|| DataFusionError::Plan(format!("Unknown window {}", name)))
+}
+
+/// Returns `spec` with the window it is defined from, if any, copied in, following
+/// the rules of PostgreSQL: the copied window has no frame, and `spec` can only add
+/// an `ORDER BY` clause when it has none
+fn resolve_window_spec(
+    windows: &HashMap<String, WindowSpec>,
+    spec: WindowSpec,
+) -> Result<WindowSpec> {
+    let name = match &spec.window_name {
+        Some(name) => name,
+        None => return Ok(spec),
+    };
+    let base = named_window(windows, name)?;
+    if !spec.partition_by.is_empty() {
+        return Err(DataFusionError::Plan(format!(
+            "Cannot override PARTITION BY clause of window {}",
+            name
+        )));
+    }
+    if !spec.order_by.is_empty() && !base.order_by.is_empty() {
+        return Err(DataFusionError::Plan(format!(
+            "Cannot override ORDER BY clause of window {}",
+            name
+        )));
+    }
+    if base.window_frame.is_some() {
+        return Err(DataFusionError::Plan(format!(
+            "Cannot copy window {} because it has a frame clause",
+            name
+        )));
+    }
+    Ok(WindowSpec {
+        window_name: None,
+        partition_by: base.partition_by.clone(),
+        order_by: if spec.order_by.is_empty() {
+            base.order_by.clone()
+        } else {
+            spec.order_by
+        },
+        window_frame: spec.window_frame,
+    })
+}
+
+/// Visitor replacing the references to named windows of a select with their
+/// definitions, outside of its subqueries, which have their own `WINDOW` clause
+struct NamedWindows {
+    windows: HashMap<String, WindowSpec>,
+    depth: usize,
+}
+
+impl VisitorMut for NamedWindows {
+    type Break = DataFusionError;
+
+    fn pre_visit_query(&mut self, _query: &mut Query) -> ControlFlow<DataFusionError> {
+        self.depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &mut Query) -> ControlFlow<DataFusionError> {
+        self.depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &mut SQLExpr) -> ControlFlow<DataFusionError> {
+        let over = match expr {
+            SQLExpr::Function(function) if self.depth == 0 => &mut function.over,
+            _ => return ControlFlow::Continue(()),
+        };
+        let spec = match over.take() {
+            Some(WindowType::NamedWindow(name)) => {
+                named_window(&self.windows, &name).cloned()
+            }
+            Some(WindowType::WindowSpec(spec)) => {
+                resolve_window_spec(&self.windows, spec)
+            }
+            None => return ControlFlow::Continue(()),
+        };
+        match spec {
+            Ok(spec) => {
+                *over = Some(WindowType::WindowSpec(spec));
+                ControlFlow::Continue(())
+            }
+            Err(e) => ControlFlow::Break(e),
+        }
+    }
+}
+
+/// Visitor collecting the names and arguments of the table function calls of
+/// a statement
+struct TableFunctionCalls {
+    calls: Vec<(ObjectName, Vec<FunctionArg>)>,
+}
+
+impl Visitor for TableFunctionCalls {
+    type Break = DataFusionError;
+
+    fn pre_visit_table_factor(
+        &mut self,
+        relation: &TableFactor,
+    ) -> ControlFlow<DataFusionError> {
+        match relation {
+            _ if is_unnest_relation(relation) => {}
+            TableFactor::Table {
+                name,
+                args: Some(args),
+                ..
+            } => self.calls.push((name.clone(), args.clone())),
+            TableFactor::TableFunction {
+                expr: SQLExpr::Function(function),
+                ..
+            } => match function_args(function.args.clone()) {
+                Ok((args, ..)) => self.calls.push((function.name.clone(), args)),
+                Err(e) => return ControlFlow::Break(e),
+            },
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
 // Parse number in sql string, convert to Expr::Literal
 fn parse_sql_number(n: &str) -> Result<Expr> {
     match n.parse::<i64>() {
@@ -3851,6 +5293,64 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn left_equijoin_with_condition_on_both_sides() {
+        let sql = "SELECT id, order_id \
+            FROM person \
+            LEFT JOIN orders \
+            ON id = customer_id AND order_id > age";
+        let expected = "Projection: #person.id, #orders.order_id\
+        \n  Left Join: #person.id = #orders.customer_id Filter: #orders.order_id > #person.age\
+        \n    TableScan: person projection=None\
+        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn left_join_without_equijoin_keys() {
+        let sql = "SELECT id, order_id \
+            FROM person \
+            LEFT JOIN orders \
+            ON order_id BETWEEN age AND id";
+        let expected = "Projection: #person.id, #orders.order_id\
+        \n  Left Join: Filter: #orders.order_id BETWEEN #person.age AND #person.id\
+        \n    TableScan: person projection=None\
+        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn asof_join() {
+        let sql = "SELECT id, order_id \
+            FROM person p \
+            ASOF JOIN orders o \
+            MATCH_CONDITION(o.qty <= p.age) \
+            ON p.id = o.customer_id";
+        let expected = "Projection: #p.id, #o.order_id\
+        \n  AsOf Join: #p.id = #o.customer_id Filter: #p.age >= #o.qty\
+        \n    SubqueryAlias: p\
+        \n      TableScan: person projection=None\
+        \n    SubqueryAlias: o\
+        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn asof_join_with_non_equality_in_on_clause() {
+        let sql = "SELECT id, order_id \
+            FROM person p \
+            ASOF JOIN orders o \
+            MATCH_CONDITION(p.age >= o.qty) \
+            ON p.id > o.customer_id";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert!(
+            err.to_string()
+                .contains("The ON clause of an ASOF join only supports equalities"),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn join_with_table_name() {
         let sql = "SELECT id, order_id \
@@ -4124,9 +5624,23 @@ mod tests {
     #[test]
     fn over_order_by_with_window_frame_range_value_check() {
         let sql = "SELECT order_id, MAX(qty) OVER (ORDER BY order_id RANGE 3 PRECEDING) from orders";
+        let expected = "\
+        Projection: #orders.order_id, #MAX(orders.qty) ORDER BY [#orders.order_id ASC NULLS LAST] RANGE BETWEEN 3 PRECEDING AND CURRENT ROW\
+        \n  WindowAggr: windowExpr=[[MAX(#orders.qty) ORDER BY [#orders.order_id ASC NULLS LAST] RANGE BETWEEN 3 PRECEDING AND CURRENT ROW]]\
+        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT order_id, MAX(qty) OVER (ORDER BY order_id RANGE -3 PRECEDING) from orders";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Invalid window frame offset -3 for RANGE frame: it must be a non-negative number or interval\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "SELECT order_id, MAX(qty) OVER (ORDER BY order_id ROWS qty PRECEDING) from orders";
         let err = logical_plan(sql).expect_err("query should have failed");
         assert_eq!(
-            "NotImplemented(\"With WindowFrameUnits=RANGE, the bound cannot be 3 PRECEDING or FOLLOWING at the moment\")",
+            "Plan(\"Window frame offset must be a constant, got #qty\")",
             format!("{:?}", err)
         );
     }
@@ -4164,6 +5678,111 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn over_qualify() {
+        let sql = "SELECT order_id, qty FROM orders \
+            QUALIFY ROW_NUMBER() OVER (PARTITION BY order_id ORDER BY qty DESC) = 1";
+        let expected = "\
+        Projection: #orders.order_id, #orders.qty\
+        \n  Filter: #ROW_NUMBER() PARTITION BY [#orders.order_id] ORDER BY [#orders.qty DESC NULLS FIRST] = Int64(1)\
+        \n    WindowAggr: windowExpr=[[ROW_NUMBER() PARTITION BY [#orders.order_id] ORDER BY [#orders.qty DESC NULLS FIRST]]]\
+        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn over_named_windows() {
+        // windows defined from previous ones, and referred to by the QUALIFY clause
+        let sql =
+            "SELECT order_id, MAX(qty) OVER w1, MIN(qty) OVER (w1 ROWS 1 PRECEDING) \
+            FROM orders WINDOW w1 AS (PARTITION BY order_id), W2 AS (w1 ORDER BY qty) \
+            QUALIFY ROW_NUMBER() OVER w2 = 1";
+        let expected = "\
+        Projection: #orders.order_id, #MAX(orders.qty) PARTITION BY [#orders.order_id], #MIN(orders.qty) PARTITION BY [#orders.order_id] ROWS BETWEEN 1 PRECEDING AND CURRENT ROW\
+        \n  Filter: #ROW_NUMBER() PARTITION BY [#orders.order_id] ORDER BY [#orders.qty ASC NULLS LAST] = Int64(1)\
+        \n    WindowAggr: windowExpr=[[MAX(#orders.qty) PARTITION BY [#orders.order_id], MIN(#orders.qty) PARTITION BY [#orders.order_id] ROWS BETWEEN 1 PRECEDING AND CURRENT ROW]]\
+        \n      WindowAggr: windowExpr=[[ROW_NUMBER() PARTITION BY [#orders.order_id] ORDER BY [#orders.qty ASC NULLS LAST]]]\
+        \n        TableScan: orders projection=None";
+        quick_test(sql, expected);
+
+        // the names are local to their select
+        let sql = "SELECT * FROM (SELECT MAX(qty) OVER w FROM orders WINDOW w AS (ORDER BY qty)) \
+            UNION ALL SELECT MAX(qty) OVER w FROM orders WINDOW w AS (ORDER BY order_id)";
+        let expected = "\
+        Union\
+        \n  Projection: #MAX(orders.qty) ORDER BY [#orders.qty ASC NULLS LAST]\
+        \n    Projection: #MAX(orders.qty) ORDER BY [#orders.qty ASC NULLS LAST]\
+        \n      Projection: #MAX(orders.qty) ORDER BY [#orders.qty ASC NULLS LAST]\
+        \n        WindowAggr: windowExpr=[[MAX(#orders.qty) ORDER BY [#orders.qty ASC NULLS LAST]]]\
+        \n          TableScan: orders projection=None\
+        \n  Projection: #MAX(orders.qty) ORDER BY [#orders.order_id ASC NULLS LAST]\
+        \n    WindowAggr: windowExpr=[[MAX(#orders.qty) ORDER BY [#orders.order_id ASC NULLS LAST]]]\
+        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+
+        // and the ORDER BY clause of the query can refer to them
+        let sql = "SELECT order_id, MAX(qty) OVER w FROM orders \
+            WINDOW w AS (PARTITION BY order_id) ORDER BY MAX(qty) OVER w";
+        let expected = "\
+        Sort: #MAX(orders.qty) PARTITION BY [#orders.order_id] ASC NULLS LAST\
+        \n  Projection: #orders.order_id, #MAX(orders.qty) PARTITION BY [#orders.order_id]\
+        \n    WindowAggr: windowExpr=[[MAX(#orders.qty) PARTITION BY [#orders.order_id]]]\
+        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn over_named_windows_errors() {
+        let cases = [
+            ("SELECT MAX(qty) OVER w FROM orders", "Unknown window w"),
+            (
+                "SELECT MAX(qty) OVER (w ORDER BY qty) FROM orders WINDOW v AS ()",
+                "Unknown window w",
+            ),
+            (
+                "SELECT MAX(qty) OVER w FROM orders WINDOW w AS (), w AS ()",
+                "Window w is already defined",
+            ),
+            (
+                "SELECT MAX(qty) OVER (w PARTITION BY qty) FROM orders \
+                WINDOW w AS (PARTITION BY order_id)",
+                "Cannot override PARTITION BY clause of window w",
+            ),
+            (
+                "SELECT MAX(qty) OVER (w ORDER BY qty) FROM orders \
+                WINDOW w AS (ORDER BY order_id)",
+                "Cannot override ORDER BY clause of window w",
+            ),
+            (
+                "SELECT MAX(qty) OVER (w) FROM orders \
+                WINDOW w AS (ORDER BY order_id ROWS 1 PRECEDING)",
+                "Cannot copy window w because it has a frame clause",
+            ),
+            (
+                "SELECT (SELECT MAX(qty) OVER w FROM orders) FROM orders \
+                WINDOW w AS (ORDER BY order_id)",
+                "Unknown window w",
+            ),
+        ];
+        for (sql, expected) in cases {
+            let err = logical_plan(sql).expect_err("query should have failed");
+            assert_contains!(err.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn select_distinct_on() {
+        let sql = "SELECT DISTINCT ON (order_id) order_id, qty FROM orders \
+            ORDER BY order_id, qty DESC";
+        let expected = "\
+        Sort: #orders.order_id ASC NULLS LAST, #orders.qty DESC NULLS FIRST\
+        \n  Projection: #orders.order_id, #orders.qty\
+        \n    Filter: #ROW_NUMBER() PARTITION BY [#orders.order_id] ORDER BY [#orders.order_id ASC NULLS LAST, #orders.qty DESC NULLS FIRST] = UInt64(1)\
+        \n      WindowAggr: windowExpr=[[ROW_NUMBER() PARTITION BY [#orders.order_id] ORDER BY [#orders.order_id ASC NULLS LAST, #orders.qty DESC NULLS FIRST]]]\
+        \n        TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
     /// psql result
     /// ```
     ///                                     QUERY PLAN
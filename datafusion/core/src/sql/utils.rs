@@ -18,7 +18,7 @@
 //! SQL Utility Functions
 
 use arrow::datatypes::{DataType, DECIMAL_MAX_PRECISION};
-use sqlparser::ast::Ident;
+use sqlparser::ast::{ExactNumberInfo, Ident};
 
 use crate::error::{DataFusionError, Result};
 use crate::logical_plan::{Expr, LogicalPlan};
@@ -164,6 +164,8 @@ where
                 fun,
                 args,
                 distinct,
+                filter,
+                order_by,
             } => Ok(Expr::AggregateFunction {
                 fun: fun.clone(),
                 args: args
@@ -171,6 +173,16 @@ where
                     .map(|e| clone_with_replacement(e, replacement_fn))
                     .collect::<Result<Vec<Expr>>>()?,
                 distinct: *distinct,
+                filter: match filter {
+                    Some(filter) => {
+                        Some(Box::new(clone_with_replacement(filter, replacement_fn)?))
+                    }
+                    None => None,
+                },
+                order_by: order_by
+                    .iter()
+                    .map(|e| clone_with_replacement(e, replacement_fn))
+                    .collect::<Result<Vec<Expr>>>()?,
             }),
             Expr::WindowFunction {
                 fun,
@@ -192,7 +204,7 @@ where
                     .iter()
                     .map(|e| clone_with_replacement(e, replacement_fn))
                     .collect::<Result<Vec<_>>>()?,
-                window_frame: *window_frame,
+                window_frame: window_frame.clone(),
             }),
             Expr::AggregateUDF { fun, args } => Ok(Expr::AggregateUDF {
                 fun: fun.clone(),
@@ -446,10 +458,14 @@ pub(crate) fn window_expr_common_partition_keys(
 
 /// Returns a validated `DataType` for the specified precision and
 /// scale
-pub(crate) fn make_decimal_type(
-    precision: Option<u64>,
-    scale: Option<u64>,
-) -> Result<DataType> {
+pub(crate) fn make_decimal_type(info: &ExactNumberInfo) -> Result<DataType> {
+    let (precision, scale) = match *info {
+        ExactNumberInfo::None => (None, None),
+        ExactNumberInfo::Precision(precision) => (Some(precision), None),
+        ExactNumberInfo::PrecisionAndScale(precision, scale) => {
+            (Some(precision), Some(scale))
+        }
+    };
     match (precision, scale) {
         (None, _) | (_, None) => {
             return Err(DataFusionError::Internal(format!(
@@ -119,9 +119,12 @@ pub fn partitioned_csv_config(
         file_schema: schema,
         file_groups,
         statistics: Default::default(),
+        pruned_files: 0,
         projection: None,
         limit: None,
         table_partition_cols: vec![],
+        output_ordering: None,
+        bucket_columns: None,
     })
 }
 
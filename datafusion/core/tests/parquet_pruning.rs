@@ -428,9 +428,10 @@ async fn prune_int32_eq_in_list_2() {
         .await;
 
     println!("{}", output.description());
-    // This should prune out groups without error
-    assert_eq!(output.predicate_evaluation_errors(), Some(0));
-    assert_eq!(output.row_groups_pruned(), Some(4));
+    // The file statistics show that no row can match, so the whole file is
+    // pruned before any row group is read
+    assert_eq!(output.files_pruned_by_statistics(), Some(1));
+    assert_eq!(output.row_groups_pruned(), None);
     assert_eq!(output.result_rows, 0, "{}", output.description());
 }
 
@@ -505,6 +506,11 @@ impl TestOutput {
         self.metric_value("row_groups_pruned")
     }
 
+    /// The number of files skipped using their statistics before the scan
+    fn files_pruned_by_statistics(&self) -> Option<usize> {
+        self.metric_value("files_pruned_by_statistics")
+    }
+
     fn description(&self) -> String {
         format!(
             "Input:\n{}\nQuery:\n{}\nOutput:\n{}\nMetrics:\n{}",
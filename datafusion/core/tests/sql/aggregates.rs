@@ -1347,6 +1347,225 @@ async fn query_count_distinct() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn query_multiple_distinct_arguments() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("k", DataType::Utf8, false),
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Int32, true),
+        Field::new("c", DataType::Int64, true),
+    ]));
+
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec!["x", "x", "x", "x", "y"])),
+            Arc::new(Int32Array::from(vec![
+                Some(1),
+                Some(1),
+                Some(2),
+                None,
+                Some(3),
+            ])),
+            Arc::new(Int32Array::from(vec![
+                Some(1),
+                Some(2),
+                Some(2),
+                Some(2),
+                None,
+            ])),
+            Arc::new(Int64Array::from(vec![
+                Some(5),
+                Some(5),
+                Some(7),
+                None,
+                Some(1),
+            ])),
+        ],
+    )?;
+
+    let table = MemTable::try_new(schema, vec![vec![data]])?;
+    let ctx = SessionContext::new();
+    ctx.register_table("test", Arc::new(table))?;
+
+    let sql =
+        "SELECT k, COUNT(DISTINCT a), COUNT(DISTINCT b), SUM(DISTINCT c), COUNT(*) \
+               FROM test GROUP BY k ORDER BY k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+------------------------+------------------------+----------------------+-----------------+",
+        "| k | COUNT(DISTINCT test.a) | COUNT(DISTINCT test.b) | SUM(DISTINCT test.c) | COUNT(UInt8(1)) |",
+        "+---+------------------------+------------------------+----------------------+-----------------+",
+        "| x | 2                      | 2                      | 12                   | 4               |",
+        "| y | 1                      | 0                      | 1                    | 1               |",
+        "+---+------------------------+------------------------+----------------------+-----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the global aggregate of an empty input still returns a single row
+    let sql =
+        "SELECT COUNT(DISTINCT a), COUNT(DISTINCT b), COUNT(*) FROM test WHERE k = 'z'";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+------------------------+------------------------+-----------------+",
+        "| COUNT(DISTINCT test.a) | COUNT(DISTINCT test.b) | COUNT(UInt8(1)) |",
+        "+------------------------+------------------------+-----------------+",
+        "| 0                      | 0                      | 0               |",
+        "+------------------------+------------------------+-----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_aggregate_filter_and_order_by() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("k", DataType::Utf8, false),
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Int32, true),
+    ]));
+
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec!["x", "x", "x", "y", "y"])),
+            Arc::new(Int32Array::from(vec![
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                None,
+            ])),
+            Arc::new(Int32Array::from(vec![
+                Some(3),
+                None,
+                Some(1),
+                Some(2),
+                Some(1),
+            ])),
+        ],
+    )?;
+
+    let table = MemTable::try_new(schema, vec![vec![data]])?;
+    let ctx = SessionContext::new();
+    ctx.register_table("test", Arc::new(table))?;
+
+    let sql =
+        "SELECT k, SUM(a) FILTER (WHERE b > 1), COUNT(*) FILTER (WHERE a IS NOT NULL), \
+               ARRAY_AGG(a ORDER BY b DESC NULLS LAST) \
+               FROM test GROUP BY k ORDER BY k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+-----------------------------------------------+---------------------------------------------------+-----------------------------------------------------+",
+        "| k | SUM(test.a) FILTER (WHERE test.b Gt Int64(1)) | COUNT(UInt8(1)) FILTER (WHERE test.a IS NOT NULL) | ARRAYAGG(test.a) ORDER BY [#test.b DESC NULLS LAST] |",
+        "+---+-----------------------------------------------+---------------------------------------------------+-----------------------------------------------------+",
+        "| x | 1                                             | 3                                                 | [1, 3, 2]                                           |",
+        "| y | 4                                             | 1                                                 | [4, ]                                               |",
+        "+---+-----------------------------------------------+---------------------------------------------------+-----------------------------------------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // a filtered distinct aggregate next to an unfiltered one
+    let sql =
+        "SELECT COUNT(DISTINCT a), COUNT(DISTINCT a) FILTER (WHERE b = 1) FROM test";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+------------------------+---------------------------------------------------------+",
+        "| COUNT(DISTINCT test.a) | COUNT(DISTINCT test.a) FILTER (WHERE test.b = Int64(1)) |",
+        "+------------------------+---------------------------------------------------------+",
+        "| 4                      | 1                                                       |",
+        "+------------------------+---------------------------------------------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_postgres_aggregates() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("k", DataType::Utf8, false),
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Boolean, true),
+        Field::new("s", DataType::Utf8, false),
+    ]));
+
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec!["x", "x", "x", "x", "y", "y"])),
+            Arc::new(Int32Array::from(vec![
+                Some(1),
+                Some(2),
+                Some(2),
+                Some(7),
+                Some(4),
+                None,
+            ])),
+            Arc::new(BooleanArray::from(vec![
+                Some(true),
+                Some(true),
+                Some(false),
+                None,
+                Some(true),
+                Some(true),
+            ])),
+            Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e", "f"])),
+        ],
+    )?;
+
+    let table = MemTable::try_new(schema, vec![vec![data]])?;
+    let ctx = SessionContext::new();
+    ctx.register_table("test", Arc::new(table))?;
+
+    let sql = "SELECT k, BOOL_AND(b) AS bool_and, BOOL_OR(b) AS bool_or, \
+               BIT_AND(a) AS bit_and, BIT_OR(a) AS bit_or, BIT_XOR(a) AS bit_xor \
+               FROM test GROUP BY k ORDER BY k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----------+---------+---------+--------+---------+",
+        "| k | bool_and | bool_or | bit_and | bit_or | bit_xor |",
+        "+---+----------+---------+---------+--------+---------+",
+        "| x | false    | true    | 0       | 7      | 6       |",
+        "| y | true     | true    | 4       | 4      | 4       |",
+        "+---+----------+---------+---------+--------+---------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT k, STRING_AGG(s, ',' ORDER BY a DESC, s) AS string_agg, \
+               MEDIAN(a) AS median, \
+               PERCENTILE_CONT(0.25) WITHIN GROUP (ORDER BY a) AS p25, \
+               MODE() WITHIN GROUP (ORDER BY a) AS mode, \
+               FIRST_VALUE(s ORDER BY a NULLS LAST) AS first, \
+               LAST_VALUE(s ORDER BY a NULLS LAST) AS last \
+               FROM test GROUP BY k ORDER BY k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+------------+--------+------+------+-------+------+",
+        "| k | string_agg | median | p25  | mode | first | last |",
+        "+---+------------+--------+------+------+-------+------+",
+        "| x | d,b,c,a    | 2      | 1.75 | 2    | a     | d    |",
+        "| y | f,e        | 4      | 4    | 4    | e     | f    |",
+        "+---+------------+--------+------+------+-------+------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the values of y = 2x + 1 are on a line
+    let sql = "SELECT REGR_SLOPE(2 * a + 1, a) AS slope, \
+               REGR_INTERCEPT(2 * a + 1, a) AS intercept, \
+               REGR_COUNT(2 * a + 1, a) AS count \
+               FROM test";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+-----------+-------+",
+        "| slope | intercept | count |",
+        "+-------+-----------+-------+",
+        "| 2     | 1         | 5     |",
+        "+-------+-----------+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
 async fn run_count_distinct_integers_aggregated_scenario(
     partitions: Vec<Vec<(&str, u64)>>,
 ) -> Result<Vec<RecordBatch>> {
@@ -0,0 +1,128 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+use arrow::error::Result as ArrowResult;
+use datafusion::datasource::MemTable;
+use datafusion::physical_plan::file_format::ArrowExec;
+use tempfile::TempDir;
+
+/// Write 100 rows of `(a: i, b: i % 5)` as Arrow IPC files in batches of 10
+/// rows to `tmp_dir`, returning their directory
+async fn write_test_data(tmp_dir: &TempDir) -> Result<String> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Int32, false),
+    ]));
+    let batches = (0..10)
+        .map(|i| {
+            let a = (10 * i..10 * i + 10).collect::<Vec<_>>();
+            let b = a.iter().map(|a| a % 5).collect::<Vec<_>>();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+            )
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+    let ctx = SessionContext::new();
+    ctx.register_table("data", Arc::new(MemTable::try_new(schema, vec![batches])?))?;
+
+    let out_dir = tmp_dir.path().join("data");
+    let out_dir = out_dir.to_str().unwrap().to_owned();
+    ctx.table("data")?.write_arrow(&out_dir).await?;
+    Ok(out_dir)
+}
+
+#[tokio::test]
+async fn arrow_query() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let path = write_test_data(&tmp_dir).await?;
+
+    let ctx = SessionContext::new();
+    ctx.register_arrow("t", &path, ArrowReadOptions::default())
+        .await?;
+    let sql = "SELECT b, count(*), sum(a) FROM t GROUP BY b ORDER BY b";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+-----------------+----------+",
+        "| b | COUNT(UInt8(1)) | SUM(t.a) |",
+        "+---+-----------------+----------+",
+        "| 0 | 20              | 950      |",
+        "| 1 | 20              | 970      |",
+        "| 2 | 20              | 990      |",
+        "| 3 | 20              | 1010     |",
+        "| 4 | 20              | 1030     |",
+        "+---+-----------------+----------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = format!(
+        "CREATE EXTERNAL TABLE t2 STORED AS ARROW LOCATION '{}'",
+        path
+    );
+    ctx.sql(&sql).await?.collect().await?;
+    let actual = execute_to_batches(&ctx, "SELECT a FROM t2 LIMIT 3").await;
+    assert_eq!(actual.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+    let df = ctx.read_arrow(&path, ArrowReadOptions::default()).await?;
+    assert_eq!(
+        df.collect()
+            .await?
+            .iter()
+            .map(|b| b.num_rows())
+            .sum::<usize>(),
+        100
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn arrow_scan_is_split_into_byte_ranges() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let path = write_test_data(&tmp_dir).await?;
+
+    let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(4));
+    ctx.register_arrow("t", &path, ArrowReadOptions::default())
+        .await?;
+    let sql = "SELECT count(*), sum(a) FROM t WHERE b > 1";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+
+    let mut scans = vec![];
+    let mut stack = vec![plan.clone()];
+    while let Some(plan) = stack.pop() {
+        if let Some(exec) = plan.as_any().downcast_ref::<ArrowExec>() {
+            scans.push(exec.base_config().file_groups.clone());
+        }
+        stack.extend(plan.children());
+    }
+    assert_eq!(scans.len(), 1);
+    assert_eq!(scans[0].len(), 4);
+    assert!(scans[0].iter().flatten().all(|file| file.range.is_some()));
+
+    let actual = collect(plan, ctx.task_ctx()).await?;
+    let expected = vec![
+        "+-----------------+----------+",
+        "| COUNT(UInt8(1)) | SUM(t.a) |",
+        "+-----------------+----------+",
+        "| 60              | 3030     |",
+        "+-----------------+----------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
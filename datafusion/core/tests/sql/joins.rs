@@ -16,7 +16,10 @@
 // under the License.
 
 use super::*;
+use datafusion::datasource::listing::BucketSpec;
+use datafusion::datasource::MemTable;
 use datafusion::from_slice::FromSlice;
+use datafusion::physical_plan::bucket_hash::{bucket_of, create_bucket_hashes};
 
 #[tokio::test]
 async fn equijoin() -> Result<()> {
@@ -149,15 +152,190 @@ async fn equijoin_right_and_condition_from_left() -> Result<()> {
 }
 
 #[tokio::test]
-async fn equijoin_and_unsupported_condition() -> Result<()> {
+async fn equijoin_left_and_condition_from_left() -> Result<()> {
     let ctx = create_join_context("t1_id", "t2_id")?;
     let sql =
-        "SELECT t1_id, t1_name, t2_name FROM t1 LEFT JOIN t2 ON t1_id = t2_id AND t1_id >= '44' ORDER BY t1_id";
-    let res = ctx.create_logical_plan(sql);
+        "SELECT t1_id, t1_name, t2_name FROM t1 LEFT JOIN t2 ON t1_id = t2_id AND t1_id >= 44 ORDER BY t1_id";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+---------+---------+",
+        "| t1_id | t1_name | t2_name |",
+        "+-------+---------+---------+",
+        "| 11    | a       |         |",
+        "| 22    | b       |         |",
+        "| 33    | c       |         |",
+        "| 44    | d       | x       |",
+        "+-------+---------+---------+",
+    ];
+    assert_batches_eq!(expected, &actual);
 
-    assert!(res.is_err());
-    assert_eq!(format!("{}", res.unwrap_err()), "This feature is not implemented: Unsupported expressions in Left JOIN: [#t1_id >= Utf8(\"44\")]");
+    Ok(())
+}
 
+#[tokio::test]
+async fn left_join_with_equijoin_keys_and_filter() -> Result<()> {
+    let ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT t1_id, t2_name FROM t1 LEFT JOIN t2 \
+        ON t1_id = t2_id AND t1_id + t2_id > 40 ORDER BY t1_id";
+
+    let plan = ctx.create_logical_plan(&("EXPLAIN ".to_owned() + sql))?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let task_ctx = ctx.task_ctx();
+    let actual = collect(plan, task_ctx).await?;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)?.to_string();
+    assert_contains!(&formatted, "HashJoinExec");
+    assert_contains!(&formatted, "filter=");
+    assert_not_contains!(&formatted, "NestedLoopJoinExec");
+
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+---------+",
+        "| t1_id | t2_name |",
+        "+-------+---------+",
+        "| 11    |         |",
+        "| 22    | y       |",
+        "| 33    |         |",
+        "| 44    | x       |",
+        "+-------+---------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn left_join_without_equijoin_keys() -> Result<()> {
+    let ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT t1_id, t2_id FROM t1 LEFT JOIN t2 \
+        ON t2_id BETWEEN t1_id AND t1_id + 10 ORDER BY t1_id";
+
+    let plan = ctx.create_logical_plan(&("EXPLAIN ".to_owned() + sql))?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let task_ctx = ctx.task_ctx();
+    let actual = collect(plan, task_ctx).await?;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)?.to_string();
+    assert_contains!(&formatted, "NestedLoopJoinExec: join_type=Left");
+
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+-------+",
+        "| t1_id | t2_id |",
+        "+-------+-------+",
+        "| 11    | 11    |",
+        "| 22    | 22    |",
+        "| 33    |       |",
+        "| 44    | 44    |",
+        "+-------+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn full_join_without_equijoin_keys() -> Result<()> {
+    let ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT t1_id, t2_id FROM t1 FULL JOIN t2 ON t1_id > t2_id + 20";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+-------+",
+        "| t1_id | t2_id |",
+        "+-------+-------+",
+        "|       | 44    |",
+        "|       | 55    |",
+        "| 11    |       |",
+        "| 22    |       |",
+        "| 33    | 11    |",
+        "| 44    | 11    |",
+        "| 44    | 22    |",
+        "+-------+-------+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn asof_join() -> Result<()> {
+    let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(4));
+    let register = |name: &str, rows: Vec<(&str, i64, i64)>| -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("ts", DataType::Int64, false),
+            Field::new("price", DataType::Int64, false),
+        ]));
+        let partitions = rows
+            .chunks(2)
+            .map(|chunk| {
+                let symbols = StringArray::from_iter_values(chunk.iter().map(|r| r.0));
+                let ts = Int64Array::from_iter_values(chunk.iter().map(|r| r.1));
+                let prices = Int64Array::from_iter_values(chunk.iter().map(|r| r.2));
+                Ok(vec![RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(symbols), Arc::new(ts), Arc::new(prices)],
+                )?])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let table = MemTable::try_new(schema, partitions)?;
+        ctx.register_table(name, Arc::new(table))?;
+        Ok(())
+    };
+    register(
+        "trades",
+        vec![("a", 10, 100), ("b", 10, 200), ("a", 25, 101), ("a", 5, 99)],
+    )?;
+    register(
+        "quotes",
+        vec![
+            ("a", 20, 1),
+            ("a", 10, 2),
+            ("b", 15, 3),
+            ("a", 8, 4),
+            ("c", 1, 5),
+        ],
+    )?;
+
+    let sql = "EXPLAIN SELECT t.symbol, t.ts, q.ts FROM trades t \
+        ASOF JOIN quotes q MATCH_CONDITION(t.ts >= q.ts) ON t.symbol = q.symbol";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let plan = arrow::util::pretty::pretty_format_batches(&actual)?.to_string();
+    assert_contains!(
+        &plan,
+        "AsOf Join: #t.symbol = #q.symbol Filter: #t.ts >= #q.ts"
+    );
+    assert_contains!(&plan, "SortMergeJoin: join_type=AsOf");
+    assert_contains!(&plan, "match_condition=ts@1 >= ts@1");
+
+    // each trade with the latest quote at or before it
+    let sql = "SELECT t.symbol, t.ts, t.price, q.ts, q.price FROM trades t \
+        ASOF JOIN quotes q MATCH_CONDITION(t.ts >= q.ts) ON t.symbol = q.symbol";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+--------+----+-------+----+-------+",
+        "| symbol | ts | price | ts | price |",
+        "+--------+----+-------+----+-------+",
+        "| a      | 10 | 100   | 10 | 2     |",
+        "| a      | 25 | 101   | 20 | 1     |",
+        "| a      | 5  | 99    |    |       |",
+        "| b      | 10 | 200   |    |       |",
+        "+--------+----+-------+----+-------+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    // each trade with the first quote strictly after it, regardless of symbol
+    let sql = "SELECT t.symbol, t.ts, q.symbol, q.ts FROM trades t \
+        ASOF JOIN quotes q MATCH_CONDITION(q.ts > t.ts)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+--------+----+--------+----+",
+        "| symbol | ts | symbol | ts |",
+        "+--------+----+--------+----+",
+        "| a      | 10 | b      | 15 |",
+        "| a      | 25 |        |    |",
+        "| a      | 5  | a      | 8  |",
+        "| b      | 10 | b      | 15 |",
+        "+--------+----+--------+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
     Ok(())
 }
 
@@ -1131,3 +1309,178 @@ async fn join_partitioned() -> Result<()> {
 
     Ok(())
 }
+
+/// Writes 100 rows of `(k: i % 10, v: i)` into 4 buckets on `k` as Parquet
+/// files under `tmp_dir`, returning their directory
+async fn write_bucketed_data(tmp_dir: &TempDir, name: &str) -> Result<String> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("k", DataType::Int32, false),
+        Field::new("v", DataType::Int32, false),
+    ]));
+    let v = (0..100).collect::<Vec<_>>();
+    let k = v.iter().map(|v| v % 10).collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(Int32Array::from(k)), Arc::new(Int32Array::from(v))],
+    )?;
+    let ctx = SessionContext::new();
+    ctx.register_table(
+        "data",
+        Arc::new(MemTable::try_new(schema, vec![vec![batch]])?),
+    )?;
+
+    let out_dir = tmp_dir.path().join(name);
+    let out_dir = out_dir.to_str().unwrap().to_owned();
+    ctx.table("data")?
+        .bucket_by(&BucketSpec::new(vec!["k".to_owned()], 4))?
+        .write_parquet(&out_dir, None)
+        .await?;
+    Ok(out_dir)
+}
+
+#[tokio::test]
+async fn bucket_files_hold_the_rows_of_their_bucket() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let path = write_bucketed_data(&tmp_dir, "b").await?;
+    let ctx = SessionContext::new();
+    for bucket in 0..4 {
+        let file = format!("{}/part-{}.parquet", path, bucket);
+        let batches = ctx
+            .read_parquet(&file, ParquetReadOptions::default())
+            .await?
+            .collect()
+            .await?;
+        for batch in batches {
+            let mut hashes = vec![0; batch.num_rows()];
+            create_bucket_hashes(&[batch.column(0).clone()], &mut hashes)?;
+            assert!(hashes.iter().all(|hash| bucket_of(*hash, 4) == bucket));
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn join_bucketed_tables_without_repartition() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(3));
+    for name in ["b1", "b2"] {
+        let path = write_bucketed_data(&tmp_dir, name).await?;
+        let sql = format!(
+            "CREATE EXTERNAL TABLE {} STORED AS PARQUET CLUSTERED BY (k) INTO 4 BUCKETS LOCATION '{}'",
+            name, path
+        );
+        ctx.sql(&sql).await?.collect().await?;
+    }
+
+    // both sides are bucketed alike on the join key
+    let sql = "SELECT b1.k, count(*) FROM b1 JOIN b2 ON b1.k = b2.k GROUP BY b1.k ORDER BY b1.k";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let formatted = displayable(plan.as_ref()).indent().to_string();
+    assert_contains!(&formatted, "HashJoinExec: mode=Partitioned");
+    assert_contains!(&formatted, "mode=FinalPartitioned");
+    assert_not_contains!(&formatted, "partitioning=Hash");
+
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+-----------------+",
+        "| k | COUNT(UInt8(1)) |",
+        "+---+-----------------+",
+        "| 0 | 100             |",
+        "| 1 | 100             |",
+        "| 2 | 100             |",
+        "| 3 | 100             |",
+        "| 4 | 100             |",
+        "| 5 | 100             |",
+        "| 6 | 100             |",
+        "| 7 | 100             |",
+        "| 8 | 100             |",
+        "| 9 | 100             |",
+        "+---+-----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // only the side that is not bucketed is repartitioned, into the buckets
+    let sql = "SELECT count(*) FROM b1 JOIN (SELECT v FROM b2) t ON b1.k = t.v";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let formatted = displayable(plan.as_ref()).indent().to_string();
+    assert_contains!(
+        &formatted,
+        "RepartitionExec: partitioning=Hash([Column { name: \"v\", index: 0 }], 4)"
+    );
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----------------+",
+        "| COUNT(UInt8(1)) |",
+        "+-----------------+",
+        "| 100             |",
+        "+-----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn not_in_subquery_with_nulls() -> Result<()> {
+    let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(4));
+    let register = |name: &str, values: Vec<Option<i32>>| -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int32, true)]));
+        let partitions = values
+            .chunks(2)
+            .map(|chunk| {
+                let array = Int32Array::from(chunk.to_vec());
+                Ok(vec![RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(array)],
+                )?])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let partitions = if partitions.is_empty() {
+            vec![vec![]]
+        } else {
+            partitions
+        };
+        let table = MemTable::try_new(schema, partitions)?;
+        ctx.register_table(name, Arc::new(table))?;
+        Ok(())
+    };
+    register("t", vec![Some(1), Some(2), None, Some(4)])?;
+    register("no_nulls", vec![Some(1), Some(3), Some(5)])?;
+    register("with_null", vec![Some(1), Some(3), None])?;
+    register("empty", vec![])?;
+
+    let sql = "EXPLAIN SELECT v FROM t WHERE v NOT IN (SELECT v FROM no_nulls)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let plan = arrow::util::pretty::pretty_format_batches(&actual)?.to_string();
+    assert_contains!(&plan, "Anti Join: #t.v = #no_nulls.v, null_aware=true");
+    assert_contains!(&plan, "join_type=Anti, on=[(Column { name: \"v\", index: 0 }, Column { name: \"v\", index: 0 })], null_aware=true");
+
+    // the comparison with a null is unknown, so its row is dropped
+    let sql = "SELECT v FROM t WHERE v NOT IN (SELECT v FROM no_nulls) ORDER BY v";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec!["+---+", "| v |", "+---+", "| 2 |", "| 4 |", "+---+"];
+    assert_batches_eq!(expected, &actual);
+
+    // all comparisons are unknown or false if the subquery has a null
+    let sql = "SELECT v FROM t WHERE v NOT IN (SELECT v FROM with_null)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(vec!["++", "++"], &actual);
+
+    // NOT IN an empty set is true for all rows
+    let sql = "SELECT v FROM t WHERE v NOT IN (SELECT v FROM empty) ORDER BY v";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+", "| v |", "+---+", "| 1 |", "| 2 |", "| 4 |", "|   |", "+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // filters on the outer key must not remove the nulls of the subquery
+    let sql = "SELECT v FROM t WHERE v > 1 AND v NOT IN (SELECT v FROM with_null)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(vec!["++", "++"], &actual);
+
+    Ok(())
+}
@@ -77,6 +77,7 @@ macro_rules! test_expression {
 }
 
 pub mod aggregates;
+pub mod arrow_file;
 #[cfg(feature = "avro")]
 pub mod avro;
 pub mod create_drop;
@@ -93,11 +94,14 @@ pub mod order;
 pub mod parquet;
 pub mod predicates;
 pub mod projection;
+pub mod recursive;
 pub mod references;
 pub mod select;
+pub mod table_functions;
 pub mod timestamp;
 pub mod udf;
 pub mod union;
+pub mod unnest;
 pub mod wildcard;
 pub mod window;
 
@@ -232,3 +232,126 @@ async fn sort_with_lots_of_repetition_values() -> Result<()> {
     }
     Ok(())
 }
+
+#[tokio::test]
+async fn sort_removed_for_declared_file_sort_order() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    // each file is sorted by ts, but their ranges overlap
+    for (i, rows) in [[1, 4, 7], [2, 5, 8], [3, 6, 9]].iter().enumerate() {
+        let lines = rows
+            .iter()
+            .map(|ts| format!("{},{}\n", ts, i))
+            .collect::<String>();
+        std::fs::write(
+            tmp_dir.path().join(format!("file{}.csv", i)),
+            format!("ts,v\n{}", lines),
+        )?;
+    }
+
+    let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(2));
+    let sql = format!(
+        "CREATE EXTERNAL TABLE t (ts INT NOT NULL, v INT NOT NULL) \
+        STORED AS CSV WITH HEADER ROW WITH ORDER (ts ASC) LOCATION '{}'",
+        tmp_dir.path().to_str().unwrap()
+    );
+    ctx.sql(&sql).await?.collect().await?;
+
+    let sql = "SELECT ts, v FROM t WHERE v >= 0 ORDER BY ts";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let formatted = displayable(plan.as_ref()).indent().to_string();
+    assert_contains!(&formatted, "SortPreservingMergeExec: [ts@0 ASC NULLS LAST]");
+    assert_not_contains!(&formatted, "SortExec");
+
+    let actual = collect(plan, ctx.task_ctx()).await?;
+    let expected = vec![
+        "+----+---+",
+        "| ts | v |",
+        "+----+---+",
+        "| 1  | 0 |",
+        "| 2  | 1 |",
+        "| 3  | 2 |",
+        "| 4  | 0 |",
+        "| 5  | 1 |",
+        "| 6  | 2 |",
+        "| 7  | 0 |",
+        "| 8  | 1 |",
+        "| 9  | 2 |",
+        "+----+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // a sort that the files do not satisfy is kept
+    let sql = "SELECT ts, v FROM t ORDER BY ts DESC";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let formatted = displayable(plan.as_ref()).indent().to_string();
+    assert_contains!(&formatted, "SortExec: [ts@0 DESC]");
+    Ok(())
+}
+
+#[tokio::test]
+async fn sort_ranges_in_parallel() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+    let partitions = (0..3)
+        .map(|p| {
+            let values = (0..300)
+                .map(|i| (p * 300 + i) * 37 % 900)
+                .collect::<Vec<_>>();
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(values))],
+            )
+            .unwrap();
+            vec![batch]
+        })
+        .collect::<Vec<_>>();
+    let ctx = SessionContext::with_config(
+        SessionConfig::new()
+            .with_target_partitions(4)
+            .with_repartition_sorts(true),
+    );
+    ctx.register_table("t", Arc::new(MemTable::try_new(schema, partitions)?))?;
+
+    let sql = "SELECT a FROM t ORDER BY a DESC";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let formatted = displayable(plan.as_ref()).indent().to_string();
+    assert_contains!(&formatted, "SortPreservingMergeExec: [a@0 DESC]");
+    assert_contains!(&formatted, "RepartitionExec: partitioning=Range");
+
+    let actual = collect(plan, ctx.task_ctx()).await?;
+    let values = actual
+        .iter()
+        .flat_map(|batch| {
+            let array = batch.column(0);
+            let array = array.as_any().downcast_ref::<Int32Array>().unwrap();
+            array.values().to_vec()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, (0..900).rev().collect::<Vec<_>>());
+
+    // each range of a global sort is written as its own file
+    let tmp_dir = TempDir::new()?;
+    let path = tmp_dir.path().join("sorted");
+    ctx.sql("SELECT a FROM t ORDER BY a")
+        .await?
+        .write_csv(path.to_str().unwrap())
+        .await?;
+    let files = (0..4)
+        .map(|i| {
+            let file = std::fs::read_to_string(path.join(format!("part-{}.csv", i)))?;
+            Ok(file
+                .lines()
+                .filter(|line| *line != "a")
+                .map(|line| line.parse::<i32>().unwrap())
+                .collect::<Vec<_>>())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    assert!(files.iter().all(|values| !values.is_empty()));
+    assert_eq!(files.concat(), (0..900).collect::<Vec<_>>());
+    Ok(())
+}
@@ -18,6 +18,8 @@
 use std::{collections::HashMap, fs, path::Path};
 
 use ::parquet::arrow::ArrowWriter;
+use datafusion::execution::cache_manager::CacheManagerConfig;
+use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use tempfile::TempDir;
 
 use super::*;
@@ -229,3 +231,185 @@ async fn schema_merge_ignores_metadata() {
 
     assert_eq!(result[0].schema().metadata(), result[1].schema().metadata());
 }
+
+#[tokio::test]
+async fn parquet_file_pruning_with_statistics() -> Result<()> {
+    // Create three parquet files with the ids 0..10, 10..20 and 20..30
+    let tmp_dir = TempDir::new()?;
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, true)]));
+    for i in 0..3 {
+        let path = tmp_dir.path().join(format!("part-{}.parquet", i));
+        let file = fs::File::create(path)?;
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), None)?;
+        let ids = Int32Array::from_iter_values(i * 10..(i + 1) * 10);
+        writer.write(&RecordBatch::try_new(schema.clone(), vec![Arc::new(ids)])?)?;
+        writer.close()?;
+    }
+
+    let ctx = SessionContext::new();
+    ctx.register_parquet(
+        "t",
+        tmp_dir.path().to_str().unwrap(),
+        ParquetReadOptions::default(),
+    )
+    .await?;
+
+    let sql = "SELECT count(*) AS n, min(id) AS lo FROM t WHERE id >= 25";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+",
+        "| n | lo |",
+        "+---+----+",
+        "| 5 | 25 |",
+        "+---+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the two files that cannot match are skipped and reported
+    let sql = "EXPLAIN ANALYZE SELECT id FROM t WHERE id >= 25";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+    assert_contains!(&formatted, "part-2.parquet");
+    assert!(!formatted.contains("part-0.parquet"), "{}", formatted);
+    assert_contains!(&formatted, "files_pruned_by_statistics=2");
+
+    // filters on columns without statistics do not prune files
+    let sql = "EXPLAIN ANALYZE SELECT id FROM t WHERE id % 10 = 5";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+    assert_contains!(&formatted, "files_pruned_by_statistics=0");
+    Ok(())
+}
+
+#[tokio::test]
+async fn parquet_metadata_and_listing_caches() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, true)]));
+    let write_file = |i: i32| -> Result<()> {
+        let path = tmp_dir.path().join(format!("part-{}.parquet", i));
+        let mut writer =
+            ArrowWriter::try_new(fs::File::create(path)?, schema.clone(), None)?;
+        let ids = Int32Array::from_iter_values(i * 10..(i + 1) * 10);
+        writer.write(&RecordBatch::try_new(schema.clone(), vec![Arc::new(ids)])?)?;
+        writer.close()?;
+        Ok(())
+    };
+    write_file(0)?;
+
+    let cache_config = CacheManagerConfig::new()
+        .with_list_files_cache_ttl(std::time::Duration::from_secs(3600));
+    let runtime = RuntimeEnv::new(RuntimeConfig::new().with_cache_manager(cache_config))?;
+    let ctx = SessionContext::with_config_rt(SessionConfig::new(), Arc::new(runtime));
+    ctx.register_parquet(
+        "t",
+        tmp_dir.path().to_str().unwrap(),
+        ParquetReadOptions::default(),
+    )
+    .await?;
+
+    let cache_manager = Arc::clone(&ctx.runtime_env().cache_manager);
+    let metadata_cache = cache_manager.file_metadata_cache().unwrap();
+    // the footer and the schema of the file were cached when inferring the
+    // schema, then its statistics when collecting those of the table
+    assert_eq!(metadata_cache.len(), 3);
+
+    let sql = "SELECT count(*) AS n FROM t";
+    let expected = vec!["+----+", "| n  |", "+----+", "| 10 |", "+----+"];
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+    // and the cached statistics are reused when planning the query
+    assert_eq!(metadata_cache.len(), 3);
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+    assert_eq!(metadata_cache.len(), 3);
+
+    // the new file is only listed once the cached listing is dropped
+    write_file(1)?;
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+
+    cache_manager.list_files_cache().unwrap().clear();
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec!["+----+", "| n  |", "+----+", "| 20 |", "+----+"];
+    assert_batches_eq!(expected, &actual);
+    assert_eq!(metadata_cache.len(), 5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn aggregate_push_down_on_listing_tables() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let write_table = |name: &str, batch: RecordBatch| -> Result<String> {
+        let dir = tmp_dir.path().join(name);
+        fs::create_dir(&dir)?;
+        let file = fs::File::create(dir.join("part-0.parquet"))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(dir.to_str().unwrap().to_owned())
+    };
+    // 1000 fact rows for 10 join keys
+    let fact = RecordBatch::try_from_iter(vec![
+        (
+            "dk",
+            Arc::new(Int64Array::from_iter_values((0..1000).map(|i| i % 10))) as ArrayRef,
+        ),
+        (
+            "amount",
+            Arc::new(Int64Array::from_iter_values(0..1000)) as ArrayRef,
+        ),
+    ])?;
+    let dim = RecordBatch::try_from_iter(vec![
+        (
+            "k",
+            Arc::new(Int64Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+        (
+            "name",
+            Arc::new(StringArray::from_iter_values((0..10).map(|i| {
+                if i < 5 {
+                    "low"
+                } else {
+                    "high"
+                }
+            }))) as ArrayRef,
+        ),
+    ])?;
+
+    let ctx = SessionContext::new();
+    let fact_path = write_table("fact", fact)?;
+    ctx.register_parquet("fact", &fact_path, ParquetReadOptions::default())
+        .await?;
+    let dim_path = write_table("dim", dim)?;
+    ctx.register_parquet("dim", &dim_path, ParquetReadOptions::default())
+        .await?;
+
+    // the statistics of the files collected when registering the tables
+    // show that the fact rows can be aggregated by join key before the join
+    let sql =
+        "SELECT d.name, SUM(f.amount) AS total FROM fact f JOIN dim d ON f.dk = d.k \
+               GROUP BY d.name ORDER BY d.name";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let formatted = format!("{}", plan.display_indent());
+    assert_contains!(
+        &formatted,
+        "Aggregate: groupBy=[[#f.dk]], aggr=[[SUM(#f.amount)]]"
+    );
+
+    let expected = vec![
+        "+------+--------+",
+        "| name | total  |",
+        "+------+--------+",
+        "| high | 251000 |",
+        "| low  | 248500 |",
+        "+------+--------+",
+    ];
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
@@ -0,0 +1,216 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+
+/// Registers the table `name` with the given non-null Int32 columns
+fn register_int32_table(
+    ctx: &SessionContext,
+    name: &str,
+    columns: Vec<(&str, Vec<Option<i32>>)>,
+) -> Result<()> {
+    let batch =
+        RecordBatch::try_from_iter(columns.into_iter().map(|(name, values)| {
+            (name, Arc::new(Int32Array::from(values)) as ArrayRef)
+        }))?;
+    let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+    ctx.register_table(name, Arc::new(table))?;
+    Ok(())
+}
+
+/// Registers the table `edges (src INT, dst INT)` of a graph with a cycle
+fn register_edges(ctx: &SessionContext) -> Result<()> {
+    register_int32_table(
+        ctx,
+        "edges",
+        vec![
+            ("src", vec![Some(1), Some(2), Some(3), Some(3)]),
+            ("dst", vec![Some(2), Some(3), Some(1), Some(4)]),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn recursive_cte_counting() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "WITH RECURSIVE t(n) AS (\
+                 SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5\
+               ) SELECT n FROM t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+", "| n |", "+---+", "| 1 |", "| 2 |", "| 3 |", "| 4 |", "| 5 |", "+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the recursive term is cast to the types of the static term
+    let sql = "WITH RECURSIVE t(n, s) AS (\
+                 SELECT CAST(1 AS INT), 'a' \
+                 UNION ALL SELECT n + 1, concat(s, 'a') FROM t WHERE n < 3\
+               ) SELECT n, s FROM t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+-----+",
+        "| n | s   |",
+        "+---+-----+",
+        "| 1 | a   |",
+        "| 2 | aa  |",
+        "| 3 | aaa |",
+        "+---+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn recursive_cte_hierarchy() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_int32_table(
+        &ctx,
+        "employees",
+        vec![
+            ("id", vec![Some(1), Some(2), Some(3), Some(4), Some(5)]),
+            ("manager_id", vec![None, Some(1), Some(1), Some(2), Some(4)]),
+        ],
+    )?;
+
+    let sql = "WITH RECURSIVE chain AS (\
+                 SELECT id, 1 AS depth FROM employees WHERE manager_id IS NULL \
+                 UNION ALL \
+                 SELECT e.id, c.depth + 1 FROM employees e JOIN chain c ON e.manager_id = c.id\
+               ) SELECT * FROM chain ORDER BY id";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+-------+",
+        "| id | depth |",
+        "+----+-------+",
+        "| 1  | 1     |",
+        "| 2  | 2     |",
+        "| 3  | 2     |",
+        "| 4  | 3     |",
+        "| 5  | 4     |",
+        "+----+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // a recursive CTE can be joined with other relations and CTEs
+    let sql =
+        "WITH RECURSIVE roots AS (SELECT id FROM employees WHERE manager_id IS NULL), \
+               reports AS (\
+                 SELECT id FROM roots \
+                 UNION ALL \
+                 SELECT e.id FROM reports r JOIN employees e ON e.manager_id = r.id\
+               ) SELECT count(*) AS n FROM reports r JOIN employees e ON r.id = e.id \
+               WHERE e.manager_id IS NOT NULL";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec!["+---+", "| n |", "+---+", "| 4 |", "+---+"];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn recursive_cte_union_distinct() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_edges(&ctx)?;
+
+    // UNION discards the rows already produced, so the cycle terminates
+    let sql = "WITH RECURSIVE reachable(node) AS (\
+                 SELECT CAST(1 AS INT) \
+                 UNION \
+                 SELECT e.dst FROM edges e JOIN reachable r ON e.src = r.node\
+               ) SELECT node FROM reachable ORDER BY node";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+------+", "| node |", "+------+", "| 1    |", "| 2    |", "| 3    |",
+        "| 4    |", "+------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // duplicates of the static term are removed too
+    let sql = "WITH RECURSIVE t(n) AS (\
+                 SELECT src FROM edges \
+                 UNION \
+                 SELECT n FROM t\
+               ) SELECT n FROM t ORDER BY n";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+", "| n |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn recursive_cte_max_iterations() -> Result<()> {
+    let ctx = SessionContext::with_config(
+        SessionConfig::new().with_max_recursion_iterations(10),
+    );
+    register_edges(&ctx)?;
+
+    // UNION ALL keeps following the cycle
+    let sql = "WITH RECURSIVE walk(node) AS (\
+                 SELECT CAST(1 AS INT) \
+                 UNION ALL \
+                 SELECT e.dst FROM edges e JOIN walk w ON e.src = w.node\
+               ) SELECT count(*) FROM walk";
+    let err = plan_and_collect(&ctx, sql).await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Recursive query walk exceeded the maximum of 10 iterations"),
+        "{}",
+        err
+    );
+
+    // the limit applies to evaluations of the recursive term, including the
+    // last one producing no rows, rather than to rows
+    let sql = "WITH RECURSIVE t(n) AS (\
+                 SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 10\
+               ) SELECT count(*) AS c FROM t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec!["+----+", "| c  |", "+----+", "| 10 |", "+----+"];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn recursive_cte_errors() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    let sql =
+        "WITH RECURSIVE t AS (SELECT 1 UNION ALL SELECT 1, 2 FROM t) SELECT * FROM t";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: Recursive query t has 1 columns in its static term but 2 in its recursive term"
+    );
+
+    let sql =
+        "WITH RECURSIVE t(a, b) AS (SELECT 1 UNION ALL SELECT a FROM t) SELECT * FROM t";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: WITH query t has 1 columns available but 2 columns specified"
+    );
+
+    // the static term cannot reference the query
+    let sql = "WITH RECURSIVE t AS (SELECT * FROM t UNION ALL SELECT 1) SELECT * FROM t";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: 'datafusion.public.t' not found"
+    );
+    Ok(())
+}
@@ -0,0 +1,317 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+use async_trait::async_trait;
+use datafusion::datasource::TableFunction;
+use datafusion::execution::context::SessionState;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::scalar::ScalarValue;
+
+#[tokio::test]
+async fn generate_series() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    let sql = "SELECT * FROM generate_series(1, 10, 4)";
+    let actual = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+-----------------+",
+        "| generate_series |",
+        "+-----------------+",
+        "| 1               |",
+        "| 5               |",
+        "| 9               |",
+        "+-----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT s.n * 2 AS x FROM generate_series(3, 1, -1) AS s (n)";
+    let actual = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+---+", "| x |", "+---+", "| 6 |", "| 4 |", "| 2 |", "+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT count(*), sum(range) FROM range(100000)";
+    let actual = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+-----------------+------------------+",
+        "| COUNT(UInt8(1)) | SUM(range.range) |",
+        "+-----------------+------------------+",
+        "| 100000          | 4999950000       |",
+        "+-----------------+------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT * FROM TABLE(range(2, 4))";
+    let actual = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+-------+",
+        "| range |",
+        "+-------+",
+        "| 2     |",
+        "| 3     |",
+        "+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn generate_series_of_timestamps() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    let sql = "SELECT * FROM generate_series(\
+               TIMESTAMP '2022-01-31T00:00:00', '2022-04-30', INTERVAL '1 month')";
+    let actual = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+---------------------+",
+        "| generate_series     |",
+        "+---------------------+",
+        "| 2022-01-31 00:00:00 |",
+        "| 2022-02-28 00:00:00 |",
+        "| 2022-03-31 00:00:00 |",
+        "| 2022-04-30 00:00:00 |",
+        "+---------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT * FROM range(\
+               '2022-01-01T00:00:00', '2022-01-02T00:00:00', INTERVAL '8 hours')";
+    let actual = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+---------------------+",
+        "| range               |",
+        "+---------------------+",
+        "| 2022-01-01 00:00:00 |",
+        "| 2022-01-01 08:00:00 |",
+        "| 2022-01-01 16:00:00 |",
+        "+---------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn generate_series_errors() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    let err = ctx
+        .sql("SELECT * FROM generate_series(1, 10, 0)")
+        .await
+        .unwrap_err();
+    assert_contains!(err.to_string(), "The step of a series must not be zero");
+
+    let err = ctx
+        .sql("SELECT * FROM generate_series(1, 'a')")
+        .await
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "generate_series expects an integer argument, got Utf8(\"a\")"
+    );
+
+    let err = ctx
+        .sql("SELECT * FROM generate_series(1, random())")
+        .await
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "Arguments of table function 'generate_series' must be constants"
+    );
+
+    let err = ctx
+        .sql("SELECT * FROM no_such_function(1)")
+        .await
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "There is no table function named 'no_such_function'"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn generate_series_in_logical_plan() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    // the table of generate_series is created without I/O, so it is
+    // available to all the planning entry points and not only to sql
+    let plan = ctx.create_logical_plan("SELECT * FROM generate_series(1,3)")?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan).await?;
+    let actual = collect(plan, ctx.task_ctx()).await?;
+    let expected = vec![
+        "+-----------------+",
+        "| generate_series |",
+        "+-----------------+",
+        "| 1               |",
+        "| 2               |",
+        "| 3               |",
+        "+-----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let err = ctx
+        .create_logical_plan("SELECT * FROM read_csv('data.csv')")
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "Table function 'read_csv' does I/O to create its table"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_csv() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    for i in 0..2 {
+        let mut file = File::create(tmp_dir.path().join(format!("part-{}.csv", i)))?;
+        writeln!(file, "a,b")?;
+        writeln!(file, "{},x{}", i, i)?;
+    }
+    let mut file = File::create(tmp_dir.path().join("other.csv"))?;
+    writeln!(file, "1;2")?;
+
+    let ctx = SessionContext::new();
+    let sql = format!(
+        "SELECT * FROM read_csv('{}/part-*.csv')",
+        tmp_dir.path().display()
+    );
+    let actual = plan_and_collect(&ctx, &sql).await?;
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 0 | x0 |",
+        "| 1 | x1 |",
+        "+---+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    let sql = format!(
+        "SELECT * FROM read_csv('{}/other.csv', false, ';')",
+        tmp_dir.path().display()
+    );
+    let actual = plan_and_collect(&ctx, &sql).await?;
+    let expected = vec![
+        "+----------+----------+",
+        "| column_1 | column_2 |",
+        "+----------+----------+",
+        "| 1        | 2        |",
+        "+----------+----------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = format!(
+        "SELECT * FROM read_csv('{}/other.csv', false, ';;')",
+        tmp_dir.path().display()
+    );
+    let err = ctx.sql(&sql).await.unwrap_err();
+    assert_contains!(err.to_string(), "expects a single character delimiter");
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_parquet() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int32, false)]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(Int32Array::from_slice(&[1, 2, 3]))],
+    )?;
+    let file = File::create(tmp_dir.path().join("data.parquet"))?;
+    let mut writer = ArrowWriter::try_new(file, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    let ctx = SessionContext::new();
+    let sql = format!(
+        "SELECT sum(t.v) FROM read_parquet('{}') t",
+        tmp_dir.path().display()
+    );
+    let actual = plan_and_collect(&ctx, &sql).await?;
+    let expected = vec![
+        "+----------+",
+        "| SUM(t.v) |",
+        "+----------+",
+        "| 6        |",
+        "+----------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = format!(
+        "SELECT * FROM read_parquet('{}/missing')",
+        tmp_dir.path().display()
+    );
+    assert!(ctx.sql(&sql).await.is_err());
+    Ok(())
+}
+
+/// A table function returning a table with `n` copies of a string
+struct Repeat {}
+
+#[async_trait]
+impl TableFunction for Repeat {
+    async fn call(
+        &self,
+        _state: &SessionState,
+        args: &[ScalarValue],
+    ) -> Result<Arc<dyn TableProvider>> {
+        let (value, n) = match args {
+            [ScalarValue::Utf8(Some(value)), ScalarValue::Int64(Some(n))] => {
+                (value, *n as usize)
+            }
+            _ => {
+                return Err(DataFusionError::Plan(
+                    "repeat expects a string and a count".to_string(),
+                ))
+            }
+        };
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec![value.as_str(); n]))],
+        )?;
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    }
+}
+
+#[tokio::test]
+async fn table_udf() -> Result<()> {
+    let mut ctx = SessionContext::new();
+    ctx.register_udtf("repeat", Arc::new(Repeat {}));
+
+    let sql = "SELECT r.s, g.generate_series FROM repeat('a' || 'b', 1 + 1) r \
+               CROSS JOIN generate_series(1, 2) g";
+    let actual = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+----+-----------------+",
+        "| s  | generate_series |",
+        "+----+-----------------+",
+        "| ab | 1               |",
+        "| ab | 1               |",
+        "| ab | 2               |",
+        "| ab | 2               |",
+        "+----+-----------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    let err = ctx.sql("SELECT * FROM repeat(1)").await.unwrap_err();
+    assert_contains!(err.to_string(), "repeat expects a string and a count");
+    Ok(())
+}
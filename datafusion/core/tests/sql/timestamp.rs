@@ -814,3 +814,108 @@ async fn group_by_timestamp_millis() -> Result<()> {
     assert_batches_eq!(expected, &actual);
     Ok(())
 }
+
+#[tokio::test]
+async fn timestamp_with_time_zone_literals() -> Result<()> {
+    let ctx = SessionContext::with_config(
+        SessionConfig::new().with_time_zone("America/New_York"),
+    );
+
+    // local times, before and after the clocks go forward, and an instant in UTC
+    let sql = "SELECT \
+        CAST(TIMESTAMP WITH TIME ZONE '2022-03-13 01:30:00' AS VARCHAR) AS a, \
+        CAST(TIMESTAMPTZ '2022-03-13 03:30:00' AS VARCHAR) AS b, \
+        CAST(CAST('2022-03-13T12:00:00Z' AS TIMESTAMPTZ) AS VARCHAR) AS c, \
+        TIMESTAMPTZ '2022-03-13 01:30:00' < TIMESTAMPTZ '2022-03-13 03:30:00' AS d";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---------------------------+---------------------------+---------------------------+------+",
+        "| a                         | b                         | c                         | d    |",
+        "+---------------------------+---------------------------+---------------------------+------+",
+        "| 2022-03-13 01:30:00-05:00 | 2022-03-13 03:30:00-04:00 | 2022-03-13 08:00:00-04:00 | true |",
+        "+---------------------------+---------------------------+---------------------------+------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let ctx = SessionContext::with_config(SessionConfig::new().with_time_zone("Mars"));
+    let sql = "SELECT TIMESTAMPTZ '2022-03-13 01:30:00'";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: Invalid time zone 'Mars' of the session"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn at_time_zone() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT \
+        TIMESTAMPTZ '2022-11-06 05:30:00' AT TIME ZONE 'America/New_York' AS a, \
+        TIMESTAMPTZ '2022-11-06 06:30:00' AT TIME ZONE 'America/New_York' AS b, \
+        CAST(TIMESTAMP '2022-11-06 01:30:00' AT TIME ZONE 'America/New_York' AS VARCHAR) AS c, \
+        CAST('2022-11-06 01:30:00'::timestamp AT TIME ZONE '+05:30' AS VARCHAR) AS d, \
+        TIMESTAMP '2022-11-06 01:30:00' AT TIME ZONE 'Asia/Tokyo' AT TIME ZONE 'Europe/Paris' AS e";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---------------------+---------------------+---------------------------+---------------------------+---------------------+",
+        "| a                   | b                   | c                         | d                         | e                   |",
+        "+---------------------+---------------------+---------------------------+---------------------------+---------------------+",
+        "| 2022-11-06 01:30:00 | 2022-11-06 01:30:00 | 2022-11-06 06:30:00+00:00 | 2022-11-05 20:00:00+00:00 | 2022-11-05 17:30:00 |",
+        "+---------------------+---------------------+---------------------------+---------------------------+---------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT TIMESTAMP '2022-11-06 01:30:00' AT TIME ZONE 'Mars'";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: Invalid time zone 'Mars'"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn date_trunc_and_part_with_time_zone() -> Result<()> {
+    let ctx = SessionContext::new();
+    let table = make_timestamp_tz_table::<TimestampNanosecondType>(Some(
+        "America/New_York".to_owned(),
+    ))?;
+    ctx.register_table("t", table)?;
+
+    let sql = "SELECT CAST(ts AS VARCHAR) AS ts, \
+        CAST(date_trunc('day', ts) AS VARCHAR) AS day, \
+        date_part('hour', ts) AS hour \
+        FROM t ORDER BY ts";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----------------------------------+---------------------------+------+",
+        "| ts                               | day                       | hour |",
+        "+----------------------------------+---------------------------+------+",
+        "| 2020-09-08 07:42:29.190855-04:00 | 2020-09-08 00:00:00-04:00 | 7    |",
+        "| 2020-09-08 08:42:29.190855-04:00 | 2020-09-08 00:00:00-04:00 | 8    |",
+        "| 2020-09-08 09:42:29.190855-04:00 | 2020-09-08 00:00:00-04:00 | 9    |",
+        "+----------------------------------+---------------------------+------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the days and hours around the transitions of daylight saving time
+    let ctx = SessionContext::with_config(
+        SessionConfig::new().with_time_zone("America/New_York"),
+    );
+    let sql = "SELECT \
+        CAST(date_trunc('day', TIMESTAMPTZ '2022-03-13 12:00:00') AS VARCHAR) AS a, \
+        CAST(date_trunc('hour', CAST('2022-11-06T05:30:00Z' AS TIMESTAMPTZ)) AS VARCHAR) AS b, \
+        CAST(date_trunc('hour', CAST('2022-11-06T06:30:00Z' AS TIMESTAMPTZ)) AS VARCHAR) AS c, \
+        date_part('day', CAST('2022-07-01T02:00:00Z' AS TIMESTAMPTZ)) AS d";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---------------------------+---------------------------+---------------------------+----+",
+        "| a                         | b                         | c                         | d  |",
+        "+---------------------------+---------------------------+---------------------------+----+",
+        "| 2022-03-13 00:00:00-05:00 | 2022-11-06 01:00:00-04:00 | 2022-11-06 01:00:00-05:00 | 30 |",
+        "+---------------------------+---------------------------+---------------------------+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
@@ -18,9 +18,14 @@
 use super::*;
 use arrow::compute::add;
 use datafusion::{
-    logical_plan::{create_udaf, FunctionRegistry, LogicalPlanBuilder},
-    physical_plan::{expressions::AvgAccumulator, functions::make_scalar_function},
+    logical_plan::{create_udaf, create_udwf, FunctionRegistry, LogicalPlanBuilder},
+    physical_plan::{
+        expressions::AvgAccumulator, functions::make_scalar_function,
+        udwf::PartitionEvaluator,
+    },
+    scalar::ScalarValue,
 };
+use std::ops::Range;
 
 /// test that casting happens on udfs.
 /// c11 is f32, but `custom_sqrt` requires f64. Casting happens but the logical plan and
@@ -185,3 +190,179 @@ async fn simple_udaf() -> Result<()> {
 
     Ok(())
 }
+
+/// tests a UDAF whose accumulator supports retraction over a sliding window frame
+#[tokio::test]
+async fn udaf_as_window_function() -> Result<()> {
+    let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(Int32Array::from_slice(&[1, 2, 3, 4, 5]))],
+    )?;
+
+    let mut ctx = SessionContext::new();
+
+    let provider = MemTable::try_new(Arc::new(schema), vec![vec![batch]])?;
+    ctx.register_table("t", Arc::new(provider))?;
+
+    // `AvgAccumulator` implements `retract_batch`
+    let my_avg = create_udaf(
+        "my_avg",
+        DataType::Float64,
+        Arc::new(DataType::Float64),
+        Volatility::Immutable,
+        Arc::new(|| Ok(Box::new(AvgAccumulator::try_new(&DataType::Float64)?))),
+        Arc::new(vec![DataType::UInt64, DataType::Float64]),
+    );
+
+    ctx.register_udaf(my_avg);
+
+    let result = plan_and_collect(
+        &ctx,
+        "SELECT a, MY_AVG(a) OVER (ORDER BY a ROWS BETWEEN 2 PRECEDING AND CURRENT ROW) AS m FROM t ORDER BY a",
+    )
+    .await?;
+
+    let expected = vec![
+        "+---+-----+",
+        "| a | m   |",
+        "+---+-----+",
+        "| 1 | 1   |",
+        "| 2 | 1.5 |",
+        "| 3 | 2   |",
+        "| 4 | 3   |",
+        "| 5 | 4   |",
+        "+---+-----+",
+    ];
+    assert_batches_eq!(expected, &result);
+
+    Ok(())
+}
+
+/// numbers the rows of the partition, evaluating it as a whole
+#[derive(Debug)]
+struct MyRowNumber {}
+
+impl PartitionEvaluator for MyRowNumber {
+    fn evaluate_all(
+        &mut self,
+        _values: &[ArrayRef],
+        num_rows: usize,
+    ) -> Result<ArrayRef> {
+        Ok(Arc::new(UInt64Array::from_iter_values(1..=num_rows as u64)))
+    }
+}
+
+/// numbers the groups of peer rows of the partition
+#[derive(Debug)]
+struct MyDenseRank {}
+
+impl PartitionEvaluator for MyDenseRank {
+    fn include_rank(&self) -> bool {
+        true
+    }
+
+    fn evaluate_all_with_rank(
+        &mut self,
+        _num_rows: usize,
+        ranks_in_partition: &[Range<usize>],
+    ) -> Result<ArrayRef> {
+        Ok(Arc::new(UInt64Array::from_iter_values(
+            ranks_in_partition
+                .iter()
+                .enumerate()
+                .flat_map(|(i, range)| std::iter::repeat(i as u64 + 1).take(range.len())),
+        )))
+    }
+}
+
+/// sums the values of the window frame of each row
+#[derive(Debug)]
+struct MyFrameSum {}
+
+impl PartitionEvaluator for MyFrameSum {
+    fn uses_window_frame(&self) -> bool {
+        true
+    }
+
+    fn evaluate(
+        &mut self,
+        values: &[ArrayRef],
+        range: &Range<usize>,
+    ) -> Result<ScalarValue> {
+        let values = values[0].as_any().downcast_ref::<Int64Array>().unwrap();
+        Ok(ScalarValue::Int64(Some(
+            range.clone().map(|i| values.value(i)).sum(),
+        )))
+    }
+}
+
+/// tests the creation, registration and usage of UDWFs
+#[tokio::test]
+async fn simple_udwf() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("p", DataType::Int32, false),
+        Field::new("a", DataType::Int32, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.clone()),
+        vec![
+            Arc::new(Int32Array::from_slice(&[1, 1, 1, 2, 2])),
+            Arc::new(Int32Array::from_slice(&[1, 2, 2, 4, 5])),
+        ],
+    )?;
+
+    let mut ctx = SessionContext::new();
+
+    let provider = MemTable::try_new(Arc::new(schema), vec![vec![batch]])?;
+    ctx.register_table("t", Arc::new(provider))?;
+
+    ctx.register_udwf(create_udwf(
+        "my_row_number",
+        vec![],
+        Arc::new(DataType::UInt64),
+        Volatility::Immutable,
+        Arc::new(|| Ok(Box::new(MyRowNumber {}))),
+    ));
+    ctx.register_udwf(create_udwf(
+        "my_dense_rank",
+        vec![],
+        Arc::new(DataType::UInt64),
+        Volatility::Immutable,
+        Arc::new(|| Ok(Box::new(MyDenseRank {}))),
+    ));
+    ctx.register_udwf(create_udwf(
+        "my_frame_sum",
+        vec![DataType::Int64],
+        Arc::new(DataType::Int64),
+        Volatility::Immutable,
+        Arc::new(|| Ok(Box::new(MyFrameSum {}))),
+    ));
+
+    let result = plan_and_collect(
+        &ctx,
+        "SELECT p, a, \
+         MY_ROW_NUMBER() OVER (PARTITION BY p ORDER BY a) AS rn, \
+         MY_DENSE_RANK() OVER (PARTITION BY p ORDER BY a) AS dr, \
+         MY_FRAME_SUM(a) OVER (PARTITION BY p ORDER BY a ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) AS s \
+         FROM t ORDER BY p, a",
+    )
+    .await?;
+
+    let expected = vec![
+        "+---+---+----+----+---+",
+        "| p | a | rn | dr | s |",
+        "+---+---+----+----+---+",
+        "| 1 | 1 | 1  | 1  | 1 |",
+        "| 1 | 2 | 2  | 2  | 3 |",
+        "| 1 | 2 | 3  | 2  | 4 |",
+        "| 2 | 4 | 1  | 1  | 4 |",
+        "| 2 | 5 | 2  | 2  | 9 |",
+        "+---+---+----+----+---+",
+    ];
+    assert_batches_eq!(expected, &result);
+
+    assert!(ctx.udwf("my_frame_sum").is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::*;
+use datafusion::arrow::array::{ListBuilder, StringBuilder};
+
+/// Registers the table `t (id INT, tags LIST<STRING>)`
+fn register_tags(ctx: &SessionContext) -> Result<()> {
+    let mut tags = ListBuilder::new(StringBuilder::new(8));
+    for row in [Some(vec!["a", "b"]), Some(vec![]), None, Some(vec!["c"])] {
+        match row {
+            Some(values) => {
+                for value in values {
+                    tags.values().append_value(value)?;
+                }
+                tags.append(true)?;
+            }
+            None => tags.append(false)?,
+        }
+    }
+    let tags = tags.finish();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("tags", tags.data_type().clone(), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from_slice(&[1, 2, 3, 4])),
+            Arc::new(tags),
+        ],
+    )?;
+    let table = MemTable::try_new(schema, vec![vec![batch]])?;
+    ctx.register_table("t", Arc::new(table))?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn unnest_in_select_list() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_tags(&ctx)?;
+
+    let sql = "SELECT id, unnest(tags) AS tag FROM t ORDER BY id, tag";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+-----+",
+        "| id | tag |",
+        "+----+-----+",
+        "| 1  | a   |",
+        "| 1  | b   |",
+        "| 4  | c   |",
+        "+----+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT unnest(tags) FROM t WHERE id = 1";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----------------+",
+        "| unnest(t.tags) |",
+        "+----------------+",
+        "| a              |",
+        "| b              |",
+        "+----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT unnest(tags) AS tag, count(*) AS n FROM t \
+               GROUP BY tag ORDER BY tag";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----+---+",
+        "| tag | n |",
+        "+-----+---+",
+        "| a   | 1 |",
+        "| b   | 1 |",
+        "| c   | 1 |",
+        "+-----+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT unnest(array(1, 2, 3)) AS v";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+", "| v |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn unnest_in_from() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_tags(&ctx)?;
+
+    let sql = "SELECT t.id, x.tag FROM t, unnest(t.tags) AS x(tag) \
+               WHERE x.tag <> 'b' ORDER BY t.id";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+-----+",
+        "| id | tag |",
+        "+----+-----+",
+        "| 1  | a   |",
+        "| 4  | c   |",
+        "+----+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT t.id, u.unnest FROM t CROSS JOIN unnest(t.tags) AS u \
+               ORDER BY t.id, u.unnest";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+--------+",
+        "| id | unnest |",
+        "+----+--------+",
+        "| 1  | a      |",
+        "| 1  | b      |",
+        "| 4  | c      |",
+        "+----+--------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT * FROM unnest(array(3, 4))";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+--------+",
+        "| unnest |",
+        "+--------+",
+        "| 3      |",
+        "| 4      |",
+        "+--------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cross_join_lateral() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_tags(&ctx)?;
+
+    let sql = "SELECT t.id, x.tag, x.next_id FROM t CROSS JOIN LATERAL \
+               (SELECT unnest(t.tags) AS tag, t.id + 1 AS next_id WHERE t.id > 1) AS x";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+-----+---------+",
+        "| id | tag | next_id |",
+        "+----+-----+---------+",
+        "| 4  | c   | 5       |",
+        "+----+-----+---------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT count(*) FROM t, LATERAL (SELECT unnest(t.tags)) AS x (tag)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----------------+",
+        "| COUNT(UInt8(1)) |",
+        "+-----------------+",
+        "| 3               |",
+        "+-----------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn unnest_errors() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_tags(&ctx)?;
+
+    let err = ctx
+        .sql("SELECT unnest(tags) || 'x' FROM t")
+        .await
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "unnest is only supported as an item of a SELECT list or as a relation"
+    );
+
+    let err = ctx.sql("SELECT unnest(id) FROM t").await.unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "Unnest of column unnest(t.id) requires a list, got Int32"
+    );
+
+    let err = ctx
+        .sql("SELECT unnest(tags), unnest(tags) AS x FROM t")
+        .await
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "Only a single unnest is supported per SELECT list"
+    );
+
+    let err = ctx
+        .sql(
+            "SELECT * FROM t CROSS JOIN LATERAL (SELECT * FROM t AS s WHERE s.id = t.id)",
+        )
+        .await
+        .unwrap_err();
+    assert_contains!(err.to_string(), "LATERAL is only supported for subqueries");
+    Ok(())
+}
@@ -298,3 +298,366 @@ async fn window_partition_by_order_by() -> Result<()> {
     assert_batches_eq!(expected, &results);
     Ok(())
 }
+
+#[tokio::test]
+async fn window_frames() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("p", DataType::Utf8, false),
+        Field::new("x", DataType::Int32, false),
+        Field::new("v", DataType::Int32, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from_slice(&["a", "a", "a", "a", "b", "b"])),
+            Arc::new(Int32Array::from_slice(&[1, 2, 2, 3, 1, 2])),
+            Arc::new(Int32Array::from(vec![
+                Some(10),
+                Some(20),
+                Some(30),
+                Some(40),
+                Some(50),
+                None,
+            ])),
+        ],
+    )?;
+    let ctx = SessionContext::new();
+    let provider = MemTable::try_new(schema, vec![vec![batch]])?;
+    ctx.register_table("t", Arc::new(provider))?;
+
+    let sql = "SELECT p, v, \
+        SUM(v) OVER (PARTITION BY p ORDER BY v ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) AS s, \
+        MIN(v) OVER (PARTITION BY p ORDER BY v ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) AS mn, \
+        MAX(v) OVER (PARTITION BY p ORDER BY v ROWS BETWEEN 1 FOLLOWING AND 2 FOLLOWING) AS mx, \
+        COUNT(v) OVER (PARTITION BY p ORDER BY x GROUPS BETWEEN 1 PRECEDING AND CURRENT ROW) AS c, \
+        AVG(v) OVER (PARTITION BY p ORDER BY x RANGE BETWEEN CURRENT ROW AND UNBOUNDED FOLLOWING) AS av \
+        FROM t ORDER BY p, v";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+----+----+----+---+----+",
+        "| p | v  | s  | mn | mx | c | av |",
+        "+---+----+----+----+----+---+----+",
+        "| a | 10 | 10 | 10 | 30 | 1 | 25 |",
+        "| a | 20 | 30 | 10 | 40 | 3 | 30 |",
+        "| a | 30 | 50 | 20 | 40 | 3 | 30 |",
+        "| a | 40 | 70 | 30 |    | 3 | 40 |",
+        "| b | 50 | 50 | 50 |    | 1 | 50 |",
+        "| b |    | 50 | 50 |    | 1 |    |",
+        "+---+----+----+----+----+---+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn window_frames_of_non_finite_floats() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("v", DataType::Float64, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from_slice(&[1, 2, 3, 4, 5])),
+            Arc::new(Float64Array::from_slice(&[
+                1.0,
+                f64::INFINITY,
+                f64::NAN,
+                2.0,
+                4.0,
+            ])),
+        ],
+    )?;
+    let ctx = SessionContext::new();
+    let provider = MemTable::try_new(schema, vec![vec![batch]])?;
+    ctx.register_table("t", Arc::new(provider))?;
+
+    // the frames after the infinite and NaN values are finite again
+    let sql = "SELECT x, \
+        SUM(v) OVER (ORDER BY x ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) AS s, \
+        AVG(v) OVER (ORDER BY x ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) AS a \
+        FROM t ORDER BY x";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+-----+-----+",
+        "| x | s   | a   |",
+        "+---+-----+-----+",
+        "| 1 | 1   | 1   |",
+        "| 2 | inf | inf |",
+        "| 3 | NaN | NaN |",
+        "| 4 | NaN | NaN |",
+        "| 5 | 6   | 3   |",
+        "+---+-----+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // as are the variances and standard deviations
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("v", DataType::Float64, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])),
+            Arc::new(Float64Array::from_slice(&[
+                1.0,
+                2.0,
+                f64::NEG_INFINITY,
+                4.0,
+                f64::NAN,
+                6.0,
+                7.0,
+                8.0,
+                9.0,
+                10.0,
+            ])),
+        ],
+    )?;
+    let provider = MemTable::try_new(schema, vec![vec![batch]])?;
+    ctx.register_table("u", Arc::new(provider))?;
+    let sql = "SELECT x, \
+        VAR(v) OVER (ORDER BY x ROWS BETWEEN 2 PRECEDING AND 2 FOLLOWING) AS var, \
+        STDDEV(v) OVER (ORDER BY x ROWS BETWEEN 2 PRECEDING AND 2 FOLLOWING) AS std \
+        FROM u ORDER BY x";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+--------------------+--------------------+",
+        "| x  | var                | std                |",
+        "+----+--------------------+--------------------+",
+        "| 1  | NaN                | NaN                |",
+        "| 2  | NaN                | NaN                |",
+        "| 3  | NaN                | NaN                |",
+        "| 4  | NaN                | NaN                |",
+        "| 5  | NaN                | NaN                |",
+        "| 6  | NaN                | NaN                |",
+        "| 7  | NaN                | NaN                |",
+        "| 8  | 2.4999999999999996 | 1.5811388300841895 |",
+        "| 9  | 1.6666666666666667 | 1.2909944487358056 |",
+        "| 10 | 1                  | 1                  |",
+        "+----+--------------------+--------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn window_frames_range_offsets() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("ts", DataType::Timestamp(TimeUnit::Second, None), false),
+        Field::new("x", DataType::Int32, false),
+        Field::new("v", DataType::Int32, false),
+    ]));
+    // 2022-01-01, 2022-01-15, 2022-02-10, 2022-03-15 and 2022-03-20
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(TimestampSecondArray::from_vec(
+                vec![1640995200, 1642204800, 1644451200, 1647302400, 1647734400],
+                None,
+            )),
+            Arc::new(Int32Array::from_slice(&[1, 2, 4, 5, 7])),
+            Arc::new(Int32Array::from_slice(&[1, 2, 3, 4, 5])),
+        ],
+    )?;
+    let ctx = SessionContext::new();
+    let provider = MemTable::try_new(schema, vec![vec![batch]])?;
+    ctx.register_table("t", Arc::new(provider))?;
+
+    let sql = "SELECT ts, x, \
+        SUM(v) OVER (ORDER BY ts RANGE BETWEEN INTERVAL '30' DAY PRECEDING AND CURRENT ROW) AS d, \
+        SUM(v) OVER (ORDER BY ts RANGE BETWEEN CURRENT ROW AND INTERVAL '1' MONTH FOLLOWING) AS m, \
+        COUNT(v) OVER (ORDER BY x DESC RANGE BETWEEN 1 PRECEDING AND 2 FOLLOWING) AS c \
+        FROM t ORDER BY ts";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---------------------+---+---+---+---+",
+        "| ts                  | x | d | m | c |",
+        "+---------------------+---+---+---+---+",
+        "| 2022-01-01 00:00:00 | 1 | 1 | 3 | 2 |",
+        "| 2022-01-15 00:00:00 | 2 | 3 | 5 | 2 |",
+        "| 2022-02-10 00:00:00 | 4 | 5 | 3 | 3 |",
+        "| 2022-03-15 00:00:00 | 5 | 4 | 9 | 2 |",
+        "| 2022-03-20 00:00:00 | 7 | 9 | 5 | 2 |",
+        "+---------------------+---+---+---+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT SUM(v) OVER (ORDER BY ts RANGE 2 PRECEDING) FROM t";
+    let err = plan_and_collect(&ctx, sql).await.unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "Invalid window frame offset 2 for RANGE frame with ORDER BY expression of type Timestamp(Second, None)"
+    );
+    Ok(())
+}
+
+fn register_trades(ctx: &SessionContext) -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("k", DataType::Utf8, false),
+        Field::new("ts", DataType::Int32, false),
+        Field::new("v", DataType::Int32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from_slice(&["a", "a", "a", "b", "b", "c"])),
+            Arc::new(Int32Array::from_slice(&[1, 3, 2, 1, 2, 1])),
+            Arc::new(Int32Array::from_slice(&[10, 30, 20, 40, 50, 60])),
+        ],
+    )?;
+    let provider = MemTable::try_new(schema, vec![vec![batch]])?;
+    ctx.register_table("trades", Arc::new(provider))?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn window_qualify() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_trades(&ctx)?;
+
+    let sql = "SELECT k, v FROM trades \
+        QUALIFY row_number() OVER (PARTITION BY k ORDER BY ts DESC) = 1 ORDER BY k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+",
+        "| k | v  |",
+        "+---+----+",
+        "| a | 30 |",
+        "| b | 50 |",
+        "| c | 60 |",
+        "+---+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // referring to an aliased window function, with aggregates
+    let sql = "SELECT k, SUM(v) AS s, RANK() OVER (ORDER BY SUM(v) DESC) AS r \
+        FROM trades GROUP BY k QUALIFY r <= 2 ORDER BY r";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+---+",
+        "| k | s  | r |",
+        "+---+----+---+",
+        "| b | 90 | 1 |",
+        "| a | 60 | 2 |",
+        "| c | 60 | 2 |",
+        "+---+----+---+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn window_named_windows() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_trades(&ctx)?;
+
+    let sql = "SELECT k, ts, row_number() OVER w AS rn, \
+        SUM(v) OVER (w ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) AS s \
+        FROM trades WINDOW w AS (PARTITION BY k ORDER BY ts) ORDER BY k, ts";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+----+----+",
+        "| k | ts | rn | s  |",
+        "+---+----+----+----+",
+        "| a | 1  | 1  | 10 |",
+        "| a | 2  | 2  | 30 |",
+        "| a | 3  | 3  | 50 |",
+        "| b | 1  | 1  | 40 |",
+        "| b | 2  | 2  | 90 |",
+        "| c | 1  | 1  | 60 |",
+        "+---+----+----+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the ORDER BY clause refers to the windows of the select
+    let sql = "SELECT k, SUM(v) OVER w AS s FROM trades \
+        WINDOW w AS (PARTITION BY k) ORDER BY SUM(v) OVER w DESC, k";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+",
+        "| k | s  |",
+        "+---+----+",
+        "| b | 90 |",
+        "| b | 90 |",
+        "| a | 60 |",
+        "| a | 60 |",
+        "| a | 60 |",
+        "| c | 60 |",
+        "+---+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_distinct_on() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_trades(&ctx)?;
+
+    let sql = "SELECT DISTINCT ON (k) k, ts, v FROM trades ORDER BY k, ts DESC";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+----+",
+        "| k | ts | v  |",
+        "+---+----+----+",
+        "| a | 3  | 30 |",
+        "| b | 2  | 50 |",
+        "| c | 1  | 60 |",
+        "+---+----+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // aliases of the select list
+    let sql = "SELECT DISTINCT ON (key) k AS key, v FROM trades ORDER BY key, v LIMIT 2";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----+----+",
+        "| key | v  |",
+        "+-----+----+",
+        "| a   | 10 |",
+        "| b   | 40 |",
+        "+-----+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // positions of the select list
+    let sql = "SELECT DISTINCT ON (k) k, ts FROM trades ORDER BY 1, 2 DESC";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----+",
+        "| k | ts |",
+        "+---+----+",
+        "| a | 3  |",
+        "| b | 2  |",
+        "| c | 1  |",
+        "+---+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT DISTINCT ON (k) k, v * 2 FROM trades ORDER BY 1, 2";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+----------------------------+",
+        "| k | trades.v Multiply Int64(2) |",
+        "+---+----------------------------+",
+        "| a | 20                         |",
+        "| b | 80                         |",
+        "| c | 120                        |",
+        "+---+----------------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the DISTINCT ON expressions must be the leftmost ORDER BY expressions
+    let err = plan_and_collect(
+        &ctx,
+        "SELECT DISTINCT ON (k) k, ts FROM trades ORDER BY ts, k",
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: SELECT DISTINCT ON expressions must match initial ORDER BY expressions"
+    );
+    Ok(())
+}
@@ -18,7 +18,7 @@
 //! Accumulator module contains the trait definition for aggregation function's accumulators.
 
 use arrow::array::ArrayRef;
-use datafusion_common::{Result, ScalarValue};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
 use std::fmt::Debug;
 
 /// An accumulator represents a stateful object that lives throughout the evaluation of multiple rows and
@@ -29,6 +29,8 @@ use std::fmt::Debug;
 /// * convert its internal state to a vector of scalar values
 /// * update its state from multiple accumulators' states via `merge_batch`
 /// * compute the final value from its internal state via `evaluate`
+/// * optionally, remove previously added inputs from its state via `retract_batch`,
+///   which lets sliding window frames be evaluated incrementally
 pub trait Accumulator: Send + Sync + Debug {
     /// Returns the state of the accumulator at the end of the accumulation.
     // in the case of an average on which we track `sum` and `n`, this function should return a vector
@@ -43,4 +45,19 @@ pub trait Accumulator: Send + Sync + Debug {
 
     /// returns its value based on its current state.
     fn evaluate(&self) -> Result<ScalarValue>;
+
+    /// removes from the accumulator's state a vector of arrays that were previously
+    /// passed to `update_batch`. Only called when `supports_retract_batch` is true.
+    fn retract_batch(&mut self, _values: &[ArrayRef]) -> Result<()> {
+        Err(DataFusionError::NotImplemented(format!(
+            "Retractable accumulator hasn't been implemented for {:?} yet",
+            self
+        )))
+    }
+
+    /// whether the accumulator implements `retract_batch`. When it doesn't, a sliding
+    /// window frame is evaluated by accumulating each frame from scratch.
+    fn supports_retract_batch(&self) -> bool {
+        false
+    }
 }
@@ -40,6 +40,17 @@ pub static NUMERICS: &[DataType] = &[
     DataType::Float64,
 ];
 
+pub static INTEGERS: &[DataType] = &[
+    DataType::Int8,
+    DataType::Int16,
+    DataType::Int32,
+    DataType::Int64,
+    DataType::UInt8,
+    DataType::UInt16,
+    DataType::UInt32,
+    DataType::UInt64,
+];
+
 pub static TIMESTAMPS: &[DataType] = &[
     DataType::Timestamp(TimeUnit::Second, None),
     DataType::Timestamp(TimeUnit::Millisecond, None),
@@ -88,6 +99,46 @@ pub enum AggregateFunction {
     ApproxMedian,
     /// Grouping
     Grouping,
+    /// string_agg
+    StringAgg,
+    /// bool_and
+    BoolAnd,
+    /// bool_or
+    BoolOr,
+    /// bit_and
+    BitAnd,
+    /// bit_or
+    BitOr,
+    /// bit_xor
+    BitXor,
+    /// Exact median
+    Median,
+    /// Exact continuous percentile function
+    PercentileCont,
+    /// Most frequent value
+    Mode,
+    /// First value of the group, in the order of the `ORDER BY` clause if any
+    FirstValue,
+    /// Last value of the group, in the order of the `ORDER BY` clause if any
+    LastValue,
+    /// Slope of the least-squares-fit linear equation
+    RegrSlope,
+    /// Y-intercept of the least-squares-fit linear equation
+    RegrIntercept,
+    /// Number of rows in which both arguments are not null
+    RegrCount,
+    /// Square of the correlation coefficient
+    RegrR2,
+    /// Average of the independent variable
+    RegrAvgx,
+    /// Average of the dependent variable
+    RegrAvgy,
+    /// Sum of squares of the independent variable
+    RegrSxx,
+    /// Sum of squares of the dependent variable
+    RegrSyy,
+    /// Sum of products of the independent and the dependent variable
+    RegrSxy,
 }
 
 impl fmt::Display for AggregateFunction {
@@ -124,6 +175,26 @@ impl FromStr for AggregateFunction {
             }
             "approx_median" => AggregateFunction::ApproxMedian,
             "grouping" => AggregateFunction::Grouping,
+            "string_agg" => AggregateFunction::StringAgg,
+            "bool_and" | "every" => AggregateFunction::BoolAnd,
+            "bool_or" => AggregateFunction::BoolOr,
+            "bit_and" => AggregateFunction::BitAnd,
+            "bit_or" => AggregateFunction::BitOr,
+            "bit_xor" => AggregateFunction::BitXor,
+            "median" => AggregateFunction::Median,
+            "percentile_cont" => AggregateFunction::PercentileCont,
+            "mode" => AggregateFunction::Mode,
+            "first_value" => AggregateFunction::FirstValue,
+            "last_value" => AggregateFunction::LastValue,
+            "regr_slope" => AggregateFunction::RegrSlope,
+            "regr_intercept" => AggregateFunction::RegrIntercept,
+            "regr_count" => AggregateFunction::RegrCount,
+            "regr_r2" => AggregateFunction::RegrR2,
+            "regr_avgx" => AggregateFunction::RegrAvgx,
+            "regr_avgy" => AggregateFunction::RegrAvgy,
+            "regr_sxx" => AggregateFunction::RegrSxx,
+            "regr_syy" => AggregateFunction::RegrSyy,
+            "regr_sxy" => AggregateFunction::RegrSxy,
             _ => {
                 return Err(DataFusionError::Plan(format!(
                     "There is no built-in function named {}",
@@ -177,6 +248,26 @@ pub fn return_type(
         }
         AggregateFunction::ApproxMedian => Ok(coerced_data_types[0].clone()),
         AggregateFunction::Grouping => Ok(DataType::Int32),
+        AggregateFunction::StringAgg => Ok(coerced_data_types[0].clone()),
+        AggregateFunction::BoolAnd | AggregateFunction::BoolOr => Ok(DataType::Boolean),
+        AggregateFunction::BitAnd
+        | AggregateFunction::BitOr
+        | AggregateFunction::BitXor
+        | AggregateFunction::Mode
+        | AggregateFunction::FirstValue
+        | AggregateFunction::LastValue => Ok(coerced_data_types[0].clone()),
+        AggregateFunction::Median | AggregateFunction::PercentileCont => {
+            Ok(DataType::Float64)
+        }
+        AggregateFunction::RegrCount => Ok(DataType::UInt64),
+        AggregateFunction::RegrSlope
+        | AggregateFunction::RegrIntercept
+        | AggregateFunction::RegrR2
+        | AggregateFunction::RegrAvgx
+        | AggregateFunction::RegrAvgy
+        | AggregateFunction::RegrSxx
+        | AggregateFunction::RegrSyy
+        | AggregateFunction::RegrSxy => Ok(DataType::Float64),
     }
 }
 
@@ -331,6 +422,87 @@ pub fn coerce_types(
             Ok(input_types.to_vec())
         }
         AggregateFunction::Grouping => Ok(vec![input_types[0].clone()]),
+        AggregateFunction::StringAgg => {
+            if !matches!(input_types[0], DataType::Utf8 | DataType::LargeUtf8) {
+                return Err(DataFusionError::Plan(format!(
+                    "The function {:?} does not support inputs of type {:?}.",
+                    agg_fun, input_types[0]
+                )));
+            }
+            if !matches!(input_types[1], DataType::Utf8 | DataType::LargeUtf8) {
+                return Err(DataFusionError::Plan(format!(
+                    "The delimiter argument for {:?} must be a string, not {:?}.",
+                    agg_fun, input_types[1]
+                )));
+            }
+            Ok(input_types.to_vec())
+        }
+        AggregateFunction::BoolAnd | AggregateFunction::BoolOr => {
+            if !matches!(input_types[0], DataType::Boolean | DataType::Null) {
+                return Err(DataFusionError::Plan(format!(
+                    "The function {:?} does not support inputs of type {:?}.",
+                    agg_fun, input_types[0]
+                )));
+            }
+            Ok(vec![DataType::Boolean])
+        }
+        AggregateFunction::BitAnd
+        | AggregateFunction::BitOr
+        | AggregateFunction::BitXor => {
+            if !INTEGERS.contains(&input_types[0]) {
+                return Err(DataFusionError::Plan(format!(
+                    "The function {:?} does not support inputs of type {:?}.",
+                    agg_fun, input_types[0]
+                )));
+            }
+            Ok(input_types.to_vec())
+        }
+        AggregateFunction::Median => {
+            if !is_approx_percentile_cont_supported_arg_type(&input_types[0]) {
+                return Err(DataFusionError::Plan(format!(
+                    "The function {:?} does not support inputs of type {:?}.",
+                    agg_fun, input_types[0]
+                )));
+            }
+            Ok(input_types.to_vec())
+        }
+        AggregateFunction::PercentileCont => {
+            if !is_approx_percentile_cont_supported_arg_type(&input_types[0]) {
+                return Err(DataFusionError::Plan(format!(
+                    "The function {:?} does not support inputs of type {:?}.",
+                    agg_fun, input_types[0]
+                )));
+            }
+            if !matches!(input_types[1], DataType::Float64) {
+                return Err(DataFusionError::Plan(format!(
+                    "The percentile argument for {:?} must be Float64, not {:?}.",
+                    agg_fun, input_types[1]
+                )));
+            }
+            Ok(input_types.to_vec())
+        }
+        AggregateFunction::Mode
+        | AggregateFunction::FirstValue
+        | AggregateFunction::LastValue => Ok(input_types.to_vec()),
+        AggregateFunction::RegrSlope
+        | AggregateFunction::RegrIntercept
+        | AggregateFunction::RegrCount
+        | AggregateFunction::RegrR2
+        | AggregateFunction::RegrAvgx
+        | AggregateFunction::RegrAvgy
+        | AggregateFunction::RegrSxx
+        | AggregateFunction::RegrSyy
+        | AggregateFunction::RegrSxy => {
+            for input_type in input_types {
+                if !is_covariance_support_arg_type(input_type) {
+                    return Err(DataFusionError::Plan(format!(
+                        "The function {:?} does not support inputs of type {:?}.",
+                        agg_fun, input_type
+                    )));
+                }
+            }
+            Ok(input_types.to_vec())
+        }
     }
 }
 
@@ -341,7 +513,29 @@ pub fn signature(fun: &AggregateFunction) -> Signature {
         AggregateFunction::Count
         | AggregateFunction::ApproxDistinct
         | AggregateFunction::Grouping
-        | AggregateFunction::ArrayAgg => Signature::any(1, Volatility::Immutable),
+        | AggregateFunction::ArrayAgg
+        | AggregateFunction::Mode
+        | AggregateFunction::FirstValue
+        | AggregateFunction::LastValue => Signature::any(1, Volatility::Immutable),
+        AggregateFunction::StringAgg => Signature::one_of(
+            STRINGS
+                .iter()
+                .flat_map(|value| {
+                    STRINGS.iter().map(|delimiter| {
+                        TypeSignature::Exact(vec![value.clone(), delimiter.clone()])
+                    })
+                })
+                .collect(),
+            Volatility::Immutable,
+        ),
+        AggregateFunction::BoolAnd | AggregateFunction::BoolOr => {
+            Signature::uniform(1, vec![DataType::Boolean], Volatility::Immutable)
+        }
+        AggregateFunction::BitAnd
+        | AggregateFunction::BitOr
+        | AggregateFunction::BitXor => {
+            Signature::uniform(1, INTEGERS.to_vec(), Volatility::Immutable)
+        }
         AggregateFunction::Min | AggregateFunction::Max => {
             let valid = STRINGS
                 .iter()
@@ -358,23 +552,35 @@ pub fn signature(fun: &AggregateFunction) -> Signature {
         | AggregateFunction::VariancePop
         | AggregateFunction::Stddev
         | AggregateFunction::StddevPop
-        | AggregateFunction::ApproxMedian => {
+        | AggregateFunction::ApproxMedian
+        | AggregateFunction::Median => {
             Signature::uniform(1, NUMERICS.to_vec(), Volatility::Immutable)
         }
         AggregateFunction::Covariance | AggregateFunction::CovariancePop => {
             Signature::uniform(2, NUMERICS.to_vec(), Volatility::Immutable)
         }
-        AggregateFunction::Correlation => {
+        AggregateFunction::Correlation
+        | AggregateFunction::RegrSlope
+        | AggregateFunction::RegrIntercept
+        | AggregateFunction::RegrCount
+        | AggregateFunction::RegrR2
+        | AggregateFunction::RegrAvgx
+        | AggregateFunction::RegrAvgy
+        | AggregateFunction::RegrSxx
+        | AggregateFunction::RegrSyy
+        | AggregateFunction::RegrSxy => {
             Signature::uniform(2, NUMERICS.to_vec(), Volatility::Immutable)
         }
-        AggregateFunction::ApproxPercentileCont => Signature::one_of(
-            // Accept any numeric value paired with a float64 percentile
-            NUMERICS
-                .iter()
-                .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Float64]))
-                .collect(),
-            Volatility::Immutable,
-        ),
+        AggregateFunction::ApproxPercentileCont | AggregateFunction::PercentileCont => {
+            Signature::one_of(
+                // Accept any numeric value paired with a float64 percentile
+                NUMERICS
+                    .iter()
+                    .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Float64]))
+                    .collect(),
+                Volatility::Immutable,
+            )
+        }
         AggregateFunction::ApproxPercentileContWithWeight => Signature::one_of(
             // Accept any numeric value paired with a float64 percentile
             NUMERICS
@@ -796,6 +1002,103 @@ mod tests {
             );
             assert_eq!(*input_type, result.unwrap());
         }
+
+        // bit_and, bit_or and bit_xor only support integers
+        for fun in [
+            AggregateFunction::BitAnd,
+            AggregateFunction::BitOr,
+            AggregateFunction::BitXor,
+        ] {
+            let signature = aggregate_function::signature(&fun);
+            let result = coerce_types(&fun, &[DataType::UInt16], &signature);
+            assert_eq!(vec![DataType::UInt16], result.unwrap());
+            let result = coerce_types(&fun, &[DataType::Float64], &signature);
+            assert!(result.is_err());
+        }
+
+        // bool_and and bool_or coerce nulls to booleans
+        let fun = AggregateFunction::BoolAnd;
+        let signature = aggregate_function::signature(&fun);
+        let result = coerce_types(&fun, &[DataType::Null], &signature);
+        assert_eq!(vec![DataType::Boolean], result.unwrap());
+        let result = coerce_types(&fun, &[DataType::Int32], &signature);
+        assert_eq!(
+            "Error during planning: The function BoolAnd does not support inputs of type Int32.",
+            result.unwrap_err().to_string()
+        );
+
+        // string_agg takes a string and a string delimiter
+        let fun = AggregateFunction::StringAgg;
+        let signature = aggregate_function::signature(&fun);
+        let input_types = vec![DataType::LargeUtf8, DataType::Utf8];
+        let result = coerce_types(&fun, &input_types, &signature);
+        assert_eq!(input_types, result.unwrap());
+        let result = coerce_types(&fun, &[DataType::Utf8, DataType::Int32], &signature);
+        assert!(result.is_err());
+
+        // the regr_* functions take two numbers
+        let fun = AggregateFunction::RegrSlope;
+        let signature = aggregate_function::signature(&fun);
+        let input_types = vec![DataType::Int32, DataType::Float64];
+        let result = coerce_types(&fun, &input_types, &signature);
+        assert_eq!(input_types, result.unwrap());
+        let result = coerce_types(&fun, &[DataType::Int32], &signature);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_new_aggregate_return_data_type() -> Result<()> {
+        let cases = vec![
+            (
+                AggregateFunction::BitXor,
+                vec![DataType::Int16],
+                DataType::Int16,
+            ),
+            (
+                AggregateFunction::BoolOr,
+                vec![DataType::Boolean],
+                DataType::Boolean,
+            ),
+            (
+                AggregateFunction::StringAgg,
+                vec![DataType::LargeUtf8, DataType::Utf8],
+                DataType::LargeUtf8,
+            ),
+            (
+                AggregateFunction::Median,
+                vec![DataType::Int32],
+                DataType::Float64,
+            ),
+            (
+                AggregateFunction::PercentileCont,
+                vec![DataType::UInt8, DataType::Float64],
+                DataType::Float64,
+            ),
+            (
+                AggregateFunction::Mode,
+                vec![DataType::Utf8],
+                DataType::Utf8,
+            ),
+            (
+                AggregateFunction::LastValue,
+                vec![DataType::Date32],
+                DataType::Date32,
+            ),
+            (
+                AggregateFunction::RegrCount,
+                vec![DataType::Int32, DataType::Int64],
+                DataType::UInt64,
+            ),
+            (
+                AggregateFunction::RegrR2,
+                vec![DataType::Int32, DataType::Int64],
+                DataType::Float64,
+            ),
+        ];
+        for (fun, input_types, expected) in cases {
+            assert_eq!(expected, return_type(&fun, &input_types)?);
+        }
+        Ok(())
     }
 
     #[test]
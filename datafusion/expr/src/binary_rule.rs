@@ -504,14 +504,8 @@ fn temporal_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataTyp
         (Date64, Utf8) => Some(Date64),
         (Timestamp(lhs_unit, lhs_tz), Timestamp(rhs_unit, rhs_tz)) => {
             let tz = match (lhs_tz, rhs_tz) {
-                // can't cast across timezones
-                (Some(lhs_tz), Some(rhs_tz)) => {
-                    if lhs_tz != rhs_tz {
-                        return None;
-                    } else {
-                        Some(lhs_tz.clone())
-                    }
-                }
+                // the same instants in any of the time zones
+                (Some(lhs_tz), Some(_)) => Some(lhs_tz.clone()),
                 (Some(lhs_tz), None) => Some(lhs_tz.clone()),
                 (None, Some(rhs_tz)) => Some(rhs_tz.clone()),
                 (None, None) => None,
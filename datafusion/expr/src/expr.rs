@@ -197,6 +197,11 @@ pub enum Expr {
         args: Vec<Expr>,
         /// Whether this is a DISTINCT aggregation or not
         distinct: bool,
+        /// Optional filter, only the rows for which it is true are aggregated
+        filter: Option<Box<Expr>>,
+        /// List of order by expressions, for the aggregates whose result depends on
+        /// the order of their input
+        order_by: Vec<Expr>,
     },
     /// Represents the call of a window function with arguments.
     WindowFunction {
@@ -455,6 +460,7 @@ impl std::fmt::Display for Expr {
                 ref args,
                 /// Whether this is a DISTINCT aggregation or not
                 ref distinct,
+                ..
             } => fmt_function(f, &fun.to_string(), *distinct, args, true),
             Expr::ScalarFunction {
                 /// Name of the function
@@ -575,8 +581,18 @@ impl fmt::Debug for Expr {
                 fun,
                 distinct,
                 ref args,
-                ..
-            } => fmt_function(f, &fun.to_string(), *distinct, args, true),
+                filter,
+                order_by,
+            } => {
+                fmt_function(f, &fun.to_string(), *distinct, args, true)?;
+                if !order_by.is_empty() {
+                    write!(f, " ORDER BY {:?}", order_by)?;
+                }
+                if let Some(filter) = filter {
+                    write!(f, " FILTER (WHERE {:?})", filter)?;
+                }
+                Ok(())
+            }
             Expr::AggregateUDF { fun, ref args, .. } => {
                 fmt_function(f, &fun.name, false, args, false)
             }
@@ -798,8 +814,20 @@ fn create_name(e: &Expr, input_schema: &DFSchema) -> Result<String> {
             fun,
             distinct,
             args,
-            ..
-        } => create_function_name(&fun.to_string(), *distinct, args, input_schema),
+            filter,
+            order_by,
+        } => {
+            let mut name =
+                create_function_name(&fun.to_string(), *distinct, args, input_schema)?;
+            if !order_by.is_empty() {
+                name += &format!(" ORDER BY {:?}", order_by);
+            }
+            if let Some(filter) = filter {
+                name +=
+                    &format!(" FILTER (WHERE {})", create_name(filter, input_schema)?);
+            }
+            Ok(name)
+        }
         Expr::AggregateUDF { fun, args } => {
             let mut names = Vec::with_capacity(args.len());
             for e in args {
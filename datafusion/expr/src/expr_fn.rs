@@ -60,6 +60,8 @@ pub fn min(expr: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::Min,
         distinct: false,
         args: vec![expr],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -69,6 +71,8 @@ pub fn max(expr: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::Max,
         distinct: false,
         args: vec![expr],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -78,6 +82,8 @@ pub fn sum(expr: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::Sum,
         distinct: false,
         args: vec![expr],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -87,6 +93,8 @@ pub fn avg(expr: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::Avg,
         distinct: false,
         args: vec![expr],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -96,6 +104,8 @@ pub fn count(expr: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::Count,
         distinct: false,
         args: vec![expr],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -105,6 +115,8 @@ pub fn count_distinct(expr: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::Count,
         distinct: true,
         args: vec![expr],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -157,6 +169,8 @@ pub fn approx_distinct(expr: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::ApproxDistinct,
         distinct: false,
         args: vec![expr],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -166,6 +180,8 @@ pub fn approx_percentile_cont(expr: Expr, percentile: Expr) -> Expr {
         fun: aggregate_function::AggregateFunction::ApproxPercentileCont,
         distinct: false,
         args: vec![expr, percentile],
+        filter: None,
+        order_by: vec![],
     }
 }
 
@@ -179,6 +195,8 @@ pub fn approx_percentile_cont_with_weight(
         fun: aggregate_function::AggregateFunction::ApproxPercentileContWithWeight,
         distinct: false,
         args: vec![expr, weight_expr, percentile],
+        filter: None,
+        order_by: vec![],
     }
 }
 
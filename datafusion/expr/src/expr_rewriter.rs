@@ -18,7 +18,7 @@
 //! Expression rewriter
 
 use crate::expr::GroupingSet;
-use crate::logical_plan::Aggregate;
+use crate::logical_plan::{Aggregate, Window};
 use crate::{Expr, ExprSchemable, LogicalPlan};
 use datafusion_common::Result;
 use datafusion_common::{Column, DFSchema};
@@ -208,10 +208,14 @@ impl ExprRewritable for Expr {
                 args,
                 fun,
                 distinct,
+                filter,
+                order_by,
             } => Expr::AggregateFunction {
                 args: rewrite_vec(args, rewriter)?,
                 fun,
                 distinct,
+                filter: rewrite_option_box(filter, rewriter)?,
+                order_by: rewrite_vec(order_by, rewriter)?,
             },
             Expr::GroupingSet(grouping_set) => match grouping_set {
                 GroupingSet::Rollup(exprs) => {
@@ -293,8 +297,8 @@ where
     v.into_iter().map(|expr| expr.rewrite(rewriter)).collect()
 }
 
-/// Rewrite sort on aggregate expressions to sort on the column of aggregate output
-/// For example, `max(x)` is written to `col("MAX(x)")`
+/// Rewrite sort on aggregate and window expressions to sort on the column of
+/// their output. For example, `max(x)` is written to `col("MAX(x)")`
 pub fn rewrite_sort_cols_by_aggs(
     exprs: impl IntoIterator<Item = impl Into<Expr>>,
     plan: &LogicalPlan,
@@ -326,47 +330,58 @@ fn rewrite_sort_col_by_aggs(expr: Expr, plan: &LogicalPlan) -> Result<Expr> {
     match plan {
         LogicalPlan::Aggregate(Aggregate {
             input, aggr_expr, ..
+        }) => rewrite_sort_col_by_exprs(expr, plan, input, aggr_expr),
+        LogicalPlan::Window(Window {
+            input, window_expr, ..
         }) => {
-            struct Rewriter<'a> {
-                plan: &'a LogicalPlan,
-                input: &'a LogicalPlan,
-                aggr_expr: &'a Vec<Expr>,
-            }
-
-            impl<'a> ExprRewriter for Rewriter<'a> {
-                fn mutate(&mut self, expr: Expr) -> Result<Expr> {
-                    let normalized_expr = normalize_col(expr.clone(), self.plan);
-                    if normalized_expr.is_err() {
-                        // The expr is not based on Aggregate plan output. Skip it.
-                        return Ok(expr);
-                    }
-                    let normalized_expr = normalized_expr.unwrap();
-                    if let Some(found_agg) =
-                        self.aggr_expr.iter().find(|a| (**a) == normalized_expr)
-                    {
-                        let agg = normalize_col(found_agg.clone(), self.plan)?;
-                        let col = Expr::Column(
-                            agg.to_field(self.input.schema())
-                                .map(|f| f.qualified_column())?,
-                        );
-                        Ok(col)
-                    } else {
-                        Ok(expr)
-                    }
-                }
-            }
-
-            expr.rewrite(&mut Rewriter {
-                plan,
-                input,
-                aggr_expr,
-            })
+            // the window functions may be computed from the output of an aggregate
+            let expr = rewrite_sort_col_by_aggs(expr, input)?;
+            rewrite_sort_col_by_exprs(expr, plan, input, window_expr)
         }
         LogicalPlan::Projection(_) => rewrite_sort_col_by_aggs(expr, plan.inputs()[0]),
         _ => Ok(expr),
     }
 }
 
+/// Rewrite the sub-expressions of `expr` that are among the expressions `exprs`
+/// computed by `plan` from `input` to the column of their output
+fn rewrite_sort_col_by_exprs(
+    expr: Expr,
+    plan: &LogicalPlan,
+    input: &LogicalPlan,
+    exprs: &[Expr],
+) -> Result<Expr> {
+    struct Rewriter<'a> {
+        plan: &'a LogicalPlan,
+        input: &'a LogicalPlan,
+        exprs: &'a [Expr],
+    }
+
+    impl<'a> ExprRewriter for Rewriter<'a> {
+        fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+            let normalized_expr = normalize_col(expr.clone(), self.plan);
+            if normalized_expr.is_err() {
+                // The expr is not based on the plan output. Skip it.
+                return Ok(expr);
+            }
+            let normalized_expr = normalized_expr.unwrap();
+            if let Some(found) = self.exprs.iter().find(|a| (**a) == normalized_expr) {
+                let found = normalize_col(found.clone(), self.plan)?;
+                let col = Expr::Column(
+                    found
+                        .to_field(self.input.schema())
+                        .map(|f| f.qualified_column())?,
+                );
+                Ok(col)
+            } else {
+                Ok(expr)
+            }
+        }
+    }
+
+    expr.rewrite(&mut Rewriter { plan, input, exprs })
+}
+
 /// Recursively call [`Column::normalize_with_schemas`] on all Column expressions
 /// in the `expr` expression tree.
 pub fn normalize_col(expr: Expr, plan: &LogicalPlan) -> Result<Expr> {
@@ -170,7 +170,8 @@ impl ExprSchemable for Expr {
                 } else if let Some(e) = else_expr {
                     e.nullable(input_schema)
                 } else {
-                    Ok(false)
+                    // without an else, rows that match no when are null
+                    Ok(true)
                 }
             }
             Expr::Cast { expr, .. } => expr.nullable(input_schema),
@@ -258,7 +259,7 @@ impl ExprSchemable for Expr {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{col, lit};
+    use crate::{col, lit, when};
     use arrow::datatypes::DataType;
     use datafusion_common::Column;
 
@@ -271,6 +272,17 @@ mod tests {
             .unwrap());
     }
 
+    #[test]
+    fn case_without_else_nullability() {
+        let expr = when(col("foo").eq(lit(1)), lit(2)).end().unwrap();
+        assert!(expr.nullable(&MockExprSchema::new()).unwrap());
+
+        let expr = when(col("foo").eq(lit(1)), lit(2))
+            .otherwise(lit(3))
+            .unwrap();
+        assert!(!expr.nullable(&MockExprSchema::new()).unwrap());
+    }
+
     #[test]
     fn expr_schema_data_type() {
         let expr = col("foo");
@@ -160,10 +160,26 @@ impl ExprVisitable for Expr {
             }
             Expr::ScalarFunction { args, .. }
             | Expr::ScalarUDF { args, .. }
-            | Expr::AggregateFunction { args, .. }
             | Expr::AggregateUDF { args, .. } => args
                 .iter()
                 .try_fold(visitor, |visitor, arg| arg.accept(visitor)),
+            Expr::AggregateFunction {
+                args,
+                filter,
+                order_by,
+                ..
+            } => {
+                let visitor = args
+                    .iter()
+                    .try_fold(visitor, |visitor, arg| arg.accept(visitor))?;
+                let visitor = match filter {
+                    Some(filter) => filter.accept(visitor)?,
+                    None => visitor,
+                };
+                order_by
+                    .iter()
+                    .try_fold(visitor, |visitor, arg| arg.accept(visitor))
+            }
             Expr::WindowFunction {
                 args,
                 partition_by,
@@ -22,7 +22,7 @@ use crate::type_coercion::data_types;
 use crate::ColumnarValue;
 use crate::{
     array_expressions, conditional_expressions, struct_expressions, Accumulator,
-    BuiltinScalarFunction, Signature, TypeSignature,
+    BuiltinScalarFunction, PartitionEvaluator, Signature, TypeSignature,
 };
 use arrow::datatypes::{DataType, Field, TimeUnit};
 use datafusion_common::{DataFusionError, Result};
@@ -47,6 +47,10 @@ pub type ReturnTypeFunction =
 pub type AccumulatorFunctionImplementation =
     Arc<dyn Fn() -> Result<Box<dyn Accumulator>> + Send + Sync>;
 
+/// the implementation of a window function, which creates the evaluator of a partition
+pub type PartitionEvaluatorFunctionImplementation =
+    Arc<dyn Fn() -> Result<Box<dyn PartitionEvaluator>> + Send + Sync>;
+
 /// This signature corresponds to which types an aggregator serializes
 /// its state, given its return datatype.
 pub type StateTypeFunction =
@@ -117,9 +121,12 @@ pub fn return_type(
         BuiltinScalarFunction::Concat => Ok(DataType::Utf8),
         BuiltinScalarFunction::ConcatWithSeparator => Ok(DataType::Utf8),
         BuiltinScalarFunction::DatePart => Ok(DataType::Int32),
-        BuiltinScalarFunction::DateTrunc => {
-            Ok(DataType::Timestamp(TimeUnit::Nanosecond, None))
-        }
+        BuiltinScalarFunction::DateTrunc => match &input_expr_types[1] {
+            DataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+                Ok(DataType::Timestamp(TimeUnit::Nanosecond, tz.clone()))
+            }
+            _ => Ok(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        },
         BuiltinScalarFunction::InitCap => {
             utf8_to_str_type(&input_expr_types[0], "initcap")
         }
@@ -17,21 +17,25 @@
 
 //! This module provides a builder for creating LogicalPlans
 
-use crate::expr_rewriter::{normalize_col, normalize_cols, rewrite_sort_cols_by_aggs};
+use crate::expr_fn::binary_expr;
+use crate::expr_rewriter::{
+    normalize_col, normalize_col_with_schemas, normalize_cols, rewrite_sort_cols_by_aggs,
+};
 use crate::utils::{columnize_expr, exprlist_to_fields, from_plan};
 use crate::{
     logical_plan::{
         Aggregate, Analyze, CrossJoin, EmptyRelation, Explain, Filter, Join,
         JoinConstraint, JoinType, Limit, LogicalPlan, Offset, Partitioning, PlanType,
-        Projection, Repartition, Sort, SubqueryAlias, TableScan, ToStringifiedPlan,
-        Union, Values, Window,
+        Projection, RecursiveQuery, Repartition, Sort, SubqueryAlias, TableScan,
+        ToStringifiedPlan, Union, Unnest, UnnestOptions, Values, Window,
     },
     utils::{
         expand_qualified_wildcard, expand_wildcard, expr_to_columns,
         group_window_expr_by_sort_keys,
     },
-    Expr, ExprSchemable, TableSource,
+    Expr, ExprSchemable, Operator, TableSource,
 };
+use arrow::compute::can_cast_types;
 use arrow::datatypes::{DataType, Schema, SchemaRef};
 use datafusion_common::{
     Column, DFField, DFSchema, DFSchemaRef, DataFusionError, Result, ScalarValue,
@@ -477,6 +481,48 @@ impl LogicalPlanBuilder {
         join_type: JoinType,
         join_keys: (Vec<impl Into<Column>>, Vec<impl Into<Column>>),
         null_equals_null: bool,
+    ) -> Result<Self> {
+        self.join_with_keys(right, join_type, join_keys, None, null_equals_null)
+    }
+
+    /// Apply a join with on constraint, whose joined rows must also satisfy
+    /// `filter`. The join keys may be empty.
+    pub fn join_with_filter(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        join_keys: (Vec<impl Into<Column>>, Vec<impl Into<Column>>),
+        filter: Expr,
+    ) -> Result<Self> {
+        let mut schemas = self.plan.all_schemas();
+        schemas.extend(right.all_schemas());
+        let mut using_columns = self.plan.using_columns()?;
+        using_columns.extend(right.using_columns()?);
+        let filter = normalize_col_with_schemas(filter, &schemas, &using_columns)?;
+        self.join_with_keys(right, join_type, join_keys, Some(filter), false)
+    }
+
+    /// Apply an ASOF join, which pairs each row with the nearest row of `right` that
+    /// has equal join keys and satisfies `match_condition`, or with nulls if there is
+    /// none. The match condition compares a column of each side with `>=`, `>`, `<=`
+    /// or `<`, e.g. `trades.ts >= quotes.ts` pairs each trade with the latest quote
+    /// at or before it.
+    pub fn join_asof(
+        &self,
+        right: &LogicalPlan,
+        join_keys: (Vec<impl Into<Column>>, Vec<impl Into<Column>>),
+        match_condition: Expr,
+    ) -> Result<Self> {
+        self.join_with_filter(right, JoinType::AsOf, join_keys, match_condition)
+    }
+
+    fn join_with_keys(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        join_keys: (Vec<impl Into<Column>>, Vec<impl Into<Column>>),
+        filter: Option<Expr>,
+        null_equals_null: bool,
     ) -> Result<Self> {
         if join_keys.0.len() != join_keys.1.len() {
             return Err(DataFusionError::Plan(
@@ -484,6 +530,18 @@ impl LogicalPlanBuilder {
             ));
         }
 
+        let filter = match (join_type, filter) {
+            (JoinType::AsOf, Some(filter)) => {
+                Some(self.asof_match_condition(right, filter)?)
+            }
+            (JoinType::AsOf, None) => {
+                return Err(DataFusionError::Plan(
+                    "ASOF join requires a match condition".to_string(),
+                ))
+            }
+            (_, filter) => filter,
+        };
+
         let (left_keys, right_keys): (Vec<Result<Column>>, Vec<Result<Column>>) =
             join_keys
                 .0
@@ -571,13 +629,55 @@ impl LogicalPlanBuilder {
             left: Arc::new(self.plan.clone()),
             right: Arc::new(right.clone()),
             on,
+            filter,
             join_type,
             join_constraint: JoinConstraint::On,
             schema: DFSchemaRef::new(join_schema),
             null_equals_null,
+            null_aware: false,
         })))
     }
 
+    /// Returns the match condition of an ASOF join with `right`, with the column of
+    /// this plan on its left side
+    fn asof_match_condition(&self, right: &LogicalPlan, condition: Expr) -> Result<Expr> {
+        if let Expr::BinaryExpr {
+            left: l,
+            op,
+            right: r,
+        } = &condition
+        {
+            let swapped_op = match op {
+                Operator::GtEq => Some(Operator::LtEq),
+                Operator::Gt => Some(Operator::Lt),
+                Operator::LtEq => Some(Operator::GtEq),
+                Operator::Lt => Some(Operator::Gt),
+                _ => None,
+            };
+            if let (Expr::Column(l), Expr::Column(r), Some(swapped_op)) =
+                (l.as_ref(), r.as_ref(), swapped_op)
+            {
+                let is_left =
+                    |c: &Column| self.plan.schema().field_from_column(c).is_ok();
+                let is_right = |c: &Column| right.schema().field_from_column(c).is_ok();
+                if is_left(l) && is_right(r) {
+                    return Ok(condition);
+                }
+                if is_left(r) && is_right(l) {
+                    return Ok(binary_expr(
+                        Expr::Column(r.clone()),
+                        swapped_op,
+                        Expr::Column(l.clone()),
+                    ));
+                }
+            }
+        }
+        Err(DataFusionError::Plan(format!(
+            "ASOF match condition must compare a column of each side with >=, >, <= or <, found {:?}",
+            condition
+        )))
+    }
+
     /// Apply a join with using constraint, which duplicates all join columns in output schema.
     pub fn join_using(
         &self,
@@ -603,10 +703,12 @@ impl LogicalPlanBuilder {
             left: Arc::new(self.plan.clone()),
             right: Arc::new(right.clone()),
             on,
+            filter: None,
             join_type,
             join_constraint: JoinConstraint::Using,
             schema: DFSchemaRef::new(join_schema),
             null_equals_null: false,
+            null_aware: false,
         })))
     }
 
@@ -620,6 +722,42 @@ impl LogicalPlanBuilder {
         })))
     }
 
+    /// Unnest the list column `column`, producing a row for each of its elements
+    pub fn unnest_column(&self, column: impl Into<Column>) -> Result<Self> {
+        self.unnest_column_with_options(column, UnnestOptions::default())
+    }
+
+    /// Unnest the list column `column` with the given options
+    pub fn unnest_column_with_options(
+        &self,
+        column: impl Into<Column>,
+        options: UnnestOptions,
+    ) -> Result<Self> {
+        Ok(Self::from(unnest_with_options(
+            self.plan.clone(),
+            column.into(),
+            options,
+        )?))
+    }
+
+    /// Evaluate this plan as the static term of the recursive query `name`,
+    /// repeatedly applying `recursive_term` to the rows of the previous
+    /// iteration, which it scans as the table `name`. Duplicate rows are
+    /// removed if `is_distinct` is true.
+    pub fn to_recursive_query(
+        &self,
+        name: impl Into<String>,
+        recursive_term: LogicalPlan,
+        is_distinct: bool,
+    ) -> Result<Self> {
+        Ok(Self::from(recursive_query(
+            name.into(),
+            self.plan.clone(),
+            recursive_term,
+            is_distinct,
+        )?))
+    }
+
     /// Repartition
     pub fn repartition(&self, partitioning_scheme: Partitioning) -> Result<Self> {
         Ok(Self::from(LogicalPlan::Repartition(Repartition {
@@ -774,7 +912,11 @@ pub fn build_join_schema(
     join_type: &JoinType,
 ) -> Result<DFSchema> {
     let fields: Vec<DFField> = match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::Full
+        | JoinType::Right
+        | JoinType::AsOf => {
             let right_fields = right.fields().iter();
             let left_fields = left.fields().iter();
             // left then right
@@ -934,6 +1076,126 @@ pub fn project_with_alias(
     }))
 }
 
+/// Unnest the list column `column` of `input`. The list is replaced in the
+/// output by its elements, followed by an ordinality column if requested.
+/// # Errors
+/// This function errors if `column` is not a `List`, `LargeList` or
+/// `FixedSizeList`.
+pub fn unnest_with_options(
+    input: LogicalPlan,
+    column: Column,
+    options: UnnestOptions,
+) -> Result<LogicalPlan> {
+    let input_schema = input.schema();
+    let list_field = input_schema.field_from_column(&column)?;
+    let element_type = match list_field.data_type() {
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _) => field.data_type().clone(),
+        other => {
+            return Err(DataFusionError::Plan(format!(
+                "Unnest of column {} requires a list, got {:?}",
+                list_field.qualified_name(),
+                other
+            )))
+        }
+    };
+    let qualifier = list_field.qualifier().map(|q| q.as_str());
+    let mut fields = input_schema
+        .fields()
+        .iter()
+        .map(|field| {
+            if field == list_field {
+                DFField::new(qualifier, field.name(), element_type.clone(), true)
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    if let Some(ordinality) = &options.ordinality {
+        let nullable = options.preserve_nulls;
+        fields.push(DFField::new(
+            qualifier,
+            ordinality,
+            DataType::Int64,
+            nullable,
+        ));
+    }
+    let schema = DFSchema::new_with_metadata(fields, input_schema.metadata().clone())?;
+
+    Ok(LogicalPlan::Unnest(Unnest {
+        column: list_field.qualified_column(),
+        input: Arc::new(input),
+        options,
+        schema: Arc::new(schema),
+    }))
+}
+
+/// Creates a [`RecursiveQuery`], casting the columns of `recursive_term` to
+/// the types of those of `static_term` where they differ
+pub fn recursive_query(
+    name: String,
+    static_term: LogicalPlan,
+    recursive_term: LogicalPlan,
+    is_distinct: bool,
+) -> Result<LogicalPlan> {
+    let static_fields = static_term.schema().fields();
+    let recursive_fields = recursive_term.schema().fields();
+    if static_fields.len() != recursive_fields.len() {
+        return Err(DataFusionError::Plan(format!(
+            "Recursive query {} has {} columns in its static term but {} in its recursive term",
+            name,
+            static_fields.len(),
+            recursive_fields.len()
+        )));
+    }
+
+    let recursive_term = if static_fields
+        .iter()
+        .zip(recursive_fields)
+        .all(|(s, r)| s.data_type() == r.data_type())
+    {
+        recursive_term
+    } else {
+        let expr = static_fields
+            .iter()
+            .zip(recursive_fields)
+            .map(|(s, r)| {
+                if !can_cast_types(r.data_type(), s.data_type()) {
+                    return Err(DataFusionError::Plan(format!(
+                        "Recursive query {} cannot cast column {} from {:?} to {:?}",
+                        name,
+                        r.qualified_name(),
+                        r.data_type(),
+                        s.data_type()
+                    )));
+                }
+                Ok(Expr::Cast {
+                    expr: Box::new(Expr::Column(r.qualified_column())),
+                    data_type: s.data_type().clone(),
+                }
+                .alias(s.name()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        project_with_alias(recursive_term, expr, None)?
+    };
+
+    let fields = static_fields
+        .iter()
+        .map(|f| DFField::new(None, f.name(), f.data_type().clone(), true))
+        .collect();
+    let schema =
+        DFSchema::new_with_metadata(fields, static_term.schema().metadata().clone())?;
+
+    Ok(LogicalPlan::RecursiveQuery(RecursiveQuery {
+        name,
+        static_term: Arc::new(static_term),
+        recursive_term: Arc::new(recursive_term),
+        is_distinct,
+        schema: Arc::new(schema),
+    }))
+}
+
 /// Create a LogicalPlanBuilder representing a scan of a table with the provided name and schema.
 /// This is mostly used for testing and documentation.
 pub fn table_scan(
@@ -1023,6 +1285,88 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_unnest() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new(
+                "tags",
+                DataType::FixedSizeList(
+                    Box::new(Field::new("item", DataType::Utf8, false)),
+                    2,
+                ),
+                false,
+            ),
+        ]);
+        let plan = table_scan(Some("t"), &schema, None)?
+            .unnest_column_with_options(
+                "tags",
+                UnnestOptions::default()
+                    .with_preserve_nulls(true)
+                    .with_ordinality("n"),
+            )?
+            .build()?;
+
+        let expected = "Unnest: #t.tags, preserve_nulls, ordinality=n\
+        \n  TableScan: t projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        let fields = plan.schema().fields();
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[1].qualified_name(), "t.tags");
+        assert_eq!(fields[1].data_type(), &DataType::Utf8);
+        assert!(fields[1].is_nullable());
+        assert_eq!(fields[2].qualified_name(), "t.n");
+        assert_eq!(fields[2].data_type(), &DataType::Int64);
+
+        let err = match table_scan(Some("t"), &schema, None)?.unnest_column("id") {
+            Err(e) => e,
+            Ok(_) => panic!("expected an error"),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Unnest of column t.id requires a list, got Int32"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_recursive_query() -> Result<()> {
+        let static_schema = Schema::new(vec![Field::new("n", DataType::Int32, false)]);
+        let work_schema = Schema::new(vec![Field::new("n", DataType::Int64, true)]);
+        let recursive_term = table_scan(Some("t"), &work_schema, None)?
+            .filter(col("n").lt(lit(10i64)))?
+            .build()?;
+        let plan = table_scan(Some("base"), &static_schema, None)?
+            .to_recursive_query("t", recursive_term, true)?
+            .build()?;
+
+        let expected = "RecursiveQuery: name=t, distinct\
+        \n  TableScan: base projection=None\
+        \n  Projection: CAST(#t.n AS Int32) AS n\
+        \n    Filter: #t.n < Int64(10)\
+        \n      TableScan: t projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        let fields = plan.schema().fields();
+        assert_eq!(fields[0].qualified_name(), "n");
+        assert_eq!(fields[0].data_type(), &DataType::Int32);
+        assert!(fields[0].is_nullable());
+
+        let recursive_term = table_scan(Some("t"), &employee_schema(), None)?.build()?;
+        let err = match table_scan(Some("base"), &static_schema, None)?
+            .to_recursive_query("t", recursive_term, false)
+        {
+            Err(e) => e,
+            Ok(_) => panic!("expected an error"),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Recursive query t has 1 columns in its static term but 5 in its recursive term"
+        );
+        Ok(())
+    }
+
     #[test]
     fn plan_builder_sort() -> Result<()> {
         let plan =
@@ -1069,6 +1413,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_asof_join() -> Result<()> {
+        let t2 = table_scan(Some("t2"), &employee_schema(), None)?.build()?;
+
+        // the match condition is flipped to have the left column first
+        let plan = table_scan(Some("t1"), &employee_schema(), None)?
+            .join_asof(
+                &t2,
+                (vec!["state"], vec!["state"]),
+                col("t2.id").lt(col("t1.id")),
+            )?
+            .build()?;
+
+        let expected = "AsOf Join: #t1.state = #t2.state Filter: #t1.id > #t2.id\
+        \n  TableScan: t1 projection=None\
+        \n  TableScan: t2 projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        let res = table_scan(Some("t1"), &employee_schema(), None)?.join_asof(
+            &t2,
+            (vec!["state"], vec!["state"]),
+            col("t1.id").eq(col("t2.id")),
+        );
+        match res {
+            Err(e) => assert!(e.to_string().contains("ASOF match condition"), "{}", e),
+            Ok(_) => panic!("expected an error for an equality match condition"),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn plan_builder_union_combined_single_union() -> Result<()> {
         let plan =
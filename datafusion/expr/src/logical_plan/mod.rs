@@ -25,9 +25,9 @@ pub use plan::{
     Aggregate, Analyze, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
     CreateMemoryTable, CreateView, CrossJoin, DropTable, EmptyRelation, Explain,
     Extension, FileType, Filter, Join, JoinConstraint, JoinType, Limit, LogicalPlan,
-    Offset, Partitioning, PlanType, PlanVisitor, Projection, Repartition, Sort,
-    StringifiedPlan, Subquery, SubqueryAlias, TableScan, ToStringifiedPlan, Union,
-    Values, Window,
+    Offset, Partitioning, PlanType, PlanVisitor, Projection, RecursiveQuery, Repartition,
+    Sort, StringifiedPlan, Subquery, SubqueryAlias, TableScan, ToStringifiedPlan, Union,
+    Unnest, UnnestOptions, Values, Window,
 };
 
 pub use display::display_schema;
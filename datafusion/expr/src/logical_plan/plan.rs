@@ -63,6 +63,9 @@ pub enum LogicalPlan {
     Repartition(Repartition),
     /// Union multiple inputs
     Union(Union),
+    /// Evaluates a recursive common table expression, repeatedly applying
+    /// its recursive term to the rows produced by the previous iteration
+    RecursiveQuery(RecursiveQuery),
     /// Produces rows from a table provider by reference or from the context
     TableScan(TableScan),
     /// Produces no rows: An empty relation with an empty schema
@@ -75,6 +78,9 @@ pub enum LogicalPlan {
     Limit(Limit),
     /// Adjusts the starting point at which the rest of the expressions begin to effect
     Offset(Offset),
+    /// Produces a row for each element of a list column, repeating the
+    /// values of the other columns.
+    Unnest(Unnest),
     /// Creates an external table.
     CreateExternalTable(CreateExternalTable),
     /// Creates an in memory table.
@@ -120,6 +126,7 @@ impl LogicalPlan {
             LogicalPlan::Repartition(Repartition { input, .. }) => input.schema(),
             LogicalPlan::Limit(Limit { input, .. }) => input.schema(),
             LogicalPlan::Offset(Offset { input, .. }) => input.schema(),
+            LogicalPlan::Unnest(Unnest { schema, .. }) => schema,
             LogicalPlan::Subquery(Subquery { subquery, .. }) => subquery.schema(),
             LogicalPlan::SubqueryAlias(SubqueryAlias { schema, .. }) => schema,
             LogicalPlan::CreateExternalTable(CreateExternalTable { schema, .. }) => {
@@ -129,6 +136,7 @@ impl LogicalPlan {
             LogicalPlan::Analyze(analyze) => &analyze.schema,
             LogicalPlan::Extension(extension) => extension.node.schema(),
             LogicalPlan::Union(Union { schema, .. }) => schema,
+            LogicalPlan::RecursiveQuery(RecursiveQuery { schema, .. }) => schema,
             LogicalPlan::CreateMemoryTable(CreateMemoryTable { input, .. })
             | LogicalPlan::CreateView(CreateView { input, .. }) => input.schema(),
             LogicalPlan::CreateCatalogSchema(CreateCatalogSchema { schema, .. }) => {
@@ -148,6 +156,7 @@ impl LogicalPlan {
             LogicalPlan::Values(Values { schema, .. }) => vec![schema],
             LogicalPlan::Window(Window { input, schema, .. })
             | LogicalPlan::Projection(Projection { input, schema, .. })
+            | LogicalPlan::Unnest(Unnest { input, schema, .. })
             | LogicalPlan::Aggregate(Aggregate { input, schema, .. }) => {
                 let mut schemas = input.all_schemas();
                 schemas.insert(0, schema);
@@ -176,6 +185,17 @@ impl LogicalPlan {
             LogicalPlan::Union(Union { schema, .. }) => {
                 vec![schema]
             }
+            LogicalPlan::RecursiveQuery(RecursiveQuery {
+                static_term,
+                recursive_term,
+                schema,
+                ..
+            }) => {
+                let mut schemas = static_term.all_schemas();
+                schemas.extend(recursive_term.all_schemas());
+                schemas.insert(0, schema);
+                schemas
+            }
             LogicalPlan::Extension(extension) => vec![extension.node.schema()],
             LogicalPlan::Explain(Explain { schema, .. })
             | LogicalPlan::Analyze(Analyze { schema, .. })
@@ -227,11 +247,15 @@ impl LogicalPlan {
                 aggr_expr,
                 ..
             }) => group_expr.iter().chain(aggr_expr.iter()).cloned().collect(),
-            LogicalPlan::Join(Join { on, .. }) => on
+            LogicalPlan::Join(Join { on, filter, .. }) => on
                 .iter()
                 .flat_map(|(l, r)| vec![Expr::Column(l.clone()), Expr::Column(r.clone())])
+                .chain(filter.clone())
                 .collect(),
             LogicalPlan::Sort(Sort { expr, .. }) => expr.clone(),
+            LogicalPlan::Unnest(Unnest { column, .. }) => {
+                vec![Expr::Column(column.clone())]
+            }
             LogicalPlan::Extension(extension) => extension.node.expressions(),
             // plans without expressions
             LogicalPlan::TableScan { .. }
@@ -249,7 +273,8 @@ impl LogicalPlan {
             | LogicalPlan::CrossJoin(_)
             | LogicalPlan::Analyze { .. }
             | LogicalPlan::Explain { .. }
-            | LogicalPlan::Union(_) => {
+            | LogicalPlan::Union(_)
+            | LogicalPlan::RecursiveQuery(_) => {
                 vec![]
             }
         }
@@ -269,10 +294,16 @@ impl LogicalPlan {
             LogicalPlan::CrossJoin(CrossJoin { left, right, .. }) => vec![left, right],
             LogicalPlan::Limit(Limit { input, .. }) => vec![input],
             LogicalPlan::Offset(Offset { input, .. }) => vec![input],
+            LogicalPlan::Unnest(Unnest { input, .. }) => vec![input],
             LogicalPlan::Subquery(Subquery { subquery, .. }) => vec![subquery],
             LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => vec![input],
             LogicalPlan::Extension(extension) => extension.node.inputs(),
             LogicalPlan::Union(Union { inputs, .. }) => inputs.iter().collect(),
+            LogicalPlan::RecursiveQuery(RecursiveQuery {
+                static_term,
+                recursive_term,
+                ..
+            }) => vec![static_term, recursive_term],
             LogicalPlan::Explain(explain) => vec![&explain.plan],
             LogicalPlan::Analyze(analyze) => vec![&analyze.input],
             LogicalPlan::CreateMemoryTable(CreateMemoryTable { input, .. })
@@ -408,8 +439,14 @@ impl LogicalPlan {
                 }
                 true
             }
+            LogicalPlan::RecursiveQuery(RecursiveQuery {
+                static_term,
+                recursive_term,
+                ..
+            }) => static_term.accept(visitor)? && recursive_term.accept(visitor)?,
             LogicalPlan::Limit(Limit { input, .. }) => input.accept(visitor)?,
             LogicalPlan::Offset(Offset { input, .. }) => input.accept(visitor)?,
+            LogicalPlan::Unnest(Unnest { input, .. }) => input.accept(visitor)?,
             LogicalPlan::Subquery(Subquery { subquery, .. }) => {
                 subquery.accept(visitor)?
             }
@@ -745,6 +782,8 @@ impl LogicalPlan {
                         on: ref keys,
                         join_constraint,
                         join_type,
+                        filter,
+                        null_aware,
                         ..
                     }) => {
                         let join_expr: Vec<String> =
@@ -761,7 +800,15 @@ impl LogicalPlan {
                                     join_expr.join(", ")
                                 )
                             }
+                        }?;
+                        if let Some(filter) = filter {
+                            let separator = if keys.is_empty() { "" } else { " " };
+                            write!(f, "{}Filter: {:?}", separator, filter)?;
                         }
+                        if *null_aware {
+                            write!(f, ", null_aware=true")?;
+                        }
+                        Ok(())
                     }
                     LogicalPlan::CrossJoin(_) => {
                         write!(f, "CrossJoin:")
@@ -790,6 +837,20 @@ impl LogicalPlan {
                     LogicalPlan::Offset(Offset { ref offset, .. }) => {
                         write!(f, "Offset: {}", offset)
                     }
+                    LogicalPlan::Unnest(Unnest {
+                        ref column,
+                        ref options,
+                        ..
+                    }) => {
+                        write!(f, "Unnest: {:?}", Expr::Column(column.clone()))?;
+                        if options.preserve_nulls {
+                            write!(f, ", preserve_nulls")?;
+                        }
+                        if let Some(ordinality) = &options.ordinality {
+                            write!(f, ", ordinality={}", ordinality)?;
+                        }
+                        Ok(())
+                    }
                     LogicalPlan::Subquery(Subquery { subquery, .. }) => {
                         write!(f, "Subquery: {:?}", subquery)
                     }
@@ -829,6 +890,17 @@ impl LogicalPlan {
                     LogicalPlan::Explain { .. } => write!(f, "Explain"),
                     LogicalPlan::Analyze { .. } => write!(f, "Analyze"),
                     LogicalPlan::Union(_) => write!(f, "Union"),
+                    LogicalPlan::RecursiveQuery(RecursiveQuery {
+                        ref name,
+                        is_distinct,
+                        ..
+                    }) => {
+                        write!(f, "RecursiveQuery: name={}", name)?;
+                        if *is_distinct {
+                            write!(f, ", distinct")?;
+                        }
+                        Ok(())
+                    }
                     LogicalPlan::Extension(e) => e.node.fmt_for_explain(f),
                 }
             }
@@ -864,6 +936,9 @@ pub enum JoinType {
     Semi,
     /// Anti Join
     Anti,
+    /// ASOF Join: pairs each left row with the nearest right row, by the match
+    /// condition, among those with equal join keys, or with nulls if there is none
+    AsOf,
 }
 
 impl Display for JoinType {
@@ -875,6 +950,7 @@ impl Display for JoinType {
             JoinType::Full => "Full",
             JoinType::Semi => "Semi",
             JoinType::Anti => "Anti",
+            JoinType::AsOf => "AsOf",
         };
         write!(f, "{}", join_type)
     }
@@ -1042,6 +1118,26 @@ pub struct Union {
     pub alias: Option<String>,
 }
 
+/// A recursive common table expression, `WITH RECURSIVE name AS
+/// (static_term UNION [ALL] recursive_term)`. The static term is evaluated
+/// once, then the recursive term is evaluated repeatedly against a work
+/// table, scanned as `name`, holding the rows produced by the previous
+/// iteration, until an iteration produces no rows.
+#[derive(Clone)]
+pub struct RecursiveQuery {
+    /// The name of the query, under which the recursive term scans the work table
+    pub name: String,
+    /// The term evaluated once, producing the initial rows
+    pub static_term: Arc<LogicalPlan>,
+    /// The term evaluated against the rows of the previous iteration
+    pub recursive_term: Arc<LogicalPlan>,
+    /// Whether duplicate rows are removed (`UNION`), including rows already
+    /// produced by previous iterations, rather than kept (`UNION ALL`)
+    pub is_distinct: bool,
+    /// The output schema, that of the static term with all fields nullable
+    pub schema: DFSchemaRef,
+}
+
 /// Creates an in memory table.
 #[derive(Clone)]
 pub struct CreateMemoryTable {
@@ -1075,6 +1171,8 @@ pub enum FileType {
     CSV,
     /// Avro binary records
     Avro,
+    /// Apache Arrow IPC file
+    Arrow,
 }
 
 /// Creates an external table.
@@ -1094,6 +1192,10 @@ pub struct CreateExternalTable {
     pub delimiter: char,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// Declared sort order of the rows of each file, as sort expressions
+    pub order_exprs: Vec<Expr>,
+    /// Bucket columns and number of buckets of the files
+    pub bucketing: Option<(Vec<String>, usize)>,
     /// Option to not error if table already exists
     pub if_not_exists: bool,
 }
@@ -1149,6 +1251,46 @@ pub struct Offset {
     pub input: Arc<LogicalPlan>,
 }
 
+/// Produces a row for each element of the list `column` of its input,
+/// in which the list is replaced by the element and the values of the
+/// other columns are repeated. Empty lists produce no rows.
+#[derive(Clone)]
+pub struct Unnest {
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// The list column to unnest
+    pub column: Column,
+    /// Options of the unnest
+    pub options: UnnestOptions,
+    /// The output schema, in which the list column is replaced by its elements
+    pub schema: DFSchemaRef,
+}
+
+/// Options of an [`Unnest`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct UnnestOptions {
+    /// Whether a null list produces a row with a null element, rather than
+    /// no rows
+    pub preserve_nulls: bool,
+    /// The name of a column, appended to the output, with the 1-based
+    /// position of each element within its list
+    pub ordinality: Option<String>,
+}
+
+impl UnnestOptions {
+    /// Returns these options with `preserve_nulls` set
+    pub fn with_preserve_nulls(mut self, preserve_nulls: bool) -> Self {
+        self.preserve_nulls = preserve_nulls;
+        self
+    }
+
+    /// Returns these options with an ordinality column named `name`
+    pub fn with_ordinality(mut self, name: impl Into<String>) -> Self {
+        self.ordinality = Some(name.into());
+        self
+    }
+}
+
 /// Aggregates its input based on a set of grouping and aggregate
 /// expressions (e.g. SUM).
 #[derive(Clone)]
@@ -1181,6 +1323,9 @@ pub struct Join {
     pub right: Arc<LogicalPlan>,
     /// Equijoin clause expressed as pairs of (left, right) join columns
     pub on: Vec<(Column, Column)>,
+    /// Condition the joined rows must also satisfy, beyond the equality of
+    /// the join columns
+    pub filter: Option<Expr>,
     /// Join type
     pub join_type: JoinType,
     /// Join constraint
@@ -1189,6 +1334,10 @@ pub struct Join {
     pub schema: DFSchemaRef,
     /// If null_equals_null is true, null == null else null != null
     pub null_equals_null: bool,
+    /// If true, an anti join has the semantics of `NOT IN`: no row is
+    /// produced if the right input has a null key, nor for rows with a
+    /// null key, unless the right input is empty
+    pub null_aware: bool,
 }
 
 /// Subquery
@@ -0,0 +1,82 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Partition evaluator module contains the trait definition for the evaluators of
+//! user-defined window functions.
+
+use arrow::array::ArrayRef;
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use std::fmt::Debug;
+use std::ops::Range;
+
+/// A partition evaluator computes the values of a user-defined window function over
+/// one partition of its input, which is sorted by the window's `ORDER BY` expressions.
+/// A new evaluator is created for every partition.
+///
+/// Depending on the flags it returns, the evaluator is either evaluated:
+/// * once per row, over the row's window frame, via `evaluate`, when `uses_window_frame`
+/// * for the whole partition, given the ranges of its peer rows, via
+///   `evaluate_all_with_rank`, when `include_rank`
+/// * for the whole partition otherwise, via `evaluate_all`
+pub trait PartitionEvaluator: Send + Debug {
+    /// Whether the evaluator computes the value of each row from its window frame
+    fn uses_window_frame(&self) -> bool {
+        false
+    }
+
+    /// Whether the evaluator needs the ranges of the peer rows of the partition, i.e. the
+    /// rows that are equal with respect to the window's `ORDER BY` expressions
+    fn include_rank(&self) -> bool {
+        false
+    }
+
+    /// Evaluates the values of the `num_rows` rows of the partition, from the values of
+    /// the function's arguments
+    fn evaluate_all(
+        &mut self,
+        _values: &[ArrayRef],
+        _num_rows: usize,
+    ) -> Result<ArrayRef> {
+        Err(DataFusionError::NotImplemented(
+            "evaluate_all is not implemented by default".into(),
+        ))
+    }
+
+    /// Evaluates the values of the `num_rows` rows of the partition, given the ranges of
+    /// its peer rows
+    fn evaluate_all_with_rank(
+        &mut self,
+        _num_rows: usize,
+        _ranks_in_partition: &[Range<usize>],
+    ) -> Result<ArrayRef> {
+        Err(DataFusionError::NotImplemented(
+            "evaluate_all_with_rank is not implemented by default".into(),
+        ))
+    }
+
+    /// Evaluates the value of a row whose window frame is the `range` of the values of
+    /// the function's arguments, which are those of the whole partition
+    fn evaluate(
+        &mut self,
+        _values: &[ArrayRef],
+        _range: &Range<usize>,
+    ) -> Result<ScalarValue> {
+        Err(DataFusionError::NotImplemented(
+            "evaluate is not implemented by default".into(),
+        ))
+    }
+}
@@ -124,7 +124,7 @@ fn maybe_data_types(
     for (i, valid_type) in valid_types.iter().enumerate() {
         let current_type = &current_types[i];
 
-        if current_type == valid_type {
+        if current_type == valid_type || is_time_zone_of(current_type, valid_type) {
             new_type.push(current_type.clone())
         } else {
             // attempt to coerce
@@ -139,6 +139,17 @@ fn maybe_data_types(
     Some(new_type)
 }
 
+/// Returns whether `current_type` is a timestamp with a time zone, that functions
+/// accepting timestamps without time zone of the same unit, such as `date_trunc`,
+/// take as is, evaluating them in their local time
+fn is_time_zone_of(current_type: &DataType, valid_type: &DataType) -> bool {
+    matches!(
+        (current_type, valid_type),
+        (DataType::Timestamp(unit, Some(_)), DataType::Timestamp(valid_unit, None))
+            if unit == valid_unit
+    )
+}
+
 /// Return true if a value of type `type_from` can be coerced
 /// (losslessly converted) into a value of `type_to`
 ///
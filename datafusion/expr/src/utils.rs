@@ -18,11 +18,13 @@
 //! Expression utilities
 
 use crate::expr_visitor::{ExprVisitable, ExpressionVisitor, Recursion};
-use crate::logical_plan::builder::build_join_schema;
+use crate::logical_plan::builder::{
+    build_join_schema, recursive_query, unnest_with_options,
+};
 use crate::logical_plan::{
     Aggregate, Analyze, CreateMemoryTable, CreateView, Extension, Filter, Join, Limit,
-    Offset, Partitioning, Projection, Repartition, Sort, Subquery, SubqueryAlias, Union,
-    Values, Window,
+    Offset, Partitioning, Projection, RecursiveQuery, Repartition, Sort, Subquery,
+    SubqueryAlias, Union, Unnest, Values, Window,
 };
 use crate::{Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder};
 use datafusion_common::{
@@ -379,6 +381,8 @@ pub fn from_plan(
             join_constraint,
             on,
             null_equals_null,
+            filter,
+            null_aware,
             ..
         }) => {
             let schema =
@@ -389,8 +393,11 @@ pub fn from_plan(
                 join_type: *join_type,
                 join_constraint: *join_constraint,
                 on: on.clone(),
+                // the filter follows the join columns in the expressions
+                filter: filter.as_ref().map(|_| expr[on.len() * 2].clone()),
                 schema: DFSchemaRef::new(schema),
                 null_equals_null: *null_equals_null,
+                null_aware: *null_aware,
             }))
         }
         LogicalPlan::CrossJoin(_) => {
@@ -422,6 +429,24 @@ pub fn from_plan(
             offset: *offset,
             input: Arc::new(inputs[0].clone()),
         })),
+        LogicalPlan::RecursiveQuery(RecursiveQuery {
+            name, is_distinct, ..
+        }) => recursive_query(
+            name.clone(),
+            inputs[0].clone(),
+            inputs[1].clone(),
+            *is_distinct,
+        ),
+        LogicalPlan::Unnest(Unnest { options, .. }) => match &expr[0] {
+            // the schema is recomputed as the input may have lost columns
+            Expr::Column(column) => {
+                unnest_with_options(inputs[0].clone(), column.clone(), options.clone())
+            }
+            other => Err(DataFusionError::Plan(format!(
+                "Unnest expects a column, got {:?}",
+                other
+            ))),
+        },
         LogicalPlan::CreateMemoryTable(CreateMemoryTable {
             name,
             if_not_exists,
@@ -23,7 +23,7 @@
 //! - An ending frame boundary,
 //! - An EXCLUDE clause.
 
-use datafusion_common::{DataFusionError, Result};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
 use sqlparser::ast;
 use std::cmp::Ordering;
 use std::convert::{From, TryFrom};
@@ -35,7 +35,7 @@ use std::hash::{Hash, Hasher};
 /// The ending frame boundary can be omitted (if the BETWEEN and AND keywords that surround the
 /// starting frame boundary are also omitted), in which case the ending frame boundary defaults to
 /// CURRENT ROW.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub struct WindowFrame {
     /// A frame type - either ROWS, RANGE or GROUPS
     pub units: WindowFrameUnits,
@@ -60,12 +60,29 @@ impl TryFrom<ast::WindowFrame> for WindowFrame {
     type Error = DataFusionError;
 
     fn try_from(value: ast::WindowFrame) -> Result<Self> {
-        let start_bound = value.start_bound.into();
+        let start_bound = value.start_bound.try_into()?;
         let end_bound = value
             .end_bound
-            .map(WindowFrameBound::from)
+            .map(WindowFrameBound::try_from)
+            .transpose()?
             .unwrap_or(WindowFrameBound::CurrentRow);
+        Self::try_new(value.units.into(), start_bound, end_bound)
+    }
+}
 
+impl WindowFrame {
+    /// Creates a window frame, checking its bounds.
+    ///
+    /// The offsets of ROWS and GROUPS frames must be non-negative integers, which are
+    /// converted to `UInt64`, while those of RANGE frames must be non-negative numbers
+    /// or intervals, as they are added to the values of the ORDER BY column.
+    pub fn try_new(
+        units: WindowFrameUnits,
+        start_bound: WindowFrameBound,
+        end_bound: WindowFrameBound,
+    ) -> Result<Self> {
+        let start_bound = start_bound.check_offset(units)?;
+        let end_bound = end_bound.check_offset(units)?;
         if let WindowFrameBound::Following(None) = start_bound {
             Err(DataFusionError::Execution(
                 "Invalid window frame: start bound cannot be unbounded following"
@@ -82,23 +99,6 @@ impl TryFrom<ast::WindowFrame> for WindowFrame {
         start_bound, end_bound
       )))
         } else {
-            let units = value.units.into();
-            if units == WindowFrameUnits::Range {
-                for bound in &[start_bound, end_bound] {
-                    match bound {
-                        WindowFrameBound::Preceding(Some(v))
-                        | WindowFrameBound::Following(Some(v))
-                            if *v > 0 =>
-                        {
-                            Err(DataFusionError::NotImplemented(format!(
-                                "With WindowFrameUnits={}, the bound cannot be {} PRECEDING or FOLLOWING at the moment",
-                                units, v
-                            )))
-                        }
-                        _ => Ok(()),
-                    }?;
-                }
-            }
             Ok(Self {
                 units,
                 start_bound,
@@ -126,8 +126,10 @@ impl Default for WindowFrame {
 /// 4. <expr> FOLLOWING
 /// 5. UNBOUNDED FOLLOWING
 ///
-/// in this implementation we'll only allow <expr> to be u64 (i.e. no dynamic boundary)
-#[derive(Debug, Clone, Copy, Eq)]
+/// in this implementation we'll only allow <expr> to be a constant (i.e. no dynamic boundary):
+/// a `UInt64` number of rows or groups for ROWS and GROUPS frames, and a number or an
+/// interval for RANGE frames
+#[derive(Debug, Clone, Eq)]
 pub enum WindowFrameBound {
     /// 1. UNBOUNDED PRECEDING
     /// The frame boundary is the first row in the partition.
@@ -135,7 +137,7 @@ pub enum WindowFrameBound {
     /// 2. <expr> PRECEDING
     /// <expr> must be a non-negative constant numeric expression. The boundary is a row that
     /// is <expr> "units" prior to the current row.
-    Preceding(Option<u64>),
+    Preceding(Option<ScalarValue>),
     /// 3. The current row.
     ///
     /// For RANGE and GROUPS frame types, peers of the current row are also
@@ -148,16 +150,40 @@ pub enum WindowFrameBound {
     ///
     /// 5. UNBOUNDED FOLLOWING
     /// The frame boundary is the last row in the partition.
-    Following(Option<u64>),
+    Following(Option<ScalarValue>),
 }
 
-impl From<ast::WindowFrameBound> for WindowFrameBound {
-    fn from(value: ast::WindowFrameBound) -> Self {
-        match value {
-            ast::WindowFrameBound::Preceding(v) => Self::Preceding(v),
-            ast::WindowFrameBound::Following(v) => Self::Following(v),
+impl TryFrom<ast::WindowFrameBound> for WindowFrameBound {
+    type Error = DataFusionError;
+
+    fn try_from(value: ast::WindowFrameBound) -> Result<Self> {
+        Ok(match value {
+            ast::WindowFrameBound::Preceding(v) => {
+                Self::Preceding(v.map(|v| bound_offset(*v)).transpose()?)
+            }
+            ast::WindowFrameBound::Following(v) => {
+                Self::Following(v.map(|v| bound_offset(*v)).transpose()?)
+            }
             ast::WindowFrameBound::CurrentRow => Self::CurrentRow,
-        }
+        })
+    }
+}
+
+/// Converts the number `<expr>` of `<expr> PRECEDING` / `<expr> FOLLOWING` into an offset,
+/// the SQL planner also accepting other constants such as intervals
+fn bound_offset(expr: ast::Expr) -> Result<ScalarValue> {
+    match expr {
+        ast::Expr::Value(ast::Value::Number(n, false)) => n
+            .parse()
+            .map(|n| ScalarValue::Int64(Some(n)))
+            .or_else(|_| n.parse().map(|n| ScalarValue::Float64(Some(n))))
+            .map_err(|_| {
+                DataFusionError::Plan(format!("Invalid window frame offset: {}", n))
+            }),
+        other => Err(DataFusionError::NotImplemented(format!(
+            "Window frame offset must be a non-negative number literal, got {}",
+            other
+        ))),
     }
 }
 
@@ -175,44 +201,152 @@ impl fmt::Display for WindowFrameBound {
 
 impl PartialEq for WindowFrameBound {
     fn eq(&self, other: &Self) -> bool {
-        self.cmp(other) == Ordering::Equal
+        self.partial_cmp(other) == Some(Ordering::Equal)
     }
 }
 
+/// Bounds are ordered by their position relative to the current row, offsets of different
+/// types not being comparable
 impl PartialOrd for WindowFrameBound {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for WindowFrameBound {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.get_rank().cmp(&other.get_rank())
+        match self.get_rank().cmp(&other.get_rank()) {
+            Ordering::Equal => match (self, other) {
+                // ! the larger the offset the smaller the bound
+                (
+                    WindowFrameBound::Preceding(Some(v1)),
+                    WindowFrameBound::Preceding(Some(v2)),
+                ) => v2.partial_cmp(v1),
+                (
+                    WindowFrameBound::Following(Some(v1)),
+                    WindowFrameBound::Following(Some(v2)),
+                ) => v1.partial_cmp(v2),
+                _ => Some(Ordering::Equal),
+            },
+            ordering => Some(ordering),
+        }
     }
 }
 
 impl Hash for WindowFrameBound {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.get_rank().hash(state)
+        self.get_rank().hash(state);
+        match self {
+            WindowFrameBound::Preceding(Some(v))
+            | WindowFrameBound::Following(Some(v))
+                if !is_zero(v) =>
+            {
+                v.hash(state)
+            }
+            _ => {}
+        }
     }
 }
 
 impl WindowFrameBound {
-    /// get the rank of this window frame bound.
+    /// get the rank of the kind of this window frame bound, the bounds of the same rank being
+    /// ordered by their offsets.
     ///
-    /// the rank is a tuple of (u8, u64) because we'll firstly compare the kind and then the value
-    /// which requires special handling e.g. with preceding the larger the value the smaller the
-    /// rank and also for 0 preceding / following it is the same as current row
-    fn get_rank(&self) -> (u8, u64) {
+    /// 0 preceding / following has the same rank as current row
+    fn get_rank(&self) -> u8 {
         match self {
-            WindowFrameBound::Preceding(None) => (0, 0),
-            WindowFrameBound::Following(None) => (4, 0),
-            WindowFrameBound::Preceding(Some(0))
-            | WindowFrameBound::CurrentRow
-            | WindowFrameBound::Following(Some(0)) => (2, 0),
-            WindowFrameBound::Preceding(Some(v)) => (1, u64::MAX - *v),
-            WindowFrameBound::Following(Some(v)) => (3, *v),
+            WindowFrameBound::Preceding(None) => 0,
+            WindowFrameBound::Following(None) => 4,
+            WindowFrameBound::CurrentRow => 2,
+            WindowFrameBound::Preceding(Some(v))
+            | WindowFrameBound::Following(Some(v))
+                if is_zero(v) =>
+            {
+                2
+            }
+            WindowFrameBound::Preceding(Some(_)) => 1,
+            WindowFrameBound::Following(Some(_)) => 3,
+        }
+    }
+
+    /// whether this bound is the current row or the peers of the current row, that is it
+    /// has no offset or a zero one
+    pub fn is_current_row(&self) -> bool {
+        self.get_rank() == 2
+    }
+
+    /// checks that the offset of this bound is valid for `units`, converting the offsets
+    /// of ROWS and GROUPS frames to `UInt64`
+    fn check_offset(self, units: WindowFrameUnits) -> Result<Self> {
+        let check = |offset: ScalarValue| -> Result<ScalarValue> {
+            let invalid = || {
+                DataFusionError::Plan(format!(
+                    "Invalid window frame offset {} for {} frame: it must be a non-negative {}",
+                    offset,
+                    units,
+                    match units {
+                        WindowFrameUnits::Range => "number or interval",
+                        _ => "integer",
+                    }
+                ))
+            };
+            match units {
+                WindowFrameUnits::Rows | WindowFrameUnits::Groups => {
+                    offset_to_u64(&offset).map(|n| ScalarValue::UInt64(Some(n)))
+                }
+                WindowFrameUnits::Range if is_non_negative(&offset) => {
+                    Some(offset.clone())
+                }
+                WindowFrameUnits::Range => None,
+            }
+            .ok_or_else(invalid)
+        };
+        Ok(match self {
+            WindowFrameBound::Preceding(Some(offset)) => {
+                WindowFrameBound::Preceding(Some(check(offset)?))
+            }
+            WindowFrameBound::Following(Some(offset)) => {
+                WindowFrameBound::Following(Some(check(offset)?))
+            }
+            bound => bound,
+        })
+    }
+}
+
+/// the non-negative integer `offset` as a `u64`
+fn offset_to_u64(offset: &ScalarValue) -> Option<u64> {
+    match offset {
+        ScalarValue::UInt8(Some(v)) => Some(*v as u64),
+        ScalarValue::UInt16(Some(v)) => Some(*v as u64),
+        ScalarValue::UInt32(Some(v)) => Some(*v as u64),
+        ScalarValue::UInt64(Some(v)) => Some(*v),
+        ScalarValue::Int8(Some(v)) => u64::try_from(*v).ok(),
+        ScalarValue::Int16(Some(v)) => u64::try_from(*v).ok(),
+        ScalarValue::Int32(Some(v)) => u64::try_from(*v).ok(),
+        ScalarValue::Int64(Some(v)) => u64::try_from(*v).ok(),
+        _ => None,
+    }
+}
+
+/// whether `offset` is a non-negative number or an interval without negative fields
+fn is_non_negative(offset: &ScalarValue) -> bool {
+    match offset {
+        ScalarValue::Float32(Some(v)) => *v >= 0.0,
+        ScalarValue::Float64(Some(v)) => *v >= 0.0,
+        ScalarValue::Decimal128(Some(v), _, _) => *v >= 0,
+        ScalarValue::IntervalYearMonth(Some(v)) => *v >= 0,
+        ScalarValue::IntervalDayTime(Some(v)) => (*v >> 32) as i32 >= 0 && *v as i32 >= 0,
+        ScalarValue::IntervalMonthDayNano(Some(v)) => {
+            (*v >> 96) as i32 >= 0 && (*v >> 64) as i32 >= 0 && *v as i64 >= 0
         }
+        other => offset_to_u64(other).is_some(),
+    }
+}
+
+/// whether `offset` is zero, making the bound the current row
+fn is_zero(offset: &ScalarValue) -> bool {
+    match offset {
+        ScalarValue::Float32(Some(v)) => *v == 0.0,
+        ScalarValue::Float64(Some(v)) => *v == 0.0,
+        ScalarValue::Decimal128(Some(v), _, _) => *v == 0,
+        ScalarValue::IntervalYearMonth(Some(v)) => *v == 0,
+        ScalarValue::IntervalDayTime(Some(v)) => *v == 0,
+        ScalarValue::IntervalMonthDayNano(Some(v)) => *v == 0,
+        other => offset_to_u64(other) == Some(0),
     }
 }
 
@@ -259,6 +393,14 @@ impl From<ast::WindowFrameUnits> for WindowFrameUnits {
 mod tests {
     use super::*;
 
+    fn number(n: &str) -> ast::Expr {
+        ast::Expr::Value(ast::Value::Number(n.to_owned(), false))
+    }
+
+    fn offset(n: u64) -> Option<ScalarValue> {
+        Some(ScalarValue::UInt64(Some(n)))
+    }
+
     #[test]
     fn test_window_frame_creation() -> Result<()> {
         let window_frame = ast::WindowFrame {
@@ -287,8 +429,10 @@ mod tests {
 
         let window_frame = ast::WindowFrame {
             units: ast::WindowFrameUnits::Range,
-            start_bound: ast::WindowFrameBound::Preceding(Some(1)),
-            end_bound: Some(ast::WindowFrameBound::Preceding(Some(2))),
+            start_bound: ast::WindowFrameBound::Preceding(Some(Box::new(number("1")))),
+            end_bound: Some(ast::WindowFrameBound::Preceding(Some(Box::new(number(
+                "2",
+            ))))),
         };
         let result = WindowFrame::try_from(window_frame);
         assert_eq!(
@@ -298,19 +442,34 @@ mod tests {
 
         let window_frame = ast::WindowFrame {
             units: ast::WindowFrameUnits::Range,
-            start_bound: ast::WindowFrameBound::Preceding(Some(2)),
-            end_bound: Some(ast::WindowFrameBound::Preceding(Some(1))),
+            start_bound: ast::WindowFrameBound::Preceding(Some(Box::new(number("2")))),
+            end_bound: Some(ast::WindowFrameBound::Preceding(Some(Box::new(number(
+                "1",
+            ))))),
+        };
+        let result = WindowFrame::try_from(window_frame)?;
+        assert_eq!(
+            result.start_bound,
+            WindowFrameBound::Preceding(Some(ScalarValue::Int64(Some(2))))
+        );
+
+        let window_frame = ast::WindowFrame {
+            units: ast::WindowFrameUnits::Rows,
+            start_bound: ast::WindowFrameBound::Preceding(Some(Box::new(number("1.5")))),
+            end_bound: None,
         };
         let result = WindowFrame::try_from(window_frame);
         assert_eq!(
             result.err().unwrap().to_string(),
-            "This feature is not implemented: With WindowFrameUnits=RANGE, the bound cannot be 2 PRECEDING or FOLLOWING at the moment".to_owned()
+            "Error during planning: Invalid window frame offset 1.5 for ROWS frame: it must be a non-negative integer".to_owned()
         );
 
         let window_frame = ast::WindowFrame {
             units: ast::WindowFrameUnits::Rows,
-            start_bound: ast::WindowFrameBound::Preceding(Some(2)),
-            end_bound: Some(ast::WindowFrameBound::Preceding(Some(1))),
+            start_bound: ast::WindowFrameBound::Preceding(Some(Box::new(number("2")))),
+            end_bound: Some(ast::WindowFrameBound::Preceding(Some(Box::new(number(
+                "1",
+            ))))),
         };
         let result = WindowFrame::try_from(window_frame);
         assert!(result.is_ok());
@@ -320,24 +479,24 @@ mod tests {
     #[test]
     fn test_eq() {
         assert_eq!(
-            WindowFrameBound::Preceding(Some(0)),
+            WindowFrameBound::Preceding(offset(0)),
             WindowFrameBound::CurrentRow
         );
         assert_eq!(
             WindowFrameBound::CurrentRow,
-            WindowFrameBound::Following(Some(0))
+            WindowFrameBound::Following(offset(0))
         );
         assert_eq!(
-            WindowFrameBound::Following(Some(2)),
-            WindowFrameBound::Following(Some(2))
+            WindowFrameBound::Following(offset(2)),
+            WindowFrameBound::Following(offset(2))
         );
         assert_eq!(
             WindowFrameBound::Following(None),
             WindowFrameBound::Following(None)
         );
         assert_eq!(
-            WindowFrameBound::Preceding(Some(2)),
-            WindowFrameBound::Preceding(Some(2))
+            WindowFrameBound::Preceding(offset(2)),
+            WindowFrameBound::Preceding(offset(2))
         );
         assert_eq!(
             WindowFrameBound::Preceding(None),
@@ -347,34 +506,41 @@ mod tests {
 
     #[test]
     fn test_ord() {
-        assert!(WindowFrameBound::Preceding(Some(1)) < WindowFrameBound::CurrentRow);
+        assert!(WindowFrameBound::Preceding(offset(1)) < WindowFrameBound::CurrentRow);
         // ! yes this is correct!
         assert!(
-            WindowFrameBound::Preceding(Some(2)) < WindowFrameBound::Preceding(Some(1))
+            WindowFrameBound::Preceding(offset(2))
+                < WindowFrameBound::Preceding(offset(1))
         );
         assert!(
-            WindowFrameBound::Preceding(Some(u64::MAX))
-                < WindowFrameBound::Preceding(Some(u64::MAX - 1))
+            WindowFrameBound::Preceding(offset(u64::MAX))
+                < WindowFrameBound::Preceding(offset(u64::MAX - 1))
         );
         assert!(
             WindowFrameBound::Preceding(None)
-                < WindowFrameBound::Preceding(Some(1000000))
+                < WindowFrameBound::Preceding(offset(1000000))
         );
         assert!(
             WindowFrameBound::Preceding(None)
-                < WindowFrameBound::Preceding(Some(u64::MAX))
+                < WindowFrameBound::Preceding(offset(u64::MAX))
+        );
+        assert!(
+            WindowFrameBound::Preceding(None) < WindowFrameBound::Following(offset(0))
+        );
+        assert!(
+            WindowFrameBound::Preceding(offset(1))
+                < WindowFrameBound::Following(offset(1))
         );
-        assert!(WindowFrameBound::Preceding(None) < WindowFrameBound::Following(Some(0)));
+        assert!(WindowFrameBound::CurrentRow < WindowFrameBound::Following(offset(1)));
         assert!(
-            WindowFrameBound::Preceding(Some(1)) < WindowFrameBound::Following(Some(1))
+            WindowFrameBound::Following(offset(1))
+                < WindowFrameBound::Following(offset(2))
         );
-        assert!(WindowFrameBound::CurrentRow < WindowFrameBound::Following(Some(1)));
         assert!(
-            WindowFrameBound::Following(Some(1)) < WindowFrameBound::Following(Some(2))
+            WindowFrameBound::Following(offset(2)) < WindowFrameBound::Following(None)
         );
-        assert!(WindowFrameBound::Following(Some(2)) < WindowFrameBound::Following(None));
         assert!(
-            WindowFrameBound::Following(Some(u64::MAX))
+            WindowFrameBound::Following(offset(u64::MAX))
                 < WindowFrameBound::Following(None)
         );
     }
@@ -23,9 +23,12 @@
 
 use crate::aggregate_function::AggregateFunction;
 use crate::type_coercion::data_types;
-use crate::{aggregate_function, Signature, TypeSignature, Volatility};
+use crate::{
+    aggregate_function, AggregateUDF, Signature, TypeSignature, Volatility, WindowUDF,
+};
 use arrow::datatypes::DataType;
 use datafusion_common::{DataFusionError, Result};
+use std::sync::Arc;
 use std::{fmt, str::FromStr};
 
 /// WindowFunction
@@ -35,18 +38,22 @@ pub enum WindowFunction {
     AggregateFunction(AggregateFunction),
     /// window function that leverages a built-in window function
     BuiltInWindowFunction(BuiltInWindowFunction),
+    /// window function that leverages a user-defined aggregate function
+    AggregateUDF(Arc<AggregateUDF>),
+    /// user-defined window function
+    WindowUDF(Arc<WindowUDF>),
 }
 
 impl FromStr for WindowFunction {
     type Err = DataFusionError;
     fn from_str(name: &str) -> Result<WindowFunction> {
         let name = name.to_lowercase();
-        if let Ok(aggregate) = AggregateFunction::from_str(name.as_str()) {
-            Ok(WindowFunction::AggregateFunction(aggregate))
-        } else if let Ok(built_in_function) =
-            BuiltInWindowFunction::from_str(name.as_str())
-        {
+        // `first_value` and `last_value` are both aggregate and built-in window
+        // functions, the latter of which can be evaluated without an accumulator
+        if let Ok(built_in_function) = BuiltInWindowFunction::from_str(name.as_str()) {
             Ok(WindowFunction::BuiltInWindowFunction(built_in_function))
+        } else if let Ok(aggregate) = AggregateFunction::from_str(name.as_str()) {
+            Ok(WindowFunction::AggregateFunction(aggregate))
         } else {
             Err(DataFusionError::Plan(format!(
                 "There is no window function named {}",
@@ -79,6 +86,8 @@ impl fmt::Display for WindowFunction {
         match self {
             WindowFunction::AggregateFunction(fun) => fun.fmt(f),
             WindowFunction::BuiltInWindowFunction(fun) => fun.fmt(f),
+            WindowFunction::AggregateUDF(fun) => f.write_str(&fun.name),
+            WindowFunction::WindowUDF(fun) => f.write_str(&fun.name),
         }
     }
 }
@@ -153,6 +162,12 @@ pub fn return_type(
         WindowFunction::BuiltInWindowFunction(fun) => {
             return_type_for_built_in(fun, input_expr_types)
         }
+        WindowFunction::AggregateUDF(fun) => {
+            Ok((fun.return_type)(input_expr_types)?.as_ref().clone())
+        }
+        WindowFunction::WindowUDF(fun) => {
+            Ok((fun.return_type)(input_expr_types)?.as_ref().clone())
+        }
     }
 }
 
@@ -188,6 +203,8 @@ pub fn signature(fun: &WindowFunction) -> Signature {
     match fun {
         WindowFunction::AggregateFunction(fun) => aggregate_function::signature(fun),
         WindowFunction::BuiltInWindowFunction(fun) => signature_for_built_in(fun),
+        WindowFunction::AggregateUDF(fun) => fun.signature.clone(),
+        WindowFunction::WindowUDF(fun) => fun.signature.clone(),
     }
 }
 
@@ -137,6 +137,7 @@ pub struct AvgAccumulator {
     // sum is used for null
     sum: ScalarValue,
     count: u64,
+    non_finite: sum::NonFiniteCounts,
 }
 
 impl AvgAccumulator {
@@ -145,41 +146,61 @@ impl AvgAccumulator {
         Ok(Self {
             sum: ScalarValue::try_from(datatype)?,
             count: 0,
+            non_finite: sum::NonFiniteCounts::default(),
         })
     }
 }
 
 impl Accumulator for AvgAccumulator {
     fn state(&self) -> Result<Vec<ScalarValue>> {
-        Ok(vec![ScalarValue::from(self.count), self.sum.clone()])
+        Ok(vec![
+            ScalarValue::from(self.count),
+            self.non_finite.sum(&self.sum),
+        ])
     }
 
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let values = &values[0];
+        let sum_type = self.sum.get_datatype();
 
         self.count += (values.len() - values.data().null_count()) as u64;
-        self.sum = sum::sum(
-            &self.sum,
-            &sum::sum_batch(values, &self.sum.get_datatype())?,
-        )?;
+        let values = self.non_finite.add(values, &sum_type)?;
+        self.sum = sum::sum(&self.sum, &sum::sum_batch(&values, &sum_type)?)?;
         Ok(())
     }
 
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        let sum_type = self.sum.get_datatype();
+
+        self.count -= (values.len() - values.data().null_count()) as u64;
+        let values = self.non_finite.retract(values, &sum_type)?;
+        self.sum = sum::sub(&self.sum, &sum::sum_batch(&values, &sum_type)?)?;
+        Ok(())
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+
     fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
         let counts = states[0].as_any().downcast_ref::<UInt64Array>().unwrap();
         // counts are summed
         self.count += compute::sum(counts).unwrap_or(0);
 
         // sums are summed
-        self.sum = sum::sum(
-            &self.sum,
-            &sum::sum_batch(&states[1], &self.sum.get_datatype())?,
-        )?;
+        let sum_type = self.sum.get_datatype();
+        let sums = self.non_finite.add(&states[1], &sum_type)?;
+        self.sum = sum::sum(&self.sum, &sum::sum_batch(&sums, &sum_type)?)?;
         Ok(())
     }
 
     fn evaluate(&self) -> Result<ScalarValue> {
-        match self.sum {
+        // the sum isn't null once all of its values were retracted
+        if self.count == 0 {
+            return ScalarValue::try_from(&self.sum.get_datatype());
+        }
+        match self.non_finite.sum(&self.sum) {
             ScalarValue::Float64(e) => {
                 Ok(ScalarValue::Float64(e.map(|f| f / self.count as f64)))
             }
@@ -420,4 +441,30 @@ mod tests {
             DataType::Float64
         )
     }
+
+    #[test]
+    fn avg_retract() -> Result<()> {
+        let mut accumulator = AvgAccumulator::try_new(&DataType::Float64)?;
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(2)]));
+        let b: ArrayRef = Arc::new(Int32Array::from(vec![Some(4), Some(6)]));
+        accumulator.update_batch(&[a.clone()])?;
+        accumulator.update_batch(&[b.clone()])?;
+        accumulator.retract_batch(&[a])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(5_f64));
+
+        accumulator.retract_batch(&[b])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::Float64(None));
+
+        // the average is finite again once the NaN leaves the frame
+        let nan: ArrayRef = Arc::new(Float64Array::from(vec![f64::NAN]));
+        let c: ArrayRef = Arc::new(Float64Array::from(vec![3_f64]));
+        accumulator.update_batch(&[nan.clone()])?;
+        accumulator.update_batch(&[c])?;
+        assert!(
+            matches!(accumulator.evaluate()?, ScalarValue::Float64(Some(v)) if v.is_nan())
+        );
+        accumulator.retract_batch(&[nan])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(3_f64));
+        Ok(())
+    }
 }
@@ -0,0 +1,399 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions for BIT_AND, BIT_OR and BIT_XOR that can be evaluated at runtime during query execution
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::aggregate::row_accumulator::RowAccumulator;
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::{
+    ArrayRef, Int16Array, Int32Array, Int64Array, Int8Array, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
+};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+use datafusion_row::accessor::RowAccessor;
+
+/// The bitwise operation of a bit aggregate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BitOp {
+    And,
+    Or,
+    Xor,
+}
+
+// Statically-typed version of the bitwise operation over the non-null values of an array
+macro_rules! typed_bit_batch {
+    ($VALUES:expr, $ARRAYTYPE:ident, $SCALAR:ident, $OP:expr) => {{
+        let array = $VALUES.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
+        let values = array.iter().flatten();
+        ScalarValue::$SCALAR(match $OP {
+            BitOp::And => values.reduce(|lhs, rhs| lhs & rhs),
+            BitOp::Or => values.reduce(|lhs, rhs| lhs | rhs),
+            BitOp::Xor => values.reduce(|lhs, rhs| lhs ^ rhs),
+        })
+    }};
+}
+
+/// The bitwise operation `op` over the non-null values of `values`, or null if there
+/// are none
+fn bit_batch(values: &ArrayRef, op: BitOp) -> Result<ScalarValue> {
+    Ok(match values.data_type() {
+        DataType::Int8 => typed_bit_batch!(values, Int8Array, Int8, op),
+        DataType::Int16 => typed_bit_batch!(values, Int16Array, Int16, op),
+        DataType::Int32 => typed_bit_batch!(values, Int32Array, Int32, op),
+        DataType::Int64 => typed_bit_batch!(values, Int64Array, Int64, op),
+        DataType::UInt8 => typed_bit_batch!(values, UInt8Array, UInt8, op),
+        DataType::UInt16 => typed_bit_batch!(values, UInt16Array, UInt16, op),
+        DataType::UInt32 => typed_bit_batch!(values, UInt32Array, UInt32, op),
+        DataType::UInt64 => typed_bit_batch!(values, UInt64Array, UInt64, op),
+        e => {
+            return Err(DataFusionError::Internal(format!(
+                "Bit aggregate is not expected to receive the type {:?}",
+                e
+            )))
+        }
+    })
+}
+
+// the bitwise operation of two optional values, ignoring nulls
+macro_rules! typed_bit_op {
+    ($LHS:expr, $RHS:expr, $SCALAR:ident, $OP:expr) => {{
+        ScalarValue::$SCALAR(match ($LHS, $RHS) {
+            (Some(lhs), Some(rhs)) => Some(match $OP {
+                BitOp::And => lhs & rhs,
+                BitOp::Or => lhs | rhs,
+                BitOp::Xor => lhs ^ rhs,
+            }),
+            (lhs, None) => *lhs,
+            (None, rhs) => *rhs,
+        })
+    }};
+}
+
+/// The bitwise operation `op` of two scalar values of the same type
+fn bit_op(lhs: &ScalarValue, rhs: &ScalarValue, op: BitOp) -> Result<ScalarValue> {
+    Ok(match (lhs, rhs) {
+        (ScalarValue::Int8(lhs), ScalarValue::Int8(rhs)) => {
+            typed_bit_op!(lhs, rhs, Int8, op)
+        }
+        (ScalarValue::Int16(lhs), ScalarValue::Int16(rhs)) => {
+            typed_bit_op!(lhs, rhs, Int16, op)
+        }
+        (ScalarValue::Int32(lhs), ScalarValue::Int32(rhs)) => {
+            typed_bit_op!(lhs, rhs, Int32, op)
+        }
+        (ScalarValue::Int64(lhs), ScalarValue::Int64(rhs)) => {
+            typed_bit_op!(lhs, rhs, Int64, op)
+        }
+        (ScalarValue::UInt8(lhs), ScalarValue::UInt8(rhs)) => {
+            typed_bit_op!(lhs, rhs, UInt8, op)
+        }
+        (ScalarValue::UInt16(lhs), ScalarValue::UInt16(rhs)) => {
+            typed_bit_op!(lhs, rhs, UInt16, op)
+        }
+        (ScalarValue::UInt32(lhs), ScalarValue::UInt32(rhs)) => {
+            typed_bit_op!(lhs, rhs, UInt32, op)
+        }
+        (ScalarValue::UInt64(lhs), ScalarValue::UInt64(rhs)) => {
+            typed_bit_op!(lhs, rhs, UInt64, op)
+        }
+        e => {
+            return Err(DataFusionError::Internal(format!(
+            "Bit aggregate is not expected to receive scalars of incompatible types {:?}",
+            e
+        )))
+        }
+    })
+}
+
+// update the row state at `$INDEX` with the bitwise operation of a non-null value
+macro_rules! typed_bit_op_row {
+    ($INDEX:expr, $ACC:ident, $VALUE:expr, $NATIVE:ident, $OP:expr) => {{
+        paste::item! {
+            if let Some(value) = $VALUE {
+                match $OP {
+                    BitOp::And => $ACC.[<bitand_ $NATIVE>]($INDEX, *value),
+                    BitOp::Or => $ACC.[<bitor_ $NATIVE>]($INDEX, *value),
+                    BitOp::Xor => $ACC.[<bitxor_ $NATIVE>]($INDEX, *value),
+                }
+            }
+        }
+    }};
+}
+
+/// Update the row state at `index` with the bitwise operation `op` of `s`
+fn bit_op_row(
+    index: usize,
+    accessor: &mut RowAccessor,
+    s: &ScalarValue,
+    op: BitOp,
+) -> Result<()> {
+    match s {
+        ScalarValue::Int8(v) => typed_bit_op_row!(index, accessor, v, i8, op),
+        ScalarValue::Int16(v) => typed_bit_op_row!(index, accessor, v, i16, op),
+        ScalarValue::Int32(v) => typed_bit_op_row!(index, accessor, v, i32, op),
+        ScalarValue::Int64(v) => typed_bit_op_row!(index, accessor, v, i64, op),
+        ScalarValue::UInt8(v) => typed_bit_op_row!(index, accessor, v, u8, op),
+        ScalarValue::UInt16(v) => typed_bit_op_row!(index, accessor, v, u16, op),
+        ScalarValue::UInt32(v) => typed_bit_op_row!(index, accessor, v, u32, op),
+        ScalarValue::UInt64(v) => typed_bit_op_row!(index, accessor, v, u64, op),
+        e => {
+            return Err(DataFusionError::Internal(format!(
+                "Bit aggregate is not expected to receive the scalar {:?}",
+                e
+            )))
+        }
+    }
+    Ok(())
+}
+
+// Defines the aggregate expression of a bitwise operation
+macro_rules! bit_aggregate {
+    ($(#[$DOC:meta])* $NAME:ident, $OP:expr, $STATE_NAME:literal) => {
+        $(#[$DOC])*
+        #[derive(Debug)]
+        pub struct $NAME {
+            name: String,
+            data_type: DataType,
+            expr: Arc<dyn PhysicalExpr>,
+        }
+
+        impl $NAME {
+            /// Create a new bit aggregate function
+            pub fn new(
+                expr: Arc<dyn PhysicalExpr>,
+                name: impl Into<String>,
+                data_type: DataType,
+            ) -> Self {
+                Self {
+                    name: name.into(),
+                    data_type,
+                    expr,
+                }
+            }
+        }
+
+        impl AggregateExpr for $NAME {
+            /// Return a reference to Any that can be used for downcasting
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+
+            fn field(&self) -> Result<Field> {
+                Ok(Field::new(&self.name, self.data_type.clone(), true))
+            }
+
+            fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+                Ok(Box::new(BitAccumulator::try_new(&self.data_type, $OP)?))
+            }
+
+            fn state_fields(&self) -> Result<Vec<Field>> {
+                Ok(vec![Field::new(
+                    &format_state_name(&self.name, $STATE_NAME),
+                    self.data_type.clone(),
+                    true,
+                )])
+            }
+
+            fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+                vec![self.expr.clone()]
+            }
+
+            fn name(&self) -> &str {
+                &self.name
+            }
+
+            fn row_accumulator_supported(&self) -> bool {
+                true
+            }
+
+            fn create_row_accumulator(
+                &self,
+                start_index: usize,
+            ) -> Result<Box<dyn RowAccumulator>> {
+                Ok(Box::new(BitRowAccumulator::new(
+                    start_index,
+                    self.data_type.clone(),
+                    $OP,
+                )))
+            }
+        }
+    };
+}
+
+bit_aggregate!(
+    /// BIT_AND aggregate expression
+    BitAnd,
+    BitOp::And,
+    "bit_and"
+);
+bit_aggregate!(
+    /// BIT_OR aggregate expression
+    BitOr,
+    BitOp::Or,
+    "bit_or"
+);
+bit_aggregate!(
+    /// BIT_XOR aggregate expression
+    BitXor,
+    BitOp::Xor,
+    "bit_xor"
+);
+
+#[derive(Debug)]
+struct BitAccumulator {
+    value: ScalarValue,
+    op: BitOp,
+}
+
+impl BitAccumulator {
+    fn try_new(data_type: &DataType, op: BitOp) -> Result<Self> {
+        Ok(Self {
+            value: ScalarValue::try_from(data_type)?,
+            op,
+        })
+    }
+}
+
+impl Accumulator for BitAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.value.clone()])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let delta = &bit_batch(&values[0], self.op)?;
+        self.value = bit_op(&self.value, delta, self.op)?;
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(self.value.clone())
+    }
+}
+
+#[derive(Debug)]
+struct BitRowAccumulator {
+    index: usize,
+    data_type: DataType,
+    op: BitOp,
+}
+
+impl BitRowAccumulator {
+    fn new(index: usize, data_type: DataType, op: BitOp) -> Self {
+        Self {
+            index,
+            data_type,
+            op,
+        }
+    }
+}
+
+impl RowAccumulator for BitRowAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        accessor: &mut RowAccessor,
+    ) -> Result<()> {
+        let delta = &bit_batch(&values[0], self.op)?;
+        bit_op_row(self.index, accessor, delta, self.op)
+    }
+
+    fn merge_batch(
+        &mut self,
+        states: &[ArrayRef],
+        accessor: &mut RowAccessor,
+    ) -> Result<()> {
+        self.update_batch(states, accessor)
+    }
+
+    fn evaluate(&self, accessor: &RowAccessor) -> Result<ScalarValue> {
+        Ok(accessor.get_as_scalar(&self.data_type, self.index))
+    }
+
+    #[inline(always)]
+    fn state_index(&self) -> usize {
+        self.index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::aggregate;
+    use crate::generic_test_op;
+    use arrow::datatypes::*;
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn bit_and_i32() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(7), None, Some(13)]));
+        generic_test_op!(
+            a,
+            DataType::Int32,
+            BitAnd,
+            ScalarValue::from(5i32),
+            DataType::Int32
+        )
+    }
+
+    #[test]
+    fn bit_or_u8() -> Result<()> {
+        let a: ArrayRef = Arc::new(UInt8Array::from(vec![1u8, 4, 8]));
+        generic_test_op!(
+            a,
+            DataType::UInt8,
+            BitOr,
+            ScalarValue::from(13u8),
+            DataType::UInt8
+        )
+    }
+
+    #[test]
+    fn bit_xor_i64() -> Result<()> {
+        let a: ArrayRef =
+            Arc::new(Int64Array::from(vec![Some(3), Some(5), None, Some(1)]));
+        generic_test_op!(
+            a,
+            DataType::Int64,
+            BitXor,
+            ScalarValue::from(7i64),
+            DataType::Int64
+        )
+    }
+
+    #[test]
+    fn bit_and_all_nulls() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![None, None]));
+        generic_test_op!(
+            a,
+            DataType::Int32,
+            BitAnd,
+            ScalarValue::Int32(None),
+            DataType::Int32
+        )
+    }
+}
@@ -0,0 +1,376 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions for BOOL_AND and BOOL_OR that can be evaluated at runtime during query execution
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::aggregate::row_accumulator::RowAccumulator;
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+use datafusion_row::accessor::RowAccessor;
+
+/// The logical `AND` of the non-null values of `values`, or null if there are none
+fn bool_and_batch(values: &ArrayRef) -> Result<Option<bool>> {
+    let values = downcast_boolean(values)?;
+    if values.null_count() == values.len() {
+        return Ok(None);
+    }
+    Ok(Some(!values.iter().any(|v| v == Some(false))))
+}
+
+/// The logical `OR` of the non-null values of `values`, or null if there are none
+fn bool_or_batch(values: &ArrayRef) -> Result<Option<bool>> {
+    let values = downcast_boolean(values)?;
+    if values.null_count() == values.len() {
+        return Ok(None);
+    }
+    Ok(Some(values.iter().any(|v| v == Some(true))))
+}
+
+fn downcast_boolean(values: &ArrayRef) -> Result<&BooleanArray> {
+    values
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "BOOL_AND/BOOL_OR is not expected to receive the type {:?}",
+                values.data_type()
+            ))
+        })
+}
+
+/// BOOL_AND aggregate expression
+#[derive(Debug)]
+pub struct BoolAnd {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl BoolAnd {
+    /// Create a new BOOL_AND aggregate function
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for BoolAnd {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(BoolAndAccumulator { bool_and: None }))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "bool_and"),
+            DataType::Boolean,
+            true,
+        )])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn row_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_row_accumulator(
+        &self,
+        start_index: usize,
+    ) -> Result<Box<dyn RowAccumulator>> {
+        Ok(Box::new(BoolAndRowAccumulator::new(start_index)))
+    }
+}
+
+#[derive(Debug)]
+struct BoolAndAccumulator {
+    bool_and: Option<bool>,
+}
+
+impl Accumulator for BoolAndAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Boolean(self.bool_and)])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        if let Some(delta) = bool_and_batch(&values[0])? {
+            self.bool_and = Some(self.bool_and.unwrap_or(true) && delta);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Boolean(self.bool_and))
+    }
+}
+
+#[derive(Debug)]
+struct BoolAndRowAccumulator {
+    index: usize,
+}
+
+impl BoolAndRowAccumulator {
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+impl RowAccumulator for BoolAndRowAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        accessor: &mut RowAccessor,
+    ) -> Result<()> {
+        if let Some(delta) = bool_and_batch(&values[0])? {
+            accessor.bitand_bool(self.index, delta);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        states: &[ArrayRef],
+        accessor: &mut RowAccessor,
+    ) -> Result<()> {
+        self.update_batch(states, accessor)
+    }
+
+    fn evaluate(&self, accessor: &RowAccessor) -> Result<ScalarValue> {
+        Ok(accessor.get_as_scalar(&DataType::Boolean, self.index))
+    }
+
+    #[inline(always)]
+    fn state_index(&self) -> usize {
+        self.index
+    }
+}
+
+/// BOOL_OR aggregate expression
+#[derive(Debug)]
+pub struct BoolOr {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl BoolOr {
+    /// Create a new BOOL_OR aggregate function
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for BoolOr {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(BoolOrAccumulator { bool_or: None }))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "bool_or"),
+            DataType::Boolean,
+            true,
+        )])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn row_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_row_accumulator(
+        &self,
+        start_index: usize,
+    ) -> Result<Box<dyn RowAccumulator>> {
+        Ok(Box::new(BoolOrRowAccumulator::new(start_index)))
+    }
+}
+
+#[derive(Debug)]
+struct BoolOrAccumulator {
+    bool_or: Option<bool>,
+}
+
+impl Accumulator for BoolOrAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Boolean(self.bool_or)])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        if let Some(delta) = bool_or_batch(&values[0])? {
+            self.bool_or = Some(self.bool_or.unwrap_or(false) || delta);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Boolean(self.bool_or))
+    }
+}
+
+#[derive(Debug)]
+struct BoolOrRowAccumulator {
+    index: usize,
+}
+
+impl BoolOrRowAccumulator {
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+impl RowAccumulator for BoolOrRowAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        accessor: &mut RowAccessor,
+    ) -> Result<()> {
+        if let Some(delta) = bool_or_batch(&values[0])? {
+            accessor.bitor_bool(self.index, delta);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        states: &[ArrayRef],
+        accessor: &mut RowAccessor,
+    ) -> Result<()> {
+        self.update_batch(states, accessor)
+    }
+
+    fn evaluate(&self, accessor: &RowAccessor) -> Result<ScalarValue> {
+        Ok(accessor.get_as_scalar(&DataType::Boolean, self.index))
+    }
+
+    #[inline(always)]
+    fn state_index(&self) -> usize {
+        self.index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::aggregate;
+    use crate::generic_test_op;
+    use arrow::datatypes::*;
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn bool_and_with_nulls() -> Result<()> {
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![
+            Some(true),
+            None,
+            Some(false),
+            Some(true),
+        ]));
+        generic_test_op!(
+            a,
+            DataType::Boolean,
+            BoolAnd,
+            ScalarValue::from(false),
+            DataType::Boolean
+        )
+    }
+
+    #[test]
+    fn bool_or_with_nulls() -> Result<()> {
+        let a: ArrayRef =
+            Arc::new(BooleanArray::from(vec![Some(false), None, Some(true)]));
+        generic_test_op!(
+            a,
+            DataType::Boolean,
+            BoolOr,
+            ScalarValue::from(true),
+            DataType::Boolean
+        )
+    }
+
+    #[test]
+    fn bool_and_all_nulls() -> Result<()> {
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![None, None]));
+        generic_test_op!(
+            a,
+            DataType::Boolean,
+            BoolAnd,
+            ScalarValue::Boolean(None),
+            DataType::Boolean
+        )
+    }
+}
@@ -27,7 +27,7 @@
 //! * Return type: a function `(arg_types) -> return_type`. E.g. for min, ([f32]) -> f32, ([f64]) -> f64.
 
 use crate::aggregate::coercion_rule::coerce_exprs;
-use crate::{expressions, AggregateExpr, PhysicalExpr};
+use crate::{expressions, AggregateExpr, PhysicalExpr, PhysicalSortExpr};
 use arrow::datatypes::Schema;
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::aggregate_function;
@@ -255,9 +255,199 @@ pub fn create_aggregate_expr(
                 "MEDIAN(DISTINCT) aggregations are not available".to_string(),
             ));
         }
+        (AggregateFunction::StringAgg, false) => {
+            Arc::new(expressions::StringAgg::try_new(
+                // Pass in the delimiter expr
+                coerced_phy_exprs,
+                name,
+                return_type,
+            )?)
+        }
+        (AggregateFunction::StringAgg, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "STRING_AGG(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (AggregateFunction::BoolAnd, _) => Arc::new(expressions::BoolAnd::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::BoolOr, _) => Arc::new(expressions::BoolOr::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::BitAnd, _) => Arc::new(expressions::BitAnd::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::BitOr, _) => Arc::new(expressions::BitOr::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::BitXor, false) => Arc::new(expressions::BitXor::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::BitXor, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "BIT_XOR(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (AggregateFunction::Median, false) => Arc::new(expressions::PercentileCont::new(
+            coerced_phy_exprs[0].clone(),
+            0.5,
+            name,
+        )),
+        (AggregateFunction::Median, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "MEDIAN(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (AggregateFunction::PercentileCont, false) => {
+            Arc::new(expressions::PercentileCont::try_new(
+                // Pass in the desired percentile expr
+                coerced_phy_exprs,
+                name,
+            )?)
+        }
+        (AggregateFunction::PercentileCont, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "PERCENTILE_CONT(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (AggregateFunction::Mode, false) => Arc::new(expressions::Mode::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::Mode, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "MODE(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (AggregateFunction::FirstValue, false) => Arc::new(expressions::FirstValue::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::FirstValue, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "FIRST_VALUE(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (AggregateFunction::LastValue, false) => Arc::new(expressions::LastValue::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            return_type,
+        )),
+        (AggregateFunction::LastValue, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "LAST_VALUE(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (
+            AggregateFunction::RegrSlope
+            | AggregateFunction::RegrIntercept
+            | AggregateFunction::RegrCount
+            | AggregateFunction::RegrR2
+            | AggregateFunction::RegrAvgx
+            | AggregateFunction::RegrAvgy
+            | AggregateFunction::RegrSxx
+            | AggregateFunction::RegrSyy
+            | AggregateFunction::RegrSxy,
+            false,
+        ) => {
+            let regr_type = match fun {
+                AggregateFunction::RegrSlope => expressions::RegrType::Slope,
+                AggregateFunction::RegrIntercept => expressions::RegrType::Intercept,
+                AggregateFunction::RegrCount => expressions::RegrType::Count,
+                AggregateFunction::RegrR2 => expressions::RegrType::R2,
+                AggregateFunction::RegrAvgx => expressions::RegrType::AvgX,
+                AggregateFunction::RegrAvgy => expressions::RegrType::AvgY,
+                AggregateFunction::RegrSxx => expressions::RegrType::SXX,
+                AggregateFunction::RegrSyy => expressions::RegrType::SYY,
+                AggregateFunction::RegrSxy => expressions::RegrType::SXY,
+                _ => unreachable!(),
+            };
+            Arc::new(expressions::Regr::new(
+                coerced_phy_exprs[0].clone(),
+                coerced_phy_exprs[1].clone(),
+                name,
+                regr_type,
+                return_type,
+            ))
+        }
+        (
+            AggregateFunction::RegrSlope
+            | AggregateFunction::RegrIntercept
+            | AggregateFunction::RegrCount
+            | AggregateFunction::RegrR2
+            | AggregateFunction::RegrAvgx
+            | AggregateFunction::RegrAvgy
+            | AggregateFunction::RegrSxx
+            | AggregateFunction::RegrSyy
+            | AggregateFunction::RegrSxy,
+            true,
+        ) => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "{}(DISTINCT) aggregations are not available",
+                fun
+            )));
+        }
     })
 }
 
+/// Create a physical aggregation expression with the `FILTER (WHERE ...)` clause
+/// `filter`, and the `ORDER BY` clause `order_by`, which order-sensitive aggregates
+/// sort their input rows by, and the other aggregates ignore.
+pub fn create_aggregate_expr_with_clauses(
+    fun: &AggregateFunction,
+    distinct: bool,
+    input_phy_exprs: &[Arc<dyn PhysicalExpr>],
+    filter: Option<Arc<dyn PhysicalExpr>>,
+    order_by: &[PhysicalSortExpr],
+    input_schema: &Schema,
+    name: impl Into<String>,
+) -> Result<Arc<dyn AggregateExpr>> {
+    let name = name.into();
+    let mut aggregate =
+        create_aggregate_expr(fun, distinct, input_phy_exprs, input_schema, &name)?;
+    if !order_by.is_empty() && is_order_sensitive(fun) {
+        if distinct {
+            return Err(DataFusionError::NotImplemented(format!(
+                "ORDER BY in {}(DISTINCT) aggregations is not supported",
+                fun
+            )));
+        }
+        aggregate = Arc::new(expressions::OrderedAggregate::try_new(
+            aggregate,
+            order_by.to_vec(),
+            input_schema,
+        )?);
+    }
+    if let Some(filter) = filter {
+        aggregate = Arc::new(expressions::FilteredAggregate::new(aggregate, filter));
+    }
+    Ok(aggregate)
+}
+
+/// Returns whether the result of the aggregate function depends on the order of its
+/// input rows
+fn is_order_sensitive(fun: &AggregateFunction) -> bool {
+    matches!(
+        fun,
+        AggregateFunction::ArrayAgg
+            | AggregateFunction::StringAgg
+            | AggregateFunction::FirstValue
+            | AggregateFunction::LastValue
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
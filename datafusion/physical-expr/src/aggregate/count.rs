@@ -127,6 +127,16 @@ impl Accumulator for CountAccumulator {
         Ok(())
     }
 
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let array = &values[0];
+        self.count -= (array.len() - array.data().null_count()) as u64;
+        Ok(())
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+
     fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
         let counts = states[0].as_any().downcast_ref::<UInt64Array>().unwrap();
         let delta = &compute::sum(counts);
@@ -284,4 +294,16 @@ mod tests {
             DataType::UInt64
         )
     }
+
+    #[test]
+    fn count_retract() -> Result<()> {
+        let mut accumulator = CountAccumulator::new();
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(2)]));
+        let b: ArrayRef = Arc::new(Int32Array::from(vec![Some(4), None]));
+        accumulator.update_batch(&[a.clone()])?;
+        accumulator.update_batch(&[b])?;
+        accumulator.retract_batch(&[a])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(1u64));
+        Ok(())
+    }
 }
@@ -0,0 +1,181 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `FILTER (WHERE ...)` clause of aggregate expressions
+
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::{ArrayRef, BooleanArray};
+use arrow::compute::filter;
+use arrow::datatypes::Field;
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+use std::any::Any;
+use std::sync::Arc;
+
+/// An aggregate expression that only aggregates the input rows for which its filter
+/// is true
+#[derive(Debug)]
+pub struct FilteredAggregate {
+    aggregate: Arc<dyn AggregateExpr>,
+    filter: Arc<dyn PhysicalExpr>,
+}
+
+impl FilteredAggregate {
+    /// Create a new aggregate expression that filters the input of `aggregate`
+    pub fn new(aggregate: Arc<dyn AggregateExpr>, filter: Arc<dyn PhysicalExpr>) -> Self {
+        Self { aggregate, filter }
+    }
+
+    /// The filtered aggregate expression
+    pub fn aggregate(&self) -> &Arc<dyn AggregateExpr> {
+        &self.aggregate
+    }
+
+    /// The filter of the input rows
+    pub fn filter(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.filter
+    }
+}
+
+impl AggregateExpr for FilteredAggregate {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        self.aggregate.field()
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(FilteredAccumulator {
+            accumulator: self.aggregate.create_accumulator()?,
+        }))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        self.aggregate.state_fields()
+    }
+
+    /// the expressions of the filtered aggregate, followed by the filter
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        let mut expressions = self.aggregate.expressions();
+        expressions.push(self.filter.clone());
+        expressions
+    }
+
+    fn name(&self) -> &str {
+        self.aggregate.name()
+    }
+}
+
+/// Accumulator that passes the values of the rows for which the filter, its last
+/// input, is true to the accumulator of the filtered aggregate
+#[derive(Debug)]
+struct FilteredAccumulator {
+    accumulator: Box<dyn Accumulator>,
+}
+
+impl Accumulator for FilteredAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        self.accumulator.state()
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (predicate, values) = values.split_last().ok_or_else(|| {
+            DataFusionError::Internal("Missing filter of an aggregate".to_string())
+        })?;
+        let predicate = predicate
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "Aggregate filter must be boolean, found {}",
+                    predicate.data_type()
+                ))
+            })?;
+        // nulls in the predicate are treated as false
+        let values = values
+            .iter()
+            .map(|array| filter(array.as_ref(), predicate))
+            .collect::<arrow::error::Result<Vec<_>>>()?;
+        self.accumulator.update_batch(&values)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.accumulator.merge_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        self.accumulator.evaluate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::tests::aggregate;
+    use crate::expressions::{binary, BinaryExpr};
+    use crate::expressions::{col, lit, Count, Sum};
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Schema};
+    use arrow::record_batch::RecordBatch;
+    use datafusion_expr::Operator;
+
+    fn batch() -> Result<RecordBatch> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int64, true)]);
+        let a = Int64Array::from(vec![Some(1), Some(2), None, Some(4), Some(5)]);
+        Ok(RecordBatch::try_new(Arc::new(schema), vec![Arc::new(a)])?)
+    }
+
+    #[test]
+    fn filtered_sum() -> Result<()> {
+        let batch = batch()?;
+        let a = col("a", &batch.schema())?;
+        let filter = binary(
+            a.clone(),
+            Operator::Gt,
+            lit(ScalarValue::from(1i64)),
+            &batch.schema(),
+        )?;
+        let sum = Arc::new(Sum::new(a, "bla", DataType::Int64));
+        let agg = Arc::new(FilteredAggregate::new(sum, filter));
+
+        assert_eq!(aggregate(&batch, agg)?, ScalarValue::from(11i64));
+        Ok(())
+    }
+
+    #[test]
+    fn filtered_count_null_predicate() -> Result<()> {
+        let batch = batch()?;
+        let a = col("a", &batch.schema())?;
+        // the predicate is null for the null value of `a`, which must not be counted
+        let filter: Arc<dyn PhysicalExpr> = Arc::new(BinaryExpr::new(
+            a.clone(),
+            Operator::Lt,
+            lit(ScalarValue::from(5i64)),
+        ));
+        let count = Arc::new(Count::new(
+            lit(ScalarValue::from(1i64)),
+            "bla",
+            DataType::UInt64,
+        ));
+        let agg = Arc::new(FilteredAggregate::new(count, filter));
+
+        assert_eq!(aggregate(&batch, agg)?, ScalarValue::from(3u64));
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions for the FIRST_VALUE and LAST_VALUE aggregates that can be evaluated at runtime during query execution
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::{ArrayRef, BooleanArray};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::Accumulator;
+
+/// FIRST_VALUE aggregate expression, the value of its first input row, which is
+/// only deterministic with an `ORDER BY` clause
+#[derive(Debug)]
+pub struct FirstValue {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl FirstValue {
+    /// Create a new FIRST_VALUE aggregate function
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for FirstValue {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(FirstLastAccumulator::try_new(
+            &self.data_type,
+            true,
+        )?))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(state_fields(&self.name, "first_value", &self.data_type))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// LAST_VALUE aggregate expression, the value of its last input row, which is
+/// only deterministic with an `ORDER BY` clause
+#[derive(Debug)]
+pub struct LastValue {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl LastValue {
+    /// Create a new LAST_VALUE aggregate function
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for LastValue {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(FirstLastAccumulator::try_new(
+            &self.data_type,
+            false,
+        )?))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(state_fields(&self.name, "last_value", &self.data_type))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// the value, and whether there was an input row, which the value doesn't tell as
+/// it may be null
+fn state_fields(name: &str, state_name: &str, data_type: &DataType) -> Vec<Field> {
+    vec![
+        Field::new(
+            &format_state_name(name, state_name),
+            data_type.clone(),
+            true,
+        ),
+        Field::new(&format_state_name(name, "is_set"), DataType::Boolean, false),
+    ]
+}
+
+#[derive(Debug)]
+struct FirstLastAccumulator {
+    value: ScalarValue,
+    is_set: bool,
+    /// keeps the first row if true, and the last one otherwise
+    first: bool,
+}
+
+impl FirstLastAccumulator {
+    fn try_new(data_type: &DataType, first: bool) -> Result<Self> {
+        Ok(Self {
+            value: ScalarValue::try_from(data_type)?,
+            is_set: false,
+            first,
+        })
+    }
+
+    fn update(&mut self, values: &ArrayRef, index: Option<usize>) -> Result<()> {
+        if let Some(index) = index {
+            if !(self.first && self.is_set) {
+                self.value = ScalarValue::try_from_array(values, index)?;
+                self.is_set = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for FirstLastAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.value.clone(), ScalarValue::from(self.is_set)])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        let index = match (values.len(), self.first) {
+            (0, _) => None,
+            (_, true) => Some(0),
+            (len, false) => Some(len - 1),
+        };
+        self.update(values, index)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let is_set = states[1].as_any().downcast_ref::<BooleanArray>().unwrap();
+        let mut set_indices = (0..is_set.len()).filter(|i| is_set.value(*i));
+        let index = if self.first {
+            set_indices.next()
+        } else {
+            set_indices.next_back()
+        };
+        self.update(&states[0], index)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(self.value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::aggregate;
+    use crate::generic_test_op;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::*;
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn first_value() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![None, Some(2), Some(3)]));
+        generic_test_op!(
+            a,
+            DataType::Int32,
+            FirstValue,
+            ScalarValue::Int32(None),
+            DataType::Int32
+        )
+    }
+
+    #[test]
+    fn last_value() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), Some(2), Some(3)]));
+        generic_test_op!(
+            a,
+            DataType::Int32,
+            LastValue,
+            ScalarValue::from(3i32),
+            DataType::Int32
+        )
+    }
+
+    #[test]
+    fn first_last_merge() -> Result<()> {
+        for (first, expected) in [(true, 1i32), (false, 4i32)] {
+            let mut states = vec![];
+            for values in [vec![], vec![1, 2], vec![], vec![3, 4], vec![]] {
+                let mut accumulator =
+                    FirstLastAccumulator::try_new(&DataType::Int32, first)?;
+                let values: ArrayRef = Arc::new(Int32Array::from(values));
+                accumulator.update_batch(&[values])?;
+                states.push(accumulator.state()?);
+            }
+            let states = (0..2)
+                .map(|i| ScalarValue::iter_to_array(states.iter().map(|s| s[i].clone())))
+                .collect::<Result<Vec<_>>>()?;
+
+            let mut accumulator = FirstLastAccumulator::try_new(&DataType::Int32, first)?;
+            accumulator.merge_batch(&states)?;
+            assert_eq!(accumulator.evaluate()?, ScalarValue::from(expected));
+        }
+        Ok(())
+    }
+}
@@ -18,6 +18,8 @@
 //! Defines physical expressions that can evaluated at runtime during query execution
 
 use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
@@ -110,6 +112,13 @@ impl AggregateExpr for Max {
         Ok(Box::new(MaxAccumulator::try_new(&self.data_type)?))
     }
 
+    fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SlidingMinMaxAccumulator::try_new(
+            &self.data_type,
+            false,
+        )?))
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -631,6 +640,13 @@ impl AggregateExpr for Min {
         Ok(Box::new(MinAccumulator::try_new(&self.data_type)?))
     }
 
+    fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SlidingMinMaxAccumulator::try_new(
+            &self.data_type,
+            true,
+        )?))
+    }
+
     fn state_fields(&self) -> Result<Vec<Field>> {
         Ok(vec![Field::new(
             &format_state_name(&self.name, "min"),
@@ -710,6 +726,107 @@ impl Accumulator for MinAccumulator {
     }
 }
 
+/// A non-null value ordered by `partial_cmp`, which is total among the values of a
+/// single data type
+#[derive(Debug, PartialEq, Eq)]
+struct OrderedScalar(ScalarValue);
+
+impl PartialOrd for OrderedScalar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedScalar {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// An accumulator to compute the minimum or maximum value of a sliding window frame,
+/// which keeps the multiset of its input values so that they can be retracted
+#[derive(Debug)]
+struct SlidingMinMaxAccumulator {
+    data_type: DataType,
+    values: BTreeMap<OrderedScalar, usize>,
+    /// computes the minimum if true, and the maximum otherwise
+    is_min: bool,
+}
+
+impl SlidingMinMaxAccumulator {
+    fn try_new(datatype: &DataType, is_min: bool) -> Result<Self> {
+        // fails early on the data types that don't have a null value
+        ScalarValue::try_from(datatype)?;
+        Ok(Self {
+            data_type: datatype.clone(),
+            values: BTreeMap::new(),
+            is_min,
+        })
+    }
+}
+
+impl Accumulator for SlidingMinMaxAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        for index in 0..values.len() {
+            let value = ScalarValue::try_from_array(values, index)?;
+            if !value.is_null() {
+                *self.values.entry(OrderedScalar(value)).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        for index in 0..values.len() {
+            let value = ScalarValue::try_from_array(values, index)?;
+            if value.is_null() {
+                continue;
+            }
+            let value = OrderedScalar(value);
+            match self.values.get_mut(&value) {
+                Some(count) if *count > 1 => *count -= 1,
+                Some(_) => {
+                    self.values.remove(&value);
+                }
+                None => {
+                    return Err(DataFusionError::Internal(format!(
+                        "Cannot retract {} which wasn't accumulated",
+                        value.0
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let mut values = self.values.keys();
+        let value = if self.is_min {
+            values.next()
+        } else {
+            values.next_back()
+        };
+        match value {
+            Some(value) => Ok(value.0.clone()),
+            None => ScalarValue::try_from(&self.data_type),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct MinRowAccumulator {
     index: usize,
@@ -1189,4 +1306,32 @@ mod tests {
             DataType::Date64
         )
     }
+
+    #[test]
+    fn sliding_min_max_retract() -> Result<()> {
+        let mut min = SlidingMinMaxAccumulator::try_new(&DataType::Int32, true)?;
+        let mut max = SlidingMinMaxAccumulator::try_new(&DataType::Int32, false)?;
+        let first: ArrayRef = Arc::new(Int32Array::from(vec![Some(3), None, Some(1)]));
+        let second: ArrayRef = Arc::new(Int32Array::from(vec![Some(5), Some(1)]));
+        for accumulator in [&mut min, &mut max] {
+            accumulator.update_batch(&[first.clone()])?;
+            accumulator.update_batch(&[second.clone()])?;
+        }
+        assert_eq!(min.evaluate()?, ScalarValue::Int32(Some(1)));
+        assert_eq!(max.evaluate()?, ScalarValue::Int32(Some(5)));
+
+        // the duplicated minimum remains after one of its occurrences is retracted
+        for accumulator in [&mut min, &mut max] {
+            accumulator.retract_batch(&[first.clone()])?;
+        }
+        assert_eq!(min.evaluate()?, ScalarValue::Int32(Some(1)));
+        assert_eq!(max.evaluate()?, ScalarValue::Int32(Some(5)));
+
+        for accumulator in [&mut min, &mut max] {
+            accumulator.retract_batch(&[second.clone()])?;
+        }
+        assert_eq!(min.evaluate()?, ScalarValue::Int32(None));
+        assert_eq!(max.evaluate()?, ScalarValue::Int32(None));
+        Ok(())
+    }
 }
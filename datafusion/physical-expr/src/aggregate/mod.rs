@@ -31,19 +31,28 @@ pub(crate) mod approx_percentile_cont_with_weight;
 pub(crate) mod array_agg;
 pub(crate) mod array_agg_distinct;
 pub(crate) mod average;
+pub(crate) mod bit_and_or_xor;
+pub(crate) mod bool_and_or;
 pub(crate) mod coercion_rule;
 pub(crate) mod correlation;
 pub(crate) mod count;
 pub(crate) mod count_distinct;
 pub(crate) mod covariance;
+pub(crate) mod filtered;
+pub(crate) mod first_last;
 pub(crate) mod grouping;
 #[macro_use]
 pub(crate) mod min_max;
 pub mod build_in;
 mod hyperloglog;
+pub(crate) mod mode;
+pub(crate) mod ordered;
+pub(crate) mod percentile_cont;
+pub(crate) mod regr;
 pub mod row_accumulator;
 pub(crate) mod stats;
 pub(crate) mod stddev;
+pub(crate) mod string_agg;
 pub(crate) mod sum;
 pub(crate) mod sum_distinct;
 mod tdigest;
@@ -67,6 +76,14 @@ pub trait AggregateExpr: Send + Sync + Debug {
     /// return states with the same description as `state_fields`
     fn create_accumulator(&self) -> Result<Box<dyn Accumulator>>;
 
+    /// the accumulator used to evaluate sliding window frames, into which rows entering
+    /// the frame are passed to `update_batch` and rows leaving it to `retract_batch`.
+    /// Aggregations that can only support retraction with extra bookkeeping (e.g. `min`)
+    /// override this, the others use their regular accumulator.
+    fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        self.create_accumulator()
+    }
+
     /// the fields that encapsulate the Accumulator's state
     /// the number of fields here equals the number of states that the accumulator contains
     fn state_fields(&self) -> Result<Vec<Field>>;
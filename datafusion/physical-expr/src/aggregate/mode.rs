@@ -0,0 +1,242 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions for MODE that can be evaluated at runtime during query execution
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::{Array, ArrayRef, Int64Array, ListArray};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+
+/// MODE aggregate expression, the most frequent non-null value of its input. Ties
+/// are broken by the smallest value.
+#[derive(Debug)]
+pub struct Mode {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl Mode {
+    /// Create a new MODE aggregate function
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for Mode {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ModeAccumulator {
+            data_type: self.data_type.clone(),
+            counts: HashMap::new(),
+        }))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![
+            Field::new(
+                &format_state_name(&self.name, "values"),
+                DataType::List(Box::new(Field::new(
+                    "item",
+                    self.data_type.clone(),
+                    true,
+                ))),
+                false,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "counts"),
+                DataType::List(Box::new(Field::new("item", DataType::Int64, true))),
+                false,
+            ),
+        ])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// An accumulator to compute the mode, which counts the occurrences of every
+/// distinct value
+#[derive(Debug)]
+struct ModeAccumulator {
+    data_type: DataType,
+    counts: HashMap<ScalarValue, i64>,
+}
+
+impl Accumulator for ModeAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        let (values, counts): (Vec<_>, Vec<_>) = self
+            .counts
+            .iter()
+            .map(|(value, count)| (value.clone(), ScalarValue::from(*count)))
+            .unzip();
+        Ok(vec![
+            ScalarValue::List(Some(values), Box::new(self.data_type.clone())),
+            ScalarValue::List(Some(counts), Box::new(DataType::Int64)),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        for index in 0..values.len() {
+            if values.is_valid(index) {
+                let value = ScalarValue::try_from_array(values, index)?;
+                *self.counts.entry(value).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let values = downcast_list(&states[0])?;
+        let counts = downcast_list(&states[1])?;
+        for (values, counts) in values.iter().zip(counts.iter()) {
+            if let (Some(values), Some(counts)) = (values, counts) {
+                let counts = counts.as_any().downcast_ref::<Int64Array>().unwrap();
+                for index in 0..values.len() {
+                    let value = ScalarValue::try_from_array(&values, index)?;
+                    *self.counts.entry(value).or_default() += counts.value(index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let mode = self.counts.iter().fold(
+            None,
+            |mode: Option<(&ScalarValue, i64)>, (value, count)| match mode {
+                Some((mode_value, mode_count))
+                    if mode_count > *count
+                        || (mode_count == *count && mode_value < value) =>
+                {
+                    mode
+                }
+                _ => Some((value, *count)),
+            },
+        );
+        match mode {
+            Some((value, _)) => Ok(value.clone()),
+            None => ScalarValue::try_from(&self.data_type),
+        }
+    }
+}
+
+fn downcast_list(array: &ArrayRef) -> Result<&ListArray> {
+    array
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .ok_or_else(|| DataFusionError::Internal("MODE state must be list!".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::aggregate;
+    use crate::generic_test_op;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::*;
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn mode_i32() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(3),
+            None,
+            Some(1),
+            None,
+            Some(3),
+            None,
+            Some(2),
+        ]));
+        generic_test_op!(
+            a,
+            DataType::Int32,
+            Mode,
+            ScalarValue::from(3i32),
+            DataType::Int32
+        )
+    }
+
+    #[test]
+    fn mode_ties_smallest_value() -> Result<()> {
+        let a: ArrayRef = Arc::new(StringArray::from(vec!["b", "a", "c", "b", "a"]));
+        generic_test_op!(
+            a,
+            DataType::Utf8,
+            Mode,
+            ScalarValue::from("a"),
+            DataType::Utf8
+        )
+    }
+
+    #[test]
+    fn mode_merge() -> Result<()> {
+        let agg = Mode::new(
+            col(
+                "a",
+                &Schema::new(vec![Field::new("a", DataType::Int32, true)]),
+            )?,
+            "bla",
+            DataType::Int32,
+        );
+        let mut states = vec![];
+        for values in [vec![1, 2, 2], vec![1, 1, 3]] {
+            let mut accumulator = agg.create_accumulator()?;
+            let values: ArrayRef = Arc::new(Int32Array::from(values));
+            accumulator.update_batch(&[values])?;
+            states.push(accumulator.state()?);
+        }
+        let states = (0..2)
+            .map(|i| ScalarValue::iter_to_array(states.iter().map(|s| s[i].clone())))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut accumulator = agg.create_accumulator()?;
+        accumulator.merge_batch(&states)?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(1i32));
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `ORDER BY` clause of order-sensitive aggregate expressions
+
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, PhysicalExpr, PhysicalSortExpr};
+use arrow::array::ArrayRef;
+use arrow::compute::{lexsort_to_indices, take, SortColumn, SortOptions};
+use arrow::datatypes::{DataType, Field, Schema};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+use std::any::Any;
+use std::sync::Arc;
+
+/// An aggregate expression that passes its input rows to an order-sensitive aggregate,
+/// such as `ARRAY_AGG`, sorted by its `ORDER BY` expressions.
+///
+/// The rows are kept until the aggregate is evaluated, and are the state of its
+/// accumulator, as a list per input expression and per sort expression.
+#[derive(Debug)]
+pub struct OrderedAggregate {
+    aggregate: Arc<dyn AggregateExpr>,
+    order_by: Vec<PhysicalSortExpr>,
+    /// the types of the expressions of the aggregate, followed by the types of the
+    /// sort expressions
+    input_types: Vec<DataType>,
+}
+
+impl OrderedAggregate {
+    /// Create a new aggregate expression that sorts the input of `aggregate`
+    pub fn try_new(
+        aggregate: Arc<dyn AggregateExpr>,
+        order_by: Vec<PhysicalSortExpr>,
+        input_schema: &Schema,
+    ) -> Result<Self> {
+        let input_types = aggregate
+            .expressions()
+            .iter()
+            .chain(order_by.iter().map(|sort| &sort.expr))
+            .map(|expr| expr.data_type(input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            aggregate,
+            order_by,
+            input_types,
+        })
+    }
+
+    /// The ordered aggregate expression
+    pub fn aggregate(&self) -> &Arc<dyn AggregateExpr> {
+        &self.aggregate
+    }
+
+    /// The sort expressions of the input rows
+    pub fn order_by(&self) -> &[PhysicalSortExpr] {
+        &self.order_by
+    }
+}
+
+impl AggregateExpr for OrderedAggregate {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        self.aggregate.field()
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(OrderedAccumulator {
+            aggregate: self.aggregate.clone(),
+            options: self.order_by.iter().map(|sort| sort.options).collect(),
+            input_types: self.input_types.clone(),
+            rows: vec![vec![]; self.input_types.len()],
+        }))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(self
+            .input_types
+            .iter()
+            .enumerate()
+            .map(|(i, data_type)| {
+                Field::new(
+                    &format_state_name(self.aggregate.name(), &format!("ordered{}", i)),
+                    DataType::List(Box::new(Field::new("item", data_type.clone(), true))),
+                    false,
+                )
+            })
+            .collect())
+    }
+
+    /// the expressions of the ordered aggregate, followed by the sort expressions
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        let mut expressions = self.aggregate.expressions();
+        expressions.extend(self.order_by.iter().map(|sort| sort.expr.clone()));
+        expressions
+    }
+
+    fn name(&self) -> &str {
+        self.aggregate.name()
+    }
+}
+
+/// Accumulator that keeps its input rows, and sorts them when evaluated to pass them
+/// to a new accumulator of the ordered aggregate
+#[derive(Debug)]
+struct OrderedAccumulator {
+    aggregate: Arc<dyn AggregateExpr>,
+    options: Vec<SortOptions>,
+    input_types: Vec<DataType>,
+    /// the values of the input rows, per input
+    rows: Vec<Vec<ScalarValue>>,
+}
+
+impl Accumulator for OrderedAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(self
+            .rows
+            .iter()
+            .zip(&self.input_types)
+            .map(|(values, data_type)| {
+                ScalarValue::List(Some(values.clone()), Box::new(data_type.clone()))
+            })
+            .collect())
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        for (rows, array) in self.rows.iter_mut().zip(values) {
+            for index in 0..array.len() {
+                rows.push(ScalarValue::try_from_array(array, index)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        for (rows, array) in self.rows.iter_mut().zip(states) {
+            for index in 0..array.len() {
+                match ScalarValue::try_from_array(array, index)? {
+                    ScalarValue::List(Some(values), _) => rows.extend(values),
+                    ScalarValue::List(None, _) => {}
+                    _ => {
+                        return Err(DataFusionError::Internal(
+                            "ordered aggregate state must be list!".into(),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let mut accumulator = self.aggregate.create_accumulator()?;
+        if !self.rows[0].is_empty() {
+            let arrays = self
+                .rows
+                .iter()
+                .map(|values| ScalarValue::iter_to_array(values.iter().cloned()))
+                .collect::<Result<Vec<_>>>()?;
+            let (values, keys) = arrays.split_at(arrays.len() - self.options.len());
+            let columns = keys
+                .iter()
+                .zip(&self.options)
+                .map(|(values, options)| SortColumn {
+                    values: values.clone(),
+                    options: Some(*options),
+                })
+                .collect::<Vec<_>>();
+            let indices = lexsort_to_indices(&columns, None)?;
+            let values = values
+                .iter()
+                .map(|values| take(values.as_ref(), &indices, None))
+                .collect::<arrow::error::Result<Vec<_>>>()?;
+            accumulator.update_batch(&values)?;
+        }
+        accumulator.evaluate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::tests::aggregate;
+    use crate::expressions::{col, ArrayAgg};
+    use arrow::array::Int32Array;
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn ordered_array_agg() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let a = Int32Array::from(vec![1, 2, 3, 4, 5]);
+        let b = Int32Array::from(vec![Some(3), None, Some(1), Some(5), Some(2)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(a), Arc::new(b)],
+        )?;
+
+        let array_agg =
+            Arc::new(ArrayAgg::new(col("a", &schema)?, "bla", DataType::Int32));
+        let order_by = vec![PhysicalSortExpr {
+            expr: col("b", &schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: false,
+            },
+        }];
+        let agg = Arc::new(OrderedAggregate::try_new(array_agg, order_by, &schema)?);
+
+        let expected = ScalarValue::List(
+            Some(
+                vec![4, 1, 5, 3, 2]
+                    .into_iter()
+                    .map(ScalarValue::from)
+                    .collect(),
+            ),
+            Box::new(DataType::Int32),
+        );
+        assert_eq!(aggregate(&batch, agg)?, expected);
+        Ok(())
+    }
+}
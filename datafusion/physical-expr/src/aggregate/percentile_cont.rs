@@ -0,0 +1,251 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions for the exact PERCENTILE_CONT and MEDIAN that can be evaluated at runtime during query execution
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::expressions::{format_state_name, Literal};
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::{ArrayRef, Float64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+use ordered_float::OrderedFloat;
+
+/// PERCENTILE_CONT aggregate expression, the value at a percentile of the sorted
+/// input, interpolated between the adjacent values. Unlike `APPROX_PERCENTILE_CONT`,
+/// it keeps all the non-null input values.
+#[derive(Debug)]
+pub struct PercentileCont {
+    name: String,
+    expr: Arc<dyn PhysicalExpr>,
+    percentile: f64,
+}
+
+impl PercentileCont {
+    /// Create a new PERCENTILE_CONT aggregate function of `percentile`
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        percentile: f64,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            expr,
+            percentile,
+        }
+    }
+
+    /// Create a new PERCENTILE_CONT aggregate function from its arguments, the
+    /// input expression and the percentile literal
+    pub fn try_new(
+        expr: Vec<Arc<dyn PhysicalExpr>>,
+        name: impl Into<String>,
+    ) -> Result<Self> {
+        // Arguments should be [ColumnExpr, DesiredPercentileLiteral]
+        debug_assert_eq!(expr.len(), 2);
+
+        let lit = expr[1]
+            .as_any()
+            .downcast_ref::<Literal>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "desired percentile argument must be float literal".to_string(),
+                )
+            })?
+            .value();
+        let percentile = match lit {
+            ScalarValue::Float32(Some(q)) => *q as f64,
+            ScalarValue::Float64(Some(q)) => *q,
+            got => return Err(DataFusionError::NotImplemented(format!(
+                "Percentile value for 'PERCENTILE_CONT' must be Float32 or Float64 literal (got data type {})",
+                got
+            )))
+        };
+
+        if !(0.0..=1.0).contains(&percentile) {
+            return Err(DataFusionError::Plan(format!(
+                "Percentile value must be between 0.0 and 1.0 inclusive, {} is invalid",
+                percentile
+            )));
+        }
+
+        Ok(Self::new(expr[0].clone(), percentile, name))
+    }
+}
+
+impl AggregateExpr for PercentileCont {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Float64, true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(PercentileContAccumulator {
+            percentile: self.percentile,
+            values: vec![],
+        }))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "values"),
+            DataType::List(Box::new(Field::new("item", DataType::Float64, true))),
+            false,
+        )])
+    }
+
+    /// the percentile is a literal, so only the input expression is evaluated
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug)]
+struct PercentileContAccumulator {
+    percentile: f64,
+    values: Vec<f64>,
+}
+
+impl Accumulator for PercentileContAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        let values = self.values.iter().map(|v| ScalarValue::from(*v)).collect();
+        Ok(vec![ScalarValue::List(
+            Some(values),
+            Box::new(DataType::Float64),
+        )])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &cast(&values[0], &DataType::Float64)?;
+        let values = values.as_any().downcast_ref::<Float64Array>().unwrap();
+        self.values.extend(values.iter().flatten());
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let states = &states[0];
+        for index in 0..states.len() {
+            match ScalarValue::try_from_array(states, index)? {
+                ScalarValue::List(Some(values), _) => {
+                    for value in values {
+                        if let ScalarValue::Float64(Some(v)) = value {
+                            self.values.push(v);
+                        }
+                    }
+                }
+                ScalarValue::List(None, _) => {}
+                _ => {
+                    return Err(DataFusionError::Internal(
+                        "PERCENTILE_CONT state must be list!".into(),
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        if self.values.is_empty() {
+            return Ok(ScalarValue::Float64(None));
+        }
+        let mut values = self.values.clone();
+        values.sort_by_key(|v| OrderedFloat(*v));
+
+        let position = self.percentile * (values.len() - 1) as f64;
+        let lower = position.floor();
+        let upper = position.ceil();
+        let value = values[lower as usize]
+            + (values[upper as usize] - values[lower as usize]) * (position - lower);
+        Ok(ScalarValue::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::aggregate;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn percentile_cont(values: Vec<Option<i32>>, percentile: f64) -> Result<ScalarValue> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(Int32Array::from(values))],
+        )?;
+        let agg = Arc::new(PercentileCont::try_new(
+            vec![
+                col("a", &schema)?,
+                Arc::new(Literal::new(ScalarValue::from(percentile))),
+            ],
+            "bla",
+        )?);
+        aggregate(&batch, agg)
+    }
+
+    #[test]
+    fn median_odd_and_even() -> Result<()> {
+        let odd = vec![Some(5), Some(1), None, Some(3)];
+        assert_eq!(percentile_cont(odd, 0.5)?, ScalarValue::from(3_f64));
+        let even = vec![Some(4), Some(1), Some(3), Some(2)];
+        assert_eq!(percentile_cont(even, 0.5)?, ScalarValue::from(2.5_f64));
+        Ok(())
+    }
+
+    #[test]
+    fn percentile_cont_interpolates() -> Result<()> {
+        let values = (1..=11).map(Some).collect::<Vec<_>>();
+        assert_eq!(
+            percentile_cont(values.clone(), 0.9)?,
+            ScalarValue::from(10_f64)
+        );
+        assert_eq!(
+            percentile_cont(values.clone(), 0.0)?,
+            ScalarValue::from(1_f64)
+        );
+        assert_eq!(percentile_cont(values, 0.25)?, ScalarValue::from(3.5_f64));
+        Ok(())
+    }
+
+    #[test]
+    fn percentile_cont_empty() -> Result<()> {
+        assert_eq!(
+            percentile_cont(vec![None], 0.5)?,
+            ScalarValue::Float64(None)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn percentile_cont_out_of_range() {
+        assert!(percentile_cont(vec![Some(1)], 1.5).is_err());
+    }
+}
@@ -0,0 +1,376 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `REGR_*` linear regression aggregate expressions
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::Float64Array;
+use arrow::{
+    array::{ArrayRef, UInt64Array},
+    compute::cast,
+    datatypes::DataType,
+    datatypes::Field,
+};
+use datafusion_common::ScalarValue;
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::Accumulator;
+
+use crate::expressions::format_state_name;
+
+/// The statistic of the least-squares-fit linear equation a [`Regr`] computes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegrType {
+    /// Slope of the linear equation
+    Slope,
+    /// Y-intercept of the linear equation
+    Intercept,
+    /// Number of rows in which both arguments are not null
+    Count,
+    /// Square of the correlation coefficient
+    R2,
+    /// Average of the independent variable
+    AvgX,
+    /// Average of the dependent variable
+    AvgY,
+    /// Sum of squares of the independent variable
+    SXX,
+    /// Sum of squares of the dependent variable
+    SYY,
+    /// Sum of products of the independent and the dependent variable
+    SXY,
+}
+
+/// REGR_* aggregate expression, over the rows in which both the dependent variable
+/// `y` and the independent variable `x` are not null
+#[derive(Debug)]
+pub struct Regr {
+    name: String,
+    regr_type: RegrType,
+    expr_y: Arc<dyn PhysicalExpr>,
+    expr_x: Arc<dyn PhysicalExpr>,
+}
+
+impl Regr {
+    /// Create a new REGR_* aggregate function
+    pub fn new(
+        expr_y: Arc<dyn PhysicalExpr>,
+        expr_x: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        regr_type: RegrType,
+        data_type: DataType,
+    ) -> Self {
+        // the result of regr_count is UINT64, and of the others FLOAT64.
+        assert!(match regr_type {
+            RegrType::Count => matches!(data_type, DataType::UInt64),
+            _ => matches!(data_type, DataType::Float64),
+        });
+        Self {
+            name: name.into(),
+            regr_type,
+            expr_y,
+            expr_x,
+        }
+    }
+}
+
+impl AggregateExpr for Regr {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        let data_type = match self.regr_type {
+            RegrType::Count => DataType::UInt64,
+            _ => DataType::Float64,
+        };
+        Ok(Field::new(&self.name, data_type, true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(RegrAccumulator::try_new(self.regr_type)?))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![
+            Field::new(
+                &format_state_name(&self.name, "count"),
+                DataType::UInt64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "mean_x"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "mean_y"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "m2_x"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "m2_y"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "algo_const"),
+                DataType::Float64,
+                true,
+            ),
+        ])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr_y.clone(), self.expr_x.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// An accumulator to compute the linear regression statistics, with the same
+/// online algorithm as the covariance
+#[derive(Debug)]
+pub struct RegrAccumulator {
+    regr_type: RegrType,
+    count: u64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    algo_const: f64,
+}
+
+impl RegrAccumulator {
+    /// Creates a new `RegrAccumulator`
+    pub fn try_new(regr_type: RegrType) -> Result<Self> {
+        Ok(Self {
+            regr_type,
+            count: 0,
+            mean_x: 0_f64,
+            mean_y: 0_f64,
+            m2_x: 0_f64,
+            m2_y: 0_f64,
+            algo_const: 0_f64,
+        })
+    }
+}
+
+impl Accumulator for RegrAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![
+            ScalarValue::from(self.count),
+            ScalarValue::from(self.mean_x),
+            ScalarValue::from(self.mean_y),
+            ScalarValue::from(self.m2_x),
+            ScalarValue::from(self.m2_y),
+            ScalarValue::from(self.algo_const),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values_y = &cast(&values[0], &DataType::Float64)?;
+        let values_x = &cast(&values[1], &DataType::Float64)?;
+        let values_y = values_y.as_any().downcast_ref::<Float64Array>().unwrap();
+        let values_x = values_x.as_any().downcast_ref::<Float64Array>().unwrap();
+
+        for (y, x) in values_y.iter().zip(values_x.iter()) {
+            let (y, x) = match (y, x) {
+                (Some(y), Some(x)) => (y, x),
+                _ => continue,
+            };
+
+            let new_count = self.count + 1;
+            let delta_x = x - self.mean_x;
+            let new_mean_x = delta_x / new_count as f64 + self.mean_x;
+            let delta_y = y - self.mean_y;
+            let new_mean_y = delta_y / new_count as f64 + self.mean_y;
+
+            self.count = new_count;
+            self.m2_x += delta_x * (x - new_mean_x);
+            self.m2_y += delta_y * (y - new_mean_y);
+            self.algo_const += delta_x * (y - new_mean_y);
+            self.mean_x = new_mean_x;
+            self.mean_y = new_mean_y;
+        }
+
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let counts = states[0].as_any().downcast_ref::<UInt64Array>().unwrap();
+        let means_x = states[1].as_any().downcast_ref::<Float64Array>().unwrap();
+        let means_y = states[2].as_any().downcast_ref::<Float64Array>().unwrap();
+        let m2s_x = states[3].as_any().downcast_ref::<Float64Array>().unwrap();
+        let m2s_y = states[4].as_any().downcast_ref::<Float64Array>().unwrap();
+        let cs = states[5].as_any().downcast_ref::<Float64Array>().unwrap();
+
+        for i in 0..counts.len() {
+            let c = counts.value(i);
+            if c == 0_u64 {
+                continue;
+            }
+            let new_count = self.count + c;
+            let factor = self.count as f64 * c as f64 / new_count as f64;
+            let delta_x = means_x.value(i) - self.mean_x;
+            let delta_y = means_y.value(i) - self.mean_y;
+
+            self.mean_x += delta_x * c as f64 / new_count as f64;
+            self.mean_y += delta_y * c as f64 / new_count as f64;
+            self.m2_x += m2s_x.value(i) + delta_x * delta_x * factor;
+            self.m2_y += m2s_y.value(i) + delta_y * delta_y * factor;
+            self.algo_const += cs.value(i) + delta_x * delta_y * factor;
+            self.count = new_count;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        if self.regr_type == RegrType::Count {
+            return Ok(ScalarValue::from(self.count));
+        }
+        if self.count == 0 {
+            return Ok(ScalarValue::Float64(None));
+        }
+
+        let slope = if self.m2_x == 0_f64 {
+            None
+        } else {
+            Some(self.algo_const / self.m2_x)
+        };
+        let value = match self.regr_type {
+            RegrType::Slope => slope,
+            RegrType::Intercept => slope.map(|slope| self.mean_y - slope * self.mean_x),
+            RegrType::R2 => {
+                if self.m2_x == 0_f64 {
+                    None
+                } else if self.m2_y == 0_f64 {
+                    Some(1_f64)
+                } else {
+                    Some(self.algo_const * self.algo_const / (self.m2_x * self.m2_y))
+                }
+            }
+            RegrType::AvgX => Some(self.mean_x),
+            RegrType::AvgY => Some(self.mean_y),
+            RegrType::SXX => Some(self.m2_x),
+            RegrType::SYY => Some(self.m2_y),
+            RegrType::SXY => Some(self.algo_const),
+            RegrType::Count => {
+                return Err(DataFusionError::Internal(
+                    "REGR_COUNT is evaluated from the count".to_string(),
+                ))
+            }
+        };
+        Ok(ScalarValue::Float64(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::aggregate;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn regr(regr_type: RegrType) -> Result<ScalarValue> {
+        let schema = Schema::new(vec![
+            Field::new("y", DataType::Int32, true),
+            Field::new("x", DataType::Float64, true),
+        ]);
+        let y = Int32Array::from(vec![Some(3), Some(5), Some(7), None, Some(100)]);
+        let x = Float64Array::from(vec![
+            Some(1_f64),
+            Some(2_f64),
+            Some(3_f64),
+            Some(4_f64),
+            None,
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(y), Arc::new(x)],
+        )?;
+        let data_type = match regr_type {
+            RegrType::Count => DataType::UInt64,
+            _ => DataType::Float64,
+        };
+        let agg = Arc::new(Regr::new(
+            col("y", &schema)?,
+            col("x", &schema)?,
+            "bla",
+            regr_type,
+            data_type,
+        ));
+        aggregate(&batch, agg)
+    }
+
+    #[test]
+    fn regr_of_a_line() -> Result<()> {
+        // y = 2x + 1 over the rows in which both are not null
+        assert_eq!(regr(RegrType::Count)?, ScalarValue::from(3_u64));
+        assert_eq!(regr(RegrType::Slope)?, ScalarValue::from(2_f64));
+        assert_eq!(regr(RegrType::Intercept)?, ScalarValue::from(1_f64));
+        assert_eq!(regr(RegrType::R2)?, ScalarValue::from(1_f64));
+        assert_eq!(regr(RegrType::AvgX)?, ScalarValue::from(2_f64));
+        assert_eq!(regr(RegrType::AvgY)?, ScalarValue::from(5_f64));
+        assert_eq!(regr(RegrType::SXX)?, ScalarValue::from(2_f64));
+        assert_eq!(regr(RegrType::SYY)?, ScalarValue::from(8_f64));
+        assert_eq!(regr(RegrType::SXY)?, ScalarValue::from(4_f64));
+        Ok(())
+    }
+
+    #[test]
+    fn regr_merge() -> Result<()> {
+        let parts: Vec<(Vec<f64>, Vec<f64>)> = vec![
+            (vec![3_f64, 5_f64], vec![1_f64, 2_f64]),
+            (vec![7_f64, 10_f64], vec![3_f64, 4_f64]),
+        ];
+        let mut states = vec![];
+        for (y, x) in parts {
+            let mut accumulator = RegrAccumulator::try_new(RegrType::SXY)?;
+            let y: ArrayRef = Arc::new(Float64Array::from(y));
+            let x: ArrayRef = Arc::new(Float64Array::from(x));
+            accumulator.update_batch(&[y, x])?;
+            states.push(accumulator.state()?);
+        }
+        let states = (0..states[0].len())
+            .map(|i| ScalarValue::iter_to_array(states.iter().map(|s| s[i].clone())))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut merged = RegrAccumulator::try_new(RegrType::SXY)?;
+        merged.merge_batch(&states)?;
+
+        let mut single = RegrAccumulator::try_new(RegrType::SXY)?;
+        let y: ArrayRef = Arc::new(Float64Array::from(vec![3_f64, 5_f64, 7_f64, 10_f64]));
+        let x: ArrayRef = Arc::new(Float64Array::from(vec![1_f64, 2_f64, 3_f64, 4_f64]));
+        single.update_batch(&[y, x])?;
+
+        assert_eq!(merged.evaluate()?, single.evaluate()?);
+        Ok(())
+    }
+}
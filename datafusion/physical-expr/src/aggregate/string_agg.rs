@@ -0,0 +1,228 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions for STRING_AGG that can be evaluated at runtime during query execution
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::expressions::{format_state_name, Literal};
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+
+/// STRING_AGG aggregate expression, which concatenates the non-null values of its
+/// input separated by a literal delimiter
+#[derive(Debug)]
+pub struct StringAgg {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+    delimiter: String,
+}
+
+impl StringAgg {
+    /// Create a new STRING_AGG aggregate function from its arguments, the
+    /// concatenated expression and the delimiter literal
+    pub fn try_new(
+        expr: Vec<Arc<dyn PhysicalExpr>>,
+        name: impl Into<String>,
+        data_type: DataType,
+    ) -> Result<Self> {
+        // Arguments should be [ColumnExpr, DelimiterLiteral]
+        debug_assert_eq!(expr.len(), 2);
+
+        let lit = expr[1]
+            .as_any()
+            .downcast_ref::<Literal>()
+            .ok_or_else(|| {
+                DataFusionError::NotImplemented(
+                    "Delimiter of 'STRING_AGG' must be a string literal".to_string(),
+                )
+            })?
+            .value();
+        let delimiter = match lit {
+            ScalarValue::Utf8(delimiter) | ScalarValue::LargeUtf8(delimiter) => {
+                delimiter.clone().unwrap_or_default()
+            }
+            ScalarValue::Null => String::new(),
+            got => {
+                return Err(DataFusionError::Plan(format!(
+                    "Delimiter of 'STRING_AGG' must be a string literal (got {})",
+                    got
+                )))
+            }
+        };
+
+        Ok(Self {
+            name: name.into(),
+            data_type,
+            expr: expr[0].clone(),
+            delimiter,
+        })
+    }
+}
+
+impl AggregateExpr for StringAgg {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(StringAggAccumulator {
+            data_type: self.data_type.clone(),
+            delimiter: self.delimiter.clone(),
+            value: None,
+        }))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "string_agg"),
+            self.data_type.clone(),
+            true,
+        )])
+    }
+
+    /// the delimiter is a literal, so only the concatenated expression is evaluated
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug)]
+struct StringAggAccumulator {
+    data_type: DataType,
+    delimiter: String,
+    value: Option<String>,
+}
+
+impl StringAggAccumulator {
+    fn append(&mut self, s: &str) {
+        match &mut self.value {
+            Some(value) => {
+                value.push_str(&self.delimiter);
+                value.push_str(s);
+            }
+            None => self.value = Some(s.to_string()),
+        }
+    }
+}
+
+impl Accumulator for StringAggAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        for index in 0..values.len() {
+            match ScalarValue::try_from_array(values, index)? {
+                ScalarValue::Utf8(Some(s)) | ScalarValue::LargeUtf8(Some(s)) => {
+                    self.append(&s)
+                }
+                ScalarValue::Utf8(None) | ScalarValue::LargeUtf8(None) => {}
+                e => {
+                    return Err(DataFusionError::Internal(format!(
+                        "STRING_AGG is not expected to receive the scalar {:?}",
+                        e
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// the partial results are concatenated like the input values
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(match self.data_type {
+            DataType::LargeUtf8 => ScalarValue::LargeUtf8(self.value.clone()),
+            _ => ScalarValue::Utf8(self.value.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::aggregate;
+    use arrow::array::StringArray;
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn string_agg(
+        values: Vec<Option<&str>>,
+        delimiter: ScalarValue,
+    ) -> Result<ScalarValue> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(StringArray::from(values))],
+        )?;
+        let agg = Arc::new(StringAgg::try_new(
+            vec![col("a", &schema)?, Arc::new(Literal::new(delimiter))],
+            "bla",
+            DataType::Utf8,
+        )?);
+        aggregate(&batch, agg)
+    }
+
+    #[test]
+    fn string_agg_skips_nulls() -> Result<()> {
+        let actual = string_agg(
+            vec![Some("a"), None, Some("b"), Some("c")],
+            ScalarValue::from(", "),
+        )?;
+        assert_eq!(actual, ScalarValue::from("a, b, c"));
+        Ok(())
+    }
+
+    #[test]
+    fn string_agg_all_nulls() -> Result<()> {
+        let actual = string_agg(vec![None, None], ScalarValue::from(","))?;
+        assert_eq!(actual, ScalarValue::Utf8(None));
+        Ok(())
+    }
+
+    #[test]
+    fn string_agg_non_literal_delimiter() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let result = StringAgg::try_new(
+            vec![col("a", &schema)?, col("a", &schema)?],
+            "bla",
+            DataType::Utf8,
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -130,6 +130,10 @@ impl AggregateExpr for Sum {
 #[derive(Debug)]
 struct SumAccumulator {
     sum: ScalarValue,
+    // the number of non-null values in the sum, which tells whether it's null once
+    // values are retracted
+    count: u64,
+    non_finite: NonFiniteCounts,
 }
 
 impl SumAccumulator {
@@ -137,6 +141,8 @@ impl SumAccumulator {
     pub fn try_new(data_type: &DataType) -> Result<Self> {
         Ok(Self {
             sum: ScalarValue::try_from(data_type)?,
+            count: 0,
+            non_finite: NonFiniteCounts::default(),
         })
     }
 }
@@ -356,6 +362,150 @@ pub(crate) fn sum(lhs: &ScalarValue, rhs: &ScalarValue) -> Result<ScalarValue> {
     })
 }
 
+// returns the difference of two scalar values of the same type, as summed by `sum_batch`.
+macro_rules! typed_sub {
+    ($OLD_VALUE:expr, $DELTA:expr) => {{
+        match ($OLD_VALUE, $DELTA) {
+            (a, None) => *a,
+            (Some(a), Some(b)) => Some(a - b),
+            (None, Some(_)) => {
+                return Err(DataFusionError::Internal(
+                    "Cannot retract a value from an empty sum".to_string(),
+                ))
+            }
+        }
+    }};
+}
+
+/// The numbers of NaN and infinite values of a float sum. They are kept out
+/// of the sum, so that the sum of the finite values is restored once they
+/// are retracted.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct NonFiniteCounts {
+    nan: u64,
+    pos_inf: u64,
+    neg_inf: u64,
+}
+
+// replaces the NaN and infinite values of a float array with nulls, and counts them
+macro_rules! typed_split_non_finite {
+    ($VALUES:expr, $ARRAYTYPE:ident) => {{
+        let values = $VALUES;
+        let array = values.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
+        let mut counts = NonFiniteCounts::default();
+        if array.iter().flatten().all(|v| v.is_finite()) {
+            return Ok((values, counts));
+        }
+        let finite = array
+            .iter()
+            .map(|v| match v {
+                Some(v) if v.is_nan() => {
+                    counts.nan += 1;
+                    None
+                }
+                Some(v) if v.is_infinite() && v.is_sign_positive() => {
+                    counts.pos_inf += 1;
+                    None
+                }
+                Some(v) if v.is_infinite() => {
+                    counts.neg_inf += 1;
+                    None
+                }
+                v => v,
+            })
+            .collect::<$ARRAYTYPE>();
+        (Arc::new(finite) as ArrayRef, counts)
+    }};
+}
+
+impl NonFiniteCounts {
+    /// Adds the NaN and infinite values of `values`, summed as `sum_type`,
+    /// and returns the other values
+    pub(crate) fn add(
+        &mut self,
+        values: &ArrayRef,
+        sum_type: &DataType,
+    ) -> Result<ArrayRef> {
+        let (values, counts) = Self::split(values, sum_type)?;
+        self.nan += counts.nan;
+        self.pos_inf += counts.pos_inf;
+        self.neg_inf += counts.neg_inf;
+        Ok(values)
+    }
+
+    /// Retracts the NaN and infinite values of `values`, summed as
+    /// `sum_type`, and returns the other values
+    pub(crate) fn retract(
+        &mut self,
+        values: &ArrayRef,
+        sum_type: &DataType,
+    ) -> Result<ArrayRef> {
+        let (values, counts) = Self::split(values, sum_type)?;
+        self.nan -= counts.nan;
+        self.pos_inf -= counts.pos_inf;
+        self.neg_inf -= counts.neg_inf;
+        Ok(values)
+    }
+
+    fn split(values: &ArrayRef, sum_type: &DataType) -> Result<(ArrayRef, Self)> {
+        Ok(match sum_type {
+            DataType::Float64 => {
+                typed_split_non_finite!(cast(values, sum_type)?, Float64Array)
+            }
+            DataType::Float32 => {
+                typed_split_non_finite!(cast(values, sum_type)?, Float32Array)
+            }
+            _ => (values.clone(), Self::default()),
+        })
+    }
+
+    /// The value of the float sum whose finite values add up to `sum`
+    pub(crate) fn sum(&self, sum: &ScalarValue) -> ScalarValue {
+        let non_finite = if self.nan > 0 || (self.pos_inf > 0 && self.neg_inf > 0) {
+            f64::NAN
+        } else if self.pos_inf > 0 {
+            f64::INFINITY
+        } else if self.neg_inf > 0 {
+            f64::NEG_INFINITY
+        } else {
+            return sum.clone();
+        };
+        match sum {
+            ScalarValue::Float64(_) => ScalarValue::Float64(Some(non_finite)),
+            ScalarValue::Float32(_) => ScalarValue::Float32(Some(non_finite as f32)),
+            sum => sum.clone(),
+        }
+    }
+}
+
+pub(crate) fn sub(lhs: &ScalarValue, rhs: &ScalarValue) -> Result<ScalarValue> {
+    Ok(match (lhs, rhs) {
+        (ScalarValue::Decimal128(v1, p1, s1), ScalarValue::Decimal128(v2, _, s2))
+            if s1 == s2 =>
+        {
+            ScalarValue::Decimal128(typed_sub!(v1, v2), *p1, *s1)
+        }
+        (ScalarValue::Float64(lhs), ScalarValue::Float64(rhs)) => {
+            ScalarValue::Float64(typed_sub!(lhs, rhs))
+        }
+        (ScalarValue::Float32(lhs), ScalarValue::Float32(rhs)) => {
+            ScalarValue::Float32(typed_sub!(lhs, rhs))
+        }
+        (ScalarValue::UInt64(lhs), ScalarValue::UInt64(rhs)) => {
+            ScalarValue::UInt64(typed_sub!(lhs, rhs))
+        }
+        (ScalarValue::Int64(lhs), ScalarValue::Int64(rhs)) => {
+            ScalarValue::Int64(typed_sub!(lhs, rhs))
+        }
+        e => {
+            return Err(DataFusionError::Internal(format!(
+                "Sum is not expected to retract a scalar {:?}",
+                e
+            )));
+        }
+    })
+}
+
 pub(crate) fn add_to_row(
     dt: &DataType,
     index: usize,
@@ -436,15 +586,31 @@ pub(crate) fn add_to_row(
 
 impl Accumulator for SumAccumulator {
     fn state(&self) -> Result<Vec<ScalarValue>> {
-        Ok(vec![self.sum.clone()])
+        Ok(vec![self.evaluate()?])
     }
 
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let values = &values[0];
-        self.sum = sum(&self.sum, &sum_batch(values, &self.sum.get_datatype())?)?;
+        let sum_type = self.sum.get_datatype();
+        self.count += (values.len() - values.data().null_count()) as u64;
+        let values = self.non_finite.add(values, &sum_type)?;
+        self.sum = sum(&self.sum, &sum_batch(&values, &sum_type)?)?;
         Ok(())
     }
 
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        let sum_type = self.sum.get_datatype();
+        self.count -= (values.len() - values.data().null_count()) as u64;
+        let values = self.non_finite.retract(values, &sum_type)?;
+        self.sum = sub(&self.sum, &sum_batch(&values, &sum_type)?)?;
+        Ok(())
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+
     fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
         // sum(sum1, sum2, sum3, ...) = sum1 + sum2 + sum3 + ...
         self.update_batch(states)
@@ -453,7 +619,10 @@ impl Accumulator for SumAccumulator {
     fn evaluate(&self) -> Result<ScalarValue> {
         // TODO: add the checker for overflow
         // For the decimal(precision,_) data type, the absolute of value must be less than 10^precision.
-        Ok(self.sum.clone())
+        if self.count == 0 {
+            return ScalarValue::try_from(&self.sum.get_datatype());
+        }
+        Ok(self.non_finite.sum(&self.sum))
     }
 }
 
@@ -706,4 +875,53 @@ mod tests {
             DataType::Float64
         )
     }
+
+    #[test]
+    fn sum_retract() -> Result<()> {
+        let mut accumulator = SumAccumulator::try_new(&DataType::Int64)?;
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(2)]));
+        let b: ArrayRef = Arc::new(Int32Array::from(vec![Some(4)]));
+        accumulator.update_batch(&[a.clone()])?;
+        accumulator.update_batch(&[b.clone()])?;
+        accumulator.retract_batch(&[a])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::Int64(Some(4)));
+
+        // the sum of an empty frame is null rather than zero
+        accumulator.retract_batch(&[b])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::Int64(None));
+        assert!(accumulator.supports_retract_batch());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sum_retract_non_finite() -> Result<()> {
+        let mut accumulator = SumAccumulator::try_new(&DataType::Float64)?;
+        let finite: ArrayRef = Arc::new(Float64Array::from(vec![1_f64, 2_f64]));
+        let nan: ArrayRef = Arc::new(Float64Array::from(vec![f64::NAN]));
+        let inf: ArrayRef = Arc::new(Float32Array::from(vec![f32::INFINITY]));
+        let neg_inf: ArrayRef = Arc::new(Float64Array::from(vec![f64::NEG_INFINITY]));
+        accumulator.update_batch(&[finite])?;
+        accumulator.update_batch(&[inf.clone()])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(f64::INFINITY));
+        accumulator.update_batch(&[neg_inf.clone()])?;
+        assert!(
+            matches!(accumulator.evaluate()?, ScalarValue::Float64(Some(v)) if v.is_nan())
+        );
+        accumulator.retract_batch(&[inf])?;
+        assert_eq!(
+            accumulator.evaluate()?,
+            ScalarValue::from(f64::NEG_INFINITY)
+        );
+        accumulator.update_batch(&[nan.clone()])?;
+        accumulator.retract_batch(&[neg_inf])?;
+        assert!(
+            matches!(accumulator.evaluate()?, ScalarValue::Float64(Some(v)) if v.is_nan())
+        );
+
+        // the finite sum is restored once the non-finite values leave
+        accumulator.retract_batch(&[nan])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(3_f64));
+        Ok(())
+    }
 }
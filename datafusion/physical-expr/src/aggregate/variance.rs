@@ -181,7 +181,11 @@ impl AggregateExpr for VariancePop {
 pub struct VarianceAccumulator {
     m2: f64,
     mean: f64,
+    // the number of finite values in the mean and m2
     count: u64,
+    // the number of NaN and infinite values, which are kept out of the mean
+    // and m2 so that they are restored once these values are retracted
+    non_finite: u64,
     stats_type: StatsType,
 }
 
@@ -192,29 +196,40 @@ impl VarianceAccumulator {
             m2: 0_f64,
             mean: 0_f64,
             count: 0_u64,
+            non_finite: 0_u64,
             stats_type: s_type,
         })
     }
 
+    /// The number of non-null values
     pub fn get_count(&self) -> u64 {
-        self.count
+        self.count + self.non_finite
     }
 
+    /// The mean of the values, NaN if one of them is not finite
     pub fn get_mean(&self) -> f64 {
-        self.mean
+        match self.non_finite {
+            0 => self.mean,
+            _ => f64::NAN,
+        }
     }
 
+    /// The sum of the squared differences of the values to their mean, NaN
+    /// if one of them is not finite
     pub fn get_m2(&self) -> f64 {
-        self.m2
+        match self.non_finite {
+            0 => self.m2,
+            _ => f64::NAN,
+        }
     }
 }
 
 impl Accumulator for VarianceAccumulator {
     fn state(&self) -> Result<Vec<ScalarValue>> {
         Ok(vec![
-            ScalarValue::from(self.count),
-            ScalarValue::from(self.mean),
-            ScalarValue::from(self.m2),
+            ScalarValue::from(self.get_count()),
+            ScalarValue::from(self.get_mean()),
+            ScalarValue::from(self.get_m2()),
         ])
     }
 
@@ -228,6 +243,10 @@ impl Accumulator for VarianceAccumulator {
             .flatten();
 
         for value in arr {
+            if !value.is_finite() {
+                self.non_finite += 1;
+                continue;
+            }
             let new_count = self.count + 1;
             let delta1 = value - self.mean;
             let new_mean = delta1 / new_count as f64 + self.mean;
@@ -242,6 +261,42 @@ impl Accumulator for VarianceAccumulator {
         Ok(())
     }
 
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &cast(&values[0], &DataType::Float64)?;
+        let arr = values
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap()
+            .iter()
+            .flatten();
+
+        // reverses the update of Welford's algorithm
+        for value in arr {
+            if !value.is_finite() {
+                self.non_finite -= 1;
+                continue;
+            }
+            let new_count = self.count - 1;
+            if new_count == 0 {
+                self.mean = 0_f64;
+                self.m2 = 0_f64;
+            } else {
+                let delta1 = self.mean - value;
+                let new_mean = delta1 / new_count as f64 + self.mean;
+                let delta2 = new_mean - value;
+                self.mean = new_mean;
+                self.m2 -= delta1 * delta2;
+            }
+            self.count = new_count;
+        }
+
+        Ok(())
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        true
+    }
+
     fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
         let counts = states[0].as_any().downcast_ref::<UInt64Array>().unwrap();
         let means = states[1].as_any().downcast_ref::<Float64Array>().unwrap();
@@ -252,6 +307,10 @@ impl Accumulator for VarianceAccumulator {
             if c == 0_u64 {
                 continue;
             }
+            if !means.value(i).is_finite() || !m2s.value(i).is_finite() {
+                self.non_finite += c;
+                continue;
+            }
             let new_count = self.count + c;
             let new_mean = self.mean * self.count as f64 / new_count as f64
                 + means.value(i) * c as f64 / new_count as f64;
@@ -268,13 +327,14 @@ impl Accumulator for VarianceAccumulator {
     }
 
     fn evaluate(&self) -> Result<ScalarValue> {
+        let total_count = self.get_count();
         let count = match self.stats_type {
-            StatsType::Population => self.count,
+            StatsType::Population => total_count,
             StatsType::Sample => {
-                if self.count > 0 {
-                    self.count - 1
+                if total_count > 0 {
+                    total_count - 1
                 } else {
-                    self.count
+                    total_count
                 }
             }
         };
@@ -285,10 +345,10 @@ impl Accumulator for VarianceAccumulator {
             ));
         }
 
-        if self.count == 0 {
+        if total_count == 0 {
             Ok(ScalarValue::Float64(None))
         } else {
-            Ok(ScalarValue::Float64(Some(self.m2 / count as f64)))
+            Ok(ScalarValue::Float64(Some(self.get_m2() / count as f64)))
         }
     }
 }
@@ -531,4 +591,43 @@ mod tests {
         accum1.merge_batch(&state2)?;
         accum1.evaluate()
     }
+
+    #[test]
+    fn variance_retract() -> Result<()> {
+        let mut accumulator = VarianceAccumulator::try_new(StatsType::Population)?;
+        let a: ArrayRef = Arc::new(Float64Array::from(vec![Some(100_f64), None]));
+        let b: ArrayRef =
+            Arc::new(Float64Array::from(vec![1_f64, 2_f64, 3_f64, 4_f64, 5_f64]));
+        accumulator.update_batch(&[a.clone()])?;
+        accumulator.update_batch(&[b])?;
+        accumulator.retract_batch(&[a])?;
+
+        let variance = match accumulator.evaluate()? {
+            ScalarValue::Float64(Some(v)) => v,
+            v => panic!("unexpected variance {:?}", v),
+        };
+        assert!((variance - 2_f64).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn variance_retract_non_finite() -> Result<()> {
+        let mut accumulator = VarianceAccumulator::try_new(StatsType::Population)?;
+        let a: ArrayRef = Arc::new(Float64Array::from(vec![
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ]));
+        let b: ArrayRef = Arc::new(Float64Array::from(vec![1_f64, 3_f64]));
+        accumulator.update_batch(&[a.clone()])?;
+        accumulator.update_batch(&[b])?;
+        assert!(
+            matches!(accumulator.evaluate()?, ScalarValue::Float64(Some(v)) if v.is_nan())
+        );
+
+        // the state of the finite values is restored once the others leave
+        accumulator.retract_batch(&[a])?;
+        assert_eq!(accumulator.evaluate()?, ScalarValue::from(1_f64));
+        Ok(())
+    }
 }
@@ -17,6 +17,7 @@
 
 //! DateTime expressions
 
+use crate::timezone::{timestamps_to_local, Tz};
 use arrow::{
     array::{Array, ArrayRef, GenericStringArray, OffsetSizeTrait, PrimitiveArray},
     compute::kernels::cast_utils::string_to_timestamp_nanos,
@@ -246,7 +247,35 @@ pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
             ));
         };
 
-    let f = |x: Option<i64>| x.map(|x| date_trunc_single(granularity, x)).transpose();
+    let time_zone = match array {
+        ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(_, tz_opt)) => tz_opt,
+        ColumnarValue::Array(array) => match array.data_type() {
+            DataType::Timestamp(_, tz_opt) => tz_opt,
+            _ => &None,
+        },
+        _ => &None,
+    };
+    let tz = time_zone.as_deref().map(Tz::parse).transpose()?;
+
+    // timestamps with a time zone are truncated in their local time, converted back to
+    // UTC with their own offset when it is still in effect, e.g. for the hour repeated
+    // when the clocks go back
+    let f = |x: Option<i64>| {
+        x.map(|x| match &tz {
+            Some(tz) => {
+                let offset = tz.offset_from_utc(x.div_euclid(1_000_000_000)) as i64;
+                let local = date_trunc_single(granularity, x + offset * 1_000_000_000)?;
+                let utc = local - offset * 1_000_000_000;
+                if tz.offset_from_utc(utc.div_euclid(1_000_000_000)) as i64 == offset {
+                    Ok(utc)
+                } else {
+                    Ok(tz.to_utc(local, &TimeUnit::Nanosecond))
+                }
+            }
+            None => date_trunc_single(granularity, x),
+        })
+        .transpose()
+    };
 
     Ok(match array {
         ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(v, tz_opt)) => {
@@ -260,12 +289,12 @@ pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
                 .as_any()
                 .downcast_ref::<TimestampNanosecondArray>()
                 .unwrap();
-            let array = array
-                .iter()
-                .map(f)
-                .collect::<Result<TimestampNanosecondArray>>()?;
+            let array = array.iter().map(f).collect::<Result<Vec<_>>>()?;
 
-            ColumnarValue::Array(Arc::new(array))
+            ColumnarValue::Array(Arc::new(TimestampNanosecondArray::from_opt_vec(
+                array,
+                time_zone.clone(),
+            )))
         }
         _ => {
             return Err(DataFusionError::Execution(
@@ -347,6 +376,11 @@ pub fn date_part(args: &[ColumnarValue]) -> Result<ColumnarValue> {
         ColumnarValue::Array(array) => array.clone(),
         ColumnarValue::Scalar(scalar) => scalar.to_array(),
     };
+    // the parts of timestamps with a time zone are those of their local time
+    let array = match array.data_type() {
+        DataType::Timestamp(_, Some(tz)) => timestamps_to_local(&array, tz)?,
+        _ => array,
+    };
 
     let arr = match date_part.to_lowercase().as_str() {
         "year" => extract_date_part!(array, temporal::year),
@@ -502,6 +536,51 @@ mod tests {
         });
     }
 
+    #[test]
+    fn date_trunc_time_zone_test() {
+        let cases = vec![
+            (
+                "2020-09-08T13:42:29.190855Z",
+                "day",
+                "2020-09-08T00:00:00.000000-04:00",
+            ),
+            // the day the clocks go forward is only 23 hours long
+            (
+                "2022-03-13T12:00:00Z",
+                "day",
+                "2022-03-13T00:00:00.000000-05:00",
+            ),
+            // the hour repeated when the clocks go back
+            (
+                "2022-11-06T05:30:00Z",
+                "hour",
+                "2022-11-06T01:00:00.000000-04:00",
+            ),
+            (
+                "2022-11-06T06:30:00Z",
+                "hour",
+                "2022-11-06T01:00:00.000000-05:00",
+            ),
+        ];
+
+        cases.iter().for_each(|(original, granularity, expected)| {
+            let value = string_to_timestamp_nanos(original).unwrap();
+            let args = [
+                ColumnarValue::Scalar(ScalarValue::Utf8(Some(granularity.to_string()))),
+                ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(
+                    Some(value),
+                    Some("America/New_York".to_string()),
+                )),
+            ];
+            let result = match date_trunc(&args).unwrap() {
+                ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(v, _)) => v,
+                _ => panic!("Expected a timestamp scalar"),
+            };
+            let expected = string_to_timestamp_nanos(expected).unwrap();
+            assert_eq!(result, Some(expected), "{} = {}", original, expected);
+        });
+    }
+
     #[test]
     fn to_timestamp_invalid_input_type() -> Result<()> {
         // pass the wrong type of input array to to_timestamp and test
@@ -243,7 +243,8 @@ impl PhysicalExpr for CaseExpr {
         } else if let Some(e) = &self.else_expr {
             e.nullable(input_schema)
         } else {
-            Ok(false)
+            // without an else, rows that match no when are null
+            Ok(true)
         }
     }
 
@@ -342,6 +343,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn case_nullable() -> Result<()> {
+        let batch = case_test_batch()?;
+        let schema = batch.schema();
+
+        // CASE a WHEN 'foo' THEN 123 END
+        let when = lit(ScalarValue::Utf8(Some("foo".to_string())));
+        let then = lit(ScalarValue::Int32(Some(123)));
+        let expr = case(
+            Some(col("a", &schema)?),
+            &[(when.clone(), then.clone())],
+            None,
+        )?;
+        assert!(expr.nullable(&schema)?);
+
+        // CASE a WHEN 'foo' THEN 123 ELSE 999 END
+        let else_value = lit(ScalarValue::Int32(Some(999)));
+        let expr = case(Some(col("a", &schema)?), &[(when, then)], Some(else_value))?;
+        assert!(!expr.nullable(&schema)?);
+
+        Ok(())
+    }
+
     #[test]
     fn case_with_expr_divide_by_zero() -> Result<()> {
         let batch = case_test_batch1()?;
@@ -19,13 +19,16 @@ use std::any::Any;
 use std::fmt;
 use std::sync::Arc;
 
+use crate::timezone::{timestamps_from_local, timestamps_to_local, unit_scale, Tz};
 use crate::PhysicalExpr;
+use arrow::array::{Array, ArrayRef, GenericStringArray, Int64Array, OffsetSizeTrait};
 use arrow::compute;
 use arrow::compute::kernels;
+use arrow::compute::kernels::cast_utils::string_to_timestamp_nanos;
 use arrow::compute::CastOptions;
-use arrow::datatypes::{DataType, Schema};
+use arrow::datatypes::{DataType, Schema, TimeUnit};
 use arrow::record_batch::RecordBatch;
-use compute::can_cast_types;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Timelike};
 use datafusion_common::ScalarValue;
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::ColumnarValue;
@@ -102,19 +105,159 @@ pub fn cast_column(
     cast_options: &CastOptions,
 ) -> Result<ColumnarValue> {
     match value {
-        ColumnarValue::Array(array) => Ok(ColumnarValue::Array(
-            kernels::cast::cast_with_options(array, cast_type, cast_options)?,
-        )),
+        ColumnarValue::Array(array) => Ok(ColumnarValue::Array(cast_array(
+            array,
+            cast_type,
+            cast_options,
+        )?)),
         ColumnarValue::Scalar(scalar) => {
             let scalar_array = scalar.to_array();
-            let cast_array =
-                kernels::cast::cast_with_options(&scalar_array, cast_type, cast_options)?;
+            let cast_array = cast_array(&scalar_array, cast_type, cast_options)?;
             let cast_scalar = ScalarValue::try_from_array(&cast_array, 0)?;
             Ok(ColumnarValue::Scalar(cast_scalar))
         }
     }
 }
 
+/// Casts `array` to `cast_type`, taking the time zones of timestamps into account:
+/// timestamps without time zone are the local times of the time zone of those with
+/// one, in which strings without an offset from UTC are parsed, and that they are
+/// formatted in
+pub fn cast_array(
+    array: &ArrayRef,
+    cast_type: &DataType,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef> {
+    match (array.data_type(), cast_type) {
+        (DataType::Timestamp(_, None), DataType::Timestamp(unit, Some(tz))) => {
+            let local = kernels::cast::cast_with_options(
+                array,
+                &DataType::Timestamp(unit.clone(), None),
+                cast_options,
+            )?;
+            timestamps_from_local(&local, tz)
+        }
+        (
+            DataType::Timestamp(_, Some(tz)),
+            DataType::Timestamp(_, None) | DataType::Date32 | DataType::Date64,
+        ) => {
+            let local = timestamps_to_local(array, tz)?;
+            Ok(kernels::cast::cast_with_options(
+                &local,
+                cast_type,
+                cast_options,
+            )?)
+        }
+        (DataType::Timestamp(_, Some(tz)), DataType::Utf8 | DataType::LargeUtf8) => {
+            let strings = format_timestamps(array, tz)?;
+            Ok(kernels::cast::cast_with_options(
+                &strings,
+                cast_type,
+                cast_options,
+            )?)
+        }
+        (DataType::Utf8, DataType::Timestamp(unit, Some(tz))) => {
+            parse_timestamps::<i32>(array, unit, tz, cast_options)
+        }
+        (DataType::LargeUtf8, DataType::Timestamp(unit, Some(tz))) => {
+            parse_timestamps::<i64>(array, unit, tz, cast_options)
+        }
+        _ => Ok(kernels::cast::cast_with_options(
+            array,
+            cast_type,
+            cast_options,
+        )?),
+    }
+}
+
+/// Returns whether [`cast_array`] supports casting `from_type` to `to_type`, that is
+/// whether arrow does, or it is a cast of strings to timestamps with a time zone
+pub fn can_cast_types(from_type: &DataType, to_type: &DataType) -> bool {
+    matches!(
+        (from_type, to_type),
+        (
+            DataType::Utf8 | DataType::LargeUtf8,
+            DataType::Timestamp(_, Some(_))
+        )
+    ) || compute::can_cast_types(from_type, to_type)
+}
+
+/// Formats the timestamps of `array`, whose time zone is `time_zone`, as their local
+/// time followed by its offset from UTC, e.g. `2022-03-13 01:30:00-05:00`
+fn format_timestamps(array: &ArrayRef, time_zone: &str) -> Result<ArrayRef> {
+    let tz = Tz::parse(time_zone)?;
+    let unit = match array.data_type() {
+        DataType::Timestamp(unit, _) => unit.clone(),
+        _ => unreachable!(),
+    };
+    let scale = unit_scale(&unit);
+    let values = Int64Array::from(array.data().clone());
+    let strings = values
+        .iter()
+        .map(|value| {
+            value
+                .map(|value| {
+                    let offset = tz.offset_from_utc(value.div_euclid(scale));
+                    let local = tz.to_local(value, &unit);
+                    let nanos = local.rem_euclid(scale) * (1_000_000_000 / scale);
+                    let local =
+                        DateTime::from_timestamp(local.div_euclid(scale), nanos as u32)
+                            .zip(FixedOffset::east_opt(offset))
+                            .ok_or_else(|| {
+                                DataFusionError::Execution(format!(
+                                    "Timestamp {} is out of range",
+                                    value
+                                ))
+                            })?;
+                    Ok(format!("{}{}", local.0.naive_utc(), local.1))
+                })
+                .transpose()
+        })
+        .collect::<Result<GenericStringArray<i32>>>()?;
+    Ok(Arc::new(strings))
+}
+
+/// Parses the strings of `array` as timestamps in `unit` with time zone `time_zone`,
+/// local times of this time zone when they don't have an offset from UTC
+fn parse_timestamps<T: OffsetSizeTrait>(
+    array: &ArrayRef,
+    unit: &TimeUnit,
+    time_zone: &str,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef> {
+    let tz = Tz::parse(time_zone)?;
+    let scale = unit_scale(unit);
+    let parse = |s: &str| -> Result<i64> {
+        let local = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s.trim(), format).ok());
+        match local {
+            Some(local) => {
+                let value = local.and_utc().timestamp() * scale
+                    + local.nanosecond() as i64 / (1_000_000_000 / scale);
+                Ok(tz.to_utc(value, unit))
+            }
+            None => Ok(string_to_timestamp_nanos(s)?.div_euclid(1_000_000_000 / scale)),
+        }
+    };
+    let strings = array
+        .as_any()
+        .downcast_ref::<GenericStringArray<T>>()
+        .unwrap();
+    let values = strings
+        .iter()
+        .map(|s| match s.map(parse).transpose() {
+            Err(_) if cast_options.safe => Ok(None),
+            result => result,
+        })
+        .collect::<Result<Int64Array>>()?;
+    let values: ArrayRef = Arc::new(values);
+    Ok(kernels::cast::cast(
+        &values,
+        &DataType::Timestamp(unit.clone(), Some(time_zone.to_owned())),
+    )?)
+}
+
 /// Return a PhysicalExpression representing `expr` casted to
 /// `cast_type`, if any casting is needed.
 ///
@@ -45,16 +45,27 @@ pub use crate::aggregate::approx_percentile_cont_with_weight::ApproxPercentileCo
 pub use crate::aggregate::array_agg::ArrayAgg;
 pub use crate::aggregate::array_agg_distinct::DistinctArrayAgg;
 pub use crate::aggregate::average::{Avg, AvgAccumulator};
-pub use crate::aggregate::build_in::create_aggregate_expr;
+pub use crate::aggregate::bit_and_or_xor::{BitAnd, BitOr, BitXor};
+pub use crate::aggregate::bool_and_or::{BoolAnd, BoolOr};
+pub use crate::aggregate::build_in::{
+    create_aggregate_expr, create_aggregate_expr_with_clauses,
+};
 pub use crate::aggregate::correlation::Correlation;
 pub use crate::aggregate::count::Count;
 pub use crate::aggregate::count_distinct::DistinctCount;
 pub use crate::aggregate::covariance::{Covariance, CovariancePop};
+pub use crate::aggregate::filtered::FilteredAggregate;
+pub use crate::aggregate::first_last::{FirstValue, LastValue};
 pub use crate::aggregate::grouping::Grouping;
 pub use crate::aggregate::min_max::{Max, Min};
 pub use crate::aggregate::min_max::{MaxAccumulator, MinAccumulator};
+pub use crate::aggregate::mode::Mode;
+pub use crate::aggregate::ordered::OrderedAggregate;
+pub use crate::aggregate::percentile_cont::PercentileCont;
+pub use crate::aggregate::regr::{Regr, RegrType};
 pub use crate::aggregate::stats::StatsType;
 pub use crate::aggregate::stddev::{Stddev, StddevPop};
+pub use crate::aggregate::string_agg::StringAgg;
 pub use crate::aggregate::sum::Sum;
 pub use crate::aggregate::sum_distinct::DistinctSum;
 pub use crate::aggregate::variance::{Variance, VariancePop};
@@ -68,7 +79,8 @@ pub use crate::window::row_number::RowNumber;
 pub use binary::{binary, BinaryExpr};
 pub use case::{case, CaseExpr};
 pub use cast::{
-    cast, cast_column, cast_with_options, CastExpr, DEFAULT_DATAFUSION_CAST_OPTIONS,
+    can_cast_types, cast, cast_array, cast_column, cast_with_options, CastExpr,
+    DEFAULT_DATAFUSION_CAST_OPTIONS,
 };
 pub use column::{col, Column};
 pub use datetime::DateIntervalExpr;
@@ -30,6 +30,7 @@ pub mod regex_expressions;
 mod sort_expr;
 pub mod string_expressions;
 pub mod struct_expressions;
+pub mod timezone;
 #[cfg(feature = "unicode_expressions")]
 pub mod unicode_expressions;
 pub mod window;
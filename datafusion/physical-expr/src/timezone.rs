@@ -0,0 +1,235 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Time zones of timestamps
+//!
+//! A time zone is either a fixed offset from UTC, such as `+05:30`, or the name of a
+//! zone of the IANA time zone database, such as `America/New_York`, whose rules,
+//! including its daylight saving time transitions, are those of the version of the
+//! database built into `chrono-tz`.
+
+use arrow::array::{Array, ArrayRef, Int64Array};
+use arrow::compute::kernels::{arity::unary, cast::cast};
+use arrow::datatypes::{DataType, Int64Type, TimeUnit};
+use chrono::{DateTime, LocalResult, Offset, TimeZone};
+use datafusion_common::{DataFusionError, Result};
+use std::sync::Arc;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A time zone, that maps UTC instants to local times
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tz {
+    /// A fixed offset from UTC, in seconds
+    Fixed(i32),
+    /// A zone of the time zone database
+    Zone(chrono_tz::Tz),
+}
+
+impl Tz {
+    /// Parses a time zone: `UTC`, `Z`, an offset such as `+05:30`, `-0800` or `+01`, or
+    /// the name of a zone of the time zone database
+    pub fn parse(name: &str) -> Result<Self> {
+        let name = name.trim();
+        if ["UTC", "GMT", "Z"]
+            .iter()
+            .any(|utc| name.eq_ignore_ascii_case(utc))
+        {
+            return Ok(Self::Fixed(0));
+        }
+        if name.starts_with('+') || name.starts_with('-') {
+            return parse_fixed_offset(name).map(Self::Fixed).ok_or_else(|| {
+                DataFusionError::Execution(format!("Invalid time zone offset '{}'", name))
+            });
+        }
+        name.parse().map(Self::Zone).map_err(|_| {
+            DataFusionError::Execution(format!("Invalid time zone '{}'", name))
+        })
+    }
+
+    /// Returns the offset from UTC, in seconds, of the local time at the UTC instant
+    /// `utc`, in seconds since the epoch
+    pub fn offset_from_utc(&self, utc: i64) -> i32 {
+        match self {
+            Self::Fixed(offset) => *offset,
+            // instants out of the range of chrono have no offset
+            Self::Zone(tz) => DateTime::from_timestamp(utc, 0).map_or(0, |utc| {
+                tz.offset_from_utc_datetime(&utc.naive_utc())
+                    .fix()
+                    .local_minus_utc()
+            }),
+        }
+    }
+
+    /// Returns the offset from UTC, in seconds, of the local time `local`, in seconds
+    /// since the local epoch.
+    ///
+    /// As in PostgreSQL, a local time repeated when the clocks go back takes the offset
+    /// in effect after the transition, and a local time skipped when the clocks go
+    /// forward takes the offset in effect before it
+    pub fn offset_from_local(&self, local: i64) -> i32 {
+        let tz = match self {
+            Self::Fixed(offset) => return *offset,
+            Self::Zone(tz) => tz,
+        };
+        let local_time = match DateTime::from_timestamp(local, 0) {
+            Some(local_time) => local_time.naive_utc(),
+            None => return 0,
+        };
+        match tz.offset_from_local_datetime(&local_time) {
+            LocalResult::Single(offset) | LocalResult::Ambiguous(_, offset) => {
+                offset.fix().local_minus_utc()
+            }
+            // zones change their offset at most once a day
+            LocalResult::None => self.offset_from_utc(local - SECONDS_PER_DAY),
+        }
+    }
+
+    /// Converts the UTC timestamps `value`, in `unit`, to their local time
+    pub fn to_local(&self, value: i64, unit: &TimeUnit) -> i64 {
+        let scale = unit_scale(unit);
+        value.wrapping_add(self.offset_from_utc(value.div_euclid(scale)) as i64 * scale)
+    }
+
+    /// Converts the local timestamp `value`, in `unit`, to UTC
+    pub fn to_utc(&self, value: i64, unit: &TimeUnit) -> i64 {
+        let scale = unit_scale(unit);
+        value.wrapping_sub(self.offset_from_local(value.div_euclid(scale)) as i64 * scale)
+    }
+}
+
+/// Returns the number of `unit`s in a second
+pub fn unit_scale(unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    }
+}
+
+/// Converts the timestamps of `array`, whose time zone is `time_zone`, to the
+/// timestamps without time zone of their local time
+pub fn timestamps_to_local(array: &ArrayRef, time_zone: &str) -> Result<ArrayRef> {
+    let tz = Tz::parse(time_zone)?;
+    map_timestamps(array, None, |value, unit| tz.to_local(value, unit))
+}
+
+/// Converts the timestamps without time zone of `array`, taken as local times of
+/// `time_zone`, to timestamps with this time zone
+pub fn timestamps_from_local(array: &ArrayRef, time_zone: &str) -> Result<ArrayRef> {
+    let tz = Tz::parse(time_zone)?;
+    map_timestamps(array, Some(time_zone.to_owned()), |value, unit| {
+        tz.to_utc(value, unit)
+    })
+}
+
+/// Applies `op` to the values of the timestamps of `array`, returning timestamps of
+/// the same unit with time zone `time_zone`
+fn map_timestamps<F>(
+    array: &ArrayRef,
+    time_zone: Option<String>,
+    op: F,
+) -> Result<ArrayRef>
+where
+    F: Fn(i64, &TimeUnit) -> i64,
+{
+    let unit = match array.data_type() {
+        DataType::Timestamp(unit, _) => unit.clone(),
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Expected timestamps, found {:?}",
+                other
+            )))
+        }
+    };
+    let values = Int64Array::from(array.data().clone());
+    let values: ArrayRef =
+        Arc::new(unary::<Int64Type, _, Int64Type>(&values, |v| op(v, &unit)));
+    Ok(cast(&values, &DataType::Timestamp(unit, time_zone))?)
+}
+
+/// Parses an offset such as `+05:30`, `-0800` or `+01`, in seconds
+fn parse_fixed_offset(s: &str) -> Option<i32> {
+    let sign = if s.starts_with('-') { -1 } else { 1 };
+    let digits = s[1..].replace(':', "");
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (digits[..2].parse().ok()?, digits[2..].parse::<i32>().ok()?),
+        _ => return None,
+    };
+    (hours < 24 && minutes < 60).then(|| sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn seconds(s: &str) -> i64 {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_utc()
+            .timestamp()
+    }
+
+    #[test]
+    fn fixed_offsets() -> Result<()> {
+        assert_eq!(Tz::parse("UTC")?, Tz::Fixed(0));
+        assert_eq!(Tz::parse("z")?, Tz::Fixed(0));
+        assert_eq!(Tz::parse("+05:30")?, Tz::Fixed(19800));
+        assert_eq!(Tz::parse("-0800")?, Tz::Fixed(-28800));
+        assert_eq!(Tz::parse("+01")?, Tz::Fixed(3600));
+        assert!(Tz::parse("+25:00").is_err());
+        assert!(Tz::parse("../etc/passwd").is_err());
+        assert!(Tz::parse("Mars/Olympus_Mons").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn zones() -> Result<()> {
+        let tz = Tz::parse("America/New_York")?;
+        for year in [2022, 2037] {
+            let winter = seconds(&format!("{}-01-15 12:00:00", year));
+            let summer = seconds(&format!("{}-07-15 12:00:00", year));
+            assert_eq!(tz.offset_from_utc(winter), -18000);
+            assert_eq!(tz.offset_from_utc(summer), -14400);
+        }
+
+        // 02:30 is skipped on 2022-03-13, and taken as EST
+        let skipped = seconds("2022-03-13 02:30:00");
+        assert_eq!(tz.offset_from_local(skipped), -18000);
+        // 01:30 is repeated on 2022-11-06, and taken as EST
+        let repeated = seconds("2022-11-06 01:30:00");
+        assert_eq!(tz.offset_from_local(repeated), -18000);
+        assert_eq!(tz.offset_from_local(seconds("2022-11-05 01:30:00")), -14400);
+
+        let nanos = seconds("2022-03-13 07:30:00") * 1_000_000_000;
+        let local = tz.to_local(nanos, &TimeUnit::Nanosecond);
+        assert_eq!(local, seconds("2022-03-13 03:30:00") * 1_000_000_000);
+        assert_eq!(tz.to_utc(local, &TimeUnit::Nanosecond), nanos);
+
+        // southern hemisphere
+        let tz = Tz::parse("Australia/Sydney")?;
+        assert_eq!(tz.offset_from_utc(seconds("2022-01-15 00:00:00")), 39600);
+        assert_eq!(tz.offset_from_utc(seconds("2022-07-15 00:00:00")), 36000);
+        Ok(())
+    }
+}
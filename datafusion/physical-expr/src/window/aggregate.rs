@@ -18,15 +18,16 @@
 //! Physical exec for aggregate window function expressions.
 
 use crate::window::partition_evaluator::find_ranges_in_range;
+use crate::window::range::RangeKeys;
 use crate::{expressions::PhysicalSortExpr, PhysicalExpr};
 use crate::{window::WindowExpr, AggregateExpr};
 use arrow::compute::concat;
 use arrow::record_batch::RecordBatch;
 use arrow::{array::ArrayRef, datatypes::Field};
 use datafusion_common::DataFusionError;
-use datafusion_common::Result;
+use datafusion_common::{Result, ScalarValue};
 use datafusion_expr::Accumulator;
-use datafusion_expr::{WindowFrame, WindowFrameUnits};
+use datafusion_expr::{WindowFrame, WindowFrameBound, WindowFrameUnits};
 use std::any::Any;
 use std::iter::IntoIterator;
 use std::ops::Range;
@@ -57,12 +58,6 @@ impl AggregateWindowExpr {
         }
     }
 
-    /// the aggregate window function operates based on window frame, and by default the mode is
-    /// "range".
-    fn evaluation_mode(&self) -> WindowFrameUnits {
-        self.window_frame.unwrap_or_default().units
-    }
-
     /// create a new accumulator based on the underlying aggregation function
     fn create_accumulator(&self) -> Result<AggregateWindowAccumulator> {
         let accumulator = self.aggregate.create_accumulator()?;
@@ -71,8 +66,13 @@ impl AggregateWindowExpr {
 
     /// peer based evaluation based on the fact that batch is pre-sorted given the sort columns
     /// and then per partition point we'll evaluate the peer group (e.g. SUM or MAX gives the same
-    /// results for peers) and concatenate the results.
-    fn peer_based_evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+    /// results for peers) and concatenate the results. The first partition continues the rows
+    /// summarized by `state`.
+    fn peer_based_evaluate(
+        &self,
+        batch: &RecordBatch,
+        state: &[ScalarValue],
+    ) -> Result<ArrayRef> {
         let num_rows = batch.num_rows();
         let partition_points =
             self.evaluate_partition_points(num_rows, &self.partition_columns(batch)?)?;
@@ -85,6 +85,9 @@ impl AggregateWindowExpr {
                 let sort_partition_points =
                     find_ranges_in_range(partition_range, &sort_partition_points);
                 let mut window_accumulators = self.create_accumulator()?;
+                if partition_range.start == 0 {
+                    merge_state(&mut *window_accumulators.accumulator, state)?;
+                }
                 sort_partition_points
                     .iter()
                     .map(|range| window_accumulators.scan_peers(&values, range))
@@ -98,18 +101,237 @@ impl AggregateWindowExpr {
         concat(&results).map_err(DataFusionError::ArrowError)
     }
 
-    fn group_based_evaluate(&self, _batch: &RecordBatch) -> Result<ArrayRef> {
-        Err(DataFusionError::NotImplemented(format!(
-            "Group based evaluation for {} is not yet implemented",
-            self.name()
-        )))
+    /// frame based evaluation, where the frame of each row is computed from the bounds of
+    /// the window frame. As both the start and the end of the frames only move forward within
+    /// a partition, the rows entering the frame are added to the accumulator and the ones
+    /// leaving it are retracted, if the accumulator supports it; otherwise the frame is
+    /// accumulated from scratch whenever its start moves. The first partition continues the
+    /// rows summarized by `state`.
+    fn frame_based_evaluate(
+        &self,
+        batch: &RecordBatch,
+        window_frame: &WindowFrame,
+        state: &[ScalarValue],
+    ) -> Result<ArrayRef> {
+        let num_rows = batch.num_rows();
+        let partition_points =
+            self.evaluate_partition_points(num_rows, &self.partition_columns(batch)?)?;
+        let sort_partition_points =
+            self.evaluate_partition_points(num_rows, &self.sort_columns(batch)?)?;
+        let values = self.evaluate_args(batch)?;
+        let range_keys = RangeKeys::try_new(window_frame, &self.order_by, batch)?;
+        let mut results = Vec::with_capacity(num_rows);
+        for partition_range in &partition_points {
+            let peers = find_ranges_in_range(partition_range, &sort_partition_points);
+            let mut accumulator = self.aggregate.create_sliding_accumulator()?;
+            // only frames starting with the partition summarize dropped rows, so the
+            // accumulator never retracts the state
+            if partition_range.start == 0 {
+                merge_state(&mut *accumulator, state)?;
+            }
+            let mut frame = partition_range.start..partition_range.start;
+            let mut value: Option<ScalarValue> = None;
+            let mut peer_index = 0;
+            for row in partition_range.clone() {
+                while peers[peer_index].end <= row {
+                    peer_index += 1;
+                }
+                let new_frame = frame_range(
+                    window_frame,
+                    row,
+                    partition_range,
+                    peers,
+                    peer_index,
+                    range_keys.as_ref(),
+                )?;
+                // peers share their frame unless it's made of rows
+                match &value {
+                    Some(value) if new_frame == frame => {
+                        results.push(value.clone());
+                        continue;
+                    }
+                    _ => {}
+                }
+                if new_frame.start > frame.start && !accumulator.supports_retract_batch()
+                {
+                    accumulator = self.aggregate.create_sliding_accumulator()?;
+                    frame = new_frame.start..new_frame.start;
+                }
+                if new_frame.end > frame.end {
+                    accumulator
+                        .update_batch(&slice(&values, frame.end..new_frame.end))?;
+                }
+                if new_frame.start > frame.start {
+                    accumulator
+                        .retract_batch(&slice(&values, frame.start..new_frame.start))?;
+                }
+                frame = new_frame;
+                let result = accumulator.evaluate()?;
+                results.push(result.clone());
+                value = Some(result);
+            }
+        }
+        ScalarValue::iter_to_array(results)
+    }
+
+    /// the window frame of the rows, which defaults to the rows up to the last peer of
+    /// the current row
+    fn window_frame(&self) -> WindowFrame {
+        self.window_frame.clone().unwrap_or_default()
+    }
+
+    /// the frames of the rows of `partition`, a partition of `batch`
+    fn partition_frames<'a>(
+        &self,
+        batch: &RecordBatch,
+        window_frame: &'a WindowFrame,
+        partition: &'a Range<usize>,
+    ) -> Result<impl Fn(usize) -> Result<Range<usize>> + 'a> {
+        let sort_partition_points =
+            self.evaluate_partition_points(batch.num_rows(), &self.sort_columns(batch)?)?;
+        let range_keys = RangeKeys::try_new(window_frame, &self.order_by, batch)?;
+        Ok(move |row| {
+            let peers = find_ranges_in_range(partition, &sort_partition_points);
+            let peer_index = peers.partition_point(|peers| peers.end <= row);
+            frame_range(
+                window_frame,
+                row,
+                partition,
+                peers,
+                peer_index,
+                range_keys.as_ref(),
+            )
+        })
     }
+}
 
-    fn row_based_evaluate(&self, _batch: &RecordBatch) -> Result<ArrayRef> {
-        Err(DataFusionError::NotImplemented(format!(
-            "Row based evaluation for {} is not yet implemented",
-            self.name()
-        )))
+/// merges the state of the rows summarized by `state`, if any, into `accumulator`
+fn merge_state(accumulator: &mut dyn Accumulator, state: &[ScalarValue]) -> Result<()> {
+    if state.is_empty() {
+        return Ok(());
+    }
+    let states = state
+        .iter()
+        .map(|value| value.to_array_of_size(1))
+        .collect::<Vec<_>>();
+    accumulator.merge_batch(&states)
+}
+
+/// slices the evaluation arguments to the rows in `range`
+pub(crate) fn slice(values: &[ArrayRef], range: Range<usize>) -> Vec<ArrayRef> {
+    values
+        .iter()
+        .map(|v| v.slice(range.start, range.end - range.start))
+        .collect()
+}
+
+/// the range of rows in the window frame of `row`, given the peer groups of its partition,
+/// the index of its own peer group and, for RANGE frames with offsets, the keys of the rows
+pub(crate) fn frame_range(
+    window_frame: &WindowFrame,
+    row: usize,
+    partition_range: &Range<usize>,
+    peers: &[Range<usize>],
+    peer_index: usize,
+    range_keys: Option<&RangeKeys>,
+) -> Result<Range<usize>> {
+    let start = frame_bound_index(
+        window_frame,
+        &window_frame.start_bound,
+        true,
+        row,
+        partition_range,
+        peers,
+        peer_index,
+        range_keys,
+    )?;
+    let end = frame_bound_index(
+        window_frame,
+        &window_frame.end_bound,
+        false,
+        row,
+        partition_range,
+        peers,
+        peer_index,
+        range_keys,
+    )?;
+    Ok(start..end.max(start))
+}
+
+/// the index of the first row of the window frame of `row` for its start bound, and of the
+/// row following its last row for its end bound, within the partition
+#[allow(clippy::too_many_arguments)]
+fn frame_bound_index(
+    window_frame: &WindowFrame,
+    bound: &WindowFrameBound,
+    is_start: bool,
+    row: usize,
+    partition_range: &Range<usize>,
+    peers: &[Range<usize>],
+    peer_index: usize,
+    range_keys: Option<&RangeKeys>,
+) -> Result<usize> {
+    let index = match (window_frame.units, bound) {
+        (_, WindowFrameBound::Preceding(None)) => partition_range.start,
+        (_, WindowFrameBound::Following(None)) => partition_range.end,
+        (WindowFrameUnits::Rows, bound) => {
+            let row = if is_start { row } else { row + 1 };
+            match bound {
+                WindowFrameBound::Preceding(Some(n)) => {
+                    row.saturating_sub(row_offset(n)?)
+                }
+                WindowFrameBound::Following(Some(n)) => {
+                    row.saturating_add(row_offset(n)?)
+                }
+                _ => row,
+            }
+        }
+        (WindowFrameUnits::Groups, bound) => {
+            let peer_index = match bound {
+                WindowFrameBound::Preceding(Some(n)) => {
+                    peer_index.checked_sub(row_offset(n)?)
+                }
+                WindowFrameBound::Following(Some(n)) => {
+                    Some(peer_index.saturating_add(row_offset(n)?))
+                }
+                _ => Some(peer_index),
+            };
+            match peer_index.map(|i| peers.get(i)) {
+                Some(Some(peers)) if is_start => peers.start,
+                Some(Some(peers)) => peers.end,
+                // after the last peer group
+                Some(None) => partition_range.end,
+                // before the first peer group
+                None => partition_range.start,
+            }
+        }
+        (WindowFrameUnits::Range, bound) if bound.is_current_row() => {
+            if is_start {
+                peers[peer_index].start
+            } else {
+                peers[peer_index].end
+            }
+        }
+        (WindowFrameUnits::Range, bound) => range_keys
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Window frame bound {} of type RANGE requires the keys of the rows",
+                    bound
+                ))
+            })?
+            .bound_index(bound, is_start, row, partition_range, &peers[peer_index])?,
+    };
+    Ok(index.clamp(partition_range.start, partition_range.end))
+}
+
+/// the number of rows or peer groups of the offset of a ROWS or GROUPS frame bound
+fn row_offset(offset: &ScalarValue) -> Result<usize> {
+    match offset {
+        ScalarValue::UInt64(Some(n)) => Ok(usize::try_from(*n).unwrap_or(usize::MAX)),
+        other => Err(DataFusionError::Internal(format!(
+            "Expected a UInt64 window frame offset, got {:?}",
+            other
+        ))),
     }
 }
 
@@ -141,12 +363,110 @@ impl WindowExpr for AggregateWindowExpr {
 
     /// evaluate the window function values against the batch
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
-        match self.evaluation_mode() {
-            WindowFrameUnits::Range => self.peer_based_evaluate(batch),
-            WindowFrameUnits::Rows => self.row_based_evaluate(batch),
-            WindowFrameUnits::Groups => self.group_based_evaluate(batch),
+        self.evaluate_with_state(batch, &[])
+    }
+
+    fn evaluate_with_state(
+        &self,
+        batch: &RecordBatch,
+        state: &[ScalarValue],
+    ) -> Result<ArrayRef> {
+        let window_frame = self.window_frame();
+        match window_frame {
+            // the default frame, made of the rows up to the last peer of the current row,
+            // is the running aggregate of the peer groups
+            WindowFrame {
+                units: WindowFrameUnits::Range,
+                start_bound: WindowFrameBound::Preceding(None),
+                end_bound: WindowFrameBound::CurrentRow,
+            } => self.peer_based_evaluate(batch, state),
+            _ => self.frame_based_evaluate(batch, &window_frame, state),
         }
     }
+
+    /// the frames are final before the end of the partition unless they extend to it,
+    /// which is also the case of the RANGE and GROUPS frames without ORDER BY, as all the
+    /// rows of the partition are then peers
+    fn streams_within_partition(&self) -> bool {
+        let window_frame = self.window_frame();
+        window_frame.end_bound != WindowFrameBound::Following(None)
+            && (window_frame.units == WindowFrameUnits::Rows || !self.order_by.is_empty())
+    }
+
+    fn final_rows_end(
+        &self,
+        batch: &RecordBatch,
+        partition: &Range<usize>,
+    ) -> Result<usize> {
+        if !self.streams_within_partition() {
+            return Ok(partition.start);
+        }
+        let window_frame = self.window_frame();
+        match (window_frame.units, &window_frame.end_bound) {
+            (WindowFrameUnits::Rows, WindowFrameBound::Following(Some(n))) => {
+                Ok(partition
+                    .end
+                    .saturating_sub(row_offset(n)?)
+                    .max(partition.start))
+            }
+            (WindowFrameUnits::Rows, _) => Ok(partition.end),
+            // the frame of a row is only known to be complete once a row of the partition
+            // follows it, as the rows that follow the batch sort after its rows. The ends
+            // of the frames only move forward, so these rows are the leading ones.
+            _ => {
+                let frame = self.partition_frames(batch, &window_frame, partition)?;
+                let mut end = partition.end;
+                while end > partition.start && frame(end - 1)?.end == partition.end {
+                    end -= 1;
+                }
+                Ok(end)
+            }
+        }
+    }
+
+    fn required_rows_start(
+        &self,
+        batch: &RecordBatch,
+        partition: &Range<usize>,
+        row: usize,
+    ) -> Result<usize> {
+        if !self.streams_within_partition() || partition.is_empty() {
+            return Ok(partition.start);
+        }
+        let window_frame = self.window_frame();
+        // the frames only move forward, so the frame of the last row of the partition
+        // bounds the frames of the rows which may follow it
+        let frame = self.partition_frames(batch, &window_frame, partition)?(
+            row.min(partition.end - 1),
+        )?;
+        if window_frame.start_bound == WindowFrameBound::Preceding(None) {
+            // the rows before the end of the frame are summarized by the state
+            Ok(frame.end.min(row))
+        } else {
+            Ok(frame.start.min(row))
+        }
+    }
+
+    fn dropped_rows_state(
+        &self,
+        batch: &RecordBatch,
+        partition: &Range<usize>,
+        row: usize,
+        state: Vec<ScalarValue>,
+    ) -> Result<Vec<ScalarValue>> {
+        if !self.streams_within_partition()
+            || self.window_frame().start_bound != WindowFrameBound::Preceding(None)
+        {
+            return Ok(vec![]);
+        }
+        let mut accumulator = self.aggregate.create_accumulator()?;
+        if partition.start == 0 {
+            merge_state(&mut *accumulator, &state)?;
+        }
+        let values = self.evaluate_args(batch)?;
+        accumulator.update_batch(&slice(&values, partition.start..row))?;
+        accumulator.state()
+    }
 }
 
 /// Aggregate window accumulator utilizes the accumulator from aggregation and do a accumulative sum
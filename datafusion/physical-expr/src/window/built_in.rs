@@ -26,6 +26,7 @@ use arrow::{array::ArrayRef, datatypes::Field};
 use datafusion_common::DataFusionError;
 use datafusion_common::Result;
 use std::any::Any;
+use std::ops::Range;
 use std::sync::Arc;
 
 /// A window expr that takes the form of a built in window function
@@ -92,4 +93,25 @@ impl WindowExpr for BuiltInWindowExpr {
         let results = results.iter().map(|i| i.as_ref()).collect::<Vec<_>>();
         concat(&results).map_err(DataFusionError::ArrowError)
     }
+
+    fn streams_within_partition(&self) -> bool {
+        self.expr.streams_within_partition()
+    }
+
+    fn final_rows_end(
+        &self,
+        _batch: &RecordBatch,
+        partition: &Range<usize>,
+    ) -> Result<usize> {
+        Ok(self.expr.final_rows_end(partition))
+    }
+
+    fn required_rows_start(
+        &self,
+        _batch: &RecordBatch,
+        partition: &Range<usize>,
+        row: usize,
+    ) -> Result<usize> {
+        Ok(self.expr.required_rows_start(partition, row))
+    }
 }
@@ -21,6 +21,7 @@ use arrow::datatypes::Field;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::Result;
 use std::any::Any;
+use std::ops::Range;
 use std::sync::Arc;
 
 /// A window expression that is a built-in window function.
@@ -50,4 +51,21 @@ pub trait BuiltInWindowFunctionExpr: Send + Sync + std::fmt::Debug {
         &self,
         batch: &RecordBatch,
     ) -> Result<Box<dyn PartitionEvaluator>>;
+
+    /// Whether the results of the rows of a partition can be final before all its rows
+    /// are known, see [`crate::window::WindowExpr::streams_within_partition`]
+    fn streams_within_partition(&self) -> bool {
+        false
+    }
+
+    /// the end of the leading rows of `partition` whose results are final, knowing that
+    /// more rows may follow
+    fn final_rows_end(&self, partition: &Range<usize>) -> usize {
+        partition.start
+    }
+
+    /// the first row of `partition` that is needed to evaluate its rows from `row` on
+    fn required_rows_start(&self, partition: &Range<usize>, _row: usize) -> usize {
+        partition.start
+    }
 }
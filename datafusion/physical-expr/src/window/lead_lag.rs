@@ -111,6 +111,30 @@ impl BuiltInWindowFunctionExpr for WindowShift {
             default_value: self.default_value.clone(),
         }))
     }
+
+    fn streams_within_partition(&self) -> bool {
+        true
+    }
+
+    fn final_rows_end(&self, partition: &Range<usize>) -> usize {
+        if self.shift_offset >= 0 {
+            partition.end
+        } else {
+            // a lead is final once its offset row is known
+            let offset =
+                usize::try_from(self.shift_offset.unsigned_abs()).unwrap_or(usize::MAX);
+            partition.end.saturating_sub(offset).max(partition.start)
+        }
+    }
+
+    fn required_rows_start(&self, partition: &Range<usize>, row: usize) -> usize {
+        if self.shift_offset > 0 {
+            let offset = usize::try_from(self.shift_offset).unwrap_or(usize::MAX);
+            row.saturating_sub(offset).max(partition.start)
+        } else {
+            row
+        }
+    }
 }
 
 pub(crate) struct WindowShiftEvaluator {
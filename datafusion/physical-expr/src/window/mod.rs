@@ -22,11 +22,14 @@ pub(crate) mod cume_dist;
 pub(crate) mod lead_lag;
 pub(crate) mod nth_value;
 pub(crate) mod partition_evaluator;
+mod range;
 pub(crate) mod rank;
 pub(crate) mod row_number;
+mod udwf;
 mod window_expr;
 
 pub use aggregate::AggregateWindowExpr;
 pub use built_in::BuiltInWindowExpr;
 pub use built_in_window_function_expr::BuiltInWindowFunctionExpr;
+pub use udwf::WindowUDFExpr;
 pub use window_expr::WindowExpr;
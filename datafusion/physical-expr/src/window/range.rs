@@ -0,0 +1,504 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Bounds of RANGE window frames with offsets, found by binary search on the values of
+//! the ORDER BY column, which are sorted within each partition.
+
+use crate::expressions::PhysicalSortExpr;
+use arrow::array::{ArrayRef, Float64Array, Int64Array, UInt64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Months, NaiveDateTime};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::{WindowFrame, WindowFrameBound, WindowFrameUnits};
+use std::ops::Range;
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_DAY: i128 = 86_400 * NANOS_PER_SECOND;
+
+/// The values of the single ORDER BY column of a RANGE window frame with offsets, which
+/// the offsets are added to or subtracted from
+#[derive(Debug)]
+pub(crate) struct RangeKeys {
+    keys: Keys,
+    descending: bool,
+    nulls_first: bool,
+    start_offset: Option<Offset>,
+    end_offset: Option<Offset>,
+}
+
+#[derive(Debug)]
+enum Keys {
+    /// integers, or timestamps and dates as nanoseconds since the epoch, which intervals
+    /// are added to
+    Integers(Vec<Option<i128>>),
+    /// floating point numbers and decimals
+    Floats(Vec<Option<f64>>),
+}
+
+/// An offset of a bound, converted to the type of the keys
+#[derive(Debug, Clone, Copy)]
+enum Offset {
+    Integer(i128),
+    Float(f64),
+    /// a number of months, applied to the date before the nanoseconds
+    Interval {
+        months: i32,
+        nanos: i128,
+    },
+}
+
+impl RangeKeys {
+    /// the keys of the rows of `batch` to find the bounds of `window_frame`, if it is a
+    /// RANGE frame with a bound that has an offset
+    pub(crate) fn try_new(
+        window_frame: &WindowFrame,
+        order_by: &[PhysicalSortExpr],
+        batch: &RecordBatch,
+    ) -> Result<Option<Self>> {
+        let offset = |bound: &WindowFrameBound| match bound {
+            WindowFrameBound::Preceding(Some(offset))
+            | WindowFrameBound::Following(Some(offset))
+                if !bound.is_current_row() =>
+            {
+                Some(offset.clone())
+            }
+            _ => None,
+        };
+        let (start_offset, end_offset) = (
+            offset(&window_frame.start_bound),
+            offset(&window_frame.end_bound),
+        );
+        if window_frame.units != WindowFrameUnits::Range
+            || (start_offset.is_none() && end_offset.is_none())
+        {
+            return Ok(None);
+        }
+        let sort_expr = match order_by {
+            [sort_expr] => sort_expr,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "With window frame of type RANGE and offsets, the order by expression must be of length 1, got {}",
+                    order_by.len()
+                )))
+            }
+        };
+        let column = sort_expr.evaluate_to_sort_column(batch)?;
+        let data_type = column.values.data_type().clone();
+        let float_offsets = [&start_offset, &end_offset].iter().any(|offset| {
+            matches!(
+                offset,
+                Some(
+                    ScalarValue::Float32(_)
+                        | ScalarValue::Float64(_)
+                        | ScalarValue::Decimal128(..)
+                )
+            )
+        });
+        let keys = match &data_type {
+            DataType::Timestamp(unit, _) => {
+                let scale = match unit {
+                    TimeUnit::Second => NANOS_PER_SECOND,
+                    TimeUnit::Millisecond => 1_000_000,
+                    TimeUnit::Microsecond => 1_000,
+                    TimeUnit::Nanosecond => 1,
+                };
+                Keys::Integers(scaled_integers(&column.values, scale)?)
+            }
+            DataType::Date32 => {
+                Keys::Integers(scaled_integers(&column.values, NANOS_PER_DAY)?)
+            }
+            DataType::Date64 => {
+                Keys::Integers(scaled_integers(&column.values, 1_000_000)?)
+            }
+            DataType::UInt64 if !float_offsets => {
+                let values = cast(&column.values, &DataType::UInt64)?;
+                let values = values.as_any().downcast_ref::<UInt64Array>().unwrap();
+                Keys::Integers(values.iter().map(|v| v.map(i128::from)).collect())
+            }
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+                if !float_offsets =>
+            {
+                Keys::Integers(scaled_integers(&column.values, 1)?)
+            }
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Decimal(_, _) => {
+                let values = cast(&column.values, &DataType::Float64)?;
+                let values = values.as_any().downcast_ref::<Float64Array>().unwrap();
+                Keys::Floats(values.iter().collect())
+            }
+            other => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Window frame of type RANGE with offsets is not supported for ORDER BY expressions of type {}",
+                    other
+                )))
+            }
+        };
+        let is_temporal = matches!(
+            data_type,
+            DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64
+        );
+        let convert = |offset: Option<ScalarValue>| {
+            offset
+                .map(|offset| {
+                    Offset::try_new(&offset, &keys, is_temporal).ok_or_else(|| {
+                        DataFusionError::Plan(format!(
+                            "Invalid window frame offset {} for RANGE frame with ORDER BY expression of type {}",
+                            offset, data_type
+                        ))
+                    })
+                })
+                .transpose()
+        };
+        Ok(Some(Self {
+            start_offset: convert(start_offset)?,
+            end_offset: convert(end_offset)?,
+            keys,
+            descending: column.options.map_or(false, |o| o.descending),
+            nulls_first: column.options.map_or(true, |o| o.nulls_first),
+        }))
+    }
+
+    /// the index of the first row of the frame of `row` for the start bound with an offset,
+    /// or of the row following its last row for the end bound, within the partition. The
+    /// frame of a row whose key is null is made of its peers, the other null rows.
+    pub(crate) fn bound_index(
+        &self,
+        bound: &WindowFrameBound,
+        is_start: bool,
+        row: usize,
+        partition_range: &Range<usize>,
+        peers: &Range<usize>,
+    ) -> Result<usize> {
+        let offset = if is_start {
+            self.start_offset
+        } else {
+            self.end_offset
+        }
+        .ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "Window frame bound {} has no offset",
+                bound
+            ))
+        })?;
+        // following rows have larger keys unless the order is descending
+        let add = matches!(bound, WindowFrameBound::Following(_)) != self.descending;
+        let index = match &self.keys {
+            Keys::Integers(keys) => {
+                let target =
+                    match (keys[row], offset) {
+                        (None, _) => {
+                            return Ok(if is_start { peers.start } else { peers.end })
+                        }
+                        (Some(key), Offset::Integer(offset)) => {
+                            Some(if add { key + offset } else { key - offset })
+                        }
+                        (Some(key), Offset::Interval { months, nanos }) => {
+                            add_months(key, if add { months } else { -months })
+                                .map(|key| if add { key + nanos } else { key - nanos })
+                        }
+                        (Some(_), Offset::Float(_)) => unreachable!(),
+                    };
+                // the frame is the whole partition when the target is out of the range
+                // of dates
+                let target = match target {
+                    Some(target) => target,
+                    None if add != self.descending => {
+                        return Ok(self.non_null_range(keys, partition_range).end)
+                    }
+                    None => return Ok(self.non_null_range(keys, partition_range).start),
+                };
+                self.search(keys, partition_range, target, is_start)
+            }
+            Keys::Floats(keys) => {
+                let (key, offset) = match (keys[row], offset) {
+                    (None, _) => {
+                        return Ok(if is_start { peers.start } else { peers.end })
+                    }
+                    (Some(key), Offset::Float(offset)) => (key, offset),
+                    _ => unreachable!(),
+                };
+                let target = if add { key + offset } else { key - offset };
+                self.search(keys, partition_range, target, is_start)
+            }
+        };
+        Ok(index)
+    }
+
+    /// the rows of the partition whose keys are not null, the null ones being all first or
+    /// last
+    fn non_null_range<T>(
+        &self,
+        keys: &[Option<T>],
+        partition_range: &Range<usize>,
+    ) -> Range<usize> {
+        let partition = &keys[partition_range.clone()];
+        if self.nulls_first {
+            partition_range.start + partition.partition_point(|k| k.is_none())
+                ..partition_range.end
+        } else {
+            partition_range.start
+                ..partition_range.start + partition.partition_point(|k| k.is_some())
+        }
+    }
+
+    /// the index of the first row of the partition whose key is within the frame for the
+    /// start bound, or the first one after it for the end bound
+    fn search<T: PartialOrd + Copy>(
+        &self,
+        keys: &[Option<T>],
+        partition_range: &Range<usize>,
+        target: T,
+        is_start: bool,
+    ) -> usize {
+        let range = self.non_null_range(keys, partition_range);
+        let descending = self.descending;
+        range.start
+            + keys[range].partition_point(|key| {
+                let key = key.unwrap();
+                match (descending, is_start) {
+                    (false, true) => key < target,
+                    (false, false) => key <= target,
+                    (true, true) => key > target,
+                    (true, false) => key >= target,
+                }
+            })
+    }
+}
+
+impl Offset {
+    /// converts `offset` to the type of `keys`, if they can be added
+    fn try_new(offset: &ScalarValue, keys: &Keys, is_temporal: bool) -> Option<Self> {
+        match (keys, offset) {
+            (Keys::Integers(_), ScalarValue::IntervalYearMonth(Some(v)))
+                if is_temporal =>
+            {
+                Some(Self::Interval {
+                    months: *v,
+                    nanos: 0,
+                })
+            }
+            (Keys::Integers(_), ScalarValue::IntervalDayTime(Some(v))) if is_temporal => {
+                let (days, millis) = ((*v >> 32) as i32, *v as i32);
+                Some(Self::Interval {
+                    months: 0,
+                    nanos: days as i128 * NANOS_PER_DAY + millis as i128 * 1_000_000,
+                })
+            }
+            (Keys::Integers(_), ScalarValue::IntervalMonthDayNano(Some(v)))
+                if is_temporal =>
+            {
+                let (months, days, nanos) =
+                    ((*v >> 96) as i32, (*v >> 64) as i32, *v as i64);
+                Some(Self::Interval {
+                    months,
+                    nanos: days as i128 * NANOS_PER_DAY + nanos as i128,
+                })
+            }
+            (Keys::Integers(_), offset) if !is_temporal => {
+                integer_offset(offset).map(Self::Integer)
+            }
+            (Keys::Floats(_), ScalarValue::Float32(Some(v))) => {
+                Some(Self::Float(*v as f64))
+            }
+            (Keys::Floats(_), ScalarValue::Float64(Some(v))) => Some(Self::Float(*v)),
+            (Keys::Floats(_), ScalarValue::Decimal128(Some(v), _, scale)) => {
+                Some(Self::Float(*v as f64 / 10_f64.powi(*scale as i32)))
+            }
+            (Keys::Floats(_), offset) => {
+                integer_offset(offset).map(|v| Self::Float(v as f64))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// the integer `offset`
+fn integer_offset(offset: &ScalarValue) -> Option<i128> {
+    match offset {
+        ScalarValue::Int8(Some(v)) => Some(*v as i128),
+        ScalarValue::Int16(Some(v)) => Some(*v as i128),
+        ScalarValue::Int32(Some(v)) => Some(*v as i128),
+        ScalarValue::Int64(Some(v)) => Some(*v as i128),
+        ScalarValue::UInt8(Some(v)) => Some(*v as i128),
+        ScalarValue::UInt16(Some(v)) => Some(*v as i128),
+        ScalarValue::UInt32(Some(v)) => Some(*v as i128),
+        ScalarValue::UInt64(Some(v)) => Some(*v as i128),
+        _ => None,
+    }
+}
+
+/// the integers of `array`, multiplied by `scale`
+fn scaled_integers(array: &ArrayRef, scale: i128) -> Result<Vec<Option<i128>>> {
+    let values = cast(array, &DataType::Int64)?;
+    let values = values.as_any().downcast_ref::<Int64Array>().unwrap();
+    Ok(values
+        .iter()
+        .map(|v| v.map(|v| v as i128 * scale))
+        .collect())
+}
+
+/// adds `months` to the date and time of `nanos` since the epoch, in UTC, if it is in the
+/// range of dates
+fn add_months(nanos: i128, months: i32) -> Option<i128> {
+    if months == 0 {
+        return Some(nanos);
+    }
+    let seconds = i64::try_from(nanos.div_euclid(NANOS_PER_SECOND)).ok()?;
+    let subsec_nanos = nanos.rem_euclid(NANOS_PER_SECOND) as u32;
+    let date: NaiveDateTime =
+        DateTime::from_timestamp(seconds, subsec_nanos)?.naive_utc();
+    let date = if months > 0 {
+        date.checked_add_months(Months::new(months as u32))?
+    } else {
+        date.checked_sub_months(Months::new(months.unsigned_abs()))?
+    };
+    let date = date.and_utc();
+    Some(
+        date.timestamp() as i128 * NANOS_PER_SECOND
+            + date.timestamp_subsec_nanos() as i128,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use arrow::array::{Int32Array, TimestampSecondArray};
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    fn range_keys(array: ArrayRef, frame: WindowFrame, descending: bool) -> RangeKeys {
+        let schema = Schema::new(vec![Field::new("x", array.data_type().clone(), true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![array]).unwrap();
+        let order_by = [PhysicalSortExpr {
+            expr: col("x", &schema).unwrap(),
+            options: SortOptions {
+                descending,
+                nulls_first: true,
+            },
+        }];
+        RangeKeys::try_new(&frame, &order_by, &batch)
+            .unwrap()
+            .unwrap()
+    }
+
+    fn frames(
+        keys: &RangeKeys,
+        frame: &WindowFrame,
+        num_rows: usize,
+    ) -> Vec<Range<usize>> {
+        let partition = 0..num_rows;
+        (0..num_rows)
+            .map(|row| {
+                let index = |bound: &WindowFrameBound, is_start: bool| match bound {
+                    bound if bound.is_current_row() => row + !is_start as usize,
+                    bound => keys
+                        .bound_index(bound, is_start, row, &partition, &(row..row + 1))
+                        .unwrap(),
+                };
+                index(&frame.start_bound, true)..index(&frame.end_bound, false)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn integer_offsets() {
+        let frame = WindowFrame {
+            units: WindowFrameUnits::Range,
+            start_bound: WindowFrameBound::Preceding(Some(ScalarValue::Int64(Some(2)))),
+            end_bound: WindowFrameBound::Following(Some(ScalarValue::Int64(Some(1)))),
+        };
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![
+            None,
+            Some(1),
+            Some(2),
+            Some(2),
+            Some(5),
+            Some(6),
+        ]));
+        let keys = range_keys(array, frame.clone(), false);
+        assert_eq!(
+            frames(&keys, &frame, 6),
+            vec![0..1, 1..4, 1..4, 1..4, 4..6, 4..6]
+        );
+
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![
+            None,
+            Some(6),
+            Some(5),
+            Some(2),
+            Some(2),
+            Some(1),
+        ]));
+        let keys = range_keys(array, frame.clone(), true);
+        assert_eq!(
+            frames(&keys, &frame, 6),
+            vec![0..1, 1..3, 1..3, 3..6, 3..6, 3..6]
+        );
+    }
+
+    #[test]
+    fn interval_offsets() {
+        const DAY: i64 = 86_400;
+        // 2022-01-31, 2022-02-28, 2022-03-01 and 2022-03-31
+        let array: ArrayRef = Arc::new(TimestampSecondArray::from_vec(
+            vec![1643587200, 1646006400, 1646092800, 1648684800],
+            None,
+        ));
+        let frame = WindowFrame {
+            units: WindowFrameUnits::Range,
+            start_bound: WindowFrameBound::Preceding(Some(
+                ScalarValue::IntervalYearMonth(Some(1)),
+            )),
+            end_bound: WindowFrameBound::CurrentRow,
+        };
+        let keys = range_keys(array.clone(), frame.clone(), false);
+        assert_eq!(frames(&keys, &frame, 4), vec![0..1, 0..2, 1..3, 1..4]);
+
+        let frame = WindowFrame {
+            units: WindowFrameUnits::Range,
+            start_bound: WindowFrameBound::CurrentRow,
+            end_bound: WindowFrameBound::Following(Some(ScalarValue::IntervalDayTime(
+                Some(1 << 32),
+            ))),
+        };
+        let keys = range_keys(array, frame.clone(), false);
+        assert_eq!(frames(&keys, &frame, 4), vec![0..1, 1..3, 2..3, 3..4]);
+        assert_eq!(
+            add_months(0, -1),
+            Some(-31 * DAY as i128 * NANOS_PER_SECOND)
+        );
+    }
+}
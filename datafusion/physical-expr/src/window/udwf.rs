@@ -0,0 +1,178 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Physical exec for user-defined window function expressions.
+
+use super::aggregate::{frame_range, slice};
+use super::partition_evaluator::find_ranges_in_range;
+use super::range::RangeKeys;
+use super::WindowExpr;
+use crate::{expressions::PhysicalSortExpr, PhysicalExpr};
+use arrow::compute::concat;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use arrow::{array::ArrayRef, datatypes::Field};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::{WindowFrame, WindowUDF};
+use std::any::Any;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// A window expr that takes the form of a user-defined window function
+#[derive(Debug)]
+pub struct WindowUDFExpr {
+    fun: Arc<WindowUDF>,
+    args: Vec<Arc<dyn PhysicalExpr>>,
+    name: String,
+    data_type: DataType,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    order_by: Vec<PhysicalSortExpr>,
+    window_frame: Option<WindowFrame>,
+}
+
+impl WindowUDFExpr {
+    /// create a new user-defined window function expression
+    pub fn new(
+        fun: Arc<WindowUDF>,
+        args: Vec<Arc<dyn PhysicalExpr>>,
+        name: impl Into<String>,
+        data_type: DataType,
+        partition_by: &[Arc<dyn PhysicalExpr>],
+        order_by: &[PhysicalSortExpr],
+        window_frame: Option<WindowFrame>,
+    ) -> Self {
+        Self {
+            fun,
+            args,
+            name: name.into(),
+            data_type,
+            partition_by: partition_by.to_vec(),
+            order_by: order_by.to_vec(),
+            window_frame,
+        }
+    }
+
+    /// evaluate the function over one partition with a new evaluator, the values and peer
+    /// groups being relative to the start of the partition
+    fn evaluate_partition(
+        &self,
+        values: &[ArrayRef],
+        partition_range: &Range<usize>,
+        peers: &[Range<usize>],
+        range_keys: Option<&RangeKeys>,
+    ) -> Result<ArrayRef> {
+        let mut evaluator = (self.fun.partition_evaluator)()?;
+        let num_rows = partition_range.end - partition_range.start;
+        let values = slice(values, partition_range.clone());
+        let relative = |range: &Range<usize>| {
+            range.start - partition_range.start..range.end - partition_range.start
+        };
+        let result = if evaluator.uses_window_frame() {
+            let window_frame = self.window_frame.clone().unwrap_or_default();
+            let mut peer_index = 0;
+            let mut results = Vec::with_capacity(num_rows);
+            for row in partition_range.clone() {
+                while peers[peer_index].end <= row {
+                    peer_index += 1;
+                }
+                let frame = frame_range(
+                    &window_frame,
+                    row,
+                    partition_range,
+                    peers,
+                    peer_index,
+                    range_keys,
+                )?;
+                results.push(evaluator.evaluate(&values, &relative(&frame))?);
+            }
+            ScalarValue::iter_to_array(results)?
+        } else if evaluator.include_rank() {
+            let ranks_in_partition = peers.iter().map(relative).collect::<Vec<_>>();
+            evaluator.evaluate_all_with_rank(num_rows, &ranks_in_partition)?
+        } else {
+            evaluator.evaluate_all(&values, num_rows)?
+        };
+
+        if result.len() != num_rows || result.data_type() != &self.data_type {
+            return Err(DataFusionError::Execution(format!(
+                "Window function {} is expected to return {} values of type {}, got {} values of type {}",
+                self.name,
+                num_rows,
+                self.data_type,
+                result.len(),
+                result.data_type()
+            )));
+        }
+        Ok(result)
+    }
+}
+
+impl WindowExpr for WindowUDFExpr {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        self.args.clone()
+    }
+
+    fn partition_by(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.partition_by
+    }
+
+    fn order_by(&self) -> &[PhysicalSortExpr] {
+        &self.order_by
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let num_rows = batch.num_rows();
+        let partition_points =
+            self.evaluate_partition_points(num_rows, &self.partition_columns(batch)?)?;
+        let sort_partition_points =
+            self.evaluate_partition_points(num_rows, &self.sort_columns(batch)?)?;
+        let values = self.evaluate_args(batch)?;
+        let range_keys = match &self.window_frame {
+            Some(window_frame) => {
+                RangeKeys::try_new(window_frame, &self.order_by, batch)?
+            }
+            None => None,
+        };
+        let results = partition_points
+            .iter()
+            .map(|partition_range| {
+                let peers = find_ranges_in_range(partition_range, &sort_partition_points);
+                self.evaluate_partition(
+                    &values,
+                    partition_range,
+                    peers,
+                    range_keys.as_ref(),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let results = results.iter().map(|i| i.as_ref()).collect::<Vec<_>>();
+        concat(&results).map_err(DataFusionError::ArrowError)
+    }
+}
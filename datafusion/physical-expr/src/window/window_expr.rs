@@ -20,7 +20,7 @@ use arrow::compute::kernels::partition::lexicographical_partition_ranges;
 use arrow::compute::kernels::sort::{SortColumn, SortOptions};
 use arrow::record_batch::RecordBatch;
 use arrow::{array::ArrayRef, datatypes::Field};
-use datafusion_common::{DataFusionError, Result};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
 use std::any::Any;
 use std::fmt::Debug;
 use std::ops::Range;
@@ -60,6 +60,61 @@ pub trait WindowExpr: Send + Sync + Debug {
     /// evaluate the window function values against the batch
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef>;
 
+    /// evaluate the window function values against the batch, whose first partition
+    /// continues the rows summarized by `state`, as returned by
+    /// [`WindowExpr::dropped_rows_state`]. An empty `state` summarizes no rows.
+    fn evaluate_with_state(
+        &self,
+        batch: &RecordBatch,
+        _state: &[ScalarValue],
+    ) -> Result<ArrayRef> {
+        self.evaluate(batch)
+    }
+
+    /// Whether the results of the rows of a partition can be final before all its rows
+    /// are known, which allows to evaluate input sorted by the partition and order by
+    /// keys as it is received, see [`WindowExpr::final_rows_end`].
+    fn streams_within_partition(&self) -> bool {
+        false
+    }
+
+    /// Given `partition`, the last partition of `batch`, which may continue with the
+    /// rows that follow `batch`, the end of its leading rows whose results are final.
+    /// By default none are, as the results may depend on the whole partition.
+    fn final_rows_end(
+        &self,
+        _batch: &RecordBatch,
+        partition: &Range<usize>,
+    ) -> Result<usize> {
+        Ok(partition.start)
+    }
+
+    /// The first row of `partition` in `batch` that is needed to evaluate its rows
+    /// from `row` on, which may be `partition.end` when `partition` is the last
+    /// partition of `batch`, and so may continue after it. The rows before it are either
+    /// not needed or summarized by [`WindowExpr::dropped_rows_state`].
+    fn required_rows_start(
+        &self,
+        _batch: &RecordBatch,
+        partition: &Range<usize>,
+        _row: usize,
+    ) -> Result<usize> {
+        Ok(partition.start)
+    }
+
+    /// The state summarizing the rows of `partition`, the partition of `batch` that
+    /// contains `row`, which are dropped before `row`, given `state` summarizing the
+    /// rows dropped before `batch` if `partition` is the first partition of `batch`.
+    fn dropped_rows_state(
+        &self,
+        _batch: &RecordBatch,
+        _partition: &Range<usize>,
+        _row: usize,
+        _state: Vec<ScalarValue>,
+    ) -> Result<Vec<ScalarValue>> {
+        Ok(vec![])
+    }
+
     /// evaluate the partition points given the sort columns; if the sort columns are
     /// empty then the result will be a single element vec of the whole column rows.
     fn evaluate_partition_points(
@@ -19,7 +19,7 @@ use std::{collections::HashSet, sync::Arc};
 
 use datafusion::{
     common::{DataFusionError, Result},
-    logical_expr::{AggregateUDF, ScalarUDF},
+    logical_expr::{AggregateUDF, ScalarUDF, WindowUDF},
     logical_plan::FunctionRegistry,
 };
 
@@ -43,4 +43,10 @@ impl FunctionRegistry for NoRegistry {
             format!("No function registry provided to deserialize, so can not deserialize User Defined Aggregate Function '{}'", name))
         )
     }
+
+    fn udwf(&self, name: &str) -> Result<Arc<WindowUDF>> {
+        Err(DataFusionError::Plan(
+            format!("No function registry provided to deserialize, so can not deserialize User Defined Window Function '{}'", name))
+        )
+    }
 }
@@ -497,6 +497,26 @@ impl From<protobuf::AggregateFunction> for AggregateFunction {
             }
             protobuf::AggregateFunction::ApproxMedian => Self::ApproxMedian,
             protobuf::AggregateFunction::Grouping => Self::Grouping,
+            protobuf::AggregateFunction::StringAgg => Self::StringAgg,
+            protobuf::AggregateFunction::BoolAnd => Self::BoolAnd,
+            protobuf::AggregateFunction::BoolOr => Self::BoolOr,
+            protobuf::AggregateFunction::BitAnd => Self::BitAnd,
+            protobuf::AggregateFunction::BitOr => Self::BitOr,
+            protobuf::AggregateFunction::BitXor => Self::BitXor,
+            protobuf::AggregateFunction::Median => Self::Median,
+            protobuf::AggregateFunction::PercentileCont => Self::PercentileCont,
+            protobuf::AggregateFunction::Mode => Self::Mode,
+            protobuf::AggregateFunction::FirstValue => Self::FirstValue,
+            protobuf::AggregateFunction::LastValue => Self::LastValue,
+            protobuf::AggregateFunction::RegrSlope => Self::RegrSlope,
+            protobuf::AggregateFunction::RegrIntercept => Self::RegrIntercept,
+            protobuf::AggregateFunction::RegrCount => Self::RegrCount,
+            protobuf::AggregateFunction::RegrR2 => Self::RegrR2,
+            protobuf::AggregateFunction::RegrAvgx => Self::RegrAvgx,
+            protobuf::AggregateFunction::RegrAvgy => Self::RegrAvgy,
+            protobuf::AggregateFunction::RegrSxx => Self::RegrSxx,
+            protobuf::AggregateFunction::RegrSyy => Self::RegrSyy,
+            protobuf::AggregateFunction::RegrSxy => Self::RegrSxy,
         }
     }
 }
@@ -965,6 +985,15 @@ pub fn parse_expr(
                     }
                 })
                 .transpose()?;
+            let args = match &expr.expr {
+                // plans serialized before `args` was added only carry the first argument
+                Some(e) if expr.args.is_empty() => vec![parse_expr(e, registry)?],
+                _ => expr
+                    .args
+                    .iter()
+                    .map(|e| parse_expr(e, registry))
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
 
             match window_function {
                 window_expr_node::WindowFunction::AggrFunction(i) => {
@@ -974,7 +1003,7 @@ pub fn parse_expr(
                         fun: window_function::WindowFunction::AggregateFunction(
                             aggr_function,
                         ),
-                        args: vec![parse_required_expr(&expr.expr, registry, "expr")?],
+                        args,
                         partition_by,
                         order_by,
                         window_frame,
@@ -989,7 +1018,29 @@ pub fn parse_expr(
                         fun: window_function::WindowFunction::BuiltInWindowFunction(
                             built_in_function,
                         ),
-                        args: vec![parse_required_expr(&expr.expr, registry, "expr")?],
+                        args,
+                        partition_by,
+                        order_by,
+                        window_frame,
+                    })
+                }
+                window_expr_node::WindowFunction::Udaf(udaf_name) => {
+                    let udaf_function = registry.udaf(udaf_name)?;
+
+                    Ok(Expr::WindowFunction {
+                        fun: window_function::WindowFunction::AggregateUDF(udaf_function),
+                        args,
+                        partition_by,
+                        order_by,
+                        window_frame,
+                    })
+                }
+                window_expr_node::WindowFunction::Udwf(udwf_name) => {
+                    let udwf_function = registry.udwf(udwf_name)?;
+
+                    Ok(Expr::WindowFunction {
+                        fun: window_function::WindowFunction::WindowUDF(udwf_function),
+                        args,
                         partition_by,
                         order_by,
                         window_frame,
@@ -1007,7 +1058,17 @@ pub fn parse_expr(
                     .iter()
                     .map(|e| parse_expr(e, registry))
                     .collect::<Result<Vec<_>, _>>()?,
-                distinct: false, // TODO
+                distinct: expr.distinct,
+                filter: expr
+                    .filter
+                    .as_ref()
+                    .map(|e| parse_expr(e, registry).map(Box::new))
+                    .transpose()?,
+                order_by: expr
+                    .order_by
+                    .iter()
+                    .map(|e| parse_expr(e, registry))
+                    .collect::<Result<Vec<_>, _>>()?,
             })
         }
         ExprType::Alias(alias) => Ok(Expr::Alias(
@@ -1296,11 +1357,7 @@ impl TryFrom<protobuf::WindowFrame> for WindowFrame {
             })
             .transpose()?
             .unwrap_or(WindowFrameBound::CurrentRow);
-        Ok(Self {
-            units,
-            start_bound,
-            end_bound,
-        })
+        Ok(Self::try_new(units, start_bound, end_bound)?)
     }
 }
 
@@ -1313,18 +1370,21 @@ impl TryFrom<protobuf::WindowFrameBound> for WindowFrameBound {
                 .ok_or_else(|| {
                     Error::unknown("WindowFrameBoundType", bound.window_frame_bound_type)
                 })?;
+        let offset = bound
+            .bound_value
+            .map(|value| match value {
+                protobuf::window_frame_bound::BoundValue::Value(n) => {
+                    Ok(ScalarValue::UInt64(Some(n)))
+                }
+                protobuf::window_frame_bound::BoundValue::ScalarValue(offset) => {
+                    (&offset).try_into()
+                }
+            })
+            .transpose()?;
         match bound_type {
             protobuf::WindowFrameBoundType::CurrentRow => Ok(Self::CurrentRow),
-            protobuf::WindowFrameBoundType::Preceding => {
-                // FIXME implement bound value parsing
-                // https://github.com/apache/arrow-datafusion/issues/361
-                Ok(Self::Preceding(Some(1)))
-            }
-            protobuf::WindowFrameBoundType::Following => {
-                // FIXME implement bound value parsing
-                // https://github.com/apache/arrow-datafusion/issues/361
-                Ok(Self::Following(Some(1)))
-            }
+            protobuf::WindowFrameBoundType::Preceding => Ok(Self::Preceding(offset)),
+            protobuf::WindowFrameBoundType::Following => Ok(Self::Following(offset)),
         }
     }
 }
@@ -30,12 +30,15 @@ mod roundtrip_tests {
     use super::from_proto::parse_expr;
     use super::protobuf;
     use datafusion::arrow::array::ArrayRef;
-    use datafusion::logical_plan::create_udaf;
+    use datafusion::logical_plan::{create_udaf, create_udwf};
+    use datafusion::physical_plan::expressions::MaxAccumulator;
     use datafusion::physical_plan::functions::make_scalar_function;
     use datafusion::physical_plan::Accumulator;
     use datafusion::{
         arrow::datatypes::{DataType, Field, IntervalUnit, TimeUnit, UnionMode},
         logical_expr::{BuiltinScalarFunction::Sqrt, Volatility},
+        logical_expr::{PartitionEvaluator, WindowFunction},
+        logical_plan::window_frames::{WindowFrame, WindowFrameBound, WindowFrameUnits},
         logical_plan::{col, Expr},
         physical_plan::aggregates,
         prelude::*,
@@ -683,6 +686,37 @@ mod roundtrip_tests {
         roundtrip_expr_test!(test_expr, ctx);
     }
 
+    #[test]
+    fn roundtrip_window_frame() {
+        let window = |units, start_bound, end_bound| Expr::WindowFunction {
+            fun: WindowFunction::AggregateFunction(aggregates::AggregateFunction::Sum),
+            args: vec![col("v")],
+            partition_by: vec![],
+            order_by: vec![col("ts").sort(true, false)],
+            window_frame: Some(WindowFrame {
+                units,
+                start_bound,
+                end_bound,
+            }),
+        };
+
+        let ctx = SessionContext::new();
+        let test_expr = window(
+            WindowFrameUnits::Rows,
+            WindowFrameBound::Preceding(Some(ScalarValue::UInt64(Some(10)))),
+            WindowFrameBound::CurrentRow,
+        );
+        roundtrip_expr_test!(test_expr, ctx);
+        let test_expr = window(
+            WindowFrameUnits::Range,
+            WindowFrameBound::Preceding(Some(ScalarValue::IntervalDayTime(Some(
+                30 << 32,
+            )))),
+            WindowFrameBound::Following(Some(ScalarValue::Float64(Some(1.5)))),
+        );
+        roundtrip_expr_test!(test_expr, ctx);
+    }
+
     #[test]
     fn roundtrip_wildcard() {
         let test_expr = Expr::Wildcard;
@@ -707,6 +741,22 @@ mod roundtrip_tests {
             fun: aggregates::AggregateFunction::ApproxPercentileCont,
             args: vec![col("bananas"), lit(0.42_f32)],
             distinct: false,
+            filter: None,
+            order_by: vec![],
+        };
+
+        let ctx = SessionContext::new();
+        roundtrip_expr_test!(test_expr, ctx);
+    }
+
+    #[test]
+    fn roundtrip_aggregate_filter_order_by() {
+        let test_expr = Expr::AggregateFunction {
+            fun: aggregates::AggregateFunction::ArrayAgg,
+            args: vec![col("bananas")],
+            distinct: false,
+            filter: Some(Box::new(col("apples").gt(lit(1_i64)))),
+            order_by: vec![col("pears").sort(false, true)],
         };
 
         let ctx = SessionContext::new();
@@ -767,6 +817,60 @@ mod roundtrip_tests {
         roundtrip_expr_test!(test_expr, ctx);
     }
 
+    #[test]
+    fn roundtrip_window_udf() {
+        #[derive(Debug)]
+        struct Dummy {}
+
+        impl PartitionEvaluator for Dummy {}
+
+        let udwf = |name: &str, input_types: Vec<DataType>| {
+            create_udwf(
+                name,
+                input_types,
+                Arc::new(DataType::Float64),
+                Volatility::Immutable,
+                Arc::new(|| Ok(Box::new(Dummy {}))),
+            )
+        };
+        let nullary = udwf("dummy_nullary", vec![]);
+        let binary = udwf("dummy_binary", vec![DataType::Float64, DataType::Float64]);
+        let dummy_agg = create_udaf(
+            "dummy_agg",
+            DataType::Float64,
+            Arc::new(DataType::Float64),
+            Volatility::Immutable,
+            Arc::new(|| Ok(Box::new(MaxAccumulator::try_new(&DataType::Float64)?))),
+            Arc::new(vec![DataType::Float64]),
+        );
+
+        let window = |fun, args| Expr::WindowFunction {
+            fun,
+            args,
+            partition_by: vec![col("p")],
+            order_by: vec![col("ts").sort(true, false)],
+            window_frame: None,
+        };
+
+        let mut ctx = SessionContext::new();
+        ctx.register_udwf(nullary.clone());
+        ctx.register_udwf(binary.clone());
+        ctx.register_udaf(dummy_agg.clone());
+
+        let test_expr = window(WindowFunction::WindowUDF(Arc::new(nullary)), vec![]);
+        roundtrip_expr_test!(test_expr, ctx);
+        let test_expr = window(
+            WindowFunction::WindowUDF(Arc::new(binary)),
+            vec![col("a"), lit(2.0_f64)],
+        );
+        roundtrip_expr_test!(test_expr, ctx);
+        let test_expr = window(
+            WindowFunction::AggregateUDF(Arc::new(dummy_agg)),
+            vec![col("a"), lit(2.0_f64)],
+        );
+        roundtrip_expr_test!(test_expr, ctx);
+    }
+
     #[test]
     fn roundtrip_scalar_udf() {
         let fn_impl = |args: &[ArrayRef]| Ok(Arc::new(args[0].clone()) as ArrayRef);
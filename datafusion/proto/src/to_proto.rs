@@ -357,6 +357,26 @@ impl From<&AggregateFunction> for protobuf::AggregateFunction {
             }
             AggregateFunction::ApproxMedian => Self::ApproxMedian,
             AggregateFunction::Grouping => Self::Grouping,
+            AggregateFunction::StringAgg => Self::StringAgg,
+            AggregateFunction::BoolAnd => Self::BoolAnd,
+            AggregateFunction::BoolOr => Self::BoolOr,
+            AggregateFunction::BitAnd => Self::BitAnd,
+            AggregateFunction::BitOr => Self::BitOr,
+            AggregateFunction::BitXor => Self::BitXor,
+            AggregateFunction::Median => Self::Median,
+            AggregateFunction::PercentileCont => Self::PercentileCont,
+            AggregateFunction::Mode => Self::Mode,
+            AggregateFunction::FirstValue => Self::FirstValue,
+            AggregateFunction::LastValue => Self::LastValue,
+            AggregateFunction::RegrSlope => Self::RegrSlope,
+            AggregateFunction::RegrIntercept => Self::RegrIntercept,
+            AggregateFunction::RegrCount => Self::RegrCount,
+            AggregateFunction::RegrR2 => Self::RegrR2,
+            AggregateFunction::RegrAvgx => Self::RegrAvgx,
+            AggregateFunction::RegrAvgy => Self::RegrAvgy,
+            AggregateFunction::RegrSxx => Self::RegrSxx,
+            AggregateFunction::RegrSyy => Self::RegrSyy,
+            AggregateFunction::RegrSxy => Self::RegrSxy,
         }
     }
 }
@@ -389,9 +409,19 @@ impl From<WindowFrameUnits> for protobuf::WindowFrameUnits {
     }
 }
 
-impl From<WindowFrameBound> for protobuf::WindowFrameBound {
-    fn from(bound: WindowFrameBound) -> Self {
-        match bound {
+impl TryFrom<WindowFrameBound> for protobuf::WindowFrameBound {
+    type Error = Error;
+
+    fn try_from(bound: WindowFrameBound) -> Result<Self, Self::Error> {
+        let bound_value = |offset: Option<ScalarValue>| -> Result<_, Error> {
+            use protobuf::window_frame_bound::BoundValue;
+            Ok(match offset {
+                Some(ScalarValue::UInt64(Some(n))) => Some(BoundValue::Value(n)),
+                Some(offset) => Some(BoundValue::ScalarValue((&offset).try_into()?)),
+                None => None,
+            })
+        };
+        Ok(match bound {
             WindowFrameBound::CurrentRow => Self {
                 window_frame_bound_type: protobuf::WindowFrameBoundType::CurrentRow
                     .into(),
@@ -399,25 +429,27 @@ impl From<WindowFrameBound> for protobuf::WindowFrameBound {
             },
             WindowFrameBound::Preceding(v) => Self {
                 window_frame_bound_type: protobuf::WindowFrameBoundType::Preceding.into(),
-                bound_value: v.map(protobuf::window_frame_bound::BoundValue::Value),
+                bound_value: bound_value(v)?,
             },
             WindowFrameBound::Following(v) => Self {
                 window_frame_bound_type: protobuf::WindowFrameBoundType::Following.into(),
-                bound_value: v.map(protobuf::window_frame_bound::BoundValue::Value),
+                bound_value: bound_value(v)?,
             },
-        }
+        })
     }
 }
 
-impl From<WindowFrame> for protobuf::WindowFrame {
-    fn from(window: WindowFrame) -> Self {
-        Self {
+impl TryFrom<WindowFrame> for protobuf::WindowFrame {
+    type Error = Error;
+
+    fn try_from(window: WindowFrame) -> Result<Self, Self::Error> {
+        Ok(Self {
             window_frame_units: protobuf::WindowFrameUnits::from(window.units).into(),
-            start_bound: Some(window.start_bound.into()),
+            start_bound: Some(window.start_bound.try_into()?),
             end_bound: Some(protobuf::window_frame::EndBound::Bound(
-                window.end_bound.into(),
+                window.end_bound.try_into()?,
             )),
-        }
+        })
     }
 }
 
@@ -474,13 +506,17 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                             protobuf::BuiltInWindowFunction::from(fun).into(),
                         )
                     }
+                    WindowFunction::AggregateUDF(fun) => {
+                        protobuf::window_expr_node::WindowFunction::Udaf(fun.name.clone())
+                    }
+                    WindowFunction::WindowUDF(fun) => {
+                        protobuf::window_expr_node::WindowFunction::Udwf(fun.name.clone())
+                    }
                 };
-                let arg_expr: Option<Box<Self>> = if !args.is_empty() {
-                    let arg = &args[0];
-                    Some(Box::new(arg.try_into()?))
-                } else {
-                    None
-                };
+                let args = args
+                    .iter()
+                    .map(|e| e.try_into())
+                    .collect::<Result<Vec<_>, _>>()?;
                 let partition_by = partition_by
                     .iter()
                     .map(|e| e.try_into())
@@ -489,11 +525,17 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                     .iter()
                     .map(|e| e.try_into())
                     .collect::<Result<Vec<_>, _>>()?;
-                let window_frame = window_frame.map(|window_frame| {
-                    protobuf::window_expr_node::WindowFrame::Frame(window_frame.into())
-                });
+                let window_frame = window_frame
+                    .clone()
+                    .map(|window_frame| {
+                        Ok::<_, Error>(protobuf::window_expr_node::WindowFrame::Frame(
+                            window_frame.try_into()?,
+                        ))
+                    })
+                    .transpose()?;
                 let window_expr = Box::new(protobuf::WindowExprNode {
-                    expr: arg_expr,
+                    expr: None,
+                    args,
                     window_function: Some(window_function),
                     partition_by,
                     order_by,
@@ -504,7 +546,11 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                 }
             }
             Expr::AggregateFunction {
-                ref fun, ref args, ..
+                ref fun,
+                ref args,
+                distinct,
+                ref filter,
+                ref order_by,
             } => {
                 let aggr_function = match fun {
                     AggregateFunction::ApproxDistinct => {
@@ -543,6 +589,40 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                         protobuf::AggregateFunction::ApproxMedian
                     }
                     AggregateFunction::Grouping => protobuf::AggregateFunction::Grouping,
+                    AggregateFunction::StringAgg => {
+                        protobuf::AggregateFunction::StringAgg
+                    }
+                    AggregateFunction::BoolAnd => protobuf::AggregateFunction::BoolAnd,
+                    AggregateFunction::BoolOr => protobuf::AggregateFunction::BoolOr,
+                    AggregateFunction::BitAnd => protobuf::AggregateFunction::BitAnd,
+                    AggregateFunction::BitOr => protobuf::AggregateFunction::BitOr,
+                    AggregateFunction::BitXor => protobuf::AggregateFunction::BitXor,
+                    AggregateFunction::Median => protobuf::AggregateFunction::Median,
+                    AggregateFunction::PercentileCont => {
+                        protobuf::AggregateFunction::PercentileCont
+                    }
+                    AggregateFunction::Mode => protobuf::AggregateFunction::Mode,
+                    AggregateFunction::FirstValue => {
+                        protobuf::AggregateFunction::FirstValue
+                    }
+                    AggregateFunction::LastValue => {
+                        protobuf::AggregateFunction::LastValue
+                    }
+                    AggregateFunction::RegrSlope => {
+                        protobuf::AggregateFunction::RegrSlope
+                    }
+                    AggregateFunction::RegrIntercept => {
+                        protobuf::AggregateFunction::RegrIntercept
+                    }
+                    AggregateFunction::RegrCount => {
+                        protobuf::AggregateFunction::RegrCount
+                    }
+                    AggregateFunction::RegrR2 => protobuf::AggregateFunction::RegrR2,
+                    AggregateFunction::RegrAvgx => protobuf::AggregateFunction::RegrAvgx,
+                    AggregateFunction::RegrAvgy => protobuf::AggregateFunction::RegrAvgy,
+                    AggregateFunction::RegrSxx => protobuf::AggregateFunction::RegrSxx,
+                    AggregateFunction::RegrSyy => protobuf::AggregateFunction::RegrSyy,
+                    AggregateFunction::RegrSxy => protobuf::AggregateFunction::RegrSxy,
                 };
 
                 let aggregate_expr = protobuf::AggregateExprNode {
@@ -551,9 +631,18 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                         .iter()
                         .map(|v| v.try_into())
                         .collect::<Result<Vec<_>, _>>()?,
+                    distinct: *distinct,
+                    filter: match filter {
+                        Some(e) => Some(Box::new(e.as_ref().try_into()?)),
+                        None => None,
+                    },
+                    order_by: order_by
+                        .iter()
+                        .map(|v| v.try_into())
+                        .collect::<Result<Vec<_>, _>>()?,
                 };
                 Self {
-                    expr_type: Some(ExprType::AggregateExpr(aggregate_expr)),
+                    expr_type: Some(ExprType::AggregateExpr(Box::new(aggregate_expr))),
                 }
             }
             Expr::ScalarVariable(_, _) => unimplemented!(),
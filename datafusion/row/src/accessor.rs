@@ -86,6 +86,23 @@ macro_rules! fn_max_min_idx {
     };
 }
 
+macro_rules! fn_bit_op_idx {
+    ($NATIVE: ident, $OP: ident, $OPERATOR: tt) => {
+        paste::item! {
+            /// apply the bitwise operator then update
+            pub fn [<$OP _ $NATIVE>](&mut self, idx: usize, value: $NATIVE) {
+                if self.is_valid_at(idx) {
+                    let v = self.[<get_ $NATIVE>](idx) $OPERATOR value;
+                    self.[<set_ $NATIVE>](idx, v);
+                } else {
+                    self.set_non_null_at(idx);
+                    self.[<set_ $NATIVE>](idx, value);
+                }
+            }
+        }
+    };
+}
+
 macro_rules! fn_get_idx_scalar {
     ($NATIVE: ident, $SCALAR:ident) => {
         paste::item! {
@@ -236,6 +253,12 @@ impl<'a> RowAccessor<'a> {
         }
     }
 
+    fn set_bool(&mut self, idx: usize, value: bool) {
+        self.assert_index_valid(idx);
+        let offset = self.field_offsets()[idx];
+        self.data[offset] = if value { 1 } else { 0 };
+    }
+
     fn set_u8(&mut self, idx: usize, value: u8) {
         self.assert_index_valid(idx);
         let offset = self.field_offsets()[idx];
@@ -293,4 +316,33 @@ impl<'a> RowAccessor<'a> {
     fn_max_min_idx!(i64, min);
     fn_max_min_idx!(f32, min);
     fn_max_min_idx!(f64, min);
+
+    fn_bit_op_idx!(bool, bitand, &);
+    fn_bit_op_idx!(u8, bitand, &);
+    fn_bit_op_idx!(u16, bitand, &);
+    fn_bit_op_idx!(u32, bitand, &);
+    fn_bit_op_idx!(u64, bitand, &);
+    fn_bit_op_idx!(i8, bitand, &);
+    fn_bit_op_idx!(i16, bitand, &);
+    fn_bit_op_idx!(i32, bitand, &);
+    fn_bit_op_idx!(i64, bitand, &);
+
+    fn_bit_op_idx!(bool, bitor, |);
+    fn_bit_op_idx!(u8, bitor, |);
+    fn_bit_op_idx!(u16, bitor, |);
+    fn_bit_op_idx!(u32, bitor, |);
+    fn_bit_op_idx!(u64, bitor, |);
+    fn_bit_op_idx!(i8, bitor, |);
+    fn_bit_op_idx!(i16, bitor, |);
+    fn_bit_op_idx!(i32, bitor, |);
+    fn_bit_op_idx!(i64, bitor, |);
+
+    fn_bit_op_idx!(u8, bitxor, ^);
+    fn_bit_op_idx!(u16, bitxor, ^);
+    fn_bit_op_idx!(u32, bitxor, ^);
+    fn_bit_op_idx!(u64, bitxor, ^);
+    fn_bit_op_idx!(i8, bitxor, ^);
+    fn_bit_op_idx!(i16, bitxor, ^);
+    fn_bit_op_idx!(i32, bitxor, ^);
+    fn_bit_op_idx!(i64, bitxor, ^);
 }